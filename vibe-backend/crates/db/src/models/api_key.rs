@@ -1,10 +1,172 @@
+use axum::{
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use sqlx::{FromRow, PgPool};
+use strum_macros::{Display, EnumString};
+use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
 
+/// A permission that can be granted to an API key.
+///
+/// Write scopes imply the matching read scope and `admin` implies every
+/// scope. Session (JWT) users are never scoped.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS, Display, EnumString,
+)]
+pub enum ApiScope {
+    #[serde(rename = "tasks:read")]
+    #[strum(serialize = "tasks:read")]
+    TasksRead,
+    #[serde(rename = "tasks:write")]
+    #[strum(serialize = "tasks:write")]
+    TasksWrite,
+    #[serde(rename = "documents:read")]
+    #[strum(serialize = "documents:read")]
+    DocumentsRead,
+    #[serde(rename = "documents:write")]
+    #[strum(serialize = "documents:write")]
+    DocumentsWrite,
+    #[serde(rename = "projects:read")]
+    #[strum(serialize = "projects:read")]
+    ProjectsRead,
+    #[serde(rename = "projects:write")]
+    #[strum(serialize = "projects:write")]
+    ProjectsWrite,
+    #[serde(rename = "teams:read")]
+    #[strum(serialize = "teams:read")]
+    TeamsRead,
+    #[serde(rename = "teams:write")]
+    #[strum(serialize = "teams:write")]
+    TeamsWrite,
+    #[serde(rename = "executions:read")]
+    #[strum(serialize = "executions:read")]
+    ExecutionsRead,
+    #[serde(rename = "executions:run")]
+    #[strum(serialize = "executions:run")]
+    ExecutionsRun,
+    #[serde(rename = "admin")]
+    #[strum(serialize = "admin")]
+    Admin,
+}
+
+impl ApiScope {
+    /// Scopes given to keys created without an explicit list: read access
+    /// everywhere plus writing tasks and documents
+    pub const DEFAULT: &'static [ApiScope] = &[
+        ApiScope::TasksWrite,
+        ApiScope::DocumentsWrite,
+        ApiScope::ProjectsRead,
+        ApiScope::TeamsRead,
+        ApiScope::ExecutionsRead,
+    ];
+
+    /// The read scope implied by a write scope
+    fn implied_read(self) -> Option<ApiScope> {
+        match self {
+            ApiScope::TasksWrite => Some(ApiScope::TasksRead),
+            ApiScope::DocumentsWrite => Some(ApiScope::DocumentsRead),
+            ApiScope::ProjectsWrite => Some(ApiScope::ProjectsRead),
+            ApiScope::TeamsWrite => Some(ApiScope::TeamsRead),
+            ApiScope::ExecutionsRun => Some(ApiScope::ExecutionsRead),
+            _ => None,
+        }
+    }
+
+    /// Whether holding this scope satisfies `required`
+    pub fn grants(self, required: ApiScope) -> bool {
+        self == ApiScope::Admin || self == required || self.implied_read() == Some(required)
+    }
+
+    /// Whether any of `granted` satisfies `required`
+    pub fn is_granted_by(self, granted: &[ApiScope]) -> bool {
+        granted.iter().any(|scope| scope.grants(self))
+    }
+
+    /// Parse stored scope strings, skipping any this build does not know
+    pub fn parse_list(scopes: &[String]) -> Vec<ApiScope> {
+        scopes
+            .iter()
+            .filter_map(|scope| match scope.parse() {
+                Ok(scope) => Some(scope),
+                Err(_) => {
+                    tracing::warn!("Ignoring unknown API key scope '{}'", scope);
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+/// Scopes of the API key that authenticated a request, stored as a request
+/// extension. Absent for sessions, which have full access.
+#[derive(Debug, Clone)]
+pub struct ApiKeyScopes(pub Vec<ApiScope>);
+
+impl ApiKeyScopes {
+    /// Check a scope a handler needs beyond its route group's [`ScopeRule`]
+    pub fn require(&self, scope: ApiScope) -> Result<(), MissingScope> {
+        if scope.is_granted_by(&self.0) {
+            Ok(())
+        } else {
+            Err(MissingScope(scope))
+        }
+    }
+}
+
+/// Scopes an API key needs for a group of routes: `read` for safe methods,
+/// `write` for everything else
+#[derive(Debug, Clone, Copy)]
+pub struct ScopeRule {
+    pub read: ApiScope,
+    pub write: ApiScope,
+}
+
+impl ScopeRule {
+    pub const fn new(read: ApiScope, write: ApiScope) -> Self {
+        Self { read, write }
+    }
+
+    pub fn required(&self, method: &Method) -> ApiScope {
+        if method.is_safe() {
+            self.read
+        } else {
+            self.write
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Error)]
+#[error("API key is missing the required scope '{0}'")]
+pub struct MissingScope(pub ApiScope);
+
+/// Scope middleware, layered per route group after authentication. Requests
+/// without [`ApiKeyScopes`] pass through; `R` is the caller's 403 response.
+pub async fn scope_middleware<R>(
+    State(rule): State<ScopeRule>,
+    request: Request,
+    next: Next,
+) -> Result<Response, R>
+where
+    R: From<MissingScope> + IntoResponse,
+{
+    if let Some(ApiKeyScopes(granted)) = request.extensions().get::<ApiKeyScopes>() {
+        let required = rule.required(request.method());
+        if !required.is_granted_by(granted) {
+            tracing::warn!(%required, uri = %request.uri(), "API key missing required scope");
+            return Err(MissingScope(required).into());
+        }
+    }
+
+    Ok(next.run(request).await)
+}
+
 /// An API key for programmatic access (MCP servers, CLI tools, etc.)
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct ApiKey {
@@ -34,6 +196,7 @@ pub struct ApiKeyWithSecret {
     pub key_prefix: String,
     /// The full API key - only returned once at creation time
     pub key: String,
+    pub scopes: Vec<String>,
     #[ts(type = "Date | null")]
     pub expires_at: Option<DateTime<Utc>>,
     #[ts(type = "Date")]
@@ -77,6 +240,9 @@ pub struct CreateApiKey {
     pub name: String,
     /// Optional expiration in days (default: no expiration)
     pub expires_in_days: Option<i64>,
+    /// Scopes to grant (default: `ApiScope::DEFAULT`)
+    #[serde(default)]
+    pub scopes: Option<Vec<ApiScope>>,
 }
 
 // Helper struct for raw DB rows
@@ -154,10 +320,18 @@ impl ApiKey {
             .expires_in_days
             .map(|days| Utc::now() + chrono::Duration::days(days));
 
+        let scopes: Vec<String> = data
+            .scopes
+            .as_deref()
+            .unwrap_or(ApiScope::DEFAULT)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
         let row = sqlx::query_as!(
             ApiKeyRow,
-            r#"INSERT INTO api_keys (id, user_id, name, key_prefix, key_hash, expires_at)
-               VALUES ($1, $2, $3, $4, $5, $6)
+            r#"INSERT INTO api_keys (id, user_id, name, key_prefix, key_hash, scopes, expires_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
                RETURNING id as "id!: Uuid",
                          user_id,
                          name,
@@ -174,6 +348,7 @@ impl ApiKey {
             data.name,
             key_prefix,
             key_hash,
+            &scopes,
             expires_at
         )
         .fetch_one(pool)
@@ -184,6 +359,7 @@ impl ApiKey {
             name: row.name,
             key_prefix: row.key_prefix,
             key,
+            scopes: row.scopes,
             expires_at: row.expires_at,
             created_at: row.created_at,
         })
@@ -216,8 +392,13 @@ impl ApiKey {
         Ok(row.map(|r| r.into()))
     }
 
-    /// Validate an API key and return the user_id if valid
-    pub async fn validate(pool: &PgPool, key: &str) -> Result<Option<String>, sqlx::Error> {
+    /// Scopes granted to this key
+    pub fn granted_scopes(&self) -> Vec<ApiScope> {
+        ApiScope::parse_list(&self.scopes)
+    }
+
+    /// Validate an API key and return it if it is usable
+    pub async fn validate(pool: &PgPool, key: &str) -> Result<Option<Self>, sqlx::Error> {
         if let Some(api_key) = Self::find_by_key(pool, key).await? {
             // Check if expired
             if let Some(expires_at) = api_key.expires_at
@@ -234,7 +415,7 @@ impl ApiKey {
             .execute(pool)
             .await?;
 
-            return Ok(Some(api_key.user_id));
+            return Ok(Some(api_key));
         }
         Ok(None)
     }
@@ -294,3 +475,56 @@ impl ApiKey {
         Ok(result.rows_affected() > 0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_round_trip() {
+        assert_eq!(ApiScope::TasksWrite.to_string(), "tasks:write");
+        assert_eq!(
+            "executions:run".parse::<ApiScope>().unwrap(),
+            ApiScope::ExecutionsRun
+        );
+        assert_eq!(
+            serde_json::to_string(&ApiScope::Admin).unwrap(),
+            "\"admin\""
+        );
+        assert!("tasks:delete".parse::<ApiScope>().is_err());
+    }
+
+    #[test]
+    fn test_scope_implications() {
+        assert!(ApiScope::TasksRead.is_granted_by(&[ApiScope::TasksWrite]));
+        assert!(!ApiScope::TasksWrite.is_granted_by(&[ApiScope::TasksRead]));
+        assert!(!ApiScope::TeamsWrite.is_granted_by(&[ApiScope::TasksWrite]));
+        assert!(ApiScope::TeamsWrite.is_granted_by(&[ApiScope::Admin]));
+        assert!(!ApiScope::Admin.is_granted_by(ApiScope::DEFAULT));
+    }
+
+    #[test]
+    fn test_scope_rule_picks_scope_by_method() {
+        let rule = ScopeRule::new(ApiScope::DocumentsRead, ApiScope::DocumentsWrite);
+        assert_eq!(rule.required(&Method::GET), ApiScope::DocumentsRead);
+        assert_eq!(rule.required(&Method::HEAD), ApiScope::DocumentsRead);
+        assert_eq!(rule.required(&Method::PATCH), ApiScope::DocumentsWrite);
+        assert_eq!(rule.required(&Method::DELETE), ApiScope::DocumentsWrite);
+    }
+
+    #[test]
+    fn test_require_extra_scope() {
+        let scopes = ApiKeyScopes(vec![ApiScope::TasksWrite]);
+        assert!(scopes.require(ApiScope::TasksRead).is_ok());
+        assert!(matches!(
+            scopes.require(ApiScope::ExecutionsRun),
+            Err(MissingScope(ApiScope::ExecutionsRun))
+        ));
+    }
+
+    #[test]
+    fn test_parse_list_skips_unknown() {
+        let scopes = vec!["tasks:read".to_string(), "everything".to_string()];
+        assert_eq!(ApiScope::parse_list(&scopes), vec![ApiScope::TasksRead]);
+    }
+}
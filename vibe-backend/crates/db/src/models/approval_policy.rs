@@ -0,0 +1,253 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// What happens to a tool call matched by a policy rule
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum PolicyEffect {
+    /// Approve without asking
+    Allow,
+    /// Deny without asking
+    Deny,
+    /// Always ask the user, even if a lower-priority rule would allow
+    Ask,
+}
+
+impl PolicyEffect {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Deny => "deny",
+            Self::Ask => "ask",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "allow" => Some(Self::Allow),
+            "deny" => Some(Self::Deny),
+            "ask" => Some(Self::Ask),
+            _ => None,
+        }
+    }
+}
+
+/// A per-project rule deciding executor tool approvals.
+///
+/// Every condition that is set must match; unset conditions match anything.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ApprovalPolicyRule {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub name: String,
+    pub effect: PolicyEffect,
+    /// Glob on the executor's tool name, e.g. `Bash` or `mcp__*`
+    pub tool_name: Option<String>,
+    /// Action kind of the tool call: `file_read`, `file_edit`, `command_run`,
+    /// `search`, `web_fetch` or `tool`
+    pub action_kind: Option<String>,
+    /// Path glob for file actions, command pattern for `command_run`,
+    /// domain for `web_fetch`
+    pub pattern: Option<String>,
+    /// Executor the rule applies to, e.g. `CLAUDE_CODE`
+    pub executor: Option<String>,
+    pub priority: i32,
+    pub enabled: bool,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+#[ts(export)]
+pub struct CreateApprovalPolicyRule {
+    pub name: String,
+    pub effect: PolicyEffect,
+    pub tool_name: Option<String>,
+    pub action_kind: Option<String>,
+    pub pattern: Option<String>,
+    pub executor: Option<String>,
+    pub priority: Option<i32>,
+    pub enabled: Option<bool>,
+}
+
+/// Full replacement of a rule's editable fields
+pub type UpdateApprovalPolicyRule = CreateApprovalPolicyRule;
+
+// Effect is stored as text; rows with an unknown effect are skipped
+#[derive(FromRow)]
+struct ApprovalPolicyRuleRow {
+    id: Uuid,
+    project_id: Uuid,
+    name: String,
+    effect: String,
+    tool_name: Option<String>,
+    action_kind: Option<String>,
+    pattern: Option<String>,
+    executor: Option<String>,
+    priority: i32,
+    enabled: bool,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl ApprovalPolicyRuleRow {
+    fn into_rule(self) -> Option<ApprovalPolicyRule> {
+        let Some(effect) = PolicyEffect::parse(&self.effect) else {
+            tracing::warn!(rule_id = %self.id, effect = %self.effect, "unknown approval policy effect");
+            return None;
+        };
+        Some(ApprovalPolicyRule {
+            id: self.id,
+            project_id: self.project_id,
+            name: self.name,
+            effect,
+            tool_name: self.tool_name,
+            action_kind: self.action_kind,
+            pattern: self.pattern,
+            executor: self.executor,
+            priority: self.priority,
+            enabled: self.enabled,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        })
+    }
+}
+
+const RULE_COLUMNS: &str = r#"
+    id, project_id, name, effect, tool_name, action_kind, pattern, executor,
+    priority, enabled, created_at, updated_at
+"#;
+
+impl ApprovalPolicyRule {
+    /// All rules of a project, highest priority first
+    pub async fn find_by_project_id(
+        pool: &PgPool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        Self::fetch(pool, project_id, false).await
+    }
+
+    /// Enabled rules of a project, highest priority first
+    pub async fn find_enabled_by_project_id(
+        pool: &PgPool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        Self::fetch(pool, project_id, true).await
+    }
+
+    async fn fetch(
+        pool: &PgPool,
+        project_id: Uuid,
+        enabled_only: bool,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT {RULE_COLUMNS}
+            FROM approval_policy_rules
+            WHERE project_id = $1 AND (NOT $2 OR enabled)
+            ORDER BY priority DESC, created_at ASC
+            "#
+        );
+        let rows = sqlx::query_as::<_, ApprovalPolicyRuleRow>(&query)
+            .bind(project_id)
+            .bind(enabled_only)
+            .fetch_all(pool)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(ApprovalPolicyRuleRow::into_rule)
+            .collect())
+    }
+
+    pub async fn find_by_id(
+        pool: &PgPool,
+        project_id: Uuid,
+        id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let query = format!(
+            "SELECT {RULE_COLUMNS} FROM approval_policy_rules WHERE id = $1 AND project_id = $2"
+        );
+        let row = sqlx::query_as::<_, ApprovalPolicyRuleRow>(&query)
+            .bind(id)
+            .bind(project_id)
+            .fetch_optional(pool)
+            .await?;
+        Ok(row.and_then(ApprovalPolicyRuleRow::into_rule))
+    }
+
+    pub async fn create(
+        pool: &PgPool,
+        project_id: Uuid,
+        data: &CreateApprovalPolicyRule,
+    ) -> Result<Self, sqlx::Error> {
+        let query = format!(
+            r#"
+            INSERT INTO approval_policy_rules
+                (project_id, name, effect, tool_name, action_kind, pattern, executor, priority, enabled)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING {RULE_COLUMNS}
+            "#
+        );
+        let row = sqlx::query_as::<_, ApprovalPolicyRuleRow>(&query)
+            .bind(project_id)
+            .bind(&data.name)
+            .bind(data.effect.as_str())
+            .bind(&data.tool_name)
+            .bind(&data.action_kind)
+            .bind(&data.pattern)
+            .bind(&data.executor)
+            .bind(data.priority.unwrap_or(0))
+            .bind(data.enabled.unwrap_or(true))
+            .fetch_one(pool)
+            .await?;
+        row.into_rule().ok_or(sqlx::Error::RowNotFound)
+    }
+
+    pub async fn update(
+        pool: &PgPool,
+        project_id: Uuid,
+        id: Uuid,
+        data: &UpdateApprovalPolicyRule,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let query = format!(
+            r#"
+            UPDATE approval_policy_rules
+            SET name = $3, effect = $4, tool_name = $5, action_kind = $6, pattern = $7,
+                executor = $8, priority = $9, enabled = $10, updated_at = NOW()
+            WHERE id = $1 AND project_id = $2
+            RETURNING {RULE_COLUMNS}
+            "#
+        );
+        let row = sqlx::query_as::<_, ApprovalPolicyRuleRow>(&query)
+            .bind(id)
+            .bind(project_id)
+            .bind(&data.name)
+            .bind(data.effect.as_str())
+            .bind(&data.tool_name)
+            .bind(&data.action_kind)
+            .bind(&data.pattern)
+            .bind(&data.executor)
+            .bind(data.priority.unwrap_or(0))
+            .bind(data.enabled.unwrap_or(true))
+            .fetch_optional(pool)
+            .await?;
+        Ok(row.and_then(ApprovalPolicyRuleRow::into_rule))
+    }
+
+    pub async fn delete(pool: &PgPool, project_id: Uuid, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result =
+            sqlx::query("DELETE FROM approval_policy_rules WHERE id = $1 AND project_id = $2")
+                .bind(id)
+                .bind(project_id)
+                .execute(pool)
+                .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,245 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Attempts of one task launched together so their results can be compared
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct AttemptGroup {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    /// Shell script run in each worktree to check an attempt's result
+    pub verification_script: Option<String>,
+    pub winner_workspace_id: Option<Uuid>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date | null")]
+    pub decided_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum AttemptStatus {
+    Active,
+    Winner,
+    /// Not picked; its worktree has been removed but its branch is kept
+    Archived,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct AttemptGroupMember {
+    pub id: Uuid,
+    pub group_id: Uuid,
+    pub workspace_id: Uuid,
+    #[ts(type = "ExecutorProfileId")]
+    pub executor_profile_id: Json<ExecutorProfileId>,
+    pub status: AttemptStatus,
+    pub verification_passed: Option<bool>,
+    pub verification_exit_code: Option<i32>,
+    /// Tail of the script's combined stdout and stderr
+    pub verification_output: Option<String>,
+    pub verification_duration_ms: Option<i64>,
+    #[ts(type = "Date | null")]
+    pub verified_at: Option<DateTime<Utc>>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct AttemptGroupWithMembers {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub group: AttemptGroup,
+    pub members: Vec<AttemptGroupMember>,
+}
+
+/// Outcome of one verification script run
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct VerificationRun {
+    pub passed: bool,
+    /// `None` when the script was killed, e.g. on timeout
+    pub exit_code: Option<i32>,
+    pub output: String,
+    pub duration_ms: i64,
+}
+
+const GROUP_COLUMNS: &str =
+    "id, task_id, verification_script, winner_workspace_id, created_at, decided_at";
+
+const MEMBER_COLUMNS: &str = "id, group_id, workspace_id, executor_profile_id, status, \
+     verification_passed, verification_exit_code, verification_output, \
+     verification_duration_ms, verified_at, created_at";
+
+impl AttemptGroup {
+    pub async fn create(
+        pool: &PgPool,
+        task_id: Uuid,
+        verification_script: Option<&str>,
+    ) -> Result<Self, sqlx::Error> {
+        let query = format!(
+            r#"INSERT INTO attempt_groups (task_id, verification_script)
+               VALUES ($1, $2)
+               RETURNING {GROUP_COLUMNS}"#
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(task_id)
+            .bind(verification_script)
+            .fetch_one(pool)
+            .await
+    }
+
+    pub async fn find_by_id(pool: &PgPool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        let query = format!("SELECT {GROUP_COLUMNS} FROM attempt_groups WHERE id = $1");
+        sqlx::query_as::<_, Self>(&query)
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Newest first
+    pub async fn find_by_task_id(pool: &PgPool, task_id: Uuid) -> Result<Vec<Self>, sqlx::Error> {
+        let query = format!(
+            "SELECT {GROUP_COLUMNS} FROM attempt_groups
+             WHERE task_id = $1
+             ORDER BY created_at DESC"
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(task_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn with_members(self, pool: &PgPool) -> Result<AttemptGroupWithMembers, sqlx::Error> {
+        let members = AttemptGroupMember::find_by_group_id(pool, self.id).await?;
+        Ok(AttemptGroupWithMembers {
+            group: self,
+            members,
+        })
+    }
+
+    /// Record `workspace_id` as the winner and archive every other member.
+    /// Returns `None` when a winner was already picked.
+    pub async fn decide(
+        pool: &PgPool,
+        id: Uuid,
+        workspace_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let query = format!(
+            r#"UPDATE attempt_groups
+               SET winner_workspace_id = $2, decided_at = NOW()
+               WHERE id = $1 AND winner_workspace_id IS NULL
+               RETURNING {GROUP_COLUMNS}"#
+        );
+        let Some(group) = sqlx::query_as::<_, Self>(&query)
+            .bind(id)
+            .bind(workspace_id)
+            .fetch_optional(&mut *tx)
+            .await?
+        else {
+            return Ok(None);
+        };
+        sqlx::query(
+            r#"UPDATE attempt_group_members
+               SET status = CASE WHEN workspace_id = $2 THEN 'winner' ELSE 'archived' END
+               WHERE group_id = $1"#,
+        )
+        .bind(id)
+        .bind(workspace_id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(Some(group))
+    }
+
+    /// Undo [`AttemptGroup::decide`] for `workspace_id`, e.g. when merging it
+    /// failed, making every member active again
+    pub async fn clear_winner(
+        pool: &PgPool,
+        id: Uuid,
+        workspace_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let cleared = sqlx::query(
+            r#"UPDATE attempt_groups
+               SET winner_workspace_id = NULL, decided_at = NULL
+               WHERE id = $1 AND winner_workspace_id = $2"#,
+        )
+        .bind(id)
+        .bind(workspace_id)
+        .execute(&mut *tx)
+        .await?;
+        if cleared.rows_affected() > 0 {
+            sqlx::query(
+                r#"UPDATE attempt_group_members
+                   SET status = 'active'
+                   WHERE group_id = $1"#,
+            )
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+impl AttemptGroupMember {
+    pub async fn create(
+        pool: &PgPool,
+        group_id: Uuid,
+        workspace_id: Uuid,
+        executor_profile_id: &ExecutorProfileId,
+    ) -> Result<Self, sqlx::Error> {
+        let query = format!(
+            r#"INSERT INTO attempt_group_members (group_id, workspace_id, executor_profile_id)
+               VALUES ($1, $2, $3)
+               RETURNING {MEMBER_COLUMNS}"#
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(group_id)
+            .bind(workspace_id)
+            .bind(Json(executor_profile_id))
+            .fetch_one(pool)
+            .await
+    }
+
+    /// In launch order
+    pub async fn find_by_group_id(pool: &PgPool, group_id: Uuid) -> Result<Vec<Self>, sqlx::Error> {
+        let query = format!(
+            "SELECT {MEMBER_COLUMNS} FROM attempt_group_members
+             WHERE group_id = $1
+             ORDER BY created_at ASC, id ASC"
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(group_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn record_verification(
+        pool: &PgPool,
+        id: Uuid,
+        run: &VerificationRun,
+    ) -> Result<Self, sqlx::Error> {
+        let query = format!(
+            r#"UPDATE attempt_group_members
+               SET verification_passed = $2, verification_exit_code = $3,
+                   verification_output = $4, verification_duration_ms = $5,
+                   verified_at = NOW()
+               WHERE id = $1
+               RETURNING {MEMBER_COLUMNS}"#
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(id)
+            .bind(run.passed)
+            .bind(run.exit_code)
+            .bind(&run.output)
+            .bind(run.duration_ms)
+            .fetch_one(pool)
+            .await
+    }
+}
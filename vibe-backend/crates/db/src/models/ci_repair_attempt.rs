@@ -0,0 +1,194 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CiRepairStatus {
+    Running,
+    /// The agent's fix was pushed, so CI runs again
+    Pushed,
+    /// The agent made no commits
+    NoChanges,
+    Failed,
+    /// The PR had no repairs left; the failure went to the inbox instead
+    Escalated,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct CiRepairAttempt {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub pr_number: i64,
+    pub head_sha: String,
+    pub execution_process_id: Option<Uuid>,
+    #[ts(type = "Array<string>")]
+    pub failed_jobs: Json<Vec<String>>,
+    pub status: CiRepairStatus,
+    pub commit_sha: Option<String>,
+    pub error: Option<String>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date | null")]
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateCiRepairAttempt {
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub pr_number: i64,
+    pub head_sha: String,
+    pub status: CiRepairStatus,
+}
+
+const CI_REPAIR_ATTEMPT_COLUMNS: &str = "id, workspace_id, repo_id, pr_number, head_sha, \
+     execution_process_id, failed_jobs, status, commit_sha, error, created_at, completed_at";
+
+impl CiRepairAttempt {
+    /// Record the failure of a head commit. Returns `None` when that commit
+    /// already has a row, i.e. another webhook got to it first, unless that
+    /// repair failed before the agent was started: a replayed delivery may
+    /// take it over.
+    pub async fn claim(
+        pool: &PgPool,
+        data: &CreateCiRepairAttempt,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let query = format!(
+            r#"INSERT INTO ci_repair_attempts
+                   (workspace_id, repo_id, pr_number, head_sha, status, completed_at)
+               VALUES ($1, $2, $3, $4, $5, CASE WHEN $5 = 'running' THEN NULL ELSE NOW() END)
+               ON CONFLICT (workspace_id, repo_id, head_sha) DO UPDATE
+                   SET status = EXCLUDED.status, error = NULL,
+                       created_at = NOW(), completed_at = EXCLUDED.completed_at
+                   WHERE ci_repair_attempts.status = 'failed'
+                     AND ci_repair_attempts.execution_process_id IS NULL
+               RETURNING {CI_REPAIR_ATTEMPT_COLUMNS}"#
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(data.workspace_id)
+            .bind(data.repo_id)
+            .bind(data.pr_number)
+            .bind(&data.head_sha)
+            .bind(data.status)
+            .fetch_optional(pool)
+            .await
+    }
+
+    pub async fn set_execution_process(
+        pool: &PgPool,
+        id: Uuid,
+        execution_process_id: Uuid,
+        failed_jobs: &[String],
+    ) -> Result<Self, sqlx::Error> {
+        let query = format!(
+            "UPDATE ci_repair_attempts
+             SET execution_process_id = $2, failed_jobs = $3
+             WHERE id = $1
+             RETURNING {CI_REPAIR_ATTEMPT_COLUMNS}"
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(id)
+            .bind(execution_process_id)
+            .bind(Json(failed_jobs))
+            .fetch_one(pool)
+            .await
+    }
+
+    /// Repairs of a PR the agent was started for
+    pub async fn count_repairs(
+        pool: &PgPool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        pr_number: i64,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT COUNT(*) FROM ci_repair_attempts
+             WHERE workspace_id = $1 AND repo_id = $2 AND pr_number = $3
+               AND execution_process_id IS NOT NULL",
+        )
+        .bind(workspace_id)
+        .bind(repo_id)
+        .bind(pr_number)
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn has_escalated(
+        pool: &PgPool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        pr_number: i64,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT EXISTS(
+                 SELECT 1 FROM ci_repair_attempts
+                 WHERE workspace_id = $1 AND repo_id = $2 AND pr_number = $3
+                   AND status = 'escalated'
+             )",
+        )
+        .bind(workspace_id)
+        .bind(repo_id)
+        .bind(pr_number)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Repairs of a workspace, newest first
+    pub async fn find_by_workspace_id(
+        pool: &PgPool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let query = format!(
+            "SELECT {CI_REPAIR_ATTEMPT_COLUMNS} FROM ci_repair_attempts
+             WHERE workspace_id = $1
+             ORDER BY created_at DESC"
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(workspace_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Repairs whose follow-up was started and hasn't been pushed yet
+    pub async fn find_running_by_workspace_id(
+        pool: &PgPool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let query = format!(
+            "SELECT {CI_REPAIR_ATTEMPT_COLUMNS} FROM ci_repair_attempts
+             WHERE workspace_id = $1 AND status = 'running'
+               AND execution_process_id IS NOT NULL
+             ORDER BY created_at ASC"
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(workspace_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn complete(
+        pool: &PgPool,
+        id: Uuid,
+        status: CiRepairStatus,
+        commit_sha: Option<&str>,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE ci_repair_attempts
+             SET status = $2, commit_sha = $3, error = $4, completed_at = NOW()
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(status)
+        .bind(commit_sha)
+        .bind(error)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
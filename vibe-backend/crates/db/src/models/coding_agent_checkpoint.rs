@@ -0,0 +1,90 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// State of one repository right before a coding agent follow-up
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct CodingAgentCheckpoint {
+    pub id: Uuid,
+    pub execution_process_id: Uuid,
+    pub repo_id: Uuid,
+    pub commit_sha: String,
+    /// Ref keeping the checkpoint commit reachable
+    pub ref_name: String,
+    /// Whether the checkpoint commit snapshots uncommitted changes on top of
+    /// the branch's commit rather than being that commit
+    pub committed_changes: bool,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateCodingAgentCheckpoint {
+    pub repo_id: Uuid,
+    pub commit_sha: String,
+    pub ref_name: String,
+    pub committed_changes: bool,
+}
+
+const CHECKPOINT_COLUMNS: &str =
+    "id, execution_process_id, repo_id, commit_sha, ref_name, committed_changes, created_at";
+
+impl CodingAgentCheckpoint {
+    pub async fn create_many(
+        pool: &PgPool,
+        execution_process_id: Uuid,
+        checkpoints: &[CreateCodingAgentCheckpoint],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        for checkpoint in checkpoints {
+            sqlx::query(
+                r#"INSERT INTO coding_agent_checkpoints
+                       (execution_process_id, repo_id, commit_sha, ref_name, committed_changes)
+                   VALUES ($1, $2, $3, $4, $5)"#,
+            )
+            .bind(execution_process_id)
+            .bind(checkpoint.repo_id)
+            .bind(&checkpoint.commit_sha)
+            .bind(&checkpoint.ref_name)
+            .bind(checkpoint.committed_changes)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &PgPool,
+        execution_process_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let query = format!(
+            "SELECT {CHECKPOINT_COLUMNS} FROM coding_agent_checkpoints
+             WHERE execution_process_id = $1"
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(execution_process_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Checkpoints of the session's turns that have not been dropped, oldest
+    /// turn first
+    pub async fn find_by_session_id(
+        pool: &PgPool,
+        session_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            r#"SELECT c.id, c.execution_process_id, c.repo_id, c.commit_sha, c.ref_name,
+                      c.committed_changes, c.created_at
+               FROM coding_agent_checkpoints c
+               JOIN execution_processes ep ON ep.id = c.execution_process_id
+               WHERE ep.session_id = $1 AND ep.dropped = FALSE
+               ORDER BY ep.created_at ASC, c.repo_id ASC"#,
+        )
+        .bind(session_id)
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -0,0 +1,119 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolutionStatus {
+    Running,
+    /// Every file was resolved and the operation continued
+    Resolved,
+    /// Conflicts remain in `unresolved_files`; the operation is still stopped
+    Unresolved,
+    Failed,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ConflictResolution {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub execution_process_id: Uuid,
+    #[ts(type = "Array<string>")]
+    pub conflicted_files: Json<Vec<String>>,
+    pub status: ConflictResolutionStatus,
+    #[ts(type = "Array<string>")]
+    pub unresolved_files: Json<Vec<String>>,
+    pub error: Option<String>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date | null")]
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateConflictResolution {
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub execution_process_id: Uuid,
+    pub conflicted_files: Vec<String>,
+}
+
+const CONFLICT_RESOLUTION_COLUMNS: &str = "id, workspace_id, repo_id, execution_process_id, \
+     conflicted_files, status, unresolved_files, error, created_at, completed_at";
+
+impl ConflictResolution {
+    pub async fn create(
+        pool: &PgPool,
+        data: &CreateConflictResolution,
+    ) -> Result<Self, sqlx::Error> {
+        let query = format!(
+            r#"INSERT INTO conflict_resolutions
+                   (workspace_id, repo_id, execution_process_id, conflicted_files)
+               VALUES ($1, $2, $3, $4)
+               RETURNING {CONFLICT_RESOLUTION_COLUMNS}"#
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(data.workspace_id)
+            .bind(data.repo_id)
+            .bind(data.execution_process_id)
+            .bind(Json(&data.conflicted_files))
+            .fetch_one(pool)
+            .await
+    }
+
+    /// Resolutions of a workspace, newest first
+    pub async fn find_by_workspace_id(
+        pool: &PgPool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let query = format!(
+            "SELECT {CONFLICT_RESOLUTION_COLUMNS} FROM conflict_resolutions
+             WHERE workspace_id = $1
+             ORDER BY created_at DESC"
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(workspace_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn find_running_by_workspace_id(
+        pool: &PgPool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let query = format!(
+            "SELECT {CONFLICT_RESOLUTION_COLUMNS} FROM conflict_resolutions
+             WHERE workspace_id = $1 AND status = 'running'
+             ORDER BY created_at ASC"
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(workspace_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn complete(
+        pool: &PgPool,
+        id: Uuid,
+        status: ConflictResolutionStatus,
+        unresolved_files: &[String],
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE conflict_resolutions
+             SET status = $2, unresolved_files = $3, error = $4, completed_at = NOW()
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(status)
+        .bind(Json(unresolved_files))
+        .bind(error)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
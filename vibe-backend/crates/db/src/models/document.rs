@@ -1,9 +1,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, PgPool};
+use sqlx::{Executor, FromRow, PgPool, Postgres};
 use ts_rs::TS;
 use uuid::Uuid;
 
+use super::document_revision::{DocumentRevision, RevisionAuthor, RevisionSource};
+
 /// Supported document file types
 #[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq, Default)]
 #[serde(rename_all = "lowercase")]
@@ -590,7 +592,10 @@ impl Document {
         }
     }
 
-    pub async fn find_by_id(pool: &PgPool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+    pub async fn find_by_id<'e, E>(executor: E, id: Uuid) -> Result<Option<Self>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
         sqlx::query_as!(
             Document,
             r#"SELECT id as "id!: Uuid",
@@ -618,7 +623,7 @@ impl Document {
                WHERE id = $1"#,
             id
         )
-        .fetch_optional(pool)
+        .fetch_optional(executor)
         .await
     }
 
@@ -660,12 +665,13 @@ impl Document {
         .await
     }
 
+    /// Full-text search over a team's non-archived documents, best matches
+    /// first. Accepts web-search syntax (`"exact phrase"`, `-exclude`, `or`).
     pub async fn search(
         pool: &PgPool,
         team_id: Uuid,
         query: &str,
     ) -> Result<Vec<Self>, sqlx::Error> {
-        let search_pattern = format!("%{}%", query);
         sqlx::query_as!(
             Document,
             r#"SELECT id as "id!: Uuid",
@@ -691,16 +697,22 @@ impl Document {
                       storage_provider as "storage_provider!"
                FROM documents
                WHERE team_id = $1 AND is_archived = FALSE
-                 AND (title LIKE $2 OR content LIKE $2)
-               ORDER BY is_pinned DESC, updated_at DESC"#,
+                 AND search_vector @@ websearch_to_tsquery('english', $2)
+               ORDER BY ts_rank_cd(search_vector, websearch_to_tsquery('english', $2)) DESC,
+                        is_pinned DESC, updated_at DESC"#,
             team_id,
-            search_pattern
+            query
         )
         .fetch_all(pool)
         .await
     }
 
-    pub async fn create(pool: &PgPool, data: &CreateDocument) -> Result<Self, sqlx::Error> {
+    /// Create a document, recording its initial content as revision 1
+    pub async fn create(
+        pool: &PgPool,
+        data: &CreateDocument,
+        author: &RevisionAuthor,
+    ) -> Result<Self, sqlx::Error> {
         let id = Uuid::new_v4();
         let file_type = data
             .file_type
@@ -735,7 +747,8 @@ impl Document {
             .clone()
             .unwrap_or_else(|| "local".to_string());
 
-        sqlx::query_as!(
+        let mut tx = pool.begin().await?;
+        let document = sqlx::query_as!(
             Document,
             r#"INSERT INTO documents (id, team_id, folder_id, title, slug, content, file_type, icon, position, file_path, file_size, mime_type, storage_provider, storage_key)
                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
@@ -775,16 +788,76 @@ impl Document {
             storage_provider,
             data.storage_key
         )
-        .fetch_one(pool)
-        .await
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if let Some(content) = &document.content {
+            DocumentRevision::record(
+                &mut *tx,
+                document.id,
+                &document.title,
+                content,
+                author,
+                None,
+            )
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(document)
     }
 
+    /// Update a document, recording a revision when its content changes
     pub async fn update(
         pool: &PgPool,
         id: Uuid,
         data: &UpdateDocument,
+        author: &RevisionAuthor,
     ) -> Result<Self, sqlx::Error> {
-        let existing = Self::find_by_id(pool, id)
+        Self::update_with_revision(pool, id, data, author, None).await
+    }
+
+    /// Replace the content with that of `revision`, recording the restore as
+    /// a new revision
+    pub async fn restore_revision(
+        pool: &PgPool,
+        revision: &DocumentRevision,
+        author: &RevisionAuthor,
+    ) -> Result<Self, sqlx::Error> {
+        let data = UpdateDocument {
+            folder_id: None,
+            title: None,
+            content: Some(revision.content.clone()),
+            icon: None,
+            is_pinned: None,
+            is_archived: None,
+            position: None,
+        };
+        Self::update_with_revision(
+            pool,
+            revision.document_id,
+            &data,
+            author,
+            Some(revision.revision_number),
+        )
+        .await
+    }
+
+    async fn update_with_revision(
+        pool: &PgPool,
+        id: Uuid,
+        data: &UpdateDocument,
+        author: &RevisionAuthor,
+        restored_from: Option<i32>,
+    ) -> Result<Self, sqlx::Error> {
+        // Lock the row before reading it so concurrent updates apply in turn
+        // instead of both merging onto the same old content
+        let mut tx = pool.begin().await?;
+        sqlx::query("SELECT id FROM documents WHERE id = $1 FOR UPDATE")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+        let existing = Self::find_by_id(&mut *tx, id)
             .await?
             .ok_or(sqlx::Error::RowNotFound)?;
 
@@ -800,13 +873,14 @@ impl Document {
         } else {
             existing.slug
         };
+        let content_changed = data.content.is_some() && data.content != existing.content;
         let content = data.content.clone().or(existing.content);
         let icon = data.icon.clone().or(existing.icon);
         let is_pinned = data.is_pinned.unwrap_or(existing.is_pinned);
         let is_archived = data.is_archived.unwrap_or(existing.is_archived);
         let position = data.position.unwrap_or(existing.position);
 
-        sqlx::query_as!(
+        let document = sqlx::query_as!(
             Document,
             r#"UPDATE documents
                SET folder_id = $2, title = $3, slug = $4, content = $5, icon = $6, is_pinned = $7,
@@ -843,8 +917,24 @@ impl Document {
             is_archived,
             position
         )
-        .fetch_one(pool)
-        .await
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if let Some(content) = &document.content
+            && (content_changed || restored_from.is_some())
+        {
+            DocumentRevision::record(
+                &mut *tx,
+                document.id,
+                &document.title,
+                content,
+                author,
+                restored_from,
+            )
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(document)
     }
 
     pub async fn delete(pool: &PgPool, id: Uuid) -> Result<u64, sqlx::Error> {
@@ -952,4 +1042,76 @@ impl Document {
         .fetch_one(pool)
         .await
     }
+
+    /// Store (or with `None`, clear) the cloud sync state under
+    /// `storage_metadata.cloud_sync`, leaving other metadata and `updated_at`
+    /// untouched
+    pub async fn set_cloud_sync_state(
+        pool: &PgPool,
+        id: Uuid,
+        state: Option<&serde_json::Value>,
+    ) -> Result<(), sqlx::Error> {
+        match state {
+            Some(state) => {
+                sqlx::query(
+                    r#"UPDATE documents
+                       SET storage_metadata = jsonb_set(
+                           COALESCE(storage_metadata, '{}'::jsonb), '{cloud_sync}', $2)
+                       WHERE id = $1"#,
+                )
+                .bind(id)
+                .bind(state)
+                .execute(pool)
+                .await?;
+            }
+            None => {
+                sqlx::query(
+                    "UPDATE documents SET storage_metadata = storage_metadata - 'cloud_sync' WHERE id = $1",
+                )
+                .bind(id)
+                .execute(pool)
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Replace the content with a version pulled from cloud storage, unless
+    /// the document was edited since `expected_updated_at`. Returns whether
+    /// the update was applied; applied pulls are recorded as a cloud storage
+    /// sync revision.
+    pub async fn update_synced_content(
+        pool: &PgPool,
+        id: Uuid,
+        content: &str,
+        expected_updated_at: DateTime<Utc>,
+    ) -> Result<bool, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let title = sqlx::query_scalar::<_, String>(
+            r#"UPDATE documents
+               SET content = $2, updated_at = NOW()
+               WHERE id = $1 AND updated_at = $3
+               RETURNING title"#,
+        )
+        .bind(id)
+        .bind(content)
+        .bind(expected_updated_at)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(title) = title else {
+            return Ok(false);
+        };
+        DocumentRevision::record(
+            &mut *tx,
+            id,
+            &title,
+            content,
+            &RevisionAuthor::sync(RevisionSource::CloudStorageSync, None),
+            None,
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(true)
+    }
 }
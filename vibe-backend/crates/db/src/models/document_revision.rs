@@ -0,0 +1,261 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Executor, FromRow, PgPool, Postgres, Type};
+use strum_macros::Display;
+use ts_rs::TS;
+use utils::diff::{compute_line_change_counts, create_unified_diff};
+use uuid::Uuid;
+
+use super::execution_process::{
+    ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
+};
+
+/// Header MCP clients send so edits made by a coding agent are attributed to
+/// its execution process
+pub const WORKSPACE_ID_HEADER: &str = "x-workspace-id";
+
+/// System process that made a content change
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS, Display)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum RevisionSource {
+    FilesystemScan,
+    GithubSync,
+    CloudStorageSync,
+}
+
+/// A snapshot of a document's content, taken on every content change
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct DocumentRevision {
+    pub id: Uuid,
+    pub document_id: Uuid,
+    /// 1-based, per document
+    pub revision_number: i32,
+    pub title: String,
+    pub content: String,
+    /// Content size in bytes
+    pub content_size: i64,
+    pub author_user_id: Option<String>,
+    /// Set when the change was made by a coding agent
+    pub author_execution_process_id: Option<Uuid>,
+    /// Set when the change was made by a scan or sync rather than an edit
+    pub author_source: Option<RevisionSource>,
+    /// Revision number this revision restored, if any
+    pub restored_from: Option<i32>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// A revision without its content, for history listings
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct DocumentRevisionSummary {
+    pub id: Uuid,
+    pub document_id: Uuid,
+    pub revision_number: i32,
+    pub title: String,
+    pub content_size: i64,
+    pub author_user_id: Option<String>,
+    pub author_execution_process_id: Option<Uuid>,
+    pub author_source: Option<RevisionSource>,
+    pub restored_from: Option<i32>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Line diff between two revisions of a document
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct DocumentRevisionDiff {
+    pub document_id: Uuid,
+    pub from_revision: i32,
+    pub to_revision: i32,
+    pub unified_diff: String,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+/// Who made a content change. All fields are empty only when no user is
+/// signed in.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RevisionAuthor {
+    pub user_id: Option<String>,
+    pub execution_process_id: Option<Uuid>,
+    pub source: Option<RevisionSource>,
+}
+
+impl RevisionAuthor {
+    pub fn user(user_id: impl Into<String>) -> Self {
+        Self {
+            user_id: Some(user_id.into()),
+            ..Self::default()
+        }
+    }
+
+    /// A change made by `source`, on behalf of `user_id` when a user started it
+    pub fn sync(source: RevisionSource, user_id: Option<String>) -> Self {
+        Self {
+            user_id,
+            execution_process_id: None,
+            source: Some(source),
+        }
+    }
+
+    /// Attribute a change to `user_id` and, when `workspace_id` has a running
+    /// coding agent, to that agent's execution process.
+    ///
+    /// `workspace_id` usually comes from a client header; callers must check
+    /// that the user can access the workspace's task first.
+    pub async fn resolve(
+        pool: &PgPool,
+        user_id: Option<String>,
+        workspace_id: Option<Uuid>,
+    ) -> Result<Self, sqlx::Error> {
+        let execution_process_id = match workspace_id {
+            Some(workspace_id) => ExecutionProcess::find_latest_by_workspace_and_run_reason(
+                pool,
+                workspace_id,
+                &ExecutionProcessRunReason::CodingAgent,
+            )
+            .await?
+            .filter(|process| process.status == ExecutionProcessStatus::Running)
+            .map(|process| process.id),
+            None => None,
+        };
+        Ok(Self {
+            user_id,
+            execution_process_id,
+            source: None,
+        })
+    }
+}
+
+const SUMMARY_COLUMNS: &str = "id, document_id, revision_number, title, content_size, \
+     author_user_id, author_execution_process_id, author_source, restored_from, created_at";
+
+const REVISION_COLUMNS: &str = "id, document_id, revision_number, title, content, content_size, \
+     author_user_id, author_execution_process_id, author_source, restored_from, created_at";
+
+impl DocumentRevision {
+    /// Store a new revision numbered after the document's latest one.
+    ///
+    /// Run this in the transaction that changed the document: the row lock
+    /// taken by that update keeps concurrent writers from picking the same
+    /// number.
+    pub async fn record<'e, E>(
+        executor: E,
+        document_id: Uuid,
+        title: &str,
+        content: &str,
+        author: &RevisionAuthor,
+        restored_from: Option<i32>,
+    ) -> Result<Self, sqlx::Error>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = format!(
+            r#"INSERT INTO document_revisions
+                   (document_id, revision_number, title, content, content_size,
+                    author_user_id, author_execution_process_id, author_source, restored_from)
+               SELECT $1, COALESCE(MAX(revision_number), 0) + 1, $2, $3, $4, $5, $6, $7, $8
+               FROM document_revisions
+               WHERE document_id = $1
+               RETURNING {REVISION_COLUMNS}"#
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(document_id)
+            .bind(title)
+            .bind(content)
+            .bind(content.len() as i64)
+            .bind(&author.user_id)
+            .bind(author.execution_process_id)
+            .bind(author.source)
+            .bind(restored_from)
+            .fetch_one(executor)
+            .await
+    }
+
+    /// Newest first
+    pub async fn list_by_document(
+        pool: &PgPool,
+        document_id: Uuid,
+    ) -> Result<Vec<DocumentRevisionSummary>, sqlx::Error> {
+        let query = format!(
+            "SELECT {SUMMARY_COLUMNS} FROM document_revisions
+             WHERE document_id = $1
+             ORDER BY revision_number DESC"
+        );
+        sqlx::query_as::<_, DocumentRevisionSummary>(&query)
+            .bind(document_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn find_by_number(
+        pool: &PgPool,
+        document_id: Uuid,
+        revision_number: i32,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let query = format!(
+            "SELECT {REVISION_COLUMNS} FROM document_revisions
+             WHERE document_id = $1 AND revision_number = $2"
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(document_id)
+            .bind(revision_number)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Diff `self` (the older side) against `to`
+    pub fn diff(&self, to: &DocumentRevision) -> DocumentRevisionDiff {
+        let (additions, deletions) = compute_line_change_counts(&self.content, &to.content);
+        DocumentRevisionDiff {
+            document_id: self.document_id,
+            from_revision: self.revision_number,
+            to_revision: to.revision_number,
+            unified_diff: create_unified_diff(&to.title, &self.content, &to.content),
+            additions,
+            deletions,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn revision(number: i32, content: &str) -> DocumentRevision {
+        DocumentRevision {
+            id: Uuid::new_v4(),
+            document_id: Uuid::nil(),
+            revision_number: number,
+            title: "plan.md".to_string(),
+            content: content.to_string(),
+            content_size: content.len() as i64,
+            author_user_id: None,
+            author_execution_process_id: None,
+            author_source: None,
+            restored_from: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn diff_counts_changed_lines() {
+        let from = revision(1, "# Plan\n\n- one\n- two\n");
+        let to = revision(3, "# Plan\n\n- one\n- three\n- four\n");
+
+        let diff = from.diff(&to);
+        assert_eq!((diff.from_revision, diff.to_revision), (1, 3));
+        assert_eq!((diff.additions, diff.deletions), (2, 1));
+        assert!(diff.unified_diff.contains("-- two"));
+        assert!(diff.unified_diff.contains("+- three"));
+    }
+
+    #[test]
+    fn diff_of_identical_revisions_is_empty() {
+        let diff = revision(1, "same").diff(&revision(2, "same"));
+        assert_eq!((diff.additions, diff.deletions), (0, 0));
+        assert!(!diff.unified_diff.contains("@@"));
+    }
+}
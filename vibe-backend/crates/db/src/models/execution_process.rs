@@ -321,6 +321,27 @@ impl ExecutionProcess {
         .await
     }
 
+    /// Wall-clock time spent in coding agent runs of a workspace, counting
+    /// still-running processes up to now
+    pub async fn coding_agent_run_time_ms(
+        pool: &PgPool,
+        workspace_id: Uuid,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar::<_, i64>(
+            r#"SELECT COALESCE(
+                   SUM(EXTRACT(EPOCH FROM (COALESCE(ep.completed_at, NOW()) - ep.started_at)) * 1000),
+                   0
+               )::BIGINT
+               FROM execution_processes ep
+               JOIN sessions s ON ep.session_id = s.id
+               WHERE s.workspace_id = $1
+                 AND ep.run_reason = 'codingagent'"#,
+        )
+        .bind(workspace_id)
+        .fetch_one(pool)
+        .await
+    }
+
     /// Find latest coding_agent_turn agent_session_id by session (simple scalar query)
     pub async fn find_latest_coding_agent_turn_session_id(
         pool: &PgPool,
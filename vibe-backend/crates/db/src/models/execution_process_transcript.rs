@@ -0,0 +1,81 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+/// Compressed normalized conversation of one execution process
+#[derive(Debug, Clone, FromRow)]
+pub struct ExecutionProcessTranscript {
+    pub execution_process_id: Uuid,
+    pub executor_type: String,
+    pub entry_count: i32,
+    /// Size of the serialized conversation before compression
+    pub byte_size: i64,
+    /// Gzip-compressed JSON
+    pub data: Vec<u8>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UpsertExecutionProcessTranscript<'a> {
+    pub executor_type: &'a str,
+    pub entry_count: i32,
+    pub byte_size: i64,
+    pub data: &'a [u8],
+}
+
+const TRANSCRIPT_COLUMNS: &str =
+    "execution_process_id, executor_type, entry_count, byte_size, data, created_at";
+
+impl ExecutionProcessTranscript {
+    pub async fn upsert(
+        pool: &PgPool,
+        execution_process_id: Uuid,
+        data: &UpsertExecutionProcessTranscript<'_>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"INSERT INTO execution_process_transcripts
+                   (execution_process_id, executor_type, entry_count, byte_size, data)
+               VALUES ($1, $2, $3, $4, $5)
+               ON CONFLICT (execution_process_id) DO UPDATE SET
+                   executor_type = EXCLUDED.executor_type,
+                   entry_count = EXCLUDED.entry_count,
+                   byte_size = EXCLUDED.byte_size,
+                   data = EXCLUDED.data"#,
+        )
+        .bind(execution_process_id)
+        .bind(data.executor_type)
+        .bind(data.entry_count)
+        .bind(data.byte_size)
+        .bind(data.data)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &PgPool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let query = format!(
+            "SELECT {TRANSCRIPT_COLUMNS} FROM execution_process_transcripts
+             WHERE execution_process_id = $1"
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(execution_process_id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Delete transcripts stored before `cutoff`, returning how many were
+    /// removed
+    pub async fn delete_created_before(
+        pool: &PgPool,
+        cutoff: DateTime<Utc>,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM execution_process_transcripts WHERE created_at < $1")
+            .bind(cutoff)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
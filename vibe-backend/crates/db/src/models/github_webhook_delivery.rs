@@ -0,0 +1,170 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool, Type};
+use strum_macros::{Display, EnumString};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Processing state of a stored GitHub webhook delivery
+#[derive(
+    Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS, EnumString, Display,
+)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+#[ts(export)]
+pub enum WebhookDeliveryStatus {
+    Received,
+    Processed,
+    Ignored,
+    Failed,
+}
+
+/// A GitHub webhook delivery recorded in the ledger
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, TS)]
+#[ts(export)]
+pub struct GitHubWebhookDelivery {
+    pub id: Uuid,
+    pub delivery_id: String,
+    pub event_type: String,
+    pub action: Option<String>,
+    #[ts(type = "unknown")]
+    pub payload: serde_json::Value,
+    pub status: WebhookDeliveryStatus,
+    pub error_message: Option<String>,
+    pub attempts: i32,
+    #[ts(type = "Date")]
+    pub received_at: DateTime<Utc>,
+    #[ts(type = "Date | null")]
+    pub processed_at: Option<DateTime<Utc>>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// How long a delivery may stay `received` before it is considered abandoned
+/// (e.g. the server crashed mid-processing) and may be processed again
+pub const PROCESSING_LEASE_SECONDS: i64 = 10 * 60;
+
+const DELIVERY_COLUMNS: &str = r#"
+    id, delivery_id, event_type, action, payload, status, error_message,
+    attempts, received_at, processed_at, updated_at
+"#;
+
+impl GitHubWebhookDelivery {
+    /// Store a delivery before processing it.
+    ///
+    /// Returns `None` when the delivery id was already recorded and is
+    /// processed, ignored or still being processed, i.e. it is a duplicate
+    /// that must be skipped. A failed delivery, or one left `received` for
+    /// longer than the processing lease, is reset and returned so it is
+    /// processed again.
+    pub async fn record(
+        pool: &PgPool,
+        delivery_id: &str,
+        event_type: &str,
+        action: Option<&str>,
+        payload: &serde_json::Value,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let query = format!(
+            r#"
+            INSERT INTO github_webhook_deliveries (delivery_id, event_type, action, payload, attempts)
+            VALUES ($1, $2, $3, $4, 1)
+            ON CONFLICT (delivery_id) DO UPDATE
+            SET status = 'received',
+                payload = EXCLUDED.payload,
+                error_message = NULL,
+                attempts = github_webhook_deliveries.attempts + 1,
+                updated_at = NOW()
+            WHERE github_webhook_deliveries.status = 'failed'
+               OR (github_webhook_deliveries.status = 'received'
+                   AND github_webhook_deliveries.updated_at < NOW() - $5 * INTERVAL '1 second')
+            RETURNING {DELIVERY_COLUMNS}
+            "#
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(delivery_id)
+            .bind(event_type)
+            .bind(action)
+            .bind(payload)
+            .bind(PROCESSING_LEASE_SECONDS)
+            .fetch_optional(pool)
+            .await
+    }
+
+    pub async fn find_by_id(pool: &PgPool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        let query =
+            format!("SELECT {DELIVERY_COLUMNS} FROM github_webhook_deliveries WHERE id = $1");
+        sqlx::query_as::<_, Self>(&query)
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// List deliveries, newest first, optionally filtered by status
+    pub async fn list(
+        pool: &PgPool,
+        status: Option<WebhookDeliveryStatus>,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT {DELIVERY_COLUMNS}
+            FROM github_webhook_deliveries
+            WHERE $1::text IS NULL OR status = $1
+            ORDER BY received_at DESC
+            LIMIT $2
+            "#
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(status)
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Claim a failed or abandoned delivery for replay. Returns `None` if it
+    /// is neither (already replayed, processed, or still being processed).
+    pub async fn begin_replay(pool: &PgPool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        let query = format!(
+            r#"
+            UPDATE github_webhook_deliveries
+            SET status = 'received', error_message = NULL,
+                attempts = attempts + 1, updated_at = NOW()
+            WHERE id = $1
+              AND (status = 'failed'
+                   OR (status = 'received' AND updated_at < NOW() - $2 * INTERVAL '1 second'))
+            RETURNING {DELIVERY_COLUMNS}
+            "#
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(id)
+            .bind(PROCESSING_LEASE_SECONDS)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Record the outcome of processing a delivery
+    pub async fn finish(
+        pool: &PgPool,
+        id: Uuid,
+        status: WebhookDeliveryStatus,
+        error_message: Option<&str>,
+    ) -> Result<Self, sqlx::Error> {
+        let query = format!(
+            r#"
+            UPDATE github_webhook_deliveries
+            SET status = $2, error_message = $3,
+                processed_at = CASE WHEN $2 = 'failed' THEN processed_at ELSE NOW() END,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING {DELIVERY_COLUMNS}
+            "#
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(id)
+            .bind(status)
+            .bind(error_message)
+            .fetch_one(pool)
+            .await
+    }
+}
@@ -69,6 +69,18 @@ pub enum MergeType {
     Pr,
 }
 
+/// Open PR columns; `pr_status` is implied by the query
+#[derive(FromRow)]
+struct OpenPrRow {
+    id: Uuid,
+    workspace_id: Uuid,
+    repo_id: Uuid,
+    target_branch_name: String,
+    pr_number: i64,
+    pr_url: String,
+    created_at: DateTime<Utc>,
+}
+
 #[derive(FromRow)]
 struct MergeRow {
     id: Uuid,
@@ -299,6 +311,40 @@ impl Merge {
 
         Ok(rows.into_iter().map(Into::into).collect())
     }
+
+    /// Find open PRs by URL, used to match provider webhooks to workspaces
+    pub async fn find_open_prs_by_url(
+        pool: &PgPool,
+        pr_url: &str,
+    ) -> Result<Vec<PrMerge>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, OpenPrRow>(
+            r#"SELECT id, workspace_id, repo_id, target_branch_name, pr_number, pr_url, created_at
+               FROM merges
+               WHERE merge_type = 'pr' AND pr_status = 'open' AND pr_url = $1
+               ORDER BY created_at DESC"#,
+        )
+        .bind(pr_url)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| PrMerge {
+                id: row.id,
+                workspace_id: row.workspace_id,
+                repo_id: row.repo_id,
+                created_at: row.created_at,
+                target_branch_name: row.target_branch_name,
+                pr_info: PullRequestInfo {
+                    number: row.pr_number,
+                    url: row.pr_url,
+                    status: MergeStatus::Open,
+                    merged_at: None,
+                    merge_commit_sha: None,
+                },
+            })
+            .collect())
+    }
 }
 
 // Conversion implementations
@@ -0,0 +1,385 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// How a verified attempt lands on its target branch
+#[derive(Debug, Clone, Copy, Default, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStrategy {
+    /// One commit on the target, like a regular merge
+    #[default]
+    Squash,
+    /// The rebased commits as they are
+    FastForward,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum MergeQueueStatus {
+    Queued,
+    Rebasing,
+    Verifying,
+    Merged,
+    /// Taken out of the queue, see `eject_reason`
+    Ejected,
+}
+
+/// Verification applied to a project repository's merge queue
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct MergeQueueConfig {
+    pub project_id: Uuid,
+    pub repo_id: Uuid,
+    /// Run with the shell in the rebased worktree; a non-zero exit ejects
+    pub verification_script: Option<String>,
+    pub verification_timeout_secs: i32,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpsertMergeQueueConfig {
+    pub verification_script: Option<String>,
+    pub verification_timeout_secs: Option<i32>,
+}
+
+const DEFAULT_VERIFICATION_TIMEOUT_SECS: i32 = 1800;
+
+const MERGE_QUEUE_CONFIG_COLUMNS: &str = "project_id, repo_id, verification_script, \
+     verification_timeout_secs, created_at, updated_at";
+
+impl MergeQueueConfig {
+    pub async fn find(
+        pool: &PgPool,
+        project_id: Uuid,
+        repo_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let query = format!(
+            "SELECT {MERGE_QUEUE_CONFIG_COLUMNS} FROM merge_queue_configs
+             WHERE project_id = $1 AND repo_id = $2"
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(project_id)
+            .bind(repo_id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    pub async fn upsert(
+        pool: &PgPool,
+        project_id: Uuid,
+        repo_id: Uuid,
+        data: &UpsertMergeQueueConfig,
+    ) -> Result<Self, sqlx::Error> {
+        let query = format!(
+            r#"INSERT INTO merge_queue_configs
+                   (project_id, repo_id, verification_script, verification_timeout_secs)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT (project_id, repo_id) DO UPDATE SET
+                   verification_script = EXCLUDED.verification_script,
+                   verification_timeout_secs = EXCLUDED.verification_timeout_secs,
+                   updated_at = NOW()
+               RETURNING {MERGE_QUEUE_CONFIG_COLUMNS}"#
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(project_id)
+            .bind(repo_id)
+            .bind(&data.verification_script)
+            .bind(
+                data.verification_timeout_secs
+                    .unwrap_or(DEFAULT_VERIFICATION_TIMEOUT_SECS),
+            )
+            .fetch_one(pool)
+            .await
+    }
+
+    pub async fn delete(
+        pool: &PgPool,
+        project_id: Uuid,
+        repo_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result =
+            sqlx::query("DELETE FROM merge_queue_configs WHERE project_id = $1 AND repo_id = $2")
+                .bind(project_id)
+                .bind(repo_id)
+                .execute(pool)
+                .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct MergeQueueEntry {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    pub target_branch: String,
+    pub strategy: MergeStrategy,
+    pub status: MergeQueueStatus,
+    pub merge_commit_sha: Option<String>,
+    pub eject_reason: Option<String>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+    #[ts(type = "Date | null")]
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateMergeQueueEntry {
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    pub target_branch: String,
+    pub strategy: MergeStrategy,
+}
+
+const MERGE_QUEUE_ENTRY_COLUMNS: &str = "id, workspace_id, repo_id, task_id, project_id, \
+     target_branch, strategy, status, merge_commit_sha, eject_reason, created_at, updated_at, \
+     completed_at";
+
+impl MergeQueueEntry {
+    /// Fails with a unique violation when the attempt's repository is
+    /// already queued
+    pub async fn create(pool: &PgPool, data: &CreateMergeQueueEntry) -> Result<Self, sqlx::Error> {
+        let query = format!(
+            r#"INSERT INTO merge_queue_entries
+                   (workspace_id, repo_id, task_id, project_id, target_branch, strategy)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING {MERGE_QUEUE_ENTRY_COLUMNS}"#
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(data.workspace_id)
+            .bind(data.repo_id)
+            .bind(data.task_id)
+            .bind(data.project_id)
+            .bind(&data.target_branch)
+            .bind(data.strategy)
+            .fetch_one(pool)
+            .await
+    }
+
+    pub async fn find_by_id(pool: &PgPool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        let query =
+            format!("SELECT {MERGE_QUEUE_ENTRY_COLUMNS} FROM merge_queue_entries WHERE id = $1");
+        sqlx::query_as::<_, Self>(&query)
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Active entries of a project and those finished in the last day, in
+    /// queue order
+    pub async fn find_by_project_id(
+        pool: &PgPool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let query = format!(
+            "SELECT {MERGE_QUEUE_ENTRY_COLUMNS} FROM merge_queue_entries
+             WHERE project_id = $1
+               AND (completed_at IS NULL OR completed_at > NOW() - INTERVAL '1 day')
+             ORDER BY created_at ASC"
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(project_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Entries of an attempt, newest first
+    pub async fn find_by_workspace_id(
+        pool: &PgPool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let query = format!(
+            "SELECT {MERGE_QUEUE_ENTRY_COLUMNS} FROM merge_queue_entries
+             WHERE workspace_id = $1
+             ORDER BY created_at DESC"
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(workspace_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    /// The entry a lane lands next
+    pub async fn next_queued(
+        pool: &PgPool,
+        repo_id: Uuid,
+        target_branch: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let query = format!(
+            "SELECT {MERGE_QUEUE_ENTRY_COLUMNS} FROM merge_queue_entries
+             WHERE repo_id = $1 AND target_branch = $2 AND status = 'queued'
+             ORDER BY created_at ASC
+             LIMIT 1"
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(repo_id)
+            .bind(target_branch)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Lanes with entries waiting to land
+    pub async fn find_queued_lanes(pool: &PgPool) -> Result<Vec<(Uuid, String)>, sqlx::Error> {
+        sqlx::query_as(
+            "SELECT DISTINCT repo_id, target_branch FROM merge_queue_entries
+             WHERE status = 'queued'",
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn set_status(
+        pool: &PgPool,
+        id: Uuid,
+        status: MergeQueueStatus,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE merge_queue_entries SET status = $2, updated_at = NOW() WHERE id = $1")
+            .bind(id)
+            .bind(status)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn finish(
+        pool: &PgPool,
+        id: Uuid,
+        status: MergeQueueStatus,
+        merge_commit_sha: Option<&str>,
+        eject_reason: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE merge_queue_entries
+             SET status = $2, merge_commit_sha = $3, eject_reason = $4,
+                 updated_at = NOW(), completed_at = NOW()
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(status)
+        .bind(merge_commit_sha)
+        .bind(eject_reason)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Take an entry out of the queue before its lane picks it up
+    pub async fn remove_queued(pool: &PgPool, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result =
+            sqlx::query("DELETE FROM merge_queue_entries WHERE id = $1 AND status = 'queued'")
+                .bind(id)
+                .execute(pool)
+                .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Put entries a restart interrupted back in the queue; landing starts
+    /// over with a fresh rebase
+    pub async fn requeue_interrupted(pool: &PgPool) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE merge_queue_entries SET status = 'queued', updated_at = NOW()
+             WHERE status IN ('rebasing', 'verifying')",
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROJECT: Uuid = Uuid::from_u128(0xb1);
+    const TASK: Uuid = Uuid::from_u128(0xc1);
+    const REPO: Uuid = Uuid::from_u128(0xe1);
+
+    async fn entry_with_status(pool: &PgPool, status: MergeQueueStatus) -> MergeQueueEntry {
+        let entry = MergeQueueEntry::create(
+            pool,
+            &CreateMergeQueueEntry {
+                workspace_id: Uuid::new_v4(),
+                repo_id: REPO,
+                task_id: TASK,
+                project_id: PROJECT,
+                target_branch: "main".to_string(),
+                strategy: MergeStrategy::Squash,
+            },
+        )
+        .await
+        .unwrap();
+        match status {
+            MergeQueueStatus::Queued => {}
+            MergeQueueStatus::Rebasing | MergeQueueStatus::Verifying => {
+                MergeQueueEntry::set_status(pool, entry.id, status)
+                    .await
+                    .unwrap()
+            }
+            MergeQueueStatus::Merged | MergeQueueStatus::Ejected => {
+                MergeQueueEntry::finish(pool, entry.id, status, None, None)
+                    .await
+                    .unwrap()
+            }
+        }
+        entry
+    }
+
+    async fn status_of(pool: &PgPool, entry: &MergeQueueEntry) -> MergeQueueStatus {
+        MergeQueueEntry::find_by_id(pool, entry.id)
+            .await
+            .unwrap()
+            .unwrap()
+            .status
+    }
+
+    #[ignore = "requires DATABASE_URL"]
+    #[sqlx::test(
+        migrations = false,
+        fixtures(
+            "../../tests/fixtures/merge_queue_seed.sql",
+            "../../../remote/migrations/20260129090000_create_change_feed_triggers.sql",
+            "../../../remote/migrations/20260216090000_create_merge_queue.sql"
+        )
+    )]
+    async fn requeue_interrupted_puts_only_landing_entries_back(pool: PgPool) {
+        let queued = entry_with_status(&pool, MergeQueueStatus::Queued).await;
+        let rebasing = entry_with_status(&pool, MergeQueueStatus::Rebasing).await;
+        let verifying = entry_with_status(&pool, MergeQueueStatus::Verifying).await;
+        let merged = entry_with_status(&pool, MergeQueueStatus::Merged).await;
+        let ejected = entry_with_status(&pool, MergeQueueStatus::Ejected).await;
+
+        assert_eq!(
+            MergeQueueEntry::requeue_interrupted(&pool).await.unwrap(),
+            2
+        );
+
+        for entry in [&queued, &rebasing, &verifying] {
+            assert_eq!(status_of(&pool, entry).await, MergeQueueStatus::Queued);
+        }
+        assert_eq!(status_of(&pool, &merged).await, MergeQueueStatus::Merged);
+        assert_eq!(status_of(&pool, &ejected).await, MergeQueueStatus::Ejected);
+
+        // The lane lands them again in queue order
+        let next = MergeQueueEntry::next_queued(&pool, REPO, "main")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(next.id, queued.id);
+        assert_eq!(
+            MergeQueueEntry::find_queued_lanes(&pool).await.unwrap(),
+            [(REPO, "main".to_string())]
+        );
+    }
+}
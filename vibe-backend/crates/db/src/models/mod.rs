@@ -6,36 +6,55 @@ pub use abuse_detection_signal::{
 pub mod agent_config;
 pub mod ai_provider_key;
 pub mod api_key;
+pub mod approval_policy;
+pub mod attempt_group;
 pub mod chat_message;
+pub mod ci_repair_attempt;
+pub mod coding_agent_checkpoint;
 pub mod coding_agent_turn;
+pub mod conflict_resolution;
 pub mod conversation;
 pub mod conversation_participant;
 pub mod copilot_assignment;
 pub mod copilot_deployment_config;
 pub mod document;
+pub mod document_revision;
 pub mod execution_process;
 pub mod execution_process_logs;
 pub mod execution_process_repo_state;
+pub mod execution_process_transcript;
 pub mod github_connection;
+pub mod github_webhook_delivery;
 pub mod gitlab_connection;
 pub mod image;
 pub mod inbox;
 pub mod member_project_access;
 pub mod merge;
+pub mod merge_queue;
 pub mod plan_limits;
+pub mod pr_review_followup;
 pub mod project;
+pub mod project_budget;
 pub mod project_repo;
+pub mod project_sandbox;
+pub mod queued_attempt;
 pub mod repo;
 pub mod scratch;
+pub mod search;
 pub mod session;
 pub mod tag;
 pub mod task;
 pub mod task_comment;
+pub mod task_dependency;
 pub mod task_document_link;
 pub mod task_tag;
+pub mod task_template;
 pub mod team;
 pub mod team_member;
+pub mod team_storage_config;
 pub mod tenant_workspace;
+pub mod token_usage;
+pub mod tool_approval;
 pub mod trust_level_progression;
 pub mod user_registration;
 pub mod user_trust_moderation;
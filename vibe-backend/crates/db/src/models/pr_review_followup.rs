@@ -0,0 +1,156 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A review comment a follow-up was asked to address
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum ReviewCommentRef {
+    /// Conversation comment on the PR
+    General { id: String, url: String },
+    /// Inline review thread, identified by its first comment
+    Thread { root_id: i64, comment_ids: Vec<i64> },
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PrReviewFollowUpStatus {
+    Running,
+    /// Pushed, and every addressed thread got a reply
+    Replied,
+    /// The agent made no commits, so nothing was pushed or replied
+    NoChanges,
+    Failed,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct PrReviewFollowUp {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub execution_process_id: Uuid,
+    pub pr_number: i64,
+    pub pr_url: String,
+    #[ts(type = "Array<ReviewCommentRef>")]
+    pub comments: Json<Vec<ReviewCommentRef>>,
+    pub status: PrReviewFollowUpStatus,
+    pub commit_sha: Option<String>,
+    pub error: Option<String>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date | null")]
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreatePrReviewFollowUp {
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub execution_process_id: Uuid,
+    pub pr_number: i64,
+    pub pr_url: String,
+    pub comments: Vec<ReviewCommentRef>,
+}
+
+const PR_REVIEW_FOLLOWUP_COLUMNS: &str = "id, workspace_id, repo_id, execution_process_id, \
+     pr_number, pr_url, comments, status, commit_sha, error, created_at, completed_at";
+
+impl PrReviewFollowUp {
+    pub async fn create(pool: &PgPool, data: &CreatePrReviewFollowUp) -> Result<Self, sqlx::Error> {
+        let query = format!(
+            r#"INSERT INTO pr_review_followups
+                   (workspace_id, repo_id, execution_process_id, pr_number, pr_url, comments)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING {PR_REVIEW_FOLLOWUP_COLUMNS}"#
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(data.workspace_id)
+            .bind(data.repo_id)
+            .bind(data.execution_process_id)
+            .bind(data.pr_number)
+            .bind(&data.pr_url)
+            .bind(Json(&data.comments))
+            .fetch_one(pool)
+            .await
+    }
+
+    /// Follow-ups of a workspace, newest first
+    pub async fn find_by_workspace_id(
+        pool: &PgPool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let query = format!(
+            "SELECT {PR_REVIEW_FOLLOWUP_COLUMNS} FROM pr_review_followups
+             WHERE workspace_id = $1
+             ORDER BY created_at DESC"
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(workspace_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn find_running_by_workspace_id(
+        pool: &PgPool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let query = format!(
+            "SELECT {PR_REVIEW_FOLLOWUP_COLUMNS} FROM pr_review_followups
+             WHERE workspace_id = $1 AND status = 'running'
+             ORDER BY created_at ASC"
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(workspace_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Comments of a PR that are being or have been addressed. Failed and
+    /// change-free follow-ups don't count, so their comments can be retried.
+    pub async fn find_addressed_comments(
+        pool: &PgPool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        pr_number: i64,
+    ) -> Result<Vec<ReviewCommentRef>, sqlx::Error> {
+        let rows: Vec<Json<Vec<ReviewCommentRef>>> = sqlx::query_scalar(
+            "SELECT comments FROM pr_review_followups
+             WHERE workspace_id = $1 AND repo_id = $2 AND pr_number = $3
+               AND status IN ('running', 'replied')",
+        )
+        .bind(workspace_id)
+        .bind(repo_id)
+        .bind(pr_number)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .flat_map(|Json(comments)| comments)
+            .collect())
+    }
+
+    pub async fn complete(
+        pool: &PgPool,
+        id: Uuid,
+        status: PrReviewFollowUpStatus,
+        commit_sha: Option<&str>,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE pr_review_followups
+             SET status = $2, commit_sha = $3, error = $4, completed_at = NOW()
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(status)
+        .bind(commit_sha)
+        .bind(error)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
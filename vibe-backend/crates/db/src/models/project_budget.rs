@@ -0,0 +1,200 @@
+use chrono::{DateTime, Datelike, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::token_usage::ExecutionProcessUsage;
+
+/// Window a project budget is measured over
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum BudgetPeriod {
+    /// Resets on the first of each UTC month
+    Monthly,
+    /// Lifetime spend of the project
+    Total,
+}
+
+impl BudgetPeriod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Monthly => "monthly",
+            Self::Total => "total",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "monthly" => Some(Self::Monthly),
+            "total" => Some(Self::Total),
+            _ => None,
+        }
+    }
+
+    /// Start of the period containing `now`; `None` for lifetime budgets
+    pub fn start(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Monthly => Utc
+                .with_ymd_and_hms(now.year(), now.month(), 1, 0, 0, 0)
+                .single(),
+            Self::Total => None,
+        }
+    }
+}
+
+/// Spend limit on a project's coding agent runs
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ProjectBudget {
+    pub project_id: Uuid,
+    pub limit_microdollars: i64,
+    pub period: BudgetPeriod,
+    /// Stop running attempts when the budget is exceeded, not only block new
+    /// ones
+    pub stop_running: bool,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+#[ts(export)]
+pub struct UpsertProjectBudget {
+    pub limit_microdollars: i64,
+    pub period: Option<BudgetPeriod>,
+    pub stop_running: Option<bool>,
+}
+
+/// Spend against a budget for the current period
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct BudgetStatus {
+    pub budget: ProjectBudget,
+    pub spent_microdollars: i64,
+    #[ts(type = "Date | null")]
+    pub period_start: Option<DateTime<Utc>>,
+    pub exceeded: bool,
+}
+
+// Period is stored as text; rows with an unknown period are treated as
+// monthly budgets
+#[derive(FromRow)]
+struct ProjectBudgetRow {
+    project_id: Uuid,
+    limit_microdollars: i64,
+    period: String,
+    stop_running: bool,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<ProjectBudgetRow> for ProjectBudget {
+    fn from(row: ProjectBudgetRow) -> Self {
+        let period = BudgetPeriod::parse(&row.period).unwrap_or_else(|| {
+            tracing::warn!(project_id = %row.project_id, period = %row.period, "unknown budget period");
+            BudgetPeriod::Monthly
+        });
+        Self {
+            project_id: row.project_id,
+            limit_microdollars: row.limit_microdollars,
+            period,
+            stop_running: row.stop_running,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+const BUDGET_COLUMNS: &str =
+    "project_id, limit_microdollars, period, stop_running, created_at, updated_at";
+
+impl ProjectBudget {
+    pub async fn find_by_project_id(
+        pool: &PgPool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let query = format!("SELECT {BUDGET_COLUMNS} FROM project_budgets WHERE project_id = $1");
+        let row = sqlx::query_as::<_, ProjectBudgetRow>(&query)
+            .bind(project_id)
+            .fetch_optional(pool)
+            .await?;
+        Ok(row.map(Into::into))
+    }
+
+    pub async fn upsert(
+        pool: &PgPool,
+        project_id: Uuid,
+        data: &UpsertProjectBudget,
+    ) -> Result<Self, sqlx::Error> {
+        let query = format!(
+            r#"INSERT INTO project_budgets (project_id, limit_microdollars, period, stop_running)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT (project_id) DO UPDATE SET
+                   limit_microdollars = EXCLUDED.limit_microdollars,
+                   period = EXCLUDED.period,
+                   stop_running = EXCLUDED.stop_running,
+                   updated_at = NOW()
+               RETURNING {BUDGET_COLUMNS}"#
+        );
+        let row = sqlx::query_as::<_, ProjectBudgetRow>(&query)
+            .bind(project_id)
+            .bind(data.limit_microdollars)
+            .bind(data.period.unwrap_or(BudgetPeriod::Monthly).as_str())
+            .bind(data.stop_running.unwrap_or(false))
+            .fetch_one(pool)
+            .await?;
+        Ok(row.into())
+    }
+
+    pub async fn delete(pool: &PgPool, project_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM project_budgets WHERE project_id = $1")
+            .bind(project_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Current-period spend against this budget
+    pub async fn status(self, pool: &PgPool) -> Result<BudgetStatus, sqlx::Error> {
+        let period_start = self.period.start(Utc::now());
+        let spent_microdollars =
+            ExecutionProcessUsage::project_cost_since(pool, self.project_id, period_start).await?;
+        Ok(BudgetStatus {
+            exceeded: spent_microdollars >= self.limit_microdollars,
+            spent_microdollars,
+            period_start,
+            budget: self,
+        })
+    }
+
+    /// Budget status of a project, `None` when it has no budget
+    pub async fn status_for_project(
+        pool: &PgPool,
+        project_id: Uuid,
+    ) -> Result<Option<BudgetStatus>, sqlx::Error> {
+        match Self::find_by_project_id(pool, project_id).await? {
+            Some(budget) => Ok(Some(budget.status(pool).await?)),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_period_start() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 17, 14, 5, 0).unwrap();
+        assert_eq!(
+            BudgetPeriod::Monthly.start(now),
+            Some(Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap())
+        );
+        assert_eq!(BudgetPeriod::Total.start(now), None);
+        assert_eq!(BudgetPeriod::parse("monthly"), Some(BudgetPeriod::Monthly));
+        assert_eq!(BudgetPeriod::parse("weekly"), None);
+    }
+}
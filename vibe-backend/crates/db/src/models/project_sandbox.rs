@@ -0,0 +1,166 @@
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use executors::sandbox::SandboxPolicy;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use ts_rs::TS;
+use utils::path::expand_tilde;
+use uuid::Uuid;
+
+/// Sandbox applied to a project's coding agents and scripts
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ProjectSandboxConfig {
+    pub project_id: Uuid,
+    pub enabled: bool,
+    pub allow_network: bool,
+    pub memory_limit_mb: Option<i32>,
+    /// Share of one CPU, e.g. 200 for two cores
+    pub cpu_limit_percent: Option<i32>,
+    /// Host environment variables passed into the sandbox; `None` passes all
+    pub env_allowlist: Option<Vec<String>>,
+    /// Extra read-write paths besides the workspace; `~/` expands to the
+    /// server user's home directory
+    pub writable_paths: Vec<String>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+#[ts(export)]
+pub struct UpsertProjectSandboxConfig {
+    pub enabled: Option<bool>,
+    pub allow_network: Option<bool>,
+    pub memory_limit_mb: Option<i32>,
+    pub cpu_limit_percent: Option<i32>,
+    pub env_allowlist: Option<Vec<String>>,
+    #[serde(default)]
+    pub writable_paths: Vec<String>,
+}
+
+const SANDBOX_COLUMNS: &str = "project_id, enabled, allow_network, memory_limit_mb, \
+     cpu_limit_percent, env_allowlist, writable_paths, created_at, updated_at";
+
+impl ProjectSandboxConfig {
+    pub async fn find_by_project_id(
+        pool: &PgPool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let query =
+            format!("SELECT {SANDBOX_COLUMNS} FROM project_sandbox_configs WHERE project_id = $1");
+        sqlx::query_as::<_, Self>(&query)
+            .bind(project_id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    pub async fn upsert(
+        pool: &PgPool,
+        project_id: Uuid,
+        data: &UpsertProjectSandboxConfig,
+    ) -> Result<Self, sqlx::Error> {
+        let query = format!(
+            r#"INSERT INTO project_sandbox_configs
+                   (project_id, enabled, allow_network, memory_limit_mb, cpu_limit_percent,
+                    env_allowlist, writable_paths)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               ON CONFLICT (project_id) DO UPDATE SET
+                   enabled = EXCLUDED.enabled,
+                   allow_network = EXCLUDED.allow_network,
+                   memory_limit_mb = EXCLUDED.memory_limit_mb,
+                   cpu_limit_percent = EXCLUDED.cpu_limit_percent,
+                   env_allowlist = EXCLUDED.env_allowlist,
+                   writable_paths = EXCLUDED.writable_paths,
+                   updated_at = NOW()
+               RETURNING {SANDBOX_COLUMNS}"#
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(project_id)
+            .bind(data.enabled.unwrap_or(true))
+            .bind(data.allow_network.unwrap_or(false))
+            .bind(data.memory_limit_mb)
+            .bind(data.cpu_limit_percent)
+            .bind(&data.env_allowlist)
+            .bind(&data.writable_paths)
+            .fetch_one(pool)
+            .await
+    }
+
+    pub async fn delete(pool: &PgPool, project_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM project_sandbox_configs WHERE project_id = $1")
+            .bind(project_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Sandbox policy for one execution, `None` when the sandbox is disabled.
+    /// `writable_paths` (the workspace and the git directories it commits to)
+    /// are writable in addition to the configured paths; `read_only_paths`
+    /// stay read-only even inside any of them.
+    pub fn policy(
+        &self,
+        writable_paths: impl IntoIterator<Item = PathBuf>,
+        read_only_paths: impl IntoIterator<Item = PathBuf>,
+    ) -> Option<SandboxPolicy> {
+        if !self.enabled {
+            return None;
+        }
+        let mut paths: Vec<PathBuf> = writable_paths.into_iter().collect();
+        paths.extend(self.writable_paths.iter().map(|path| expand_tilde(path)));
+        Some(SandboxPolicy {
+            writable_paths: paths,
+            read_only_paths: read_only_paths.into_iter().collect(),
+            allow_network: self.allow_network,
+            memory_limit_mb: self.memory_limit_mb.and_then(|mb| u32::try_from(mb).ok()),
+            cpu_limit_percent: self
+                .cpu_limit_percent
+                .and_then(|percent| u32::try_from(percent).ok()),
+            env_allowlist: self.env_allowlist.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(enabled: bool) -> ProjectSandboxConfig {
+        ProjectSandboxConfig {
+            project_id: Uuid::new_v4(),
+            enabled,
+            allow_network: false,
+            memory_limit_mb: Some(4096),
+            cpu_limit_percent: None,
+            env_allowlist: None,
+            writable_paths: vec!["/var/cache/builds".to_string()],
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_policy_combines_writable_paths() {
+        let policy = config(true)
+            .policy(
+                [PathBuf::from("/work/attempt")],
+                [PathBuf::from("/repo/.git/hooks")],
+            )
+            .unwrap();
+        assert_eq!(
+            policy.writable_paths,
+            [
+                PathBuf::from("/work/attempt"),
+                PathBuf::from("/var/cache/builds")
+            ]
+        );
+        assert_eq!(policy.read_only_paths, [PathBuf::from("/repo/.git/hooks")]);
+        assert_eq!(policy.memory_limit_mb, Some(4096));
+        assert!(!policy.allow_network);
+
+        assert!(config(false).policy([], []).is_none());
+    }
+}
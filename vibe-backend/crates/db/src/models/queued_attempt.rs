@@ -0,0 +1,249 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// An attempt waiting for a free execution slot. Its workspace exists but no
+/// process has been started yet.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct QueuedAttempt {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    #[ts(type = "ExecutorProfileId")]
+    pub executor_profile_id: Json<ExecutorProfileId>,
+    /// Task priority when queued: 0=none, 1=urgent, 2=high, 3=medium, 4=low
+    pub priority: i32,
+    /// Zero-based place in the queue
+    pub position: i32,
+    /// Why the attempt last failed to start. It is skipped until moved or removed.
+    pub launch_error: Option<String>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateQueuedAttempt {
+    pub workspace_id: Uuid,
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    pub executor_profile_id: ExecutorProfileId,
+    pub priority: i32,
+}
+
+/// An attempt with a setup script or coding agent running
+#[derive(Debug, Clone, FromRow)]
+pub struct RunningAttempt {
+    pub workspace_id: Uuid,
+    pub project_id: Uuid,
+    pub executor: Option<String>,
+}
+
+const QUEUED_ATTEMPT_COLUMNS: &str = "id, workspace_id, task_id, project_id, executor_profile_id, \
+     priority, position, launch_error, created_at";
+
+/// Higher runs first. Tasks without a priority go after low priority ones.
+pub fn priority_rank(priority: i32) -> i32 {
+    match priority {
+        1..=4 => 5 - priority,
+        _ => 0,
+    }
+}
+
+impl QueuedAttempt {
+    /// Queue an attempt after every entry of equal or higher priority
+    pub async fn enqueue(pool: &PgPool, data: &CreateQueuedAttempt) -> Result<Self, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let queued: Vec<(i32, i32)> =
+            sqlx::query_as("SELECT priority, position FROM queued_attempts ORDER BY position ASC")
+                .fetch_all(&mut *tx)
+                .await?;
+        let rank = priority_rank(data.priority);
+        let position = queued
+            .iter()
+            .find(|(priority, _)| priority_rank(*priority) < rank)
+            .map(|(_, position)| *position)
+            .unwrap_or(queued.len() as i32);
+
+        sqlx::query("UPDATE queued_attempts SET position = position + 1 WHERE position >= $1")
+            .bind(position)
+            .execute(&mut *tx)
+            .await?;
+        let query = format!(
+            r#"INSERT INTO queued_attempts
+                   (workspace_id, task_id, project_id, executor_profile_id, priority, position)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING {QUEUED_ATTEMPT_COLUMNS}"#
+        );
+        let queued_attempt = sqlx::query_as::<_, Self>(&query)
+            .bind(data.workspace_id)
+            .bind(data.task_id)
+            .bind(data.project_id)
+            .bind(Json(&data.executor_profile_id))
+            .bind(data.priority)
+            .bind(position)
+            .fetch_one(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(queued_attempt)
+    }
+
+    /// The whole queue, next to start first
+    pub async fn find_all(pool: &PgPool) -> Result<Vec<Self>, sqlx::Error> {
+        let query =
+            format!("SELECT {QUEUED_ATTEMPT_COLUMNS} FROM queued_attempts ORDER BY position ASC");
+        sqlx::query_as::<_, Self>(&query).fetch_all(pool).await
+    }
+
+    pub async fn find_by_project_id(
+        pool: &PgPool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let query = format!(
+            "SELECT {QUEUED_ATTEMPT_COLUMNS} FROM queued_attempts
+             WHERE project_id = $1
+             ORDER BY position ASC"
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(project_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn find_by_workspace_id(
+        pool: &PgPool,
+        workspace_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let query =
+            format!("SELECT {QUEUED_ATTEMPT_COLUMNS} FROM queued_attempts WHERE workspace_id = $1");
+        sqlx::query_as::<_, Self>(&query)
+            .bind(workspace_id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Tasks with at least one queued attempt
+    pub async fn find_task_ids(pool: &PgPool) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar("SELECT DISTINCT task_id FROM queued_attempts")
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Move an entry to `position`, clamped to the queue, and clear its launch
+    /// error so it is retried. Returns `false` when the workspace is not queued.
+    pub async fn move_to(
+        pool: &PgPool,
+        workspace_id: Uuid,
+        position: i32,
+    ) -> Result<bool, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let Some(current): Option<i32> =
+            sqlx::query_scalar("SELECT position FROM queued_attempts WHERE workspace_id = $1")
+                .bind(workspace_id)
+                .fetch_optional(&mut *tx)
+                .await?
+        else {
+            return Ok(false);
+        };
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM queued_attempts")
+            .fetch_one(&mut *tx)
+            .await?;
+        let target = position.clamp(0, (count as i32 - 1).max(0));
+
+        if target < current {
+            sqlx::query(
+                "UPDATE queued_attempts SET position = position + 1
+                 WHERE position >= $1 AND position < $2",
+            )
+            .bind(target)
+            .bind(current)
+            .execute(&mut *tx)
+            .await?;
+        } else if target > current {
+            sqlx::query(
+                "UPDATE queued_attempts SET position = position - 1
+                 WHERE position > $1 AND position <= $2",
+            )
+            .bind(current)
+            .bind(target)
+            .execute(&mut *tx)
+            .await?;
+        }
+        sqlx::query(
+            "UPDATE queued_attempts SET position = $2, launch_error = NULL WHERE workspace_id = $1",
+        )
+        .bind(workspace_id)
+        .bind(target)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Take an attempt off the queue. Returns `false` when it was not queued.
+    pub async fn remove(pool: &PgPool, workspace_id: Uuid) -> Result<bool, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let Some(position): Option<i32> = sqlx::query_scalar(
+            "DELETE FROM queued_attempts WHERE workspace_id = $1 RETURNING position",
+        )
+        .bind(workspace_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Ok(false);
+        };
+        sqlx::query("UPDATE queued_attempts SET position = position - 1 WHERE position > $1")
+            .bind(position)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Keep a queued attempt that failed to start, with the reason, until it
+    /// is moved or removed
+    pub async fn mark_launch_failed(
+        pool: &PgPool,
+        workspace_id: Uuid,
+        error: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE queued_attempts SET launch_error = $2 WHERE workspace_id = $1")
+            .bind(workspace_id)
+            .bind(error)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Attempts currently holding an execution slot: those with a setup script
+    /// or coding agent running
+    pub async fn find_running_attempts(pool: &PgPool) -> Result<Vec<RunningAttempt>, sqlx::Error> {
+        sqlx::query_as::<_, RunningAttempt>(
+            r#"SELECT DISTINCT ON (w.id)
+                      w.id AS workspace_id, t.project_id, s.executor
+               FROM execution_processes ep
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE ep.status = 'running'
+                 AND ep.run_reason IN ('setupscript', 'codingagent')"#,
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_priority_rank_orders_urgent_first_and_none_last() {
+        let mut priorities = vec![0, 4, 1, 3, 2];
+        priorities.sort_by_key(|priority| std::cmp::Reverse(priority_rank(*priority)));
+        assert_eq!(priorities, vec![1, 2, 3, 4, 0]);
+    }
+}
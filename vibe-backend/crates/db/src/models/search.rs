@@ -0,0 +1,397 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task::TaskStatus;
+
+pub const DEFAULT_SEARCH_LIMIT: i64 = 20;
+pub const MAX_SEARCH_LIMIT: i64 = 100;
+
+/// Kind of record a search hit points at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum SearchEntityType {
+    Task,
+    Comment,
+    Document,
+    ProjectUpdate,
+}
+
+impl SearchEntityType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Task => "task",
+            Self::Comment => "comment",
+            Self::Document => "document",
+            Self::ProjectUpdate => "project_update",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "task" => Some(Self::Task),
+            "comment" => Some(Self::Comment),
+            "document" => Some(Self::Document),
+            "project_update" => Some(Self::ProjectUpdate),
+            _ => None,
+        }
+    }
+}
+
+/// Narrows a search. Task-only filters (status, assignee, tag) leave only
+/// tasks and their comments; a project filter drops documents, which belong
+/// to teams rather than projects.
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[ts(export)]
+pub struct SearchFilters {
+    pub team_id: Option<Uuid>,
+    pub project_id: Option<Uuid>,
+    pub status: Option<TaskStatus>,
+    pub assignee_id: Option<Uuid>,
+    pub tag_id: Option<Uuid>,
+    /// Restrict to these kinds of records; all kinds when empty
+    #[serde(default)]
+    pub types: Vec<SearchEntityType>,
+}
+
+/// Query string of the `/search` endpoints
+#[derive(Debug, Clone, Deserialize, TS)]
+#[ts(export)]
+pub struct SearchQuery {
+    pub q: String,
+    /// Limit results to one workspace the caller belongs to
+    #[serde(default)]
+    pub workspace_id: Option<Uuid>,
+    #[serde(default)]
+    pub team_id: Option<Uuid>,
+    #[serde(default)]
+    pub project_id: Option<Uuid>,
+    #[serde(default)]
+    pub status: Option<TaskStatus>,
+    #[serde(default)]
+    pub assignee_id: Option<Uuid>,
+    #[serde(default)]
+    pub tag_id: Option<Uuid>,
+    /// Comma-separated entity types, e.g. `task,comment`
+    #[serde(default)]
+    pub types: Option<String>,
+    #[serde(default)]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub offset: Option<i64>,
+}
+
+impl SearchQuery {
+    pub fn filters(&self) -> Result<SearchFilters, SearchError> {
+        Ok(SearchFilters {
+            team_id: self.team_id,
+            project_id: self.project_id,
+            status: self.status.clone(),
+            assignee_id: self.assignee_id,
+            tag_id: self.tag_id,
+            types: match self.types.as_deref() {
+                Some(types) => parse_types(types)?,
+                None => Vec::new(),
+            },
+        })
+    }
+}
+
+/// Parse a comma-separated list of entity types, ignoring blanks
+pub fn parse_types(types: &str) -> Result<Vec<SearchEntityType>, SearchError> {
+    types
+        .split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| {
+            SearchEntityType::parse(value)
+                .ok_or_else(|| SearchError::UnknownType(value.to_string()))
+        })
+        .collect()
+}
+
+#[derive(Debug, Error)]
+pub enum SearchError {
+    #[error("Unknown search type: {0}")]
+    UnknownType(String),
+    #[error("Not a member of this workspace")]
+    NotAMember,
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// A ranked search hit
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SearchResult {
+    pub entity_type: SearchEntityType,
+    /// Id of the matched task, comment, document or project update
+    pub id: Uuid,
+    /// Task title for tasks and comments, document title, or project name
+    pub title: String,
+    /// Matching excerpt with terms wrapped in `<mark>` tags
+    pub snippet: String,
+    pub rank: f32,
+    /// The task a task or comment hit belongs to
+    pub task_id: Option<Uuid>,
+    pub project_id: Option<Uuid>,
+    pub team_id: Option<Uuid>,
+    /// Issue key such as `IKA-38`, when the hit is on a team task
+    pub issue_key: Option<String>,
+    pub status: Option<TaskStatus>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(FromRow)]
+struct SearchResultRow {
+    entity_type: String,
+    id: Uuid,
+    title: String,
+    snippet: String,
+    rank: f32,
+    task_id: Option<Uuid>,
+    project_id: Option<Uuid>,
+    team_id: Option<Uuid>,
+    issue_key: Option<String>,
+    status: Option<String>,
+    updated_at: DateTime<Utc>,
+}
+
+impl SearchResultRow {
+    fn into_result(self) -> Option<SearchResult> {
+        Some(SearchResult {
+            entity_type: SearchEntityType::parse(&self.entity_type)?,
+            id: self.id,
+            title: self.title,
+            snippet: self.snippet,
+            rank: self.rank,
+            task_id: self.task_id,
+            project_id: self.project_id,
+            team_id: self.team_id,
+            issue_key: self.issue_key,
+            status: self.status.and_then(|status| status.parse().ok()),
+            updated_at: self.updated_at,
+        })
+    }
+}
+
+// Hits from every searchable table are ranked together and paged before
+// highlighting, so ts_headline only runs on the rows that are returned.
+// Membership is decided by the tenant workspace of the owning project or
+// team; rows without one are never returned, since nobody can be shown to
+// be a member of them.
+const SEARCH_QUERY: &str = r#"
+WITH q AS (
+    SELECT websearch_to_tsquery('english', $1) AS query
+),
+hits AS (
+    SELECT 'task' AS entity_type, t.id, t.title,
+           coalesce(t.description, '') AS body,
+           ts_rank_cd(t.search_vector, q.query) AS rank,
+           t.id AS task_id, t.project_id, t.team_id, t.updated_at
+    FROM tasks t
+    CROSS JOIN q
+    JOIN projects p ON p.id = t.project_id
+    WHERE t.search_vector @@ q.query
+      AND ($7::text[] IS NULL OR 'task' = ANY($7))
+      AND ($2::uuid IS NULL OR t.team_id = $2)
+      AND ($3::uuid IS NULL OR t.project_id = $3)
+      AND ($4::text IS NULL OR t.status::text = $4)
+      AND ($5::uuid IS NULL OR t.assignee_id = $5)
+      AND ($6::uuid IS NULL OR EXISTS (
+          SELECT 1 FROM task_tags tt WHERE tt.task_id = t.id AND tt.tag_id = $6))
+      AND p.tenant_workspace_id = ANY($8)
+
+    UNION ALL
+
+    SELECT 'comment', c.id, t.title,
+           c.content,
+           ts_rank_cd(c.search_vector, q.query),
+           t.id, t.project_id, t.team_id, c.updated_at
+    FROM task_comments c
+    CROSS JOIN q
+    JOIN tasks t ON t.id = c.task_id
+    JOIN projects p ON p.id = t.project_id
+    WHERE c.search_vector @@ q.query
+      AND ($7::text[] IS NULL OR 'comment' = ANY($7))
+      AND ($2::uuid IS NULL OR t.team_id = $2)
+      AND ($3::uuid IS NULL OR t.project_id = $3)
+      AND ($4::text IS NULL OR t.status::text = $4)
+      AND ($5::uuid IS NULL OR t.assignee_id = $5)
+      AND ($6::uuid IS NULL OR EXISTS (
+          SELECT 1 FROM task_tags tt WHERE tt.task_id = t.id AND tt.tag_id = $6))
+      AND p.tenant_workspace_id = ANY($8)
+
+    UNION ALL
+
+    SELECT 'document', d.id, d.title,
+           coalesce(d.content, ''),
+           ts_rank_cd(d.search_vector, q.query),
+           NULL::uuid, NULL::uuid, d.team_id, d.updated_at
+    FROM documents d
+    CROSS JOIN q
+    JOIN teams tm ON tm.id = d.team_id
+    WHERE d.search_vector @@ q.query
+      AND d.is_archived = FALSE
+      AND ($7::text[] IS NULL OR 'document' = ANY($7))
+      AND $3::uuid IS NULL AND $4::text IS NULL AND $5::uuid IS NULL AND $6::uuid IS NULL
+      AND ($2::uuid IS NULL OR d.team_id = $2)
+      AND tm.tenant_workspace_id = ANY($8)
+
+    UNION ALL
+
+    SELECT 'project_update', u.id, p.name,
+           u.content,
+           ts_rank_cd(u.search_vector, q.query),
+           NULL::uuid, u.project_id, NULL::uuid, u.updated_at
+    FROM project_updates u
+    CROSS JOIN q
+    JOIN projects p ON p.id = u.project_id
+    WHERE u.search_vector @@ q.query
+      AND ($7::text[] IS NULL OR 'project_update' = ANY($7))
+      AND $4::text IS NULL AND $5::uuid IS NULL AND $6::uuid IS NULL
+      AND ($2::uuid IS NULL OR EXISTS (
+          SELECT 1 FROM team_projects tp WHERE tp.project_id = u.project_id AND tp.team_id = $2))
+      AND ($3::uuid IS NULL OR u.project_id = $3)
+      AND p.tenant_workspace_id = ANY($8)
+),
+page AS (
+    SELECT * FROM hits
+    ORDER BY rank DESC, updated_at DESC, id
+    LIMIT $9 OFFSET $10
+)
+SELECT
+    page.entity_type,
+    page.id,
+    page.title,
+    ts_headline(
+        'english',
+        CASE WHEN page.body = '' THEN page.title ELSE page.body END,
+        q.query,
+        'StartSel=<mark>, StopSel=</mark>, MaxWords=35, MinWords=15, MaxFragments=2'
+    ) AS snippet,
+    page.rank,
+    page.task_id,
+    page.project_id,
+    page.team_id,
+    tm.identifier || '-' || t.issue_number AS issue_key,
+    t.status::text AS status,
+    page.updated_at
+FROM page
+CROSS JOIN q
+LEFT JOIN tasks t ON t.id = page.task_id
+LEFT JOIN teams tm ON tm.id = t.team_id
+ORDER BY page.rank DESC, page.updated_at DESC, page.id
+"#;
+
+pub struct Search;
+
+impl Search {
+    /// Run a search request for a caller who belongs to `member_workspace_ids`.
+    /// A requested `workspace_id` must be one of them.
+    pub async fn search_as_member(
+        pool: &PgPool,
+        query: &SearchQuery,
+        member_workspace_ids: Vec<Uuid>,
+    ) -> Result<Vec<SearchResult>, SearchError> {
+        let filters = query.filters()?;
+        let workspace_ids = match query.workspace_id {
+            Some(workspace_id) if member_workspace_ids.contains(&workspace_id) => {
+                vec![workspace_id]
+            }
+            Some(_) => return Err(SearchError::NotAMember),
+            None => member_workspace_ids,
+        };
+
+        Ok(Self::search(
+            pool,
+            &query.q,
+            &filters,
+            &workspace_ids,
+            query.limit.unwrap_or(DEFAULT_SEARCH_LIMIT),
+            query.offset.unwrap_or(0),
+        )
+        .await?)
+    }
+
+    /// Ranked full-text search across tasks, task comments, documents and
+    /// project updates in `workspace_ids`. `query` accepts web-search syntax
+    /// (`"exact phrase"`, `-exclude`, `or`).
+    pub async fn search(
+        pool: &PgPool,
+        query: &str,
+        filters: &SearchFilters,
+        workspace_ids: &[Uuid],
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<SearchResult>, sqlx::Error> {
+        let query = query.trim();
+        if query.is_empty() || workspace_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let types: Option<Vec<&str>> = (!filters.types.is_empty())
+            .then(|| filters.types.iter().map(SearchEntityType::as_str).collect());
+
+        let rows = sqlx::query_as::<_, SearchResultRow>(SEARCH_QUERY)
+            .bind(query)
+            .bind(filters.team_id)
+            .bind(filters.project_id)
+            .bind(filters.status.as_ref().map(ToString::to_string))
+            .bind(filters.assignee_id)
+            .bind(filters.tag_id)
+            .bind(types)
+            .bind(workspace_ids)
+            .bind(limit.clamp(1, MAX_SEARCH_LIMIT))
+            .bind(offset.max(0))
+            .fetch_all(pool)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(SearchResultRow::into_result)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entity_type_round_trips() {
+        for entity_type in [
+            SearchEntityType::Task,
+            SearchEntityType::Comment,
+            SearchEntityType::Document,
+            SearchEntityType::ProjectUpdate,
+        ] {
+            assert_eq!(
+                SearchEntityType::parse(entity_type.as_str()),
+                Some(entity_type)
+            );
+            assert_eq!(
+                serde_json::to_value(entity_type).unwrap(),
+                serde_json::json!(entity_type.as_str())
+            );
+        }
+        assert_eq!(SearchEntityType::parse("issue"), None);
+    }
+
+    #[test]
+    fn test_parse_types_skips_blanks_and_rejects_unknown() {
+        assert_eq!(
+            parse_types(" task, ,project_update,").unwrap(),
+            vec![SearchEntityType::Task, SearchEntityType::ProjectUpdate]
+        );
+        assert!(matches!(
+            parse_types("task,issue"),
+            Err(SearchError::UnknownType(value)) if value == "issue"
+        ));
+    }
+}
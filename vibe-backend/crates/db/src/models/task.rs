@@ -1,11 +1,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{Executor, FromRow, PgPool, Postgres, Type};
+use sqlx::{Acquire, Executor, FromRow, PgPool, Postgres, Type};
 use strum_macros::{Display, EnumString};
 use ts_rs::TS;
 use uuid::Uuid;
 
-use super::{project::Project, workspace::Workspace};
+use super::{project::Project, queued_attempt::QueuedAttempt, workspace::Workspace};
 
 #[derive(
     Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS, EnumString, Display, Default,
@@ -79,6 +79,9 @@ pub struct TaskWithAttemptStatus {
     #[ts(flatten)]
     pub task: Task,
     pub has_in_progress_attempt: bool,
+    /// An attempt is waiting in the execution queue
+    #[serde(default)]
+    pub has_queued_attempt: bool,
     pub last_attempt_failed: bool,
     pub executor: Option<String>,
 }
@@ -267,6 +270,7 @@ ORDER BY t.created_at DESC"#,
         .fetch_all(pool)
         .await?;
 
+        let queued_task_ids = QueuedAttempt::find_task_ids(pool).await?;
         let tasks = records
             .into_iter()
             .map(|rec| TaskWithAttemptStatus {
@@ -287,6 +291,7 @@ ORDER BY t.created_at DESC"#,
                     updated_at: rec.updated_at,
                 },
                 has_in_progress_attempt: rec.has_in_progress_attempt != 0,
+                has_queued_attempt: queued_task_ids.contains(&rec.id),
                 last_attempt_failed: rec.last_attempt_failed != 0,
                 executor: rec.executor,
             })
@@ -355,6 +360,7 @@ ORDER BY t.issue_number ASC"#,
         .fetch_all(pool)
         .await?;
 
+        let queued_task_ids = QueuedAttempt::find_task_ids(pool).await?;
         let tasks = records
             .into_iter()
             .map(|rec| TaskWithAttemptStatus {
@@ -375,6 +381,7 @@ ORDER BY t.issue_number ASC"#,
                     updated_at: rec.updated_at,
                 },
                 has_in_progress_attempt: rec.has_in_progress_attempt != 0,
+                has_queued_attempt: queued_task_ids.contains(&rec.id),
                 last_attempt_failed: rec.last_attempt_failed != 0,
                 executor: rec.executor,
             })
@@ -395,6 +402,67 @@ ORDER BY t.issue_number ASC"#,
         .await
     }
 
+    /// Find a single task with the same attempt status flags as
+    /// `find_by_project_id_with_attempt_status`
+    pub async fn find_by_id_with_attempt_status(
+        pool: &PgPool,
+        id: Uuid,
+    ) -> Result<Option<TaskWithAttemptStatus>, sqlx::Error> {
+        let Some(task) = Self::find_by_id(pool, id).await? else {
+            return Ok(None);
+        };
+
+        let (has_in_progress_attempt, has_queued_attempt, last_attempt_failed, executor): (
+            bool,
+            bool,
+            bool,
+            Option<String>,
+        ) = sqlx::query_as(
+            r#"SELECT
+  EXISTS (
+    SELECT 1
+      FROM workspaces w
+      JOIN sessions s ON s.workspace_id = w.id
+      JOIN execution_processes ep ON ep.session_id = s.id
+     WHERE w.task_id       = $1
+       AND ep.status        = 'running'
+       AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+  ),
+
+  EXISTS (SELECT 1 FROM queued_attempts qa WHERE qa.task_id = $1),
+
+  COALESCE((
+    SELECT ep.status::text
+      FROM workspaces w
+      JOIN sessions s ON s.workspace_id = w.id
+      JOIN execution_processes ep ON ep.session_id = s.id
+     WHERE w.task_id       = $1
+     AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+     ORDER BY ep.created_at DESC
+     LIMIT 1
+  ) IN ('failed','killed'), FALSE),
+
+  ( SELECT s.executor
+      FROM workspaces w
+      JOIN sessions s ON s.workspace_id = w.id
+      WHERE w.task_id = $1
+     ORDER BY s.created_at DESC
+      LIMIT 1
+  )"#,
+        )
+        .bind(id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(Some(TaskWithAttemptStatus {
+            task,
+            has_in_progress_attempt,
+            has_queued_attempt,
+            last_attempt_failed,
+            executor,
+        }))
+    }
+
     pub async fn find_by_shared_task_id<'e, E>(
         executor: E,
         shared_task_id: Uuid,
@@ -425,11 +493,17 @@ ORDER BY t.issue_number ASC"#,
         .await
     }
 
-    pub async fn create(
-        pool: &PgPool,
+    /// Accepts a pool or a transaction, so related rows can be created with
+    /// the task atomically
+    pub async fn create<'a, A>(
+        conn: A,
         data: &CreateTask,
         task_id: Uuid,
-    ) -> Result<Self, sqlx::Error> {
+    ) -> Result<Self, sqlx::Error>
+    where
+        A: Acquire<'a, Database = Postgres>,
+    {
+        let mut conn = conn.acquire().await?;
         let status = data.status.clone().unwrap_or_default();
 
         // Auto-assign issue_number if team_id is provided
@@ -441,7 +515,7 @@ ORDER BY t.issue_number ASC"#,
                    WHERE team_id = $1"#,
                 team_id
             )
-            .fetch_one(pool)
+            .fetch_one(&mut *conn)
             .await?;
             Some(next)
         } else {
@@ -466,7 +540,7 @@ ORDER BY t.issue_number ASC"#,
             data.due_date,
             data.assignee_id
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await
     }
 
@@ -553,6 +627,23 @@ ORDER BY t.issue_number ASC"#,
         Ok(())
     }
 
+    /// Make a task a sub-issue of `parent_id`
+    pub async fn set_parent<'e, E>(
+        executor: E,
+        task_id: Uuid,
+        parent_id: Option<Uuid>,
+    ) -> Result<(), sqlx::Error>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        sqlx::query("UPDATE tasks SET parent_id = $2, updated_at = NOW() WHERE id = $1")
+            .bind(task_id)
+            .bind(parent_id)
+            .execute(executor)
+            .await?;
+        Ok(())
+    }
+
     /// Nullify parent_workspace_id for all tasks that reference the given workspace ID
     /// This breaks parent-child relationships before deleting a parent task
     pub async fn nullify_children_by_workspace_id<'e, E>(
@@ -0,0 +1,228 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task::TaskStatus;
+
+/// A "task_id is blocked by depends_on_task_id" edge
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, TS)]
+#[ts(export)]
+pub struct TaskDependency {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub depends_on_task_id: Uuid,
+    pub created_by_user_id: Option<Uuid>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Request to mark a task as blocked by another task
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct AddDependencyRequest {
+    pub depends_on_task_id: Uuid,
+}
+
+/// The task on the other end of a dependency, for display
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DependencyTask {
+    pub dependency_id: Uuid,
+    pub task_id: Uuid,
+    pub title: String,
+    pub status: TaskStatus,
+    /// Issue key such as `IKA-38`, when the task belongs to a team
+    pub issue_key: Option<String>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Both directions of a task's dependencies
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TaskDependencies {
+    /// Tasks that must be done before this one
+    pub blockers: Vec<DependencyTask>,
+    /// Tasks waiting on this one
+    pub blocked_tasks: Vec<DependencyTask>,
+}
+
+#[derive(Debug, Error)]
+pub enum TaskDependencyError {
+    #[error("A task cannot depend on itself")]
+    SelfDependency,
+    #[error("Dependency would create a cycle")]
+    Cycle,
+    #[error("Dependency already exists")]
+    AlreadyExists,
+    #[error("Task not found")]
+    TaskNotFound,
+    #[error("Dependency not found")]
+    NotFound,
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+// Status is read as text to avoid binding the enum type in runtime queries
+#[derive(FromRow)]
+struct DependencyTaskRow {
+    dependency_id: Uuid,
+    task_id: Uuid,
+    title: String,
+    status: String,
+    issue_key: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<DependencyTaskRow> for DependencyTask {
+    fn from(row: DependencyTaskRow) -> Self {
+        Self {
+            dependency_id: row.dependency_id,
+            task_id: row.task_id,
+            title: row.title,
+            status: row.status.parse().unwrap_or_default(),
+            issue_key: row.issue_key,
+            created_at: row.created_at,
+        }
+    }
+}
+
+/// Serializes dependency inserts so two concurrent edges cannot close a cycle
+const DEPENDENCY_LOCK_KEY: &str = "task_dependencies";
+
+impl TaskDependency {
+    /// Blockers and blocked tasks for a task
+    pub async fn find_by_task_id(
+        pool: &PgPool,
+        task_id: Uuid,
+    ) -> Result<TaskDependencies, sqlx::Error> {
+        Ok(TaskDependencies {
+            blockers: Self::find_related(pool, task_id, "depends_on_task_id", "task_id").await?,
+            blocked_tasks: Self::find_related(pool, task_id, "task_id", "depends_on_task_id")
+                .await?,
+        })
+    }
+
+    /// Tasks joined through `join_column` on edges whose `filter_column` is `task_id`
+    async fn find_related(
+        pool: &PgPool,
+        task_id: Uuid,
+        join_column: &str,
+        filter_column: &str,
+    ) -> Result<Vec<DependencyTask>, sqlx::Error> {
+        let query = format!(
+            r#"SELECT
+                d.id AS dependency_id,
+                t.id AS task_id,
+                t.title,
+                t.status::text AS status,
+                tm.identifier || '-' || t.issue_number AS issue_key,
+                d.created_at
+            FROM task_dependencies d
+            JOIN tasks t ON t.id = d.{join_column}
+            LEFT JOIN teams tm ON tm.id = t.team_id
+            WHERE d.{filter_column} = $1
+            ORDER BY d.created_at ASC"#
+        );
+        let rows = sqlx::query_as::<_, DependencyTaskRow>(&query)
+            .bind(task_id)
+            .fetch_all(pool)
+            .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Blockers of `task_id` that are not yet done
+    pub async fn find_unfinished_blockers(
+        pool: &PgPool,
+        task_id: Uuid,
+    ) -> Result<Vec<DependencyTask>, sqlx::Error> {
+        let blockers = Self::find_related(pool, task_id, "depends_on_task_id", "task_id").await?;
+        Ok(blockers
+            .into_iter()
+            .filter(|blocker| blocker.status != TaskStatus::Done)
+            .collect())
+    }
+
+    /// Record that `task_id` is blocked by `depends_on_task_id`, rejecting
+    /// self-references, duplicates and edges that would close a cycle
+    pub async fn create(
+        pool: &PgPool,
+        task_id: Uuid,
+        depends_on_task_id: Uuid,
+        created_by_user_id: Option<Uuid>,
+    ) -> Result<Self, TaskDependencyError> {
+        if task_id == depends_on_task_id {
+            return Err(TaskDependencyError::SelfDependency);
+        }
+
+        let mut tx = pool.begin().await?;
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
+            .bind(DEPENDENCY_LOCK_KEY)
+            .execute(&mut *tx)
+            .await?;
+
+        let found: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM tasks WHERE id IN ($1, $2)")
+            .bind(task_id)
+            .bind(depends_on_task_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        if found < 2 {
+            return Err(TaskDependencyError::TaskNotFound);
+        }
+
+        // The new edge closes a cycle if task_id is already reachable by
+        // following blockers from depends_on_task_id
+        let creates_cycle: bool = sqlx::query_scalar(
+            r#"WITH RECURSIVE upstream(id) AS (
+                SELECT $1::uuid
+                UNION
+                SELECT d.depends_on_task_id
+                FROM task_dependencies d
+                JOIN upstream u ON d.task_id = u.id
+            )
+            SELECT EXISTS (SELECT 1 FROM upstream WHERE id = $2)"#,
+        )
+        .bind(depends_on_task_id)
+        .bind(task_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        if creates_cycle {
+            return Err(TaskDependencyError::Cycle);
+        }
+
+        let dependency = sqlx::query_as::<_, Self>(
+            r#"INSERT INTO task_dependencies (task_id, depends_on_task_id, created_by_user_id)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (task_id, depends_on_task_id) DO NOTHING
+            RETURNING id, task_id, depends_on_task_id, created_by_user_id, created_at"#,
+        )
+        .bind(task_id)
+        .bind(depends_on_task_id)
+        .bind(created_by_user_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(TaskDependencyError::AlreadyExists)?;
+
+        tx.commit().await?;
+        Ok(dependency)
+    }
+
+    /// Remove the edge `task_id` -> `depends_on_task_id`, returning rows affected
+    pub async fn delete(
+        pool: &PgPool,
+        task_id: Uuid,
+        depends_on_task_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            "DELETE FROM task_dependencies WHERE task_id = $1 AND depends_on_task_id = $2",
+        )
+        .bind(task_id)
+        .bind(depends_on_task_id)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
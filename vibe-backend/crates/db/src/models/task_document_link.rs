@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, PgPool};
+use sqlx::{Acquire, FromRow, PgPool, Postgres};
 use ts_rs::TS;
 use uuid::Uuid;
 
@@ -104,11 +104,15 @@ impl TaskDocumentLink {
     }
 
     /// Link multiple documents to a task
-    pub async fn link_documents(
-        pool: &PgPool,
+    pub async fn link_documents<'a, A>(
+        conn: A,
         task_id: Uuid,
         document_ids: &[Uuid],
-    ) -> Result<Vec<TaskDocumentLink>, sqlx::Error> {
+    ) -> Result<Vec<TaskDocumentLink>, sqlx::Error>
+    where
+        A: Acquire<'a, Database = Postgres>,
+    {
+        let mut conn = conn.acquire().await?;
         let mut links = Vec::new();
 
         for doc_id in document_ids {
@@ -119,7 +123,7 @@ impl TaskDocumentLink {
                 task_id,
                 doc_id
             )
-            .fetch_optional(pool)
+            .fetch_optional(&mut *conn)
             .await?;
 
             if existing.is_some() {
@@ -139,7 +143,7 @@ impl TaskDocumentLink {
                 task_id,
                 doc_id
             )
-            .fetch_one(pool)
+            .fetch_one(&mut *conn)
             .await?;
 
             links.push(row.into());
@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, PgPool};
+use sqlx::{Executor, FromRow, PgPool, Postgres};
 use ts_rs::TS;
 use uuid::Uuid;
 
@@ -107,11 +107,14 @@ impl TaskTag {
     }
 
     /// Add a tag to a task (race-condition safe using ON CONFLICT)
-    pub async fn add_tag(
-        pool: &PgPool,
+    pub async fn add_tag<'e, E>(
+        executor: E,
         task_id: Uuid,
         tag_id: Uuid,
-    ) -> Result<TaskTag, sqlx::Error> {
+    ) -> Result<TaskTag, sqlx::Error>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
         let id = Uuid::new_v4();
         // Use ON CONFLICT to handle race conditions - if link exists, return existing row
         let row = sqlx::query_as!(
@@ -127,7 +130,7 @@ impl TaskTag {
             task_id,
             tag_id
         )
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(row.into())
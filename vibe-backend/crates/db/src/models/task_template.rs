@@ -0,0 +1,388 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::workspace_repo::CreateWorkspaceRepo;
+
+/// A reusable task definition owned by a team. `title_template` and
+/// `description_template` may contain `{{variable}}` placeholders.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskTemplate {
+    pub id: Uuid,
+    pub team_id: Uuid,
+    /// Project tasks created from this template belong to
+    pub project_id: Uuid,
+    pub name: String,
+    pub title_template: String,
+    pub description_template: Option<String>,
+    pub priority: Option<i32>, // 0=none, 1=urgent, 2=high, 3=medium, 4=low
+    pub assignee_id: Option<Uuid>,
+    pub tag_ids: Vec<Uuid>,
+    pub document_ids: Vec<Uuid>,
+    #[ts(type = "Array<TemplateSubIssue>")]
+    pub sub_issues: Json<Vec<TemplateSubIssue>>,
+    /// Default values for template variables
+    #[ts(type = "Record<string, string>")]
+    pub variables: Json<HashMap<String, String>>,
+    pub created_by: Option<String>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A sub-issue created under every task instantiated from a template
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TemplateSubIssue {
+    pub title: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateTaskTemplate {
+    pub project_id: Uuid,
+    pub name: String,
+    pub title_template: String,
+    pub description_template: Option<String>,
+    pub priority: Option<i32>,
+    pub assignee_id: Option<Uuid>,
+    #[serde(default)]
+    pub tag_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub document_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub sub_issues: Vec<TemplateSubIssue>,
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateTaskTemplate {
+    pub project_id: Option<Uuid>,
+    pub name: Option<String>,
+    pub title_template: Option<String>,
+    pub description_template: Option<String>,
+    pub priority: Option<i32>,
+    pub assignee_id: Option<Uuid>,
+    pub tag_ids: Option<Vec<Uuid>>,
+    pub document_ids: Option<Vec<Uuid>>,
+    pub sub_issues: Option<Vec<TemplateSubIssue>>,
+    pub variables: Option<HashMap<String, String>>,
+}
+
+/// Creates tasks from a template on an RRULE schedule
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskTemplateRecurrence {
+    pub id: Uuid,
+    pub template_id: Uuid,
+    /// RRULE subset, e.g. `FREQ=WEEKLY;BYDAY=MO;BYHOUR=9`
+    pub rrule: String,
+    /// Anchor for `INTERVAL` and the default time of day
+    #[ts(type = "Date")]
+    pub starts_at: DateTime<Utc>,
+    /// Variable values overriding the template defaults
+    #[ts(type = "Record<string, string>")]
+    pub variables: Json<HashMap<String, String>>,
+    /// When set, an attempt is started with this profile for each created task
+    #[ts(type = "ExecutorProfileId | null")]
+    pub executor_profile_id: Option<Json<ExecutorProfileId>>,
+    #[ts(type = "Array<CreateWorkspaceRepo>")]
+    pub repos: Json<Vec<CreateWorkspaceRepo>>,
+    pub enabled: bool,
+    /// `None` once the rule has no further occurrences
+    #[ts(type = "Date | null")]
+    pub next_run_at: Option<DateTime<Utc>>,
+    #[ts(type = "Date | null")]
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub last_task_id: Option<Uuid>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateTaskTemplateRecurrence {
+    pub rrule: String,
+    /// Defaults to now
+    #[ts(type = "Date | null")]
+    pub starts_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+    pub executor_profile_id: Option<ExecutorProfileId>,
+    #[serde(default)]
+    pub repos: Vec<CreateWorkspaceRepo>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateTaskTemplateRecurrence {
+    pub rrule: Option<String>,
+    #[ts(type = "Date | null")]
+    pub starts_at: Option<DateTime<Utc>>,
+    pub variables: Option<HashMap<String, String>>,
+    pub executor_profile_id: Option<ExecutorProfileId>,
+    /// Stop auto-starting attempts; takes precedence over `executor_profile_id`
+    #[serde(default)]
+    pub clear_executor_profile: bool,
+    pub repos: Option<Vec<CreateWorkspaceRepo>>,
+    pub enabled: Option<bool>,
+}
+
+const TEMPLATE_COLUMNS: &str = "id, team_id, project_id, name, title_template, \
+     description_template, priority, assignee_id, tag_ids, document_ids, sub_issues, \
+     variables, created_by, created_at, updated_at";
+
+const RECURRENCE_COLUMNS: &str = "id, template_id, rrule, starts_at, variables, \
+     executor_profile_id, repos, enabled, next_run_at, last_run_at, last_task_id, \
+     created_at, updated_at";
+
+impl TaskTemplate {
+    pub async fn find_by_team(pool: &PgPool, team_id: Uuid) -> Result<Vec<Self>, sqlx::Error> {
+        let query = format!(
+            "SELECT {TEMPLATE_COLUMNS} FROM task_templates
+             WHERE team_id = $1
+             ORDER BY name ASC"
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(team_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn find_by_id(pool: &PgPool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        let query = format!("SELECT {TEMPLATE_COLUMNS} FROM task_templates WHERE id = $1");
+        sqlx::query_as::<_, Self>(&query)
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    pub async fn create(
+        pool: &PgPool,
+        team_id: Uuid,
+        data: &CreateTaskTemplate,
+        created_by: Option<&str>,
+    ) -> Result<Self, sqlx::Error> {
+        let query = format!(
+            r#"INSERT INTO task_templates
+                   (team_id, project_id, name, title_template, description_template, priority,
+                    assignee_id, tag_ids, document_ids, sub_issues, variables, created_by)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+               RETURNING {TEMPLATE_COLUMNS}"#
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(team_id)
+            .bind(data.project_id)
+            .bind(&data.name)
+            .bind(&data.title_template)
+            .bind(&data.description_template)
+            .bind(data.priority)
+            .bind(data.assignee_id)
+            .bind(&data.tag_ids)
+            .bind(&data.document_ids)
+            .bind(Json(&data.sub_issues))
+            .bind(Json(&data.variables))
+            .bind(created_by)
+            .fetch_one(pool)
+            .await
+    }
+
+    /// Omitted fields keep their current value
+    pub async fn update(
+        pool: &PgPool,
+        existing: &Self,
+        data: &UpdateTaskTemplate,
+    ) -> Result<Self, sqlx::Error> {
+        let query = format!(
+            r#"UPDATE task_templates
+               SET project_id = $2, name = $3, title_template = $4, description_template = $5,
+                   priority = $6, assignee_id = $7, tag_ids = $8, document_ids = $9,
+                   sub_issues = $10, variables = $11, updated_at = NOW()
+               WHERE id = $1
+               RETURNING {TEMPLATE_COLUMNS}"#
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(existing.id)
+            .bind(data.project_id.unwrap_or(existing.project_id))
+            .bind(data.name.as_ref().unwrap_or(&existing.name))
+            .bind(
+                data.title_template
+                    .as_ref()
+                    .unwrap_or(&existing.title_template),
+            )
+            .bind(
+                data.description_template
+                    .as_ref()
+                    .or(existing.description_template.as_ref()),
+            )
+            .bind(data.priority.or(existing.priority))
+            .bind(data.assignee_id.or(existing.assignee_id))
+            .bind(data.tag_ids.as_ref().unwrap_or(&existing.tag_ids))
+            .bind(data.document_ids.as_ref().unwrap_or(&existing.document_ids))
+            .bind(Json(
+                data.sub_issues.as_ref().unwrap_or(&existing.sub_issues.0),
+            ))
+            .bind(Json(
+                data.variables.as_ref().unwrap_or(&existing.variables.0),
+            ))
+            .fetch_one(pool)
+            .await
+    }
+
+    pub async fn delete(pool: &PgPool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM task_templates WHERE id = $1")
+            .bind(id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl TaskTemplateRecurrence {
+    pub async fn find_by_template(
+        pool: &PgPool,
+        template_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let query = format!(
+            "SELECT {RECURRENCE_COLUMNS} FROM task_template_recurrences
+             WHERE template_id = $1
+             ORDER BY created_at ASC"
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(template_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn find_by_id(pool: &PgPool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        let query =
+            format!("SELECT {RECURRENCE_COLUMNS} FROM task_template_recurrences WHERE id = $1");
+        sqlx::query_as::<_, Self>(&query)
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Enabled recurrences whose next occurrence is at or before `now`
+    pub async fn find_due(pool: &PgPool, now: DateTime<Utc>) -> Result<Vec<Self>, sqlx::Error> {
+        let query = format!(
+            "SELECT {RECURRENCE_COLUMNS} FROM task_template_recurrences
+             WHERE enabled = TRUE AND next_run_at <= $1
+             ORDER BY next_run_at ASC"
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(now)
+            .fetch_all(pool)
+            .await
+    }
+
+    /// `next_run_at` is computed by the caller from the parsed rule
+    pub async fn create(
+        pool: &PgPool,
+        template_id: Uuid,
+        data: &CreateTaskTemplateRecurrence,
+        starts_at: DateTime<Utc>,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, sqlx::Error> {
+        let query = format!(
+            r#"INSERT INTO task_template_recurrences
+                   (template_id, rrule, starts_at, variables, executor_profile_id, repos,
+                    enabled, next_run_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+               RETURNING {RECURRENCE_COLUMNS}"#
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(template_id)
+            .bind(&data.rrule)
+            .bind(starts_at)
+            .bind(Json(&data.variables))
+            .bind(data.executor_profile_id.as_ref().map(Json))
+            .bind(Json(&data.repos))
+            .bind(data.enabled.unwrap_or(true))
+            .bind(next_run_at)
+            .fetch_one(pool)
+            .await
+    }
+
+    /// Omitted fields keep their current value. `next_run_at` is recomputed
+    /// by the caller since the rule or anchor may have changed.
+    pub async fn update(
+        pool: &PgPool,
+        existing: &Self,
+        data: &UpdateTaskTemplateRecurrence,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, sqlx::Error> {
+        let executor_profile_id = if data.clear_executor_profile {
+            None
+        } else {
+            data.executor_profile_id
+                .as_ref()
+                .or(existing.executor_profile_id.as_deref())
+        };
+        let query = format!(
+            r#"UPDATE task_template_recurrences
+               SET rrule = $2, starts_at = $3, variables = $4, executor_profile_id = $5,
+                   repos = $6, enabled = $7, next_run_at = $8, updated_at = NOW()
+               WHERE id = $1
+               RETURNING {RECURRENCE_COLUMNS}"#
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(existing.id)
+            .bind(data.rrule.as_ref().unwrap_or(&existing.rrule))
+            .bind(data.starts_at.unwrap_or(existing.starts_at))
+            .bind(Json(
+                data.variables.as_ref().unwrap_or(&existing.variables.0),
+            ))
+            .bind(executor_profile_id.map(Json))
+            .bind(Json(data.repos.as_ref().unwrap_or(&existing.repos.0)))
+            .bind(data.enabled.unwrap_or(existing.enabled))
+            .bind(next_run_at)
+            .fetch_one(pool)
+            .await
+    }
+
+    /// Record a run and move the schedule to `next_run_at`. Only succeeds
+    /// while the row still has `expected_next_run_at`, so two schedulers
+    /// racing on the same occurrence create one task between them.
+    pub async fn claim_run(
+        pool: &PgPool,
+        id: Uuid,
+        expected_next_run_at: DateTime<Utc>,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"UPDATE task_template_recurrences
+               SET next_run_at = $3, last_run_at = NOW(), updated_at = NOW()
+               WHERE id = $1 AND next_run_at = $2"#,
+        )
+        .bind(id)
+        .bind(expected_next_run_at)
+        .bind(next_run_at)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    pub async fn set_last_task(pool: &PgPool, id: Uuid, task_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE task_template_recurrences SET last_task_id = $2 WHERE id = $1")
+            .bind(id)
+            .bind(task_id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &PgPool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM task_template_recurrences WHERE id = $1")
+            .bind(id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,148 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+/// A team's connection to a cloud storage provider.
+///
+/// Tokens and secrets are stored encrypted; callers decrypt them with
+/// `services::cloud_storage::encryption`. Not serializable so credentials
+/// never end up in an API response.
+#[derive(Debug, Clone, FromRow)]
+pub struct TeamStorageConfig {
+    pub id: Uuid,
+    pub team_id: Uuid,
+    /// `google_drive`, `s3` or `dropbox`
+    pub provider: String,
+    pub access_token: Option<String>,
+    pub refresh_token: Option<String>,
+    pub token_expires_at: Option<DateTime<Utc>>,
+    /// Provider folder (Drive folder ID, Dropbox path) documents sync under
+    pub folder_id: Option<String>,
+    /// Provider-specific settings, e.g. the S3 bucket and region
+    pub config_data: serde_json::Value,
+    pub is_active: bool,
+    pub connected_email: Option<String>,
+    pub connected_account_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UpsertTeamStorageConfig {
+    pub provider: String,
+    pub access_token: Option<String>,
+    pub refresh_token: Option<String>,
+    pub token_expires_at: Option<DateTime<Utc>>,
+    pub folder_id: Option<String>,
+    pub config_data: serde_json::Value,
+    pub connected_email: Option<String>,
+    pub connected_account_id: Option<String>,
+}
+
+const CONFIG_COLUMNS: &str = "id, team_id, provider, access_token, refresh_token, \
+     token_expires_at, folder_id, config_data, is_active, connected_email, \
+     connected_account_id, created_at, updated_at";
+
+impl TeamStorageConfig {
+    /// The team's active connection, most recently updated first when a team
+    /// has connected several providers
+    pub async fn find_active_by_team(
+        pool: &PgPool,
+        team_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let query = format!(
+            "SELECT {CONFIG_COLUMNS} FROM team_storage_configs
+             WHERE team_id = $1 AND is_active = TRUE
+             ORDER BY updated_at DESC
+             LIMIT 1"
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(team_id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    pub async fn find_by_team_and_provider(
+        pool: &PgPool,
+        team_id: Uuid,
+        provider: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let query = format!(
+            "SELECT {CONFIG_COLUMNS} FROM team_storage_configs
+             WHERE team_id = $1 AND provider = $2"
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(team_id)
+            .bind(provider)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Create or replace the team's connection to `data.provider`, marking it
+    /// active
+    pub async fn upsert(
+        pool: &PgPool,
+        team_id: Uuid,
+        data: &UpsertTeamStorageConfig,
+    ) -> Result<Self, sqlx::Error> {
+        let query = format!(
+            r#"INSERT INTO team_storage_configs
+                   (team_id, provider, access_token, refresh_token, token_expires_at,
+                    folder_id, config_data, connected_email, connected_account_id)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+               ON CONFLICT (team_id, provider) DO UPDATE SET
+                   access_token = EXCLUDED.access_token,
+                   refresh_token = EXCLUDED.refresh_token,
+                   token_expires_at = EXCLUDED.token_expires_at,
+                   folder_id = EXCLUDED.folder_id,
+                   config_data = EXCLUDED.config_data,
+                   connected_email = EXCLUDED.connected_email,
+                   connected_account_id = EXCLUDED.connected_account_id,
+                   is_active = TRUE,
+                   updated_at = NOW()
+               RETURNING {CONFIG_COLUMNS}"#
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(team_id)
+            .bind(&data.provider)
+            .bind(&data.access_token)
+            .bind(&data.refresh_token)
+            .bind(data.token_expires_at)
+            .bind(&data.folder_id)
+            .bind(&data.config_data)
+            .bind(&data.connected_email)
+            .bind(&data.connected_account_id)
+            .fetch_one(pool)
+            .await
+    }
+
+    /// Store a refreshed (encrypted) OAuth access token
+    pub async fn update_access_token(
+        pool: &PgPool,
+        id: Uuid,
+        access_token: &str,
+        token_expires_at: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"UPDATE team_storage_configs
+               SET access_token = $2, token_expires_at = $3, updated_at = NOW()
+               WHERE id = $1"#,
+        )
+        .bind(id)
+        .bind(access_token)
+        .bind(token_expires_at)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &PgPool, team_id: Uuid, provider: &str) -> Result<u64, sqlx::Error> {
+        let result =
+            sqlx::query("DELETE FROM team_storage_configs WHERE team_id = $1 AND provider = $2")
+                .bind(team_id)
+                .bind(provider)
+                .execute(pool)
+                .await?;
+        Ok(result.rows_affected())
+    }
+}
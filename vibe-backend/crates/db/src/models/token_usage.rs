@@ -0,0 +1,301 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Tokens used by one execution process with one model, summed over every
+/// usage event the executor reported
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ExecutionProcessUsage {
+    pub id: Uuid,
+    pub execution_process_id: Uuid,
+    pub coding_agent_turn_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub project_id: Option<Uuid>,
+    pub executor: String,
+    /// Empty when the agent did not report a model
+    pub model: String,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cache_write_tokens: i64,
+    pub cost_microdollars: i64,
+    /// False when the model has no entry in the price table
+    pub priced: bool,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// One usage increment to add to an execution process
+#[derive(Debug, Clone)]
+pub struct RecordUsage {
+    pub execution_process_id: Uuid,
+    pub coding_agent_turn_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub project_id: Option<Uuid>,
+    pub executor: String,
+    pub model: String,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cache_write_tokens: i64,
+    pub cost_microdollars: i64,
+    pub priced: bool,
+}
+
+/// Usage of a single model inside a summary
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ModelUsage {
+    pub model: String,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cache_write_tokens: i64,
+    pub cost_microdollars: i64,
+    pub priced: bool,
+}
+
+/// Usage totals for a task, project, team or execution process
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct UsageSummary {
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cache_write_tokens: i64,
+    pub cost_microdollars: i64,
+    /// True when some usage could not be priced, so the cost is a lower bound
+    pub has_unpriced: bool,
+    pub by_model: Vec<ModelUsage>,
+}
+
+impl UsageSummary {
+    fn from_models(by_model: Vec<ModelUsage>) -> Self {
+        let mut summary = Self::default();
+        for usage in &by_model {
+            summary.input_tokens += usage.input_tokens;
+            summary.output_tokens += usage.output_tokens;
+            summary.cache_read_tokens += usage.cache_read_tokens;
+            summary.cache_write_tokens += usage.cache_write_tokens;
+            summary.cost_microdollars += usage.cost_microdollars;
+            summary.has_unpriced |= !usage.priced;
+        }
+        summary.by_model = by_model;
+        summary
+    }
+}
+
+const USAGE_COLUMNS: &str = r#"
+    id, execution_process_id, coding_agent_turn_id, task_id, project_id, executor,
+    model, input_tokens, output_tokens, cache_read_tokens, cache_write_tokens,
+    cost_microdollars, priced, created_at, updated_at
+"#;
+
+// Per-model totals; the filter is spliced in by `summarize`
+const SUMMARY_SELECT: &str = r#"
+    SELECT u.model,
+           SUM(u.input_tokens)::BIGINT AS input_tokens,
+           SUM(u.output_tokens)::BIGINT AS output_tokens,
+           SUM(u.cache_read_tokens)::BIGINT AS cache_read_tokens,
+           SUM(u.cache_write_tokens)::BIGINT AS cache_write_tokens,
+           SUM(u.cost_microdollars)::BIGINT AS cost_microdollars,
+           BOOL_AND(u.priced) AS priced
+    FROM execution_process_usage u
+"#;
+
+impl ExecutionProcessUsage {
+    /// Add a usage increment to the running totals of its execution process
+    /// and model
+    pub async fn record(pool: &PgPool, data: &RecordUsage) -> Result<Self, sqlx::Error> {
+        let query = format!(
+            r#"INSERT INTO execution_process_usage (
+                   execution_process_id, coding_agent_turn_id, task_id, project_id,
+                   executor, model, input_tokens, output_tokens, cache_read_tokens,
+                   cache_write_tokens, cost_microdollars, priced
+               )
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+               ON CONFLICT (execution_process_id, model) DO UPDATE SET
+                   coding_agent_turn_id = COALESCE(execution_process_usage.coding_agent_turn_id,
+                                                   EXCLUDED.coding_agent_turn_id),
+                   input_tokens = execution_process_usage.input_tokens + EXCLUDED.input_tokens,
+                   output_tokens = execution_process_usage.output_tokens + EXCLUDED.output_tokens,
+                   cache_read_tokens = execution_process_usage.cache_read_tokens
+                                       + EXCLUDED.cache_read_tokens,
+                   cache_write_tokens = execution_process_usage.cache_write_tokens
+                                        + EXCLUDED.cache_write_tokens,
+                   cost_microdollars = execution_process_usage.cost_microdollars
+                                       + EXCLUDED.cost_microdollars,
+                   priced = execution_process_usage.priced AND EXCLUDED.priced,
+                   updated_at = NOW()
+               RETURNING {USAGE_COLUMNS}"#
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(data.execution_process_id)
+            .bind(data.coding_agent_turn_id)
+            .bind(data.task_id)
+            .bind(data.project_id)
+            .bind(&data.executor)
+            .bind(&data.model)
+            .bind(data.input_tokens)
+            .bind(data.output_tokens)
+            .bind(data.cache_read_tokens)
+            .bind(data.cache_write_tokens)
+            .bind(data.cost_microdollars)
+            .bind(data.priced)
+            .fetch_one(pool)
+            .await
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &PgPool,
+        execution_process_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let query = format!(
+            "SELECT {USAGE_COLUMNS} FROM execution_process_usage
+             WHERE execution_process_id = $1
+             ORDER BY model"
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(execution_process_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn summary_for_execution_process(
+        pool: &PgPool,
+        execution_process_id: Uuid,
+    ) -> Result<UsageSummary, sqlx::Error> {
+        Self::summarize(
+            pool,
+            "WHERE u.execution_process_id = $1",
+            execution_process_id,
+            None,
+        )
+        .await
+    }
+
+    pub async fn summary_for_task(
+        pool: &PgPool,
+        task_id: Uuid,
+    ) -> Result<UsageSummary, sqlx::Error> {
+        Self::summarize(pool, "WHERE u.task_id = $1", task_id, None).await
+    }
+
+    /// Project usage, optionally only usage recorded at or after `since`
+    pub async fn summary_for_project(
+        pool: &PgPool,
+        project_id: Uuid,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<UsageSummary, sqlx::Error> {
+        Self::summarize(pool, "WHERE u.project_id = $1", project_id, since).await
+    }
+
+    /// Usage of every task owned by the team
+    pub async fn summary_for_team(
+        pool: &PgPool,
+        team_id: Uuid,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<UsageSummary, sqlx::Error> {
+        Self::summarize(
+            pool,
+            "JOIN tasks t ON t.id = u.task_id WHERE t.team_id = $1",
+            team_id,
+            since,
+        )
+        .await
+    }
+
+    /// Usage of every execution process run in the workspace's sessions
+    pub async fn summary_for_workspace(
+        pool: &PgPool,
+        workspace_id: Uuid,
+    ) -> Result<UsageSummary, sqlx::Error> {
+        Self::summarize(
+            pool,
+            "JOIN execution_processes ep ON ep.id = u.execution_process_id
+             JOIN sessions s ON s.id = ep.session_id
+             WHERE s.workspace_id = $1",
+            workspace_id,
+            None,
+        )
+        .await
+    }
+
+    /// Total spend of a project in micro-dollars, optionally since a point in
+    /// time
+    pub async fn project_cost_since(
+        pool: &PgPool,
+        project_id: Uuid,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar::<_, i64>(
+            r#"SELECT COALESCE(SUM(cost_microdollars), 0)::BIGINT
+               FROM execution_process_usage
+               WHERE project_id = $1
+                 AND ($2::timestamptz IS NULL OR created_at >= $2)"#,
+        )
+        .bind(project_id)
+        .bind(since)
+        .fetch_one(pool)
+        .await
+    }
+
+    async fn summarize(
+        pool: &PgPool,
+        filter: &str,
+        id: Uuid,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<UsageSummary, sqlx::Error> {
+        let query = format!(
+            "{SUMMARY_SELECT} {filter}
+               AND ($2::timestamptz IS NULL OR u.created_at >= $2)
+             GROUP BY u.model
+             ORDER BY cost_microdollars DESC, u.model"
+        );
+        let by_model = sqlx::query_as::<_, ModelUsage>(&query)
+            .bind(id)
+            .bind(since)
+            .fetch_all(pool)
+            .await?;
+        Ok(UsageSummary::from_models(by_model))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model_usage(model: &str, cost_microdollars: i64, priced: bool) -> ModelUsage {
+        ModelUsage {
+            model: model.to_string(),
+            input_tokens: 1_000,
+            output_tokens: 200,
+            cache_read_tokens: 50,
+            cache_write_tokens: 0,
+            cost_microdollars,
+            priced,
+        }
+    }
+
+    #[test]
+    fn test_summary_totals_models() {
+        let summary = UsageSummary::from_models(vec![
+            model_usage("claude-sonnet-4", 6_000, true),
+            model_usage("gpt-5", 2_500, true),
+        ]);
+        assert_eq!(summary.input_tokens, 2_000);
+        assert_eq!(summary.output_tokens, 400);
+        assert_eq!(summary.cache_read_tokens, 100);
+        assert_eq!(summary.cost_microdollars, 8_500);
+        assert!(!summary.has_unpriced);
+        assert_eq!(summary.by_model.len(), 2);
+
+        let summary = UsageSummary::from_models(vec![model_usage("", 0, false)]);
+        assert!(summary.has_unpriced);
+    }
+}
@@ -0,0 +1,287 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use ts_rs::TS;
+use utils::approvals::{ApprovalRequest, ApprovalStatus};
+use uuid::Uuid;
+
+/// Who settled an approval request
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum ApprovalDecider {
+    User,
+    Policy,
+    Timeout,
+}
+
+impl ApprovalDecider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Policy => "policy",
+            Self::Timeout => "timeout",
+        }
+    }
+}
+
+/// Decision recorded for an approval request
+#[derive(Debug, Clone)]
+pub struct ApprovalDecision {
+    pub status: ApprovalStatus,
+    pub decided_by: ApprovalDecider,
+    pub decided_by_user_id: Option<String>,
+    pub rule_id: Option<Uuid>,
+}
+
+impl ApprovalDecision {
+    pub fn by_user(status: ApprovalStatus, user_id: Option<String>) -> Self {
+        Self {
+            status,
+            decided_by: ApprovalDecider::User,
+            decided_by_user_id: user_id,
+            rule_id: None,
+        }
+    }
+
+    pub fn by_policy(status: ApprovalStatus, rule_id: Uuid) -> Self {
+        Self {
+            status,
+            decided_by: ApprovalDecider::Policy,
+            decided_by_user_id: None,
+            rule_id: Some(rule_id),
+        }
+    }
+
+    pub fn timed_out() -> Self {
+        Self {
+            status: ApprovalStatus::TimedOut,
+            decided_by: ApprovalDecider::Timeout,
+            decided_by_user_id: None,
+            rule_id: None,
+        }
+    }
+
+    fn status_str(&self) -> &'static str {
+        status_str(&self.status)
+    }
+
+    fn reason(&self) -> Option<&str> {
+        match &self.status {
+            ApprovalStatus::Denied { reason } => reason.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+fn status_str(status: &ApprovalStatus) -> &'static str {
+    match status {
+        ApprovalStatus::Pending => "pending",
+        ApprovalStatus::Approved => "approved",
+        ApprovalStatus::Denied { .. } => "denied",
+        ApprovalStatus::TimedOut => "timed_out",
+    }
+}
+
+/// A tool approval request and how it was decided
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, TS)]
+#[ts(export)]
+pub struct ToolApproval {
+    pub id: String,
+    pub execution_process_id: Uuid,
+    pub tool_name: String,
+    pub tool_call_id: String,
+    #[ts(type = "unknown")]
+    pub tool_input: serde_json::Value,
+    /// Normalized action of the tool call, when it could be determined
+    #[ts(type = "unknown")]
+    pub action: Option<serde_json::Value>,
+    /// `pending`, `approved`, `denied` or `timed_out`
+    pub status: String,
+    /// `user`, `policy` or `timeout`
+    pub decided_by: Option<String>,
+    pub decided_by_user_id: Option<String>,
+    pub rule_id: Option<Uuid>,
+    pub reason: Option<String>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub timeout_at: DateTime<Utc>,
+    #[ts(type = "Date | null")]
+    pub decided_at: Option<DateTime<Utc>>,
+}
+
+const APPROVAL_COLUMNS: &str = r#"
+    id, execution_process_id, tool_name, tool_call_id, tool_input, action,
+    status, decided_by, decided_by_user_id, rule_id, reason,
+    created_at, timeout_at, decided_at
+"#;
+
+impl ToolApproval {
+    /// The recorded status, in the shape executors expect
+    pub fn approval_status(&self) -> ApprovalStatus {
+        match self.status.as_str() {
+            "approved" => ApprovalStatus::Approved,
+            "denied" => ApprovalStatus::Denied {
+                reason: self.reason.clone(),
+            },
+            "timed_out" => ApprovalStatus::TimedOut,
+            _ => ApprovalStatus::Pending,
+        }
+    }
+
+    /// Rebuild the in-memory request for a stored approval
+    pub fn to_request(&self) -> ApprovalRequest {
+        ApprovalRequest {
+            id: self.id.clone(),
+            tool_name: self.tool_name.clone(),
+            tool_input: self.tool_input.clone(),
+            tool_call_id: self.tool_call_id.clone(),
+            execution_process_id: self.execution_process_id,
+            created_at: self.created_at,
+            timeout_at: self.timeout_at,
+        }
+    }
+
+    /// Store a request that is waiting for a user decision
+    pub async fn create_pending(
+        pool: &PgPool,
+        request: &ApprovalRequest,
+        action: Option<&serde_json::Value>,
+    ) -> Result<Self, sqlx::Error> {
+        let query = format!(
+            r#"
+            INSERT INTO tool_approvals
+                (id, execution_process_id, tool_name, tool_call_id, tool_input, action,
+                 created_at, timeout_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING {APPROVAL_COLUMNS}
+            "#
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(&request.id)
+            .bind(request.execution_process_id)
+            .bind(&request.tool_name)
+            .bind(&request.tool_call_id)
+            .bind(&request.tool_input)
+            .bind(action)
+            .bind(request.created_at)
+            .bind(request.timeout_at)
+            .fetch_one(pool)
+            .await
+    }
+
+    /// Store a request that was settled immediately, e.g. by a policy rule
+    pub async fn create_decided(
+        pool: &PgPool,
+        request: &ApprovalRequest,
+        action: Option<&serde_json::Value>,
+        decision: &ApprovalDecision,
+    ) -> Result<Self, sqlx::Error> {
+        let query = format!(
+            r#"
+            INSERT INTO tool_approvals
+                (id, execution_process_id, tool_name, tool_call_id, tool_input, action,
+                 status, decided_by, decided_by_user_id, rule_id, reason,
+                 created_at, timeout_at, decided_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, NOW())
+            RETURNING {APPROVAL_COLUMNS}
+            "#
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(&request.id)
+            .bind(request.execution_process_id)
+            .bind(&request.tool_name)
+            .bind(&request.tool_call_id)
+            .bind(&request.tool_input)
+            .bind(action)
+            .bind(decision.status_str())
+            .bind(decision.decided_by.as_str())
+            .bind(&decision.decided_by_user_id)
+            .bind(decision.rule_id)
+            .bind(decision.reason())
+            .bind(request.created_at)
+            .bind(request.timeout_at)
+            .fetch_one(pool)
+            .await
+    }
+
+    /// Settle a pending request. Returns `None` if it was already decided.
+    pub async fn resolve(
+        pool: &PgPool,
+        id: &str,
+        decision: &ApprovalDecision,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let query = format!(
+            r#"
+            UPDATE tool_approvals
+            SET status = $2, decided_by = $3, decided_by_user_id = $4, rule_id = $5,
+                reason = $6, decided_at = NOW()
+            WHERE id = $1 AND status = 'pending'
+            RETURNING {APPROVAL_COLUMNS}
+            "#
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(id)
+            .bind(decision.status_str())
+            .bind(decision.decided_by.as_str())
+            .bind(&decision.decided_by_user_id)
+            .bind(decision.rule_id)
+            .bind(decision.reason())
+            .fetch_optional(pool)
+            .await
+    }
+
+    pub async fn find_by_id(pool: &PgPool, id: &str) -> Result<Option<Self>, sqlx::Error> {
+        let query = format!("SELECT {APPROVAL_COLUMNS} FROM tool_approvals WHERE id = $1");
+        sqlx::query_as::<_, Self>(&query)
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// All requests still waiting for a decision, oldest first
+    pub async fn find_pending(pool: &PgPool) -> Result<Vec<Self>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT {APPROVAL_COLUMNS}
+            FROM tool_approvals
+            WHERE status = 'pending'
+            ORDER BY created_at ASC
+            "#
+        );
+        sqlx::query_as::<_, Self>(&query).fetch_all(pool).await
+    }
+
+    /// Approval history of an execution process, oldest first
+    pub async fn find_by_execution_process_id(
+        pool: &PgPool,
+        execution_process_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT {APPROVAL_COLUMNS}
+            FROM tool_approvals
+            WHERE execution_process_id = $1
+            ORDER BY created_at ASC
+            "#
+        );
+        sqlx::query_as::<_, Self>(&query)
+            .bind(execution_process_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Mark pending requests whose deadline has passed as timed out
+    pub async fn expire_overdue(pool: &PgPool) -> Result<Vec<Self>, sqlx::Error> {
+        let query = format!(
+            r#"
+            UPDATE tool_approvals
+            SET status = 'timed_out', decided_by = 'timeout', decided_at = NOW()
+            WHERE status = 'pending' AND timeout_at <= NOW()
+            RETURNING {APPROVAL_COLUMNS}
+            "#
+        );
+        sqlx::query_as::<_, Self>(&query).fetch_all(pool).await
+    }
+}
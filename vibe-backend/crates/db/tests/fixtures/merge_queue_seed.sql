@@ -0,0 +1,22 @@
+-- Minimal stand-ins for the project and task tables, which are not created
+-- by the migrations, plus one project with one task
+CREATE TABLE projects (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL
+);
+
+CREATE TABLE tasks (
+    id UUID PRIMARY KEY,
+    project_id UUID NOT NULL REFERENCES projects(id),
+    title TEXT NOT NULL
+);
+
+INSERT INTO projects (id, name)
+VALUES ('00000000-0000-0000-0000-0000000000b1', 'Shop');
+
+INSERT INTO tasks (id, project_id, title)
+VALUES (
+    '00000000-0000-0000-0000-0000000000c1',
+    '00000000-0000-0000-0000-0000000000b1',
+    'Add retries'
+);
@@ -6,6 +6,7 @@ use axum::response::sse::Event;
 use db::{
     DBService,
     models::{
+        merge_queue::MergeQueueEntry,
         project::{CreateProject, Project},
         project_repo::CreateProjectRepo,
         workspace::WorkspaceError,
@@ -27,17 +28,20 @@ use services::services::{
     filesystem_watcher::FilesystemWatcherError,
     git::{GitService, GitServiceError},
     image::{ImageError, ImageService},
+    merge_queue,
     pr_monitor::PrMonitorService,
     project::ProjectService,
     queued_message::QueuedMessageService,
     repo::RepoService,
     share::SharePublisher,
+    task_templates::TaskTemplateScheduler,
     worktree_manager::WorktreeError,
 };
 use sqlx::Error as SqlxError;
 use thiserror::Error;
 use tokio::sync::RwLock;
 use utils::sentry as sentry_utils;
+use uuid::Uuid;
 
 #[derive(Debug, Clone, Copy, Error)]
 #[error("Remote client not configured")]
@@ -91,7 +95,7 @@ pub trait Deployment: Clone + Send + Sync + 'static {
 
     fn analytics(&self) -> &Option<AnalyticsService>;
 
-    fn container(&self) -> &impl ContainerService;
+    fn container(&self) -> &(impl ContainerService + Sync);
 
     fn git(&self) -> &GitService;
 
@@ -138,6 +142,34 @@ pub trait Deployment: Clone + Send + Sync + 'static {
         PrMonitorService::spawn(db, analytics, publisher).await
     }
 
+    async fn spawn_task_template_scheduler(&self) -> tokio::task::JoinHandle<()> {
+        let deployment = self.clone();
+        tokio::spawn(async move {
+            TaskTemplateScheduler::new(deployment.container())
+                .run()
+                .await;
+        })
+    }
+
+    /// Land the queued entries of a merge queue lane in the background
+    fn process_merge_queue_lane(&self, repo_id: Uuid, target_branch: String) {
+        let deployment = self.clone();
+        tokio::spawn(async move {
+            merge_queue::run_lane(deployment.container(), repo_id, &target_branch).await;
+        });
+    }
+
+    /// Restart lanes a shutdown interrupted; entries that were mid-landing
+    /// are rebased and verified again
+    async fn resume_merge_queue(&self) -> Result<(), DeploymentError> {
+        let pool = &self.db().pool;
+        MergeQueueEntry::requeue_interrupted(pool).await?;
+        for (repo_id, target_branch) in MergeQueueEntry::find_queued_lanes(pool).await? {
+            self.process_merge_queue_lane(repo_id, target_branch);
+        }
+        Ok(())
+    }
+
     async fn track_if_analytics_allowed(&self, event_name: &str, properties: Value) {
         let analytics_enabled = self.config().read().await.analytics_enabled;
         // Track events unless user has explicitly opted out
@@ -1,19 +1,34 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, ffi::OsStr};
 
 use tokio::process::Command;
 
-use crate::command::CmdOverrides;
+use crate::{command::CmdOverrides, executors::ExecutorError, sandbox::SandboxPolicy};
 
 /// Environment variables to inject into executor processes
 #[derive(Debug, Clone, Default)]
 pub struct ExecutionEnv {
     pub vars: HashMap<String, String>,
+    /// Run executor processes in this sandbox rather than directly on the host
+    pub sandbox: Option<SandboxPolicy>,
+    /// Stored provider key for the API agent. It goes to the agent's HTTP
+    /// client only and is never set on a process.
+    pub api_agent_key: Option<String>,
 }
 
 impl ExecutionEnv {
     pub fn new() -> Self {
         Self {
             vars: HashMap::new(),
+            sandbox: None,
+            api_agent_key: None,
+        }
+    }
+
+    /// Start a command for `program`, inside the sandbox when one is set
+    pub async fn command(&self, program: impl AsRef<OsStr>) -> Result<Command, ExecutorError> {
+        match &self.sandbox {
+            Some(sandbox) => sandbox.command(program).await,
+            None => Ok(Command::new(program)),
         }
     }
 
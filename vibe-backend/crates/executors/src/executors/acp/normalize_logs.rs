@@ -64,6 +64,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                         streaming.assistant_text = None;
                         streaming.thinking_text = None;
                     }
+                    AcpEvent::Usage(usage) => msg_store.push_token_usage(usage),
                     AcpEvent::Message(content) => {
                         streaming.thinking_text = None;
                         if let agent_client_protocol::ContentBlock::Text(text) = content {
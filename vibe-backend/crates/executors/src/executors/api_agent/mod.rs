@@ -0,0 +1,699 @@
+//! Coding agent that talks to the Anthropic, OpenAI or Gemini streaming APIs
+//! directly instead of driving a CLI. It runs its own tool loop (read, write,
+//! edit, run command, search) in the worktree.
+//!
+//! The container tracks executions as child processes, so the agent spawns a
+//! small anchor process and writes its JSONL events to the anchor's stdout.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use command_group::AsyncCommandGroup;
+use derivative::Derivative;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+use tokio::{io::AsyncWriteExt, process::Command, sync::mpsc};
+use ts_rs::TS;
+use workspace_utils::{approvals::ApprovalStatus, log_msg::TokenUsage, msg_store::MsgStore};
+
+use crate::{
+    approvals::{ExecutorApprovalError, ExecutorApprovalService},
+    env::ExecutionEnv,
+    executors::{
+        AppendPrompt, AvailabilityInfo, ExecutorError, ExecutorExitResult, SpawnedChild,
+        StandardCodingAgentExecutor,
+    },
+    sse_bridge::{AiProvider, SseBridgeError},
+};
+
+mod normalize_logs;
+pub mod provider;
+pub mod tools;
+
+use provider::{Message, ProviderClient, ToolCall, ToolResultMessage};
+use tools::{ToolOutcome, WorkspaceTools};
+
+const DEFAULT_MAX_TURNS: u32 = 50;
+const SESSION_NAMESPACE: &str = "api_agent_sessions";
+
+const SYSTEM_PROMPT: &str = "You are a coding agent working in a git worktree. \
+Use the tools to inspect and change files and to run commands such as builds and tests. \
+Paths are relative to the workspace root. Prefer edit_file over write_file for existing files. \
+When the task is done, reply with a short summary of what you changed.";
+
+#[derive(Derivative, Clone, Serialize, Deserialize, TS, JsonSchema)]
+#[derivative(Debug, PartialEq)]
+pub struct ApiAgent {
+    #[serde(default)]
+    pub append_prompt: AppendPrompt,
+    #[schemars(
+        title = "Provider",
+        description = "API used to run the model: anthropic, openai or google"
+    )]
+    pub provider: AiProvider,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[schemars(
+        title = "Base URL",
+        description = "Override the provider API URL, e.g. for a proxy"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    #[schemars(
+        title = "Max Turns",
+        description = "Maximum number of model requests per run"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_turns: Option<u32>,
+    /// Auto-approve agent actions
+    #[serde(default)]
+    pub auto_approve: bool,
+    #[serde(skip)]
+    #[ts(skip)]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    pub approvals: Option<Arc<dyn ExecutorApprovalService>>,
+}
+
+#[derive(Debug, Error)]
+pub enum ApiAgentError {
+    #[error("{0} is not set; add a provider key to the workspace or the environment")]
+    MissingApiKey(&'static str),
+    #[error("request failed: {0}")]
+    Http(reqwest::Error),
+    #[error("provider returned {status}: {body}")]
+    Status { status: u16, body: String },
+    #[error(transparent)]
+    Sse(#[from] SseBridgeError),
+    #[error("{0}")]
+    Provider(String),
+    #[error("stopped after {0} turns without finishing")]
+    MaxTurns(u32),
+    #[error(transparent)]
+    Approval(#[from] ExecutorApprovalError),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+/// Events written to stdout as JSONL and turned into normalized entries
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ApiAgentEvent {
+    SessionStart {
+        session_id: String,
+        provider: AiProvider,
+        model: String,
+    },
+    User {
+        content: String,
+    },
+    /// Streamed assistant text, appended to the current assistant message
+    AssistantDelta {
+        text: String,
+    },
+    /// Ends the current assistant message
+    AssistantDone,
+    ToolCall {
+        id: String,
+        name: String,
+        arguments: Value,
+    },
+    ToolResult {
+        id: String,
+        name: String,
+        #[serde(flatten)]
+        outcome: ToolOutcome,
+    },
+    ToolDenied {
+        id: String,
+        name: String,
+        reason: Option<String>,
+    },
+    Usage {
+        usage: TokenUsage,
+    },
+    Error {
+        message: String,
+    },
+    Done {
+        stop_reason: Option<String>,
+    },
+}
+
+/// Conversation saved between runs so follow-ups can continue it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredSession {
+    provider: AiProvider,
+    model: String,
+    messages: Vec<Message>,
+}
+
+struct SessionStore {
+    base_dir: PathBuf,
+}
+
+impl SessionStore {
+    fn new() -> io::Result<Self> {
+        let mut vk_dir = dirs::home_dir()
+            .ok_or_else(|| io::Error::other("Could not determine home directory"))?
+            .join(".vibe-kanban");
+
+        if cfg!(debug_assertions) {
+            vk_dir = vk_dir.join("dev");
+        }
+
+        let base_dir = vk_dir.join(SESSION_NAMESPACE);
+        std::fs::create_dir_all(&base_dir)?;
+        Ok(Self { base_dir })
+    }
+
+    fn path(&self, session_id: &str) -> PathBuf {
+        self.base_dir.join(format!("{session_id}.json"))
+    }
+
+    async fn load(&self, session_id: &str) -> io::Result<Option<StoredSession>> {
+        match tokio::fs::read(self.path(session_id)).await {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn save(&self, session_id: &str, session: &StoredSession) -> io::Result<()> {
+        let bytes = serde_json::to_vec(session)?;
+        tokio::fs::write(self.path(session_id), bytes).await
+    }
+}
+
+/// Everything a run needs, moved into the background task
+struct AgentRun {
+    client: ProviderClient,
+    provider: AiProvider,
+    session_id: String,
+    session: StoredSession,
+    store: SessionStore,
+    tools: WorkspaceTools,
+    approvals: Option<Arc<dyn ExecutorApprovalService>>,
+    max_turns: u32,
+    events: mpsc::UnboundedSender<ApiAgentEvent>,
+}
+
+impl AgentRun {
+    fn emit(&self, event: ApiAgentEvent) {
+        let _ = self.events.send(event);
+    }
+
+    async fn run(&mut self) -> Result<Option<String>, ApiAgentError> {
+        let specs = tools::tool_specs();
+
+        for _ in 0..self.max_turns {
+            let events = self.events.clone();
+            let mut on_text = move |text: &str| {
+                let _ = events.send(ApiAgentEvent::AssistantDelta {
+                    text: text.to_string(),
+                });
+            };
+            let turn = self
+                .client
+                .stream_turn(SYSTEM_PROMPT, &self.session.messages, &specs, &mut on_text)
+                .await?;
+
+            if !turn.text.is_empty() {
+                self.emit(ApiAgentEvent::AssistantDone);
+            }
+            if let Some(usage) = turn
+                .usage
+                .clone()
+                .and_then(|usage| usage.into_token_usage(Some(self.client.model().to_string())))
+            {
+                self.emit(ApiAgentEvent::Usage { usage });
+            }
+
+            let tool_calls = turn.tool_calls.clone();
+            self.session.messages.push(Message::Assistant {
+                text: turn.text,
+                tool_calls: turn.tool_calls,
+            });
+            self.store.save(&self.session_id, &self.session).await?;
+
+            if tool_calls.is_empty() {
+                return Ok(turn.stop_reason);
+            }
+
+            let mut results = Vec::with_capacity(tool_calls.len());
+            for call in &tool_calls {
+                let outcome = self.run_tool(call).await?;
+                results.push(ToolResultMessage {
+                    tool_call_id: call.id.clone(),
+                    name: call.name.clone(),
+                    content: outcome.content,
+                    is_error: outcome.is_error,
+                });
+            }
+            self.session.messages.push(Message::Tool { results });
+            self.store.save(&self.session_id, &self.session).await?;
+        }
+
+        Err(ApiAgentError::MaxTurns(self.max_turns))
+    }
+
+    async fn run_tool(&self, call: &ToolCall) -> Result<ToolOutcome, ApiAgentError> {
+        // The tool entry must exist before approval is requested so the
+        // approval can be attached to it
+        self.emit(ApiAgentEvent::ToolCall {
+            id: call.id.clone(),
+            name: call.name.clone(),
+            arguments: call.arguments.clone(),
+        });
+
+        if let Some(approvals) = &self.approvals
+            && tools::requires_approval(&call.name)
+        {
+            let status = approvals
+                .request_tool_approval(&call.name, call.arguments.clone(), &call.id)
+                .await?;
+            let denial = match status {
+                ApprovalStatus::Approved => None,
+                ApprovalStatus::Denied { reason } => Some(reason),
+                ApprovalStatus::TimedOut => Some(Some("Approval timed out".to_string())),
+                ApprovalStatus::Pending => Some(None),
+            };
+            if let Some(reason) = denial {
+                self.emit(ApiAgentEvent::ToolDenied {
+                    id: call.id.clone(),
+                    name: call.name.clone(),
+                    reason: reason.clone(),
+                });
+                let message = match reason {
+                    Some(reason) => format!("The user denied this tool call: {reason}"),
+                    None => "The user denied this tool call".to_string(),
+                };
+                return Ok(ToolOutcome::error(message));
+            }
+        }
+
+        let outcome = self.tools.execute(call).await;
+        self.emit(ApiAgentEvent::ToolResult {
+            id: call.id.clone(),
+            name: call.name.clone(),
+            outcome: outcome.clone(),
+        });
+        Ok(outcome)
+    }
+}
+
+impl ApiAgent {
+    fn model(&self) -> String {
+        self.model
+            .clone()
+            .unwrap_or_else(|| provider::default_model(self.provider).to_string())
+    }
+
+    /// Key from the profile env, the workspace's stored provider key or the
+    /// server's env
+    fn api_key(&self, env: &ExecutionEnv) -> Result<String, ApiAgentError> {
+        let name = provider::api_key_env(self.provider);
+        env.vars
+            .get(name)
+            .cloned()
+            .or_else(|| env.api_agent_key.clone())
+            .or_else(|| std::env::var(name).ok())
+            .filter(|key| !key.trim().is_empty())
+            .ok_or(ApiAgentError::MissingApiKey(name))
+    }
+
+    async fn spawn_run(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session: Option<(String, StoredSession)>,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let api_key = self
+            .api_key(env)
+            .map_err(|e| ExecutorError::AuthRequired(e.to_string()))?;
+        let store = SessionStore::new().map_err(ExecutorError::Io)?;
+        let (session_id, mut session) = session.unwrap_or_else(|| {
+            (
+                uuid::Uuid::new_v4().to_string(),
+                StoredSession {
+                    provider: self.provider,
+                    model: self.model(),
+                    messages: Vec::new(),
+                },
+            )
+        });
+        // A follow-up keeps the conversation but uses the current profile
+        session.provider = self.provider;
+        session.model = self.model();
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+        session.messages.push(Message::User {
+            content: combined_prompt.clone(),
+        });
+
+        let mut child = spawn_anchor(current_dir, env)?;
+        let anchor_stdin = child.inner().stdin.take();
+        let writer = crate::stdout_dup::create_stdout_pipe_writer(&mut child)?;
+
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel::<ApiAgentEvent>();
+        tokio::spawn(async move {
+            let mut writer = writer;
+            while let Some(event) = event_rx.recv().await {
+                let Ok(mut line) = serde_json::to_string(&event) else {
+                    continue;
+                };
+                line.push('\n');
+                if writer.write_all(line.as_bytes()).await.is_err() {
+                    break;
+                }
+                let _ = writer.flush().await;
+            }
+        });
+
+        let _ = event_tx.send(ApiAgentEvent::SessionStart {
+            session_id: session_id.clone(),
+            provider: self.provider,
+            model: session.model.clone(),
+        });
+        let _ = event_tx.send(ApiAgentEvent::User {
+            content: combined_prompt,
+        });
+
+        let approvals = if self.auto_approve {
+            None
+        } else {
+            self.approvals.clone()
+        };
+        let mut run = AgentRun {
+            client: ProviderClient::new(
+                self.provider,
+                session.model.clone(),
+                self.base_url.as_deref(),
+                api_key,
+            ),
+            provider: self.provider,
+            session_id,
+            session,
+            store,
+            tools: WorkspaceTools::new(current_dir, env.clone()),
+            approvals,
+            max_turns: self.max_turns.unwrap_or(DEFAULT_MAX_TURNS),
+            events: event_tx,
+        };
+
+        let (exit_tx, exit_rx) = tokio::sync::oneshot::channel::<ExecutorExitResult>();
+        let (interrupt_tx, interrupt_rx) = tokio::sync::oneshot::channel::<()>();
+
+        tokio::spawn(async move {
+            // Holding stdin keeps the anchor alive until the run ends
+            let _anchor_stdin = anchor_stdin;
+            let events = run.events.clone();
+
+            let result = tokio::select! {
+                result = run.run() => result,
+                _ = interrupt_rx => {
+                    let _ = events.send(ApiAgentEvent::Done {
+                        stop_reason: Some("interrupted".to_string()),
+                    });
+                    let _ = exit_tx.send(ExecutorExitResult::Success);
+                    return;
+                }
+            };
+
+            let exit = match result {
+                Ok(stop_reason) => {
+                    run.emit(ApiAgentEvent::Done { stop_reason });
+                    ExecutorExitResult::Success
+                }
+                Err(e) => {
+                    tracing::warn!(provider = %run.provider, "API agent run failed: {e}");
+                    run.emit(ApiAgentEvent::Error {
+                        message: e.to_string(),
+                    });
+                    ExecutorExitResult::Failure
+                }
+            };
+            let _ = exit_tx.send(exit);
+        });
+
+        Ok(SpawnedChild {
+            child,
+            exit_signal: Some(exit_rx),
+            interrupt_sender: Some(interrupt_tx),
+        })
+    }
+}
+
+/// Spawn a process that idles until its stdin closes
+fn spawn_anchor(
+    current_dir: &Path,
+    env: &ExecutionEnv,
+) -> Result<command_group::AsyncGroupChild, ExecutorError> {
+    let (shell, shell_arg) = workspace_utils::shell::get_shell_command();
+    let script = if cfg!(windows) {
+        "more > NUL"
+    } else {
+        "cat > /dev/null"
+    };
+
+    let mut command = Command::new(shell);
+    command
+        .kill_on_drop(true)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .current_dir(current_dir)
+        .arg(shell_arg)
+        .arg(script);
+    env.apply_to_command(&mut command);
+
+    Ok(command.group_spawn()?)
+}
+
+#[async_trait]
+impl StandardCodingAgentExecutor for ApiAgent {
+    fn use_approvals(&mut self, approvals: Arc<dyn ExecutorApprovalService>) {
+        self.approvals = Some(approvals);
+    }
+
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        self.spawn_run(current_dir, prompt, None, env).await
+    }
+
+    async fn spawn_follow_up(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let store = SessionStore::new().map_err(ExecutorError::Io)?;
+        let session = store.load(session_id).await.map_err(ExecutorError::Io)?;
+        let Some(session) = session else {
+            return Err(ExecutorError::FollowUpNotSupported(format!(
+                "API agent session {session_id} not found"
+            )));
+        };
+        self.spawn_run(
+            current_dir,
+            prompt,
+            Some((session_id.to_string(), session)),
+            env,
+        )
+        .await
+    }
+
+    fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
+        normalize_logs::normalize_logs(msg_store, worktree_path);
+    }
+
+    // MCP servers are not supported; the agent only has its built-in tools
+    fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
+        None
+    }
+
+    fn get_availability_info(&self) -> AvailabilityInfo {
+        if std::env::var(provider::api_key_env(self.provider)).is_ok_and(|key| !key.is_empty()) {
+            AvailabilityInfo::InstallationFound
+        } else {
+            AvailabilityInfo::NotFound
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{io::AsyncReadExt, net::TcpListener};
+
+    use super::*;
+
+    /// Serve each canned SSE body once, in order, and record request bodies
+    async fn mock_sse_server(bodies: Vec<String>) -> (String, mpsc::UnboundedReceiver<Value>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (request_tx, request_rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            for body in bodies {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 8192];
+                // Read headers, then the JSON body by Content-Length
+                let header_end = loop {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                    if let Some(pos) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                        break pos + 4;
+                    }
+                };
+                let headers = String::from_utf8_lossy(&request[..header_end]).to_lowercase();
+                let length: usize = headers
+                    .lines()
+                    .find_map(|line| line.strip_prefix("content-length:"))
+                    .map(|value| value.trim().parse().unwrap())
+                    .unwrap_or(0);
+                while request.len() < header_end + length {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+                let _ = request_tx.send(serde_json::from_slice(&request[header_end..]).unwrap());
+
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+                socket.shutdown().await.unwrap();
+            }
+        });
+
+        (url, request_rx)
+    }
+
+    fn anthropic_sse(events: &[(&str, Value)]) -> String {
+        events
+            .iter()
+            .map(|(name, data)| format!("event: {name}\ndata: {data}\n\n"))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_tool_loop_against_mock_anthropic_server() {
+        let workspace = tempfile::tempdir().unwrap();
+        let home = tempfile::tempdir().unwrap();
+        std::fs::write(workspace.path().join("notes.txt"), "hello world\n").unwrap();
+
+        let first = anthropic_sse(&[
+            (
+                "message_start",
+                serde_json::json!({"type":"message_start","message":{"id":"msg_1","usage":{"input_tokens":50,"output_tokens":1}}}),
+            ),
+            (
+                "content_block_start",
+                serde_json::json!({"type":"content_block_start","index":0,"content_block":{"type":"tool_use","id":"toolu_1","name":"edit_file","input":{}}}),
+            ),
+            (
+                "content_block_delta",
+                serde_json::json!({"type":"content_block_delta","index":0,"delta":{"type":"input_json_delta","partial_json":"{\"path\":\"notes.txt\",\"old_string\":\"world\","}}),
+            ),
+            (
+                "content_block_delta",
+                serde_json::json!({"type":"content_block_delta","index":0,"delta":{"type":"input_json_delta","partial_json":"\"new_string\":\"there\"}"}}),
+            ),
+            (
+                "message_delta",
+                serde_json::json!({"type":"message_delta","delta":{"stop_reason":"tool_use"},"usage":{"output_tokens":20}}),
+            ),
+        ]);
+        let second = anthropic_sse(&[
+            (
+                "content_block_delta",
+                serde_json::json!({"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Done."}}),
+            ),
+            (
+                "message_delta",
+                serde_json::json!({"type":"message_delta","delta":{"stop_reason":"end_turn"},"usage":{"output_tokens":3}}),
+            ),
+        ]);
+        let (url, mut requests) = mock_sse_server(vec![first, second]).await;
+
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let mut run = AgentRun {
+            client: ProviderClient::new(
+                AiProvider::Anthropic,
+                "claude-sonnet-4-5",
+                Some(&url),
+                "test-key",
+            ),
+            provider: AiProvider::Anthropic,
+            session_id: "test-session".to_string(),
+            session: StoredSession {
+                provider: AiProvider::Anthropic,
+                model: "claude-sonnet-4-5".to_string(),
+                messages: vec![Message::User {
+                    content: "Say hello there".to_string(),
+                }],
+            },
+            store: SessionStore {
+                base_dir: home.path().to_path_buf(),
+            },
+            tools: WorkspaceTools::new(workspace.path(), ExecutionEnv::new()),
+            approvals: None,
+            max_turns: 5,
+            events: event_tx,
+        };
+
+        let stop_reason = run.run().await.unwrap();
+        assert_eq!(stop_reason.as_deref(), Some("end_turn"));
+        assert_eq!(
+            std::fs::read_to_string(workspace.path().join("notes.txt")).unwrap(),
+            "hello there\n"
+        );
+
+        // The second request carries the tool result back to the model
+        let _ = requests.recv().await.unwrap();
+        let second_request = requests.recv().await.unwrap();
+        let tool_result = &second_request["messages"][2]["content"][0];
+        assert_eq!(tool_result["type"], "tool_result");
+        assert_eq!(tool_result["tool_use_id"], "toolu_1");
+        assert_eq!(tool_result["is_error"], false);
+
+        drop(run);
+        let mut events = Vec::new();
+        while let Some(event) = event_rx.recv().await {
+            events.push(event);
+        }
+        assert!(
+            matches!(&events[0], ApiAgentEvent::Usage { usage } if usage.input_tokens == 50 && usage.output_tokens == 20)
+        );
+        assert!(matches!(&events[1], ApiAgentEvent::ToolCall { name, .. } if name == "edit_file"));
+        assert!(
+            matches!(&events[2], ApiAgentEvent::ToolResult { outcome, .. } if !outcome.is_error)
+        );
+        assert_eq!(
+            events[3],
+            ApiAgentEvent::AssistantDelta {
+                text: "Done.".to_string()
+            }
+        );
+        assert_eq!(events[4], ApiAgentEvent::AssistantDone);
+
+        let saved = std::fs::read_to_string(home.path().join("test-session.json")).unwrap();
+        let saved: StoredSession = serde_json::from_str(&saved).unwrap();
+        assert_eq!(saved.messages.len(), 4);
+    }
+}
@@ -0,0 +1,223 @@
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use futures::StreamExt;
+use serde_json::Value;
+use workspace_utils::{diff::create_unified_diff, msg_store::MsgStore, path::make_path_relative};
+
+use super::{
+    ApiAgentEvent,
+    tools::{EDIT_FILE, READ_FILE, RUN_COMMAND, SEARCH, ToolOutcome, WRITE_FILE},
+};
+use crate::{
+    approvals::ToolCallMetadata,
+    logs::{
+        ActionType, CommandExitStatus, CommandRunResult, FileChange, NormalizedEntry,
+        NormalizedEntryError, NormalizedEntryType, ToolResult, ToolStatus,
+        stderr_processor::normalize_stderr_logs,
+        utils::{ConversationPatch, EntryIndexProvider},
+    },
+};
+
+/// A tool entry that may still be updated by its result
+struct ToolEntry {
+    index: usize,
+    name: String,
+    arguments: Value,
+}
+
+pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
+    let entry_index = EntryIndexProvider::start_from(&msg_store);
+    normalize_stderr_logs(msg_store.clone(), entry_index.clone());
+
+    let worktree_path = worktree_path.to_string_lossy().to_string();
+    tokio::spawn(async move {
+        let mut assistant: Option<(usize, String)> = None;
+        let mut tools: HashMap<String, ToolEntry> = HashMap::new();
+
+        let mut stdout_lines = msg_store.stdout_lines_stream();
+        while let Some(Ok(line)) = stdout_lines.next().await {
+            let Ok(event) = serde_json::from_str::<ApiAgentEvent>(line.trim()) else {
+                continue;
+            };
+
+            let add = |entry: NormalizedEntry| {
+                let index = entry_index.next();
+                msg_store.push_patch(ConversationPatch::add_normalized_entry(index, entry));
+                index
+            };
+
+            match event {
+                ApiAgentEvent::SessionStart { session_id, .. } => {
+                    msg_store.push_session_id(session_id);
+                }
+                ApiAgentEvent::User { .. } => {}
+                ApiAgentEvent::AssistantDelta { text } => {
+                    let is_new = assistant.is_none();
+                    let (index, content) = assistant.get_or_insert_with(|| (0, String::new()));
+                    content.push_str(&text);
+                    let entry = NormalizedEntry {
+                        timestamp: None,
+                        entry_type: NormalizedEntryType::AssistantMessage,
+                        content: content.clone(),
+                        metadata: None,
+                    };
+                    if is_new {
+                        *index = add(entry);
+                    } else {
+                        msg_store.push_patch(ConversationPatch::replace(*index, entry));
+                    }
+                }
+                ApiAgentEvent::AssistantDone => assistant = None,
+                ApiAgentEvent::ToolCall {
+                    id,
+                    name,
+                    arguments,
+                } => {
+                    assistant = None;
+                    let tool = ToolEntry {
+                        index: 0,
+                        name,
+                        arguments,
+                    };
+                    let entry = tool_entry(&tool, &id, ToolStatus::Created, None, &worktree_path);
+                    let index = add(entry);
+                    tools.insert(id, ToolEntry { index, ..tool });
+                }
+                ApiAgentEvent::ToolResult { id, outcome, .. } => {
+                    if let Some(tool) = tools.remove(&id) {
+                        let status = if outcome.is_error {
+                            ToolStatus::Failed
+                        } else {
+                            ToolStatus::Success
+                        };
+                        let entry = tool_entry(&tool, &id, status, Some(&outcome), &worktree_path);
+                        msg_store.push_patch(ConversationPatch::replace(tool.index, entry));
+                    }
+                }
+                ApiAgentEvent::ToolDenied { id, name, reason } => {
+                    if let Some(tool) = tools.remove(&id) {
+                        let status = ToolStatus::Denied {
+                            reason: reason.clone(),
+                        };
+                        let entry = tool_entry(&tool, &id, status, None, &worktree_path);
+                        msg_store.push_patch(ConversationPatch::replace(tool.index, entry));
+                    }
+                    add(NormalizedEntry {
+                        timestamp: None,
+                        entry_type: NormalizedEntryType::UserFeedback { denied_tool: name },
+                        content: reason
+                            .unwrap_or_else(|| "User denied this tool use request".to_string()),
+                        metadata: None,
+                    });
+                }
+                ApiAgentEvent::Usage { usage } => msg_store.push_token_usage(usage),
+                ApiAgentEvent::Error { message } => {
+                    assistant = None;
+                    add(NormalizedEntry {
+                        timestamp: None,
+                        entry_type: NormalizedEntryType::ErrorMessage {
+                            error_type: NormalizedEntryError::Other,
+                        },
+                        content: message,
+                        metadata: None,
+                    });
+                }
+                ApiAgentEvent::Done { .. } => assistant = None,
+            }
+        }
+    });
+}
+
+fn tool_entry(
+    tool: &ToolEntry,
+    id: &str,
+    status: ToolStatus,
+    outcome: Option<&ToolOutcome>,
+    worktree_path: &str,
+) -> NormalizedEntry {
+    let arg = |name: &str| {
+        tool.arguments
+            .get(name)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+    let path = make_path_relative(&arg("path"), worktree_path);
+
+    let (action_type, content) = match tool.name.as_str() {
+        READ_FILE => (ActionType::FileRead { path: path.clone() }, path),
+        WRITE_FILE => (
+            ActionType::FileEdit {
+                path: path.clone(),
+                changes: vec![FileChange::Write {
+                    content: arg("content"),
+                }],
+            },
+            path,
+        ),
+        EDIT_FILE => (
+            ActionType::FileEdit {
+                path: path.clone(),
+                changes: vec![FileChange::Edit {
+                    unified_diff: create_unified_diff(
+                        &path,
+                        &arg("old_string"),
+                        &arg("new_string"),
+                    ),
+                    has_line_numbers: false,
+                }],
+            },
+            path,
+        ),
+        RUN_COMMAND => {
+            let command = arg("command");
+            let result = outcome.map(|outcome| CommandRunResult {
+                exit_status: outcome
+                    .exit_code
+                    .map(|code| CommandExitStatus::ExitCode { code })
+                    .or(Some(CommandExitStatus::Success {
+                        success: !outcome.is_error,
+                    })),
+                output: Some(outcome.content.clone()),
+            });
+            (
+                ActionType::CommandRun {
+                    command: command.clone(),
+                    result,
+                },
+                command,
+            )
+        }
+        SEARCH => {
+            let query = arg("pattern");
+            (
+                ActionType::Search {
+                    query: query.clone(),
+                },
+                query,
+            )
+        }
+        other => (
+            ActionType::Tool {
+                tool_name: other.to_string(),
+                arguments: Some(tool.arguments.clone()),
+                result: outcome.map(|outcome| ToolResult::markdown(outcome.content.clone())),
+            },
+            other.to_string(),
+        ),
+    };
+
+    NormalizedEntry {
+        timestamp: None,
+        entry_type: NormalizedEntryType::ToolUse {
+            tool_name: tool.name.clone(),
+            action_type,
+            status,
+        },
+        content,
+        metadata: serde_json::to_value(ToolCallMetadata {
+            tool_call_id: id.to_string(),
+        })
+        .ok(),
+    }
+}
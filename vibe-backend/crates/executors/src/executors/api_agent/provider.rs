@@ -0,0 +1,497 @@
+//! Streaming chat requests against the Anthropic, OpenAI and Gemini APIs.
+//!
+//! The conversation is kept in a provider-neutral form and converted to each
+//! provider's wire format per request. Responses are decoded with
+//! [`SseDecoder`] and [`SseBridge::parse_deltas`].
+
+use std::collections::BTreeMap;
+
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use super::{ApiAgentError, tools::ToolSpec};
+use crate::{
+    logs::usage::ReportedUsage,
+    sse_bridge::{AiProvider, SseBridge, SseDecoder, SseEvent, StreamDelta},
+};
+
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+const MAX_OUTPUT_TOKENS: u32 = 16_384;
+
+pub fn default_model(provider: AiProvider) -> &'static str {
+    match provider {
+        AiProvider::Anthropic => "claude-sonnet-4-5",
+        AiProvider::OpenAI => "gpt-5",
+        AiProvider::Google => "gemini-2.5-pro",
+    }
+}
+
+pub fn default_base_url(provider: AiProvider) -> &'static str {
+    match provider {
+        AiProvider::Anthropic => "https://api.anthropic.com",
+        AiProvider::OpenAI => "https://api.openai.com",
+        AiProvider::Google => "https://generativelanguage.googleapis.com",
+    }
+}
+
+/// Environment variable holding the provider's API key
+pub fn api_key_env(provider: AiProvider) -> &'static str {
+    match provider {
+        AiProvider::Anthropic => "ANTHROPIC_API_KEY",
+        AiProvider::OpenAI => "OPENAI_API_KEY",
+        AiProvider::Google => "GEMINI_API_KEY",
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolCall {
+    pub id: String,
+    pub name: String,
+    pub arguments: Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolResultMessage {
+    pub tool_call_id: String,
+    pub name: String,
+    pub content: String,
+    pub is_error: bool,
+}
+
+/// One message of the conversation, independent of the provider
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "role", rename_all = "snake_case")]
+pub enum Message {
+    User {
+        content: String,
+    },
+    Assistant {
+        text: String,
+        tool_calls: Vec<ToolCall>,
+    },
+    /// Results of the tool calls of the preceding assistant message
+    Tool {
+        results: Vec<ToolResultMessage>,
+    },
+}
+
+/// The model's complete reply to one request
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AssistantTurn {
+    pub text: String,
+    pub tool_calls: Vec<ToolCall>,
+    pub stop_reason: Option<String>,
+    pub usage: Option<ReportedUsage>,
+}
+
+/// Collects stream deltas into an [`AssistantTurn`]
+#[derive(Debug, Default)]
+struct TurnBuilder {
+    turn: AssistantTurn,
+    // Tool calls whose arguments arrive in fragments, keyed by stream index
+    partial_calls: BTreeMap<usize, (String, String, String)>,
+}
+
+impl TurnBuilder {
+    fn apply(&mut self, delta: StreamDelta) -> Result<(), ApiAgentError> {
+        match delta {
+            StreamDelta::MessageId(_) => {}
+            StreamDelta::Text(text) => self.turn.text.push_str(&text),
+            StreamDelta::ToolCallStart { index, id, name } => {
+                self.partial_calls.insert(index, (id, name, String::new()));
+            }
+            StreamDelta::ToolArguments { index, fragment } => {
+                if let Some((_, _, arguments)) = self.partial_calls.get_mut(&index) {
+                    arguments.push_str(&fragment);
+                }
+            }
+            StreamDelta::ToolCall {
+                id,
+                name,
+                arguments,
+            } => {
+                let id = id.unwrap_or_else(|| format!("call_{}", uuid::Uuid::new_v4().simple()));
+                self.turn.tool_calls.push(ToolCall {
+                    id,
+                    name,
+                    arguments,
+                });
+            }
+            StreamDelta::Usage(usage) => {
+                // Providers send usage in pieces (Anthropic) or cumulatively;
+                // later values win field by field
+                let merged = self.turn.usage.get_or_insert_with(ReportedUsage::default);
+                merged.input_tokens = usage.input_tokens.or(merged.input_tokens);
+                merged.output_tokens = usage.output_tokens.or(merged.output_tokens);
+                merged.cache_read_tokens = usage.cache_read_tokens.or(merged.cache_read_tokens);
+                merged.cache_write_tokens = usage.cache_write_tokens.or(merged.cache_write_tokens);
+            }
+            StreamDelta::Stop(reason) => self.turn.stop_reason = Some(reason),
+            StreamDelta::Error(message) => return Err(ApiAgentError::Provider(message)),
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> AssistantTurn {
+        for (_, (id, name, arguments)) in std::mem::take(&mut self.partial_calls) {
+            let arguments = if arguments.trim().is_empty() {
+                json!({})
+            } else {
+                serde_json::from_str(&arguments).unwrap_or(Value::String(arguments))
+            };
+            self.turn.tool_calls.push(ToolCall {
+                id,
+                name,
+                arguments,
+            });
+        }
+        self.turn
+    }
+}
+
+/// Streaming client for one provider and model
+#[derive(Debug, Clone)]
+pub struct ProviderClient {
+    http: reqwest::Client,
+    provider: AiProvider,
+    model: String,
+    base_url: String,
+    api_key: String,
+}
+
+impl ProviderClient {
+    pub fn new(
+        provider: AiProvider,
+        model: impl Into<String>,
+        base_url: Option<&str>,
+        api_key: impl Into<String>,
+    ) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            provider,
+            model: model.into(),
+            base_url: base_url
+                .unwrap_or(default_base_url(provider))
+                .trim_end_matches('/')
+                .to_string(),
+            api_key: api_key.into(),
+        }
+    }
+
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Send the conversation and stream the reply. Text is passed to
+    /// `on_text` as it arrives.
+    pub async fn stream_turn(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[ToolSpec],
+        on_text: &mut (dyn FnMut(&str) + Send),
+    ) -> Result<AssistantTurn, ApiAgentError> {
+        let response = self
+            .request(system, messages, tools)
+            .send()
+            .await
+            .map_err(ApiAgentError::Http)?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ApiAgentError::Status {
+                status: status.as_u16(),
+                body,
+            });
+        }
+
+        let bridge = SseBridge::new(self.provider);
+        let mut decoder = SseDecoder::new();
+        let mut builder = TurnBuilder::default();
+        let mut apply = |event: SseEvent, builder: &mut TurnBuilder| {
+            for delta in bridge.parse_deltas(&event)? {
+                if let StreamDelta::Text(text) = &delta {
+                    on_text(text);
+                }
+                builder.apply(delta)?;
+            }
+            Ok::<_, ApiAgentError>(())
+        };
+
+        let mut body = response.bytes_stream();
+        while let Some(chunk) = body.next().await {
+            let chunk = chunk.map_err(ApiAgentError::Http)?;
+            for event in decoder.push(&chunk) {
+                apply(event, &mut builder)?;
+            }
+        }
+        if let Some(event) = decoder.finish() {
+            apply(event, &mut builder)?;
+        }
+
+        Ok(builder.finish())
+    }
+
+    fn request(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[ToolSpec],
+    ) -> reqwest::RequestBuilder {
+        match self.provider {
+            AiProvider::Anthropic => self
+                .http
+                .post(format!("{}/v1/messages", self.base_url))
+                .header("x-api-key", &self.api_key)
+                .header("anthropic-version", ANTHROPIC_VERSION)
+                .json(&anthropic_body(&self.model, system, messages, tools)),
+            AiProvider::OpenAI => self
+                .http
+                .post(format!("{}/v1/chat/completions", self.base_url))
+                .bearer_auth(&self.api_key)
+                .json(&openai_body(&self.model, system, messages, tools)),
+            AiProvider::Google => self
+                .http
+                .post(format!(
+                    "{}/v1beta/models/{}:streamGenerateContent?alt=sse",
+                    self.base_url, self.model
+                ))
+                .header("x-goog-api-key", &self.api_key)
+                .json(&gemini_body(system, messages, tools)),
+        }
+    }
+}
+
+fn anthropic_body(model: &str, system: &str, messages: &[Message], tools: &[ToolSpec]) -> Value {
+    let messages: Vec<Value> = messages
+        .iter()
+        .map(|message| match message {
+            Message::User { content } => json!({ "role": "user", "content": content }),
+            Message::Assistant { text, tool_calls } => {
+                let mut content = Vec::new();
+                if !text.is_empty() {
+                    content.push(json!({ "type": "text", "text": text }));
+                }
+                content.extend(tool_calls.iter().map(|call| {
+                    json!({
+                        "type": "tool_use",
+                        "id": call.id,
+                        "name": call.name,
+                        "input": call.arguments,
+                    })
+                }));
+                json!({ "role": "assistant", "content": content })
+            }
+            Message::Tool { results } => json!({
+                "role": "user",
+                "content": results.iter().map(|result| json!({
+                    "type": "tool_result",
+                    "tool_use_id": result.tool_call_id,
+                    "content": result.content,
+                    "is_error": result.is_error,
+                })).collect::<Vec<_>>(),
+            }),
+        })
+        .collect();
+
+    json!({
+        "model": model,
+        "max_tokens": MAX_OUTPUT_TOKENS,
+        "stream": true,
+        "system": system,
+        "messages": messages,
+        "tools": tools.iter().map(|tool| json!({
+            "name": tool.name,
+            "description": tool.description,
+            "input_schema": tool.parameters,
+        })).collect::<Vec<_>>(),
+    })
+}
+
+fn openai_body(model: &str, system: &str, messages: &[Message], tools: &[ToolSpec]) -> Value {
+    let mut wire = vec![json!({ "role": "system", "content": system })];
+    for message in messages {
+        match message {
+            Message::User { content } => wire.push(json!({ "role": "user", "content": content })),
+            Message::Assistant { text, tool_calls } => {
+                let mut value = json!({
+                    "role": "assistant",
+                    "content": if text.is_empty() { Value::Null } else { json!(text) },
+                });
+                if !tool_calls.is_empty() {
+                    value["tool_calls"] = tool_calls
+                        .iter()
+                        .map(|call| {
+                            json!({
+                                "id": call.id,
+                                "type": "function",
+                                "function": {
+                                    "name": call.name,
+                                    "arguments": call.arguments.to_string(),
+                                },
+                            })
+                        })
+                        .collect();
+                }
+                wire.push(value);
+            }
+            Message::Tool { results } => {
+                wire.extend(results.iter().map(|result| {
+                    json!({
+                        "role": "tool",
+                        "tool_call_id": result.tool_call_id,
+                        "content": result.content,
+                    })
+                }));
+            }
+        }
+    }
+
+    json!({
+        "model": model,
+        "stream": true,
+        "stream_options": { "include_usage": true },
+        "messages": wire,
+        "tools": tools.iter().map(|tool| json!({
+            "type": "function",
+            "function": {
+                "name": tool.name,
+                "description": tool.description,
+                "parameters": tool.parameters,
+            },
+        })).collect::<Vec<_>>(),
+    })
+}
+
+fn gemini_body(system: &str, messages: &[Message], tools: &[ToolSpec]) -> Value {
+    let contents: Vec<Value> = messages
+        .iter()
+        .map(|message| match message {
+            Message::User { content } => json!({ "role": "user", "parts": [{ "text": content }] }),
+            Message::Assistant { text, tool_calls } => {
+                let mut parts = Vec::new();
+                if !text.is_empty() {
+                    parts.push(json!({ "text": text }));
+                }
+                parts.extend(tool_calls.iter().map(
+                    |call| json!({ "functionCall": { "name": call.name, "args": call.arguments } }),
+                ));
+                json!({ "role": "model", "parts": parts })
+            }
+            Message::Tool { results } => json!({
+                "role": "user",
+                "parts": results.iter().map(|result| json!({
+                    "functionResponse": {
+                        "name": result.name,
+                        "response": {
+                            "content": result.content,
+                            "is_error": result.is_error,
+                        },
+                    },
+                })).collect::<Vec<_>>(),
+            }),
+        })
+        .collect();
+
+    json!({
+        "systemInstruction": { "parts": [{ "text": system }] },
+        "contents": contents,
+        "tools": [{
+            "functionDeclarations": tools.iter().map(|tool| json!({
+                "name": tool.name,
+                "description": tool.description,
+                "parameters": tool.parameters,
+            })).collect::<Vec<_>>(),
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turn_builder_assembles_fragmented_tool_calls() {
+        let mut builder = TurnBuilder::default();
+        for delta in [
+            StreamDelta::Text("Let me look.".to_string()),
+            StreamDelta::ToolCallStart {
+                index: 1,
+                id: "toolu_1".to_string(),
+                name: "read_file".to_string(),
+            },
+            StreamDelta::ToolArguments {
+                index: 1,
+                fragment: r#"{"path":"#.to_string(),
+            },
+            StreamDelta::ToolArguments {
+                index: 1,
+                fragment: r#""README.md"}"#.to_string(),
+            },
+            StreamDelta::Usage(ReportedUsage {
+                input_tokens: Some(10),
+                ..Default::default()
+            }),
+            StreamDelta::Usage(ReportedUsage {
+                output_tokens: Some(5),
+                ..Default::default()
+            }),
+            StreamDelta::Stop("tool_use".to_string()),
+        ] {
+            builder.apply(delta).unwrap();
+        }
+
+        let turn = builder.finish();
+        assert_eq!(turn.text, "Let me look.");
+        assert_eq!(
+            turn.tool_calls,
+            vec![ToolCall {
+                id: "toolu_1".to_string(),
+                name: "read_file".to_string(),
+                arguments: json!({ "path": "README.md" }),
+            }]
+        );
+        let usage = turn.usage.unwrap();
+        assert_eq!(usage.input_tokens, Some(10));
+        assert_eq!(usage.output_tokens, Some(5));
+        assert_eq!(turn.stop_reason.as_deref(), Some("tool_use"));
+    }
+
+    #[test]
+    fn test_openai_body_splits_tool_results() {
+        let messages = vec![
+            Message::User {
+                content: "hi".to_string(),
+            },
+            Message::Assistant {
+                text: String::new(),
+                tool_calls: vec![ToolCall {
+                    id: "call_1".to_string(),
+                    name: "search".to_string(),
+                    arguments: json!({ "pattern": "x" }),
+                }],
+            },
+            Message::Tool {
+                results: vec![ToolResultMessage {
+                    tool_call_id: "call_1".to_string(),
+                    name: "search".to_string(),
+                    content: "No matches found".to_string(),
+                    is_error: false,
+                }],
+            },
+        ];
+
+        let body = openai_body("gpt-5", "system", &messages, &[]);
+        let wire = body["messages"].as_array().unwrap();
+        assert_eq!(wire.len(), 4);
+        assert_eq!(wire[2]["content"], Value::Null);
+        assert_eq!(
+            wire[2]["tool_calls"][0]["function"]["arguments"],
+            r#"{"pattern":"x"}"#
+        );
+        assert_eq!(wire[3]["role"], "tool");
+        assert_eq!(wire[3]["tool_call_id"], "call_1");
+    }
+}
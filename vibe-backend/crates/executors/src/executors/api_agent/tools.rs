@@ -0,0 +1,576 @@
+//! Workspace tools offered to the model: read, write, edit, run and search.
+//! Every path is resolved inside the worktree.
+
+use std::{
+    path::{Component, Path, PathBuf},
+    process::Stdio,
+    time::Duration,
+};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use workspace_utils::shell::get_shell_command;
+
+use super::provider::{ToolCall, api_key_env};
+use crate::{env::ExecutionEnv, sse_bridge::AiProvider};
+
+/// Tool output is cut to this many bytes before it is sent back to the model
+const MAX_OUTPUT_BYTES: usize = 30_000;
+const MAX_SEARCH_MATCHES: usize = 200;
+const MAX_SEARCH_FILE_BYTES: u64 = 1024 * 1024;
+const DEFAULT_COMMAND_TIMEOUT_SECS: u64 = 120;
+const MAX_COMMAND_TIMEOUT_SECS: u64 = 600;
+const SKIPPED_DIRS: &[&str] = &[".git", "node_modules", "target", "dist", ".next"];
+
+pub const READ_FILE: &str = "read_file";
+pub const WRITE_FILE: &str = "write_file";
+pub const EDIT_FILE: &str = "edit_file";
+pub const RUN_COMMAND: &str = "run_command";
+pub const SEARCH: &str = "search";
+
+/// A tool as advertised to the provider
+#[derive(Debug, Clone)]
+pub struct ToolSpec {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// JSON schema of the arguments
+    pub parameters: Value,
+}
+
+pub fn tool_specs() -> Vec<ToolSpec> {
+    vec![
+        ToolSpec {
+            name: READ_FILE,
+            description: "Read a text file in the workspace. Optionally read a range of lines.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Path relative to the workspace root" },
+                    "offset": { "type": "integer", "description": "1-based line to start at" },
+                    "limit": { "type": "integer", "description": "Maximum number of lines to read" }
+                },
+                "required": ["path"]
+            }),
+        },
+        ToolSpec {
+            name: WRITE_FILE,
+            description: "Create a file or replace its entire content.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Path relative to the workspace root" },
+                    "content": { "type": "string", "description": "New file content" }
+                },
+                "required": ["path", "content"]
+            }),
+        },
+        ToolSpec {
+            name: EDIT_FILE,
+            description: "Replace one exact occurrence of old_string with new_string in a file. \
+                          old_string must match the file exactly and be unique.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Path relative to the workspace root" },
+                    "old_string": { "type": "string", "description": "Exact text to replace" },
+                    "new_string": { "type": "string", "description": "Replacement text" }
+                },
+                "required": ["path", "old_string", "new_string"]
+            }),
+        },
+        ToolSpec {
+            name: RUN_COMMAND,
+            description: "Run a shell command in the workspace root and return its combined \
+                          stdout and stderr.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "command": { "type": "string", "description": "Shell command to run" },
+                    "timeout_secs": { "type": "integer", "description": "Timeout in seconds, default 120" }
+                },
+                "required": ["command"]
+            }),
+        },
+        ToolSpec {
+            name: SEARCH,
+            description: "Search file contents with a regular expression. Returns matching \
+                          lines as path:line: text.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "pattern": { "type": "string", "description": "Regular expression" },
+                    "path": { "type": "string", "description": "Directory or file to search, default the workspace root" }
+                },
+                "required": ["pattern"]
+            }),
+        },
+    ]
+}
+
+/// Whether the tool changes the workspace and therefore needs approval
+pub fn requires_approval(tool_name: &str) -> bool {
+    matches!(tool_name, WRITE_FILE | EDIT_FILE | RUN_COMMAND)
+}
+
+#[derive(Debug, Deserialize)]
+struct ReadFileArgs {
+    path: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WriteFileArgs {
+    path: String,
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct EditFileArgs {
+    path: String,
+    old_string: String,
+    new_string: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RunCommandArgs {
+    command: String,
+    timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchArgs {
+    pattern: String,
+    path: Option<String>,
+}
+
+/// Result of running a tool, returned to the model as text
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolOutcome {
+    pub content: String,
+    pub is_error: bool,
+    /// Exit code of `run_command`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+}
+
+impl ToolOutcome {
+    fn ok(content: impl Into<String>) -> Self {
+        Self {
+            content: truncate_output(content.into()),
+            is_error: false,
+            exit_code: None,
+        }
+    }
+
+    pub fn error(content: impl Into<String>) -> Self {
+        Self {
+            content: content.into(),
+            is_error: true,
+            exit_code: None,
+        }
+    }
+}
+
+fn provider_key_vars() -> impl Iterator<Item = &'static str> {
+    [
+        AiProvider::Anthropic,
+        AiProvider::OpenAI,
+        AiProvider::Google,
+    ]
+    .into_iter()
+    .map(api_key_env)
+}
+
+/// Runs tools against one worktree
+#[derive(Debug, Clone)]
+pub struct WorkspaceTools {
+    root: PathBuf,
+    env: ExecutionEnv,
+}
+
+impl WorkspaceTools {
+    /// Provider keys are dropped from `env`: they belong to the agent's
+    /// client, not to the commands the model runs
+    pub fn new(root: &Path, mut env: ExecutionEnv) -> Self {
+        env.api_agent_key = None;
+        for name in provider_key_vars() {
+            env.vars.remove(name);
+        }
+        Self {
+            root: root.to_path_buf(),
+            env,
+        }
+    }
+
+    pub async fn execute(&self, call: &ToolCall) -> ToolOutcome {
+        let result = match call.name.as_str() {
+            READ_FILE => match parse_args(&call.arguments) {
+                Ok(args) => self.read_file(args).await,
+                Err(e) => Err(e),
+            },
+            WRITE_FILE => match parse_args(&call.arguments) {
+                Ok(args) => self.write_file(args).await,
+                Err(e) => Err(e),
+            },
+            EDIT_FILE => match parse_args(&call.arguments) {
+                Ok(args) => self.edit_file(args).await,
+                Err(e) => Err(e),
+            },
+            RUN_COMMAND => match parse_args(&call.arguments) {
+                Ok(args) => return self.run_command(args).await,
+                Err(e) => Err(e),
+            },
+            SEARCH => match parse_args(&call.arguments) {
+                Ok(args) => self.search(args).await,
+                Err(e) => Err(e),
+            },
+            other => Err(format!("Unknown tool `{other}`")),
+        };
+
+        result.unwrap_or_else(ToolOutcome::error)
+    }
+
+    /// Resolve a model-supplied path, rejecting anything outside the worktree
+    pub fn resolve_path(&self, path: &str) -> Result<PathBuf, String> {
+        let joined = self.root.join(path);
+        let mut resolved = PathBuf::new();
+        for component in joined.components() {
+            match component {
+                Component::ParentDir => {
+                    resolved.pop();
+                }
+                Component::CurDir => {}
+                other => resolved.push(other),
+            }
+        }
+
+        let inside = |p: &Path| {
+            let root = self
+                .root
+                .canonicalize()
+                .unwrap_or_else(|_| self.root.clone());
+            p.starts_with(&self.root) || p.starts_with(root)
+        };
+        if !inside(&resolved) {
+            return Err(format!("Path `{path}` is outside the workspace"));
+        }
+        // Follow symlinks of existing paths so a link cannot point outside
+        if let Ok(canonical) = resolved.canonicalize()
+            && !inside(&canonical)
+        {
+            return Err(format!("Path `{path}` is outside the workspace"));
+        }
+        Ok(resolved)
+    }
+
+    async fn read_file(&self, args: ReadFileArgs) -> Result<ToolOutcome, String> {
+        let path = self.resolve_path(&args.path)?;
+        let content = tokio::fs::read_to_string(&path)
+            .await
+            .map_err(|e| format!("Failed to read `{}`: {e}", args.path))?;
+
+        if args.offset.is_none() && args.limit.is_none() {
+            return Ok(ToolOutcome::ok(content));
+        }
+        let skip = args.offset.unwrap_or(1).saturating_sub(1);
+        let lines: Vec<&str> = content
+            .lines()
+            .skip(skip)
+            .take(args.limit.unwrap_or(usize::MAX))
+            .collect();
+        Ok(ToolOutcome::ok(lines.join("\n")))
+    }
+
+    async fn write_file(&self, args: WriteFileArgs) -> Result<ToolOutcome, String> {
+        let path = self.resolve_path(&args.path)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("Failed to create directories for `{}`: {e}", args.path))?;
+        }
+        tokio::fs::write(&path, &args.content)
+            .await
+            .map_err(|e| format!("Failed to write `{}`: {e}", args.path))?;
+        Ok(ToolOutcome::ok(format!(
+            "Wrote {} bytes to {}",
+            args.content.len(),
+            args.path
+        )))
+    }
+
+    async fn edit_file(&self, args: EditFileArgs) -> Result<ToolOutcome, String> {
+        let path = self.resolve_path(&args.path)?;
+        let content = tokio::fs::read_to_string(&path)
+            .await
+            .map_err(|e| format!("Failed to read `{}`: {e}", args.path))?;
+
+        if args.old_string.is_empty() {
+            return Err("old_string must not be empty".to_string());
+        }
+        match content.matches(&args.old_string).count() {
+            0 => return Err(format!("old_string was not found in `{}`", args.path)),
+            1 => {}
+            n => {
+                return Err(format!(
+                    "old_string matches {n} times in `{}`; include more context",
+                    args.path
+                ));
+            }
+        }
+
+        let updated = content.replacen(&args.old_string, &args.new_string, 1);
+        tokio::fs::write(&path, updated)
+            .await
+            .map_err(|e| format!("Failed to write `{}`: {e}", args.path))?;
+        Ok(ToolOutcome::ok(format!("Edited {}", args.path)))
+    }
+
+    async fn run_command(&self, args: RunCommandArgs) -> ToolOutcome {
+        let (shell, shell_arg) = get_shell_command();
+        let timeout = Duration::from_secs(
+            args.timeout_secs
+                .unwrap_or(DEFAULT_COMMAND_TIMEOUT_SECS)
+                .clamp(1, MAX_COMMAND_TIMEOUT_SECS),
+        );
+
+        let mut command = match self.env.command(shell).await {
+            Ok(command) => command,
+            Err(e) => return ToolOutcome::error(format!("Failed to run command: {e}")),
+        };
+        command
+            .kill_on_drop(true)
+            .arg(shell_arg)
+            .arg(&args.command)
+            .current_dir(&self.root)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        // Nor may commands inherit a key from the server's own environment
+        for name in provider_key_vars() {
+            command.env_remove(name);
+        }
+        self.env.apply_to_command(&mut command);
+
+        let output = match tokio::time::timeout(timeout, command.output()).await {
+            Ok(Ok(output)) => output,
+            Ok(Err(e)) => return ToolOutcome::error(format!("Failed to run command: {e}")),
+            Err(_) => {
+                return ToolOutcome::error(format!(
+                    "Command timed out after {}s",
+                    timeout.as_secs()
+                ));
+            }
+        };
+
+        let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !stderr.is_empty() {
+            if !text.is_empty() && !text.ends_with('\n') {
+                text.push('\n');
+            }
+            text.push_str(&stderr);
+        }
+
+        let exit_code = output.status.code();
+        ToolOutcome {
+            content: truncate_output(text),
+            is_error: !output.status.success(),
+            exit_code,
+        }
+    }
+
+    async fn search(&self, args: SearchArgs) -> Result<ToolOutcome, String> {
+        let regex =
+            Regex::new(&args.pattern).map_err(|e| format!("Invalid regular expression: {e}"))?;
+        let start = self.resolve_path(args.path.as_deref().unwrap_or("."))?;
+        let root = self.root.clone();
+
+        let matches = tokio::task::spawn_blocking(move || {
+            let mut matches = Vec::new();
+            search_path(&root, &start, &regex, &mut matches);
+            matches
+        })
+        .await
+        .map_err(|e| format!("Search failed: {e}"))?;
+
+        if matches.is_empty() {
+            return Ok(ToolOutcome::ok("No matches found"));
+        }
+        let mut output = matches.join("\n");
+        if matches.len() >= MAX_SEARCH_MATCHES {
+            output.push_str(&format!(
+                "\n... stopped after {MAX_SEARCH_MATCHES} matches; narrow the search"
+            ));
+        }
+        Ok(ToolOutcome::ok(output))
+    }
+}
+
+fn parse_args<T: serde::de::DeserializeOwned>(arguments: &Value) -> Result<T, String> {
+    serde_json::from_value(arguments.clone()).map_err(|e| format!("Invalid arguments: {e}"))
+}
+
+fn search_path(root: &Path, path: &Path, regex: &Regex, matches: &mut Vec<String>) {
+    if matches.len() >= MAX_SEARCH_MATCHES {
+        return;
+    }
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return;
+    };
+
+    if metadata.is_dir() {
+        let skipped = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| SKIPPED_DIRS.contains(&name));
+        if skipped {
+            return;
+        }
+        let Ok(entries) = std::fs::read_dir(path) else {
+            return;
+        };
+        let mut entries: Vec<_> = entries.flatten().map(|entry| entry.path()).collect();
+        entries.sort();
+        for entry in entries {
+            search_path(root, &entry, regex, matches);
+        }
+    } else if metadata.is_file() && metadata.len() <= MAX_SEARCH_FILE_BYTES {
+        let Ok(content) = std::fs::read_to_string(path) else {
+            return;
+        };
+        let relative = path.strip_prefix(root).unwrap_or(path).to_string_lossy();
+        for (line_number, line) in content.lines().enumerate() {
+            if regex.is_match(line) {
+                matches.push(format!(
+                    "{relative}:{}: {}",
+                    line_number + 1,
+                    line.trim_end()
+                ));
+                if matches.len() >= MAX_SEARCH_MATCHES {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+fn truncate_output(mut text: String) -> String {
+    if text.len() <= MAX_OUTPUT_BYTES {
+        return text;
+    }
+    let mut cut = MAX_OUTPUT_BYTES;
+    while !text.is_char_boundary(cut) {
+        cut -= 1;
+    }
+    let omitted = text.len() - cut;
+    text.truncate(cut);
+    text.push_str(&format!("\n... [{omitted} bytes truncated]"));
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(name: &str, arguments: Value) -> ToolCall {
+        ToolCall {
+            id: "call_1".to_string(),
+            name: name.to_string(),
+            arguments,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_edit_requires_unique_match() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "one two two\n").unwrap();
+        let tools = WorkspaceTools::new(dir.path(), ExecutionEnv::new());
+
+        let outcome = tools
+            .execute(&call(
+                EDIT_FILE,
+                json!({ "path": "a.txt", "old_string": "two", "new_string": "three" }),
+            ))
+            .await;
+        assert!(outcome.is_error);
+        assert!(outcome.content.contains("matches 2 times"));
+
+        let outcome = tools
+            .execute(&call(
+                EDIT_FILE,
+                json!({ "path": "a.txt", "old_string": "one", "new_string": "zero" }),
+            ))
+            .await;
+        assert!(!outcome.is_error);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("a.txt")).unwrap(),
+            "zero two two\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_paths_are_confined_to_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let tools = WorkspaceTools::new(dir.path(), ExecutionEnv::new());
+
+        assert!(tools.resolve_path("src/../lib.rs").is_ok());
+        assert!(tools.resolve_path("../outside.txt").is_err());
+        assert!(tools.resolve_path("/etc/passwd").is_err());
+
+        let outcome = tools
+            .execute(&call(READ_FILE, json!({ "path": "../../etc/passwd" })))
+            .await;
+        assert!(outcome.is_error);
+    }
+
+    #[tokio::test]
+    async fn test_search_skips_ignored_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::create_dir_all(dir.path().join("node_modules/pkg")).unwrap();
+        std::fs::write(
+            dir.path().join("src/lib.rs"),
+            "// TODO: fix\nfn main() {}\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("node_modules/pkg/index.js"), "// TODO\n").unwrap();
+        let tools = WorkspaceTools::new(dir.path(), ExecutionEnv::new());
+
+        let outcome = tools
+            .execute(&call(SEARCH, json!({ "pattern": "TODO" })))
+            .await;
+        assert!(!outcome.is_error);
+        assert_eq!(outcome.content, "src/lib.rs:1: // TODO: fix");
+    }
+
+    #[test]
+    fn test_truncate_output_respects_char_boundaries() {
+        let text = "é".repeat(MAX_OUTPUT_BYTES);
+        let truncated = truncate_output(text);
+        assert!(truncated.contains("bytes truncated"));
+        assert!(truncated.len() < MAX_OUTPUT_BYTES + 64);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_commands_do_not_see_provider_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut env = ExecutionEnv::new();
+        env.insert("OPENAI_API_KEY", "sk-profile");
+        env.insert("VK_PROJECT_NAME", "demo");
+        env.api_agent_key = Some("sk-stored".to_string());
+        let tools = WorkspaceTools::new(dir.path(), env);
+
+        let outcome = tools
+            .execute(&call(
+                RUN_COMMAND,
+                json!({ "command": "echo \"[$OPENAI_API_KEY][$VK_PROJECT_NAME]\"" }),
+            ))
+            .await;
+        assert!(!outcome.is_error, "{}", outcome.content);
+        assert!(outcome.content.contains("[][demo]"), "{}", outcome.content);
+        assert!(!outcome.content.contains("sk-"));
+    }
+}
@@ -10,10 +10,12 @@ use command_group::AsyncCommandGroup;
 use futures::StreamExt;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tokio::process::Command;
 use ts_rs::TS;
 use workspace_utils::{
-    approvals::ApprovalStatus, diff::create_unified_diff, log_msg::LogMsg, msg_store::MsgStore,
+    approvals::ApprovalStatus,
+    diff::create_unified_diff,
+    log_msg::{LogMsg, TokenUsage},
+    msg_store::MsgStore,
     path::make_path_relative,
 };
 
@@ -241,7 +243,7 @@ impl ClaudeCode {
         let (program_path, args) = command_parts.into_resolved().await?;
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(program_path);
+        let mut command = env.command(program_path).await?;
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
@@ -332,6 +334,9 @@ pub struct ClaudeLogProcessor {
     strategy: HistoryStrategy,
     streaming_messages: HashMap<String, StreamingMessageState>,
     streaming_message_id: Option<String>,
+    // Usage already reported per assistant message id, and in total
+    message_usage: HashMap<String, TokenUsage>,
+    reported_usage: TokenUsage,
 }
 
 impl ClaudeLogProcessor {
@@ -347,6 +352,48 @@ impl ClaudeLogProcessor {
             strategy,
             streaming_messages: HashMap::new(),
             streaming_message_id: None,
+            message_usage: HashMap::new(),
+            reported_usage: TokenUsage::default(),
+        }
+    }
+
+    /// Usage to report for `claude_json`, as an increment over what earlier
+    /// lines reported. Claude repeats an assistant message's usage on every
+    /// content block, so each message id only adds what grew since it was
+    /// last seen; the result adds whatever its run totals exceed that by.
+    fn usage_increment(&mut self, claude_json: &ClaudeJson) -> Option<TokenUsage> {
+        match claude_json {
+            ClaudeJson::Assistant { message, .. } => {
+                let current = message
+                    .usage
+                    .as_ref()?
+                    .to_token_usage(message.model.clone().or(self.model_name.clone()), None)?;
+                let increment = match &message.id {
+                    Some(id) => {
+                        let seen = self.message_usage.entry(id.clone()).or_default();
+                        let increment = usage_difference(&current, seen);
+                        *seen = usage_max(seen, &current);
+                        increment
+                    }
+                    None => current,
+                };
+                self.reported_usage = usage_sum(&self.reported_usage, &increment);
+                (!increment.is_empty()).then_some(increment)
+            }
+            ClaudeJson::Result {
+                usage: Some(usage),
+                total_cost_usd,
+                ..
+            } => {
+                let total = usage.to_token_usage(self.model_name.clone(), *total_cost_usd)?;
+                let increment = usage_difference(&total, &self.reported_usage);
+                self.reported_usage = usage_max(&self.reported_usage, &total);
+                // The reported cost covers the whole run, so it rides along
+                // even when every token was already counted
+                (!increment.is_empty() || increment.reported_cost_usd.is_some())
+                    .then_some(increment)
+            }
+            _ => None,
         }
     }
 
@@ -368,7 +415,10 @@ impl ClaudeLogProcessor {
             while let Some(Ok(msg)) = stream.next().await {
                 let chunk = match msg {
                     LogMsg::Stdout(x) => x,
-                    LogMsg::JsonPatch(_) | LogMsg::SessionId(_) | LogMsg::Stderr(_) => continue,
+                    LogMsg::JsonPatch(_)
+                    | LogMsg::SessionId(_)
+                    | LogMsg::TokenUsage(_)
+                    | LogMsg::Stderr(_) => continue,
                     LogMsg::Finished => break,
                 };
 
@@ -404,6 +454,10 @@ impl ClaudeLogProcessor {
                                 session_id_extracted = true;
                             }
 
+                            if let Some(token_usage) = processor.usage_increment(&claude_json) {
+                                msg_store.push_token_usage(token_usage);
+                            }
+
                             let patches = processor.normalize_entries(
                                 &claude_json,
                                 &worktree_path,
@@ -1504,6 +1558,11 @@ pub enum ClaudeJson {
         num_turns: Option<u32>,
         #[serde(default, alias = "sessionId")]
         session_id: Option<String>,
+        /// Totals for the whole run
+        #[serde(default)]
+        usage: Option<ClaudeUsage>,
+        #[serde(default, alias = "totalCostUsd")]
+        total_cost_usd: Option<f64>,
     },
     #[serde(rename = "approval_response")]
     ApprovalResponse {
@@ -1528,6 +1587,9 @@ pub struct ClaudeMessage {
     pub model: Option<String>,
     pub content: Vec<ClaudeContentItem>,
     pub stop_reason: Option<String>,
+    /// Usage of this API message so far; repeated on each content block
+    #[serde(default)]
+    pub usage: Option<ClaudeUsage>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
@@ -1614,6 +1676,63 @@ pub struct ClaudeUsage {
     pub service_tier: Option<String>,
 }
 
+impl ClaudeUsage {
+    /// `None` when no tokens were reported
+    pub fn to_token_usage(
+        &self,
+        model: Option<String>,
+        reported_cost_usd: Option<f64>,
+    ) -> Option<TokenUsage> {
+        let usage = TokenUsage {
+            model,
+            input_tokens: self.input_tokens.unwrap_or(0),
+            output_tokens: self.output_tokens.unwrap_or(0),
+            cache_read_tokens: self.cache_read_input_tokens.unwrap_or(0),
+            cache_write_tokens: self.cache_creation_input_tokens.unwrap_or(0),
+            reported_cost_usd,
+        };
+        (!usage.is_empty()).then_some(usage)
+    }
+}
+
+/// Tokens in `current` beyond `seen`, keeping `current`'s model and cost
+fn usage_difference(current: &TokenUsage, seen: &TokenUsage) -> TokenUsage {
+    TokenUsage {
+        model: current.model.clone(),
+        input_tokens: current.input_tokens.saturating_sub(seen.input_tokens),
+        output_tokens: current.output_tokens.saturating_sub(seen.output_tokens),
+        cache_read_tokens: current
+            .cache_read_tokens
+            .saturating_sub(seen.cache_read_tokens),
+        cache_write_tokens: current
+            .cache_write_tokens
+            .saturating_sub(seen.cache_write_tokens),
+        reported_cost_usd: current.reported_cost_usd,
+    }
+}
+
+fn usage_max(a: &TokenUsage, b: &TokenUsage) -> TokenUsage {
+    TokenUsage {
+        model: b.model.clone().or(a.model.clone()),
+        input_tokens: a.input_tokens.max(b.input_tokens),
+        output_tokens: a.output_tokens.max(b.output_tokens),
+        cache_read_tokens: a.cache_read_tokens.max(b.cache_read_tokens),
+        cache_write_tokens: a.cache_write_tokens.max(b.cache_write_tokens),
+        reported_cost_usd: None,
+    }
+}
+
+fn usage_sum(a: &TokenUsage, b: &TokenUsage) -> TokenUsage {
+    TokenUsage {
+        model: b.model.clone().or(a.model.clone()),
+        input_tokens: a.input_tokens + b.input_tokens,
+        output_tokens: a.output_tokens + b.output_tokens,
+        cache_read_tokens: a.cache_read_tokens + b.cache_read_tokens,
+        cache_write_tokens: a.cache_write_tokens + b.cache_write_tokens,
+        reported_cost_usd: None,
+    }
+}
+
 /// Structured tool data for Claude tools based on real samples
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 #[serde(tag = "name", content = "input")]
@@ -1852,6 +1971,82 @@ mod tests {
         normalize_helper(&mut processor, json, worktree)
     }
 
+    #[test]
+    fn test_result_usage_parsing() {
+        let result_json = r#"{"type":"result","subtype":"success","is_error":false,"session_id":"abc123","total_cost_usd":0.0421,"usage":{"input_tokens":12,"cache_creation_input_tokens":2048,"cache_read_input_tokens":30000,"output_tokens":512}}"#;
+        let parsed: ClaudeJson = serde_json::from_str(result_json).unwrap();
+        let ClaudeJson::Result {
+            usage: Some(usage),
+            total_cost_usd,
+            ..
+        } = parsed
+        else {
+            panic!("expected result with usage");
+        };
+
+        let token_usage = usage
+            .to_token_usage(Some("claude-sonnet-4-20250514".to_string()), total_cost_usd)
+            .unwrap();
+        assert_eq!(token_usage.input_tokens, 12);
+        assert_eq!(token_usage.output_tokens, 512);
+        assert_eq!(token_usage.cache_read_tokens, 30000);
+        assert_eq!(token_usage.cache_write_tokens, 2048);
+        assert_eq!(token_usage.reported_cost_usd, Some(0.0421));
+    }
+
+    #[test]
+    fn test_usage_is_reported_per_message_once() {
+        fn assistant(id: &str, input: u64, output: u64) -> ClaudeJson {
+            serde_json::from_value(serde_json::json!({
+                "type": "assistant",
+                "session_id": "abc123",
+                "message": {
+                    "id": id,
+                    "type": "message",
+                    "role": "assistant",
+                    "model": "claude-sonnet-4-20250514",
+                    "content": [{"type": "text", "text": "Working on it"}],
+                    "stop_reason": null,
+                    "usage": {"input_tokens": input, "output_tokens": output}
+                }
+            }))
+            .unwrap()
+        }
+        let tokens = |usage: Option<TokenUsage>| {
+            usage.map(|u| (u.input_tokens, u.output_tokens, u.reported_cost_usd))
+        };
+
+        let mut processor = ClaudeLogProcessor::new();
+        assert_eq!(
+            tokens(processor.usage_increment(&assistant("msg_1", 10, 5))),
+            Some((10, 5, None))
+        );
+        // Same message again for its next content block
+        assert_eq!(
+            tokens(processor.usage_increment(&assistant("msg_1", 10, 5))),
+            None
+        );
+        assert_eq!(
+            tokens(processor.usage_increment(&assistant("msg_1", 10, 7))),
+            Some((0, 2, None))
+        );
+        let usage = processor
+            .usage_increment(&assistant("msg_2", 3, 4))
+            .unwrap();
+        assert_eq!(usage.model.as_deref(), Some("claude-sonnet-4-20250514"));
+        assert_eq!((usage.input_tokens, usage.output_tokens), (3, 4));
+
+        // The result only adds what the messages did not already report
+        let result: ClaudeJson = serde_json::from_str(
+            r#"{"type":"result","subtype":"success","total_cost_usd":0.0421,"usage":{"input_tokens":20,"output_tokens":11}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            tokens(processor.usage_increment(&result)),
+            Some((7, 0, Some(0.0421)))
+        );
+    }
+
     #[test]
     fn test_claude_json_parsing() {
         let system_json =
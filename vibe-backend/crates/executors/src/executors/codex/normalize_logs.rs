@@ -26,8 +26,8 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use workspace_utils::{
-    approvals::ApprovalStatus, diff::normalize_unified_diff, msg_store::MsgStore,
-    path::make_path_relative,
+    approvals::ApprovalStatus, diff::normalize_unified_diff, log_msg::TokenUsage,
+    msg_store::MsgStore, path::make_path_relative,
 };
 
 use crate::{
@@ -216,6 +216,7 @@ struct LogState {
     patches: HashMap<String, PatchState>,
     web_searches: HashMap<String, WebSearchState>,
     token_usage_info: Option<TokenUsageInfo>,
+    model: Option<String>,
 }
 
 enum StreamingTextKind {
@@ -234,9 +235,42 @@ impl LogState {
             patches: HashMap::new(),
             web_searches: HashMap::new(),
             token_usage_info: None,
+            model: None,
         }
     }
 
+    /// Codex reports running totals for the conversation. Emit what was added
+    /// since the previous report; on the first report only the latest request
+    /// counts, since a resumed conversation's totals include earlier runs.
+    fn token_usage_delta(&self, info: &TokenUsageInfo) -> Option<TokenUsage> {
+        let (input, cached, output) = match &self.token_usage_info {
+            Some(previous) => (
+                tokens(info.total_token_usage.input_tokens)
+                    .saturating_sub(tokens(previous.total_token_usage.input_tokens)),
+                tokens(info.total_token_usage.cached_input_tokens)
+                    .saturating_sub(tokens(previous.total_token_usage.cached_input_tokens)),
+                tokens(info.total_token_usage.output_tokens)
+                    .saturating_sub(tokens(previous.total_token_usage.output_tokens)),
+            ),
+            None => (
+                tokens(info.last_token_usage.input_tokens),
+                tokens(info.last_token_usage.cached_input_tokens),
+                tokens(info.last_token_usage.output_tokens),
+            ),
+        };
+
+        // Codex counts cached tokens as part of the input
+        let usage = TokenUsage {
+            model: self.model.clone(),
+            input_tokens: input.saturating_sub(cached),
+            output_tokens: output,
+            cache_read_tokens: cached,
+            cache_write_tokens: 0,
+            reported_cost_usd: None,
+        };
+        (!usage.is_empty()).then_some(usage)
+    }
+
     fn streaming_text_update(
         &mut self,
         content: String,
@@ -389,6 +423,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                     server_notification
                 {
                     msg_store.push_session_id(session_configured.session_id.to_string());
+                    state.model = Some(session_configured.model.clone());
                     handle_model_params(
                         session_configured.model,
                         session_configured.reasoning_effort,
@@ -427,6 +462,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
             match event {
                 EventMsg::SessionConfigured(payload) => {
                     msg_store.push_session_id(payload.session_id.to_string());
+                    state.model = Some(payload.model.clone());
                     handle_model_params(
                         payload.model,
                         payload.reasoning_effort,
@@ -971,6 +1007,9 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                 }
                 EventMsg::TokenCount(payload) => {
                     if let Some(info) = payload.info {
+                        if let Some(usage) = state.token_usage_delta(&info) {
+                            msg_store.push_token_usage(usage);
+                        }
                         state.token_usage_info = Some(info);
                     }
                 }
@@ -1064,6 +1103,11 @@ fn handle_model_params(
     );
 }
 
+/// Token counters are signed in some protocol versions
+fn tokens<T: TryInto<u64>>(value: T) -> u64 {
+    value.try_into().unwrap_or(0)
+}
+
 fn build_command_output(stdout: Option<&str>, stderr: Option<&str>) -> Option<String> {
     let mut sections = Vec::new();
     if let Some(out) = stdout {
@@ -14,7 +14,6 @@ use serde::{Deserialize, Serialize};
 use tokio::{
     fs,
     io::AsyncWriteExt,
-    process::Command,
     time::{interval, timeout},
 };
 use ts_rs::TS;
@@ -112,7 +111,7 @@ impl StandardCodingAgentExecutor for Copilot {
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(program_path);
+        let mut command = env.command(program_path).await?;
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
@@ -155,7 +154,7 @@ impl StandardCodingAgentExecutor for Copilot {
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(program_path);
+        let mut command = env.command(program_path).await?;
 
         command
             .kill_on_drop(true)
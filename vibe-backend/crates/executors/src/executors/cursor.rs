@@ -6,7 +6,7 @@ use command_group::AsyncCommandGroup;
 use futures::StreamExt;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tokio::{io::AsyncWriteExt, process::Command};
+use tokio::io::AsyncWriteExt;
 use ts_rs::TS;
 use workspace_utils::{
     diff::{create_unified_diff, normalize_unified_diff},
@@ -25,6 +25,7 @@ use crate::{
         ActionType, FileChange, NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
         TodoItem, ToolStatus,
         plain_text_processor::PlainTextLogProcessor,
+        usage::ReportedUsage,
         utils::{ConversationPatch, EntryIndexProvider},
     },
 };
@@ -83,7 +84,7 @@ impl StandardCodingAgentExecutor for CursorAgent {
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(executable_path);
+        let mut command = env.command(executable_path).await?;
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
@@ -122,7 +123,7 @@ impl StandardCodingAgentExecutor for CursorAgent {
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(executable_path);
+        let mut command = env.command(executable_path).await?;
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
@@ -200,6 +201,7 @@ impl StandardCodingAgentExecutor for CursorAgent {
 
             // Assistant streaming coalescer state
             let mut model_reported = false;
+            let mut model_name: Option<String> = None;
             let mut session_id_reported = false;
 
             let mut current_assistant_message_buffer = String::new();
@@ -255,6 +257,9 @@ impl StandardCodingAgentExecutor for CursorAgent {
 
                 match &cursor_json {
                     CursorJson::System { model, .. } => {
+                        if model_name.is_none() {
+                            model_name = model.clone();
+                        }
                         if !model_reported && let Some(model) = model.as_ref() {
                             let entry = NormalizedEntry {
                                 timestamp: None,
@@ -465,8 +470,14 @@ impl StandardCodingAgentExecutor for CursorAgent {
                         }
                     }
 
-                    CursorJson::Result { .. } => {
-                        // no-op; metadata-only events not surfaced
+                    CursorJson::Result { usage, .. } => {
+                        // Only token usage is kept; the rest is metadata not surfaced
+                        if let Some(usage) = usage
+                            .clone()
+                            .and_then(|usage| usage.into_token_usage(model_name.clone()))
+                        {
+                            msg_store.push_token_usage(usage);
+                        }
                     }
 
                     CursorJson::Unknown => {
@@ -570,6 +581,8 @@ pub enum CursorJson {
         #[serde(default)]
         result: Option<serde_json::Value>,
         #[serde(default)]
+        usage: Option<ReportedUsage>,
+        #[serde(default)]
         session_id: Option<String>,
     },
     #[serde(other)]
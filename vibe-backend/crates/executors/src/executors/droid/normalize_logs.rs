@@ -15,6 +15,7 @@ use crate::logs::{
     ActionType, CommandExitStatus, CommandRunResult, FileChange, NormalizedEntry,
     NormalizedEntryError, NormalizedEntryType, TodoItem, ToolResult, ToolStatus,
     plain_text_processor::PlainTextLogProcessor,
+    usage::ReportedUsage,
     utils::{
         EntryIndexProvider,
         patch::{add_normalized_entry, replace_normalized_entry},
@@ -83,6 +84,9 @@ pub fn normalize_logs(
             // Normalize JSON logs
             match droid_json {
                 DroidJson::System { model, .. } => {
+                    if state.model.is_none() {
+                        state.model = model.clone();
+                    }
                     if !state.model_reported
                         && let Some(model) = model
                     {
@@ -641,7 +645,15 @@ pub fn normalize_logs(
                     }
                 }
 
-                DroidJson::Completion { final_text, .. } => {
+                DroidJson::Completion {
+                    final_text, usage, ..
+                } => {
+                    if let Some(usage) =
+                        usage.and_then(|usage| usage.into_token_usage(state.model.clone()))
+                    {
+                        msg_store.push_token_usage(usage);
+                    }
+
                     let entry = NormalizedEntry {
                         timestamp: None,
                         entry_type: NormalizedEntryType::AssistantMessage,
@@ -860,6 +872,8 @@ pub enum DroidJson {
         duration_ms: Option<u64>,
         #[serde(default)]
         timestamp: Option<u64>,
+        #[serde(default)]
+        usage: Option<ReportedUsage>,
         session_id: String,
     },
 }
@@ -1236,6 +1250,7 @@ struct ToolCallStates {
     generic_tools: HashMap<String, GenericToolState>,
     pending_fifo: VecDeque<PendingToolCall>,
     model_reported: bool,
+    model: Option<String>,
 }
 
 impl ToolCallStates {
@@ -1251,6 +1266,7 @@ impl ToolCallStates {
             generic_tools: HashMap::new(),
             pending_fifo: VecDeque::new(),
             model_reported: false,
+            model: None,
         }
     }
 }
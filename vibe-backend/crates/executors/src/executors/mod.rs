@@ -18,14 +18,16 @@ use crate::{
     command::CommandBuildError,
     env::ExecutionEnv,
     executors::{
-        amp::Amp, claude::ClaudeCode, claude_github::Claude, codex::Codex, copilot::Copilot,
-        cursor::CursorAgent, droid::Droid, gemini::Gemini, opencode::Opencode, qwen::QwenCode,
+        amp::Amp, api_agent::ApiAgent, claude::ClaudeCode, claude_github::Claude, codex::Codex,
+        copilot::Copilot, cursor::CursorAgent, droid::Droid, gemini::Gemini, opencode::Opencode,
+        qwen::QwenCode,
     },
     mcp_config::McpConfig,
 };
 
 pub mod acp;
 pub mod amp;
+pub mod api_agent;
 pub mod claude;
 pub mod claude_github;
 pub mod codex;
@@ -71,6 +73,8 @@ pub enum ExecutorError {
     SetupHelperNotSupported,
     #[error("Auth required: {0}")]
     AuthRequired(String),
+    #[error("Sandboxed execution is only supported on Linux")]
+    SandboxUnsupported,
 }
 
 #[enum_dispatch]
@@ -106,6 +110,8 @@ pub enum CodingAgent {
     #[strum_discriminants(serde(alias = "CLAUDE"))]
     #[strum_discriminants(strum(serialize = "CLAUDE", serialize = "CLAUDE_ACTION"))]
     Claude,
+    /// Calls provider streaming APIs directly and runs its own tool loop
+    ApiAgent,
 }
 
 impl CodingAgent {
@@ -166,7 +172,8 @@ impl CodingAgent {
             | Self::Gemini(_)
             | Self::QwenCode(_)
             | Self::Droid(_)
-            | Self::Opencode(_) => vec![BaseAgentCapability::SessionFork],
+            | Self::Opencode(_)
+            | Self::ApiAgent(_) => vec![BaseAgentCapability::SessionFork],
             Self::Codex(_) => vec![
                 BaseAgentCapability::SessionFork,
                 BaseAgentCapability::SetupHelper,
@@ -297,6 +304,7 @@ impl BaseCodingAgent {
             BaseCodingAgent::CursorAgent => None,
             BaseCodingAgent::Copilot => None, // GitHub-specific auth
             BaseCodingAgent::Claude => None,  // GitHub-specific (IKA-171)
+            BaseCodingAgent::ApiAgent => None, // Provider is chosen per profile
         }
     }
 }
@@ -6,5 +6,7 @@ pub mod executors;
 pub mod logs;
 pub mod mcp_config;
 pub mod profile;
+pub mod sandbox;
+pub mod sse_bridge;
 pub mod stdout_dup;
 pub mod storage;
@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+use workspace_utils::log_msg::TokenUsage;
+
+/// Token counters as printed by agent CLIs. Agents disagree on field names,
+/// so the common snake_case and camelCase spellings are all accepted.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReportedUsage {
+    #[serde(
+        default,
+        alias = "inputTokens",
+        alias = "prompt_tokens",
+        alias = "promptTokens"
+    )]
+    pub input_tokens: Option<u64>,
+    #[serde(
+        default,
+        alias = "outputTokens",
+        alias = "completion_tokens",
+        alias = "completionTokens"
+    )]
+    pub output_tokens: Option<u64>,
+    #[serde(
+        default,
+        alias = "cache_read_input_tokens",
+        alias = "cacheReadInputTokens",
+        alias = "cacheReadTokens",
+        alias = "cached_input_tokens",
+        alias = "cachedInputTokens"
+    )]
+    pub cache_read_tokens: Option<u64>,
+    #[serde(
+        default,
+        alias = "cache_creation_input_tokens",
+        alias = "cacheCreationInputTokens",
+        alias = "cacheWriteTokens"
+    )]
+    pub cache_write_tokens: Option<u64>,
+}
+
+impl ReportedUsage {
+    /// Look for a `usage` object at the top level or under `_meta`
+    pub fn find_in(value: &serde_json::Value) -> Option<Self> {
+        [value.get("usage"), value.pointer("/_meta/usage")]
+            .into_iter()
+            .flatten()
+            .find_map(|usage| serde_json::from_value(usage.clone()).ok())
+    }
+
+    /// `None` when the agent reported no tokens at all
+    pub fn into_token_usage(self, model: Option<String>) -> Option<TokenUsage> {
+        let usage = TokenUsage {
+            model,
+            input_tokens: self.input_tokens.unwrap_or(0),
+            output_tokens: self.output_tokens.unwrap_or(0),
+            cache_read_tokens: self.cache_read_tokens.unwrap_or(0),
+            cache_write_tokens: self.cache_write_tokens.unwrap_or(0),
+            reported_cost_usd: None,
+        };
+        (!usage.is_empty()).then_some(usage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accepts_camel_case_under_meta() {
+        let value = serde_json::json!({
+            "stopReason": "end_turn",
+            "_meta": {
+                "usage": { "inputTokens": 120, "outputTokens": 30, "cacheReadTokens": 900 }
+            }
+        });
+        let usage = ReportedUsage::find_in(&value)
+            .and_then(|usage| usage.into_token_usage(Some("gemini-2.5-pro".to_string())))
+            .unwrap();
+        assert_eq!(usage.input_tokens, 120);
+        assert_eq!(usage.output_tokens, 30);
+        assert_eq!(usage.cache_read_tokens, 900);
+        assert_eq!(usage.cache_write_tokens, 0);
+    }
+
+    #[test]
+    fn test_empty_usage_is_dropped() {
+        let value = serde_json::json!({ "usage": { "input_tokens": 0 } });
+        assert!(
+            ReportedUsage::find_in(&value)
+                .and_then(|usage| usage.into_token_usage(None))
+                .is_none()
+        );
+        assert!(ReportedUsage::find_in(&serde_json::json!({ "result": "ok" })).is_none());
+    }
+}
@@ -0,0 +1,259 @@
+//! Linux namespace sandbox for executor processes
+//!
+//! Programs are wrapped in bubblewrap: the host filesystem is mounted
+//! read-only and only the workspace (plus any extra paths, such as agent
+//! state directories) is bind-mounted read-write; parts of those can be
+//! mounted read-only again. Network access can be
+//! removed with a fresh network namespace, and CPU/memory limits are applied
+//! through a transient systemd scope.
+
+use std::{
+    ffi::{OsStr, OsString},
+    path::PathBuf,
+};
+
+use tokio::process::Command;
+use workspace_utils::shell::resolve_executable_path;
+
+use crate::executors::ExecutorError;
+
+/// Host variables passed into the sandbox even with an env allowlist; agents
+/// and shells do not work without them
+const ALWAYS_PASSED_ENV: &[&str] = &[
+    "PATH", "HOME", "USER", "LOGNAME", "LANG", "LC_ALL", "TERM", "SHELL", "TMPDIR",
+];
+
+/// Error output that only appears when a sandboxed process hits one of the
+/// sandbox's restrictions
+const VIOLATION_MARKERS: &[&str] = &[
+    // Write outside the writable paths
+    "Read-only file system",
+    // Network access without a network namespace route
+    "Network is unreachable",
+    // bubblewrap itself refused to start the program
+    "bwrap:",
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SandboxPolicy {
+    /// Bind-mounted read-write; the rest of the filesystem is read-only.
+    /// Paths that do not exist are skipped.
+    pub writable_paths: Vec<PathBuf>,
+    /// Mounted read-only over the writable paths, for parts of them the
+    /// program must not change. Paths that do not exist are skipped.
+    pub read_only_paths: Vec<PathBuf>,
+    pub allow_network: bool,
+    pub memory_limit_mb: Option<u32>,
+    /// Share of one CPU, e.g. 200 for two cores
+    pub cpu_limit_percent: Option<u32>,
+    /// Host variables passed into the sandbox; `None` passes the whole
+    /// environment. Variables set explicitly for the execution are always
+    /// passed.
+    pub env_allowlist: Option<Vec<String>>,
+}
+
+impl SandboxPolicy {
+    /// bubblewrap arguments placed before the sandboxed program
+    pub fn bwrap_args(&self) -> Vec<OsString> {
+        let mut args: Vec<OsString> = [
+            "--die-with-parent",
+            "--unshare-pid",
+            "--ro-bind",
+            "/",
+            "/",
+            "--dev",
+            "/dev",
+            "--proc",
+            "/proc",
+            // Mounted before the writable paths so a workspace under /tmp
+            // stays visible
+            "--tmpfs",
+            "/tmp",
+        ]
+        .into_iter()
+        .map(OsString::from)
+        .collect();
+        if !self.allow_network {
+            args.push("--unshare-net".into());
+        }
+        for path in self.writable_paths.iter().filter(|path| path.exists()) {
+            args.push("--bind".into());
+            args.push(path.into());
+            args.push(path.into());
+        }
+        // Later mounts win, so these go after the writable paths
+        for path in self.read_only_paths.iter().filter(|path| path.exists()) {
+            args.push("--ro-bind".into());
+            args.push(path.into());
+            args.push(path.into());
+        }
+        args
+    }
+
+    /// `systemd-run` arguments placed before bubblewrap, `None` when no
+    /// resource limit is set
+    pub fn scope_args(&self) -> Option<Vec<String>> {
+        if self.memory_limit_mb.is_none() && self.cpu_limit_percent.is_none() {
+            return None;
+        }
+        let mut args = ["--user", "--scope", "--quiet", "--collect"]
+            .map(String::from)
+            .to_vec();
+        if let Some(memory_limit_mb) = self.memory_limit_mb {
+            args.push("-p".to_string());
+            args.push(format!("MemoryMax={memory_limit_mb}M"));
+        }
+        if let Some(cpu_limit_percent) = self.cpu_limit_percent {
+            args.push("-p".to_string());
+            args.push(format!("CPUQuota={cpu_limit_percent}%"));
+        }
+        Some(args)
+    }
+
+    /// Whether the host variable `key` is passed into the sandbox
+    pub fn passes_env(&self, key: &str) -> bool {
+        match &self.env_allowlist {
+            None => true,
+            Some(allowlist) => {
+                ALWAYS_PASSED_ENV.contains(&key) || allowlist.iter().any(|allowed| allowed == key)
+            }
+        }
+    }
+
+    /// A command running `program` inside the sandbox. Arguments added to the
+    /// returned command are passed to `program`.
+    pub async fn command(&self, program: impl AsRef<OsStr>) -> Result<Command, ExecutorError> {
+        if !cfg!(target_os = "linux") {
+            return Err(ExecutorError::SandboxUnsupported);
+        }
+
+        let bwrap = resolve_executable_path("bwrap").await.ok_or_else(|| {
+            ExecutorError::ExecutableNotFound {
+                program: "bwrap".to_string(),
+            }
+        })?;
+        let mut command = match self.scope_args() {
+            Some(scope_args) => {
+                let systemd_run =
+                    resolve_executable_path("systemd-run")
+                        .await
+                        .ok_or_else(|| ExecutorError::ExecutableNotFound {
+                            program: "systemd-run".to_string(),
+                        })?;
+                let mut command = Command::new(systemd_run);
+                command.args(scope_args).arg(bwrap);
+                command
+            }
+            None => Command::new(bwrap),
+        };
+        command.args(self.bwrap_args()).arg("--").arg(program);
+
+        if self.env_allowlist.is_some() {
+            command.env_clear();
+            command.envs(
+                std::env::vars_os()
+                    .filter(|(key, _)| key.to_str().is_some_and(|key| self.passes_env(key))),
+            );
+        }
+        Ok(command)
+    }
+}
+
+/// Whether executor error output reports a sandbox restriction
+pub fn is_sandbox_violation(content: &str) -> bool {
+    VIOLATION_MARKERS
+        .iter()
+        .any(|marker| content.contains(marker))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bwrap_args_bind_existing_writable_paths() {
+        let workspace = std::env::temp_dir();
+        let policy = SandboxPolicy {
+            writable_paths: vec![workspace.clone(), PathBuf::from("/does/not/exist")],
+            ..Default::default()
+        };
+        let args = policy.bwrap_args();
+
+        assert!(args.contains(&OsString::from("--unshare-net")));
+        let bind = args.iter().position(|arg| arg == "--bind").unwrap();
+        assert_eq!(args[bind + 1], workspace.into_os_string());
+        assert_eq!(args.iter().filter(|arg| *arg == "--bind").count(), 1);
+        // The writable workspace is mounted over the /tmp tmpfs
+        let tmpfs = args.iter().position(|arg| arg == "--tmpfs").unwrap();
+        assert!(tmpfs < bind);
+
+        let policy = SandboxPolicy {
+            allow_network: true,
+            ..Default::default()
+        };
+        assert!(
+            !policy
+                .bwrap_args()
+                .contains(&OsString::from("--unshare-net"))
+        );
+    }
+
+    #[test]
+    fn bwrap_args_remount_read_only_paths_after_writable_ones() {
+        let git_dir = std::env::temp_dir();
+        let hooks = git_dir.join("vk-sandbox-test-hooks");
+        std::fs::create_dir_all(&hooks).unwrap();
+        let policy = SandboxPolicy {
+            writable_paths: vec![git_dir.clone()],
+            read_only_paths: vec![hooks.clone(), PathBuf::from("/does/not/exist")],
+            ..Default::default()
+        };
+        let args = policy.bwrap_args();
+        std::fs::remove_dir(&hooks).unwrap();
+
+        let bind = args.iter().position(|arg| arg == "--bind").unwrap();
+        let ro_bind = args.iter().rposition(|arg| arg == "--ro-bind").unwrap();
+        assert!(bind < ro_bind);
+        assert_eq!(args[ro_bind + 1], hooks.into_os_string());
+        // The root and the hooks directory; the missing path is skipped
+        assert_eq!(args.iter().filter(|arg| *arg == "--ro-bind").count(), 2);
+    }
+
+    #[test]
+    fn scope_args_only_with_limits() {
+        assert_eq!(SandboxPolicy::default().scope_args(), None);
+
+        let policy = SandboxPolicy {
+            memory_limit_mb: Some(2048),
+            cpu_limit_percent: Some(150),
+            ..Default::default()
+        };
+        let args = policy.scope_args().unwrap();
+        assert!(args.contains(&"MemoryMax=2048M".to_string()));
+        assert!(args.contains(&"CPUQuota=150%".to_string()));
+    }
+
+    #[test]
+    fn env_allowlist_keeps_essentials() {
+        assert!(SandboxPolicy::default().passes_env("AWS_SECRET_ACCESS_KEY"));
+
+        let policy = SandboxPolicy {
+            env_allowlist: Some(vec!["ANTHROPIC_API_KEY".to_string()]),
+            ..Default::default()
+        };
+        assert!(policy.passes_env("ANTHROPIC_API_KEY"));
+        assert!(policy.passes_env("PATH"));
+        assert!(!policy.passes_env("AWS_SECRET_ACCESS_KEY"));
+    }
+
+    #[test]
+    fn detects_violations() {
+        assert!(is_sandbox_violation(
+            "EROFS: Read-only file system, open '/home/me/.bashrc'"
+        ));
+        assert!(is_sandbox_violation(
+            "bwrap: Can't bind mount /oldroot/work: No such file"
+        ));
+        assert!(!is_sandbox_violation("error: could not compile `server`"));
+    }
+}
@@ -0,0 +1,836 @@
+//! SSE to LogMsg Bridge (IKA-51)
+//!
+//! Converts Server-Sent Events from AI provider APIs to LogMsg format
+//! for integration with the existing WebSocket streaming system, or to
+//! structured [`StreamDelta`]s (text, tool calls, usage) for the API-mode
+//! coding agent.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+use ts_rs::TS;
+use workspace_utils::log_msg::LogMsg;
+
+use crate::logs::usage::ReportedUsage;
+
+/// Supported AI providers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AiProvider {
+    Anthropic,
+    Google,
+    OpenAI,
+}
+
+impl std::fmt::Display for AiProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AiProvider::Anthropic => write!(f, "anthropic"),
+            AiProvider::Google => write!(f, "google"),
+            AiProvider::OpenAI => write!(f, "openai"),
+        }
+    }
+}
+
+/// SSE event representation
+#[derive(Debug, Clone)]
+pub struct SseEvent {
+    /// Event type (optional, may be empty)
+    pub event: Option<String>,
+    /// Event data (JSON string)
+    pub data: String,
+}
+
+impl SseEvent {
+    pub fn new(data: impl Into<String>) -> Self {
+        Self {
+            event: None,
+            data: data.into(),
+        }
+    }
+
+    pub fn with_event(mut self, event: impl Into<String>) -> Self {
+        self.event = Some(event.into());
+        self
+    }
+}
+
+/// Incremental SSE parser: feed it raw response bytes and it yields complete
+/// events. Lines may be split across chunks.
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    buffer: Vec<u8>,
+    event: Option<String>,
+    data: Vec<String>,
+}
+
+impl SseDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Consume a chunk of the response body, returning every event it completes
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = Vec::new();
+
+        while let Some(pos) = self.buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\r', '\n']);
+            if let Some(event) = self.process_line(line) {
+                events.push(event);
+            }
+        }
+
+        events
+    }
+
+    /// Flush an event left unterminated at the end of the stream
+    pub fn finish(&mut self) -> Option<SseEvent> {
+        if !self.buffer.is_empty() {
+            let rest = std::mem::take(&mut self.buffer);
+            let line = String::from_utf8_lossy(&rest).trim_end().to_string();
+            self.process_line(&line);
+        }
+        self.dispatch()
+    }
+
+    fn process_line(&mut self, line: &str) -> Option<SseEvent> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        if line.starts_with(':') {
+            // Comment / keep-alive
+            return None;
+        }
+
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => self.event = Some(value.to_string()),
+            "data" => self.data.push(value.to_string()),
+            _ => {}
+        }
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<SseEvent> {
+        let event = self.event.take();
+        if self.data.is_empty() {
+            return None;
+        }
+        let data = std::mem::take(&mut self.data).join("\n");
+        Some(SseEvent { event, data })
+    }
+}
+
+/// A structured piece of a streamed model response
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamDelta {
+    /// Provider message or response id
+    MessageId(String),
+    Text(String),
+    /// Start of a tool call whose arguments arrive as `ToolArguments` fragments
+    ToolCallStart {
+        index: usize,
+        id: String,
+        name: String,
+    },
+    /// Fragment of a tool call's JSON arguments
+    ToolArguments {
+        index: usize,
+        fragment: String,
+    },
+    /// A tool call delivered in one piece (Gemini); Gemini may omit the id
+    ToolCall {
+        id: Option<String>,
+        name: String,
+        arguments: Value,
+    },
+    /// Token counts so far; later values replace earlier ones
+    Usage(ReportedUsage),
+    /// Why the model stopped, e.g. `end_turn`, `tool_use`, `tool_calls`, `STOP`
+    Stop(String),
+    Error(String),
+}
+
+/// Errors from SSE bridge operations
+#[derive(Debug, Error)]
+pub enum SseBridgeError {
+    #[error("Failed to parse SSE data: {0}")]
+    ParseError(String),
+
+    #[error("JSON parsing error: {0}")]
+    JsonError(#[from] serde_json::Error),
+
+    #[error("Unknown event type: {0}")]
+    UnknownEvent(String),
+
+    #[error("Stream error: {0}")]
+    StreamError(String),
+}
+
+/// SSE to LogMsg bridge
+///
+/// Converts provider-specific SSE events to standardized LogMsg format
+pub struct SseBridge {
+    provider: AiProvider,
+}
+
+impl SseBridge {
+    pub fn new(provider: AiProvider) -> Self {
+        Self { provider }
+    }
+
+    /// Parse an SSE event and convert to LogMsg
+    ///
+    /// Returns None for events that should be ignored (e.g., keep-alive)
+    pub fn parse_event(&self, event: &SseEvent) -> Result<Option<LogMsg>, SseBridgeError> {
+        match self.provider {
+            AiProvider::Anthropic => self.parse_anthropic(event),
+            AiProvider::Google => self.parse_google(event),
+            AiProvider::OpenAI => self.parse_openai(event),
+        }
+    }
+
+    /// Parse an SSE event into structured deltas
+    ///
+    /// Unlike [`SseBridge::parse_event`] this keeps tool calls and usage, and
+    /// returns every delta carried by the event.
+    pub fn parse_deltas(&self, event: &SseEvent) -> Result<Vec<StreamDelta>, SseBridgeError> {
+        let data = event.data.trim();
+        if data.is_empty() || data == "[DONE]" || event.event.as_deref() == Some("ping") {
+            return Ok(Vec::new());
+        }
+        let value: Value = serde_json::from_str(data)?;
+
+        Ok(match self.provider {
+            AiProvider::Anthropic => Self::anthropic_deltas(event.event.as_deref(), &value),
+            AiProvider::Google => Self::google_deltas(&value),
+            AiProvider::OpenAI => Self::openai_deltas(&value),
+        })
+    }
+
+    fn anthropic_deltas(event_type: Option<&str>, value: &Value) -> Vec<StreamDelta> {
+        let event_type = event_type
+            .or_else(|| value.get("type").and_then(Value::as_str))
+            .unwrap_or("");
+        let index = value.get("index").and_then(Value::as_u64).unwrap_or(0) as usize;
+        let mut deltas = Vec::new();
+
+        match event_type {
+            "message_start" => {
+                let message = value.get("message");
+                if let Some(id) = message.and_then(|m| m.get("id")).and_then(Value::as_str) {
+                    deltas.push(StreamDelta::MessageId(id.to_string()));
+                }
+                if let Some(usage) = message.and_then(|m| m.get("usage")) {
+                    deltas.extend(Self::usage_delta(usage));
+                }
+            }
+            "content_block_start" => {
+                let block = value.get("content_block");
+                match block.and_then(|b| b.get("type")).and_then(Value::as_str) {
+                    Some("tool_use") => {
+                        let field = |name: &str| {
+                            block
+                                .and_then(|b| b.get(name))
+                                .and_then(Value::as_str)
+                                .unwrap_or_default()
+                                .to_string()
+                        };
+                        deltas.push(StreamDelta::ToolCallStart {
+                            index,
+                            id: field("id"),
+                            name: field("name"),
+                        });
+                    }
+                    Some("text") => {
+                        if let Some(text) = block
+                            .and_then(|b| b.get("text"))
+                            .and_then(Value::as_str)
+                            .filter(|t| !t.is_empty())
+                        {
+                            deltas.push(StreamDelta::Text(text.to_string()));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            "content_block_delta" => {
+                let delta = value.get("delta");
+                if let Some(text) = delta.and_then(|d| d.get("text")).and_then(Value::as_str) {
+                    deltas.push(StreamDelta::Text(text.to_string()));
+                } else if let Some(fragment) = delta
+                    .and_then(|d| d.get("partial_json"))
+                    .and_then(Value::as_str)
+                {
+                    deltas.push(StreamDelta::ToolArguments {
+                        index,
+                        fragment: fragment.to_string(),
+                    });
+                }
+            }
+            "message_delta" => {
+                if let Some(usage) = value.get("usage") {
+                    deltas.extend(Self::usage_delta(usage));
+                }
+                if let Some(reason) = value
+                    .get("delta")
+                    .and_then(|d| d.get("stop_reason"))
+                    .and_then(Value::as_str)
+                {
+                    deltas.push(StreamDelta::Stop(reason.to_string()));
+                }
+            }
+            "error" => {
+                let message = value
+                    .pointer("/error/message")
+                    .and_then(Value::as_str)
+                    .unwrap_or("Unknown error");
+                deltas.push(StreamDelta::Error(format!("Anthropic error: {message}")));
+            }
+            _ => {}
+        }
+
+        deltas
+    }
+
+    fn google_deltas(value: &Value) -> Vec<StreamDelta> {
+        let mut deltas = Vec::new();
+
+        if let Some(message) = value.pointer("/error/message").and_then(Value::as_str) {
+            deltas.push(StreamDelta::Error(format!("Gemini error: {message}")));
+            return deltas;
+        }
+        if let Some(reason) = value
+            .pointer("/promptFeedback/blockReason")
+            .and_then(Value::as_str)
+        {
+            deltas.push(StreamDelta::Error(format!("Gemini blocked: {reason}")));
+            return deltas;
+        }
+        if let Some(id) = value.get("responseId").and_then(Value::as_str) {
+            deltas.push(StreamDelta::MessageId(id.to_string()));
+        }
+
+        let candidate = value
+            .get("candidates")
+            .and_then(Value::as_array)
+            .and_then(|c| c.first());
+        if let Some(parts) = candidate
+            .and_then(|c| c.pointer("/content/parts"))
+            .and_then(Value::as_array)
+        {
+            for part in parts {
+                if let Some(call) = part.get("functionCall") {
+                    deltas.push(StreamDelta::ToolCall {
+                        id: call.get("id").and_then(Value::as_str).map(str::to_string),
+                        name: call
+                            .get("name")
+                            .and_then(Value::as_str)
+                            .unwrap_or_default()
+                            .to_string(),
+                        arguments: call.get("args").cloned().unwrap_or(Value::Null),
+                    });
+                } else if let Some(text) = part.get("text").and_then(Value::as_str)
+                    && !part
+                        .get("thought")
+                        .and_then(Value::as_bool)
+                        .unwrap_or(false)
+                {
+                    deltas.push(StreamDelta::Text(text.to_string()));
+                }
+            }
+        }
+
+        if let Some(usage) = value.get("usageMetadata") {
+            let count = |name: &str| usage.get(name).and_then(Value::as_u64);
+            let cached = count("cachedContentTokenCount");
+            deltas.push(StreamDelta::Usage(ReportedUsage {
+                input_tokens: count("promptTokenCount")
+                    .map(|prompt| prompt.saturating_sub(cached.unwrap_or(0))),
+                output_tokens: count("candidatesTokenCount"),
+                cache_read_tokens: cached,
+                cache_write_tokens: None,
+            }));
+        }
+        if let Some(reason) = candidate
+            .and_then(|c| c.get("finishReason"))
+            .and_then(Value::as_str)
+        {
+            deltas.push(StreamDelta::Stop(reason.to_string()));
+        }
+
+        deltas
+    }
+
+    fn openai_deltas(value: &Value) -> Vec<StreamDelta> {
+        let mut deltas = Vec::new();
+
+        if let Some(error) = value.get("error") {
+            let message = error
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or("Unknown error");
+            deltas.push(StreamDelta::Error(format!("OpenAI error: {message}")));
+            return deltas;
+        }
+        if let Some(id) = value.get("id").and_then(Value::as_str) {
+            deltas.push(StreamDelta::MessageId(id.to_string()));
+        }
+
+        for choice in value
+            .get("choices")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let delta = choice.get("delta");
+            if let Some(text) = delta
+                .and_then(|d| d.get("content"))
+                .and_then(Value::as_str)
+                .filter(|t| !t.is_empty())
+            {
+                deltas.push(StreamDelta::Text(text.to_string()));
+            }
+            for call in delta
+                .and_then(|d| d.get("tool_calls"))
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                let index = call.get("index").and_then(Value::as_u64).unwrap_or(0) as usize;
+                if let Some(id) = call.get("id").and_then(Value::as_str) {
+                    deltas.push(StreamDelta::ToolCallStart {
+                        index,
+                        id: id.to_string(),
+                        name: call
+                            .pointer("/function/name")
+                            .and_then(Value::as_str)
+                            .unwrap_or_default()
+                            .to_string(),
+                    });
+                }
+                if let Some(fragment) = call
+                    .pointer("/function/arguments")
+                    .and_then(Value::as_str)
+                    .filter(|f| !f.is_empty())
+                {
+                    deltas.push(StreamDelta::ToolArguments {
+                        index,
+                        fragment: fragment.to_string(),
+                    });
+                }
+            }
+            if let Some(reason) = choice.get("finish_reason").and_then(Value::as_str) {
+                deltas.push(StreamDelta::Stop(reason.to_string()));
+            }
+        }
+
+        // Sent in a final chunk when `stream_options.include_usage` is set.
+        // Prompt tokens include cached tokens.
+        if let Some(usage) = value.get("usage").filter(|u| u.is_object()) {
+            let cached = usage
+                .pointer("/prompt_tokens_details/cached_tokens")
+                .and_then(Value::as_u64);
+            deltas.push(StreamDelta::Usage(ReportedUsage {
+                input_tokens: usage
+                    .get("prompt_tokens")
+                    .and_then(Value::as_u64)
+                    .map(|prompt| prompt.saturating_sub(cached.unwrap_or(0))),
+                output_tokens: usage.get("completion_tokens").and_then(Value::as_u64),
+                cache_read_tokens: cached,
+                cache_write_tokens: None,
+            }));
+        }
+
+        deltas
+    }
+
+    fn usage_delta(usage: &Value) -> Option<StreamDelta> {
+        serde_json::from_value::<ReportedUsage>(usage.clone())
+            .ok()
+            .map(StreamDelta::Usage)
+    }
+
+    /// Parse Anthropic (Claude) SSE events
+    ///
+    /// Event types:
+    /// - message_start: Contains message_id
+    /// - content_block_start: Start of content block (ignored)
+    /// - content_block_delta: Text content delta
+    /// - content_block_stop: End of content block (ignored)
+    /// - message_delta: Message metadata updates (ignored)
+    /// - message_stop: End of message
+    /// - error: Error event
+    fn parse_anthropic(&self, event: &SseEvent) -> Result<Option<LogMsg>, SseBridgeError> {
+        let event_type = event.event.as_deref().unwrap_or("");
+
+        // Handle ping/keep-alive
+        if event.data.trim().is_empty() || event_type == "ping" {
+            return Ok(None);
+        }
+
+        match event_type {
+            "message_start" => {
+                // Extract message ID
+                let data: serde_json::Value = serde_json::from_str(&event.data)?;
+                if let Some(id) = data
+                    .get("message")
+                    .and_then(|m| m.get("id"))
+                    .and_then(|id| id.as_str())
+                {
+                    return Ok(Some(LogMsg::SessionId(id.to_string())));
+                }
+                Ok(None)
+            }
+            "content_block_delta" => {
+                // Extract text delta
+                let data: serde_json::Value = serde_json::from_str(&event.data)?;
+                if let Some(text) = data
+                    .get("delta")
+                    .and_then(|d| d.get("text"))
+                    .and_then(|t| t.as_str())
+                {
+                    return Ok(Some(LogMsg::Stdout(text.to_string())));
+                }
+                Ok(None)
+            }
+            "message_stop" => Ok(Some(LogMsg::Finished)),
+            "error" => {
+                let data: serde_json::Value = serde_json::from_str(&event.data)?;
+                let error_msg = data
+                    .get("error")
+                    .and_then(|e| e.get("message"))
+                    .and_then(|m| m.as_str())
+                    .unwrap_or("Unknown error");
+                Ok(Some(LogMsg::Stderr(format!(
+                    "Anthropic error: {}",
+                    error_msg
+                ))))
+            }
+            // Ignore other event types
+            "content_block_start" | "content_block_stop" | "message_delta" => Ok(None),
+            _ => {
+                tracing::debug!("Ignoring unknown Anthropic event type: {}", event_type);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Parse Google (Gemini) SSE events
+    ///
+    /// Gemini uses a simpler format with candidates array
+    fn parse_google(&self, event: &SseEvent) -> Result<Option<LogMsg>, SseBridgeError> {
+        // Handle empty data
+        if event.data.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let data: serde_json::Value = serde_json::from_str(&event.data)?;
+
+        // Check for prompt feedback (usually an error or safety issue)
+        if let Some(feedback) = data.get("promptFeedback")
+            && let Some(block_reason) = feedback.get("blockReason").and_then(|r| r.as_str())
+        {
+            return Ok(Some(LogMsg::Stderr(format!(
+                "Gemini blocked: {}",
+                block_reason
+            ))));
+        }
+
+        // Extract text from candidates
+        if let Some(candidates) = data.get("candidates").and_then(|c| c.as_array()) {
+            for candidate in candidates {
+                // Check for finish reason
+                if let Some(finish_reason) = candidate.get("finishReason").and_then(|r| r.as_str())
+                {
+                    if finish_reason == "STOP" || finish_reason == "END_TURN" {
+                        return Ok(Some(LogMsg::Finished));
+                    }
+                    if finish_reason == "SAFETY" {
+                        return Ok(Some(LogMsg::Stderr(
+                            "Gemini: Safety filter triggered".into(),
+                        )));
+                    }
+                }
+
+                // Extract text content
+                if let Some(parts) = candidate
+                    .get("content")
+                    .and_then(|c| c.get("parts"))
+                    .and_then(|p| p.as_array())
+                {
+                    for part in parts {
+                        if let Some(text) = part.get("text").and_then(|t| t.as_str()) {
+                            return Ok(Some(LogMsg::Stdout(text.to_string())));
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Parse OpenAI SSE events
+    ///
+    /// OpenAI uses choices array with delta objects
+    fn parse_openai(&self, event: &SseEvent) -> Result<Option<LogMsg>, SseBridgeError> {
+        let data = event.data.trim();
+
+        // Handle [DONE] marker
+        if data == "[DONE]" {
+            return Ok(Some(LogMsg::Finished));
+        }
+
+        // Handle empty data
+        if data.is_empty() {
+            return Ok(None);
+        }
+
+        let parsed: serde_json::Value = serde_json::from_str(data)?;
+
+        // Check for error
+        if let Some(error) = parsed.get("error") {
+            let error_msg = error
+                .get("message")
+                .and_then(|m| m.as_str())
+                .unwrap_or("Unknown error");
+            return Ok(Some(LogMsg::Stderr(format!("OpenAI error: {}", error_msg))));
+        }
+
+        // Extract content from choices
+        if let Some(choices) = parsed.get("choices").and_then(|c| c.as_array()) {
+            for choice in choices {
+                // Check for finish reason
+                if let Some(finish_reason) = choice.get("finish_reason").and_then(|r| r.as_str())
+                    && (finish_reason == "stop" || finish_reason == "length")
+                {
+                    return Ok(Some(LogMsg::Finished));
+                }
+
+                // Extract delta content
+                if let Some(content) = choice
+                    .get("delta")
+                    .and_then(|d| d.get("content"))
+                    .and_then(|c| c.as_str())
+                    && !content.is_empty()
+                {
+                    return Ok(Some(LogMsg::Stdout(content.to_string())));
+                }
+            }
+        }
+
+        // Extract session/message ID if available
+        if let Some(id) = parsed.get("id").and_then(|id| id.as_str()) {
+            return Ok(Some(LogMsg::SessionId(id.to_string())));
+        }
+
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anthropic_content_delta() {
+        let bridge = SseBridge::new(AiProvider::Anthropic);
+        let event = SseEvent::new(r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Hello"}}"#)
+            .with_event("content_block_delta");
+
+        let result = bridge.parse_event(&event).unwrap();
+        assert!(matches!(result, Some(LogMsg::Stdout(text)) if text == "Hello"));
+    }
+
+    #[test]
+    fn test_anthropic_message_stop() {
+        let bridge = SseBridge::new(AiProvider::Anthropic);
+        let event = SseEvent::new(r#"{"type":"message_stop"}"#).with_event("message_stop");
+
+        let result = bridge.parse_event(&event).unwrap();
+        assert!(matches!(result, Some(LogMsg::Finished)));
+    }
+
+    #[test]
+    fn test_anthropic_message_start_session_id() {
+        let bridge = SseBridge::new(AiProvider::Anthropic);
+        let event = SseEvent::new(
+            r#"{"type":"message_start","message":{"id":"msg_123","type":"message","role":"assistant"}}"#,
+        )
+        .with_event("message_start");
+
+        let result = bridge.parse_event(&event).unwrap();
+        assert!(matches!(result, Some(LogMsg::SessionId(id)) if id == "msg_123"));
+    }
+
+    #[test]
+    fn test_google_text_content() {
+        let bridge = SseBridge::new(AiProvider::Google);
+        let event = SseEvent::new(
+            r#"{"candidates":[{"content":{"parts":[{"text":"Hello from Gemini"}],"role":"model"}}]}"#,
+        );
+
+        let result = bridge.parse_event(&event).unwrap();
+        assert!(matches!(result, Some(LogMsg::Stdout(text)) if text == "Hello from Gemini"));
+    }
+
+    #[test]
+    fn test_google_finish_reason() {
+        let bridge = SseBridge::new(AiProvider::Google);
+        let event = SseEvent::new(r#"{"candidates":[{"finishReason":"STOP"}]}"#);
+
+        let result = bridge.parse_event(&event).unwrap();
+        assert!(matches!(result, Some(LogMsg::Finished)));
+    }
+
+    #[test]
+    fn test_openai_delta_content() {
+        let bridge = SseBridge::new(AiProvider::OpenAI);
+        let event = SseEvent::new(
+            r#"{"id":"chatcmpl-123","choices":[{"index":0,"delta":{"content":"Hello"}}]}"#,
+        );
+
+        let result = bridge.parse_event(&event).unwrap();
+        assert!(matches!(result, Some(LogMsg::Stdout(text)) if text == "Hello"));
+    }
+
+    #[test]
+    fn test_openai_done_marker() {
+        let bridge = SseBridge::new(AiProvider::OpenAI);
+        let event = SseEvent::new("[DONE]");
+
+        let result = bridge.parse_event(&event).unwrap();
+        assert!(matches!(result, Some(LogMsg::Finished)));
+    }
+
+    #[test]
+    fn test_openai_finish_reason() {
+        let bridge = SseBridge::new(AiProvider::OpenAI);
+        let event = SseEvent::new(r#"{"choices":[{"index":0,"delta":{},"finish_reason":"stop"}]}"#);
+
+        let result = bridge.parse_event(&event).unwrap();
+        assert!(matches!(result, Some(LogMsg::Finished)));
+    }
+
+    #[test]
+    fn test_decoder_handles_split_chunks() {
+        let mut decoder = SseDecoder::new();
+        assert!(
+            decoder
+                .push(b"event: message_start\ndata: {\"a\"")
+                .is_empty()
+        );
+        let events = decoder.push(b":1}\r\n\n: keep-alive\n\ndata: [DONE]\n\n");
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event.as_deref(), Some("message_start"));
+        assert_eq!(events[0].data, r#"{"a":1}"#);
+        assert_eq!(events[1].event, None);
+        assert_eq!(events[1].data, "[DONE]");
+
+        assert!(decoder.push(b"data: tail").is_empty());
+        assert_eq!(decoder.finish().map(|e| e.data), Some("tail".to_string()));
+    }
+
+    #[test]
+    fn test_anthropic_tool_use_deltas() {
+        let bridge = SseBridge::new(AiProvider::Anthropic);
+        let start = SseEvent::new(
+            r#"{"type":"content_block_start","index":1,"content_block":{"type":"tool_use","id":"toolu_1","name":"read_file","input":{}}}"#,
+        )
+        .with_event("content_block_start");
+        let args = SseEvent::new(
+            r#"{"type":"content_block_delta","index":1,"delta":{"type":"input_json_delta","partial_json":"{\"path\":"}}"#,
+        )
+        .with_event("content_block_delta");
+        let stop = SseEvent::new(
+            r#"{"type":"message_delta","delta":{"stop_reason":"tool_use"},"usage":{"output_tokens":42}}"#,
+        )
+        .with_event("message_delta");
+
+        assert_eq!(
+            bridge.parse_deltas(&start).unwrap(),
+            vec![StreamDelta::ToolCallStart {
+                index: 1,
+                id: "toolu_1".to_string(),
+                name: "read_file".to_string(),
+            }]
+        );
+        assert_eq!(
+            bridge.parse_deltas(&args).unwrap(),
+            vec![StreamDelta::ToolArguments {
+                index: 1,
+                fragment: r#"{"path":"#.to_string(),
+            }]
+        );
+        let deltas = bridge.parse_deltas(&stop).unwrap();
+        assert!(matches!(&deltas[0], StreamDelta::Usage(usage) if usage.output_tokens == Some(42)));
+        assert_eq!(deltas[1], StreamDelta::Stop("tool_use".to_string()));
+    }
+
+    #[test]
+    fn test_openai_tool_call_and_usage_deltas() {
+        let bridge = SseBridge::new(AiProvider::OpenAI);
+        let call = SseEvent::new(
+            r#"{"id":"chatcmpl-1","choices":[{"index":0,"delta":{"tool_calls":[{"index":0,"id":"call_1","type":"function","function":{"name":"run_command","arguments":""}}]}}]}"#,
+        );
+        let usage = SseEvent::new(
+            r#"{"id":"chatcmpl-1","choices":[],"usage":{"prompt_tokens":100,"completion_tokens":20,"prompt_tokens_details":{"cached_tokens":60}}}"#,
+        );
+
+        let deltas = bridge.parse_deltas(&call).unwrap();
+        assert_eq!(
+            deltas[1],
+            StreamDelta::ToolCallStart {
+                index: 0,
+                id: "call_1".to_string(),
+                name: "run_command".to_string(),
+            }
+        );
+        let deltas = bridge.parse_deltas(&usage).unwrap();
+        assert_eq!(
+            deltas[1],
+            StreamDelta::Usage(ReportedUsage {
+                input_tokens: Some(40),
+                output_tokens: Some(20),
+                cache_read_tokens: Some(60),
+                cache_write_tokens: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_google_function_call_delta() {
+        let bridge = SseBridge::new(AiProvider::Google);
+        let event = SseEvent::new(
+            r#"{"candidates":[{"content":{"parts":[{"functionCall":{"name":"search","args":{"pattern":"TODO"}}}],"role":"model"},"finishReason":"STOP"}]}"#,
+        );
+
+        let deltas = bridge.parse_deltas(&event).unwrap();
+        assert_eq!(
+            deltas,
+            vec![
+                StreamDelta::ToolCall {
+                    id: None,
+                    name: "search".to_string(),
+                    arguments: serde_json::json!({ "pattern": "TODO" }),
+                },
+                StreamDelta::Stop("STOP".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_empty_data_ignored() {
+        let bridge = SseBridge::new(AiProvider::Anthropic);
+        let event = SseEvent::new("").with_event("ping");
+
+        let result = bridge.parse_event(&event).unwrap();
+        assert!(result.is_none());
+    }
+}
@@ -29,7 +29,8 @@ pub fn detect_recommended_storage(agent: &BaseCodingAgent) -> AgentStorageLocati
         | BaseCodingAgent::Opencode
         | BaseCodingAgent::QwenCode
         | BaseCodingAgent::CursorAgent
-        | BaseCodingAgent::Claude => AgentStorageLocation::Database,
+        | BaseCodingAgent::Claude
+        | BaseCodingAgent::ApiAgent => AgentStorageLocation::Database,
     }
 }
 
@@ -2,7 +2,10 @@ use std::{
     collections::HashMap,
     io,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
     time::Duration,
 };
 
@@ -12,12 +15,15 @@ use command_group::AsyncGroupChild;
 use db::{
     DBService,
     models::{
+        ai_provider_key::AiProviderKey,
         coding_agent_turn::CodingAgentTurn,
         execution_process::{
             ExecutionContext, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
         },
         execution_process_repo_state::ExecutionProcessRepoState,
+        project::Project,
         project_repo::ProjectRepo,
+        project_sandbox::ProjectSandboxConfig,
         repo::Repo,
         scratch::{DraftFollowUpData, Scratch, ScratchType},
         task::{Task, TaskStatus},
@@ -34,36 +40,153 @@ use executors::{
     },
     approvals::{ExecutorApprovalService, NoopExecutorApprovalService},
     env::ExecutionEnv,
-    executors::{BaseCodingAgent, ExecutorExitResult, ExecutorExitSignal, InterruptSender},
+    executors::{
+        BaseCodingAgent, CodingAgent, ExecutorExitResult, ExecutorExitSignal, InterruptSender,
+        api_agent::provider::api_key_env,
+    },
     logs::{NormalizedEntryType, utils::patch::extract_normalized_entry_from_patch},
-    profile::ExecutorProfileId,
+    profile::{ExecutorConfigs, ExecutorProfileId},
 };
 use futures::{FutureExt, TryStreamExt, stream::select};
 use serde_json::json;
 use services::services::{
     analytics::AnalyticsContext,
     approvals::{Approvals, executor_approvals::ExecutorApprovalBridge},
-    config::Config,
+    ci_repair,
+    config::{Config, ExecutionConcurrencyConfig},
+    conflict_resolution,
     container::{ContainerError, ContainerRef, ContainerService},
     diff_stream::{self, DiffStreamHandle},
     git::{Commit, GitCli, GitService},
     image::ImageService,
     notification::NotificationService,
+    pr_review,
     queued_message::QueuedMessageService,
     share::SharePublisher,
+    transcript,
     workspace_manager::{RepoWorkspaceInput, WorkspaceManager},
 };
-use tokio::{sync::RwLock, task::JoinHandle};
+use tokio::{
+    sync::{Mutex, RwLock, mpsc},
+    task::JoinHandle,
+};
 use tokio_util::io::ReaderStream;
 use utils::{
     log_msg::LogMsg,
     msg_store::MsgStore,
+    path::expand_tilde,
     text::{git_branch_id, short_uuid, truncate_to_char_boundary},
 };
 use uuid::Uuid;
 
 use crate::{command, copy};
 
+/// Directory under the app cache holding each project's sandbox npm cache,
+/// used instead of the host's ~/.npm to launch agents through npx
+const SANDBOX_NPM_CACHE_DIR: &str = "sandbox-npm";
+
+/// Where `agent` keeps its sessions, credentials and caches; only these are
+/// writable inside a project sandbox, not the whole of ~/.config or ~/.cache
+fn agent_state_paths(agent: BaseCodingAgent) -> &'static [&'static str] {
+    match agent {
+        BaseCodingAgent::ClaudeCode | BaseCodingAgent::Claude => {
+            &["~/.claude", "~/.claude.json", "~/.config/claude"]
+        }
+        BaseCodingAgent::Amp => &["~/.config/amp", "~/.local/share/amp", "~/.cache/amp"],
+        BaseCodingAgent::Gemini => &["~/.gemini"],
+        BaseCodingAgent::Codex => &["~/.codex"],
+        BaseCodingAgent::Opencode => &[
+            "~/.config/opencode",
+            "~/.local/share/opencode",
+            "~/.local/state/opencode",
+            "~/.cache/opencode",
+        ],
+        BaseCodingAgent::CursorAgent => &["~/.cursor", "~/.config/cursor"],
+        BaseCodingAgent::QwenCode => &["~/.qwen"],
+        BaseCodingAgent::Copilot => &["~/.copilot", "~/.config/github-copilot"],
+        BaseCodingAgent::Droid => &["~/.factory"],
+        BaseCodingAgent::ApiAgent => &[],
+    }
+}
+
+/// Settings files inside `agent`'s state directories that grant permissions,
+/// define hooks or configure MCP servers; read-only inside a project sandbox
+/// so a run cannot widen what later runs may do
+fn agent_config_paths(agent: BaseCodingAgent) -> &'static [&'static str] {
+    match agent {
+        BaseCodingAgent::ClaudeCode | BaseCodingAgent::Claude => &["~/.claude/settings.json"],
+        BaseCodingAgent::Amp => &["~/.config/amp/settings.json"],
+        BaseCodingAgent::Gemini => &["~/.gemini/settings.json"],
+        BaseCodingAgent::Codex => &["~/.codex/config.toml"],
+        BaseCodingAgent::Opencode => &[
+            "~/.config/opencode/opencode.json",
+            "~/.config/opencode/config.json",
+        ],
+        BaseCodingAgent::CursorAgent => &["~/.cursor/cli-config.json", "~/.cursor/mcp.json"],
+        BaseCodingAgent::QwenCode => &["~/.qwen/settings.json"],
+        BaseCodingAgent::Copilot => &["~/.copilot/config.json", "~/.copilot/mcp-config.json"],
+        BaseCodingAgent::Droid => &["~/.factory/settings.json", "~/.factory/mcp.json"],
+        BaseCodingAgent::ApiAgent => &[],
+    }
+}
+
+/// Parts of a repository's git directory a sandboxed worktree commit writes:
+/// objects, refs and their reflogs. The worktree's own git directory (its
+/// HEAD and index) is added separately.
+const GIT_WRITABLE_PATHS: &[&str] = &["objects", "refs", "logs"];
+
+/// Parts of a repository's git directory that run code or configure git for
+/// processes outside the sandbox; read-only even when a configured writable
+/// path contains the repository
+const GIT_READ_ONLY_PATHS: &[&str] = &["hooks", "config"];
+
+/// The git directory of the worktree at `worktree_path`, read from its
+/// `.git` file
+fn worktree_git_dir(worktree_path: &Path) -> Option<PathBuf> {
+    let dot_git = std::fs::read_to_string(worktree_path.join(".git")).ok()?;
+    let git_dir = PathBuf::from(dot_git.trim().strip_prefix("gitdir:")?.trim());
+    Some(if git_dir.is_absolute() {
+        git_dir
+    } else {
+        worktree_path.join(git_dir)
+    })
+}
+
+/// Writable and read-only paths of a project sandbox for `agent` running in
+/// `workspace_dir`, which holds a worktree of each of `repos`
+fn sandbox_paths(
+    workspace_dir: &Path,
+    repos: &[Repo],
+    agent: Option<BaseCodingAgent>,
+    npm_cache_dir: &Path,
+) -> (Vec<PathBuf>, Vec<PathBuf>) {
+    let mut writable_paths = vec![workspace_dir.to_path_buf(), npm_cache_dir.to_path_buf()];
+    let mut read_only_paths = Vec::new();
+    for repo in repos {
+        let git_dir = repo.path.join(".git");
+        let worktree_path = workspace_dir.join(&repo.name);
+        writable_paths.extend(GIT_WRITABLE_PATHS.iter().map(|path| git_dir.join(path)));
+        writable_paths.extend(worktree_git_dir(&worktree_path));
+        read_only_paths.extend(GIT_READ_ONLY_PATHS.iter().map(|path| git_dir.join(path)));
+        // Rewriting the worktree's .git file would point git at another
+        // git directory
+        read_only_paths.push(worktree_path.join(".git"));
+    }
+    if let Some(agent) = agent {
+        writable_paths.extend(
+            agent_state_paths(agent)
+                .iter()
+                .map(|path| expand_tilde(path)),
+        );
+        read_only_paths.extend(
+            agent_config_paths(agent)
+                .iter()
+                .map(|path| expand_tilde(path)),
+        );
+    }
+    (writable_paths, read_only_paths)
+}
+
 #[derive(Clone)]
 pub struct LocalContainerService {
     db: DBService,
@@ -78,6 +201,10 @@ pub struct LocalContainerService {
     queued_message_service: QueuedMessageService,
     publisher: Result<SharePublisher, RemoteClientNotConfigured>,
     notification_service: NotificationService,
+    budget_stop_tx: mpsc::UnboundedSender<Uuid>,
+    execution_queue_lock: Arc<Mutex<()>>,
+    /// Set once all processes are being killed so no queued attempt starts
+    shutting_down: Arc<AtomicBool>,
 }
 
 impl LocalContainerService {
@@ -96,6 +223,7 @@ impl LocalContainerService {
         let child_store = Arc::new(RwLock::new(HashMap::new()));
         let interrupt_senders = Arc::new(RwLock::new(HashMap::new()));
         let notification_service = NotificationService::new(config.clone());
+        let (budget_stop_tx, budget_stop_rx) = mpsc::unbounded_channel();
 
         let container = LocalContainerService {
             db,
@@ -110,9 +238,15 @@ impl LocalContainerService {
             queued_message_service,
             publisher,
             notification_service,
+            budget_stop_tx,
+            execution_queue_lock: Arc::new(Mutex::new(())),
+            shutting_down: Arc::new(AtomicBool::new(false)),
         };
 
         container.spawn_workspace_cleanup().await;
+        container.spawn_transcript_retention();
+        container.spawn_execution_queue();
+        container.spawn_budget_stop_listener(budget_stop_rx);
 
         container
     }
@@ -211,6 +345,135 @@ impl LocalContainerService {
         });
     }
 
+    /// Delete stored transcripts older than the configured retention
+    fn spawn_transcript_retention(&self) {
+        let db = self.db.clone();
+        let config = self.config.clone();
+        tokio::spawn(async move {
+            let mut retention_interval = tokio::time::interval(Duration::from_secs(6 * 60 * 60));
+            loop {
+                retention_interval.tick().await;
+                let Some(retention_days) = config.read().await.transcript_retention_days else {
+                    continue;
+                };
+                match transcript::purge_expired(&db.pool, retention_days).await {
+                    Ok(0) => {}
+                    Ok(deleted) => tracing::info!(
+                        "Deleted {} transcripts older than {} days",
+                        deleted,
+                        retention_days
+                    ),
+                    Err(e) => tracing::error!("Failed to purge expired transcripts: {}", e),
+                }
+            }
+        });
+    }
+
+    /// Start queued attempts periodically, so raised caps and slots freed
+    /// before a restart are picked up; finished processes also trigger a pass
+    fn spawn_execution_queue(&self) {
+        let container = self.clone();
+        tokio::spawn(async move {
+            let mut queue_interval = tokio::time::interval(Duration::from_secs(30));
+            loop {
+                queue_interval.tick().await;
+                container.start_queued_attempts_logged().await;
+            }
+        });
+    }
+
+    async fn start_queued_attempts_logged(&self) {
+        if self.shutting_down.load(Ordering::Relaxed) {
+            return;
+        }
+        match self.start_queued_attempts().await {
+            Ok(started) if !started.is_empty() => {
+                tracing::info!("Started {} queued attempts", started.len())
+            }
+            Ok(_) => {}
+            Err(e) => tracing::error!("Failed to start queued attempts: {}", e),
+        }
+    }
+
+    /// Store the conversation of a finished process so it outlives its
+    /// MsgStore (best-effort)
+    async fn persist_transcript(&self, exec_id: Uuid, msg_store: &MsgStore) {
+        let process = match ExecutionProcess::find_by_id(&self.db.pool, exec_id).await {
+            Ok(Some(process)) => process,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::warn!("Failed to load execution process {}: {}", exec_id, e);
+                return;
+            }
+        };
+        if let Err(e) = transcript::persist(&self.db.pool, &process, &msg_store.get_history()).await
+        {
+            tracing::warn!("Failed to store transcript for {}: {}", exec_id, e);
+        }
+    }
+
+    /// Push finished review follow-ups and CI repairs and continue resolved
+    /// conflicts in the background, so a slow push doesn't hold up the exit
+    /// monitor
+    fn complete_agent_follow_ups(&self, workspace: &Workspace) {
+        let container = self.clone();
+        let workspace = workspace.clone();
+        tokio::spawn(async move {
+            if let Err(e) = pr_review::complete_follow_ups(&container, &workspace).await {
+                tracing::error!(
+                    "Failed to complete review follow-ups for workspace {}: {}",
+                    workspace.id,
+                    e
+                );
+            }
+            if let Err(e) = ci_repair::complete_repairs(&container, &workspace).await {
+                tracing::error!(
+                    "Failed to complete CI repairs for workspace {}: {}",
+                    workspace.id,
+                    e
+                );
+            }
+            if let Err(e) = conflict_resolution::complete_resolutions(&container, &workspace).await
+            {
+                tracing::error!(
+                    "Failed to complete conflict resolutions for workspace {}: {}",
+                    workspace.id,
+                    e
+                );
+            }
+        });
+    }
+
+    /// Stop execution processes whose project went over a `stop_running` budget
+    fn spawn_budget_stop_listener(&self, mut budget_stop_rx: mpsc::UnboundedReceiver<Uuid>) {
+        let container = self.clone();
+        tokio::spawn(async move {
+            while let Some(exec_id) = budget_stop_rx.recv().await {
+                let process = match ExecutionProcess::find_by_id(&container.db.pool, exec_id).await
+                {
+                    Ok(Some(process)) if process.status == ExecutionProcessStatus::Running => {
+                        process
+                    }
+                    Ok(_) => continue,
+                    Err(e) => {
+                        tracing::error!("Failed to load execution process {}: {}", exec_id, e);
+                        continue;
+                    }
+                };
+                if let Err(e) = container
+                    .stop_execution(&process, ExecutionProcessStatus::Killed)
+                    .await
+                {
+                    tracing::error!(
+                        "Failed to stop execution process {} over budget: {}",
+                        exec_id,
+                        e
+                    );
+                }
+            }
+        });
+    }
+
     /// Record the current HEAD commit for each repository as the "after" state.
     /// Errors are silently ignored since this runs after the main execution completes
     /// and failure should not block process finalization.
@@ -290,6 +553,12 @@ impl LocalContainerService {
         for repo in repos {
             let worktree_path = workspace_root.join(&repo.name);
 
+            // A commit now would end up inside the stopped rebase or merge
+            if let Ok(Some(_)) = self.git().detect_conflict_op(&worktree_path) {
+                tracing::debug!("Skipping repo '{}' with conflicts in progress", repo.name);
+                continue;
+            }
+
             match git.has_changes(&worktree_path) {
                 Ok(true) => {
                     repos_with_changes.push((repo.clone(), worktree_path));
@@ -517,6 +786,8 @@ impl LocalContainerService {
                     } else {
                         container.finalize_task(publisher.as_ref().ok(), &ctx).await;
                     }
+
+                    container.complete_agent_follow_ups(&ctx.workspace);
                 }
 
                 // Fire analytics event when CodingAgent execution has finished
@@ -546,6 +817,7 @@ impl LocalContainerService {
             if let Some(msg_arc) = msg_stores.write().await.remove(&exec_id) {
                 msg_arc.push_finished();
                 tokio::time::sleep(Duration::from_millis(50)).await; // Wait for the finish message to propogate
+                container.persist_transcript(exec_id, &msg_arc).await;
                 match Arc::try_unwrap(msg_arc) {
                     Ok(inner) => drop(inner),
                     Err(arc) => tracing::error!(
@@ -558,6 +830,9 @@ impl LocalContainerService {
 
             // Cleanup child handle
             child_store.write().await.remove(&exec_id);
+
+            // A slot may have freed up for a queued attempt
+            container.start_queued_attempts_logged().await;
         })
     }
 
@@ -852,6 +1127,80 @@ impl LocalContainerService {
         )
         .await
     }
+
+    /// Run the execution in the project's sandbox when it has one enabled.
+    /// Besides the workspace, the parts of each repository's git directory
+    /// that worktree commits write, the running agent's state directories and
+    /// a per-project npm cache stay writable. Git hooks and config, worktree
+    /// .git files and agent settings stay read-only.
+    async fn apply_project_sandbox(
+        &self,
+        env: &mut ExecutionEnv,
+        workspace: &Workspace,
+        project: &Project,
+        workspace_dir: &Path,
+        executor_action: &ExecutorAction,
+    ) -> Result<(), ContainerError> {
+        let Some(config) =
+            ProjectSandboxConfig::find_by_project_id(&self.db.pool, project.id).await?
+        else {
+            return Ok(());
+        };
+        let repos = WorkspaceRepo::find_repos_for_workspace(&self.db.pool, workspace.id).await?;
+
+        let npm_cache_dir = utils::cache_dir()
+            .join(SANDBOX_NPM_CACHE_DIR)
+            .join(project.id.to_string());
+        let (writable_paths, read_only_paths) = sandbox_paths(
+            workspace_dir,
+            &repos,
+            executor_action.base_executor(),
+            &npm_cache_dir,
+        );
+
+        if let Some(policy) = config.policy(writable_paths, read_only_paths) {
+            tracing::debug!("Sandboxing execution in workspace {}", workspace.id);
+            tokio::fs::create_dir_all(&npm_cache_dir).await?;
+            env.insert("npm_config_cache", npm_cache_dir.to_string_lossy());
+            env.sandbox = Some(policy);
+        }
+        Ok(())
+    }
+
+    /// API-mode agents call the provider directly, so hand them the
+    /// workspace's stored key for the profile's provider
+    async fn insert_api_agent_key(
+        &self,
+        env: &mut ExecutionEnv,
+        executor_action: &ExecutorAction,
+        project: &Project,
+    ) -> Result<(), ContainerError> {
+        let executor_profile_id = match executor_action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => &request.executor_profile_id,
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => &request.executor_profile_id,
+            ExecutorActionType::ScriptRequest(_) => return Ok(()),
+        };
+        let Some(CodingAgent::ApiAgent(agent)) =
+            ExecutorConfigs::get_cached().get_coding_agent(executor_profile_id)
+        else {
+            return Ok(());
+        };
+        let Some(tenant_workspace_id) = project.tenant_workspace_id else {
+            return Ok(());
+        };
+
+        // A key set in the profile env takes precedence
+        if env.contains_key(api_key_env(agent.provider)) {
+            return Ok(());
+        }
+        env.api_agent_key = AiProviderKey::get_key(
+            &self.db.pool,
+            tenant_workspace_id,
+            &agent.provider.to_string(),
+        )
+        .await?;
+        Ok(())
+    }
 }
 
 fn failure_exit_status() -> std::process::ExitStatus {
@@ -889,10 +1238,22 @@ impl ContainerService for LocalContainerService {
         &self.notification_service
     }
 
+    fn budget_stop_sender(&self) -> Option<mpsc::UnboundedSender<Uuid>> {
+        Some(self.budget_stop_tx.clone())
+    }
+
     async fn git_branch_prefix(&self) -> String {
         self.config.read().await.git_branch_prefix.clone()
     }
 
+    async fn execution_concurrency(&self) -> ExecutionConcurrencyConfig {
+        self.config.read().await.execution_concurrency.clone()
+    }
+
+    fn execution_queue_lock(&self) -> &Mutex<()> {
+        &self.execution_queue_lock
+    }
+
     fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf {
         PathBuf::from(workspace.container_ref.clone().unwrap_or_default())
     }
@@ -1051,16 +1412,19 @@ impl ContainerService for LocalContainerService {
         let approvals_service: Arc<dyn ExecutorApprovalService> =
             match executor_action.base_executor() {
                 Some(
-                    BaseCodingAgent::Codex
+                    executor @ (BaseCodingAgent::Codex
                     | BaseCodingAgent::ClaudeCode
                     | BaseCodingAgent::Gemini
                     | BaseCodingAgent::QwenCode
-                    | BaseCodingAgent::Opencode,
+                    | BaseCodingAgent::Opencode
+                    | BaseCodingAgent::ApiAgent),
                 ) => ExecutorApprovalBridge::new(
                     self.approvals.clone(),
                     self.db.clone(),
                     self.notification_service.clone(),
                     execution_process.id,
+                    Some(executor),
+                    current_dir.clone(),
                 ),
                 _ => Arc::new(NoopExecutorApprovalService {}),
             };
@@ -1086,6 +1450,11 @@ impl ContainerService for LocalContainerService {
         env.insert("VK_WORKSPACE_ID", workspace.id.to_string());
         env.insert("VK_WORKSPACE_BRANCH", &workspace.branch);
 
+        self.insert_api_agent_key(&mut env, executor_action, &project)
+            .await?;
+        self.apply_project_sandbox(&mut env, workspace, &project, &current_dir, executor_action)
+            .await?;
+
         // Create the child and stream, add to execution tracker with timeout
         let mut spawned = tokio::time::timeout(
             Duration::from_secs(30),
@@ -1183,6 +1552,7 @@ impl ContainerService for LocalContainerService {
         // Mark the process finished in the MsgStore
         if let Some(msg) = self.msg_stores.write().await.remove(&execution_process.id) {
             msg.push_finished();
+            self.persist_transcript(execution_process.id, &msg).await;
         }
 
         // Update task status to InReview when execution is stopped
@@ -1340,6 +1710,7 @@ impl ContainerService for LocalContainerService {
 
     async fn kill_all_running_processes(&self) -> Result<(), ContainerError> {
         tracing::info!("Killing all running processes");
+        self.shutting_down.store(true, Ordering::Relaxed);
         let running_processes = ExecutionProcess::find_running(&self.db.pool).await?;
 
         for process in running_processes {
@@ -1370,3 +1741,109 @@ fn success_exit_status() -> std::process::ExitStatus {
         ExitStatusExt::from_raw(0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use executors::sandbox::SandboxPolicy;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_worktree_git_dir_reads_gitdir_file() {
+        let dir = TempDir::new().unwrap();
+        let worktree = dir.path().join("backend");
+        fs::create_dir(&worktree).unwrap();
+
+        fs::write(
+            worktree.join(".git"),
+            "gitdir: /repos/backend/.git/worktrees/backend\n",
+        )
+        .unwrap();
+        assert_eq!(
+            worktree_git_dir(&worktree),
+            Some(PathBuf::from("/repos/backend/.git/worktrees/backend"))
+        );
+
+        fs::write(worktree.join(".git"), "gitdir: ../.git/worktrees/backend").unwrap();
+        assert_eq!(
+            worktree_git_dir(&worktree),
+            Some(worktree.join("../.git/worktrees/backend"))
+        );
+
+        // A regular checkout has a .git directory, not a file
+        fs::remove_file(worktree.join(".git")).unwrap();
+        fs::create_dir(worktree.join(".git")).unwrap();
+        assert_eq!(worktree_git_dir(&worktree), None);
+    }
+
+    #[test]
+    fn test_agent_state_paths_stay_out_of_shared_dirs() {
+        for shared in ["~/.config", "~/.local/share", "~/.local/state", "~/.cache"] {
+            for agent in [
+                BaseCodingAgent::ClaudeCode,
+                BaseCodingAgent::Amp,
+                BaseCodingAgent::Opencode,
+                BaseCodingAgent::CursorAgent,
+                BaseCodingAgent::Copilot,
+            ] {
+                assert!(!agent_state_paths(agent).contains(&shared));
+            }
+        }
+        assert_eq!(agent_state_paths(BaseCodingAgent::Codex), ["~/.codex"]);
+    }
+
+    #[test]
+    fn test_sandbox_mounts_worktree_git_file_read_only() {
+        let dir = TempDir::new().unwrap();
+        let workspace_dir = dir.path().join("workspace");
+        let worktree = workspace_dir.join("backend");
+        fs::create_dir_all(&worktree).unwrap();
+        fs::write(
+            worktree.join(".git"),
+            "gitdir: /repos/backend/.git/worktrees/backend\n",
+        )
+        .unwrap();
+        let npm_cache_dir = dir.path().join("npm");
+        fs::create_dir(&npm_cache_dir).unwrap();
+        let repo = Repo {
+            id: Uuid::new_v4(),
+            path: PathBuf::from("/repos/backend"),
+            name: "backend".to_string(),
+            display_name: "backend".to_string(),
+            created_at: sqlx::types::chrono::Utc::now(),
+            updated_at: sqlx::types::chrono::Utc::now(),
+        };
+
+        let (writable_paths, read_only_paths) = sandbox_paths(
+            &workspace_dir,
+            &[repo],
+            Some(BaseCodingAgent::ClaudeCode),
+            &npm_cache_dir,
+        );
+        assert!(writable_paths.contains(&npm_cache_dir));
+        assert!(!writable_paths.contains(&expand_tilde("~/.npm")));
+        assert!(read_only_paths.contains(&expand_tilde("~/.claude/settings.json")));
+
+        let args = SandboxPolicy {
+            writable_paths,
+            read_only_paths,
+            ..Default::default()
+        }
+        .bwrap_args();
+        let workspace = workspace_dir.into_os_string();
+        let dot_git = worktree.join(".git").into_os_string();
+        let bind = args
+            .windows(2)
+            .position(|pair| pair[0] == "--bind" && pair[1] == workspace)
+            .unwrap();
+        let ro_bind = args
+            .windows(3)
+            .position(|triple| triple[0] == "--ro-bind" && triple[1] == dot_git)
+            .unwrap();
+        assert!(bind < ro_bind);
+        assert_eq!(args[ro_bind + 2], dot_git);
+    }
+}
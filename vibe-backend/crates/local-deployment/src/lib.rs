@@ -136,6 +136,17 @@ impl Deployment for LocalDeployment {
         }
 
         let approvals = Approvals::new(msg_stores.clone());
+        {
+            let approvals = approvals.clone();
+            let pool = db.pool.clone();
+            tokio::spawn(async move {
+                match approvals.restore_pending(&pool).await {
+                    Ok(0) => {}
+                    Ok(count) => tracing::info!("Restored {} pending approvals", count),
+                    Err(e) => tracing::error!("Failed to restore pending approvals: {}", e),
+                }
+            });
+        }
         let queued_message_service = QueuedMessageService::new();
 
         let share_config = ShareConfig::from_env();
@@ -197,6 +208,7 @@ impl Deployment for LocalDeployment {
         .await;
 
         let events = EventService::new(db.clone(), events_msg_store, events_entry_count);
+        events.spawn_change_listener();
 
         let file_search_cache = Arc::new(FileSearchCache::new());
 
@@ -244,7 +256,7 @@ impl Deployment for LocalDeployment {
         &self.analytics
     }
 
-    fn container(&self) -> &impl ContainerService {
+    fn container(&self) -> &(impl ContainerService + Sync) {
         &self.container
     }
 
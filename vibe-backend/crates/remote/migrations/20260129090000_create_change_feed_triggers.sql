@@ -0,0 +1,56 @@
+-- Realtime change feed for EventService via LISTEN/NOTIFY
+-- Publishes a small JSON payload on the `ikanban_changes` channel for every
+-- insert/update/delete on the HookTables tables. Payloads only carry ids and
+-- the scoping columns needed to filter deletes; listeners re-read the row.
+
+CREATE OR REPLACE FUNCTION notify_hook_change()
+RETURNS TRIGGER AS $$
+DECLARE
+    row_data JSONB;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        row_data := to_jsonb(OLD);
+    ELSE
+        row_data := to_jsonb(NEW);
+    END IF;
+
+    PERFORM pg_notify(
+        'ikanban_changes',
+        jsonb_strip_nulls(jsonb_build_object(
+            'table',        TG_TABLE_NAME,
+            'op',           TG_OP,
+            'id',           row_data->'id',
+            'project_id',   row_data->'project_id',
+            'task_id',      row_data->'task_id',
+            'session_id',   row_data->'session_id',
+            'scratch_type', row_data->'scratch_type'
+        ))::text
+    );
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Attach the trigger to every table that exists in this database.
+-- workspaces, execution_processes and scratch are owned by the local schema,
+-- so they may not be present on every deployment.
+DO $$
+DECLARE
+    tbl TEXT;
+BEGIN
+    FOREACH tbl IN ARRAY ARRAY['tasks', 'workspaces', 'execution_processes', 'scratch', 'projects']
+    LOOP
+        IF to_regclass('public.' || tbl) IS NOT NULL THEN
+            EXECUTE format('DROP TRIGGER IF EXISTS trg_%s_notify_change ON %I', tbl, tbl);
+            EXECUTE format(
+                'CREATE TRIGGER trg_%s_notify_change
+                    AFTER INSERT OR UPDATE OR DELETE ON %I
+                    FOR EACH ROW
+                    EXECUTE FUNCTION notify_hook_change()',
+                tbl, tbl
+            );
+        END IF;
+    END LOOP;
+END $$;
+
+COMMENT ON FUNCTION notify_hook_change() IS 'Publishes row changes on the ikanban_changes channel for realtime streams';
@@ -0,0 +1,30 @@
+-- GitHub webhook delivery ledger
+-- Every verified delivery to /webhooks/github is stored before it is processed,
+-- so duplicates can be skipped and failed deliveries replayed.
+
+CREATE TABLE IF NOT EXISTS github_webhook_deliveries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- X-GitHub-Delivery header; GitHub reuses it when a delivery is redelivered
+    delivery_id TEXT NOT NULL UNIQUE,
+    event_type TEXT NOT NULL,
+    action TEXT,
+    payload JSONB NOT NULL,
+
+    status VARCHAR(16) NOT NULL DEFAULT 'received',
+    -- Possible statuses: received, processed, ignored, failed
+    error_message TEXT,
+    attempts INT NOT NULL DEFAULT 0,
+
+    received_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    processed_at TIMESTAMPTZ,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT github_webhook_deliveries_status_check
+        CHECK (status IN ('received', 'processed', 'ignored', 'failed'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_github_webhook_deliveries_status
+    ON github_webhook_deliveries(status, received_at DESC);
+CREATE INDEX IF NOT EXISTS idx_github_webhook_deliveries_event_type
+    ON github_webhook_deliveries(event_type);
@@ -0,0 +1,39 @@
+-- Team cycles (time-boxed sprints, Linear-style)
+-- Issues join a cycle through team_cycle_issues. Rows are kept after an issue
+-- leaves the cycle so scope changes and rollover can be reported per cycle.
+
+CREATE TABLE IF NOT EXISTS team_cycles (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    team_id UUID NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+    -- Sequential per team: Cycle 1, Cycle 2, ...
+    number INT NOT NULL,
+    name TEXT,
+    starts_at TIMESTAMPTZ NOT NULL,
+    ends_at TIMESTAMPTZ NOT NULL,
+    -- Set when the cycle ends and its unfinished issues have been rolled over
+    completed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(team_id, number),
+    CONSTRAINT team_cycles_dates_check CHECK (ends_at > starts_at)
+);
+
+CREATE INDEX IF NOT EXISTS idx_team_cycles_team_id ON team_cycles(team_id, starts_at);
+CREATE INDEX IF NOT EXISTS idx_team_cycles_open_ends_at
+    ON team_cycles(ends_at) WHERE completed_at IS NULL;
+
+CREATE TABLE IF NOT EXISTS team_cycle_issues (
+    cycle_id UUID NOT NULL REFERENCES team_cycles(id) ON DELETE CASCADE,
+    task_id UUID NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    added_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Set when the issue leaves the cycle (moved, removed or carried over)
+    removed_at TIMESTAMPTZ,
+    -- Set on the new membership when an unfinished issue was rolled over
+    carried_from_cycle_id UUID REFERENCES team_cycles(id) ON DELETE SET NULL,
+    -- True on the old membership when the issue was rolled into the next cycle
+    carried_over BOOLEAN NOT NULL DEFAULT FALSE,
+    PRIMARY KEY (cycle_id, task_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_team_cycle_issues_task_id
+    ON team_cycle_issues(task_id) WHERE removed_at IS NULL;
@@ -0,0 +1,61 @@
+-- Tool-approval policies and the persisted approval ledger
+--
+-- Rules are evaluated per project, highest priority first. A rule matches
+-- when every condition it sets matches the tool call; unset conditions match
+-- anything. Every approval request and its decision is stored in
+-- tool_approvals so pending requests survive a restart.
+
+CREATE TABLE IF NOT EXISTS approval_policy_rules (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    effect VARCHAR(8) NOT NULL,
+    -- Glob on the executor's tool name, e.g. 'Bash' or 'mcp__*'
+    tool_name TEXT,
+    -- ActionType kind: file_read, file_edit, command_run, search, web_fetch, tool
+    action_kind VARCHAR(32),
+    -- Path glob (file_*), command pattern (command_run) or domain (web_fetch)
+    pattern TEXT,
+    -- Executor, e.g. CLAUDE_CODE or CODEX
+    executor TEXT,
+    priority INT NOT NULL DEFAULT 0,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT approval_policy_rules_effect_check
+        CHECK (effect IN ('allow', 'deny', 'ask'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_approval_policy_rules_project
+    ON approval_policy_rules(project_id, priority DESC);
+
+CREATE TABLE IF NOT EXISTS tool_approvals (
+    -- Approval request ID handed to the executor
+    id TEXT PRIMARY KEY,
+    execution_process_id UUID NOT NULL REFERENCES execution_processes(id) ON DELETE CASCADE,
+    tool_name TEXT NOT NULL,
+    tool_call_id TEXT NOT NULL,
+    tool_input JSONB NOT NULL DEFAULT '{}',
+    -- Normalized ActionType of the tool call, when known
+    action JSONB,
+
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    -- Possible deciders: user, policy, timeout
+    decided_by VARCHAR(16),
+    decided_by_user_id TEXT,
+    rule_id UUID REFERENCES approval_policy_rules(id) ON DELETE SET NULL,
+    reason TEXT,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    timeout_at TIMESTAMPTZ NOT NULL,
+    decided_at TIMESTAMPTZ,
+
+    CONSTRAINT tool_approvals_status_check
+        CHECK (status IN ('pending', 'approved', 'denied', 'timed_out'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_tool_approvals_execution_process
+    ON tool_approvals(execution_process_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_tool_approvals_pending
+    ON tool_approvals(timeout_at) WHERE status = 'pending';
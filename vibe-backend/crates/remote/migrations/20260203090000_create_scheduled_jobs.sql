@@ -0,0 +1,35 @@
+-- Durable background jobs for the remote service
+--
+-- One-shot jobs are inserted with a run_at and finish as succeeded or failed.
+-- Recurring jobs have a schedule_key and are a single row that is moved to
+-- its next run_at after every run. Workers claim due rows with
+-- FOR UPDATE SKIP LOCKED and hold a lease (locked_until); a row whose lease
+-- expired while running is picked up again by another replica.
+
+CREATE TABLE IF NOT EXISTS scheduled_jobs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- Handler name, e.g. 'due_date_reminders'
+    kind TEXT NOT NULL,
+    payload JSONB NOT NULL DEFAULT '{}',
+    -- Set for recurring jobs only
+    schedule_key TEXT UNIQUE,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    run_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    attempts INT NOT NULL DEFAULT 0,
+    max_attempts INT NOT NULL DEFAULT 5,
+    locked_by TEXT,
+    locked_until TIMESTAMPTZ,
+    last_error TEXT,
+    last_run_at TIMESTAMPTZ,
+    completed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT scheduled_jobs_status_check
+        CHECK (status IN ('pending', 'running', 'succeeded', 'failed'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_scheduled_jobs_due
+    ON scheduled_jobs(run_at) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_scheduled_jobs_running_lease
+    ON scheduled_jobs(locked_until) WHERE status = 'running';
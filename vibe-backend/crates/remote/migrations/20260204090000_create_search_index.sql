@@ -0,0 +1,40 @@
+-- Full-text search over tasks, task comments, documents and project updates
+--
+-- Each searchable table gets a stored tsvector column kept up to date by
+-- Postgres, plus a GIN index. Titles are weighted above bodies so a match in
+-- an issue title ranks ahead of a match deep in a description. Very long
+-- bodies are truncated before indexing to stay under the tsvector size limit.
+
+ALTER TABLE tasks ADD COLUMN IF NOT EXISTS search_vector tsvector
+    GENERATED ALWAYS AS (
+        setweight(to_tsvector('english', coalesce(title, '')), 'A') ||
+        setweight(to_tsvector('english', left(coalesce(description, ''), 200000)), 'B')
+    ) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_tasks_search_vector
+    ON tasks USING GIN (search_vector);
+
+ALTER TABLE task_comments ADD COLUMN IF NOT EXISTS search_vector tsvector
+    GENERATED ALWAYS AS (
+        to_tsvector('english', left(coalesce(content, ''), 200000))
+    ) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_task_comments_search_vector
+    ON task_comments USING GIN (search_vector);
+
+ALTER TABLE documents ADD COLUMN IF NOT EXISTS search_vector tsvector
+    GENERATED ALWAYS AS (
+        setweight(to_tsvector('english', coalesce(title, '')), 'A') ||
+        setweight(to_tsvector('english', left(coalesce(content, ''), 200000)), 'B')
+    ) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_documents_search_vector
+    ON documents USING GIN (search_vector);
+
+ALTER TABLE project_updates ADD COLUMN IF NOT EXISTS search_vector tsvector
+    GENERATED ALWAYS AS (
+        to_tsvector('english', left(coalesce(content, ''), 200000))
+    ) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_project_updates_search_vector
+    ON project_updates USING GIN (search_vector);
@@ -0,0 +1,52 @@
+-- Token and cost accounting for coding agent runs, and per-project budgets
+--
+-- Executors report token usage in their logs; the counts are summed per
+-- execution process and model. task_id and project_id are copied from the
+-- execution context when the first usage arrives so summaries do not depend
+-- on the workspace and session tables. Cost is stored in micro-dollars.
+
+CREATE TABLE IF NOT EXISTS execution_process_usage (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    execution_process_id UUID NOT NULL,
+    coding_agent_turn_id UUID,
+    task_id UUID REFERENCES tasks(id) ON DELETE SET NULL,
+    project_id UUID REFERENCES projects(id) ON DELETE SET NULL,
+    executor TEXT NOT NULL,
+    -- Empty when the agent did not say which model it used
+    model TEXT NOT NULL DEFAULT '',
+    input_tokens BIGINT NOT NULL DEFAULT 0,
+    output_tokens BIGINT NOT NULL DEFAULT 0,
+    cache_read_tokens BIGINT NOT NULL DEFAULT 0,
+    cache_write_tokens BIGINT NOT NULL DEFAULT 0,
+    cost_microdollars BIGINT NOT NULL DEFAULT 0,
+    -- FALSE when the model was missing from the price table and the cost is
+    -- the agent's own estimate, or unknown
+    priced BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT execution_process_usage_process_model_key
+        UNIQUE (execution_process_id, model)
+);
+
+CREATE INDEX IF NOT EXISTS idx_execution_process_usage_task
+    ON execution_process_usage(task_id) WHERE task_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_execution_process_usage_project
+    ON execution_process_usage(project_id, created_at) WHERE project_id IS NOT NULL;
+
+CREATE TABLE IF NOT EXISTS project_budgets (
+    project_id UUID PRIMARY KEY REFERENCES projects(id) ON DELETE CASCADE,
+    limit_microdollars BIGINT NOT NULL,
+    -- 'monthly' resets on the first of each UTC month, 'total' never resets
+    period VARCHAR(16) NOT NULL DEFAULT 'monthly',
+    -- Also stop running attempts once the budget is exceeded, rather than
+    -- only blocking new ones
+    stop_running BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT project_budgets_period_check
+        CHECK (period IN ('monthly', 'total')),
+    CONSTRAINT project_budgets_limit_check
+        CHECK (limit_microdollars >= 0)
+);
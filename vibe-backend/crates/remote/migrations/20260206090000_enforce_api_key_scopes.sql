@@ -0,0 +1,10 @@
+-- API key scopes are now enforced on every route. Keys created before scopes
+-- existed were full-access, so grant them `admin` to keep them working;
+-- owners can reissue them with narrower scopes.
+UPDATE api_keys
+SET scopes = ARRAY['admin']
+WHERE scopes IS NULL OR cardinality(scopes) = 0;
+
+ALTER TABLE api_keys
+    ALTER COLUMN scopes SET DEFAULT ARRAY['tasks:write', 'documents:write', 'projects:read', 'teams:read', 'executions:read'],
+    ALTER COLUMN scopes SET NOT NULL;
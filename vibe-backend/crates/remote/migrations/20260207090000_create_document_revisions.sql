@@ -0,0 +1,32 @@
+-- Content history for documents
+--
+-- Every change to a document's content stores a full snapshot; diffs are
+-- computed when read. The author is the user who made the change and, for
+-- edits made by a coding agent through MCP, the agent's execution process.
+
+CREATE TABLE IF NOT EXISTS document_revisions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    document_id UUID NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+    -- 1-based, per document
+    revision_number INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    content TEXT NOT NULL,
+    -- Content size in bytes
+    content_size BIGINT NOT NULL,
+    author_user_id TEXT,
+    author_execution_process_id UUID,
+    -- Revision number this revision restored, if any
+    restored_from INTEGER,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT document_revisions_document_number_key
+        UNIQUE (document_id, revision_number)
+);
+
+-- Seed history with each document's current content
+INSERT INTO document_revisions
+    (document_id, revision_number, title, content, content_size, author_user_id, created_at)
+SELECT id, 1, title, content, octet_length(content), created_by, updated_at
+FROM documents
+WHERE content IS NOT NULL
+ON CONFLICT DO NOTHING;
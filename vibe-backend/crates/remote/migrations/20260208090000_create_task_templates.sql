@@ -0,0 +1,61 @@
+-- Team task templates and recurring tasks
+--
+-- A template holds the fields of a task to create, with `{{variable}}`
+-- placeholders in the title and description. A recurrence materializes a
+-- task from its template on an RRULE schedule and can auto-start an agent
+-- attempt for it.
+
+CREATE TABLE IF NOT EXISTS task_templates (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    team_id UUID NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+    -- Project tasks created from this template belong to
+    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    title_template TEXT NOT NULL,
+    description_template TEXT,
+    -- 0=none, 1=urgent, 2=high, 3=medium, 4=low
+    priority INTEGER,
+    assignee_id UUID,
+    tag_ids UUID[] NOT NULL DEFAULT '{}',
+    document_ids UUID[] NOT NULL DEFAULT '{}',
+    -- Array of {"title", "description"} created as sub-issues
+    sub_issues JSONB NOT NULL DEFAULT '[]',
+    -- Default values for template variables
+    variables JSONB NOT NULL DEFAULT '{}',
+    created_by TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT task_templates_team_name_key UNIQUE (team_id, name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_templates_team_id ON task_templates(team_id);
+
+CREATE TABLE IF NOT EXISTS task_template_recurrences (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    template_id UUID NOT NULL REFERENCES task_templates(id) ON DELETE CASCADE,
+    -- RRULE subset, e.g. FREQ=WEEKLY;BYDAY=MO;BYHOUR=9
+    rrule TEXT NOT NULL,
+    -- Anchor for INTERVAL and default time of day (DTSTART)
+    starts_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Variable values overriding the template defaults
+    variables JSONB NOT NULL DEFAULT '{}',
+    -- When set, an attempt is started with this profile for each created task
+    executor_profile_id JSONB,
+    -- Array of {"repo_id", "target_branch"} the attempt runs against
+    repos JSONB NOT NULL DEFAULT '[]',
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    -- NULL once the rule has no further occurrences
+    next_run_at TIMESTAMPTZ,
+    last_run_at TIMESTAMPTZ,
+    last_task_id UUID REFERENCES tasks(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_template_recurrences_template_id
+    ON task_template_recurrences(template_id);
+
+CREATE INDEX IF NOT EXISTS idx_task_template_recurrences_due
+    ON task_template_recurrences(next_run_at)
+    WHERE enabled = TRUE AND next_run_at IS NOT NULL;
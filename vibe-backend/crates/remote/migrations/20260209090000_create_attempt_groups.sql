@@ -0,0 +1,37 @@
+-- Parallel attempts of one task, launched together for comparison
+--
+-- Each member is a workspace running its own executor profile in its own
+-- worktree. Once a winner is picked it is merged and the other members are
+-- archived.
+
+CREATE TABLE IF NOT EXISTS attempt_groups (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    task_id UUID NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    -- Shell script run in each worktree to check an attempt's result
+    verification_script TEXT,
+    winner_workspace_id UUID,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    decided_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_attempt_groups_task_id ON attempt_groups(task_id);
+
+CREATE TABLE IF NOT EXISTS attempt_group_members (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    group_id UUID NOT NULL REFERENCES attempt_groups(id) ON DELETE CASCADE,
+    workspace_id UUID NOT NULL UNIQUE,
+    executor_profile_id JSONB NOT NULL,
+    -- active, winner or archived
+    status TEXT NOT NULL DEFAULT 'active'
+        CHECK (status IN ('active', 'winner', 'archived')),
+    -- Result of the latest verification script run
+    verification_passed BOOLEAN,
+    verification_exit_code INTEGER,
+    verification_output TEXT,
+    verification_duration_ms BIGINT,
+    verified_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_attempt_group_members_group_id
+    ON attempt_group_members(group_id);
@@ -0,0 +1,25 @@
+-- Per-project sandbox for executor processes
+--
+-- When enabled, coding agents and scripts run inside a bubblewrap sandbox:
+-- the host filesystem is read-only except for the workspace and the listed
+-- writable paths.
+
+CREATE TABLE IF NOT EXISTS project_sandbox_configs (
+    project_id UUID PRIMARY KEY REFERENCES projects(id) ON DELETE CASCADE,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    allow_network BOOLEAN NOT NULL DEFAULT FALSE,
+    memory_limit_mb INTEGER,
+    cpu_limit_percent INTEGER,
+    -- Host environment variables passed into the sandbox; NULL passes all
+    env_allowlist TEXT[],
+    -- Extra read-write paths besides the workspace; `~/` expands to the
+    -- server user's home directory
+    writable_paths TEXT[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT project_sandbox_configs_memory_check
+        CHECK (memory_limit_mb IS NULL OR memory_limit_mb > 0),
+    CONSTRAINT project_sandbox_configs_cpu_check
+        CHECK (cpu_limit_percent IS NULL OR cpu_limit_percent > 0)
+);
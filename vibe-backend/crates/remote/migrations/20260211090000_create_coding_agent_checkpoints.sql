@@ -0,0 +1,18 @@
+-- Per-repository checkpoints taken before each coding agent follow-up
+--
+-- Uncommitted changes are committed first, then a ref pointing at the
+-- checkpoint commit keeps it reachable after the branch is rolled back.
+
+CREATE TABLE IF NOT EXISTS coding_agent_checkpoints (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    execution_process_id UUID NOT NULL,
+    repo_id UUID NOT NULL,
+    commit_sha TEXT NOT NULL,
+    ref_name TEXT NOT NULL,
+    -- Whether uncommitted changes were committed to take the checkpoint
+    committed_changes BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT coding_agent_checkpoints_process_repo_unique
+        UNIQUE (execution_process_id, repo_id)
+);
@@ -0,0 +1,18 @@
+-- Normalized conversation of each coding agent execution, kept after the
+-- in-memory log store is dropped
+--
+-- Rows older than the configured retention are purged periodically.
+
+CREATE TABLE IF NOT EXISTS execution_process_transcripts (
+    execution_process_id UUID PRIMARY KEY,
+    executor_type TEXT NOT NULL,
+    entry_count INTEGER NOT NULL,
+    -- Size of the serialized conversation before compression
+    byte_size BIGINT NOT NULL,
+    -- Gzip-compressed JSON of the normalized conversation
+    data BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_execution_process_transcripts_created_at
+    ON execution_process_transcripts(created_at);
@@ -0,0 +1,27 @@
+-- Attempts waiting for a free execution slot
+--
+-- Attempts started while a concurrency cap is reached wait here, ordered by
+-- position. New entries are placed after every entry of equal or higher task
+-- priority; users may reorder the queue afterwards.
+
+CREATE TABLE IF NOT EXISTS queued_attempts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    workspace_id UUID NOT NULL UNIQUE,
+    task_id UUID NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    project_id UUID NOT NULL,
+    executor_profile_id JSONB NOT NULL,
+    -- Task priority when queued, see TaskPriority
+    priority INTEGER NOT NULL DEFAULT 0,
+    position INTEGER NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_queued_attempts_position ON queued_attempts(position);
+CREATE INDEX IF NOT EXISTS idx_queued_attempts_task_id ON queued_attempts(task_id);
+
+-- Task cards show whether an attempt is queued
+DROP TRIGGER IF EXISTS trg_queued_attempts_notify_change ON queued_attempts;
+CREATE TRIGGER trg_queued_attempts_notify_change
+    AFTER INSERT OR UPDATE OR DELETE ON queued_attempts
+    FOR EACH ROW
+    EXECUTE FUNCTION notify_hook_change();
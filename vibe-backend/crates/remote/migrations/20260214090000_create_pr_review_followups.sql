@@ -0,0 +1,26 @@
+-- Agent follow-ups that address pull request review comments
+--
+-- Each row records which comments one coding agent follow-up was asked to
+-- address. When the follow-up finishes the branch is pushed and every
+-- addressed thread gets a reply linking the resulting commit.
+
+CREATE TABLE IF NOT EXISTS pr_review_followups (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    workspace_id UUID NOT NULL,
+    repo_id UUID NOT NULL,
+    execution_process_id UUID NOT NULL UNIQUE,
+    pr_number BIGINT NOT NULL,
+    pr_url TEXT NOT NULL,
+    -- Addressed comments, see ReviewCommentRef
+    comments JSONB NOT NULL DEFAULT '[]',
+    status TEXT NOT NULL DEFAULT 'running'
+        CHECK (status IN ('running', 'replied', 'no_changes', 'failed')),
+    -- Commit the replies link to
+    commit_sha TEXT,
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_pr_review_followups_workspace_id
+    ON pr_review_followups(workspace_id);
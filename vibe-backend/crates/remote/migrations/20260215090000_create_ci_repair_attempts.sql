@@ -0,0 +1,31 @@
+-- Agent follow-ups that repair failing CI on an attempt's pull request
+--
+-- One row per failing head commit, so the check_suite and workflow_run
+-- webhooks for the same failure start a single repair. Rows with status
+-- 'escalated' record that the PR ran out of repairs and the failure was
+-- handed to the inbox. Only rows the agent was started for count against
+-- the PR's budget.
+
+CREATE TABLE IF NOT EXISTS ci_repair_attempts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    workspace_id UUID NOT NULL,
+    repo_id UUID NOT NULL,
+    pr_number BIGINT NOT NULL,
+    -- Commit CI failed on
+    head_sha TEXT NOT NULL,
+    -- Set once the follow-up is started
+    execution_process_id UUID UNIQUE,
+    -- Names of the failed jobs handed to the agent
+    failed_jobs JSONB NOT NULL DEFAULT '[]',
+    status TEXT NOT NULL DEFAULT 'running'
+        CHECK (status IN ('running', 'pushed', 'no_changes', 'failed', 'escalated')),
+    -- Commit the repair pushed
+    commit_sha TEXT,
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ,
+    UNIQUE (workspace_id, repo_id, head_sha)
+);
+
+CREATE INDEX IF NOT EXISTS idx_ci_repair_attempts_workspace_id
+    ON ci_repair_attempts(workspace_id);
@@ -0,0 +1,55 @@
+-- Merge queue for landing attempts on a shared target branch
+--
+-- Entries of the same repository and target branch form a lane and are
+-- landed one at a time in queue order: the attempt is rebased onto the
+-- current tip, the repository's verification script runs in its worktree,
+-- and the branch is squashed or fast-forwarded in only if the script passes.
+
+CREATE TABLE IF NOT EXISTS merge_queue_configs (
+    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    repo_id UUID NOT NULL,
+    -- Run with the shell in the rebased worktree; a non-zero exit ejects
+    verification_script TEXT,
+    verification_timeout_secs INTEGER NOT NULL DEFAULT 1800,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (project_id, repo_id),
+
+    CONSTRAINT merge_queue_configs_timeout_check
+        CHECK (verification_timeout_secs > 0)
+);
+
+CREATE TABLE IF NOT EXISTS merge_queue_entries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    workspace_id UUID NOT NULL,
+    repo_id UUID NOT NULL,
+    task_id UUID NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    project_id UUID NOT NULL,
+    target_branch TEXT NOT NULL,
+    strategy TEXT NOT NULL DEFAULT 'squash'
+        CHECK (strategy IN ('squash', 'fast_forward')),
+    status TEXT NOT NULL DEFAULT 'queued'
+        CHECK (status IN ('queued', 'rebasing', 'verifying', 'merged', 'ejected')),
+    merge_commit_sha TEXT,
+    eject_reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+-- An attempt's repository is in a lane at most once at a time
+CREATE UNIQUE INDEX IF NOT EXISTS idx_merge_queue_entries_active
+    ON merge_queue_entries(workspace_id, repo_id)
+    WHERE status IN ('queued', 'rebasing', 'verifying');
+
+CREATE INDEX IF NOT EXISTS idx_merge_queue_entries_lane
+    ON merge_queue_entries(repo_id, target_branch, created_at);
+CREATE INDEX IF NOT EXISTS idx_merge_queue_entries_project_id
+    ON merge_queue_entries(project_id);
+
+-- Queue state is streamed to clients
+DROP TRIGGER IF EXISTS trg_merge_queue_entries_notify_change ON merge_queue_entries;
+CREATE TRIGGER trg_merge_queue_entries_notify_change
+    AFTER INSERT OR UPDATE OR DELETE ON merge_queue_entries
+    FOR EACH ROW
+    EXECUTE FUNCTION notify_hook_change();
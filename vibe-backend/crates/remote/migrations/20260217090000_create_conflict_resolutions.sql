@@ -0,0 +1,24 @@
+-- Agent follow-ups that resolve the conflicts of a stopped rebase or merge
+--
+-- Each row records one coding agent follow-up started in a conflicted
+-- worktree. When the follow-up finishes the files are checked for leftover
+-- conflict markers and, if none remain, the operation is continued.
+
+CREATE TABLE IF NOT EXISTS conflict_resolutions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    workspace_id UUID NOT NULL,
+    repo_id UUID NOT NULL,
+    execution_process_id UUID NOT NULL UNIQUE,
+    -- Files that were conflicted when the follow-up started
+    conflicted_files JSONB NOT NULL DEFAULT '[]',
+    status TEXT NOT NULL DEFAULT 'running'
+        CHECK (status IN ('running', 'resolved', 'unresolved', 'failed')),
+    -- Files still conflicted when the follow-up finished
+    unresolved_files JSONB NOT NULL DEFAULT '[]',
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_conflict_resolutions_workspace_id
+    ON conflict_resolutions(workspace_id);
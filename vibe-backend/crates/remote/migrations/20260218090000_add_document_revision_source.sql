@@ -0,0 +1,9 @@
+-- Record which scan or sync made a revision
+--
+-- Filesystem scans, GitHub syncs and cloud storage pulls change content
+-- without a user edit; they are recorded with their source and, when a user
+-- started them, that user.
+
+ALTER TABLE document_revisions
+    ADD COLUMN IF NOT EXISTS author_source TEXT
+        CHECK (author_source IN ('filesystem_scan', 'github_sync', 'cloud_storage_sync'));
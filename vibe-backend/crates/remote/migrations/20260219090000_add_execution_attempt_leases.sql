@@ -0,0 +1,12 @@
+-- Lease running execution attempts
+--
+-- A worker renews locked_until while an attempt runs. Once it lapses, any
+-- replica may fail the attempt and retry its execution, so attempts are not
+-- stranded when their worker dies or comes back under another id.
+
+ALTER TABLE execution_attempts
+    ADD COLUMN IF NOT EXISTS locked_until TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_execution_attempts_running_lease
+    ON execution_attempts(locked_until)
+    WHERE status = 'running';
@@ -0,0 +1,5 @@
+-- Store webhook delivery statuses as TEXT, like the other status columns, so
+-- they decode straight into WebhookDeliveryStatus
+
+ALTER TABLE github_webhook_deliveries
+    ALTER COLUMN status TYPE TEXT;
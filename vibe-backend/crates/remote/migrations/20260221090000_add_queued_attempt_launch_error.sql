@@ -0,0 +1,8 @@
+-- Record why a queued attempt failed to start
+--
+-- A queued attempt leaves the queue only once it starts. When starting it
+-- fails, the row stays with the error so the failure is visible, and the
+-- scheduler skips it until it is moved, which retries it, or removed.
+
+ALTER TABLE queued_attempts
+    ADD COLUMN IF NOT EXISTS launch_error TEXT;
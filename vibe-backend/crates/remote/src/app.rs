@@ -12,8 +12,10 @@ use crate::{
     },
     config::RemoteServerConfig,
     db,
+    execution::{ExecutionWorker, ProviderRunner},
     github_app::GitHubAppService,
-    mail::{LoopsMailer, Mailer, NoOpMailer},
+    jobs,
+    mail::{self, templates::EmailTemplates},
     r2::R2Service,
     routes,
     stripe::StripeService,
@@ -77,14 +79,11 @@ impl Server {
         let oauth_token_validator =
             Arc::new(OAuthTokenValidator::new(pool.clone(), registry.clone()));
 
-        // Use real mailer if API key is set, otherwise use no-op mailer
-        let mailer: Arc<dyn Mailer> = match std::env::var("LOOPS_EMAIL_API_KEY") {
-            Ok(api_key) => Arc::new(LoopsMailer::new(api_key)),
-            Err(_) => {
-                tracing::warn!("LOOPS_EMAIL_API_KEY not set, using NoOpMailer");
-                Arc::new(NoOpMailer::new())
-            }
-        };
+        // Local templates back the SMTP and file transports and the admin
+        // preview; Loops uses its hosted templates
+        let email_templates = Arc::new(EmailTemplates::from_env());
+        let mailer = mail::build_mailer(&config.mail, email_templates.clone())
+            .context("failed to configure mailer")?;
 
         let server_public_base_url = config.server_public_base_url.clone().ok_or_else(|| {
             anyhow::anyhow!(
@@ -145,6 +144,32 @@ impl Server {
         let clerk_auth = Arc::new(ClerkAuthState::new());
         tracing::info!("Clerk authentication service initialized");
 
+        // Cloud AI execution worker (IKA-248); disable on API-only replicas
+        if std::env::var("EXECUTION_WORKER_ENABLED").is_ok_and(|v| v == "false" || v == "0") {
+            tracing::info!("Execution worker disabled via EXECUTION_WORKER_ENABLED");
+        } else {
+            let worker_id = std::env::var("EXECUTION_WORKER_ID")
+                .or_else(|_| std::env::var("HOSTNAME"))
+                .unwrap_or_else(|_| "remote".to_string());
+            ExecutionWorker::new(
+                pool.clone(),
+                Arc::new(ProviderRunner::new(http_client.clone())),
+                worker_id,
+            )
+            .with_region(std::env::var("EXECUTION_WORKER_REGION").ok())
+            .spawn();
+        }
+
+        // Durable background jobs: cycle rollover, due date reminders, expiry
+        // and cleanup. Leases keep replicas from running the same job twice.
+        if std::env::var("JOB_SCHEDULER_ENABLED").is_ok_and(|v| v == "false" || v == "0") {
+            tracing::info!("Job scheduler disabled via JOB_SCHEDULER_ENABLED");
+        } else {
+            let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "remote".to_string());
+            let worker_id = format!("{host}-{}", uuid::Uuid::new_v4());
+            jobs::default_scheduler(pool.clone(), worker_id).spawn();
+        }
+
         let state = AppState::new(
             pool.clone(),
             config.clone(),
@@ -152,6 +177,7 @@ impl Server {
             handoff_service,
             oauth_token_validator,
             mailer,
+            email_templates,
             server_public_base_url,
             http_client,
             r2,
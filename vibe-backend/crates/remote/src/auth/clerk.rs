@@ -13,6 +13,7 @@ use axum::{
     response::{IntoResponse, Response},
 };
 use axum_extra::headers::{Authorization, HeaderMapExt, authorization::Bearer};
+use db_crate::models::api_key::ApiKeyScopes;
 use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header, jwk::JwkSet};
 use reqwest::Client;
 use serde::Deserialize;
@@ -245,9 +246,9 @@ pub async fn require_clerk_session(
     if bearer.starts_with("vk_") {
         debug!("Authenticating with API key");
 
-        // Validate API key and get user_id
-        let user_id = match ApiKeyRepository::validate_key(pool, &bearer).await {
-            Ok(Some(user_id)) => user_id,
+        // Validate API key and get user_id and scopes
+        let api_key = match ApiKeyRepository::validate_key(pool, &bearer).await {
+            Ok(Some(api_key)) => api_key,
             Ok(None) => {
                 warn!("Invalid or expired API key");
                 return StatusCode::UNAUTHORIZED.into_response();
@@ -258,6 +259,8 @@ pub async fn require_clerk_session(
             }
         };
 
+        let user_id = api_key.user_id;
+
         // Parse user_id as UUID and fetch user
         let user_uuid = match Uuid::parse_str(&user_id) {
             Ok(id) => id,
@@ -297,6 +300,7 @@ pub async fn require_clerk_session(
             access_token_expires_at: chrono::Utc::now() + chrono::Duration::hours(24),
         };
         req.extensions_mut().insert(legacy_ctx);
+        req.extensions_mut().insert(ApiKeyScopes(api_key.scopes));
 
         return next.run(req).await;
     }
@@ -1,4 +1,4 @@
-use std::env;
+use std::{env, path::PathBuf};
 
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64_STANDARD};
 use secrecy::SecretString;
@@ -17,6 +17,7 @@ pub struct RemoteServerConfig {
     pub review_worker_base_url: Option<String>,
     pub github_app: Option<GitHubAppConfig>,
     pub stripe: Option<StripeConfig>,
+    pub mail: MailConfig,
 }
 
 #[derive(Debug, Clone)]
@@ -166,6 +167,121 @@ impl StripeConfig {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpSecurity {
+    /// Implicit TLS, usually port 465
+    Tls,
+    /// Plain connection upgraded with STARTTLS, usually port 587
+    StartTls,
+    /// No encryption, for local relays and mail catchers only
+    None,
+}
+
+#[derive(Debug, Clone)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<SecretString>,
+    pub security: SmtpSecurity,
+}
+
+impl SmtpConfig {
+    fn from_env() -> Result<Self, ConfigError> {
+        let host = non_empty_var("SMTP_HOST").ok_or(ConfigError::MissingVar("SMTP_HOST"))?;
+
+        let security = match non_empty_var("SMTP_SECURITY").as_deref() {
+            Some("tls") => SmtpSecurity::Tls,
+            Some("starttls") | None => SmtpSecurity::StartTls,
+            Some("none") => SmtpSecurity::None,
+            Some(_) => return Err(ConfigError::InvalidVar("SMTP_SECURITY")),
+        };
+
+        let port = match non_empty_var("SMTP_PORT") {
+            Some(port) => port
+                .parse()
+                .map_err(|_| ConfigError::InvalidVar("SMTP_PORT"))?,
+            None => match security {
+                SmtpSecurity::Tls => 465,
+                SmtpSecurity::StartTls => 587,
+                SmtpSecurity::None => 25,
+            },
+        };
+
+        Ok(Self {
+            host,
+            port,
+            username: non_empty_var("SMTP_USERNAME"),
+            password: non_empty_var("SMTP_PASSWORD").map(|s| SecretString::new(s.into())),
+            security,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum MailTransportConfig {
+    /// Loops.so hosted templates
+    Loops { api_key: SecretString },
+    /// Local templates sent over SMTP
+    Smtp(SmtpConfig),
+    /// Local templates written to a directory as JSON (dev and tests)
+    File { dir: PathBuf },
+    /// Log emails without sending them
+    Disabled,
+}
+
+#[derive(Debug, Clone)]
+pub struct MailConfig {
+    pub transport: MailTransportConfig,
+    /// Sender for SMTP and file transports
+    pub from: String,
+}
+
+impl MailConfig {
+    /// `MAIL_TRANSPORT` picks `loops`, `smtp`, `file` or `none`. When unset,
+    /// Loops is used if `LOOPS_EMAIL_API_KEY` is set, then SMTP if
+    /// `SMTP_HOST` is set, otherwise email is disabled.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let loops_key = non_empty_var("LOOPS_EMAIL_API_KEY");
+        let kind = match non_empty_var("MAIL_TRANSPORT") {
+            Some(kind) => kind.to_lowercase(),
+            None if loops_key.is_some() => "loops".to_string(),
+            None if non_empty_var("SMTP_HOST").is_some() => "smtp".to_string(),
+            None => "none".to_string(),
+        };
+
+        let transport = match kind.as_str() {
+            "loops" => MailTransportConfig::Loops {
+                api_key: loops_key
+                    .map(|s| SecretString::new(s.into()))
+                    .ok_or(ConfigError::MissingVar("LOOPS_EMAIL_API_KEY"))?,
+            },
+            "smtp" => MailTransportConfig::Smtp(SmtpConfig::from_env()?),
+            "file" => MailTransportConfig::File {
+                dir: non_empty_var("MAIL_FILE_DIR")
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|| env::temp_dir().join("ikanban-mail")),
+            },
+            "none" => {
+                tracing::warn!("No mail transport configured, emails will only be logged");
+                MailTransportConfig::Disabled
+            }
+            _ => return Err(ConfigError::InvalidVar("MAIL_TRANSPORT")),
+        };
+
+        let from =
+            non_empty_var("MAIL_FROM").unwrap_or_else(|| "iKanban <noreply@localhost>".to_string());
+
+        Ok(Self { transport, from })
+    }
+}
+
+/// Read an env var, treating an empty value (as docker-compose passes for
+/// unset optional vars) as unset
+fn non_empty_var(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|value| !value.is_empty())
+}
+
 #[derive(Debug, Error)]
 pub enum ConfigError {
     #[error("environment variable `{0}` is not set")]
@@ -218,6 +334,8 @@ impl RemoteServerConfig {
 
         let stripe = StripeConfig::from_env()?;
 
+        let mail = MailConfig::from_env()?;
+
         Ok(Self {
             database_url,
             listen_addr,
@@ -230,6 +348,7 @@ impl RemoteServerConfig {
             review_worker_base_url,
             github_app,
             stripe,
+            mail,
         })
     }
 }
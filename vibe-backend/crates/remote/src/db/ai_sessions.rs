@@ -370,4 +370,18 @@ impl AiSessionRepository {
 
         Ok(result.rows_affected() > 0)
     }
+
+    /// Permanently remove sessions soft-deleted before `before`, along with
+    /// their messages
+    pub async fn purge_deleted(
+        pool: &PgPool,
+        before: DateTime<Utc>,
+    ) -> Result<u64, AiSessionError> {
+        let result = sqlx::query("DELETE FROM ai_sessions WHERE deleted_at < $1")
+            .bind(before)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
 }
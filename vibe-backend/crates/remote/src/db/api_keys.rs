@@ -1,6 +1,7 @@
 //! API keys database operations
 
 use chrono::{DateTime, Utc};
+use db_crate::models::api_key::ApiScope;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use sqlx::PgPool;
@@ -27,6 +28,7 @@ pub struct ApiKeyWithSecret {
     pub name: String,
     pub key_prefix: String,
     pub key: String,
+    pub scopes: Vec<String>,
     pub expires_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
 }
@@ -36,6 +38,16 @@ pub struct ApiKeyWithSecret {
 pub struct CreateApiKeyRequest {
     pub name: String,
     pub expires_in_days: Option<i64>,
+    /// Scopes to grant (default: `ApiScope::DEFAULT`)
+    #[serde(default)]
+    pub scopes: Option<Vec<ApiScope>>,
+}
+
+/// Owner and scopes of a valid API key
+#[derive(Debug, Clone)]
+pub struct ValidatedApiKey {
+    pub user_id: String,
+    pub scopes: Vec<ApiScope>,
 }
 
 #[derive(Debug, Error)]
@@ -122,16 +134,25 @@ impl ApiKeyRepository {
             .expires_in_days
             .map(|days| Utc::now() + chrono::Duration::days(days));
 
+        let scopes: Vec<String> = request
+            .scopes
+            .as_deref()
+            .unwrap_or(ApiScope::DEFAULT)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
         let row = sqlx::query!(
             r#"
-            INSERT INTO api_keys (user_id, name, key_prefix, key_hash, expires_at)
-            VALUES ($1, $2, $3, $4, $5)
-            RETURNING id, name, key_prefix, expires_at, created_at
+            INSERT INTO api_keys (user_id, name, key_prefix, key_hash, scopes, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, name, key_prefix, scopes, expires_at, created_at
             "#,
             user_id,
             request.name,
             prefix,
             hash,
+            &scopes,
             expires_at
         )
         .fetch_one(pool)
@@ -142,6 +163,7 @@ impl ApiKeyRepository {
             name: row.name,
             key_prefix: row.key_prefix,
             key: full_key,
+            scopes: row.scopes,
             expires_at: row.expires_at,
             created_at: row.created_at,
         })
@@ -177,33 +199,30 @@ impl ApiKeyRepository {
         Ok(result.rows_affected() > 0)
     }
 
-    /// Validate an API key and return the user_id if valid
-    pub async fn validate_key(pool: &PgPool, key: &str) -> Result<Option<String>, ApiKeyError> {
+    /// Validate an API key and return its owner and scopes if valid
+    pub async fn validate_key(
+        pool: &PgPool,
+        key: &str,
+    ) -> Result<Option<ValidatedApiKey>, ApiKeyError> {
         let hash = Self::hash_key(key);
 
         let row = sqlx::query!(
             r#"
-            SELECT user_id
-            FROM api_keys
+            UPDATE api_keys
+            SET last_used_at = NOW()
             WHERE key_hash = $1
               AND is_revoked = FALSE
               AND (expires_at IS NULL OR expires_at > NOW())
+            RETURNING user_id, scopes
             "#,
             hash
         )
         .fetch_optional(pool)
         .await?;
 
-        if row.is_some() {
-            // Update last_used_at
-            sqlx::query!(
-                "UPDATE api_keys SET last_used_at = NOW() WHERE key_hash = $1",
-                hash
-            )
-            .execute(pool)
-            .await?;
-        }
-
-        Ok(row.map(|r| r.user_id))
+        Ok(row.map(|r| ValidatedApiKey {
+            user_id: r.user_id,
+            scopes: ApiScope::parse_list(&r.scopes),
+        }))
     }
 }
@@ -1,8 +1,9 @@
 //! Document database operations
 
 use chrono::{DateTime, Utc};
+use db_crate::models::document_revision::{DocumentRevision, RevisionAuthor};
 use serde::{Deserialize, Serialize};
-use sqlx::PgPool;
+use sqlx::{Executor, PgPool, Postgres};
 use thiserror::Error;
 use uuid::Uuid;
 
@@ -298,7 +299,10 @@ impl DocumentRepository {
     }
 
     /// Find document by ID
-    pub async fn find_by_id(pool: &PgPool, id: Uuid) -> Result<Option<Document>, DocumentError> {
+    pub async fn find_by_id<'e, E>(executor: E, id: Uuid) -> Result<Option<Document>, DocumentError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
         let row = sqlx::query!(
             r#"
             SELECT id, team_id, folder_id, title, slug, content, file_path, file_type,
@@ -308,7 +312,7 @@ impl DocumentRepository {
             "#,
             id
         )
-        .fetch_optional(pool)
+        .fetch_optional(executor)
         .await?;
 
         Ok(row.map(|r| Document {
@@ -336,11 +340,12 @@ impl DocumentRepository {
         }))
     }
 
-    /// Create a new document
+    /// Create a new document, recording its initial content as revision 1
     pub async fn create(
         pool: &PgPool,
         team_id: Uuid,
         data: &CreateDocument,
+        author: &RevisionAuthor,
     ) -> Result<Document, DocumentError> {
         let file_type = data
             .file_type
@@ -361,6 +366,7 @@ impl DocumentRepository {
         .await?
         .unwrap_or(0) as i32;
 
+        let mut tx = pool.begin().await?;
         let row = sqlx::query!(
             r#"
             INSERT INTO documents (team_id, folder_id, title, slug, content, file_type, icon,
@@ -384,9 +390,14 @@ impl DocumentRepository {
             storage_provider,
             data.storage_key
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *tx)
         .await?;
 
+        if let Some(content) = &row.content {
+            DocumentRevision::record(&mut *tx, row.id, &row.title, content, author, None).await?;
+        }
+        tx.commit().await?;
+
         Ok(Document {
             id: row.id,
             team_id: row.team_id,
@@ -412,13 +423,22 @@ impl DocumentRepository {
         })
     }
 
-    /// Update a document
+    /// Update a document, recording a revision when its content changes
     pub async fn update(
         pool: &PgPool,
         id: Uuid,
         data: &UpdateDocument,
+        author: &RevisionAuthor,
     ) -> Result<Document, DocumentError> {
-        let existing = Self::find_by_id(pool, id)
+        // Lock the row before reading it so concurrent updates apply in turn
+        // instead of both merging onto the same old content
+        let mut tx = pool.begin().await?;
+        sqlx::query("SELECT id FROM documents WHERE id = $1 FOR UPDATE")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(DocumentError::NotFound)?;
+        let existing = Self::find_by_id(&mut *tx, id)
             .await?
             .ok_or(DocumentError::NotFound)?;
 
@@ -429,6 +449,7 @@ impl DocumentRepository {
         } else {
             existing.slug
         };
+        let content_changed = data.content.is_some() && data.content != existing.content;
         let content = data.content.clone().or(existing.content);
         let icon = data.icon.clone().or(existing.icon);
         let is_pinned = data.is_pinned.unwrap_or(existing.is_pinned);
@@ -455,9 +476,16 @@ impl DocumentRepository {
             is_archived,
             position
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *tx)
         .await?;
 
+        if let Some(content) = &row.content
+            && content_changed
+        {
+            DocumentRevision::record(&mut *tx, row.id, &row.title, content, author, None).await?;
+        }
+        tx.commit().await?;
+
         Ok(Document {
             id: row.id,
             team_id: row.team_id,
@@ -492,3 +520,81 @@ impl DocumentRepository {
         Ok(result.rows_affected() > 0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RUNBOOK: Uuid = Uuid::from_u128(0xd1);
+
+    fn edit(title: Option<&str>, content: Option<&str>) -> UpdateDocument {
+        UpdateDocument {
+            folder_id: None,
+            title: title.map(str::to_string),
+            content: content.map(str::to_string),
+            icon: None,
+            is_pinned: None,
+            is_archived: None,
+            position: None,
+        }
+    }
+
+    #[ignore = "requires DATABASE_URL"]
+    #[sqlx::test(
+        migrations = false,
+        fixtures(
+            "../../tests/fixtures/documents_seed.sql",
+            "../../migrations/20260207090000_create_document_revisions.sql",
+            "../../migrations/20260218090000_add_document_revision_source.sql"
+        )
+    )]
+    async fn concurrent_updates_keep_each_others_changes(pool: PgPool) {
+        let author = RevisionAuthor::user("user-2");
+        let retitle = edit(Some("On-call runbook"), None);
+        let rewrite = edit(None, Some("# Runbook\n\nPage the owner first."));
+
+        let (retitled, rewritten) = tokio::join!(
+            DocumentRepository::update(&pool, RUNBOOK, &retitle, &author),
+            DocumentRepository::update(&pool, RUNBOOK, &rewrite, &author),
+        );
+        retitled.unwrap();
+        rewritten.unwrap();
+
+        let document = DocumentRepository::find_by_id(&pool, RUNBOOK)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(document.title, "On-call runbook");
+        assert_eq!(
+            document.content.as_deref(),
+            Some("# Runbook\n\nPage the owner first.")
+        );
+
+        let revisions = DocumentRevision::list_by_document(&pool, RUNBOOK)
+            .await
+            .unwrap();
+        let numbers: Vec<i32> = revisions.iter().map(|r| r.revision_number).collect();
+        assert_eq!(numbers, [2, 1]);
+        assert_eq!(revisions[0].author_user_id.as_deref(), Some("user-2"));
+    }
+
+    #[ignore = "requires DATABASE_URL"]
+    #[sqlx::test(
+        migrations = false,
+        fixtures(
+            "../../tests/fixtures/documents_seed.sql",
+            "../../migrations/20260207090000_create_document_revisions.sql",
+            "../../migrations/20260218090000_add_document_revision_source.sql"
+        )
+    )]
+    async fn updating_a_missing_document_is_not_found(pool: PgPool) {
+        let result = DocumentRepository::update(
+            &pool,
+            Uuid::from_u128(0xd2),
+            &edit(Some("Gone"), None),
+            &RevisionAuthor::user("user-2"),
+        )
+        .await;
+        assert!(matches!(result, Err(DocumentError::NotFound)));
+    }
+}
@@ -22,6 +22,19 @@ pub enum AttemptStatus {
     Timeout,
 }
 
+impl AttemptStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Running => "running",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+            Self::Timeout => "timeout",
+        }
+    }
+}
+
 /// Execution attempt record
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, TS)]
 #[ts(export)]
@@ -77,6 +90,14 @@ pub enum ExecutionAttemptError {
     Database(#[from] sqlx::Error),
 }
 
+const EXECUTION_ATTEMPT_COLUMNS: &str = r#"
+    id, execution_id, attempt_number, status,
+    worker_id, worker_region, exit_code, error_message,
+    ai_model, ai_provider,
+    input_tokens, output_tokens, cache_read_tokens, cache_write_tokens,
+    created_at, started_at, completed_at
+"#;
+
 pub struct ExecutionAttemptRepository;
 
 impl ExecutionAttemptRepository {
@@ -357,6 +378,124 @@ impl ExecutionAttemptRepository {
 
         Ok(summary)
     }
+
+    /// Close out an attempt with a terminal status other than completed/failed
+    pub async fn mark_finished(
+        pool: &PgPool,
+        id: Uuid,
+        status: AttemptStatus,
+        error_message: Option<&str>,
+    ) -> Result<ExecutionAttempt, ExecutionAttemptError> {
+        let query = format!(
+            r#"
+            UPDATE execution_attempts
+            SET status = $2, completed_at = NOW(), error_message = COALESCE($3, error_message)
+            WHERE id = $1
+            RETURNING {EXECUTION_ATTEMPT_COLUMNS}
+            "#
+        );
+        sqlx::query_as::<_, ExecutionAttempt>(&query)
+            .bind(id)
+            .bind(status.as_str())
+            .bind(error_message)
+            .fetch_optional(pool)
+            .await?
+            .ok_or(ExecutionAttemptError::NotFound)
+    }
+
+    /// Record which provider and model an attempt ended up using
+    pub async fn set_model(
+        pool: &PgPool,
+        id: Uuid,
+        ai_provider: &str,
+        ai_model: &str,
+    ) -> Result<(), ExecutionAttemptError> {
+        sqlx::query("UPDATE execution_attempts SET ai_provider = $2, ai_model = $3 WHERE id = $1")
+            .bind(id)
+            .bind(ai_provider)
+            .bind(ai_model)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Mark attempt as running and lease it for `lease_seconds`
+    pub async fn start_leased(
+        pool: &PgPool,
+        id: Uuid,
+        lease_seconds: i64,
+    ) -> Result<ExecutionAttempt, ExecutionAttemptError> {
+        let query = format!(
+            r#"
+            UPDATE execution_attempts
+            SET status = 'running',
+                started_at = NOW(),
+                locked_until = NOW() + make_interval(secs => $2)
+            WHERE id = $1
+            RETURNING {EXECUTION_ATTEMPT_COLUMNS}
+            "#
+        );
+        sqlx::query_as::<_, ExecutionAttempt>(&query)
+            .bind(id)
+            .bind(lease_seconds as f64)
+            .fetch_optional(pool)
+            .await?
+            .ok_or(ExecutionAttemptError::NotFound)
+    }
+
+    /// Extend the lease of a running attempt. Returns false if the attempt
+    /// is no longer running, e.g. because another worker reclaimed it.
+    pub async fn renew_lease(
+        pool: &PgPool,
+        id: Uuid,
+        lease_seconds: i64,
+    ) -> Result<bool, ExecutionAttemptError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE execution_attempts
+            SET locked_until = NOW() + make_interval(secs => $2)
+            WHERE id = $1 AND status = 'running'
+            "#,
+        )
+        .bind(id)
+        .bind(lease_seconds as f64)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Fail every running attempt whose lease ran out and return them.
+    ///
+    /// Attempts started before leases existed have none and count as
+    /// expired. `SKIP LOCKED` lets replicas reclaim concurrently without
+    /// handing the same attempt to two of them.
+    pub async fn reclaim_expired(
+        pool: &PgPool,
+        error_message: &str,
+    ) -> Result<Vec<ExecutionAttempt>, ExecutionAttemptError> {
+        let query = format!(
+            r#"
+            UPDATE execution_attempts
+            SET status = 'failed',
+                completed_at = NOW(),
+                error_message = $1,
+                locked_until = NULL
+            WHERE id IN (
+                SELECT id FROM execution_attempts
+                WHERE status = 'running'
+                  AND (locked_until IS NULL OR locked_until < NOW())
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING {EXECUTION_ATTEMPT_COLUMNS}
+            "#
+        );
+        let attempts = sqlx::query_as::<_, ExecutionAttempt>(&query)
+            .bind(error_message)
+            .fetch_all(pool)
+            .await?;
+        Ok(attempts)
+    }
 }
 
 /// Token usage summary
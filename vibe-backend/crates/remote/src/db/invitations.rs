@@ -34,6 +34,21 @@ impl<'a> InvitationRepository<'a> {
         Self { pool }
     }
 
+    /// Mark pending invitations past their expiry as expired
+    pub async fn expire_stale(&self) -> Result<u64, IdentityError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE organization_invitations
+            SET status = 'expired'
+            WHERE status = 'pending' AND expires_at < NOW()
+            "#,
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
     pub async fn create_invitation(
         &self,
         organization_id: Uuid,
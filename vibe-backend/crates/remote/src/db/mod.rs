@@ -28,6 +28,7 @@ pub mod projects;
 pub mod pulse;
 pub mod repos;
 pub mod reviews;
+pub mod scheduled_jobs;
 pub mod subscriptions;
 pub mod superadmins;
 pub mod tags;
@@ -36,6 +37,7 @@ pub mod task_document_links;
 pub mod task_executions;
 pub mod task_tags;
 pub mod tasks;
+pub mod team_cycles;
 pub mod teams;
 pub mod users;
 
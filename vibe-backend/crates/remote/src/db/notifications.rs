@@ -3,7 +3,8 @@
 //! This module provides helper functions that are called when various events occur
 //! (task assignment, comments, status changes, etc.) to create inbox notifications.
 
-use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgPool};
 use uuid::Uuid;
 
 use super::inbox::{CreateInboxItem, InboxNotificationType, InboxRepository};
@@ -251,6 +252,49 @@ pub async fn notify_due_date_approaching(
     Ok(())
 }
 
+/// An assigned, unfinished task whose due date falls inside the reminder window
+#[derive(Debug, Clone, FromRow)]
+pub struct DueTaskReminder {
+    pub task_id: Uuid,
+    pub title: String,
+    pub assignee_id: Uuid,
+    pub project_id: Option<Uuid>,
+    pub due_date: DateTime<Utc>,
+}
+
+/// Tasks due before `until` whose assignee has not yet been reminded about
+/// the current due date. A reminder counts if it was created after the start
+/// of the window for that due date, so moving the due date re-arms it.
+pub async fn find_due_reminders(
+    pool: &PgPool,
+    until: DateTime<Utc>,
+    window: chrono::Duration,
+) -> Result<Vec<DueTaskReminder>, sqlx::Error> {
+    sqlx::query_as::<_, DueTaskReminder>(
+        r#"
+        SELECT t.id AS task_id, t.title, t.assignee_id, t.project_id, t.due_date
+        FROM tasks t
+        WHERE t.due_date IS NOT NULL
+          AND t.due_date > NOW()
+          AND t.due_date <= $1
+          AND t.assignee_id IS NOT NULL
+          AND t.status::text NOT IN ('done', 'cancelled')
+          AND NOT EXISTS (
+              SELECT 1 FROM inbox_items i
+              WHERE i.task_id = t.id
+                AND i.user_id = t.assignee_id
+                AND i.notification_type::text = 'due_date_approaching'
+                AND i.created_at >= t.due_date - make_interval(secs => $2)
+          )
+        ORDER BY t.due_date ASC
+        "#,
+    )
+    .bind(until)
+    .bind(window.num_seconds() as f64)
+    .fetch_all(pool)
+    .await
+}
+
 /// Extract @mentions from text content
 /// Returns a list of usernames (without the @ prefix)
 pub fn extract_mentions(text: &str) -> Vec<String> {
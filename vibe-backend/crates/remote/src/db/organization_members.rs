@@ -105,6 +105,30 @@ where
     Ok(exists)
 }
 
+/// Tenant workspaces a user can read, either directly or through a team
+pub(crate) async fn accessible_workspace_ids(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<Vec<Uuid>, sqlx::Error> {
+    sqlx::query_scalar::<_, Uuid>(
+        r#"
+        SELECT twm.tenant_workspace_id
+        FROM tenant_workspace_members twm
+        JOIN users u ON twm.email = u.email
+        WHERE u.id = $1
+        UNION
+        SELECT t.tenant_workspace_id
+        FROM team_members tm
+        JOIN teams t ON tm.team_id = t.id
+        JOIN users u ON tm.email = u.email
+        WHERE u.id = $1 AND t.tenant_workspace_id IS NOT NULL
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
 pub(crate) async fn assert_membership(
     pool: &PgPool,
     organization_id: Uuid,
@@ -0,0 +1,441 @@
+//! Scheduled Jobs Repository
+//! Durable one-shot and recurring background jobs, claimed with leases
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ScheduledJob {
+    pub id: Uuid,
+    pub kind: String,
+    pub payload: serde_json::Value,
+    pub schedule_key: Option<String>,
+    /// `pending`, `running`, `succeeded` or `failed`
+    pub status: String,
+    pub run_at: DateTime<Utc>,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub locked_by: Option<String>,
+    pub locked_until: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ScheduledJob {
+    pub fn is_recurring(&self) -> bool {
+        self.schedule_key.is_some()
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ScheduledJobError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+const SCHEDULED_JOB_COLUMNS: &str = r#"
+    id, kind, payload, schedule_key, status, run_at, attempts, max_attempts,
+    locked_by, locked_until, last_error, last_run_at, completed_at,
+    created_at, updated_at
+"#;
+
+pub struct ScheduledJobRepository;
+
+impl ScheduledJobRepository {
+    /// Queue a one-shot job to run at `run_at`
+    pub async fn enqueue(
+        pool: &PgPool,
+        kind: &str,
+        payload: &serde_json::Value,
+        run_at: DateTime<Utc>,
+        max_attempts: i32,
+    ) -> Result<ScheduledJob, ScheduledJobError> {
+        let query = format!(
+            r#"
+            INSERT INTO scheduled_jobs (kind, payload, run_at, max_attempts)
+            VALUES ($1, $2, $3, $4)
+            RETURNING {SCHEDULED_JOB_COLUMNS}
+            "#
+        );
+        let job = sqlx::query_as::<_, ScheduledJob>(&query)
+            .bind(kind)
+            .bind(payload)
+            .bind(run_at)
+            .bind(max_attempts.max(1))
+            .fetch_one(pool)
+            .await?;
+
+        Ok(job)
+    }
+
+    /// Create the row for a recurring job unless another replica already
+    /// did. An existing row keeps its `run_at`.
+    pub async fn ensure_recurring(
+        pool: &PgPool,
+        schedule_key: &str,
+        kind: &str,
+        first_run_at: DateTime<Utc>,
+    ) -> Result<(), ScheduledJobError> {
+        sqlx::query(
+            r#"
+            INSERT INTO scheduled_jobs (kind, schedule_key, run_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (schedule_key) DO NOTHING
+            "#,
+        )
+        .bind(kind)
+        .bind(schedule_key)
+        .bind(first_run_at)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Atomically lease the next due job of one of `kinds` for `worker_id`.
+    ///
+    /// Running jobs whose lease expired (the worker died mid-run) are due
+    /// again. `SKIP LOCKED` lets replicas poll the same table safely.
+    pub async fn claim_due(
+        pool: &PgPool,
+        worker_id: &str,
+        kinds: &[&str],
+        lease_seconds: i64,
+    ) -> Result<Option<ScheduledJob>, ScheduledJobError> {
+        let query = format!(
+            r#"
+            UPDATE scheduled_jobs
+            SET status = 'running',
+                locked_by = $1,
+                locked_until = NOW() + make_interval(secs => $3),
+                attempts = attempts + 1,
+                last_run_at = NOW(),
+                updated_at = NOW()
+            WHERE id = (
+                SELECT id FROM scheduled_jobs
+                WHERE kind = ANY($2)
+                  AND ((status = 'pending' AND run_at <= NOW())
+                    OR (status = 'running' AND locked_until < NOW()))
+                ORDER BY run_at ASC
+                FOR UPDATE SKIP LOCKED
+                LIMIT 1
+            )
+            RETURNING {SCHEDULED_JOB_COLUMNS}
+            "#
+        );
+        let job = sqlx::query_as::<_, ScheduledJob>(&query)
+            .bind(worker_id)
+            .bind(kinds)
+            .bind(lease_seconds as f64)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(job)
+    }
+
+    /// Record a successful run. Recurring jobs go back to `pending` at
+    /// `next_run_at`; one-shot jobs are marked succeeded. Returns false if
+    /// the lease was lost to another worker in the meantime.
+    pub async fn complete(
+        pool: &PgPool,
+        id: Uuid,
+        worker_id: &str,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<bool, ScheduledJobError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE scheduled_jobs
+            SET status = CASE WHEN $3::timestamptz IS NULL THEN 'succeeded' ELSE 'pending' END,
+                run_at = COALESCE($3, run_at),
+                attempts = 0,
+                last_error = NULL,
+                completed_at = CASE WHEN $3::timestamptz IS NULL THEN NOW() ELSE NULL END,
+                locked_by = NULL,
+                locked_until = NULL,
+                updated_at = NOW()
+            WHERE id = $1 AND status = 'running' AND locked_by = $2
+            "#,
+        )
+        .bind(id)
+        .bind(worker_id)
+        .bind(next_run_at)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record a failed run. With `retry_at` the job is pending again at that
+    /// time, otherwise it is marked failed for good. Returns false if the
+    /// lease was lost to another worker in the meantime.
+    pub async fn fail(
+        pool: &PgPool,
+        id: Uuid,
+        worker_id: &str,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+        reset_attempts: bool,
+    ) -> Result<bool, ScheduledJobError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE scheduled_jobs
+            SET status = CASE WHEN $4::timestamptz IS NULL THEN 'failed' ELSE 'pending' END,
+                run_at = COALESCE($4, run_at),
+                attempts = CASE WHEN $5 THEN 0 ELSE attempts END,
+                last_error = $3,
+                completed_at = CASE WHEN $4::timestamptz IS NULL THEN NOW() ELSE NULL END,
+                locked_by = NULL,
+                locked_until = NULL,
+                updated_at = NOW()
+            WHERE id = $1 AND status = 'running' AND locked_by = $2
+            "#,
+        )
+        .bind(id)
+        .bind(worker_id)
+        .bind(error)
+        .bind(retry_at)
+        .bind(reset_attempts)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete finished one-shot jobs that completed before `before`
+    pub async fn purge_finished(
+        pool: &PgPool,
+        before: DateTime<Utc>,
+    ) -> Result<u64, ScheduledJobError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM scheduled_jobs
+            WHERE schedule_key IS NULL
+              AND status IN ('succeeded', 'failed')
+              AND completed_at < $1
+            "#,
+        )
+        .bind(before)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    const WORKER: &str = "worker-a";
+    const OTHER_WORKER: &str = "worker-b";
+    const LEASE_SECONDS: i64 = 600;
+
+    async fn enqueue_due(pool: &PgPool, kind: &str, run_at: DateTime<Utc>) -> ScheduledJob {
+        ScheduledJobRepository::enqueue(pool, kind, &serde_json::json!({}), run_at, 3)
+            .await
+            .unwrap()
+    }
+
+    async fn claim(pool: &PgPool, worker_id: &str) -> Option<ScheduledJob> {
+        ScheduledJobRepository::claim_due(pool, worker_id, &["reminders"], LEASE_SECONDS)
+            .await
+            .unwrap()
+    }
+
+    async fn reload(pool: &PgPool, id: Uuid) -> ScheduledJob {
+        sqlx::query_as::<_, ScheduledJob>(&format!(
+            "SELECT {SCHEDULED_JOB_COLUMNS} FROM scheduled_jobs WHERE id = $1"
+        ))
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    /// Pretend the worker holding the job died and its lease ran out
+    async fn expire_lease(pool: &PgPool, id: Uuid) {
+        sqlx::query(
+            "UPDATE scheduled_jobs SET locked_until = NOW() - INTERVAL '1 second' WHERE id = $1",
+        )
+        .bind(id)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[ignore = "requires DATABASE_URL"]
+    #[sqlx::test(
+        migrations = false,
+        fixtures("../../migrations/20260203090000_create_scheduled_jobs.sql")
+    )]
+    async fn claim_takes_oldest_due_job_of_requested_kinds(pool: PgPool) {
+        let now = Utc::now();
+        enqueue_due(&pool, "reminders", now + Duration::hours(1)).await;
+        enqueue_due(&pool, "other_kind", now - Duration::hours(2)).await;
+        let newer = enqueue_due(&pool, "reminders", now - Duration::minutes(1)).await;
+        let older = enqueue_due(&pool, "reminders", now - Duration::minutes(5)).await;
+
+        let claimed = claim(&pool, WORKER).await.unwrap();
+        assert_eq!(claimed.id, older.id);
+        assert_eq!(claimed.status, "running");
+        assert_eq!(claimed.attempts, 1);
+        assert_eq!(claimed.locked_by.as_deref(), Some(WORKER));
+        assert!(claimed.locked_until.unwrap() > Utc::now());
+
+        assert_eq!(claim(&pool, WORKER).await.unwrap().id, newer.id);
+        // Neither the future job nor the unrequested kind is due
+        assert!(claim(&pool, WORKER).await.is_none());
+    }
+
+    #[ignore = "requires DATABASE_URL"]
+    #[sqlx::test(
+        migrations = false,
+        fixtures("../../migrations/20260203090000_create_scheduled_jobs.sql")
+    )]
+    async fn claim_skips_rows_locked_by_another_replica(pool: PgPool) {
+        let now = Utc::now();
+        let locked = enqueue_due(&pool, "reminders", now - Duration::minutes(5)).await;
+        let free = enqueue_due(&pool, "reminders", now - Duration::minutes(1)).await;
+
+        // Another replica is in the middle of claiming the older row
+        let mut tx = pool.begin().await.unwrap();
+        sqlx::query("SELECT id FROM scheduled_jobs WHERE id = $1 FOR UPDATE")
+            .bind(locked.id)
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+
+        assert_eq!(claim(&pool, WORKER).await.unwrap().id, free.id);
+        assert!(claim(&pool, WORKER).await.is_none());
+
+        tx.rollback().await.unwrap();
+        assert_eq!(claim(&pool, WORKER).await.unwrap().id, locked.id);
+    }
+
+    #[ignore = "requires DATABASE_URL"]
+    #[sqlx::test(
+        migrations = false,
+        fixtures("../../migrations/20260203090000_create_scheduled_jobs.sql")
+    )]
+    async fn expired_lease_is_taken_over_and_old_worker_loses_it(pool: PgPool) {
+        let job = enqueue_due(&pool, "reminders", Utc::now()).await;
+        claim(&pool, WORKER).await.unwrap();
+
+        // A live lease is not taken over
+        assert!(claim(&pool, OTHER_WORKER).await.is_none());
+
+        expire_lease(&pool, job.id).await;
+        let taken = claim(&pool, OTHER_WORKER).await.unwrap();
+        assert_eq!(taken.id, job.id);
+        assert_eq!(taken.locked_by.as_deref(), Some(OTHER_WORKER));
+        assert_eq!(taken.attempts, 2);
+
+        let recorded = ScheduledJobRepository::complete(&pool, job.id, WORKER, None)
+            .await
+            .unwrap();
+        assert!(!recorded, "the worker that lost the lease must not record");
+        let recorded = ScheduledJobRepository::fail(&pool, job.id, WORKER, "boom", None, false)
+            .await
+            .unwrap();
+        assert!(!recorded);
+        assert_eq!(reload(&pool, job.id).await.status, "running");
+
+        assert!(
+            ScheduledJobRepository::complete(&pool, job.id, OTHER_WORKER, None)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[ignore = "requires DATABASE_URL"]
+    #[sqlx::test(
+        migrations = false,
+        fixtures("../../migrations/20260203090000_create_scheduled_jobs.sql")
+    )]
+    async fn complete_finishes_one_shot_and_reschedules_recurring(pool: PgPool) {
+        let one_shot = enqueue_due(&pool, "reminders", Utc::now()).await;
+        claim(&pool, WORKER).await.unwrap();
+        assert!(
+            ScheduledJobRepository::complete(&pool, one_shot.id, WORKER, None)
+                .await
+                .unwrap()
+        );
+        let one_shot = reload(&pool, one_shot.id).await;
+        assert_eq!(one_shot.status, "succeeded");
+        assert!(one_shot.completed_at.is_some());
+        assert!(one_shot.locked_by.is_none() && one_shot.locked_until.is_none());
+
+        ScheduledJobRepository::ensure_recurring(&pool, "reminders", "reminders", Utc::now())
+            .await
+            .unwrap();
+        let recurring = claim(&pool, WORKER).await.unwrap();
+        assert!(recurring.is_recurring());
+        let next_run_at = Utc::now() + Duration::minutes(5);
+        assert!(
+            ScheduledJobRepository::complete(&pool, recurring.id, WORKER, Some(next_run_at))
+                .await
+                .unwrap()
+        );
+        let recurring = reload(&pool, recurring.id).await;
+        assert_eq!(recurring.status, "pending");
+        assert_eq!(recurring.attempts, 0);
+        assert!(recurring.completed_at.is_none());
+        assert_eq!(
+            recurring.run_at.timestamp_micros(),
+            next_run_at.timestamp_micros()
+        );
+        // Not due again until next_run_at
+        assert!(claim(&pool, WORKER).await.is_none());
+    }
+
+    #[ignore = "requires DATABASE_URL"]
+    #[sqlx::test(
+        migrations = false,
+        fixtures("../../migrations/20260203090000_create_scheduled_jobs.sql")
+    )]
+    async fn fail_retries_or_gives_up(pool: PgPool) {
+        let job = enqueue_due(&pool, "reminders", Utc::now()).await;
+        claim(&pool, WORKER).await.unwrap();
+
+        let retry_at = Utc::now() + Duration::seconds(30);
+        assert!(
+            ScheduledJobRepository::fail(&pool, job.id, WORKER, "timeout", Some(retry_at), false)
+                .await
+                .unwrap()
+        );
+        let retried = reload(&pool, job.id).await;
+        assert_eq!(retried.status, "pending");
+        assert_eq!(retried.attempts, 1, "attempts carry over into the retry");
+        assert_eq!(retried.last_error.as_deref(), Some("timeout"));
+        assert!(retried.completed_at.is_none());
+        assert_eq!(
+            retried.run_at.timestamp_micros(),
+            retry_at.timestamp_micros()
+        );
+
+        sqlx::query("UPDATE scheduled_jobs SET run_at = NOW() WHERE id = $1")
+            .bind(job.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(claim(&pool, WORKER).await.unwrap().attempts, 2);
+        assert!(
+            ScheduledJobRepository::fail(&pool, job.id, WORKER, "still broken", None, false)
+                .await
+                .unwrap()
+        );
+        let failed = reload(&pool, job.id).await;
+        assert_eq!(failed.status, "failed");
+        assert_eq!(failed.last_error.as_deref(), Some("still broken"));
+        assert!(failed.completed_at.is_some());
+        assert!(claim(&pool, WORKER).await.is_none());
+    }
+}
@@ -25,6 +25,43 @@ pub enum ExecutionStatus {
     Timeout,
 }
 
+impl ExecutionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Queued => "queued",
+            Self::Running => "running",
+            Self::Paused => "paused",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+            Self::Timeout => "timeout",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "pending" => Some(Self::Pending),
+            "queued" => Some(Self::Queued),
+            "running" => Some(Self::Running),
+            "paused" => Some(Self::Paused),
+            "completed" => Some(Self::Completed),
+            "failed" => Some(Self::Failed),
+            "cancelled" => Some(Self::Cancelled),
+            "timeout" => Some(Self::Timeout),
+            _ => None,
+        }
+    }
+
+    /// Terminal states never transition again except through an explicit retry
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            Self::Completed | Self::Failed | Self::Cancelled | Self::Timeout
+        )
+    }
+}
+
 /// Execution mode enum
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, TS, Default)]
 #[serde(rename_all = "snake_case")]
@@ -96,6 +133,25 @@ pub enum TaskExecutionError {
     Database(#[from] sqlx::Error),
 }
 
+impl TaskExecution {
+    pub fn parsed_status(&self) -> Option<ExecutionStatus> {
+        ExecutionStatus::parse(&self.status)
+    }
+
+    pub fn attempts_remaining(&self) -> bool {
+        self.current_attempt < self.max_attempts
+    }
+}
+
+const TASK_EXECUTION_COLUMNS: &str = r#"
+    id, task_id, project_id, organization_id,
+    status, initiated_by, execution_mode,
+    max_attempts, current_attempt,
+    max_duration_seconds, max_tokens,
+    result_summary, error_message,
+    created_at, updated_at, started_at, completed_at, deleted_at
+"#;
+
 pub struct TaskExecutionRepository;
 
 impl TaskExecutionRepository {
@@ -380,4 +436,155 @@ impl TaskExecutionRepository {
 
         Ok(result.rows_affected() > 0)
     }
+
+    /// Permanently remove executions soft-deleted before `before`, along with
+    /// their attempts and logs
+    pub async fn purge_deleted(
+        pool: &PgPool,
+        before: DateTime<Utc>,
+    ) -> Result<u64, TaskExecutionError> {
+        let result = sqlx::query("DELETE FROM task_executions WHERE deleted_at < $1")
+            .bind(before)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Move an execution to `to` only if it is currently in one of `from`.
+    /// Returns `None` when the execution is missing or in another state.
+    pub async fn transition(
+        pool: &PgPool,
+        id: Uuid,
+        from: &[ExecutionStatus],
+        to: ExecutionStatus,
+    ) -> Result<Option<TaskExecution>, TaskExecutionError> {
+        let from: Vec<&str> = from.iter().map(ExecutionStatus::as_str).collect();
+        let query = format!(
+            r#"
+            UPDATE task_executions
+            SET status = $2,
+                completed_at = CASE WHEN $3 THEN NOW() ELSE completed_at END,
+                updated_at = NOW()
+            WHERE id = $1 AND status = ANY($4) AND deleted_at IS NULL
+            RETURNING {TASK_EXECUTION_COLUMNS}
+            "#
+        );
+        let execution = sqlx::query_as::<_, TaskExecution>(&query)
+            .bind(id)
+            .bind(to.as_str())
+            .bind(to.is_terminal())
+            .bind(&from)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(execution)
+    }
+
+    /// Put a failed, timed out or cancelled execution back in the queue for
+    /// another attempt, clearing the previous outcome. A running execution is
+    /// never requeued, so it can't be picked up by a second worker.
+    pub async fn requeue(
+        pool: &PgPool,
+        id: Uuid,
+    ) -> Result<Option<TaskExecution>, TaskExecutionError> {
+        let query = format!(
+            r#"
+            UPDATE task_executions
+            SET status = 'queued', error_message = NULL, result_summary = NULL,
+                completed_at = NULL, updated_at = NOW()
+            WHERE id = $1
+              AND status IN ('failed', 'timeout', 'cancelled')
+              AND current_attempt < max_attempts
+              AND deleted_at IS NULL
+            RETURNING {TASK_EXECUTION_COLUMNS}
+            "#
+        );
+        let execution = sqlx::query_as::<_, TaskExecution>(&query)
+            .bind(id)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(execution)
+    }
+
+    /// Atomically claim the oldest queued execution for a worker.
+    /// `SKIP LOCKED` lets several workers poll the same table safely.
+    /// `started_at` is reset so each attempt gets the full time limit.
+    pub async fn claim_next_queued(
+        pool: &PgPool,
+    ) -> Result<Option<TaskExecution>, TaskExecutionError> {
+        let query = format!(
+            r#"
+            UPDATE task_executions
+            SET status = 'running', started_at = NOW(), updated_at = NOW()
+            WHERE id = (
+                SELECT id FROM task_executions
+                WHERE status = 'queued' AND deleted_at IS NULL
+                ORDER BY created_at ASC
+                FOR UPDATE SKIP LOCKED
+                LIMIT 1
+            )
+            RETURNING {TASK_EXECUTION_COLUMNS}
+            "#
+        );
+        let execution = sqlx::query_as::<_, TaskExecution>(&query)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(execution)
+    }
+
+    /// Record the outcome of a running execution. Does nothing if the
+    /// execution was paused or cancelled while the attempt was in flight.
+    pub async fn finish_running(
+        pool: &PgPool,
+        id: Uuid,
+        to: ExecutionStatus,
+        result_summary: Option<&str>,
+        error_message: Option<&str>,
+    ) -> Result<Option<TaskExecution>, TaskExecutionError> {
+        let query = format!(
+            r#"
+            UPDATE task_executions
+            SET status = $2, result_summary = $3, error_message = $4,
+                completed_at = NOW(), updated_at = NOW()
+            WHERE id = $1 AND status = 'running' AND deleted_at IS NULL
+            RETURNING {TASK_EXECUTION_COLUMNS}
+            "#
+        );
+        let execution = sqlx::query_as::<_, TaskExecution>(&query)
+            .bind(id)
+            .bind(to.as_str())
+            .bind(result_summary)
+            .bind(error_message)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(execution)
+    }
+
+    /// Mark a running execution as timed out. Does nothing if it already
+    /// finished, so a late timeout can't overwrite the real outcome.
+    pub async fn mark_timeout(
+        pool: &PgPool,
+        id: Uuid,
+        error_message: &str,
+    ) -> Result<Option<TaskExecution>, TaskExecutionError> {
+        let query = format!(
+            r#"
+            UPDATE task_executions
+            SET status = 'timeout', completed_at = NOW(), error_message = $2, updated_at = NOW()
+            WHERE id = $1 AND status = 'running' AND deleted_at IS NULL
+            RETURNING {TASK_EXECUTION_COLUMNS}
+            "#
+        );
+        let execution = sqlx::query_as::<_, TaskExecution>(&query)
+            .bind(id)
+            .bind(error_message)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(execution)
+    }
 }
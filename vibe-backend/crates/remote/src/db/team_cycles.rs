@@ -0,0 +1,529 @@
+//! Team cycles (sprints) database operations
+//!
+//! Cycle membership lives in `team_cycle_issues` and is never deleted when an
+//! issue leaves a cycle, so per-cycle scope changes and rollover stay visible.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool, Postgres, Transaction};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// A time-boxed cycle for a team
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TeamCycle {
+    pub id: Uuid,
+    pub team_id: Uuid,
+    pub number: i32,
+    pub name: Option<String>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Issue counts for a cycle
+#[derive(Debug, Clone, Default, Serialize, Deserialize, FromRow)]
+pub struct CycleStats {
+    /// Issues in the cycle, including those later carried over
+    pub total_issues: i64,
+    /// Issues still in the cycle that are done
+    pub completed_issues: i64,
+    /// Issues added after the cycle started (rollover excluded)
+    pub scope_added: i64,
+    /// Unfinished issues moved to the next cycle when this one ended
+    pub carried_over: i64,
+}
+
+/// A cycle with its derived status ("upcoming", "active", "completed") and stats
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TeamCycleWithStats {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub cycle: TeamCycle,
+    pub status: String,
+    #[sqlx(flatten)]
+    pub stats: CycleStats,
+}
+
+impl TeamCycleWithStats {
+    pub fn is_active(&self) -> bool {
+        self.status == "active"
+    }
+
+    pub fn is_completed(&self) -> bool {
+        self.status == "completed"
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateTeamCycle {
+    pub name: Option<String>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateTeamCycle {
+    pub name: Option<String>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+}
+
+/// Result of closing a cycle and moving its unfinished issues on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CycleRollover {
+    pub cycle_id: Uuid,
+    pub next_cycle_id: Uuid,
+    pub carried_over: u64,
+}
+
+#[derive(Debug, Error)]
+pub enum TeamCycleError {
+    #[error("cycle not found")]
+    NotFound,
+    #[error("issue not found in this team")]
+    IssueNotFound,
+    #[error("cycle must end after it starts")]
+    InvalidDates,
+    #[error("cycle overlaps an existing cycle")]
+    Overlap,
+    #[error("cycle is already completed")]
+    Completed,
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+const CYCLE_COLUMNS: &str = "c.id, c.team_id, c.number, c.name, c.starts_at, c.ends_at, c.completed_at, c.created_at, c.updated_at";
+
+/// Stats select shared by the list/find queries; expects `team_cycles c`
+fn stats_query(filter: &str) -> String {
+    format!(
+        r#"
+        SELECT
+            {CYCLE_COLUMNS},
+            CASE
+                WHEN c.completed_at IS NOT NULL THEN 'completed'
+                WHEN NOW() < c.starts_at THEN 'upcoming'
+                ELSE 'active'
+            END AS status,
+            COUNT(ci.task_id) FILTER (WHERE ci.removed_at IS NULL OR ci.carried_over) AS total_issues,
+            COUNT(ci.task_id) FILTER (WHERE ci.removed_at IS NULL AND t.status = 'done') AS completed_issues,
+            COUNT(ci.task_id) FILTER (
+                WHERE ci.carried_from_cycle_id IS NULL AND ci.added_at > c.starts_at
+            ) AS scope_added,
+            COUNT(ci.task_id) FILTER (WHERE ci.carried_over) AS carried_over
+        FROM team_cycles c
+        LEFT JOIN team_cycle_issues ci ON ci.cycle_id = c.id
+        LEFT JOIN tasks t ON t.id = ci.task_id
+        WHERE {filter}
+        GROUP BY c.id
+        ORDER BY c.starts_at ASC
+        "#
+    )
+}
+
+pub struct TeamCycleRepository;
+
+impl TeamCycleRepository {
+    /// All cycles for a team, oldest first
+    pub async fn list_with_stats(
+        pool: &PgPool,
+        team_id: Uuid,
+    ) -> Result<Vec<TeamCycleWithStats>, TeamCycleError> {
+        let cycles = sqlx::query_as::<_, TeamCycleWithStats>(&stats_query("c.team_id = $1"))
+            .bind(team_id)
+            .fetch_all(pool)
+            .await?;
+        Ok(cycles)
+    }
+
+    pub async fn find_with_stats(
+        pool: &PgPool,
+        team_id: Uuid,
+        cycle_id: Uuid,
+    ) -> Result<TeamCycleWithStats, TeamCycleError> {
+        sqlx::query_as::<_, TeamCycleWithStats>(&stats_query("c.team_id = $1 AND c.id = $2"))
+            .bind(team_id)
+            .bind(cycle_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or(TeamCycleError::NotFound)
+    }
+
+    /// Issue IDs in a cycle, including issues carried over out of it
+    pub async fn issue_ids(pool: &PgPool, cycle_id: Uuid) -> Result<Vec<Uuid>, TeamCycleError> {
+        let ids = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT task_id FROM team_cycle_issues
+            WHERE cycle_id = $1 AND (removed_at IS NULL OR carried_over)
+            "#,
+        )
+        .bind(cycle_id)
+        .fetch_all(pool)
+        .await?;
+        Ok(ids)
+    }
+
+    pub async fn create(
+        pool: &PgPool,
+        team_id: Uuid,
+        data: &CreateTeamCycle,
+    ) -> Result<TeamCycle, TeamCycleError> {
+        let mut tx = pool.begin().await?;
+        lock_team_cycles(&mut tx, team_id).await?;
+        let cycle = insert_cycle(
+            &mut tx,
+            team_id,
+            data.name.as_deref(),
+            data.starts_at,
+            data.ends_at,
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(cycle)
+    }
+
+    pub async fn update(
+        pool: &PgPool,
+        team_id: Uuid,
+        cycle_id: Uuid,
+        data: &UpdateTeamCycle,
+    ) -> Result<TeamCycle, TeamCycleError> {
+        let mut tx = pool.begin().await?;
+        lock_team_cycles(&mut tx, team_id).await?;
+
+        let existing = find_cycle(&mut tx, team_id, cycle_id).await?;
+        let starts_at = data.starts_at.unwrap_or(existing.starts_at);
+        let ends_at = data.ends_at.unwrap_or(existing.ends_at);
+        ensure_free_range(&mut tx, team_id, Some(cycle_id), starts_at, ends_at).await?;
+
+        let cycle = sqlx::query_as::<_, TeamCycle>(&format!(
+            r#"
+            UPDATE team_cycles c
+            SET name = COALESCE($3, c.name), starts_at = $4, ends_at = $5, updated_at = NOW()
+            WHERE c.team_id = $1 AND c.id = $2
+            RETURNING {CYCLE_COLUMNS}
+            "#
+        ))
+        .bind(team_id)
+        .bind(cycle_id)
+        .bind(data.name.as_deref())
+        .bind(starts_at)
+        .bind(ends_at)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(cycle)
+    }
+
+    pub async fn delete(
+        pool: &PgPool,
+        team_id: Uuid,
+        cycle_id: Uuid,
+    ) -> Result<(), TeamCycleError> {
+        let result = sqlx::query("DELETE FROM team_cycles WHERE team_id = $1 AND id = $2")
+            .bind(team_id)
+            .bind(cycle_id)
+            .execute(pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(TeamCycleError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Put an issue in a cycle, taking it out of any other open cycle it is in
+    pub async fn add_issue(
+        pool: &PgPool,
+        team_id: Uuid,
+        cycle_id: Uuid,
+        task_id: Uuid,
+    ) -> Result<(), TeamCycleError> {
+        let mut tx = pool.begin().await?;
+
+        let cycle = find_cycle(&mut tx, team_id, cycle_id).await?;
+        if cycle.completed_at.is_some() {
+            return Err(TeamCycleError::Completed);
+        }
+        let in_team: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM tasks WHERE id = $1 AND team_id = $2)",
+        )
+        .bind(task_id)
+        .bind(team_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        if !in_team {
+            return Err(TeamCycleError::IssueNotFound);
+        }
+
+        // Completed cycles keep their membership so their stats do not change
+        sqlx::query(
+            r#"
+            UPDATE team_cycle_issues ci SET removed_at = NOW()
+            FROM team_cycles c
+            WHERE ci.task_id = $1
+              AND ci.cycle_id <> $2
+              AND ci.removed_at IS NULL
+              AND c.id = ci.cycle_id
+              AND c.completed_at IS NULL
+            "#,
+        )
+        .bind(task_id)
+        .bind(cycle_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO team_cycle_issues (cycle_id, task_id)
+            VALUES ($1, $2)
+            ON CONFLICT (cycle_id, task_id) DO UPDATE
+            SET added_at = CASE
+                    WHEN team_cycle_issues.removed_at IS NULL THEN team_cycle_issues.added_at
+                    ELSE NOW()
+                END,
+                removed_at = NULL,
+                carried_over = FALSE
+            "#,
+        )
+        .bind(cycle_id)
+        .bind(task_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn remove_issue(
+        pool: &PgPool,
+        cycle_id: Uuid,
+        task_id: Uuid,
+    ) -> Result<(), TeamCycleError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE team_cycle_issues SET removed_at = NOW()
+            WHERE cycle_id = $1 AND task_id = $2 AND removed_at IS NULL
+            "#,
+        )
+        .bind(cycle_id)
+        .bind(task_id)
+        .execute(pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(TeamCycleError::IssueNotFound);
+        }
+        Ok(())
+    }
+
+    /// Complete a cycle now, rolling unfinished issues into the next cycle
+    pub async fn complete(
+        pool: &PgPool,
+        team_id: Uuid,
+        cycle_id: Uuid,
+    ) -> Result<CycleRollover, TeamCycleError> {
+        let mut tx = pool.begin().await?;
+        lock_team_cycles(&mut tx, team_id).await?;
+        let cycle = find_cycle(&mut tx, team_id, cycle_id).await?;
+        if cycle.completed_at.is_some() {
+            return Err(TeamCycleError::Completed);
+        }
+        let rollover = roll_over(&mut tx, &cycle).await?;
+        tx.commit().await?;
+        Ok(rollover)
+    }
+
+    /// Complete every cycle whose end date has passed
+    pub async fn roll_over_ended(pool: &PgPool) -> Result<Vec<CycleRollover>, TeamCycleError> {
+        let ended = sqlx::query_as::<_, (Uuid, Uuid)>(
+            "SELECT id, team_id FROM team_cycles WHERE completed_at IS NULL AND ends_at <= NOW()",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let mut rollovers = Vec::with_capacity(ended.len());
+        for (cycle_id, team_id) in ended {
+            match Self::complete(pool, team_id, cycle_id).await {
+                Ok(rollover) => rollovers.push(rollover),
+                // Another replica got there first
+                Err(TeamCycleError::Completed | TeamCycleError::NotFound) => {}
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(rollovers)
+    }
+}
+
+/// Serialize cycle creation/rollover per team so ranges and numbers stay unique
+async fn lock_team_cycles(
+    tx: &mut Transaction<'_, Postgres>,
+    team_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext('team_cycles:' || $1::text))")
+        .bind(team_id)
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
+
+async fn find_cycle(
+    tx: &mut Transaction<'_, Postgres>,
+    team_id: Uuid,
+    cycle_id: Uuid,
+) -> Result<TeamCycle, TeamCycleError> {
+    sqlx::query_as::<_, TeamCycle>(&format!(
+        "SELECT {CYCLE_COLUMNS} FROM team_cycles c WHERE c.team_id = $1 AND c.id = $2 FOR UPDATE"
+    ))
+    .bind(team_id)
+    .bind(cycle_id)
+    .fetch_optional(&mut **tx)
+    .await?
+    .ok_or(TeamCycleError::NotFound)
+}
+
+async fn ensure_free_range(
+    tx: &mut Transaction<'_, Postgres>,
+    team_id: Uuid,
+    exclude_cycle_id: Option<Uuid>,
+    starts_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+) -> Result<(), TeamCycleError> {
+    if ends_at <= starts_at {
+        return Err(TeamCycleError::InvalidDates);
+    }
+    let overlaps: bool = sqlx::query_scalar(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM team_cycles
+            WHERE team_id = $1
+              AND ($2::uuid IS NULL OR id <> $2)
+              AND starts_at < $4 AND ends_at > $3
+        )
+        "#,
+    )
+    .bind(team_id)
+    .bind(exclude_cycle_id)
+    .bind(starts_at)
+    .bind(ends_at)
+    .fetch_one(&mut **tx)
+    .await?;
+    if overlaps {
+        return Err(TeamCycleError::Overlap);
+    }
+    Ok(())
+}
+
+async fn insert_cycle(
+    tx: &mut Transaction<'_, Postgres>,
+    team_id: Uuid,
+    name: Option<&str>,
+    starts_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+) -> Result<TeamCycle, TeamCycleError> {
+    ensure_free_range(tx, team_id, None, starts_at, ends_at).await?;
+    let cycle = sqlx::query_as::<_, TeamCycle>(&format!(
+        r#"
+        INSERT INTO team_cycles AS c (team_id, number, name, starts_at, ends_at)
+        VALUES (
+            $1,
+            (SELECT COALESCE(MAX(number), 0) + 1 FROM team_cycles WHERE team_id = $1),
+            $2, $3, $4
+        )
+        RETURNING {CYCLE_COLUMNS}
+        "#
+    ))
+    .bind(team_id)
+    .bind(name)
+    .bind(starts_at)
+    .bind(ends_at)
+    .fetch_one(&mut **tx)
+    .await?;
+    Ok(cycle)
+}
+
+/// Move unfinished issues of `cycle` into the team's next cycle, creating one
+/// of the same length if none is scheduled, and mark `cycle` completed
+async fn roll_over(
+    tx: &mut Transaction<'_, Postgres>,
+    cycle: &TeamCycle,
+) -> Result<CycleRollover, TeamCycleError> {
+    let next = sqlx::query_as::<_, TeamCycle>(&format!(
+        r#"
+        SELECT {CYCLE_COLUMNS} FROM team_cycles c
+        WHERE c.team_id = $1 AND c.id <> $2 AND c.completed_at IS NULL AND c.starts_at >= $3
+        ORDER BY c.starts_at ASC
+        LIMIT 1
+        "#
+    ))
+    .bind(cycle.team_id)
+    .bind(cycle.id)
+    .bind(cycle.ends_at)
+    .fetch_optional(&mut **tx)
+    .await?;
+    let next = match next {
+        Some(next) => next,
+        None => {
+            let length = cycle.ends_at - cycle.starts_at;
+            insert_cycle(
+                tx,
+                cycle.team_id,
+                None,
+                cycle.ends_at,
+                cycle.ends_at + length,
+            )
+            .await?
+        }
+    };
+
+    let moved = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        UPDATE team_cycle_issues ci
+        SET removed_at = NOW(), carried_over = TRUE
+        FROM tasks t
+        WHERE ci.cycle_id = $1
+          AND ci.removed_at IS NULL
+          AND t.id = ci.task_id
+          AND t.status NOT IN ('done', 'cancelled')
+        RETURNING ci.task_id
+        "#,
+    )
+    .bind(cycle.id)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    if !moved.is_empty() {
+        sqlx::query(
+            r#"
+            INSERT INTO team_cycle_issues (cycle_id, task_id, carried_from_cycle_id)
+            SELECT $1, task_id, $2 FROM UNNEST($3::uuid[]) AS task_id
+            ON CONFLICT (cycle_id, task_id) DO UPDATE
+            SET removed_at = NULL, carried_over = FALSE, carried_from_cycle_id = $2
+            "#,
+        )
+        .bind(next.id)
+        .bind(cycle.id)
+        .bind(&moved)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    sqlx::query("UPDATE team_cycles SET completed_at = NOW(), updated_at = NOW() WHERE id = $1")
+        .bind(cycle.id)
+        .execute(&mut **tx)
+        .await?;
+
+    tracing::info!(
+        cycle_id = %cycle.id,
+        next_cycle_id = %next.id,
+        carried_over = moved.len(),
+        "cycle completed"
+    );
+    Ok(CycleRollover {
+        cycle_id: cycle.id,
+        next_cycle_id: next.id,
+        carried_over: moved.len() as u64,
+    })
+}
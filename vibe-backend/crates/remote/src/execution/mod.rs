@@ -0,0 +1,12 @@
+//! Cloud AI execution (IKA-248)
+//!
+//! Runs `task_executions` on the remote server: the worker claims queued
+//! executions, creates attempts and hands them to an [`ExecutionRunner`].
+
+pub mod providers;
+pub mod runner;
+pub mod worker;
+
+pub use providers::ProviderRunner;
+pub use runner::{AttemptContext, AttemptEvents, ExecutionRunner, RunOutput, RunnerError};
+pub use worker::ExecutionWorker;
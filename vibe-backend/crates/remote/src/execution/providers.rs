@@ -0,0 +1,473 @@
+//! Provider-backed runner for cloud executions
+//!
+//! Drives a short tool loop against the Anthropic or OpenAI APIs using the
+//! workspace's stored provider keys. The only tool offered is posting a
+//! comment on the task, and every call goes through an execution approval.
+
+use async_trait::async_trait;
+use serde_json::{Value, json};
+
+use super::runner::{
+    AttemptContext, AttemptEvents, ExecutionRunner, RunOutput, RunnerError, TokenUsage,
+};
+use crate::db::{
+    ai_provider_keys::AiProviderKeyRepository,
+    execution_attempts::ExecutionAttemptRepository,
+    execution_logs::LogType,
+    task_comments::{CreateTaskComment, TaskCommentRepository},
+};
+
+const ANTHROPIC_API_BASE: &str = "https://api.anthropic.com";
+const OPENAI_API_BASE: &str = "https://api.openai.com";
+const ANTHROPIC_MODEL: &str = "claude-sonnet-4-20250514";
+const OPENAI_MODEL: &str = "gpt-4o";
+const MAX_OUTPUT_TOKENS: u32 = 4096;
+const COMMENT_AUTHOR: &str = "iKanban AI";
+const POST_COMMENT_TOOL: &str = "post_task_comment";
+
+const SYSTEM_PROMPT: &str = "You are an engineering assistant working on a task from an \
+issue tracker. Analyse the task, then use the post_task_comment tool to share an implementation \
+plan, findings or questions with the team. Finish with a short summary of what you did.";
+
+/// Providers the cloud runner can talk to, in order of preference
+const SUPPORTED_PROVIDERS: [&str; 2] = ["anthropic", "openai"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ToolCall {
+    pub id: String,
+    pub name: String,
+    pub input: Value,
+}
+
+/// One model response, normalized across providers
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ProviderTurn {
+    pub text: String,
+    pub tool_calls: Vec<ToolCall>,
+    pub usage: TokenUsage,
+    /// The assistant message in provider format, replayed on the next turn
+    pub raw_message: Value,
+}
+
+pub struct ProviderRunner {
+    http: reqwest::Client,
+    anthropic_base: String,
+    openai_base: String,
+}
+
+impl ProviderRunner {
+    pub fn new(http: reqwest::Client) -> Self {
+        Self {
+            http,
+            anthropic_base: ANTHROPIC_API_BASE.to_string(),
+            openai_base: OPENAI_API_BASE.to_string(),
+        }
+    }
+
+    /// Point the runner at different API hosts (used for local mock servers)
+    pub fn with_base_urls(
+        mut self,
+        anthropic: impl Into<String>,
+        openai: impl Into<String>,
+    ) -> Self {
+        self.anthropic_base = anthropic.into();
+        self.openai_base = openai.into();
+        self
+    }
+
+    fn max_turns(execution_mode: &str) -> usize {
+        match execution_mode {
+            "fast" => 2,
+            "thorough" => 12,
+            _ => 6,
+        }
+    }
+
+    async fn resolve_key(
+        &self,
+        events: &AttemptEvents,
+        ctx: &AttemptContext,
+    ) -> Result<(&'static str, String), RunnerError> {
+        for provider in SUPPORTED_PROVIDERS {
+            let key = AiProviderKeyRepository::get_key(
+                events.pool(),
+                ctx.execution.organization_id,
+                provider,
+            )
+            .await
+            .map_err(|e| RunnerError::retryable(e.to_string()))?;
+            if let Some(key) = key {
+                return Ok((provider, key));
+            }
+        }
+        Err(RunnerError::fatal(
+            "no Anthropic or OpenAI API key configured for this workspace",
+        ))
+    }
+
+    async fn complete(
+        &self,
+        provider: &str,
+        api_key: &str,
+        messages: &[Value],
+    ) -> Result<ProviderTurn, RunnerError> {
+        let (request, url) = match provider {
+            "anthropic" => (
+                self.http
+                    .post(format!("{}/v1/messages", self.anthropic_base))
+                    .header("x-api-key", api_key)
+                    .header("anthropic-version", "2023-06-01")
+                    .json(&json!({
+                        "model": ANTHROPIC_MODEL,
+                        "max_tokens": MAX_OUTPUT_TOKENS,
+                        "system": SYSTEM_PROMPT,
+                        "tools": [anthropic_tool()],
+                        "messages": messages,
+                    })),
+                "anthropic",
+            ),
+            _ => {
+                let mut all = vec![json!({ "role": "system", "content": SYSTEM_PROMPT })];
+                all.extend_from_slice(messages);
+                (
+                    self.http
+                        .post(format!("{}/v1/chat/completions", self.openai_base))
+                        .bearer_auth(api_key)
+                        .json(&json!({
+                            "model": OPENAI_MODEL,
+                            "max_tokens": MAX_OUTPUT_TOKENS,
+                            "tools": [openai_tool()],
+                            "messages": all,
+                        })),
+                    "openai",
+                )
+            }
+        };
+
+        let response = request.send().await?;
+        let status = response.status();
+        let body: Value = response.json().await?;
+        if !status.is_success() {
+            let message = body
+                .pointer("/error/message")
+                .and_then(Value::as_str)
+                .unwrap_or("unknown error");
+            let error = format!("{url} API error {status}: {message}");
+            // Auth and validation failures will not fix themselves on retry
+            return Err(if status.is_client_error() && status.as_u16() != 429 {
+                RunnerError::fatal(error)
+            } else {
+                RunnerError::retryable(error)
+            });
+        }
+
+        match url {
+            "anthropic" => Ok(parse_anthropic_response(&body)),
+            _ => Ok(parse_openai_response(&body)),
+        }
+    }
+
+    async fn execute_tool(
+        &self,
+        ctx: &AttemptContext,
+        events: &AttemptEvents,
+        call: &ToolCall,
+    ) -> Result<String, RunnerError> {
+        if call.name != POST_COMMENT_TOOL {
+            return Ok(format!("Unknown tool `{}`", call.name));
+        }
+        let Some(content) = call.input.get("content").and_then(Value::as_str) else {
+            return Ok("Missing required `content` argument".to_string());
+        };
+
+        let approved = events
+            .request_approval(
+                format!("Post a comment on task \"{}\"", ctx.task_title),
+                &call.name,
+                call.input.clone(),
+                "low",
+            )
+            .await?;
+        if !approved {
+            return Ok("The reviewer rejected this comment. Do not post it again.".to_string());
+        }
+
+        TaskCommentRepository::create(
+            events.pool(),
+            ctx.execution.task_id,
+            &CreateTaskComment {
+                content: content.to_string(),
+                is_internal: false,
+                author_name: COMMENT_AUTHOR.to_string(),
+                author_email: None,
+                author_id: None,
+            },
+        )
+        .await
+        .map_err(|e| RunnerError::retryable(e.to_string()))?;
+
+        Ok("Comment posted.".to_string())
+    }
+}
+
+#[async_trait]
+impl ExecutionRunner for ProviderRunner {
+    async fn run(
+        &self,
+        ctx: &AttemptContext,
+        events: &AttemptEvents,
+    ) -> Result<RunOutput, RunnerError> {
+        let (provider, api_key) = self.resolve_key(events, ctx).await?;
+        let model = match provider {
+            "anthropic" => ANTHROPIC_MODEL,
+            _ => OPENAI_MODEL,
+        };
+        let _ =
+            ExecutionAttemptRepository::set_model(events.pool(), ctx.attempt_id, provider, model)
+                .await;
+        events
+            .log(
+                LogType::System,
+                format!("Attempt {} using {provider}/{model}", ctx.attempt_number),
+            )
+            .await;
+
+        let mut messages = vec![json!({ "role": "user", "content": ctx.prompt() })];
+        let mut summary = String::new();
+
+        for _ in 0..Self::max_turns(&ctx.execution.execution_mode) {
+            events.checkpoint().await?;
+
+            let turn = self.complete(provider, &api_key, &messages).await?;
+            events.record_usage(provider, model, turn.usage).await?;
+
+            if !turn.text.is_empty() {
+                events.log(LogType::Assistant, turn.text.clone()).await;
+                summary = turn.text.clone();
+            }
+            messages.push(turn.raw_message.clone());
+
+            if turn.tool_calls.is_empty() {
+                return Ok(RunOutput { summary });
+            }
+
+            let mut anthropic_results = Vec::new();
+            for call in &turn.tool_calls {
+                events
+                    .log_tool(
+                        LogType::ToolCall,
+                        &call.name,
+                        format!("Calling {}", call.name),
+                        Some(call.input.clone()),
+                        None,
+                    )
+                    .await;
+                let result = self.execute_tool(ctx, events, call).await?;
+                events
+                    .log_tool(
+                        LogType::ToolResult,
+                        &call.name,
+                        result.clone(),
+                        None,
+                        Some(json!({ "result": result })),
+                    )
+                    .await;
+
+                match provider {
+                    "anthropic" => anthropic_results.push(json!({
+                        "type": "tool_result",
+                        "tool_use_id": call.id,
+                        "content": result,
+                    })),
+                    _ => messages.push(json!({
+                        "role": "tool",
+                        "tool_call_id": call.id,
+                        "content": result,
+                    })),
+                }
+            }
+            if !anthropic_results.is_empty() {
+                messages.push(json!({ "role": "user", "content": anthropic_results }));
+            }
+        }
+
+        events
+            .log(
+                LogType::System,
+                "Reached the turn limit for this execution mode",
+            )
+            .await;
+        Ok(RunOutput { summary })
+    }
+}
+
+fn tool_description() -> &'static str {
+    "Post a markdown comment on the task being worked on. Requires human approval."
+}
+
+fn tool_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "content": { "type": "string", "description": "Markdown body of the comment" }
+        },
+        "required": ["content"]
+    })
+}
+
+fn anthropic_tool() -> Value {
+    json!({
+        "name": POST_COMMENT_TOOL,
+        "description": tool_description(),
+        "input_schema": tool_schema(),
+    })
+}
+
+fn openai_tool() -> Value {
+    json!({
+        "type": "function",
+        "function": {
+            "name": POST_COMMENT_TOOL,
+            "description": tool_description(),
+            "parameters": tool_schema(),
+        }
+    })
+}
+
+fn as_i32(value: Option<&Value>) -> Option<i32> {
+    value
+        .and_then(Value::as_i64)
+        .and_then(|v| i32::try_from(v).ok())
+}
+
+pub(crate) fn parse_anthropic_response(body: &Value) -> ProviderTurn {
+    let content = body.get("content").cloned().unwrap_or_else(|| json!([]));
+    let mut text = String::new();
+    let mut tool_calls = Vec::new();
+
+    for block in content.as_array().into_iter().flatten() {
+        match block.get("type").and_then(Value::as_str) {
+            Some("text") => {
+                if let Some(t) = block.get("text").and_then(Value::as_str) {
+                    text.push_str(t);
+                }
+            }
+            Some("tool_use") => tool_calls.push(ToolCall {
+                id: block["id"].as_str().unwrap_or_default().to_string(),
+                name: block["name"].as_str().unwrap_or_default().to_string(),
+                input: block.get("input").cloned().unwrap_or_else(|| json!({})),
+            }),
+            _ => {}
+        }
+    }
+
+    let usage = body.get("usage");
+    ProviderTurn {
+        text,
+        tool_calls,
+        usage: TokenUsage {
+            input_tokens: as_i32(usage.and_then(|u| u.get("input_tokens"))).unwrap_or(0),
+            output_tokens: as_i32(usage.and_then(|u| u.get("output_tokens"))).unwrap_or(0),
+            cache_read_tokens: as_i32(usage.and_then(|u| u.get("cache_read_input_tokens"))),
+            cache_write_tokens: as_i32(usage.and_then(|u| u.get("cache_creation_input_tokens"))),
+        },
+        raw_message: json!({ "role": "assistant", "content": content }),
+    }
+}
+
+pub(crate) fn parse_openai_response(body: &Value) -> ProviderTurn {
+    let message = body
+        .pointer("/choices/0/message")
+        .cloned()
+        .unwrap_or_else(|| json!({ "role": "assistant", "content": "" }));
+
+    let tool_calls = message
+        .get("tool_calls")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|call| ToolCall {
+            id: call["id"].as_str().unwrap_or_default().to_string(),
+            name: call
+                .pointer("/function/name")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            input: call
+                .pointer("/function/arguments")
+                .and_then(Value::as_str)
+                .and_then(|args| serde_json::from_str(args).ok())
+                .unwrap_or_else(|| json!({})),
+        })
+        .collect();
+
+    let usage = body.get("usage");
+    ProviderTurn {
+        text: message
+            .get("content")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+        tool_calls,
+        usage: TokenUsage {
+            input_tokens: as_i32(usage.and_then(|u| u.get("prompt_tokens"))).unwrap_or(0),
+            output_tokens: as_i32(usage.and_then(|u| u.get("completion_tokens"))).unwrap_or(0),
+            cache_read_tokens: as_i32(
+                usage.and_then(|u| u.pointer("/prompt_tokens_details/cached_tokens")),
+            ),
+            cache_write_tokens: None,
+        },
+        raw_message: message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_anthropic_tool_use() {
+        let body = json!({
+            "content": [
+                { "type": "text", "text": "Posting a plan." },
+                { "type": "tool_use", "id": "toolu_1", "name": "post_task_comment", "input": { "content": "Plan" } }
+            ],
+            "usage": { "input_tokens": 120, "output_tokens": 40, "cache_read_input_tokens": 10 }
+        });
+        let turn = parse_anthropic_response(&body);
+
+        assert_eq!(turn.text, "Posting a plan.");
+        assert_eq!(turn.tool_calls.len(), 1);
+        assert_eq!(turn.tool_calls[0].id, "toolu_1");
+        assert_eq!(turn.tool_calls[0].input["content"], "Plan");
+        assert_eq!(turn.usage.input_tokens, 120);
+        assert_eq!(turn.usage.output_tokens, 40);
+        assert_eq!(turn.usage.cache_read_tokens, Some(10));
+        assert_eq!(turn.raw_message["role"], "assistant");
+    }
+
+    #[test]
+    fn parses_openai_function_call() {
+        let body = json!({
+            "choices": [{
+                "message": {
+                    "role": "assistant",
+                    "content": null,
+                    "tool_calls": [{
+                        "id": "call_1",
+                        "type": "function",
+                        "function": { "name": "post_task_comment", "arguments": "{\"content\":\"Hi\"}" }
+                    }]
+                }
+            }],
+            "usage": { "prompt_tokens": 50, "completion_tokens": 5 }
+        });
+        let turn = parse_openai_response(&body);
+
+        assert!(turn.text.is_empty());
+        assert_eq!(turn.tool_calls[0].name, "post_task_comment");
+        assert_eq!(turn.tool_calls[0].input["content"], "Hi");
+        assert_eq!(turn.usage.input_tokens, 50);
+        assert_eq!(turn.usage.cache_read_tokens, None);
+    }
+}
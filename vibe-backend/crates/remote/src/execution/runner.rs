@@ -0,0 +1,365 @@
+//! Runner abstraction for cloud executions
+//!
+//! A runner does the actual AI work for one attempt. It reports progress
+//! through [`AttemptEvents`], which persists logs and usage, enforces the
+//! execution's token budget and blocks on human approvals.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use sqlx::PgPool;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::db::{
+    ai_usage_records::{AiUsageRepository, CreateUsageRecord},
+    execution_approvals::{CreateApprovalRequest, ExecutionApprovalRepository},
+    execution_attempts::ExecutionAttemptRepository,
+    execution_logs::{CreateExecutionLog, ExecutionLogRepository, LogType},
+    task_executions::{ExecutionStatus, TaskExecution, TaskExecutionRepository},
+};
+
+/// How often a runner blocked on an approval re-checks its state
+const APPROVAL_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Pending approvals expire after this long and count as rejected
+const APPROVAL_TTL_SECONDS: i64 = 30 * 60;
+
+/// Everything a runner needs to know about the attempt it is driving
+#[derive(Debug, Clone)]
+pub struct AttemptContext {
+    pub execution: TaskExecution,
+    pub attempt_id: Uuid,
+    pub attempt_number: i32,
+    pub task_title: String,
+    pub task_description: Option<String>,
+}
+
+impl AttemptContext {
+    pub fn prompt(&self) -> String {
+        match self
+            .task_description
+            .as_deref()
+            .filter(|d| !d.trim().is_empty())
+        {
+            Some(description) => format!("{}\n\n{}", self.task_title, description),
+            None => self.task_title.clone(),
+        }
+    }
+}
+
+/// Token counts reported by a provider for a single request
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokenUsage {
+    pub input_tokens: i32,
+    pub output_tokens: i32,
+    pub cache_read_tokens: Option<i32>,
+    pub cache_write_tokens: Option<i32>,
+}
+
+/// Reasons an attempt stops before the runner finishes on its own
+#[derive(Debug, Error)]
+pub enum AttemptAbort {
+    #[error("execution was cancelled")]
+    Cancelled,
+    #[error("execution was paused")]
+    Paused,
+    #[error("token budget exceeded: used {used} of {limit}")]
+    TokenBudgetExceeded { used: i64, limit: i64 },
+}
+
+#[derive(Debug, Error)]
+pub enum RunnerError {
+    #[error(transparent)]
+    Abort(#[from] AttemptAbort),
+    /// Configuration problems that a retry will not fix (e.g. no API key)
+    #[error("{0}")]
+    Fatal(String),
+    /// Transient failures that may succeed on another attempt
+    #[error("{0}")]
+    Retryable(String),
+}
+
+impl RunnerError {
+    pub fn retryable(message: impl Into<String>) -> Self {
+        Self::Retryable(message.into())
+    }
+
+    pub fn fatal(message: impl Into<String>) -> Self {
+        Self::Fatal(message.into())
+    }
+}
+
+impl From<reqwest::Error> for RunnerError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Retryable(error.to_string())
+    }
+}
+
+/// Outcome of a successful run
+#[derive(Debug, Clone)]
+pub struct RunOutput {
+    pub summary: String,
+}
+
+#[async_trait]
+pub trait ExecutionRunner: Send + Sync {
+    async fn run(
+        &self,
+        ctx: &AttemptContext,
+        events: &AttemptEvents,
+    ) -> Result<RunOutput, RunnerError>;
+}
+
+/// Persistence side of an attempt, handed to runners
+pub struct AttemptEvents {
+    pool: PgPool,
+    execution: TaskExecution,
+    attempt_id: Uuid,
+}
+
+impl AttemptEvents {
+    pub fn new(pool: PgPool, execution: TaskExecution, attempt_id: Uuid) -> Self {
+        Self {
+            pool,
+            execution,
+            attempt_id,
+        }
+    }
+
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    /// Append a log line to the attempt
+    pub async fn log(&self, log_type: LogType, content: impl Into<String>) {
+        self.write_log(log_type, content.into(), None, None, None)
+            .await;
+    }
+
+    /// Append a tool call or tool result entry to the attempt
+    pub async fn log_tool(
+        &self,
+        log_type: LogType,
+        tool_name: &str,
+        content: impl Into<String>,
+        tool_input: Option<serde_json::Value>,
+        tool_output: Option<serde_json::Value>,
+    ) {
+        self.write_log(
+            log_type,
+            content.into(),
+            Some(tool_name.to_string()),
+            tool_input,
+            tool_output,
+        )
+        .await;
+    }
+
+    async fn write_log(
+        &self,
+        log_type: LogType,
+        content: String,
+        tool_name: Option<String>,
+        tool_input: Option<serde_json::Value>,
+        tool_output: Option<serde_json::Value>,
+    ) {
+        let log_type = serde_json::to_value(log_type)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_else(|| "system".to_string());
+
+        let result = ExecutionLogRepository::create(
+            &self.pool,
+            CreateExecutionLog {
+                attempt_id: self.attempt_id,
+                log_type,
+                content,
+                content_type: None,
+                tool_name,
+                tool_input,
+                tool_output,
+            },
+        )
+        .await;
+
+        if let Err(error) = result {
+            tracing::warn!(?error, attempt_id = %self.attempt_id, "failed to write execution log");
+        }
+    }
+
+    /// Persist usage for one provider request and enforce `max_tokens`
+    pub async fn record_usage(
+        &self,
+        ai_provider: &str,
+        ai_model: &str,
+        usage: TokenUsage,
+    ) -> Result<(), RunnerError> {
+        ExecutionAttemptRepository::update_tokens(
+            &self.pool,
+            self.attempt_id,
+            usage.input_tokens,
+            usage.output_tokens,
+            usage.cache_read_tokens,
+            usage.cache_write_tokens,
+        )
+        .await
+        .map_err(|e| RunnerError::retryable(e.to_string()))?;
+
+        let record = AiUsageRepository::create(
+            &self.pool,
+            CreateUsageRecord {
+                execution_id: Some(self.execution.id),
+                attempt_id: Some(self.attempt_id),
+                organization_id: self.execution.organization_id,
+                user_id: self.execution.initiated_by,
+                ai_provider: ai_provider.to_string(),
+                ai_model: ai_model.to_string(),
+                input_tokens: usage.input_tokens,
+                output_tokens: usage.output_tokens,
+                cache_read_tokens: usage.cache_read_tokens,
+                cache_write_tokens: usage.cache_write_tokens,
+                request_type: Some("tool_use".to_string()),
+            },
+        )
+        .await;
+        if let Err(error) = record {
+            tracing::warn!(?error, attempt_id = %self.attempt_id, "failed to record AI usage");
+        }
+
+        if let Some(limit) = self.execution.max_tokens {
+            let totals =
+                ExecutionAttemptRepository::get_total_tokens(&self.pool, self.execution.id)
+                    .await
+                    .map_err(|e| RunnerError::retryable(e.to_string()))?;
+            let used = totals.input_tokens + totals.output_tokens;
+            if used >= i64::from(limit) {
+                return Err(AttemptAbort::TokenBudgetExceeded {
+                    used,
+                    limit: i64::from(limit),
+                }
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Stop the runner if the execution was paused or cancelled from the API
+    pub async fn checkpoint(&self) -> Result<(), RunnerError> {
+        let execution = TaskExecutionRepository::find_by_id(&self.pool, self.execution.id)
+            .await
+            .map_err(|e| RunnerError::retryable(e.to_string()))?
+            .ok_or(AttemptAbort::Cancelled)?;
+
+        match execution.parsed_status() {
+            Some(ExecutionStatus::Paused) => Err(AttemptAbort::Paused.into()),
+            Some(ExecutionStatus::Cancelled) => Err(AttemptAbort::Cancelled.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Raise an approval and wait for a decision. Returns `true` when approved.
+    /// Expired approvals count as rejected.
+    pub async fn request_approval(
+        &self,
+        action_description: String,
+        tool_name: &str,
+        tool_input: serde_json::Value,
+        risk_level: &str,
+    ) -> Result<bool, RunnerError> {
+        let approval = ExecutionApprovalRepository::create(
+            &self.pool,
+            CreateApprovalRequest {
+                execution_id: self.execution.id,
+                attempt_id: Some(self.attempt_id),
+                approval_type: "tool_execution".to_string(),
+                action_description,
+                action_details: None,
+                tool_name: Some(tool_name.to_string()),
+                tool_input: Some(tool_input),
+                risk_level: Some(risk_level.to_string()),
+                expires_in_seconds: Some(APPROVAL_TTL_SECONDS),
+            },
+        )
+        .await
+        .map_err(|e| RunnerError::retryable(e.to_string()))?;
+
+        self.log(
+            LogType::System,
+            format!("Waiting for approval of `{tool_name}` ({})", approval.id),
+        )
+        .await;
+
+        loop {
+            self.checkpoint().await?;
+
+            let current = ExecutionApprovalRepository::find_by_id(&self.pool, approval.id)
+                .await
+                .map_err(|e| RunnerError::retryable(e.to_string()))?
+                .ok_or_else(|| RunnerError::fatal("approval disappeared"))?;
+
+            match current.status.as_str() {
+                "approved" | "auto_approved" => return Ok(true),
+                "rejected" | "expired" => return Ok(false),
+                _ => {}
+            }
+
+            if current
+                .expires_at
+                .is_some_and(|expires_at| expires_at < chrono::Utc::now())
+            {
+                let _ = ExecutionApprovalRepository::expire_old(&self.pool).await;
+                return Ok(false);
+            }
+
+            tokio::time::sleep(APPROVAL_POLL_INTERVAL).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn context(description: Option<&str>) -> AttemptContext {
+        let now = Utc::now();
+        AttemptContext {
+            execution: TaskExecution {
+                id: Uuid::new_v4(),
+                task_id: Uuid::new_v4(),
+                project_id: Uuid::new_v4(),
+                organization_id: Uuid::new_v4(),
+                status: "running".to_string(),
+                initiated_by: Uuid::new_v4(),
+                execution_mode: "standard".to_string(),
+                max_attempts: 3,
+                current_attempt: 1,
+                max_duration_seconds: Some(3600),
+                max_tokens: Some(100_000),
+                result_summary: None,
+                error_message: None,
+                created_at: now,
+                updated_at: now,
+                started_at: Some(now),
+                completed_at: None,
+                deleted_at: None,
+            },
+            attempt_id: Uuid::new_v4(),
+            attempt_number: 1,
+            task_title: "Fix login".to_string(),
+            task_description: description.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn prompt_includes_description_when_present() {
+        assert_eq!(context(None).prompt(), "Fix login");
+        assert_eq!(context(Some("  ")).prompt(), "Fix login");
+        assert_eq!(
+            context(Some("Users get a 500")).prompt(),
+            "Fix login\n\nUsers get a 500"
+        );
+    }
+}
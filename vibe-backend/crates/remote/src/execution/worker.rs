@@ -0,0 +1,782 @@
+//! Background worker that drives queued executions through their attempts
+
+use std::{future::Future, sync::Arc, time::Duration};
+
+use chrono::Utc;
+use sqlx::PgPool;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use super::runner::{
+    AttemptAbort, AttemptContext, AttemptEvents, ExecutionRunner, RunOutput, RunnerError,
+};
+use crate::db::{
+    execution_approvals::ExecutionApprovalRepository,
+    execution_attempts::{AttemptStatus, CreateExecutionAttempt, ExecutionAttemptRepository},
+    execution_logs::LogType,
+    task_executions::{ExecutionStatus, TaskExecution, TaskExecutionRepository},
+    tasks::SharedTaskRepository,
+};
+
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Used when an execution has no `max_duration_seconds` of its own
+const DEFAULT_MAX_DURATION: Duration = Duration::from_secs(60 * 60);
+/// A running attempt whose lease is not renewed within this long is failed
+/// and retried by whichever replica notices first
+const DEFAULT_LEASE: Duration = Duration::from_secs(60);
+const LEASE_EXPIRED_ERROR: &str = "worker stopped renewing the attempt lease";
+
+/// Outcome of a single attempt, before it is written back
+enum AttemptOutcome {
+    Completed(RunOutput),
+    Aborted(AttemptAbort),
+    Failed { error: String, retryable: bool },
+    TimedOut,
+}
+
+pub struct ExecutionWorker {
+    pool: PgPool,
+    runner: Arc<dyn ExecutionRunner>,
+    worker_id: String,
+    worker_region: Option<String>,
+    poll_interval: Duration,
+    lease: Duration,
+}
+
+impl ExecutionWorker {
+    pub fn new(pool: PgPool, runner: Arc<dyn ExecutionRunner>, worker_id: String) -> Self {
+        Self {
+            pool,
+            runner,
+            worker_id,
+            worker_region: None,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            lease: DEFAULT_LEASE,
+        }
+    }
+
+    pub fn with_region(mut self, region: Option<String>) -> Self {
+        self.worker_region = region;
+        self
+    }
+
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    pub fn with_lease(mut self, lease: Duration) -> Self {
+        self.lease = lease;
+        self
+    }
+
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move { self.run().await })
+    }
+
+    async fn run(self) {
+        tracing::info!(worker_id = %self.worker_id, "execution worker started");
+
+        if let Err(error) = self.recover_orphaned_attempts().await {
+            tracing::error!(?error, "failed to recover orphaned execution attempts");
+        }
+
+        loop {
+            match TaskExecutionRepository::claim_next_queued(&self.pool).await {
+                Ok(Some(execution)) => {
+                    let execution_id = execution.id;
+                    if let Err(error) = self.run_attempt(execution).await {
+                        tracing::error!(?error, %execution_id, "execution attempt failed to run");
+                    }
+                }
+                Ok(None) => {
+                    if let Err(error) = ExecutionApprovalRepository::expire_old(&self.pool).await {
+                        tracing::warn!(?error, "failed to expire stale approvals");
+                    }
+                    if let Err(error) = self.recover_orphaned_attempts().await {
+                        tracing::error!(?error, "failed to recover orphaned execution attempts");
+                    }
+                    tokio::time::sleep(self.poll_interval).await;
+                }
+                Err(error) => {
+                    tracing::error!(?error, "failed to claim queued execution");
+                    tokio::time::sleep(self.poll_interval).await;
+                }
+            }
+        }
+    }
+
+    /// Attempts whose lease ran out, on any worker, are failed and their
+    /// executions retried (or failed if out of attempts)
+    async fn recover_orphaned_attempts(&self) -> anyhow::Result<()> {
+        let attempts =
+            ExecutionAttemptRepository::reclaim_expired(&self.pool, LEASE_EXPIRED_ERROR).await?;
+
+        for attempt in attempts {
+            tracing::warn!(
+                attempt_id = %attempt.id,
+                execution_id = %attempt.execution_id,
+                worker_id = ?attempt.worker_id,
+                "recovering orphaned execution attempt"
+            );
+            if let Some(execution) =
+                TaskExecutionRepository::find_by_id(&self.pool, attempt.execution_id).await?
+                && execution.parsed_status() == Some(ExecutionStatus::Running)
+            {
+                self.retry_or_fail(&execution, LEASE_EXPIRED_ERROR).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "execution_worker.attempt",
+        skip(self, execution),
+        fields(execution_id = %execution.id, worker_id = %self.worker_id)
+    )]
+    async fn run_attempt(&self, execution: TaskExecution) -> anyhow::Result<()> {
+        let execution =
+            TaskExecutionRepository::increment_attempt(&self.pool, execution.id).await?;
+
+        let Some(task) = SharedTaskRepository::new(&self.pool)
+            .find_any_task_by_id(execution.task_id)
+            .await?
+        else {
+            TaskExecutionRepository::finish_running(
+                &self.pool,
+                execution.id,
+                ExecutionStatus::Failed,
+                None,
+                Some("task no longer exists"),
+            )
+            .await?;
+            return Ok(());
+        };
+
+        let attempt = ExecutionAttemptRepository::create(
+            &self.pool,
+            CreateExecutionAttempt {
+                execution_id: execution.id,
+                attempt_number: execution.current_attempt,
+                worker_id: Some(self.worker_id.clone()),
+                worker_region: self.worker_region.clone(),
+                ai_model: None,
+                ai_provider: None,
+            },
+        )
+        .await?;
+        ExecutionAttemptRepository::start_leased(&self.pool, attempt.id, self.lease_seconds())
+            .await?;
+
+        let ctx = AttemptContext {
+            execution: execution.clone(),
+            attempt_id: attempt.id,
+            attempt_number: attempt.attempt_number,
+            task_title: task.title,
+            task_description: task.description,
+        };
+        let events = AttemptEvents::new(self.pool.clone(), execution.clone(), attempt.id);
+
+        let run = tokio::time::timeout(
+            remaining_duration(&execution),
+            self.runner.run(&ctx, &events),
+        );
+        let Some(result) = self.holding_lease(attempt.id, run).await else {
+            // Whoever reclaimed the attempt has already failed and retried it
+            tracing::warn!(attempt_id = %attempt.id, "lost attempt lease; abandoning attempt");
+            return Ok(());
+        };
+
+        let outcome = match result {
+            Err(_) => AttemptOutcome::TimedOut,
+            Ok(Ok(output)) => AttemptOutcome::Completed(output),
+            Ok(Err(RunnerError::Abort(abort))) => AttemptOutcome::Aborted(abort),
+            Ok(Err(RunnerError::Fatal(error))) => AttemptOutcome::Failed {
+                error,
+                retryable: false,
+            },
+            Ok(Err(RunnerError::Retryable(error))) => AttemptOutcome::Failed {
+                error,
+                retryable: true,
+            },
+        };
+
+        self.record_outcome(&execution, attempt.id, &events, outcome)
+            .await
+    }
+
+    /// Drive `work` while renewing the attempt's lease every third of it.
+    /// Returns `None`, dropping `work`, once the lease has been lost.
+    async fn holding_lease<F: Future>(&self, attempt_id: Uuid, work: F) -> Option<F::Output> {
+        tokio::pin!(work);
+        let mut heartbeat = tokio::time::interval(self.lease / 3);
+        heartbeat.tick().await;
+
+        loop {
+            tokio::select! {
+                output = &mut work => return Some(output),
+                _ = heartbeat.tick() => {
+                    match ExecutionAttemptRepository::renew_lease(
+                        &self.pool,
+                        attempt_id,
+                        self.lease_seconds(),
+                    )
+                    .await
+                    {
+                        Ok(true) => {}
+                        Ok(false) => return None,
+                        // The next renewal may still land before the lease runs out
+                        Err(error) => tracing::warn!(?error, %attempt_id, "failed to renew attempt lease"),
+                    }
+                }
+            }
+        }
+    }
+
+    fn lease_seconds(&self) -> i64 {
+        self.lease.as_secs().max(1) as i64
+    }
+
+    async fn record_outcome(
+        &self,
+        execution: &TaskExecution,
+        attempt_id: Uuid,
+        events: &AttemptEvents,
+        outcome: AttemptOutcome,
+    ) -> anyhow::Result<()> {
+        match outcome {
+            AttemptOutcome::Completed(output) => {
+                ExecutionAttemptRepository::mark_finished(
+                    &self.pool,
+                    attempt_id,
+                    AttemptStatus::Completed,
+                    None,
+                )
+                .await?;
+                TaskExecutionRepository::finish_running(
+                    &self.pool,
+                    execution.id,
+                    ExecutionStatus::Completed,
+                    Some(&output.summary),
+                    None,
+                )
+                .await?;
+            }
+            // Pause and cancel already moved the execution; only the attempt is closed
+            AttemptOutcome::Aborted(abort @ (AttemptAbort::Paused | AttemptAbort::Cancelled)) => {
+                events.log(LogType::System, abort.to_string()).await;
+                ExecutionAttemptRepository::mark_finished(
+                    &self.pool,
+                    attempt_id,
+                    AttemptStatus::Cancelled,
+                    Some(&abort.to_string()),
+                )
+                .await?;
+            }
+            AttemptOutcome::Aborted(abort @ AttemptAbort::TokenBudgetExceeded { .. }) => {
+                let error = abort.to_string();
+                events.log(LogType::Stderr, error.clone()).await;
+                ExecutionAttemptRepository::mark_failed(&self.pool, attempt_id, &error).await?;
+                TaskExecutionRepository::finish_running(
+                    &self.pool,
+                    execution.id,
+                    ExecutionStatus::Failed,
+                    None,
+                    Some(&error),
+                )
+                .await?;
+            }
+            AttemptOutcome::Failed { error, retryable } => {
+                events.log(LogType::Stderr, error.clone()).await;
+                ExecutionAttemptRepository::mark_failed(&self.pool, attempt_id, &error).await?;
+                if retryable {
+                    self.retry_or_fail(execution, &error).await?;
+                } else {
+                    TaskExecutionRepository::finish_running(
+                        &self.pool,
+                        execution.id,
+                        ExecutionStatus::Failed,
+                        None,
+                        Some(&error),
+                    )
+                    .await?;
+                }
+            }
+            AttemptOutcome::TimedOut => {
+                let error = "execution exceeded max_duration_seconds";
+                events.log(LogType::Stderr, error).await;
+                ExecutionAttemptRepository::mark_finished(
+                    &self.pool,
+                    attempt_id,
+                    AttemptStatus::Timeout,
+                    Some(error),
+                )
+                .await?;
+                TaskExecutionRepository::finish_running(
+                    &self.pool,
+                    execution.id,
+                    ExecutionStatus::Timeout,
+                    None,
+                    Some(error),
+                )
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fail the running execution, then put it back in the queue if it has
+    /// attempts left. Does nothing if it was paused or cancelled meanwhile.
+    async fn retry_or_fail(&self, execution: &TaskExecution, error: &str) -> anyhow::Result<()> {
+        let Some(failed) = TaskExecutionRepository::finish_running(
+            &self.pool,
+            execution.id,
+            ExecutionStatus::Failed,
+            None,
+            Some(error),
+        )
+        .await?
+        else {
+            return Ok(());
+        };
+
+        if failed.attempts_remaining()
+            && TaskExecutionRepository::requeue(&self.pool, failed.id)
+                .await?
+                .is_some()
+        {
+            tracing::info!(
+                execution_id = %failed.id,
+                attempt = failed.current_attempt,
+                max_attempts = failed.max_attempts,
+                "requeued execution after failed attempt"
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Time left before `max_duration_seconds` elapses for the current attempt,
+/// measured from when it was claimed
+fn remaining_duration(execution: &TaskExecution) -> Duration {
+    let limit = execution
+        .max_duration_seconds
+        .and_then(|secs| u64::try_from(secs).ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_MAX_DURATION);
+    let elapsed = execution
+        .started_at
+        .and_then(|started_at| (Utc::now() - started_at).to_std().ok())
+        .unwrap_or_default();
+    limit.saturating_sub(elapsed)
+}
+
+/// These run against a real Postgres: `DATABASE_URL=... cargo test -p remote -- --ignored`
+#[cfg(test)]
+mod tests {
+    use std::{collections::VecDeque, sync::Mutex};
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::db::task_executions::CreateTaskExecution;
+
+    const ORGANIZATION_ID: Uuid = Uuid::from_u128(1);
+    const USER_ID: Uuid = Uuid::from_u128(2);
+    const PROJECT_ID: Uuid = Uuid::from_u128(3);
+    const TASK_ID: Uuid = Uuid::from_u128(4);
+
+    /// Hands out one scripted result per attempt
+    struct ScriptedRunner(Mutex<VecDeque<Result<RunOutput, RunnerError>>>);
+
+    impl ScriptedRunner {
+        fn new(results: Vec<Result<RunOutput, RunnerError>>) -> Arc<Self> {
+            Arc::new(Self(Mutex::new(results.into())))
+        }
+    }
+
+    #[async_trait]
+    impl ExecutionRunner for ScriptedRunner {
+        async fn run(
+            &self,
+            _ctx: &AttemptContext,
+            _events: &AttemptEvents,
+        ) -> Result<RunOutput, RunnerError> {
+            self.0
+                .lock()
+                .unwrap()
+                .pop_front()
+                .expect("runner called more often than scripted")
+        }
+    }
+
+    /// Never finishes, like a provider call that hangs
+    struct StalledRunner;
+
+    #[async_trait]
+    impl ExecutionRunner for StalledRunner {
+        async fn run(
+            &self,
+            _ctx: &AttemptContext,
+            _events: &AttemptEvents,
+        ) -> Result<RunOutput, RunnerError> {
+            std::future::pending().await
+        }
+    }
+
+    /// Pretend the worker holding the attempt died and its lease ran out
+    async fn expire_lease(pool: &PgPool, attempt_id: Uuid) {
+        sqlx::query(
+            "UPDATE execution_attempts SET locked_until = NOW() - INTERVAL '1 second' WHERE id = $1",
+        )
+        .bind(attempt_id)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    fn worker(pool: &PgPool, runner: Arc<ScriptedRunner>) -> ExecutionWorker {
+        ExecutionWorker::new(pool.clone(), runner, "test-worker".to_string())
+    }
+
+    fn done(summary: &str) -> Result<RunOutput, RunnerError> {
+        Ok(RunOutput {
+            summary: summary.to_string(),
+        })
+    }
+
+    async fn queue_execution(pool: &PgPool, max_attempts: i32) -> TaskExecution {
+        let execution = TaskExecutionRepository::create(
+            pool,
+            CreateTaskExecution {
+                task_id: TASK_ID,
+                project_id: PROJECT_ID,
+                organization_id: ORGANIZATION_ID,
+                initiated_by: USER_ID,
+                execution_mode: None,
+                max_attempts: Some(max_attempts),
+                max_duration_seconds: None,
+                max_tokens: None,
+            },
+        )
+        .await
+        .unwrap();
+        TaskExecutionRepository::transition(
+            pool,
+            execution.id,
+            &[ExecutionStatus::Pending],
+            ExecutionStatus::Queued,
+        )
+        .await
+        .unwrap()
+        .unwrap()
+    }
+
+    /// Claim the next queued execution and run one attempt of it
+    async fn run_next(worker: &ExecutionWorker) -> TaskExecution {
+        let claimed = TaskExecutionRepository::claim_next_queued(&worker.pool)
+            .await
+            .unwrap()
+            .expect("an execution is queued");
+        worker.run_attempt(claimed.clone()).await.unwrap();
+        TaskExecutionRepository::find_by_id(&worker.pool, claimed.id)
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    async fn attempt_statuses(pool: &PgPool, execution_id: Uuid) -> Vec<String> {
+        ExecutionAttemptRepository::list_by_execution(pool, execution_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|attempt| attempt.status)
+            .collect()
+    }
+
+    #[ignore = "requires DATABASE_URL"]
+    #[sqlx::test(
+        migrations = false,
+        fixtures(
+            "../../migrations/20251001000000_shared_tasks_activity.sql",
+            "../../migrations/20251201000000_drop_unused_activity_and_columns.sql",
+            "../../migrations/20251201010000_unify_task_status_enums.sql",
+            "../../migrations/20260123202639_cloud_ai_execution_schema.sql",
+            "../../migrations/20260219090000_add_execution_attempt_leases.sql",
+            "../../tests/fixtures/execution_seed.sql"
+        )
+    )]
+    async fn completed_attempt_finishes_execution(pool: PgPool) {
+        let execution = queue_execution(&pool, 3).await;
+        let worker = worker(&pool, ScriptedRunner::new(vec![done("Added retries")]));
+
+        let execution = run_next(&worker).await;
+        assert_eq!(execution.parsed_status(), Some(ExecutionStatus::Completed));
+        assert_eq!(execution.result_summary.as_deref(), Some("Added retries"));
+        assert_eq!(execution.current_attempt, 1);
+        assert_eq!(attempt_statuses(&pool, execution.id).await, ["completed"]);
+    }
+
+    #[ignore = "requires DATABASE_URL"]
+    #[sqlx::test(
+        migrations = false,
+        fixtures(
+            "../../migrations/20251001000000_shared_tasks_activity.sql",
+            "../../migrations/20251201000000_drop_unused_activity_and_columns.sql",
+            "../../migrations/20251201010000_unify_task_status_enums.sql",
+            "../../migrations/20260123202639_cloud_ai_execution_schema.sql",
+            "../../migrations/20260219090000_add_execution_attempt_leases.sql",
+            "../../tests/fixtures/execution_seed.sql"
+        )
+    )]
+    async fn retryable_failure_requeues_until_attempts_run_out(pool: PgPool) {
+        let execution = queue_execution(&pool, 2).await;
+        let worker = worker(
+            &pool,
+            ScriptedRunner::new(vec![
+                Err(RunnerError::retryable("provider returned 503")),
+                Err(RunnerError::retryable("provider returned 503 again")),
+            ]),
+        );
+
+        let first = run_next(&worker).await;
+        assert_eq!(first.parsed_status(), Some(ExecutionStatus::Queued));
+        assert_eq!(first.error_message, None);
+        assert_eq!(first.completed_at, None);
+
+        let second = run_next(&worker).await;
+        assert_eq!(second.parsed_status(), Some(ExecutionStatus::Failed));
+        assert_eq!(second.current_attempt, 2);
+        assert_eq!(
+            second.error_message.as_deref(),
+            Some("provider returned 503 again")
+        );
+        assert_eq!(
+            attempt_statuses(&pool, execution.id).await,
+            ["failed", "failed"]
+        );
+    }
+
+    #[ignore = "requires DATABASE_URL"]
+    #[sqlx::test(
+        migrations = false,
+        fixtures(
+            "../../migrations/20251001000000_shared_tasks_activity.sql",
+            "../../migrations/20251201000000_drop_unused_activity_and_columns.sql",
+            "../../migrations/20251201010000_unify_task_status_enums.sql",
+            "../../migrations/20260123202639_cloud_ai_execution_schema.sql",
+            "../../migrations/20260219090000_add_execution_attempt_leases.sql",
+            "../../tests/fixtures/execution_seed.sql"
+        )
+    )]
+    async fn fatal_failure_is_not_retried(pool: PgPool) {
+        queue_execution(&pool, 3).await;
+        let worker = worker(
+            &pool,
+            ScriptedRunner::new(vec![Err(RunnerError::fatal("no API key configured"))]),
+        );
+
+        let execution = run_next(&worker).await;
+        assert_eq!(execution.parsed_status(), Some(ExecutionStatus::Failed));
+        assert!(
+            TaskExecutionRepository::claim_next_queued(&pool)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[ignore = "requires DATABASE_URL"]
+    #[sqlx::test(
+        migrations = false,
+        fixtures(
+            "../../migrations/20251001000000_shared_tasks_activity.sql",
+            "../../migrations/20251201000000_drop_unused_activity_and_columns.sql",
+            "../../migrations/20251201010000_unify_task_status_enums.sql",
+            "../../migrations/20260123202639_cloud_ai_execution_schema.sql",
+            "../../migrations/20260219090000_add_execution_attempt_leases.sql",
+            "../../tests/fixtures/execution_seed.sql"
+        )
+    )]
+    async fn expired_attempt_is_reclaimed_by_any_worker(pool: PgPool) {
+        let execution = queue_execution(&pool, 3).await;
+        let claimed = TaskExecutionRepository::claim_next_queued(&pool)
+            .await
+            .unwrap()
+            .unwrap();
+        let claimed = TaskExecutionRepository::increment_attempt(&pool, claimed.id)
+            .await
+            .unwrap();
+        let attempt = ExecutionAttemptRepository::create(
+            &pool,
+            CreateExecutionAttempt {
+                execution_id: claimed.id,
+                attempt_number: claimed.current_attempt,
+                worker_id: Some("crashed-worker".to_string()),
+                worker_region: None,
+                ai_model: None,
+                ai_provider: None,
+            },
+        )
+        .await
+        .unwrap();
+        ExecutionAttemptRepository::start_leased(&pool, attempt.id, 60)
+            .await
+            .unwrap();
+
+        // A live lease is left alone, whoever holds it
+        let other = worker(&pool, ScriptedRunner::new(vec![]));
+        other.recover_orphaned_attempts().await.unwrap();
+        assert_eq!(attempt_statuses(&pool, execution.id).await, ["running"]);
+
+        expire_lease(&pool, attempt.id).await;
+        other.recover_orphaned_attempts().await.unwrap();
+        assert_eq!(attempt_statuses(&pool, execution.id).await, ["failed"]);
+        let execution = TaskExecutionRepository::find_by_id(&pool, execution.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(execution.parsed_status(), Some(ExecutionStatus::Queued));
+    }
+
+    #[ignore = "requires DATABASE_URL"]
+    #[sqlx::test(
+        migrations = false,
+        fixtures(
+            "../../migrations/20251001000000_shared_tasks_activity.sql",
+            "../../migrations/20251201000000_drop_unused_activity_and_columns.sql",
+            "../../migrations/20251201010000_unify_task_status_enums.sql",
+            "../../migrations/20260123202639_cloud_ai_execution_schema.sql",
+            "../../migrations/20260219090000_add_execution_attempt_leases.sql",
+            "../../tests/fixtures/execution_seed.sql"
+        )
+    )]
+    async fn worker_that_lost_its_lease_abandons_the_attempt(pool: PgPool) {
+        let execution = queue_execution(&pool, 3).await;
+        let stalled = ExecutionWorker::new(
+            pool.clone(),
+            Arc::new(StalledRunner),
+            "stalled-worker".to_string(),
+        )
+        .with_lease(Duration::from_secs(3));
+        let claimed = TaskExecutionRepository::claim_next_queued(&pool)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let reclaim = async {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            let attempt = ExecutionAttemptRepository::list_by_execution(&pool, execution.id)
+                .await
+                .unwrap()
+                .remove(0);
+            expire_lease(&pool, attempt.id).await;
+            worker(&pool, ScriptedRunner::new(vec![]))
+                .recover_orphaned_attempts()
+                .await
+                .unwrap();
+        };
+        let (abandoned, ()) = tokio::join!(stalled.run_attempt(claimed), reclaim);
+        abandoned.unwrap();
+
+        // The reclaiming worker's outcome stands
+        let attempts = ExecutionAttemptRepository::list_by_execution(&pool, execution.id)
+            .await
+            .unwrap();
+        assert_eq!(attempts.len(), 1);
+        assert_eq!(attempts[0].status, "failed");
+        assert_eq!(
+            attempts[0].error_message.as_deref(),
+            Some(LEASE_EXPIRED_ERROR)
+        );
+        let execution = TaskExecutionRepository::find_by_id(&pool, execution.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(execution.parsed_status(), Some(ExecutionStatus::Queued));
+    }
+
+    #[ignore = "requires DATABASE_URL"]
+    #[sqlx::test(
+        migrations = false,
+        fixtures(
+            "../../migrations/20251001000000_shared_tasks_activity.sql",
+            "../../migrations/20251201000000_drop_unused_activity_and_columns.sql",
+            "../../migrations/20251201010000_unify_task_status_enums.sql",
+            "../../migrations/20260123202639_cloud_ai_execution_schema.sql",
+            "../../migrations/20260219090000_add_execution_attempt_leases.sql",
+            "../../tests/fixtures/execution_seed.sql"
+        )
+    )]
+    async fn running_execution_is_never_requeued_or_timed_out_late(pool: PgPool) {
+        let execution = queue_execution(&pool, 3).await;
+        let claimed = TaskExecutionRepository::claim_next_queued(&pool)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(claimed.id, execution.id);
+
+        // A live execution stays with the worker that claimed it
+        assert!(
+            TaskExecutionRepository::requeue(&pool, execution.id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        TaskExecutionRepository::finish_running(
+            &pool,
+            execution.id,
+            ExecutionStatus::Completed,
+            Some("done"),
+            None,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert!(
+            TaskExecutionRepository::mark_timeout(&pool, execution.id, "too slow")
+                .await
+                .unwrap()
+                .is_none()
+        );
+        let execution = TaskExecutionRepository::find_by_id(&pool, execution.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(execution.parsed_status(), Some(ExecutionStatus::Completed));
+    }
+
+    #[ignore = "requires DATABASE_URL"]
+    #[sqlx::test(
+        migrations = false,
+        fixtures(
+            "../../migrations/20251001000000_shared_tasks_activity.sql",
+            "../../migrations/20251201000000_drop_unused_activity_and_columns.sql",
+            "../../migrations/20251201010000_unify_task_status_enums.sql",
+            "../../migrations/20260123202639_cloud_ai_execution_schema.sql",
+            "../../migrations/20260219090000_add_execution_attempt_leases.sql",
+            "../../tests/fixtures/execution_seed.sql"
+        )
+    )]
+    async fn each_claim_restarts_the_time_limit(pool: PgPool) {
+        let execution = queue_execution(&pool, 3).await;
+        sqlx::query(
+            "UPDATE task_executions SET started_at = NOW() - INTERVAL '2 hours' WHERE id = $1",
+        )
+        .bind(execution.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let claimed = TaskExecutionRepository::claim_next_queued(&pool)
+            .await
+            .unwrap()
+            .unwrap();
+        let started_at = claimed.started_at.unwrap();
+        assert!(Utc::now() - started_at < chrono::Duration::minutes(1));
+        assert!(remaining_duration(&claimed) > Duration::from_secs(59 * 60));
+    }
+}
@@ -0,0 +1,150 @@
+//! Jobs every remote replica schedules
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use db_crate::models::email_verification::EmailVerification;
+use sqlx::PgPool;
+
+use super::{Job, JobScheduler, Schedule};
+use crate::db::{
+    ai_sessions::AiSessionRepository,
+    invitations::InvitationRepository,
+    notifications::{find_due_reminders, notify_due_date_approaching},
+    scheduled_jobs::ScheduledJobRepository,
+    task_executions::TaskExecutionRepository,
+    team_cycles::TeamCycleRepository,
+};
+
+/// How far ahead of a due date the assignee is reminded
+const DUE_REMINDER_WINDOW_HOURS: i64 = 24;
+/// How long soft-deleted rows and finished one-shot jobs are kept
+const RETENTION_DAYS: i64 = 30;
+
+/// Scheduler with the built-in recurring jobs registered
+pub fn default_scheduler(pool: PgPool, worker_id: String) -> JobScheduler {
+    JobScheduler::new(pool, worker_id)
+        .recurring(CycleRolloverJob, Schedule::every_minutes(5))
+        .recurring(DueDateRemindersJob, Schedule::every_minutes(15))
+        .recurring(ExpireInvitationsJob, Schedule::every_hours(1))
+        .recurring(ExpireEmailVerificationsJob, Schedule::every_hours(1))
+        .recurring(PurgeDeletedJob, Schedule::DailyAt { hour: 3, minute: 0 })
+}
+
+/// Complete ended team cycles and roll unfinished issues into the next cycle
+struct CycleRolloverJob;
+
+#[async_trait]
+impl Job for CycleRolloverJob {
+    fn kind(&self) -> &'static str {
+        "cycle_rollover"
+    }
+
+    async fn run(&self, pool: &PgPool, _payload: &serde_json::Value) -> anyhow::Result<()> {
+        let rollovers = TeamCycleRepository::roll_over_ended(pool).await?;
+        if !rollovers.is_empty() {
+            tracing::info!(count = rollovers.len(), "rolled over ended cycles");
+        }
+        Ok(())
+    }
+}
+
+/// Notify assignees of tasks that are due within the next day
+struct DueDateRemindersJob;
+
+#[async_trait]
+impl Job for DueDateRemindersJob {
+    fn kind(&self) -> &'static str {
+        "due_date_reminders"
+    }
+
+    async fn run(&self, pool: &PgPool, _payload: &serde_json::Value) -> anyhow::Result<()> {
+        let window = Duration::hours(DUE_REMINDER_WINDOW_HOURS);
+        let due = find_due_reminders(pool, Utc::now() + window, window).await?;
+
+        let mut sent = 0;
+        for task in &due {
+            let due_date = task.due_date.format("%Y-%m-%d %H:%M UTC").to_string();
+            match notify_due_date_approaching(
+                pool,
+                task.assignee_id,
+                task.task_id,
+                &task.title,
+                &due_date,
+                task.project_id,
+                None,
+            )
+            .await
+            {
+                Ok(()) => sent += 1,
+                Err(error) => {
+                    tracing::warn!(?error, task_id = %task.task_id, "failed to send due date reminder")
+                }
+            }
+        }
+
+        if sent > 0 {
+            tracing::info!(sent, "sent due date reminders");
+        }
+        Ok(())
+    }
+}
+
+/// Mark organization invitations past their expiry as expired
+struct ExpireInvitationsJob;
+
+#[async_trait]
+impl Job for ExpireInvitationsJob {
+    fn kind(&self) -> &'static str {
+        "expire_invitations"
+    }
+
+    async fn run(&self, pool: &PgPool, _payload: &serde_json::Value) -> anyhow::Result<()> {
+        let expired = InvitationRepository::new(pool).expire_stale().await?;
+        if expired > 0 {
+            tracing::info!(expired, "expired stale invitations");
+        }
+        Ok(())
+    }
+}
+
+/// Delete email verifications that expired without being used
+struct ExpireEmailVerificationsJob;
+
+#[async_trait]
+impl Job for ExpireEmailVerificationsJob {
+    fn kind(&self) -> &'static str {
+        "expire_email_verifications"
+    }
+
+    async fn run(&self, pool: &PgPool, _payload: &serde_json::Value) -> anyhow::Result<()> {
+        let deleted = EmailVerification::cleanup_expired(pool).await?;
+        if deleted > 0 {
+            tracing::info!(deleted, "deleted expired email verifications");
+        }
+        Ok(())
+    }
+}
+
+/// Permanently remove soft-deleted rows and old finished jobs once they are
+/// past the retention period
+struct PurgeDeletedJob;
+
+#[async_trait]
+impl Job for PurgeDeletedJob {
+    fn kind(&self) -> &'static str {
+        "purge_deleted"
+    }
+
+    async fn run(&self, pool: &PgPool, _payload: &serde_json::Value) -> anyhow::Result<()> {
+        let before = Utc::now() - Duration::days(RETENTION_DAYS);
+
+        let executions = TaskExecutionRepository::purge_deleted(pool, before).await?;
+        let sessions = AiSessionRepository::purge_deleted(pool, before).await?;
+        let jobs = ScheduledJobRepository::purge_finished(pool, before).await?;
+
+        if executions + sessions + jobs > 0 {
+            tracing::info!(executions, sessions, jobs, "purged deleted rows");
+        }
+        Ok(())
+    }
+}
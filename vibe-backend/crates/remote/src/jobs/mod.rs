@@ -0,0 +1,401 @@
+//! Durable background job scheduler backed by the `scheduled_jobs` table
+//!
+//! Recurring jobs are registered with a [`Schedule`] at startup; one-shot
+//! jobs are queued with [`enqueue`]. Every replica runs a scheduler, and a
+//! lease on each claimed row keeps a job from running on two replicas at once.
+
+mod builtin;
+mod schedule;
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+pub use builtin::default_scheduler;
+use chrono::{DateTime, Utc};
+pub use schedule::Schedule;
+use sqlx::PgPool;
+use tokio::task::JoinHandle;
+
+use crate::db::scheduled_jobs::{ScheduledJob, ScheduledJobError, ScheduledJobRepository};
+
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(15);
+/// A job that runs longer than its lease is abandoned and may be picked up
+/// by another replica
+const DEFAULT_LEASE: Duration = Duration::from_secs(10 * 60);
+const DEFAULT_MAX_ATTEMPTS: i32 = 5;
+/// Retry backoff for one-shot jobs: 30s, 60s, 120s, ... capped at 30 minutes
+const RETRY_BASE_SECONDS: i64 = 30;
+const RETRY_MAX_SECONDS: i64 = 30 * 60;
+
+/// A unit of background work
+#[async_trait]
+pub trait Job: Send + Sync {
+    /// Stable identifier stored in `scheduled_jobs.kind`
+    fn kind(&self) -> &'static str;
+
+    async fn run(&self, pool: &PgPool, payload: &serde_json::Value) -> anyhow::Result<()>;
+}
+
+/// Queue a one-shot job of `kind` to run at `run_at`. A scheduler with a job
+/// of that kind registered picks it up.
+pub async fn enqueue(
+    pool: &PgPool,
+    kind: &str,
+    payload: serde_json::Value,
+    run_at: DateTime<Utc>,
+) -> Result<ScheduledJob, ScheduledJobError> {
+    ScheduledJobRepository::enqueue(pool, kind, &payload, run_at, DEFAULT_MAX_ATTEMPTS).await
+}
+
+pub struct JobScheduler {
+    pool: PgPool,
+    worker_id: String,
+    jobs: HashMap<&'static str, Arc<dyn Job>>,
+    schedules: HashMap<&'static str, Schedule>,
+    poll_interval: Duration,
+    lease: Duration,
+}
+
+impl JobScheduler {
+    pub fn new(pool: PgPool, worker_id: String) -> Self {
+        Self {
+            pool,
+            worker_id,
+            jobs: HashMap::new(),
+            schedules: HashMap::new(),
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            lease: DEFAULT_LEASE,
+        }
+    }
+
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    pub fn with_lease(mut self, lease: Duration) -> Self {
+        self.lease = lease;
+        self
+    }
+
+    /// Handle one-shot jobs of this job's kind
+    pub fn register(mut self, job: impl Job + 'static) -> Self {
+        self.jobs.insert(job.kind(), Arc::new(job));
+        self
+    }
+
+    /// Run this job on `schedule`, and handle one-shot jobs of its kind
+    pub fn recurring(mut self, job: impl Job + 'static, schedule: Schedule) -> Self {
+        self.schedules.insert(job.kind(), schedule);
+        self.register(job)
+    }
+
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move { self.run().await })
+    }
+
+    async fn run(self) {
+        tracing::info!(
+            worker_id = %self.worker_id,
+            jobs = self.jobs.len(),
+            recurring = self.schedules.len(),
+            "job scheduler started"
+        );
+
+        let now = Utc::now();
+        for (kind, schedule) in &self.schedules {
+            if let Err(error) = ScheduledJobRepository::ensure_recurring(
+                &self.pool,
+                kind,
+                kind,
+                schedule.first_run(now),
+            )
+            .await
+            {
+                tracing::error!(?error, %kind, "failed to register recurring job");
+            }
+        }
+
+        let kinds: Vec<&str> = self.jobs.keys().copied().collect();
+        loop {
+            match ScheduledJobRepository::claim_due(
+                &self.pool,
+                &self.worker_id,
+                &kinds,
+                self.lease.as_secs() as i64,
+            )
+            .await
+            {
+                // Drain everything that is due before sleeping again
+                Ok(Some(job)) => self.run_job(job).await,
+                Ok(None) => tokio::time::sleep(self.poll_interval).await,
+                Err(error) => {
+                    tracing::error!(?error, "failed to claim scheduled job");
+                    tokio::time::sleep(self.poll_interval).await;
+                }
+            }
+        }
+    }
+
+    async fn run_job(&self, job: ScheduledJob) {
+        let Some(handler) = self.jobs.get(job.kind.as_str()).cloned() else {
+            // Not reachable: claims are filtered to registered kinds
+            return;
+        };
+        let schedule = self.schedules.get(job.kind.as_str()).copied();
+
+        // A one-shot job whose lease kept expiring has used up its attempts
+        let outcome = if schedule.is_none() && job.attempts > job.max_attempts {
+            Err(anyhow::anyhow!(
+                "job did not finish within its lease after {} attempts",
+                job.max_attempts
+            ))
+        } else {
+            let started = std::time::Instant::now();
+            let result = tokio::time::timeout(self.lease, handler.run(&self.pool, &job.payload))
+                .await
+                .unwrap_or_else(|_| Err(anyhow::anyhow!("job timed out after {:?}", self.lease)));
+            tracing::debug!(
+                job_id = %job.id,
+                kind = %job.kind,
+                duration_ms = started.elapsed().as_millis(),
+                "scheduled job finished"
+            );
+            result
+        };
+
+        let now = Utc::now();
+        let recorded = match (&outcome, schedule) {
+            (Ok(()), schedule) => {
+                ScheduledJobRepository::complete(
+                    &self.pool,
+                    job.id,
+                    &self.worker_id,
+                    schedule.map(|s| s.next_after(now)),
+                )
+                .await
+            }
+            // Recurring jobs are never given up on; they try again next time
+            (Err(error), Some(schedule)) => {
+                tracing::warn!(job_id = %job.id, kind = %job.kind, ?error, "recurring job failed");
+                ScheduledJobRepository::fail(
+                    &self.pool,
+                    job.id,
+                    &self.worker_id,
+                    &format!("{error:#}"),
+                    Some(schedule.next_after(now)),
+                    true,
+                )
+                .await
+            }
+            (Err(error), None) => {
+                let retry_at =
+                    (job.attempts < job.max_attempts).then(|| now + retry_delay(job.attempts));
+                tracing::warn!(
+                    job_id = %job.id,
+                    kind = %job.kind,
+                    attempts = job.attempts,
+                    will_retry = retry_at.is_some(),
+                    ?error,
+                    "scheduled job failed"
+                );
+                ScheduledJobRepository::fail(
+                    &self.pool,
+                    job.id,
+                    &self.worker_id,
+                    &format!("{error:#}"),
+                    retry_at,
+                    false,
+                )
+                .await
+            }
+        };
+
+        match recorded {
+            Ok(true) => {}
+            Ok(false) => {
+                tracing::warn!(job_id = %job.id, kind = %job.kind, "lost lease before recording job outcome")
+            }
+            Err(error) => {
+                tracing::error!(?error, job_id = %job.id, "failed to record job outcome")
+            }
+        }
+    }
+}
+
+fn retry_delay(attempts: i32) -> chrono::Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 16) as u32;
+    let seconds = RETRY_BASE_SECONDS
+        .saturating_mul(2_i64.saturating_pow(exponent))
+        .min(RETRY_MAX_SECONDS);
+    chrono::Duration::seconds(seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    const WORKER: &str = "test-worker";
+
+    /// Fails the first `failures` runs, then succeeds
+    struct FlakyJob {
+        failures: usize,
+        runs: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Job for FlakyJob {
+        fn kind(&self) -> &'static str {
+            "flaky"
+        }
+
+        async fn run(&self, _pool: &PgPool, _payload: &serde_json::Value) -> anyhow::Result<()> {
+            let run = self.runs.fetch_add(1, Ordering::SeqCst);
+            if run < self.failures {
+                anyhow::bail!("run {} failed", run + 1);
+            }
+            Ok(())
+        }
+    }
+
+    fn scheduler(pool: &PgPool, failures: usize) -> (JobScheduler, Arc<AtomicUsize>) {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let scheduler = JobScheduler::new(pool.clone(), WORKER.to_string()).register(FlakyJob {
+            failures,
+            runs: runs.clone(),
+        });
+        (scheduler, runs)
+    }
+
+    async fn claim_and_run(scheduler: &JobScheduler) -> ScheduledJob {
+        let job = ScheduledJobRepository::claim_due(&scheduler.pool, WORKER, &["flaky"], 600)
+            .await
+            .unwrap()
+            .expect("a job is due");
+        let id = job.id;
+        scheduler.run_job(job).await;
+        sqlx::query_as::<_, ScheduledJob>("SELECT * FROM scheduled_jobs WHERE id = $1")
+            .bind(id)
+            .fetch_one(&scheduler.pool)
+            .await
+            .unwrap()
+    }
+
+    /// Make a pending retry due now instead of after its backoff
+    async fn make_due(pool: &PgPool, id: uuid::Uuid) {
+        sqlx::query("UPDATE scheduled_jobs SET run_at = NOW() WHERE id = $1")
+            .bind(id)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn retry_delay_doubles_and_caps() {
+        let delays: Vec<i64> = (1..=8).map(|a| retry_delay(a).num_seconds()).collect();
+        assert_eq!(delays, [30, 60, 120, 240, 480, 960, 1800, 1800]);
+        assert_eq!(retry_delay(0).num_seconds(), 30);
+        assert_eq!(retry_delay(i32::MAX).num_seconds(), RETRY_MAX_SECONDS);
+    }
+
+    #[ignore = "requires DATABASE_URL"]
+    #[sqlx::test(
+        migrations = false,
+        fixtures("../../migrations/20260203090000_create_scheduled_jobs.sql")
+    )]
+    async fn failed_one_shot_job_backs_off_then_succeeds(pool: PgPool) {
+        let (scheduler, runs) = scheduler(&pool, 1);
+        let job = enqueue(&pool, "flaky", serde_json::json!({}), Utc::now())
+            .await
+            .unwrap();
+
+        let before = Utc::now();
+        let retried = claim_and_run(&scheduler).await;
+        assert_eq!(retried.status, "pending");
+        assert_eq!(retried.last_error.as_deref(), Some("run 1 failed"));
+        let backoff = (retried.run_at - before).num_seconds();
+        assert!((RETRY_BASE_SECONDS - 1..=RETRY_BASE_SECONDS + 1).contains(&backoff));
+
+        make_due(&pool, job.id).await;
+        let finished = claim_and_run(&scheduler).await;
+        assert_eq!(finished.status, "succeeded");
+        assert!(finished.last_error.is_none());
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+
+    #[ignore = "requires DATABASE_URL"]
+    #[sqlx::test(
+        migrations = false,
+        fixtures("../../migrations/20260203090000_create_scheduled_jobs.sql")
+    )]
+    async fn one_shot_job_fails_for_good_after_max_attempts(pool: PgPool) {
+        let (scheduler, runs) = scheduler(&pool, usize::MAX);
+        let job =
+            ScheduledJobRepository::enqueue(&pool, "flaky", &serde_json::json!({}), Utc::now(), 2)
+                .await
+                .unwrap();
+
+        assert_eq!(claim_and_run(&scheduler).await.status, "pending");
+        make_due(&pool, job.id).await;
+        let failed = claim_and_run(&scheduler).await;
+        assert_eq!(failed.status, "failed");
+        assert_eq!(failed.attempts, 2);
+        assert_eq!(failed.last_error.as_deref(), Some("run 2 failed"));
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+
+    #[ignore = "requires DATABASE_URL"]
+    #[sqlx::test(
+        migrations = false,
+        fixtures("../../migrations/20260203090000_create_scheduled_jobs.sql")
+    )]
+    async fn job_whose_lease_kept_expiring_is_not_run_again(pool: PgPool) {
+        let (scheduler, runs) = scheduler(&pool, 0);
+        let job =
+            ScheduledJobRepository::enqueue(&pool, "flaky", &serde_json::json!({}), Utc::now(), 1)
+                .await
+                .unwrap();
+        // The only allowed attempt was claimed by a worker that died
+        sqlx::query(
+            "UPDATE scheduled_jobs SET status = 'running', attempts = 1, locked_by = 'dead', \
+             locked_until = NOW() - INTERVAL '1 second' WHERE id = $1",
+        )
+        .bind(job.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let failed = claim_and_run(&scheduler).await;
+        assert_eq!(failed.status, "failed");
+        assert_eq!(runs.load(Ordering::SeqCst), 0);
+    }
+
+    #[ignore = "requires DATABASE_URL"]
+    #[sqlx::test(
+        migrations = false,
+        fixtures("../../migrations/20260203090000_create_scheduled_jobs.sql")
+    )]
+    async fn failed_recurring_job_waits_for_its_next_run(pool: PgPool) {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let schedule = Schedule::every_minutes(5);
+        let scheduler = JobScheduler::new(pool.clone(), WORKER.to_string()).recurring(
+            FlakyJob {
+                failures: 1,
+                runs: runs.clone(),
+            },
+            schedule,
+        );
+        ScheduledJobRepository::ensure_recurring(&pool, "flaky", "flaky", Utc::now())
+            .await
+            .unwrap();
+
+        let before = Utc::now();
+        let rescheduled = claim_and_run(&scheduler).await;
+        assert_eq!(rescheduled.status, "pending");
+        assert_eq!(rescheduled.attempts, 0);
+        assert_eq!(rescheduled.last_error.as_deref(), Some("run 1 failed"));
+        assert!(rescheduled.run_at >= schedule.next_after(before));
+    }
+}
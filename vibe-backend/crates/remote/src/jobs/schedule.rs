@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Days, NaiveTime, Utc};
+
+/// When a recurring job runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Schedule {
+    /// At a fixed interval after the previous run
+    Every(Duration),
+    /// Once a day at the given UTC time
+    DailyAt { hour: u32, minute: u32 },
+}
+
+impl Schedule {
+    pub fn every_minutes(minutes: u64) -> Self {
+        Self::Every(Duration::from_secs(minutes * 60))
+    }
+
+    pub fn every_hours(hours: u64) -> Self {
+        Self::Every(Duration::from_secs(hours * 60 * 60))
+    }
+
+    /// When a newly registered schedule first runs
+    pub fn first_run(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            Self::Every(_) => now,
+            Self::DailyAt { .. } => self.next_after(now),
+        }
+    }
+
+    /// The next run strictly after `after`
+    pub fn next_after(&self, after: DateTime<Utc>) -> DateTime<Utc> {
+        match *self {
+            Self::Every(interval) => {
+                let interval = chrono::Duration::from_std(interval)
+                    .unwrap_or_else(|_| chrono::Duration::days(365))
+                    .max(chrono::Duration::seconds(1));
+                after + interval
+            }
+            Self::DailyAt { hour, minute } => {
+                let time = NaiveTime::from_hms_opt(hour.min(23), minute.min(59), 0)
+                    .unwrap_or(NaiveTime::MIN);
+                let today = after.date_naive().and_time(time).and_utc();
+                if today > after {
+                    today
+                } else {
+                    today + Days::new(1)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn test_every_adds_interval() {
+        let now = Utc.with_ymd_and_hms(2026, 2, 3, 10, 0, 0).unwrap();
+        let schedule = Schedule::every_minutes(15);
+        assert_eq!(schedule.first_run(now), now);
+        assert_eq!(
+            schedule.next_after(now),
+            Utc.with_ymd_and_hms(2026, 2, 3, 10, 15, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_daily_runs_later_today_or_tomorrow() {
+        let schedule = Schedule::DailyAt {
+            hour: 8,
+            minute: 30,
+        };
+
+        let before = Utc.with_ymd_and_hms(2026, 2, 3, 7, 0, 0).unwrap();
+        assert_eq!(
+            schedule.next_after(before),
+            Utc.with_ymd_and_hms(2026, 2, 3, 8, 30, 0).unwrap()
+        );
+
+        let exactly = Utc.with_ymd_and_hms(2026, 2, 3, 8, 30, 0).unwrap();
+        assert_eq!(
+            schedule.next_after(exactly),
+            Utc.with_ymd_and_hms(2026, 2, 4, 8, 30, 0).unwrap()
+        );
+
+        let month_end = Utc.with_ymd_and_hms(2026, 2, 28, 9, 0, 0).unwrap();
+        assert_eq!(
+            schedule.first_run(month_end),
+            Utc.with_ymd_and_hms(2026, 3, 1, 8, 30, 0).unwrap()
+        );
+    }
+}
@@ -3,7 +3,9 @@ mod auth;
 pub mod cache;
 pub mod config;
 pub mod db;
+pub mod execution;
 pub mod github_app;
+pub mod jobs;
 pub mod mail;
 pub mod mcp;
 pub mod middleware;
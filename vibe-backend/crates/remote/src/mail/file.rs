@@ -0,0 +1,74 @@
+//! Drop-directory transport for development and tests.
+//!
+//! Each email is written as one JSON file instead of being sent, so local
+//! setups and tests can inspect exactly what would have gone out.
+
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{MailError, MailTransport, templates::RenderedEmail};
+
+/// An email as written to the drop directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredEmail {
+    pub from: String,
+    pub to: String,
+    pub email: RenderedEmail,
+}
+
+pub struct FileTransport {
+    dir: PathBuf,
+    from: String,
+}
+
+impl FileTransport {
+    pub fn new(dir: PathBuf, from: &str) -> Self {
+        Self {
+            dir,
+            from: from.to_string(),
+        }
+    }
+
+    /// Read every email in `dir`, oldest first
+    pub fn read_all(dir: &Path) -> Result<Vec<StoredEmail>, MailError> {
+        let mut paths = std::fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        paths
+            .into_iter()
+            .map(|path| Ok(serde_json::from_slice(&std::fs::read(path)?)?))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl MailTransport for FileTransport {
+    async fn deliver(&self, to: &str, email: &RenderedEmail) -> Result<(), MailError> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+
+        let stored = StoredEmail {
+            from: self.from.clone(),
+            to: to.to_string(),
+            email: email.clone(),
+        };
+        // Timestamp first so a directory listing is in send order
+        let file_name = format!(
+            "{}-{}-{}.json",
+            Utc::now().format("%Y%m%dT%H%M%S%.6f"),
+            email.template.name(),
+            Uuid::new_v4().simple()
+        );
+        let path = self.dir.join(file_name);
+        tokio::fs::write(&path, serde_json::to_vec_pretty(&stored)?).await?;
+
+        tracing::info!(path = %path.display(), "Wrote email to {to}");
+        Ok(())
+    }
+}
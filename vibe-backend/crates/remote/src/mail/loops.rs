@@ -3,6 +3,7 @@ use std::time::Duration;
 use async_trait::async_trait;
 use serde_json::json;
 
+use super::Mailer;
 use crate::db::organization_members::MemberRole;
 
 const LOOPS_INVITE_TEMPLATE_ID: &str = "cmhvy2wgs3s13z70i1pxakij9";
@@ -17,129 +18,6 @@ const LOOPS_REGISTRATION_REJECTED_TEMPLATE_ID: &str = "cm7xxxxxxxz";
 
 const LOOPS_API_URL: &str = "https://app.loops.so/api/v1/transactional";
 
-#[async_trait]
-pub trait Mailer: Send + Sync {
-    async fn send_org_invitation(
-        &self,
-        org_name: &str,
-        email: &str,
-        accept_url: &str,
-        role: MemberRole,
-        invited_by: Option<&str>,
-    );
-
-    async fn send_review_ready(&self, email: &str, review_url: &str, pr_name: &str);
-
-    async fn send_review_failed(&self, email: &str, pr_name: &str, review_id: &str);
-
-    /// Send email verification email (IKA-189)
-    async fn send_email_verification(&self, email: &str, verify_url: &str);
-
-    /// Notify superadmins that a new registration has been submitted (IKA-232)
-    async fn send_registration_submitted_to_admin(
-        &self,
-        admin_email: &str,
-        user_email: &str,
-        user_name: &str,
-        workspace_name: &str,
-        review_url: &str,
-    );
-
-    /// Notify user that their registration has been approved (IKA-232)
-    async fn send_registration_approved(
-        &self,
-        email: &str,
-        user_name: &str,
-        workspace_name: &str,
-        login_url: &str,
-    );
-
-    /// Notify user that their registration has been rejected (IKA-232)
-    async fn send_registration_rejected(&self, email: &str, user_name: &str, reason: Option<&str>);
-}
-
-/// No-op mailer for when LOOPS_EMAIL_API_KEY is not configured.
-/// Logs email attempts but doesn't actually send them.
-pub struct NoOpMailer;
-
-impl Default for NoOpMailer {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl NoOpMailer {
-    pub fn new() -> Self {
-        tracing::warn!("NoOpMailer initialized - emails will be logged but not sent");
-        Self
-    }
-}
-
-#[async_trait]
-impl Mailer for NoOpMailer {
-    async fn send_org_invitation(
-        &self,
-        org_name: &str,
-        email: &str,
-        accept_url: &str,
-        role: MemberRole,
-        _invited_by: Option<&str>,
-    ) {
-        tracing::info!(
-            "[NoOpMailer] Would send invitation to {email} for org {org_name} (role: {:?}, url: {accept_url})",
-            role
-        );
-    }
-
-    async fn send_review_ready(&self, email: &str, review_url: &str, pr_name: &str) {
-        tracing::info!(
-            "[NoOpMailer] Would send review ready to {email} for PR {pr_name} (url: {review_url})"
-        );
-    }
-
-    async fn send_review_failed(&self, email: &str, pr_name: &str, review_id: &str) {
-        tracing::info!(
-            "[NoOpMailer] Would send review failed to {email} for PR {pr_name} (review: {review_id})"
-        );
-    }
-
-    async fn send_email_verification(&self, email: &str, verify_url: &str) {
-        tracing::info!("[NoOpMailer] Would send verification to {email} (url: {verify_url})");
-    }
-
-    async fn send_registration_submitted_to_admin(
-        &self,
-        admin_email: &str,
-        user_email: &str,
-        user_name: &str,
-        workspace_name: &str,
-        review_url: &str,
-    ) {
-        tracing::info!(
-            "[NoOpMailer] Would notify admin {admin_email} of registration from {user_name} ({user_email}) for {workspace_name} (url: {review_url})"
-        );
-    }
-
-    async fn send_registration_approved(
-        &self,
-        email: &str,
-        user_name: &str,
-        workspace_name: &str,
-        login_url: &str,
-    ) {
-        tracing::info!(
-            "[NoOpMailer] Would send approval to {email} ({user_name}) for {workspace_name} (url: {login_url})"
-        );
-    }
-
-    async fn send_registration_rejected(&self, email: &str, user_name: &str, reason: Option<&str>) {
-        tracing::info!(
-            "[NoOpMailer] Would send rejection to {email} ({user_name}), reason: {:?}",
-            reason
-        );
-    }
-}
-
 pub struct LoopsMailer {
     client: reqwest::Client,
     api_key: String,
@@ -0,0 +1,340 @@
+mod file;
+mod loops;
+mod smtp;
+pub mod templates;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+pub use file::{FileTransport, StoredEmail};
+pub use loops::LoopsMailer;
+use secrecy::ExposeSecret;
+use serde_json::{Value, json};
+pub use smtp::SmtpTransport;
+use thiserror::Error;
+
+use self::templates::{EmailTemplate, EmailTemplates, RenderedEmail};
+use crate::{
+    config::{MailConfig, MailTransportConfig},
+    db::organization_members::MemberRole,
+};
+
+#[derive(Debug, Error)]
+pub enum MailError {
+    #[error("invalid email template: {0}")]
+    Template(#[from] handlebars::TemplateError),
+    #[error("failed to render email: {0}")]
+    Render(#[from] handlebars::RenderError),
+    #[error("invalid email address `{0}`")]
+    Address(String),
+    #[error("failed to build email: {0}")]
+    Message(#[from] lettre::error::Error),
+    #[error("SMTP error: {0}")]
+    Smtp(#[from] lettre::transport::smtp::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send_org_invitation(
+        &self,
+        org_name: &str,
+        email: &str,
+        accept_url: &str,
+        role: MemberRole,
+        invited_by: Option<&str>,
+    );
+
+    async fn send_review_ready(&self, email: &str, review_url: &str, pr_name: &str);
+
+    async fn send_review_failed(&self, email: &str, pr_name: &str, review_id: &str);
+
+    /// Send email verification email (IKA-189)
+    async fn send_email_verification(&self, email: &str, verify_url: &str);
+
+    /// Notify superadmins that a new registration has been submitted (IKA-232)
+    async fn send_registration_submitted_to_admin(
+        &self,
+        admin_email: &str,
+        user_email: &str,
+        user_name: &str,
+        workspace_name: &str,
+        review_url: &str,
+    );
+
+    /// Notify user that their registration has been approved (IKA-232)
+    async fn send_registration_approved(
+        &self,
+        email: &str,
+        user_name: &str,
+        workspace_name: &str,
+        login_url: &str,
+    );
+
+    /// Notify user that their registration has been rejected (IKA-232)
+    async fn send_registration_rejected(&self, email: &str, user_name: &str, reason: Option<&str>);
+}
+
+/// No-op mailer for when no mail transport is configured.
+/// Logs email attempts but doesn't actually send them.
+pub struct NoOpMailer;
+
+impl Default for NoOpMailer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NoOpMailer {
+    pub fn new() -> Self {
+        tracing::warn!("NoOpMailer initialized - emails will be logged but not sent");
+        Self
+    }
+}
+
+#[async_trait]
+impl Mailer for NoOpMailer {
+    async fn send_org_invitation(
+        &self,
+        org_name: &str,
+        email: &str,
+        accept_url: &str,
+        role: MemberRole,
+        _invited_by: Option<&str>,
+    ) {
+        tracing::info!(
+            "[NoOpMailer] Would send invitation to {email} for org {org_name} (role: {:?}, url: {accept_url})",
+            role
+        );
+    }
+
+    async fn send_review_ready(&self, email: &str, review_url: &str, pr_name: &str) {
+        tracing::info!(
+            "[NoOpMailer] Would send review ready to {email} for PR {pr_name} (url: {review_url})"
+        );
+    }
+
+    async fn send_review_failed(&self, email: &str, pr_name: &str, review_id: &str) {
+        tracing::info!(
+            "[NoOpMailer] Would send review failed to {email} for PR {pr_name} (review: {review_id})"
+        );
+    }
+
+    async fn send_email_verification(&self, email: &str, verify_url: &str) {
+        tracing::info!("[NoOpMailer] Would send verification to {email} (url: {verify_url})");
+    }
+
+    async fn send_registration_submitted_to_admin(
+        &self,
+        admin_email: &str,
+        user_email: &str,
+        user_name: &str,
+        workspace_name: &str,
+        review_url: &str,
+    ) {
+        tracing::info!(
+            "[NoOpMailer] Would notify admin {admin_email} of registration from {user_name} ({user_email}) for {workspace_name} (url: {review_url})"
+        );
+    }
+
+    async fn send_registration_approved(
+        &self,
+        email: &str,
+        user_name: &str,
+        workspace_name: &str,
+        login_url: &str,
+    ) {
+        tracing::info!(
+            "[NoOpMailer] Would send approval to {email} ({user_name}) for {workspace_name} (url: {login_url})"
+        );
+    }
+
+    async fn send_registration_rejected(&self, email: &str, user_name: &str, reason: Option<&str>) {
+        tracing::info!(
+            "[NoOpMailer] Would send rejection to {email} ({user_name}), reason: {:?}",
+            reason
+        );
+    }
+}
+
+/// Delivers rendered emails; used by `TemplatedMailer`
+#[async_trait]
+pub trait MailTransport: Send + Sync {
+    async fn deliver(&self, to: &str, email: &RenderedEmail) -> Result<(), MailError>;
+}
+
+/// Mailer that renders the local templates and hands the result to a
+/// transport (SMTP or a drop directory)
+pub struct TemplatedMailer {
+    templates: Arc<EmailTemplates>,
+    transport: Box<dyn MailTransport>,
+}
+
+impl TemplatedMailer {
+    pub fn new(templates: Arc<EmailTemplates>, transport: Box<dyn MailTransport>) -> Self {
+        Self {
+            templates,
+            transport,
+        }
+    }
+
+    async fn send(&self, to: &str, template: EmailTemplate, data: Value) {
+        let email = match self.templates.render(template, &data) {
+            Ok(email) => email,
+            Err(error) => {
+                tracing::error!(?error, template = template.name(), "Failed to render email");
+                return;
+            }
+        };
+
+        match self.transport.deliver(to, &email).await {
+            Ok(()) => tracing::debug!(template = template.name(), "Sent email to {to}"),
+            Err(error) => {
+                tracing::warn!(
+                    ?error,
+                    template = template.name(),
+                    "Failed to send email to {to}"
+                )
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Mailer for TemplatedMailer {
+    async fn send_org_invitation(
+        &self,
+        org_name: &str,
+        email: &str,
+        accept_url: &str,
+        role: MemberRole,
+        invited_by: Option<&str>,
+    ) {
+        let role = match role {
+            MemberRole::Admin => "admin",
+            MemberRole::Member => "member",
+        };
+        let data = json!({
+            "org_name": org_name,
+            "accept_url": accept_url,
+            "role": role,
+            "invited_by": invited_by.unwrap_or("someone"),
+        });
+        self.send(email, EmailTemplate::OrgInvitation, data).await;
+    }
+
+    async fn send_review_ready(&self, email: &str, review_url: &str, pr_name: &str) {
+        let data = json!({ "review_url": review_url, "pr_name": pr_name });
+        self.send(email, EmailTemplate::ReviewReady, data).await;
+    }
+
+    async fn send_review_failed(&self, email: &str, pr_name: &str, review_id: &str) {
+        let data = json!({ "pr_name": pr_name, "review_id": review_id });
+        self.send(email, EmailTemplate::ReviewFailed, data).await;
+    }
+
+    async fn send_email_verification(&self, email: &str, verify_url: &str) {
+        let data = json!({ "verify_url": verify_url });
+        self.send(email, EmailTemplate::EmailVerification, data)
+            .await;
+    }
+
+    async fn send_registration_submitted_to_admin(
+        &self,
+        admin_email: &str,
+        user_email: &str,
+        user_name: &str,
+        workspace_name: &str,
+        review_url: &str,
+    ) {
+        let data = json!({
+            "user_email": user_email,
+            "user_name": user_name,
+            "workspace_name": workspace_name,
+            "review_url": review_url,
+        });
+        self.send(admin_email, EmailTemplate::RegistrationSubmitted, data)
+            .await;
+    }
+
+    async fn send_registration_approved(
+        &self,
+        email: &str,
+        user_name: &str,
+        workspace_name: &str,
+        login_url: &str,
+    ) {
+        let data = json!({
+            "user_name": user_name,
+            "workspace_name": workspace_name,
+            "login_url": login_url,
+        });
+        self.send(email, EmailTemplate::RegistrationApproved, data)
+            .await;
+    }
+
+    async fn send_registration_rejected(&self, email: &str, user_name: &str, reason: Option<&str>) {
+        let data = json!({
+            "user_name": user_name,
+            "reason": reason.unwrap_or("No specific reason provided"),
+        });
+        self.send(email, EmailTemplate::RegistrationRejected, data)
+            .await;
+    }
+}
+
+/// Build the mailer selected by `MailConfig`
+pub fn build_mailer(
+    config: &MailConfig,
+    templates: Arc<EmailTemplates>,
+) -> Result<Arc<dyn Mailer>, MailError> {
+    let mailer: Arc<dyn Mailer> = match &config.transport {
+        MailTransportConfig::Loops { api_key } => {
+            Arc::new(LoopsMailer::new(api_key.expose_secret().to_string()))
+        }
+        MailTransportConfig::Smtp(smtp) => {
+            tracing::info!(host = %smtp.host, port = smtp.port, "Sending email over SMTP");
+            let transport = SmtpTransport::new(smtp, &config.from)?;
+            Arc::new(TemplatedMailer::new(templates, Box::new(transport)))
+        }
+        MailTransportConfig::File { dir } => {
+            tracing::info!(dir = %dir.display(), "Writing emails to drop directory");
+            let transport = FileTransport::new(dir.clone(), &config.from);
+            Arc::new(TemplatedMailer::new(templates, Box::new(transport)))
+        }
+        MailTransportConfig::Disabled => Arc::new(NoOpMailer::new()),
+    };
+    Ok(mailer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn templated_mailer_delivers_rendered_email() {
+        let dir = tempfile::tempdir().unwrap();
+        let templates = Arc::new(EmailTemplates::load(None).unwrap());
+        let transport =
+            FileTransport::new(dir.path().to_path_buf(), "iKanban <noreply@example.com>");
+        let mailer = TemplatedMailer::new(templates, Box::new(transport));
+
+        mailer
+            .send_email_verification("sam@example.com", "https://example.com/verify?token=t")
+            .await;
+
+        let sent = FileTransport::read_all(dir.path()).unwrap();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].to, "sam@example.com");
+        assert_eq!(sent[0].email.template, EmailTemplate::EmailVerification);
+        assert!(
+            sent[0]
+                .email
+                .text
+                .contains("https://example.com/verify?token=t")
+        );
+    }
+}
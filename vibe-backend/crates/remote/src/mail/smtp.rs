@@ -0,0 +1,72 @@
+//! SMTP transport for self-hosted deployments.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+    message::{Mailbox, MultiPart},
+    transport::smtp::authentication::Credentials,
+};
+use secrecy::ExposeSecret;
+
+use super::{MailError, MailTransport, templates::RenderedEmail};
+use crate::config::{SmtpConfig, SmtpSecurity};
+
+const SMTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct SmtpTransport {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpTransport {
+    pub fn new(config: &SmtpConfig, from: &str) -> Result<Self, MailError> {
+        let from = parse_mailbox(from)?;
+
+        let builder = match config.security {
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host)?,
+            SmtpSecurity::StartTls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)?
+            }
+            SmtpSecurity::None => {
+                AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host)
+            }
+        };
+        let mut builder = builder.port(config.port).timeout(Some(SMTP_TIMEOUT));
+        if let (Some(username), Some(password)) = (&config.username, &config.password) {
+            builder = builder.credentials(Credentials::new(
+                username.clone(),
+                password.expose_secret().to_string(),
+            ));
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            from,
+        })
+    }
+}
+
+fn parse_mailbox(address: &str) -> Result<Mailbox, MailError> {
+    address
+        .parse()
+        .map_err(|_| MailError::Address(address.to_string()))
+}
+
+#[async_trait]
+impl MailTransport for SmtpTransport {
+    async fn deliver(&self, to: &str, email: &RenderedEmail) -> Result<(), MailError> {
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(parse_mailbox(to)?)
+            .subject(&email.subject)
+            .multipart(MultiPart::alternative_plain_html(
+                email.text.clone(),
+                email.html.clone(),
+            ))?;
+
+        self.transport.send(message).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,332 @@
+//! Locally rendered email templates.
+//!
+//! Templates live in `crates/remote/templates/email` and are compiled into the
+//! binary. Each template has a `.subject.hbs`, `.html.hbs` and `.txt.hbs`
+//! part; HTML parts wrap their body in the shared `layout` partial. Setting
+//! `MAIL_TEMPLATES_DIR` lets a deployment override any part with a file of
+//! the same name.
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use handlebars::Handlebars;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use super::MailError;
+
+/// Bumped whenever the built-in templates change their variables
+pub const TEMPLATES_VERSION: u32 = 1;
+
+const APP_NAME: &str = "iKanban";
+
+const LAYOUT: &str = include_str!("../../templates/email/layout.html.hbs");
+
+macro_rules! template_parts {
+    ($name:literal) => {
+        [
+            include_str!(concat!("../../templates/email/", $name, ".subject.hbs")),
+            include_str!(concat!("../../templates/email/", $name, ".html.hbs")),
+            include_str!(concat!("../../templates/email/", $name, ".txt.hbs")),
+        ]
+    };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmailTemplate {
+    OrgInvitation,
+    ReviewReady,
+    ReviewFailed,
+    EmailVerification,
+    RegistrationSubmitted,
+    RegistrationApproved,
+    RegistrationRejected,
+}
+
+impl EmailTemplate {
+    pub const ALL: [EmailTemplate; 7] = [
+        EmailTemplate::OrgInvitation,
+        EmailTemplate::ReviewReady,
+        EmailTemplate::ReviewFailed,
+        EmailTemplate::EmailVerification,
+        EmailTemplate::RegistrationSubmitted,
+        EmailTemplate::RegistrationApproved,
+        EmailTemplate::RegistrationRejected,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EmailTemplate::OrgInvitation => "org_invitation",
+            EmailTemplate::ReviewReady => "review_ready",
+            EmailTemplate::ReviewFailed => "review_failed",
+            EmailTemplate::EmailVerification => "email_verification",
+            EmailTemplate::RegistrationSubmitted => "registration_submitted",
+            EmailTemplate::RegistrationApproved => "registration_approved",
+            EmailTemplate::RegistrationRejected => "registration_rejected",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|template| template.name() == name)
+    }
+
+    /// Built-in subject, HTML and text sources
+    fn builtin_parts(self) -> [&'static str; 3] {
+        match self {
+            EmailTemplate::OrgInvitation => template_parts!("org_invitation"),
+            EmailTemplate::ReviewReady => template_parts!("review_ready"),
+            EmailTemplate::ReviewFailed => template_parts!("review_failed"),
+            EmailTemplate::EmailVerification => template_parts!("email_verification"),
+            EmailTemplate::RegistrationSubmitted => template_parts!("registration_submitted"),
+            EmailTemplate::RegistrationApproved => template_parts!("registration_approved"),
+            EmailTemplate::RegistrationRejected => template_parts!("registration_rejected"),
+        }
+    }
+
+    /// Example variables, used for previews
+    pub fn sample_data(self) -> Value {
+        match self {
+            EmailTemplate::OrgInvitation => json!({
+                "org_name": "Acme",
+                "accept_url": "https://app.example.com/invitations/abc123/accept",
+                "role": "member",
+                "invited_by": "jordan",
+            }),
+            EmailTemplate::ReviewReady => json!({
+                "review_url": "https://app.example.com/reviews/42",
+                "pr_name": "acme/web#128",
+            }),
+            EmailTemplate::ReviewFailed => json!({
+                "pr_name": "acme/web#128",
+                "review_id": "3f1c2a9e-7d4b-4c1e-9b1a-5e2f0c8d6a71",
+            }),
+            EmailTemplate::EmailVerification => json!({
+                "verify_url": "https://app.example.com/verify-email?token=abc123",
+            }),
+            EmailTemplate::RegistrationSubmitted => json!({
+                "user_email": "sam@example.com",
+                "user_name": "Sam Lee",
+                "workspace_name": "Acme",
+                "review_url": "https://app.example.com/superadmin/registrations",
+            }),
+            EmailTemplate::RegistrationApproved => json!({
+                "user_name": "Sam Lee",
+                "workspace_name": "Acme",
+                "login_url": "https://app.example.com/sign-in",
+            }),
+            EmailTemplate::RegistrationRejected => json!({
+                "user_name": "Sam Lee",
+                "reason": "We could not verify the workspace owner",
+            }),
+        }
+    }
+}
+
+/// A rendered email, ready for a transport
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenderedEmail {
+    pub template: EmailTemplate,
+    pub version: u32,
+    pub subject: String,
+    pub html: String,
+    pub text: String,
+}
+
+/// Template info for the admin listing
+#[derive(Debug, Clone, Serialize)]
+pub struct EmailTemplateInfo {
+    pub name: &'static str,
+    pub version: u32,
+    /// True when `MAIL_TEMPLATES_DIR` replaces at least one part
+    pub overridden: bool,
+}
+
+pub struct EmailTemplates {
+    /// HTML parts, with HTML escaping
+    html: Handlebars<'static>,
+    /// Subject and text parts, without escaping
+    plain: Handlebars<'static>,
+    overridden: HashSet<EmailTemplate>,
+}
+
+impl EmailTemplates {
+    /// Load the built-in templates, replaced part by part with files from
+    /// `dir` when given
+    pub fn load(dir: Option<&Path>) -> Result<Self, MailError> {
+        let mut html = Handlebars::new();
+        html.set_strict_mode(true);
+        let mut plain = Handlebars::new();
+        plain.set_strict_mode(true);
+        plain.register_escape_fn(handlebars::no_escape);
+
+        let mut overridden = HashSet::new();
+        let read_override = |file: &str| -> Result<Option<String>, MailError> {
+            let Some(dir) = dir else {
+                return Ok(None);
+            };
+            let path = dir.join(file);
+            if !path.exists() {
+                return Ok(None);
+            }
+            tracing::info!(path = %path.display(), "Using email template override");
+            Ok(Some(std::fs::read_to_string(path)?))
+        };
+
+        let layout = read_override("layout.html.hbs")?;
+        html.register_partial("layout", layout.as_deref().unwrap_or(LAYOUT))?;
+
+        for template in EmailTemplate::ALL {
+            let name = template.name();
+            let [subject, body, text] = template.builtin_parts();
+            let subject_override = read_override(&format!("{name}.subject.hbs"))?;
+            let html_override = read_override(&format!("{name}.html.hbs"))?;
+            let text_override = read_override(&format!("{name}.txt.hbs"))?;
+
+            if subject_override.is_some() || html_override.is_some() || text_override.is_some() {
+                overridden.insert(template);
+            }
+
+            plain.register_template_string(
+                &format!("{name}.subject"),
+                subject_override.as_deref().unwrap_or(subject).trim(),
+            )?;
+            html.register_template_string(name, html_override.as_deref().unwrap_or(body))?;
+            plain.register_template_string(
+                &format!("{name}.txt"),
+                text_override.as_deref().unwrap_or(text),
+            )?;
+        }
+
+        Ok(Self {
+            html,
+            plain,
+            overridden,
+        })
+    }
+
+    /// Load templates using `MAIL_TEMPLATES_DIR`, falling back to the
+    /// built-in set if the overrides do not compile
+    pub fn from_env() -> Self {
+        let dir = std::env::var("MAIL_TEMPLATES_DIR").ok().map(PathBuf::from);
+        match Self::load(dir.as_deref()) {
+            Ok(templates) => templates,
+            Err(error) => {
+                tracing::error!(?error, "Invalid email template overrides, using built-ins");
+                Self::load(None).expect("built-in email templates compile")
+            }
+        }
+    }
+
+    pub fn list(&self) -> Vec<EmailTemplateInfo> {
+        EmailTemplate::ALL
+            .into_iter()
+            .map(|template| EmailTemplateInfo {
+                name: template.name(),
+                version: TEMPLATES_VERSION,
+                overridden: self.overridden.contains(&template),
+            })
+            .collect()
+    }
+
+    pub fn render(
+        &self,
+        template: EmailTemplate,
+        data: &Value,
+    ) -> Result<RenderedEmail, MailError> {
+        let name = template.name();
+        let mut context = match data {
+            Value::Object(map) => map.clone(),
+            _ => serde_json::Map::new(),
+        };
+        context.insert("app_name".to_string(), json!(APP_NAME));
+        context.insert("template_version".to_string(), json!(TEMPLATES_VERSION));
+
+        let subject = self.plain.render(&format!("{name}.subject"), &context)?;
+        context.insert("subject".to_string(), json!(subject));
+        let html = self.html.render(name, &context)?;
+        let text = self.plain.render(&format!("{name}.txt"), &context)?;
+
+        Ok(RenderedEmail {
+            template,
+            version: TEMPLATES_VERSION,
+            subject,
+            html,
+            text,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_every_builtin_template_with_sample_data() {
+        let templates = EmailTemplates::load(None).unwrap();
+        for template in EmailTemplate::ALL {
+            let email = templates
+                .render(template, &template.sample_data())
+                .unwrap_or_else(|e| panic!("{}: {e}", template.name()));
+            assert!(!email.subject.is_empty());
+            assert!(!email.subject.contains('\n'));
+            assert!(email.html.contains("<html>"));
+            assert!(!email.text.is_empty());
+        }
+    }
+
+    #[test]
+    fn escapes_html_but_not_text() {
+        let templates = EmailTemplates::load(None).unwrap();
+        let mut data = EmailTemplate::ReviewReady.sample_data();
+        data["pr_name"] = json!("<b>fix</b>");
+        let email = templates.render(EmailTemplate::ReviewReady, &data).unwrap();
+        assert!(email.html.contains("&lt;b&gt;fix&lt;/b&gt;"));
+        assert!(email.text.contains("<b>fix</b>"));
+        assert_eq!(email.subject, "Your review of <b>fix</b> is ready");
+    }
+
+    #[test]
+    fn missing_variables_fail_to_render() {
+        let templates = EmailTemplates::load(None).unwrap();
+        assert!(
+            templates
+                .render(EmailTemplate::EmailVerification, &json!({}))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn overrides_replace_single_parts() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("review_ready.subject.hbs"),
+            "Review done: {{pr_name}}",
+        )
+        .unwrap();
+
+        let templates = EmailTemplates::load(Some(dir.path())).unwrap();
+        let email = templates
+            .render(
+                EmailTemplate::ReviewReady,
+                &EmailTemplate::ReviewReady.sample_data(),
+            )
+            .unwrap();
+        assert_eq!(email.subject, "Review done: acme/web#128");
+        assert!(email.text.contains("has finished"));
+
+        let info = templates.list();
+        assert!(
+            info.iter()
+                .any(|t| t.name == "review_ready" && t.overridden)
+        );
+        assert!(
+            info.iter()
+                .any(|t| t.name == "review_failed" && !t.overridden)
+        );
+    }
+}
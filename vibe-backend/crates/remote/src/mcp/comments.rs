@@ -26,7 +26,7 @@ pub struct ApiComment {
 
 impl TaskServer {
     /// Resolve task_id parameter - can be UUID or issue key (IKA-123)
-    async fn resolve_task_id(&self, task_id: &str) -> Result<Uuid, CallToolResult> {
+    pub(super) async fn resolve_task_id(&self, task_id: &str) -> Result<Uuid, CallToolResult> {
         // Try as UUID first
         if let Ok(uuid) = Uuid::parse_str(task_id) {
             return Ok(uuid);
@@ -0,0 +1,123 @@
+//! Dependencies MCP tools - record and inspect "blocked by" relations so
+//! agents can plan work order
+
+use rmcp::{
+    ErrorData, handler::server::tool::Parameters, model::CallToolResult, tool, tool_router,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{task_server::TaskServer, types::*};
+
+/// Dependency edge from API response
+#[derive(Debug, Deserialize)]
+pub struct ApiDependency {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub depends_on_task_id: Uuid,
+}
+
+/// Related task from API response
+#[derive(Debug, Deserialize)]
+pub struct ApiDependencyTask {
+    pub task_id: Uuid,
+    pub title: String,
+    pub status: String,
+    pub issue_key: Option<String>,
+}
+
+/// Both directions of a task's dependencies from API response
+#[derive(Debug, Deserialize)]
+pub struct ApiDependencies {
+    pub blockers: Vec<ApiDependencyTask>,
+    pub blocked_tasks: Vec<ApiDependencyTask>,
+}
+
+impl From<ApiDependencyTask> for DependencySummary {
+    fn from(task: ApiDependencyTask) -> Self {
+        Self {
+            task_id: task.task_id.to_string(),
+            issue_key: task.issue_key,
+            title: task.title,
+            status: task.status,
+        }
+    }
+}
+
+#[tool_router(router = dependency_tool_router, vis = "pub(super)")]
+impl TaskServer {
+    /// Mark a task as blocked by another task
+    #[tool(
+        description = "Mark a task as blocked by another task (e.g., IKA-40 depends on IKA-38). Both tasks accept a UUID or issue key. Dependencies that would form a cycle are rejected."
+    )]
+    pub async fn add_dependency(
+        &self,
+        Parameters(AddDependencyRequest {
+            task_id,
+            depends_on,
+        }): Parameters<AddDependencyRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let resolved_task_id = match self.resolve_task_id(&task_id).await {
+            Ok(id) => id,
+            Err(e) => return Ok(e),
+        };
+        let resolved_depends_on = match self.resolve_task_id(&depends_on).await {
+            Ok(id) => id,
+            Err(e) => return Ok(e),
+        };
+
+        #[derive(Serialize)]
+        struct AddDependencyPayload {
+            depends_on_task_id: Uuid,
+        }
+
+        let url = self.url(&format!("/api/tasks/{}/dependencies", resolved_task_id));
+        let dependency: ApiDependency = match self
+            .send_json(self.client().post(&url).json(&AddDependencyPayload {
+                depends_on_task_id: resolved_depends_on,
+            }))
+            .await
+        {
+            Ok(d) => d,
+            Err(e) => return Ok(e),
+        };
+
+        TaskServer::success(&AddDependencyResponse {
+            dependency_id: dependency.id.to_string(),
+            task_id: dependency.task_id.to_string(),
+            depends_on_task_id: dependency.depends_on_task_id.to_string(),
+        })
+    }
+
+    /// List a task's blockers and the tasks it blocks
+    #[tool(
+        description = "List the tasks blocking a task and the tasks it blocks. `ready` is true when every blocker is done. Task can be specified by UUID or issue key (e.g., 'IKA-123')."
+    )]
+    pub async fn list_dependencies(
+        &self,
+        Parameters(ListDependenciesRequest { task_id }): Parameters<ListDependenciesRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let resolved_task_id = match self.resolve_task_id(&task_id).await {
+            Ok(id) => id,
+            Err(e) => return Ok(e),
+        };
+
+        let url = self.url(&format!("/api/tasks/{}/dependencies", resolved_task_id));
+        let dependencies: ApiDependencies = match self.send_json(self.client().get(&url)).await {
+            Ok(d) => d,
+            Err(e) => return Ok(e),
+        };
+
+        let ready = dependencies.blockers.iter().all(|b| b.status == "done");
+        TaskServer::success(&ListDependenciesResponse {
+            task_id: resolved_task_id.to_string(),
+            blockers: dependencies.blockers.into_iter().map(Into::into).collect(),
+            blocked_tasks: dependencies
+                .blocked_tasks
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            ready,
+        })
+    }
+}
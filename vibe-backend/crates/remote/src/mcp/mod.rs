@@ -8,11 +8,18 @@
 //! - `documents.rs` - Document CRUD operations
 //! - `folders.rs` - Folder CRUD operations
 //! - `comments.rs` - Task comment operations
+//! - `dependencies.rs` - Task dependency ("blocked by") operations
+//! - `revisions.rs` - Document revision history
+//! - `scopes.rs` - API key scope required by each tool
 //! - `types.rs` - Shared request/response types
 
 pub mod comments;
+pub mod dependencies;
 pub mod documents;
 pub mod folders;
+pub mod revisions;
+pub mod scopes;
+pub mod search;
 pub mod task_server;
 pub mod teams;
 pub mod types;
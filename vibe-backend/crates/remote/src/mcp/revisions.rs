@@ -0,0 +1,126 @@
+//! Document revision MCP tools - read a document's history so agents can see
+//! what changed and when
+
+use db_crate::models::document_revision::{
+    DocumentRevision, DocumentRevisionDiff, DocumentRevisionSummary as ApiRevisionSummary,
+};
+use rmcp::{
+    ErrorData, handler::server::tool::Parameters, model::CallToolResult, tool, tool_router,
+};
+
+use super::{task_server::TaskServer, types::*};
+
+impl From<ApiRevisionSummary> for DocumentRevisionSummary {
+    fn from(revision: ApiRevisionSummary) -> Self {
+        Self {
+            revision_number: revision.revision_number,
+            title: revision.title,
+            content_size: revision.content_size,
+            author_user_id: revision.author_user_id,
+            author_execution_process_id: revision
+                .author_execution_process_id
+                .map(|id| id.to_string()),
+            author_source: revision.author_source.map(|source| source.to_string()),
+            restored_from: revision.restored_from,
+            created_at: revision.created_at.to_rfc3339(),
+        }
+    }
+}
+
+impl From<DocumentRevision> for DocumentRevisionDetails {
+    fn from(revision: DocumentRevision) -> Self {
+        Self {
+            summary: DocumentRevisionSummary {
+                revision_number: revision.revision_number,
+                title: revision.title,
+                content_size: revision.content_size,
+                author_user_id: revision.author_user_id,
+                author_execution_process_id: revision
+                    .author_execution_process_id
+                    .map(|id| id.to_string()),
+                author_source: revision.author_source.map(|source| source.to_string()),
+                restored_from: revision.restored_from,
+                created_at: revision.created_at.to_rfc3339(),
+            },
+            content: revision.content,
+        }
+    }
+}
+
+#[tool_router(router = revision_tool_router, vis = "pub(super)")]
+impl TaskServer {
+    /// List a document's revisions
+    #[tool(
+        description = "List a document's revision history, newest first. Each revision records who made the change (a user or a coding agent execution), when, and the content size."
+    )]
+    pub async fn list_document_revisions(
+        &self,
+        Parameters(ListDocumentRevisionsRequest { document_id }): Parameters<
+            ListDocumentRevisionsRequest,
+        >,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!("/api/documents/{}/revisions", document_id));
+        let revisions: Vec<ApiRevisionSummary> = match self.send_json(self.client().get(&url)).await
+        {
+            Ok(r) => r,
+            Err(e) => return Ok(e),
+        };
+
+        TaskServer::success(&ListDocumentRevisionsResponse {
+            document_id: document_id.to_string(),
+            count: revisions.len(),
+            revisions: revisions.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    /// Get a document's content at a revision
+    #[tool(description = "Get a document's content as of a revision number")]
+    pub async fn get_document_revision(
+        &self,
+        Parameters(GetDocumentRevisionRequest {
+            document_id,
+            revision_number,
+        }): Parameters<GetDocumentRevisionRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!(
+            "/api/documents/{}/revisions/{}",
+            document_id, revision_number
+        ));
+        let revision: DocumentRevision = match self.send_json(self.client().get(&url)).await {
+            Ok(r) => r,
+            Err(e) => return Ok(e),
+        };
+
+        TaskServer::success(&DocumentRevisionDetails::from(revision))
+    }
+
+    /// Diff two revisions of a document
+    #[tool(
+        description = "Show a unified diff between two revisions of a document, with added and removed line counts"
+    )]
+    pub async fn diff_document_revisions(
+        &self,
+        Parameters(DiffDocumentRevisionsRequest {
+            document_id,
+            from,
+            to,
+        }): Parameters<DiffDocumentRevisionsRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!(
+            "/api/documents/{}/revisions/diff?from={}&to={}",
+            document_id, from, to
+        ));
+        let diff: DocumentRevisionDiff = match self.send_json(self.client().get(&url)).await {
+            Ok(d) => d,
+            Err(e) => return Ok(e),
+        };
+
+        TaskServer::success(&DiffDocumentRevisionsResponse {
+            from_revision: diff.from_revision,
+            to_revision: diff.to_revision,
+            additions: diff.additions,
+            deletions: diff.deletions,
+            unified_diff: diff.unified_diff,
+        })
+    }
+}
@@ -0,0 +1,52 @@
+//! API key scopes required by each MCP tool
+
+use db_crate::models::api_key::ApiScope;
+
+/// Scope an API key needs to call `tool`. Unmapped tools need `admin`, so a
+/// new tool stays closed to scoped keys until it is listed here.
+pub fn required_scope(tool: &str) -> ApiScope {
+    match tool {
+        "get_context" | "list_tasks" | "get_task" | "list_issues" | "get_issue_by_key"
+        | "list_comments" | "list_dependencies" | "search" => ApiScope::TasksRead,
+        "create_task"
+        | "update_task"
+        | "delete_task"
+        | "update_issue_by_key"
+        | "add_comment"
+        | "add_dependency" => ApiScope::TasksWrite,
+        "list_documents"
+        | "get_document"
+        | "list_document_revisions"
+        | "get_document_revision"
+        | "diff_document_revisions"
+        | "list_folders"
+        | "get_folder" => ApiScope::DocumentsRead,
+        "create_document" | "update_document" | "delete_document" | "create_folder"
+        | "update_folder" | "delete_folder" => ApiScope::DocumentsWrite,
+        "list_projects" | "list_repos" => ApiScope::ProjectsRead,
+        "list_teams" => ApiScope::TeamsRead,
+        "start_workspace_session" => ApiScope::ExecutionsRun,
+        _ => ApiScope::Admin,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_tools_to_scopes() {
+        assert_eq!(required_scope("list_tasks"), ApiScope::TasksRead);
+        assert_eq!(required_scope("delete_task"), ApiScope::TasksWrite);
+        assert_eq!(required_scope("update_document"), ApiScope::DocumentsWrite);
+        assert_eq!(
+            required_scope("diff_document_revisions"),
+            ApiScope::DocumentsRead
+        );
+        assert_eq!(
+            required_scope("start_workspace_session"),
+            ApiScope::ExecutionsRun
+        );
+        assert_eq!(required_scope("drop_database"), ApiScope::Admin);
+    }
+}
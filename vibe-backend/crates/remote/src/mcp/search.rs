@@ -0,0 +1,98 @@
+//! Search MCP tool - ranked full-text search so agents can find related
+//! issues, discussions and docs without listing everything
+
+use rmcp::{
+    ErrorData, handler::server::tool::Parameters, model::CallToolResult, tool, tool_router,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{task_server::TaskServer, teams::resolve_team, types::*};
+
+/// Search hit from API response
+#[derive(Debug, Deserialize)]
+pub struct ApiSearchResult {
+    pub entity_type: String,
+    pub id: Uuid,
+    pub title: String,
+    pub snippet: String,
+    pub task_id: Option<Uuid>,
+    pub issue_key: Option<String>,
+    pub status: Option<String>,
+}
+
+impl From<ApiSearchResult> for SearchHit {
+    fn from(result: ApiSearchResult) -> Self {
+        Self {
+            entity_type: result.entity_type,
+            id: result.id.to_string(),
+            title: result.title,
+            snippet: result.snippet,
+            task_id: result.task_id.map(|id| id.to_string()),
+            issue_key: result.issue_key,
+            status: result.status,
+        }
+    }
+}
+
+#[tool_router(router = search_tool_router, vis = "pub(super)")]
+impl TaskServer {
+    /// Full-text search across issues, comments, documents and project updates
+    #[tool(
+        description = "Search issues, issue comments, documents and project updates by text, best matches first. Optionally filter by team (e.g., 'IKA'), project, task status or result type."
+    )]
+    pub async fn search(
+        &self,
+        Parameters(SearchRequest {
+            query,
+            team,
+            project_id,
+            status,
+            types,
+            limit,
+        }): Parameters<SearchRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let team_id = match team {
+            Some(team) => match resolve_team(self, &team).await {
+                Ok(t) => Some(t.id),
+                Err(e) => return Ok(e),
+            },
+            None => None,
+        };
+
+        #[derive(Serialize)]
+        struct SearchParams {
+            q: String,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            team_id: Option<Uuid>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            project_id: Option<Uuid>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            status: Option<String>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            types: Option<String>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            limit: Option<i64>,
+        }
+
+        let url = self.url("/api/search");
+        let params = SearchParams {
+            q: query,
+            team_id,
+            project_id,
+            status,
+            types: types.map(|types| types.join(",")),
+            limit,
+        };
+        let results: Vec<ApiSearchResult> =
+            match self.send_json(self.client().get(&url).query(&params)).await {
+                Ok(r) => r,
+                Err(e) => return Ok(e),
+            };
+
+        TaskServer::success(&SearchResponse {
+            count: results.len(),
+            results: results.into_iter().map(Into::into).collect(),
+        })
+    }
+}
@@ -1,11 +1,13 @@
 //! Core MCP task server implementation
 //!
 //! This module provides the TaskServer struct and core task management tools.
-//! Additional tools are implemented in sibling modules (teams, documents, folders, comments).
+//! Additional tools are implemented in sibling modules (teams, documents, folders, comments,
+//! dependencies).
 
 use std::str::FromStr;
 
 use db_crate::models::{
+    document_revision::WORKSPACE_ID_HEADER,
     project::Project,
     repo::Repo,
     tag::Tag,
@@ -121,6 +123,8 @@ pub struct TaskSummary {
     pub updated_at: String,
     #[schemars(description = "Whether the task has an in-progress execution attempt")]
     pub has_in_progress_attempt: Option<bool>,
+    #[schemars(description = "Whether an attempt is waiting in the execution queue")]
+    pub has_queued_attempt: Option<bool>,
     #[schemars(description = "Whether the last execution attempt failed")]
     pub last_attempt_failed: Option<bool>,
 }
@@ -134,6 +138,7 @@ impl TaskSummary {
             created_at: task.created_at.to_rfc3339(),
             updated_at: task.updated_at.to_rfc3339(),
             has_in_progress_attempt: Some(task.has_in_progress_attempt),
+            has_queued_attempt: Some(task.has_queued_attempt),
             last_attempt_failed: Some(task.last_attempt_failed),
         }
     }
@@ -304,14 +309,22 @@ impl TaskServer {
         Self {
             client: reqwest::Client::new(),
             base_url: base_url.to_string(),
-            tool_router: Self::tool_router(),
+            tool_router: Self::tool_router()
+                + Self::dependency_tool_router()
+                + Self::revision_tool_router()
+                + Self::search_tool_router(),
             context: None,
             api_token,
         }
     }
 
-    /// Add Authorization header to request if API token is configured
+    /// Add Authorization header to request if API token is configured, and
+    /// the workspace header so document edits are attributed to this agent
     fn with_auth(&self, rb: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        let rb = match &self.context {
+            Some(context) => rb.header(WORKSPACE_ID_HEADER, context.workspace_id.to_string()),
+            None => rb,
+        };
         match &self.api_token {
             Some(token) => rb.header("Authorization", format!("Bearer {}", token)),
             None => rb,
@@ -422,8 +435,19 @@ impl TaskServer {
 
         if !resp.status().is_success() {
             let status = resp.status();
+            // Surface the backend's reason, e.g. which API key scope is missing
+            let reason = resp
+                .json::<serde_json::Value>()
+                .await
+                .ok()
+                .and_then(|body| {
+                    body.get("message")
+                        .or_else(|| body.get("error"))
+                        .and_then(|v| v.as_str())
+                        .map(str::to_string)
+                });
             return Err(
-                Self::err(format!("VK API returned error status: {}", status), None).unwrap(),
+                Self::err(format!("VK API returned error status: {}", status), reason).unwrap(),
             );
         }
 
@@ -848,6 +872,8 @@ impl ServerHandler for TaskServer {
             "DOCUMENTS: 'list_documents', 'get_document', 'create_document', 'update_document', 'delete_document'. ",
             "FOLDERS: 'list_folders', 'get_folder', 'create_folder', 'update_folder', 'delete_folder'. ",
             "COMMENTS: 'list_comments', 'add_comment' (accepts IKA-123 or UUID). ",
+            "DEPENDENCIES: 'add_dependency', 'list_dependencies' (check blockers before starting work). ",
+            "SEARCH: 'search' (full-text across issues, comments, documents and project updates). ",
             "REPOS: 'list_repos'. WORKSPACES: 'start_workspace_session'. ",
             "Use team identifiers (IKA, BLA) or issue keys (IKA-123) where supported."
         ).to_string();
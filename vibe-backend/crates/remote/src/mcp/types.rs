@@ -242,6 +242,85 @@ pub struct DeleteDocumentRequest {
     pub document_id: Uuid,
 }
 
+// ============================================================================
+// Document revision types
+// ============================================================================
+
+/// Revision summary for history listings
+#[derive(Debug, Serialize, rmcp::schemars::JsonSchema)]
+pub struct DocumentRevisionSummary {
+    #[schemars(description = "Revision number, starting at 1")]
+    pub revision_number: i32,
+    #[schemars(description = "Document title at this revision")]
+    pub title: String,
+    #[schemars(description = "Content size in bytes")]
+    pub content_size: i64,
+    #[schemars(description = "User who made the change, if any")]
+    pub author_user_id: Option<String>,
+    #[schemars(description = "Coding agent execution that made the change, if any")]
+    pub author_execution_process_id: Option<String>,
+    #[schemars(
+        description = "Scan or sync that made the change, if any: filesystem_scan, github_sync or cloud_storage_sync"
+    )]
+    pub author_source: Option<String>,
+    #[schemars(description = "Revision this one restored, if it was a restore")]
+    pub restored_from: Option<i32>,
+    #[schemars(description = "When the revision was recorded")]
+    pub created_at: String,
+}
+
+#[derive(Debug, Deserialize, rmcp::schemars::JsonSchema)]
+pub struct ListDocumentRevisionsRequest {
+    #[schemars(description = "The document ID")]
+    pub document_id: Uuid,
+}
+
+#[derive(Debug, Serialize, rmcp::schemars::JsonSchema)]
+pub struct ListDocumentRevisionsResponse {
+    pub document_id: String,
+    pub count: usize,
+    #[schemars(description = "Revisions, newest first")]
+    pub revisions: Vec<DocumentRevisionSummary>,
+}
+
+#[derive(Debug, Deserialize, rmcp::schemars::JsonSchema)]
+pub struct GetDocumentRevisionRequest {
+    #[schemars(description = "The document ID")]
+    pub document_id: Uuid,
+    #[schemars(description = "The revision number")]
+    pub revision_number: i32,
+}
+
+#[derive(Debug, Serialize, rmcp::schemars::JsonSchema)]
+pub struct DocumentRevisionDetails {
+    #[serde(flatten)]
+    pub summary: DocumentRevisionSummary,
+    #[schemars(description = "The document content at this revision")]
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize, rmcp::schemars::JsonSchema)]
+pub struct DiffDocumentRevisionsRequest {
+    #[schemars(description = "The document ID")]
+    pub document_id: Uuid,
+    #[schemars(description = "The older revision number")]
+    pub from: i32,
+    #[schemars(description = "The newer revision number")]
+    pub to: i32,
+}
+
+#[derive(Debug, Serialize, rmcp::schemars::JsonSchema)]
+pub struct DiffDocumentRevisionsResponse {
+    pub from_revision: i32,
+    pub to_revision: i32,
+    #[schemars(description = "Number of added lines")]
+    pub additions: usize,
+    #[schemars(description = "Number of removed lines")]
+    pub deletions: usize,
+    #[schemars(description = "Unified diff from the older to the newer revision")]
+    pub unified_diff: String,
+}
+
 // ============================================================================
 // Folder types
 // ============================================================================
@@ -348,6 +427,105 @@ pub struct AddCommentResponse {
     pub task_id: String,
 }
 
+// ============================================================================
+// Dependency types
+// ============================================================================
+
+#[derive(Debug, Deserialize, rmcp::schemars::JsonSchema)]
+pub struct AddDependencyRequest {
+    #[schemars(description = "The blocked task: UUID or issue key (e.g., 'IKA-40')")]
+    pub task_id: String,
+    #[schemars(
+        description = "The task that must be done first: UUID or issue key (e.g., 'IKA-38')"
+    )]
+    pub depends_on: String,
+}
+
+#[derive(Debug, Serialize, rmcp::schemars::JsonSchema)]
+pub struct AddDependencyResponse {
+    pub dependency_id: String,
+    pub task_id: String,
+    pub depends_on_task_id: String,
+}
+
+#[derive(Debug, Deserialize, rmcp::schemars::JsonSchema)]
+pub struct ListDependenciesRequest {
+    #[schemars(description = "Task ID (UUID) or issue key (e.g., 'IKA-123')")]
+    pub task_id: String,
+}
+
+/// The task on the other end of a dependency
+#[derive(Debug, Serialize, rmcp::schemars::JsonSchema)]
+pub struct DependencySummary {
+    #[schemars(description = "The unique identifier of the task")]
+    pub task_id: String,
+    #[schemars(description = "Issue key such as 'IKA-38', if the task belongs to a team")]
+    pub issue_key: Option<String>,
+    pub title: String,
+    #[schemars(description = "Current status of the task")]
+    pub status: String,
+}
+
+#[derive(Debug, Serialize, rmcp::schemars::JsonSchema)]
+pub struct ListDependenciesResponse {
+    pub task_id: String,
+    #[schemars(description = "Tasks that must be done before this task")]
+    pub blockers: Vec<DependencySummary>,
+    #[schemars(description = "Tasks waiting on this task")]
+    pub blocked_tasks: Vec<DependencySummary>,
+    #[schemars(description = "True when every blocker is done")]
+    pub ready: bool,
+}
+
+// ============================================================================
+// Search types
+// ============================================================================
+
+#[derive(Debug, Deserialize, rmcp::schemars::JsonSchema)]
+pub struct SearchRequest {
+    #[schemars(
+        description = "Search text. Supports quoted phrases, 'or' and '-excluded' words (e.g., '\"rate limit\" -redis')"
+    )]
+    pub query: String,
+    #[schemars(description = "Optional team identifier (e.g., 'IKA') or team UUID")]
+    pub team: Option<String>,
+    #[schemars(description = "Optional project ID to search within")]
+    pub project_id: Option<Uuid>,
+    #[schemars(
+        description = "Optional task status filter: 'todo', 'inprogress', 'inreview', 'done', 'cancelled'"
+    )]
+    pub status: Option<String>,
+    #[schemars(
+        description = "Optional kinds of results: 'task', 'comment', 'document', 'project_update' (default: all)"
+    )]
+    pub types: Option<Vec<String>>,
+    #[schemars(description = "Maximum number of results to return (default: 20, max: 100)")]
+    pub limit: Option<i64>,
+}
+
+/// A ranked search hit
+#[derive(Debug, Serialize, rmcp::schemars::JsonSchema)]
+pub struct SearchHit {
+    #[schemars(description = "'task', 'comment', 'document' or 'project_update'")]
+    pub entity_type: String,
+    pub id: String,
+    pub title: String,
+    #[schemars(description = "Matching excerpt with terms wrapped in <mark> tags")]
+    pub snippet: String,
+    #[schemars(description = "Task the hit belongs to, for tasks and comments")]
+    pub task_id: Option<String>,
+    #[schemars(description = "Issue key such as 'IKA-38', for team tasks and their comments")]
+    pub issue_key: Option<String>,
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Serialize, rmcp::schemars::JsonSchema)]
+pub struct SearchResponse {
+    pub count: usize,
+    #[schemars(description = "Results, best match first")]
+    pub results: Vec<SearchHit>,
+}
+
 // ============================================================================
 // Resolved team info (internal use)
 // ============================================================================
@@ -6,6 +6,8 @@ use axum::{
     http::StatusCode,
     routing::{get, post},
 };
+use db_crate::models::api_key::{ApiKeyScopes, ApiScope};
+use serde::Serialize;
 use tracing::instrument;
 use uuid::Uuid;
 
@@ -23,6 +25,35 @@ pub fn router() -> Router<AppState> {
         .route("/api-keys/{key_id}/revoke", post(revoke_api_key))
 }
 
+/// Routes any authenticated caller may use, whatever its scopes
+pub fn current_router() -> Router<AppState> {
+    Router::new().route("/api-keys/current", get(get_current_credentials))
+}
+
+/// Scopes held by the caller of `/api-keys/current`
+#[derive(Debug, Serialize)]
+struct CurrentCredentials {
+    is_api_key: bool,
+    scopes: Vec<ApiScope>,
+}
+
+/// Report the scopes of the presented credentials (sessions have every scope)
+async fn get_current_credentials(
+    scopes: Option<Extension<ApiKeyScopes>>,
+) -> Json<ApiResponse<CurrentCredentials>> {
+    let credentials = match scopes {
+        Some(Extension(ApiKeyScopes(scopes))) => CurrentCredentials {
+            is_api_key: true,
+            scopes,
+        },
+        None => CurrentCredentials {
+            is_api_key: false,
+            scopes: vec![ApiScope::Admin],
+        },
+    };
+    ApiResponse::success(credentials)
+}
+
 /// List all API keys for the current user
 #[instrument(
     name = "api_keys.list",
@@ -62,6 +93,13 @@ async fn create_api_key(
         ));
     }
 
+    if payload.scopes.as_ref().is_some_and(Vec::is_empty) {
+        return Err(ErrorResponse::new(
+            StatusCode::BAD_REQUEST,
+            "An API key needs at least one scope",
+        ));
+    }
+
     let user_id = ctx.user.id.to_string();
     let key = ApiKeyRepository::create(state.pool(), &user_id, &payload)
         .await
@@ -3,16 +3,23 @@
 use axum::{
     Extension, Json, Router,
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     routing::get,
 };
+use db_crate::models::{
+    document_revision::{
+        DocumentRevision, DocumentRevisionDiff, DocumentRevisionSummary, RevisionAuthor,
+        WORKSPACE_ID_HEADER,
+    },
+    workspace::Workspace,
+};
 use serde::Deserialize;
 use tracing::instrument;
 use uuid::Uuid;
 
 use super::{
     error::{ApiResponse, ErrorResponse},
-    organization_members::ensure_member_access,
+    organization_members::{ensure_member_access, ensure_team_task_access},
 };
 use crate::{
     AppState,
@@ -45,6 +52,12 @@ pub struct CreateDocumentRequest {
     pub data: CreateDocument,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct RevisionDiffQuery {
+    pub from: i32,
+    pub to: i32,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct CreateFolderRequest {
     pub team_id: Uuid,
@@ -62,6 +75,19 @@ pub fn router() -> Router<AppState> {
                 .put(update_document)
                 .delete(delete_document),
         )
+        // Revision history
+        .route(
+            "/documents/{document_id}/revisions",
+            get(list_document_revisions),
+        )
+        .route(
+            "/documents/{document_id}/revisions/diff",
+            get(diff_document_revisions),
+        )
+        .route(
+            "/documents/{document_id}/revisions/{revision_number}",
+            get(get_document_revision),
+        )
         // Folder CRUD
         .route("/folders", get(list_folders).post(create_folder))
         .route(
@@ -145,19 +171,22 @@ async fn get_document(
 async fn create_document(
     State(state): State<AppState>,
     Extension(ctx): Extension<RequestContext>,
+    headers: HeaderMap,
     Json(payload): Json<CreateDocumentRequest>,
 ) -> Result<Json<ApiResponse<Document>>, ErrorResponse> {
     verify_team_access(&state, ctx.user.id, payload.team_id).await?;
 
-    let document = DocumentRepository::create(state.pool(), payload.team_id, &payload.data)
-        .await
-        .map_err(|error| {
-            tracing::error!(?error, "failed to create document");
-            ErrorResponse::new(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "failed to create document",
-            )
-        })?;
+    let author = revision_author(&state, &ctx, &headers).await?;
+    let document =
+        DocumentRepository::create(state.pool(), payload.team_id, &payload.data, &author)
+            .await
+            .map_err(|error| {
+                tracing::error!(?error, "failed to create document");
+                ErrorResponse::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "failed to create document",
+                )
+            })?;
 
     Ok(ApiResponse::success(document))
 }
@@ -172,6 +201,7 @@ async fn update_document(
     State(state): State<AppState>,
     Extension(ctx): Extension<RequestContext>,
     Path(document_id): Path<Uuid>,
+    headers: HeaderMap,
     Json(payload): Json<UpdateDocument>,
 ) -> Result<Json<ApiResponse<Document>>, ErrorResponse> {
     // First check document exists and user has access
@@ -188,7 +218,8 @@ async fn update_document(
 
     verify_team_access(&state, ctx.user.id, existing.team_id).await?;
 
-    let document = DocumentRepository::update(state.pool(), document_id, &payload)
+    let author = revision_author(&state, &ctx, &headers).await?;
+    let document = DocumentRepository::update(state.pool(), document_id, &payload, &author)
         .await
         .map_err(|error| {
             tracing::error!(?error, %document_id, "failed to update document");
@@ -239,6 +270,68 @@ async fn delete_document(
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// List a document's revisions, newest first
+#[instrument(
+    name = "documents.list_document_revisions",
+    skip(state, ctx),
+    fields(user_id = %ctx.user.id, document_id = %document_id)
+)]
+async fn list_document_revisions(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(document_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<DocumentRevisionSummary>>>, ErrorResponse> {
+    verify_document_access(&state, ctx.user.id, document_id).await?;
+
+    let revisions = DocumentRevision::list_by_document(state.pool(), document_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, %document_id, "failed to list document revisions");
+            ErrorResponse::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "failed to list document revisions",
+            )
+        })?;
+
+    Ok(ApiResponse::success(revisions))
+}
+
+/// Get a single revision, including its content
+#[instrument(
+    name = "documents.get_document_revision",
+    skip(state, ctx),
+    fields(user_id = %ctx.user.id, document_id = %document_id, revision_number)
+)]
+async fn get_document_revision(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path((document_id, revision_number)): Path<(Uuid, i32)>,
+) -> Result<Json<ApiResponse<DocumentRevision>>, ErrorResponse> {
+    verify_document_access(&state, ctx.user.id, document_id).await?;
+
+    let revision = find_revision(&state, document_id, revision_number).await?;
+    Ok(ApiResponse::success(revision))
+}
+
+/// Diff two revisions of a document
+#[instrument(
+    name = "documents.diff_document_revisions",
+    skip(state, ctx, params),
+    fields(user_id = %ctx.user.id, document_id = %document_id, from = params.from, to = params.to)
+)]
+async fn diff_document_revisions(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(document_id): Path<Uuid>,
+    Query(params): Query<RevisionDiffQuery>,
+) -> Result<Json<ApiResponse<DocumentRevisionDiff>>, ErrorResponse> {
+    verify_document_access(&state, ctx.user.id, document_id).await?;
+
+    let from = find_revision(&state, document_id, params.from).await?;
+    let to = find_revision(&state, document_id, params.to).await?;
+    Ok(ApiResponse::success(from.diff(&to)))
+}
+
 /// List folders
 #[instrument(
     name = "documents.list_folders",
@@ -389,3 +482,66 @@ async fn verify_team_access(
     }
     Ok(())
 }
+
+/// Helper: Verify the document exists and user has access to its team
+async fn verify_document_access(
+    state: &AppState,
+    user_id: Uuid,
+    document_id: Uuid,
+) -> Result<(), ErrorResponse> {
+    let document = DocumentRepository::find_by_id(state.pool(), document_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, %document_id, "failed to get document");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "failed to get document")
+        })?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "document not found"))?;
+
+    verify_team_access(state, user_id, document.team_id).await
+}
+
+async fn find_revision(
+    state: &AppState,
+    document_id: Uuid,
+    revision_number: i32,
+) -> Result<DocumentRevision, ErrorResponse> {
+    DocumentRevision::find_by_number(state.pool(), document_id, revision_number)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, %document_id, revision_number, "failed to get revision");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "failed to get revision")
+        })?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "revision not found"))
+}
+
+/// Helper: Attribute a document change to the caller and, for MCP requests
+/// that name their workspace, to the coding agent running in it. The header
+/// is client-supplied, so the caller must have access to the workspace's task.
+pub(super) async fn revision_author(
+    state: &AppState,
+    ctx: &RequestContext,
+    headers: &HeaderMap,
+) -> Result<RevisionAuthor, ErrorResponse> {
+    let workspace_id = headers
+        .get(WORKSPACE_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| Uuid::parse_str(value).ok());
+
+    if let Some(workspace_id) = workspace_id {
+        let workspace = Workspace::find_by_id(state.pool(), workspace_id)
+            .await
+            .map_err(|error| {
+                tracing::error!(?error, %workspace_id, "failed to get workspace");
+                ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "failed to save document")
+            })?
+            .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "workspace not found"))?;
+        ensure_team_task_access(state.pool(), ctx.user.id, workspace.task_id).await?;
+    }
+
+    RevisionAuthor::resolve(state.pool(), Some(ctx.user.id.to_string()), workspace_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, "failed to resolve revision author");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "failed to save document")
+        })
+}
@@ -0,0 +1,81 @@
+//! Email template listing and preview for superadmins.
+//!
+//! Previews render the same templates the SMTP and file mailers send, using
+//! example variables that a request body can override.
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::get,
+};
+use serde_json::Value;
+use tracing::instrument;
+
+use super::error::{ApiResponse, ErrorResponse};
+use crate::{
+    AppState,
+    mail::templates::{EmailTemplate, EmailTemplateInfo, RenderedEmail},
+};
+
+/// Router for email template routes (requires superadmin auth)
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/superadmin/email-templates", get(list_templates))
+        .route(
+            "/superadmin/email-templates/{name}/preview",
+            get(preview_template).post(preview_template_with_data),
+        )
+}
+
+/// List the email templates and whether each is overridden locally
+#[instrument(name = "email_templates.list", skip(state))]
+async fn list_templates(
+    State(state): State<AppState>,
+) -> Json<ApiResponse<Vec<EmailTemplateInfo>>> {
+    ApiResponse::success(state.email_templates().list())
+}
+
+/// Render a template with its example variables
+#[instrument(name = "email_templates.preview", skip(state))]
+async fn preview_template(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<ApiResponse<RenderedEmail>>, ErrorResponse> {
+    render_preview(&state, &name, None)
+}
+
+/// Render a template with the example variables overridden by the body
+#[instrument(name = "email_templates.preview_with_data", skip(state, data))]
+async fn preview_template_with_data(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(data): Json<Value>,
+) -> Result<Json<ApiResponse<RenderedEmail>>, ErrorResponse> {
+    render_preview(&state, &name, Some(data))
+}
+
+fn render_preview(
+    state: &AppState,
+    name: &str,
+    overrides: Option<Value>,
+) -> Result<Json<ApiResponse<RenderedEmail>>, ErrorResponse> {
+    let template = EmailTemplate::from_name(name).ok_or_else(|| {
+        ErrorResponse::new(
+            StatusCode::NOT_FOUND,
+            format!("unknown email template `{name}`"),
+        )
+    })?;
+
+    let mut data = template.sample_data();
+    if let (Value::Object(data), Some(Value::Object(overrides))) = (&mut data, overrides) {
+        data.extend(overrides);
+    }
+
+    let email = state
+        .email_templates()
+        .render(template, &data)
+        .map_err(|error| ErrorResponse::new(StatusCode::UNPROCESSABLE_ENTITY, error.to_string()))?;
+
+    Ok(ApiResponse::success(email))
+}
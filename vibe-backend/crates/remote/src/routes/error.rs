@@ -3,6 +3,7 @@ use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
 };
+use db_crate::models::api_key::MissingScope;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
@@ -56,6 +57,12 @@ impl ErrorResponse {
     }
 }
 
+impl From<MissingScope> for ErrorResponse {
+    fn from(error: MissingScope) -> Self {
+        Self::new(StatusCode::FORBIDDEN, error.to_string())
+    }
+}
+
 impl IntoResponse for ErrorResponse {
     fn into_response(self) -> Response {
         (self.status, Json(json!({ "error": self.message }))).into_response()
@@ -0,0 +1,500 @@
+//! Cloud AI execution routes (IKA-248)
+//!
+//! Executions are queued here and picked up by the background
+//! `ExecutionWorker`; pause and cancel are observed by the running attempt
+//! at its next checkpoint.
+
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, post},
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tracing::instrument;
+use uuid::Uuid;
+
+use super::{
+    error::{ApiResponse, ErrorResponse},
+    organization_members::ensure_task_access,
+};
+use crate::{
+    AppState,
+    auth::RequestContext,
+    db::{
+        execution_approvals::{ApprovalError, ExecutionApproval, ExecutionApprovalRepository},
+        execution_attempts::{ExecutionAttempt, ExecutionAttemptRepository},
+        execution_logs::{ExecutionLog, ExecutionLogRepository},
+        task_executions::{
+            CreateTaskExecution, ExecutionStatus, TaskExecution, TaskExecutionRepository,
+        },
+        tasks::SharedTaskRepository,
+    },
+};
+
+const VALID_EXECUTION_MODES: [&str; 4] = ["standard", "fast", "thorough", "custom"];
+const DEFAULT_LOG_PAGE_SIZE: i64 = 500;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/tasks/{task_id}/executions",
+            get(list_task_executions).post(start_execution),
+        )
+        .route("/executions/{execution_id}", get(get_execution))
+        .route("/executions/{execution_id}/pause", post(pause_execution))
+        .route("/executions/{execution_id}/resume", post(resume_execution))
+        .route("/executions/{execution_id}/cancel", post(cancel_execution))
+        .route("/executions/{execution_id}/retry", post(retry_execution))
+        .route(
+            "/executions/{execution_id}/attempts/{attempt_id}/logs",
+            get(get_attempt_logs),
+        )
+        .route(
+            "/executions/{execution_id}/approvals",
+            get(list_execution_approvals),
+        )
+        .route(
+            "/executions/{execution_id}/approvals/{approval_id}/approve",
+            post(approve_execution_action),
+        )
+        .route(
+            "/executions/{execution_id}/approvals/{approval_id}/reject",
+            post(reject_execution_action),
+        )
+}
+
+fn internal_error(error: impl std::fmt::Debug, message: &'static str) -> ErrorResponse {
+    tracing::error!(?error, "{message}");
+    ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, message)
+}
+
+/// Load an execution and check the caller can access its task
+async fn load_execution(
+    pool: &PgPool,
+    user_id: Uuid,
+    execution_id: Uuid,
+) -> Result<TaskExecution, ErrorResponse> {
+    let execution = TaskExecutionRepository::find_by_id(pool, execution_id)
+        .await
+        .map_err(|error| internal_error(error, "failed to load execution"))?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "execution not found"))?;
+
+    ensure_task_access(pool, user_id, execution.task_id).await?;
+    Ok(execution)
+}
+
+/// Apply a status transition, mapping "wrong state" to 409
+async fn transition(
+    pool: &PgPool,
+    execution: &TaskExecution,
+    from: &[ExecutionStatus],
+    to: ExecutionStatus,
+) -> Result<TaskExecution, ErrorResponse> {
+    TaskExecutionRepository::transition(pool, execution.id, from, to)
+        .await
+        .map_err(|error| internal_error(error, "failed to update execution"))?
+        .ok_or_else(|| {
+            ErrorResponse::new(
+                StatusCode::CONFLICT,
+                format!(
+                    "cannot move execution from {} to {}",
+                    execution.status,
+                    to.as_str()
+                ),
+            )
+        })
+}
+
+#[derive(Debug, Deserialize)]
+struct StartExecutionRequest {
+    execution_mode: Option<String>,
+    max_attempts: Option<i32>,
+    max_duration_seconds: Option<i32>,
+    max_tokens: Option<i32>,
+}
+
+/// Queue a new AI execution for a task
+#[instrument(
+    name = "executions.start",
+    skip(state, ctx, payload),
+    fields(user_id = %ctx.user.id, task_id = %task_id)
+)]
+async fn start_execution(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(task_id): Path<Uuid>,
+    Json(payload): Json<StartExecutionRequest>,
+) -> Result<Json<ApiResponse<TaskExecution>>, ErrorResponse> {
+    let pool = state.pool();
+    let organization_id = ensure_task_access(pool, ctx.user.id, task_id).await?;
+
+    if let Some(mode) = payload.execution_mode.as_deref()
+        && !VALID_EXECUTION_MODES.contains(&mode)
+    {
+        return Err(ErrorResponse::new(
+            StatusCode::BAD_REQUEST,
+            "Invalid execution_mode. Must be one of: standard, fast, thorough, custom",
+        ));
+    }
+    for (name, value) in [
+        ("max_attempts", payload.max_attempts),
+        ("max_duration_seconds", payload.max_duration_seconds),
+        ("max_tokens", payload.max_tokens),
+    ] {
+        if value.is_some_and(|v| v <= 0) {
+            return Err(ErrorResponse::new(
+                StatusCode::BAD_REQUEST,
+                format!("{name} must be greater than zero"),
+            ));
+        }
+    }
+
+    let task = SharedTaskRepository::new(pool)
+        .find_any_task_by_id(task_id)
+        .await
+        .map_err(|error| internal_error(error, "failed to load task"))?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "task not found"))?;
+
+    let execution = TaskExecutionRepository::create(
+        pool,
+        CreateTaskExecution {
+            task_id,
+            project_id: task.project_id,
+            organization_id,
+            initiated_by: ctx.user.id,
+            execution_mode: payload.execution_mode,
+            max_attempts: payload.max_attempts,
+            max_duration_seconds: payload.max_duration_seconds,
+            max_tokens: payload.max_tokens,
+        },
+    )
+    .await
+    .map_err(|error| internal_error(error, "failed to create execution"))?;
+
+    let execution = transition(
+        pool,
+        &execution,
+        &[ExecutionStatus::Pending],
+        ExecutionStatus::Queued,
+    )
+    .await?;
+
+    Ok(ApiResponse::success(execution))
+}
+
+/// List executions for a task, newest first
+#[instrument(
+    name = "executions.list",
+    skip(state, ctx),
+    fields(user_id = %ctx.user.id, task_id = %task_id)
+)]
+async fn list_task_executions(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(task_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<TaskExecution>>>, ErrorResponse> {
+    ensure_task_access(state.pool(), ctx.user.id, task_id).await?;
+
+    let executions = TaskExecutionRepository::list_by_task(state.pool(), task_id)
+        .await
+        .map_err(|error| internal_error(error, "failed to list executions"))?;
+
+    Ok(ApiResponse::success(executions))
+}
+
+#[derive(Debug, Serialize)]
+struct ExecutionDetails {
+    #[serde(flatten)]
+    execution: TaskExecution,
+    attempts: Vec<ExecutionAttempt>,
+}
+
+/// Get an execution with its attempts
+#[instrument(
+    name = "executions.get",
+    skip(state, ctx),
+    fields(user_id = %ctx.user.id, execution_id = %execution_id)
+)]
+async fn get_execution(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(execution_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<ExecutionDetails>>, ErrorResponse> {
+    let execution = load_execution(state.pool(), ctx.user.id, execution_id).await?;
+    let attempts = ExecutionAttemptRepository::list_by_execution(state.pool(), execution_id)
+        .await
+        .map_err(|error| internal_error(error, "failed to list attempts"))?;
+
+    Ok(ApiResponse::success(ExecutionDetails {
+        execution,
+        attempts,
+    }))
+}
+
+/// Pause a queued or running execution
+#[instrument(
+    name = "executions.pause",
+    skip(state, ctx),
+    fields(user_id = %ctx.user.id, execution_id = %execution_id)
+)]
+async fn pause_execution(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(execution_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<TaskExecution>>, ErrorResponse> {
+    let execution = load_execution(state.pool(), ctx.user.id, execution_id).await?;
+    let execution = transition(
+        state.pool(),
+        &execution,
+        &[ExecutionStatus::Queued, ExecutionStatus::Running],
+        ExecutionStatus::Paused,
+    )
+    .await?;
+
+    Ok(ApiResponse::success(execution))
+}
+
+/// Put a paused execution back in the queue
+#[instrument(
+    name = "executions.resume",
+    skip(state, ctx),
+    fields(user_id = %ctx.user.id, execution_id = %execution_id)
+)]
+async fn resume_execution(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(execution_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<TaskExecution>>, ErrorResponse> {
+    let execution = load_execution(state.pool(), ctx.user.id, execution_id).await?;
+    let execution = transition(
+        state.pool(),
+        &execution,
+        &[ExecutionStatus::Paused],
+        ExecutionStatus::Queued,
+    )
+    .await?;
+
+    Ok(ApiResponse::success(execution))
+}
+
+/// Cancel an execution that has not finished yet
+#[instrument(
+    name = "executions.cancel",
+    skip(state, ctx),
+    fields(user_id = %ctx.user.id, execution_id = %execution_id)
+)]
+async fn cancel_execution(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(execution_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<TaskExecution>>, ErrorResponse> {
+    let execution = load_execution(state.pool(), ctx.user.id, execution_id).await?;
+    let execution = transition(
+        state.pool(),
+        &execution,
+        &[
+            ExecutionStatus::Pending,
+            ExecutionStatus::Queued,
+            ExecutionStatus::Running,
+            ExecutionStatus::Paused,
+        ],
+        ExecutionStatus::Cancelled,
+    )
+    .await?;
+
+    Ok(ApiResponse::success(execution))
+}
+
+/// Queue another attempt for a failed, timed out or cancelled execution
+#[instrument(
+    name = "executions.retry",
+    skip(state, ctx),
+    fields(user_id = %ctx.user.id, execution_id = %execution_id)
+)]
+async fn retry_execution(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(execution_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<TaskExecution>>, ErrorResponse> {
+    let execution = load_execution(state.pool(), ctx.user.id, execution_id).await?;
+
+    match execution.parsed_status() {
+        Some(ExecutionStatus::Failed | ExecutionStatus::Timeout | ExecutionStatus::Cancelled) => {}
+        _ => {
+            return Err(ErrorResponse::new(
+                StatusCode::CONFLICT,
+                format!("cannot retry an execution that is {}", execution.status),
+            ));
+        }
+    }
+    if !execution.attempts_remaining() {
+        return Err(ErrorResponse::new(
+            StatusCode::CONFLICT,
+            format!("execution has used all {} attempts", execution.max_attempts),
+        ));
+    }
+
+    let execution = TaskExecutionRepository::requeue(state.pool(), execution_id)
+        .await
+        .map_err(|error| internal_error(error, "failed to retry execution"))?
+        .ok_or_else(|| {
+            ErrorResponse::new(StatusCode::CONFLICT, "execution changed state; try again")
+        })?;
+
+    Ok(ApiResponse::success(execution))
+}
+
+#[derive(Debug, Deserialize)]
+struct LogsQuery {
+    /// Only return entries after this sequence number (for polling)
+    after: Option<i64>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+/// Get log entries for one attempt
+#[instrument(
+    name = "executions.logs",
+    skip(state, ctx, query),
+    fields(user_id = %ctx.user.id, execution_id = %execution_id, attempt_id = %attempt_id)
+)]
+async fn get_attempt_logs(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path((execution_id, attempt_id)): Path<(Uuid, Uuid)>,
+    Query(query): Query<LogsQuery>,
+) -> Result<Json<ApiResponse<Vec<ExecutionLog>>>, ErrorResponse> {
+    let pool = state.pool();
+    load_execution(pool, ctx.user.id, execution_id).await?;
+
+    let attempt = ExecutionAttemptRepository::find_by_id(pool, attempt_id)
+        .await
+        .map_err(|error| internal_error(error, "failed to load attempt"))?
+        .filter(|attempt| attempt.execution_id == execution_id)
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "attempt not found"))?;
+
+    let logs = match query.after {
+        Some(after) => ExecutionLogRepository::get_after_sequence(pool, attempt.id, after).await,
+        None => {
+            ExecutionLogRepository::list_by_attempt(
+                pool,
+                attempt.id,
+                query.limit.unwrap_or(DEFAULT_LOG_PAGE_SIZE).clamp(1, 1000),
+                query.offset.unwrap_or(0).max(0),
+            )
+            .await
+        }
+    }
+    .map_err(|error| internal_error(error, "failed to load execution logs"))?;
+
+    Ok(ApiResponse::success(logs))
+}
+
+/// List approval requests raised by an execution
+#[instrument(
+    name = "executions.approvals",
+    skip(state, ctx),
+    fields(user_id = %ctx.user.id, execution_id = %execution_id)
+)]
+async fn list_execution_approvals(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(execution_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<ExecutionApproval>>>, ErrorResponse> {
+    load_execution(state.pool(), ctx.user.id, execution_id).await?;
+
+    let approvals = ExecutionApprovalRepository::list_by_execution(state.pool(), execution_id)
+        .await
+        .map_err(|error| internal_error(error, "failed to list approvals"))?;
+
+    Ok(ApiResponse::success(approvals))
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct DecisionRequest {
+    reason: Option<String>,
+}
+
+fn approval_error_response(error: ApprovalError) -> ErrorResponse {
+    match error {
+        ApprovalError::NotFound => ErrorResponse::new(StatusCode::NOT_FOUND, "approval not found"),
+        ApprovalError::AlreadyDecided => {
+            ErrorResponse::new(StatusCode::CONFLICT, "approval already decided")
+        }
+        ApprovalError::Expired => ErrorResponse::new(StatusCode::GONE, "approval expired"),
+        ApprovalError::Forbidden => ErrorResponse::new(StatusCode::FORBIDDEN, "forbidden"),
+        ApprovalError::Database(error) => internal_error(error, "failed to update approval"),
+    }
+}
+
+async fn load_approval(
+    pool: &PgPool,
+    execution_id: Uuid,
+    approval_id: Uuid,
+) -> Result<ExecutionApproval, ErrorResponse> {
+    ExecutionApprovalRepository::find_by_id(pool, approval_id)
+        .await
+        .map_err(approval_error_response)?
+        .filter(|approval| approval.execution_id == execution_id)
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "approval not found"))
+}
+
+/// Approve an action the agent is waiting on
+#[instrument(
+    name = "executions.approve",
+    skip(state, ctx, payload),
+    fields(user_id = %ctx.user.id, execution_id = %execution_id, approval_id = %approval_id)
+)]
+async fn approve_execution_action(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path((execution_id, approval_id)): Path<(Uuid, Uuid)>,
+    payload: Option<Json<DecisionRequest>>,
+) -> Result<Json<ApiResponse<ExecutionApproval>>, ErrorResponse> {
+    let pool = state.pool();
+    load_execution(pool, ctx.user.id, execution_id).await?;
+    load_approval(pool, execution_id, approval_id).await?;
+
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+    let approval = ExecutionApprovalRepository::approve(
+        pool,
+        approval_id,
+        ctx.user.id,
+        payload.reason.as_deref(),
+    )
+    .await
+    .map_err(approval_error_response)?;
+
+    Ok(ApiResponse::success(approval))
+}
+
+/// Reject an action the agent is waiting on
+#[instrument(
+    name = "executions.reject",
+    skip(state, ctx, payload),
+    fields(user_id = %ctx.user.id, execution_id = %execution_id, approval_id = %approval_id)
+)]
+async fn reject_execution_action(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path((execution_id, approval_id)): Path<(Uuid, Uuid)>,
+    payload: Option<Json<DecisionRequest>>,
+) -> Result<Json<ApiResponse<ExecutionApproval>>, ErrorResponse> {
+    let pool = state.pool();
+    load_execution(pool, ctx.user.id, execution_id).await?;
+    load_approval(pool, execution_id, approval_id).await?;
+
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+    let approval = ExecutionApprovalRepository::reject(
+        pool,
+        approval_id,
+        ctx.user.id,
+        payload.reason.as_deref(),
+    )
+    .await
+    .map_err(approval_error_response)?;
+
+    Ok(ApiResponse::success(approval))
+}
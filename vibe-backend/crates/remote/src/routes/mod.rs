@@ -4,6 +4,7 @@ use axum::{
     middleware,
     routing::get,
 };
+use db_crate::models::api_key::{ApiScope, ScopeRule, scope_middleware};
 use tower_http::{
     cors::{AllowHeaders, AllowMethods, AllowOrigin, CorsLayer},
     request_id::{MakeRequestUuid, PropagateRequestIdLayer, RequestId, SetRequestIdLayer},
@@ -15,6 +16,7 @@ use tracing::{Level, field};
 use crate::{
     AppState,
     auth::{require_clerk_session, require_superadmin},
+    routes::error::ErrorResponse,
 };
 
 mod abuse_signals;
@@ -25,8 +27,10 @@ mod billing;
 mod copilot_claude;
 mod documents;
 mod electric_proxy;
+mod email_templates;
 mod email_verification;
 mod error;
+mod executions;
 mod github_app;
 mod github_settings;
 mod gitlab_settings;
@@ -41,12 +45,15 @@ mod pulse;
 pub mod registrations;
 mod repos;
 mod review;
+mod search;
 mod stripe;
 mod stubs;
 mod subscriptions;
 mod superadmins;
 mod tags;
+mod task_dependencies;
 pub mod tasks;
+mod team_cycles;
 mod teams;
 mod tenant_workspaces;
 mod tokens;
@@ -87,34 +94,73 @@ pub fn router(state: AppState) -> Router {
         .merge(tenant_workspaces::public_router())
         .merge(oauth_settings::public_router());
 
-    let v1_protected = Router::<AppState>::new()
-        .merge(identity::router())
+    // Protected routes grouped by the API key scope each group requires
+    let task_routes = Router::<AppState>::new()
         .merge(inbox::router())
         .merge(pulse::router())
         .merge(subscriptions::router())
-        .merge(ai_keys::router())
-        .merge(api_keys::router())
-        .merge(projects::router())
         .merge(tasks::router())
+        .merge(task_dependencies::router())
         .merge(tags::router())
+        .merge(search::router())
+        .merge(electric_proxy::router());
+
+    let project_routes = Router::<AppState>::new()
+        .merge(projects::router())
+        .merge(repos::router());
+
+    let team_routes = Router::<AppState>::new()
         .merge(teams::router())
+        .merge(team_cycles::router())
+        .merge(organizations::router())
+        .merge(organization_members::protected_router())
+        .merge(tenant_workspaces::protected_router());
+
+    let admin_routes = Router::<AppState>::new()
+        .merge(ai_keys::router())
+        .merge(api_keys::router())
         .merge(github_settings::router())
         .merge(gitlab_settings::router())
         .merge(oauth_settings::protected_router())
-        .merge(documents::router())
         .merge(admin::router())
-        .merge(organizations::router())
-        .merge(repos::router())
-        .merge(organization_members::protected_router())
         .merge(oauth::protected_router())
-        .merge(electric_proxy::router())
         .merge(github_app::protected_router())
         .merge(stripe::protected_router())
         .merge(billing::protected_router())
         .merge(trust_profiles::protected_router())
         .merge(abuse_signals::protected_router())
-        .merge(email_verification::protected_router())
-        .merge(tenant_workspaces::protected_router())
+        .merge(email_verification::protected_router());
+
+    let v1_protected = Router::<AppState>::new()
+        .merge(scoped(
+            task_routes,
+            ApiScope::TasksRead,
+            ApiScope::TasksWrite,
+        ))
+        .merge(scoped(
+            documents::router(),
+            ApiScope::DocumentsRead,
+            ApiScope::DocumentsWrite,
+        ))
+        .merge(scoped(
+            project_routes,
+            ApiScope::ProjectsRead,
+            ApiScope::ProjectsWrite,
+        ))
+        .merge(scoped(
+            team_routes,
+            ApiScope::TeamsRead,
+            ApiScope::TeamsWrite,
+        ))
+        .merge(scoped(
+            executions::router(),
+            ApiScope::ExecutionsRead,
+            ApiScope::ExecutionsRun,
+        ))
+        .merge(scoped(admin_routes, ApiScope::Admin, ApiScope::Admin))
+        // Available to every authenticated caller
+        .merge(identity::router())
+        .merge(api_keys::current_router())
         .merge(superadmins::public_router()) // Check endpoint - any authed user
         .merge(registrations::user_router()) // User's own registration status
         .merge(stubs::router()) // Stub endpoints for local-only features
@@ -127,6 +173,7 @@ pub fn router(state: AppState) -> Router {
     let v1_superadmin = Router::<AppState>::new()
         .merge(superadmins::protected_router())
         .merge(registrations::router())
+        .merge(email_templates::router())
         .layer(middleware::from_fn_with_state(
             state.clone(),
             require_superadmin,
@@ -169,6 +216,15 @@ pub fn router(state: AppState) -> Router {
         .with_state(state)
 }
 
+/// Require `read` for safe methods and `write` for the rest when a request is
+/// authenticated with an API key
+fn scoped(routes: Router<AppState>, read: ApiScope, write: ApiScope) -> Router<AppState> {
+    routes.route_layer(middleware::from_fn_with_state(
+        ScopeRule::new(read, write),
+        scope_middleware::<ErrorResponse>,
+    ))
+}
+
 async fn health() -> &'static str {
     "ok"
 }
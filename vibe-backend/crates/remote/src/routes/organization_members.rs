@@ -589,6 +589,39 @@ pub(crate) async fn ensure_task_access(
         }
     };
 
+    authorize_task_membership(pool, organization_id, user_id, task_id).await?;
+    Ok(organization_id)
+}
+
+/// Access check that only looks at the `tasks` table, for rows that reference
+/// `tasks(id)` (e.g. dependencies), so the task that is authorized is the
+/// one that gets linked
+pub(crate) async fn ensure_team_task_access(
+    pool: &PgPool,
+    user_id: Uuid,
+    task_id: Uuid,
+) -> Result<Uuid, ErrorResponse> {
+    let organization_id = SharedTaskRepository::organization_id_from_tasks_table(pool, task_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, %task_id, "failed to load task from tasks table");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+        })?
+        .ok_or_else(|| {
+            warn!(%task_id, %user_id, "task not found in tasks table");
+            ErrorResponse::new(StatusCode::NOT_FOUND, "task not found")
+        })?;
+
+    authorize_task_membership(pool, organization_id, user_id, task_id).await?;
+    Ok(organization_id)
+}
+
+async fn authorize_task_membership(
+    pool: &PgPool,
+    organization_id: Uuid,
+    user_id: Uuid,
+    task_id: Uuid,
+) -> Result<(), ErrorResponse> {
     organization_members::assert_membership(pool, organization_id, user_id)
         .await
         .map_err(|err| {
@@ -610,6 +643,5 @@ pub(crate) async fn ensure_task_access(
             }
             membership_error(err, "task not accessible")
         })?;
-
-    Ok(organization_id)
+    Ok(())
 }
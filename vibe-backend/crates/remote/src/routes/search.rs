@@ -0,0 +1,211 @@
+//! Full-text search across tasks, comments, documents and project updates
+
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, State},
+    http::StatusCode,
+    routing::get,
+};
+use db_crate::models::search::{Search, SearchError, SearchQuery, SearchResult};
+use tracing::instrument;
+
+use super::error::{ApiResponse, ErrorResponse};
+use crate::{AppState, auth::RequestContext, db::organization_members::accessible_workspace_ids};
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/search", get(search))
+}
+
+#[instrument(
+    name = "search.search",
+    skip(state, ctx, query),
+    fields(user_id = %ctx.user.id)
+)]
+async fn search(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Query(query): Query<SearchQuery>,
+) -> Result<Json<ApiResponse<Vec<SearchResult>>>, ErrorResponse> {
+    let pool = state.pool();
+
+    let workspace_ids = accessible_workspace_ids(pool, ctx.user.id)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, "failed to load accessible workspaces");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+        })?;
+
+    let results = Search::search_as_member(pool, &query, workspace_ids)
+        .await
+        .map_err(search_error)?;
+
+    Ok(ApiResponse::success(results))
+}
+
+fn search_error(error: SearchError) -> ErrorResponse {
+    match error {
+        SearchError::UnknownType(_) => {
+            ErrorResponse::new(StatusCode::BAD_REQUEST, error.to_string())
+        }
+        SearchError::NotAMember => ErrorResponse::new(StatusCode::FORBIDDEN, error.to_string()),
+        SearchError::Database(error) => {
+            tracing::error!(?error, "search query failed");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "search failed")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use db_crate::models::{search::SearchEntityType, task::TaskStatus};
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    use super::*;
+
+    const WORKSPACE_A: Uuid = Uuid::from_u128(0x0a);
+    const WORKSPACE_B: Uuid = Uuid::from_u128(0x0b);
+    const FLAKY_DEPLOY_TASK: Uuid = Uuid::from_u128(0x301);
+    const ONBOARDING_TASK: Uuid = Uuid::from_u128(0x302);
+    const FLAKY_DEPLOY_COMMENT: Uuid = Uuid::from_u128(0x401);
+    const RUNBOOK_DOCUMENT: Uuid = Uuid::from_u128(0x501);
+    const FREEZE_UPDATE: Uuid = Uuid::from_u128(0x601);
+    const RELEASE_TAG: Uuid = Uuid::from_u128(0x801);
+
+    fn query(q: &str) -> SearchQuery {
+        SearchQuery {
+            q: q.to_string(),
+            workspace_id: None,
+            team_id: None,
+            project_id: None,
+            status: None,
+            assignee_id: None,
+            tag_id: None,
+            types: None,
+            limit: None,
+            offset: None,
+        }
+    }
+
+    async fn hit_ids(pool: &PgPool, query: &SearchQuery, members: &[Uuid]) -> Vec<Uuid> {
+        Search::search_as_member(pool, query, members.to_vec())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|hit| hit.id)
+            .collect()
+    }
+
+    #[ignore = "requires DATABASE_URL"]
+    #[sqlx::test(
+        migrations = false,
+        fixtures(
+            "../../tests/fixtures/search_seed.sql",
+            "../../migrations/20260204090000_create_search_index.sql"
+        )
+    )]
+    async fn search_covers_every_entity_type_in_member_workspaces(pool: PgPool) {
+        let hits = Search::search_as_member(&pool, &query("deploy"), vec![WORKSPACE_A])
+            .await
+            .unwrap();
+
+        let mut ids: Vec<Uuid> = hits.iter().map(|hit| hit.id).collect();
+        ids.sort();
+        // Archived documents and other workspaces are left out
+        assert_eq!(
+            ids,
+            [
+                FLAKY_DEPLOY_TASK,
+                ONBOARDING_TASK,
+                FLAKY_DEPLOY_COMMENT,
+                RUNBOOK_DOCUMENT,
+                FREEZE_UPDATE
+            ]
+        );
+
+        let comment = hits
+            .iter()
+            .find(|hit| hit.id == FLAKY_DEPLOY_COMMENT)
+            .unwrap();
+        assert_eq!(comment.entity_type, SearchEntityType::Comment);
+        assert_eq!(comment.task_id, Some(FLAKY_DEPLOY_TASK));
+        assert_eq!(comment.issue_key.as_deref(), Some("IKA-38"));
+        assert_eq!(comment.status, Some(TaskStatus::InProgress));
+        assert!(comment.snippet.contains("<mark>Deploy</mark>"));
+    }
+
+    #[ignore = "requires DATABASE_URL"]
+    #[sqlx::test(
+        migrations = false,
+        fixtures(
+            "../../tests/fixtures/search_seed.sql",
+            "../../migrations/20260204090000_create_search_index.sql"
+        )
+    )]
+    async fn title_matches_rank_ahead_of_body_matches(pool: PgPool) {
+        let mut tasks = query("deploy");
+        tasks.types = Some("task".to_string());
+
+        assert_eq!(
+            hit_ids(&pool, &tasks, &[WORKSPACE_A]).await,
+            [FLAKY_DEPLOY_TASK, ONBOARDING_TASK]
+        );
+    }
+
+    #[ignore = "requires DATABASE_URL"]
+    #[sqlx::test(
+        migrations = false,
+        fixtures(
+            "../../tests/fixtures/search_seed.sql",
+            "../../migrations/20260204090000_create_search_index.sql"
+        )
+    )]
+    async fn task_filters_leave_only_tasks_and_their_comments(pool: PgPool) {
+        let mut by_status = query("deploy");
+        by_status.status = Some(TaskStatus::InProgress);
+        let mut by_tag = query("deploy");
+        by_tag.tag_id = Some(RELEASE_TAG);
+
+        for filtered in [by_status, by_tag] {
+            assert_eq!(
+                hit_ids(&pool, &filtered, &[WORKSPACE_A]).await,
+                [FLAKY_DEPLOY_TASK, FLAKY_DEPLOY_COMMENT]
+            );
+        }
+    }
+
+    #[ignore = "requires DATABASE_URL"]
+    #[sqlx::test(
+        migrations = false,
+        fixtures(
+            "../../tests/fixtures/search_seed.sql",
+            "../../migrations/20260204090000_create_search_index.sql"
+        )
+    )]
+    async fn search_never_leaves_the_callers_workspaces(pool: PgPool) {
+        // Rows of a project without a tenant workspace are invisible to
+        // everyone, even a member of every workspace
+        let everything = hit_ids(&pool, &query("deploy"), &[WORKSPACE_A, WORKSPACE_B]).await;
+        assert_eq!(everything.len(), 8);
+        assert!(
+            !everything
+                .iter()
+                .any(|id| [Uuid::from_u128(0x304), Uuid::from_u128(0x602)].contains(id))
+        );
+
+        let mut only_b = query("deploy");
+        only_b.workspace_id = Some(WORKSPACE_B);
+        assert!(matches!(
+            Search::search_as_member(&pool, &only_b, vec![WORKSPACE_A]).await,
+            Err(SearchError::NotAMember)
+        ));
+        assert_eq!(
+            hit_ids(&pool, &only_b, &[WORKSPACE_A, WORKSPACE_B])
+                .await
+                .len(),
+            3
+        );
+
+        assert!(hit_ids(&pool, &query("deploy"), &[]).await.is_empty());
+    }
+}
@@ -200,6 +200,40 @@ struct ShowcaseState {
     seen_features: Vec<String>,
 }
 
+/// Execution concurrency caps
+#[derive(Debug, Serialize, Deserialize)]
+struct ExecutionConcurrencyConfig {
+    max_running: Option<u32>,
+    max_running_per_project: Option<u32>,
+    max_running_per_executor: HashMap<String, u32>,
+}
+
+impl Default for ExecutionConcurrencyConfig {
+    fn default() -> Self {
+        Self {
+            max_running: Some(4),
+            max_running_per_project: None,
+            max_running_per_executor: HashMap::new(),
+        }
+    }
+}
+
+/// CI repair settings
+#[derive(Debug, Serialize, Deserialize)]
+struct CiRepairConfig {
+    enabled: bool,
+    max_attempts_per_pr: u32,
+}
+
+impl Default for CiRepairConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_attempts_per_pr: 3,
+        }
+    }
+}
+
 /// User configuration - matches frontend Config type
 #[derive(Debug, Serialize, Deserialize)]
 struct Config {
@@ -220,6 +254,10 @@ struct Config {
     showcases: ShowcaseState,
     pr_auto_description_enabled: bool,
     pr_auto_description_prompt: Option<String>,
+    transcript_retention_days: Option<u32>,
+    execution_concurrency: ExecutionConcurrencyConfig,
+    pr_review_auto_address: bool,
+    ci_repair: CiRepairConfig,
 }
 
 impl Default for Config {
@@ -242,6 +280,10 @@ impl Default for Config {
             showcases: ShowcaseState::default(),
             pr_auto_description_enabled: false,
             pr_auto_description_prompt: None,
+            transcript_retention_days: Some(90),
+            execution_concurrency: ExecutionConcurrencyConfig::default(),
+            pr_review_auto_address: false,
+            ci_repair: CiRepairConfig::default(),
         }
     }
 }
@@ -0,0 +1,121 @@
+//! Task dependency routes ("blocked by" relations between tasks)
+
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{delete, get},
+};
+use db_crate::models::task_dependency::{
+    AddDependencyRequest, TaskDependencies, TaskDependency, TaskDependencyError,
+};
+use tracing::{Span, instrument};
+use uuid::Uuid;
+
+use super::{
+    error::{ApiResponse, ErrorResponse},
+    organization_members::ensure_team_task_access,
+};
+use crate::{AppState, auth::RequestContext};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/tasks/{task_id}/dependencies",
+            get(list_dependencies).post(add_dependency),
+        )
+        .route(
+            "/tasks/{task_id}/dependencies/{depends_on_task_id}",
+            delete(remove_dependency),
+        )
+}
+
+fn dependency_error(error: TaskDependencyError) -> ErrorResponse {
+    let status = match &error {
+        TaskDependencyError::SelfDependency => StatusCode::BAD_REQUEST,
+        TaskDependencyError::Cycle | TaskDependencyError::AlreadyExists => StatusCode::CONFLICT,
+        TaskDependencyError::TaskNotFound | TaskDependencyError::NotFound => StatusCode::NOT_FOUND,
+        TaskDependencyError::Database(err) => {
+            tracing::error!(?err, "task dependency query failed");
+            return ErrorResponse::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "failed to update task dependencies",
+            );
+        }
+    };
+    ErrorResponse::new(status, error.to_string())
+}
+
+#[instrument(
+    name = "tasks.list_dependencies",
+    skip(state, ctx),
+    fields(user_id = %ctx.user.id, task_id = %task_id, org_id = tracing::field::Empty)
+)]
+async fn list_dependencies(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(task_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<TaskDependencies>>, ErrorResponse> {
+    let pool = state.pool();
+    let org_id = ensure_team_task_access(pool, ctx.user.id, task_id).await?;
+    Span::current().record("org_id", format_args!("{org_id}"));
+
+    let dependencies = TaskDependency::find_by_task_id(pool, task_id)
+        .await
+        .map_err(|err| dependency_error(err.into()))?;
+    Ok(ApiResponse::success(dependencies))
+}
+
+#[instrument(
+    name = "tasks.add_dependency",
+    skip(state, ctx, payload),
+    fields(user_id = %ctx.user.id, task_id = %task_id, org_id = tracing::field::Empty)
+)]
+async fn add_dependency(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(task_id): Path<Uuid>,
+    Json(payload): Json<AddDependencyRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<TaskDependency>>), ErrorResponse> {
+    let pool = state.pool();
+    let org_id = ensure_team_task_access(pool, ctx.user.id, task_id).await?;
+    Span::current().record("org_id", format_args!("{org_id}"));
+
+    let blocker_org_id =
+        ensure_team_task_access(pool, ctx.user.id, payload.depends_on_task_id).await?;
+    if blocker_org_id != org_id {
+        return Err(ErrorResponse::new(
+            StatusCode::BAD_REQUEST,
+            "dependencies must be between tasks in the same organization",
+        ));
+    }
+
+    let dependency =
+        TaskDependency::create(pool, task_id, payload.depends_on_task_id, Some(ctx.user.id))
+            .await
+            .map_err(dependency_error)?;
+    Ok((StatusCode::CREATED, ApiResponse::success(dependency)))
+}
+
+#[instrument(
+    name = "tasks.remove_dependency",
+    skip(state, ctx),
+    fields(user_id = %ctx.user.id, task_id = %task_id, org_id = tracing::field::Empty)
+)]
+async fn remove_dependency(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path((task_id, depends_on_task_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, ErrorResponse> {
+    let pool = state.pool();
+    let org_id = ensure_team_task_access(pool, ctx.user.id, task_id).await?;
+    Span::current().record("org_id", format_args!("{org_id}"));
+
+    let removed = TaskDependency::delete(pool, task_id, depends_on_task_id)
+        .await
+        .map_err(|err| dependency_error(err.into()))?;
+    if removed == 0 {
+        return Err(dependency_error(TaskDependencyError::NotFound));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -5,6 +5,7 @@ use axum::{
     response::{IntoResponse, Response},
     routing::{delete, get, patch, post},
 };
+use db_crate::models::task_dependency::{DependencyTask, TaskDependency};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use tracing::{Span, instrument};
@@ -43,7 +44,7 @@ pub fn router() -> Router<AppState> {
             patch(update_shared_task).put(update_shared_task),
         )
         .route("/tasks/{task_id}", delete(delete_shared_task))
-        // Combined endpoint for issue detail panel - returns comments, links, tags in one call
+        // Combined endpoint for issue detail panel - returns comments, links, tags, dependencies in one call
         .route("/tasks/{task_id}/details", get(get_task_details))
         .route("/tasks/{task_id}/assign", post(assign_task))
         .route(
@@ -623,7 +624,7 @@ impl From<SharedTaskWithUser> for SharedTaskResponse {
 
 // ─────────────────────────────────────────────────────────────────────────────
 // Combined Task Details Endpoint (Performance Optimization)
-// Returns comments + links + tags in a single request with one access check
+// Returns comments + links + tags + dependencies in a single request with one access check
 // ─────────────────────────────────────────────────────────────────────────────
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -631,6 +632,10 @@ pub struct TaskDetailsResponse {
     pub comments: Vec<TaskComment>,
     pub links: Vec<LinkedDocument>,
     pub tags: Vec<TaskTagWithDetails>,
+    /// Tasks that must be done before this one
+    pub blockers: Vec<DependencyTask>,
+    /// Tasks waiting on this one
+    pub blocked_tasks: Vec<DependencyTask>,
 }
 
 #[instrument(
@@ -654,11 +659,12 @@ pub async fn get_task_details(
         Err(error) => return error.into_response(),
     };
 
-    // Fetch comments, links, and tags in parallel
-    let (comments_result, links_result, tags_result) = tokio::join!(
+    // Fetch comments, links, tags, and dependencies in parallel
+    let (comments_result, links_result, tags_result, dependencies_result) = tokio::join!(
         TaskCommentRepository::find_by_task_id(pool, task_id),
         TaskDocumentLinkRepository::find_by_task_id(pool, task_id),
         TaskTagRepository::find_by_task_id(pool, task_id),
+        TaskDependency::find_by_task_id(pool, task_id),
     );
 
     // Handle errors
@@ -698,12 +704,26 @@ pub async fn get_task_details(
         }
     };
 
+    let dependencies = match dependencies_result {
+        Ok(d) => d,
+        Err(e) => {
+            tracing::error!(?e, "failed to load task dependencies in details");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"success": false, "message": "failed to load task details"})),
+            )
+                .into_response();
+        }
+    };
+
     (
         StatusCode::OK,
         ApiResponse::success(TaskDetailsResponse {
             comments,
             links,
             tags,
+            blockers: dependencies.blockers,
+            blocked_tasks: dependencies.blocked_tasks,
         }),
     )
         .into_response()
@@ -0,0 +1,254 @@
+//! Team cycles (sprints) routes
+//!
+//! Cycles that pass their end date are completed by the `cycle_rollover`
+//! scheduled job; `/complete` does the same on demand.
+
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{delete, get, post},
+};
+use serde::Deserialize;
+use sqlx::PgPool;
+use tracing::instrument;
+use uuid::Uuid;
+
+use super::{
+    error::{ApiResponse, ErrorResponse},
+    organization_members::ensure_member_access,
+};
+use crate::{
+    AppState,
+    auth::RequestContext,
+    db::{
+        team_cycles::{
+            CreateTeamCycle, CycleRollover, TeamCycle, TeamCycleError, TeamCycleRepository,
+            TeamCycleWithStats, UpdateTeamCycle,
+        },
+        teams::{Team, TeamRepository},
+    },
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/teams/{team_id}/cycles",
+            get(list_cycles).post(create_cycle),
+        )
+        .route(
+            "/teams/{team_id}/cycles/{cycle_id}",
+            get(get_cycle).patch(update_cycle).delete(delete_cycle),
+        )
+        .route(
+            "/teams/{team_id}/cycles/{cycle_id}/complete",
+            post(complete_cycle),
+        )
+        .route("/teams/{team_id}/cycles/{cycle_id}/issues", post(add_issue))
+        .route(
+            "/teams/{team_id}/cycles/{cycle_id}/issues/{issue_id}",
+            delete(remove_issue),
+        )
+}
+
+/// Load a team by ID or slug and check the caller can access its workspace
+async fn load_team(pool: &PgPool, user_id: Uuid, team_id: &str) -> Result<Team, ErrorResponse> {
+    let team = TeamRepository::get_by_id_or_slug(pool, team_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, %team_id, "failed to get team");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "failed to get team")
+        })?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "team not found"))?;
+
+    if let Some(workspace_id) =
+        TeamRepository::workspace_id(pool, team.id)
+            .await
+            .map_err(|error| {
+                tracing::error!(?error, %team_id, "failed to get team workspace");
+                ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "failed to get team")
+            })?
+    {
+        ensure_member_access(pool, workspace_id, user_id).await?;
+    }
+
+    Ok(team)
+}
+
+pub(super) fn cycle_error(error: TeamCycleError) -> ErrorResponse {
+    let status = match &error {
+        TeamCycleError::NotFound | TeamCycleError::IssueNotFound => StatusCode::NOT_FOUND,
+        TeamCycleError::InvalidDates => StatusCode::BAD_REQUEST,
+        TeamCycleError::Overlap | TeamCycleError::Completed => StatusCode::CONFLICT,
+        TeamCycleError::Database(err) => {
+            tracing::error!(?err, "team cycle query failed");
+            return ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "failed to load cycles");
+        }
+    };
+    ErrorResponse::new(status, error.to_string())
+}
+
+#[derive(Debug, Deserialize)]
+struct AddCycleIssueRequest {
+    issue_id: Uuid,
+}
+
+#[instrument(
+    name = "team_cycles.list",
+    skip(state, ctx),
+    fields(user_id = %ctx.user.id, team_id = %team_id)
+)]
+async fn list_cycles(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(team_id): Path<String>,
+) -> Result<Json<ApiResponse<Vec<TeamCycleWithStats>>>, ErrorResponse> {
+    let pool = state.pool();
+    let team = load_team(pool, ctx.user.id, &team_id).await?;
+
+    let cycles = TeamCycleRepository::list_with_stats(pool, team.id)
+        .await
+        .map_err(cycle_error)?;
+    Ok(ApiResponse::success(cycles))
+}
+
+#[instrument(
+    name = "team_cycles.create",
+    skip(state, ctx, payload),
+    fields(user_id = %ctx.user.id, team_id = %team_id)
+)]
+async fn create_cycle(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(team_id): Path<String>,
+    Json(payload): Json<CreateTeamCycle>,
+) -> Result<(StatusCode, Json<ApiResponse<TeamCycle>>), ErrorResponse> {
+    let pool = state.pool();
+    let team = load_team(pool, ctx.user.id, &team_id).await?;
+
+    let cycle = TeamCycleRepository::create(pool, team.id, &payload)
+        .await
+        .map_err(cycle_error)?;
+    Ok((StatusCode::CREATED, ApiResponse::success(cycle)))
+}
+
+#[instrument(
+    name = "team_cycles.get",
+    skip(state, ctx),
+    fields(user_id = %ctx.user.id, team_id = %team_id, cycle_id = %cycle_id)
+)]
+async fn get_cycle(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path((team_id, cycle_id)): Path<(String, Uuid)>,
+) -> Result<Json<ApiResponse<TeamCycleWithStats>>, ErrorResponse> {
+    let pool = state.pool();
+    let team = load_team(pool, ctx.user.id, &team_id).await?;
+
+    let cycle = TeamCycleRepository::find_with_stats(pool, team.id, cycle_id)
+        .await
+        .map_err(cycle_error)?;
+    Ok(ApiResponse::success(cycle))
+}
+
+#[instrument(
+    name = "team_cycles.update",
+    skip(state, ctx, payload),
+    fields(user_id = %ctx.user.id, team_id = %team_id, cycle_id = %cycle_id)
+)]
+async fn update_cycle(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path((team_id, cycle_id)): Path<(String, Uuid)>,
+    Json(payload): Json<UpdateTeamCycle>,
+) -> Result<Json<ApiResponse<TeamCycle>>, ErrorResponse> {
+    let pool = state.pool();
+    let team = load_team(pool, ctx.user.id, &team_id).await?;
+
+    let cycle = TeamCycleRepository::update(pool, team.id, cycle_id, &payload)
+        .await
+        .map_err(cycle_error)?;
+    Ok(ApiResponse::success(cycle))
+}
+
+#[instrument(
+    name = "team_cycles.delete",
+    skip(state, ctx),
+    fields(user_id = %ctx.user.id, team_id = %team_id, cycle_id = %cycle_id)
+)]
+async fn delete_cycle(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path((team_id, cycle_id)): Path<(String, Uuid)>,
+) -> Result<StatusCode, ErrorResponse> {
+    let pool = state.pool();
+    let team = load_team(pool, ctx.user.id, &team_id).await?;
+
+    TeamCycleRepository::delete(pool, team.id, cycle_id)
+        .await
+        .map_err(cycle_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// End a cycle early and roll its unfinished issues into the next cycle
+#[instrument(
+    name = "team_cycles.complete",
+    skip(state, ctx),
+    fields(user_id = %ctx.user.id, team_id = %team_id, cycle_id = %cycle_id)
+)]
+async fn complete_cycle(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path((team_id, cycle_id)): Path<(String, Uuid)>,
+) -> Result<Json<ApiResponse<CycleRollover>>, ErrorResponse> {
+    let pool = state.pool();
+    let team = load_team(pool, ctx.user.id, &team_id).await?;
+
+    let rollover = TeamCycleRepository::complete(pool, team.id, cycle_id)
+        .await
+        .map_err(cycle_error)?;
+    Ok(ApiResponse::success(rollover))
+}
+
+#[instrument(
+    name = "team_cycles.add_issue",
+    skip(state, ctx, payload),
+    fields(user_id = %ctx.user.id, team_id = %team_id, cycle_id = %cycle_id)
+)]
+async fn add_issue(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path((team_id, cycle_id)): Path<(String, Uuid)>,
+    Json(payload): Json<AddCycleIssueRequest>,
+) -> Result<StatusCode, ErrorResponse> {
+    let pool = state.pool();
+    let team = load_team(pool, ctx.user.id, &team_id).await?;
+
+    TeamCycleRepository::add_issue(pool, team.id, cycle_id, payload.issue_id)
+        .await
+        .map_err(cycle_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[instrument(
+    name = "team_cycles.remove_issue",
+    skip(state, ctx),
+    fields(user_id = %ctx.user.id, team_id = %team_id, cycle_id = %cycle_id)
+)]
+async fn remove_issue(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path((team_id, cycle_id, issue_id)): Path<(String, Uuid, Uuid)>,
+) -> Result<StatusCode, ErrorResponse> {
+    let pool = state.pool();
+    let team = load_team(pool, ctx.user.id, &team_id).await?;
+
+    // Scope the membership change to this team's cycle
+    TeamCycleRepository::find_with_stats(pool, team.id, cycle_id)
+        .await
+        .map_err(cycle_error)?;
+    TeamCycleRepository::remove_issue(pool, cycle_id, issue_id)
+        .await
+        .map_err(cycle_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -3,7 +3,7 @@
 use axum::{
     Extension, Json, Router,
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     routing::{get, patch},
 };
 use chrono::{DateTime, Utc};
@@ -12,8 +12,10 @@ use tracing::instrument;
 use uuid::Uuid;
 
 use super::{
+    documents::revision_author,
     error::{ApiResponse, ErrorResponse},
     organization_members::ensure_member_access,
+    team_cycles::cycle_error,
 };
 use crate::{
     AppState,
@@ -25,6 +27,7 @@ use crate::{
         documents::{CreateDocument, Document, DocumentRepository, UpdateDocument},
         notifications,
         projects::{Project, ProjectRepository},
+        team_cycles::{TeamCycleRepository, TeamCycleWithStats},
         teams::{
             CreateTeamIssue, Team, TeamDocument, TeamFolder, TeamInvitation, TeamIssue, TeamMember,
             TeamRepository, UpdateTeamIssue,
@@ -42,6 +45,8 @@ pub struct ListTeamsQuery {
 pub struct GetTeamIssuesQuery {
     /// Comma-separated list of tag UUIDs to filter by (AND logic)
     pub tags: Option<String>,
+    /// Cycle UUID, or "current" for the team's active cycle
+    pub cycle: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -108,6 +113,10 @@ pub struct TeamDashboard {
     pub project_ids: Vec<Uuid>,
     pub projects: Vec<Project>,
     pub issues: Vec<TeamIssue>,
+    /// The cycle in progress, if any
+    pub current_cycle: Option<TeamCycleWithStats>,
+    /// The most recently completed cycle, for carry-over and velocity
+    pub previous_cycle: Option<TeamCycleWithStats>,
 }
 
 pub fn router() -> Router<AppState> {
@@ -283,10 +292,11 @@ async fn get_team_dashboard(
     }
 
     // IKA-303: Parallelize queries using tokio::join! for better performance
-    let (members_result, project_ids_result, issues_result) = tokio::join!(
+    let (members_result, project_ids_result, issues_result, cycles_result) = tokio::join!(
         TeamRepository::get_members(pool, team_uuid),
         TeamRepository::get_project_ids(pool, team_uuid),
-        TeamRepository::get_issues(pool, team_uuid, None)
+        TeamRepository::get_issues(pool, team_uuid, None),
+        TeamCycleRepository::list_with_stats(pool, team_uuid)
     );
 
     let members = members_result.map_err(|error| {
@@ -313,6 +323,14 @@ async fn get_team_dashboard(
         )
     })?;
 
+    let cycles = cycles_result.map_err(cycle_error)?;
+    let current_cycle = cycles.iter().find(|c| c.is_active()).cloned();
+    let previous_cycle = cycles
+        .iter()
+        .filter(|c| c.is_completed())
+        .max_by_key(|c| c.cycle.ends_at)
+        .cloned();
+
     // IKA-303: Fetch projects by IDs
     // Note: Using N+1 loop for now; batch fetch with ANY($1) has runtime type issues
     let mut projects = Vec::new();
@@ -328,6 +346,8 @@ async fn get_team_dashboard(
         project_ids,
         projects,
         issues,
+        current_cycle,
+        previous_cycle,
     }))
 }
 
@@ -506,7 +526,7 @@ async fn get_team_issues(
         }
     });
 
-    let issues = TeamRepository::get_issues(pool, team.id, tag_ids.as_deref())
+    let mut issues = TeamRepository::get_issues(pool, team.id, tag_ids.as_deref())
         .await
         .map_err(|error| {
             tracing::error!(?error, %team_id, "failed to get team issues");
@@ -516,6 +536,28 @@ async fn get_team_issues(
             )
         })?;
 
+    if let Some(cycle) = query.cycle.as_deref() {
+        let cycle_id = match cycle {
+            "current" => TeamCycleRepository::list_with_stats(pool, team.id)
+                .await
+                .map_err(cycle_error)?
+                .into_iter()
+                .find(|c| c.is_active())
+                .map(|c| c.cycle.id),
+            id => Some(
+                id.parse::<Uuid>()
+                    .map_err(|_| ErrorResponse::new(StatusCode::BAD_REQUEST, "invalid cycle"))?,
+            ),
+        };
+        let issue_ids = match cycle_id {
+            Some(cycle_id) => TeamCycleRepository::issue_ids(pool, cycle_id)
+                .await
+                .map_err(cycle_error)?,
+            None => Vec::new(),
+        };
+        issues.retain(|issue| issue_ids.contains(&issue.id));
+    }
+
     Ok(ApiResponse::success(issues))
 }
 
@@ -1450,6 +1492,7 @@ async fn create_team_document(
     State(state): State<AppState>,
     Extension(ctx): Extension<RequestContext>,
     Path(team_id): Path<String>,
+    headers: HeaderMap,
     Json(payload): Json<CreateDocument>,
 ) -> Result<Json<ApiResponse<Document>>, ErrorResponse> {
     let pool = state.pool();
@@ -1473,7 +1516,8 @@ async fn create_team_document(
         ensure_member_access(pool, workspace_id, ctx.user.id).await?;
     }
 
-    let document = DocumentRepository::create(pool, team.id, &payload)
+    let author = revision_author(&state, &ctx, &headers).await?;
+    let document = DocumentRepository::create(pool, team.id, &payload, &author)
         .await
         .map_err(|error| {
             tracing::error!(?error, %team_id, "failed to create document");
@@ -1547,6 +1591,7 @@ async fn update_team_document(
     State(state): State<AppState>,
     Extension(ctx): Extension<RequestContext>,
     Path((team_id, document_id)): Path<(String, Uuid)>,
+    headers: HeaderMap,
     Json(payload): Json<UpdateDocument>,
 ) -> Result<Json<ApiResponse<Document>>, ErrorResponse> {
     let pool = state.pool();
@@ -1588,7 +1633,8 @@ async fn update_team_document(
         ));
     }
 
-    let document = DocumentRepository::update(pool, document_id, &payload)
+    let author = revision_author(&state, &ctx, &headers).await?;
+    let document = DocumentRepository::update(pool, document_id, &payload, &author)
         .await
         .map_err(|error| {
             tracing::error!(?error, %document_id, "failed to update document");
@@ -9,7 +9,7 @@ use crate::{
     cache::AppCache,
     config::RemoteServerConfig,
     github_app::GitHubAppService,
-    mail::Mailer,
+    mail::{Mailer, templates::EmailTemplates},
     r2::R2Service,
     stripe::StripeService,
 };
@@ -20,6 +20,7 @@ pub struct AppState {
     pub config: RemoteServerConfig,
     pub jwt: Arc<JwtService>,
     pub mailer: Arc<dyn Mailer>,
+    email_templates: Arc<EmailTemplates>,
     pub server_public_base_url: String,
     pub http_client: reqwest::Client,
     handoff: Arc<OAuthHandoffService>,
@@ -40,6 +41,7 @@ impl AppState {
         handoff: Arc<OAuthHandoffService>,
         oauth_token_validator: Arc<OAuthTokenValidator>,
         mailer: Arc<dyn Mailer>,
+        email_templates: Arc<EmailTemplates>,
         server_public_base_url: String,
         http_client: reqwest::Client,
         r2: Option<R2Service>,
@@ -52,6 +54,7 @@ impl AppState {
             config,
             jwt,
             mailer,
+            email_templates,
             server_public_base_url,
             http_client,
             handoff,
@@ -104,6 +107,10 @@ impl AppState {
         Arc::clone(&self.cache)
     }
 
+    pub fn email_templates(&self) -> Arc<EmailTemplates> {
+        Arc::clone(&self.email_templates)
+    }
+
     pub fn clerk_auth(&self) -> Arc<ClerkAuthState> {
         Arc::clone(&self.clerk_auth)
     }
@@ -0,0 +1,43 @@
+-- Minimal stand-ins for the team and document tables, which are not created
+-- by this crate's migrations, plus one team with one document
+CREATE TABLE teams (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL
+);
+
+CREATE TABLE documents (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    team_id UUID NOT NULL REFERENCES teams(id),
+    folder_id UUID,
+    title TEXT NOT NULL,
+    slug TEXT,
+    content TEXT,
+    file_path TEXT,
+    file_type TEXT NOT NULL DEFAULT 'markdown',
+    file_size BIGINT,
+    mime_type TEXT,
+    icon TEXT,
+    is_pinned BOOLEAN NOT NULL DEFAULT FALSE,
+    is_archived BOOLEAN NOT NULL DEFAULT FALSE,
+    position INTEGER NOT NULL DEFAULT 0,
+    created_by TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    storage_key TEXT,
+    storage_bucket TEXT,
+    storage_metadata JSONB,
+    storage_provider TEXT NOT NULL DEFAULT 'local'
+);
+
+INSERT INTO teams (id, name)
+VALUES ('00000000-0000-0000-0000-0000000000a1', 'Platform');
+
+INSERT INTO documents (id, team_id, title, slug, content, created_by)
+VALUES (
+    '00000000-0000-0000-0000-0000000000d1',
+    '00000000-0000-0000-0000-0000000000a1',
+    'Runbook',
+    'runbook',
+    '# Runbook',
+    'user-1'
+);
@@ -0,0 +1,18 @@
+-- One organization, user, project and shared task for execution tests
+INSERT INTO organizations (id, name, slug)
+VALUES ('00000000-0000-0000-0000-000000000001', 'Acme', 'acme');
+
+INSERT INTO users (id, email)
+VALUES ('00000000-0000-0000-0000-000000000002', 'dev@example.com');
+
+INSERT INTO projects (id, organization_id, name)
+VALUES ('00000000-0000-0000-0000-000000000003', '00000000-0000-0000-0000-000000000001', 'Backend');
+
+INSERT INTO shared_tasks (id, organization_id, project_id, title, description)
+VALUES (
+    '00000000-0000-0000-0000-000000000004',
+    '00000000-0000-0000-0000-000000000001',
+    '00000000-0000-0000-0000-000000000003',
+    'Add upload retries',
+    'Retry failed uploads with backoff'
+);
@@ -0,0 +1,107 @@
+-- Minimal stand-ins for the tables the search query reads, which are not
+-- created by this crate's migrations, plus rows in two tenant workspaces
+-- (…0a and …0b) and one project that belongs to no workspace.
+
+CREATE TABLE projects (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL,
+    tenant_workspace_id UUID
+);
+
+CREATE TABLE teams (
+    id UUID PRIMARY KEY,
+    identifier TEXT NOT NULL,
+    tenant_workspace_id UUID
+);
+
+CREATE TABLE team_projects (
+    team_id UUID NOT NULL REFERENCES teams(id),
+    project_id UUID NOT NULL REFERENCES projects(id)
+);
+
+CREATE TABLE tasks (
+    id UUID PRIMARY KEY,
+    project_id UUID NOT NULL REFERENCES projects(id),
+    team_id UUID REFERENCES teams(id),
+    issue_number INT,
+    title TEXT NOT NULL,
+    description TEXT,
+    status TEXT NOT NULL DEFAULT 'todo',
+    assignee_id UUID,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE task_tags (
+    task_id UUID NOT NULL REFERENCES tasks(id),
+    tag_id UUID NOT NULL
+);
+
+CREATE TABLE task_comments (
+    id UUID PRIMARY KEY,
+    task_id UUID NOT NULL REFERENCES tasks(id),
+    content TEXT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE documents (
+    id UUID PRIMARY KEY,
+    team_id UUID NOT NULL REFERENCES teams(id),
+    title TEXT NOT NULL,
+    content TEXT,
+    is_archived BOOLEAN NOT NULL DEFAULT FALSE,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE project_updates (
+    id UUID PRIMARY KEY,
+    project_id UUID NOT NULL REFERENCES projects(id),
+    content TEXT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+INSERT INTO projects (id, name, tenant_workspace_id) VALUES
+    ('00000000-0000-0000-0000-000000000101', 'Platform', '00000000-0000-0000-0000-00000000000a'),
+    ('00000000-0000-0000-0000-000000000102', 'Other tenant', '00000000-0000-0000-0000-00000000000b'),
+    ('00000000-0000-0000-0000-000000000103', 'Unowned', NULL);
+
+INSERT INTO teams (id, identifier, tenant_workspace_id) VALUES
+    ('00000000-0000-0000-0000-000000000201', 'IKA', '00000000-0000-0000-0000-00000000000a'),
+    ('00000000-0000-0000-0000-000000000202', 'OTH', '00000000-0000-0000-0000-00000000000b');
+
+INSERT INTO team_projects (team_id, project_id) VALUES
+    ('00000000-0000-0000-0000-000000000201', '00000000-0000-0000-0000-000000000101');
+
+INSERT INTO tasks (id, project_id, team_id, issue_number, title, description, status, assignee_id) VALUES
+    ('00000000-0000-0000-0000-000000000301', '00000000-0000-0000-0000-000000000101',
+     '00000000-0000-0000-0000-000000000201', 38, 'Fix flaky deploy pipeline',
+     'The release job times out', 'inprogress', '00000000-0000-0000-0000-000000000901'),
+    ('00000000-0000-0000-0000-000000000302', '00000000-0000-0000-0000-000000000101',
+     '00000000-0000-0000-0000-000000000201', 39, 'Write onboarding guide',
+     'Mention how to deploy a preview', 'done', NULL),
+    ('00000000-0000-0000-0000-000000000303', '00000000-0000-0000-0000-000000000102',
+     '00000000-0000-0000-0000-000000000202', 1, 'Deploy dashboard', NULL, 'todo', NULL),
+    ('00000000-0000-0000-0000-000000000304', '00000000-0000-0000-0000-000000000103',
+     NULL, NULL, 'Legacy deploy script', NULL, 'todo', NULL);
+
+INSERT INTO task_tags (task_id, tag_id) VALUES
+    ('00000000-0000-0000-0000-000000000301', '00000000-0000-0000-0000-000000000801');
+
+INSERT INTO task_comments (id, task_id, content) VALUES
+    ('00000000-0000-0000-0000-000000000401', '00000000-0000-0000-0000-000000000301',
+     'Deploy passed after purging the cache'),
+    ('00000000-0000-0000-0000-000000000402', '00000000-0000-0000-0000-000000000303',
+     'Deploy is blocked on review');
+
+INSERT INTO documents (id, team_id, title, content, is_archived) VALUES
+    ('00000000-0000-0000-0000-000000000501', '00000000-0000-0000-0000-000000000201',
+     'Deploy runbook', 'Steps to roll back a release', FALSE),
+    ('00000000-0000-0000-0000-000000000502', '00000000-0000-0000-0000-000000000201',
+     'Old deploy notes', NULL, TRUE),
+    ('00000000-0000-0000-0000-000000000503', '00000000-0000-0000-0000-000000000202',
+     'Deploy checklist', NULL, FALSE);
+
+INSERT INTO project_updates (id, project_id, content) VALUES
+    ('00000000-0000-0000-0000-000000000601', '00000000-0000-0000-0000-000000000101',
+     'Deploy freeze until Friday'),
+    ('00000000-0000-0000-0000-000000000602', '00000000-0000-0000-0000-000000000103',
+     'Deploy of the unowned project');
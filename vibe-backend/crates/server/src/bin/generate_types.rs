@@ -21,6 +21,8 @@ fn generate_types_content() -> String {
         db::models::project::UpdateProject::decl(),
         db::models::project::SearchResult::decl(),
         db::models::project::SearchMatchType::decl(),
+        db::models::search::SearchQuery::decl(),
+        db::models::search::SearchEntityType::decl(),
         db::models::repo::Repo::decl(),
         db::models::project_repo::ProjectRepo::decl(),
         db::models::project_repo::CreateProjectRepo::decl(),
@@ -80,6 +82,23 @@ fn generate_types_content() -> String {
         db::models::document::CreateDocument::decl(),
         db::models::document::UpdateDocument::decl(),
         server::routes::documents::ListDocumentsQuery::decl(),
+        server::routes::documents::RevisionDiffQuery::decl(),
+        db::models::document_revision::RevisionSource::decl(),
+        db::models::document_revision::DocumentRevision::decl(),
+        db::models::document_revision::DocumentRevisionSummary::decl(),
+        db::models::document_revision::DocumentRevisionDiff::decl(),
+        db::models::task_template::TaskTemplate::decl(),
+        db::models::task_template::TemplateSubIssue::decl(),
+        db::models::task_template::CreateTaskTemplate::decl(),
+        db::models::task_template::UpdateTaskTemplate::decl(),
+        db::models::task_template::TaskTemplateRecurrence::decl(),
+        db::models::task_template::CreateTaskTemplateRecurrence::decl(),
+        db::models::task_template::UpdateTaskTemplateRecurrence::decl(),
+        db::models::attempt_group::AttemptGroup::decl(),
+        db::models::attempt_group::AttemptStatus::decl(),
+        db::models::attempt_group::AttemptGroupMember::decl(),
+        db::models::attempt_group::AttemptGroupWithMembers::decl(),
+        db::models::attempt_group::VerificationRun::decl(),
         db::models::inbox::InboxNotificationType::decl(),
         db::models::inbox::InboxItem::decl(),
         db::models::inbox::CreateInboxItem::decl(),
@@ -178,6 +197,29 @@ fn generate_types_content() -> String {
         server::routes::shared_tasks::AssignSharedTaskRequest::decl(),
         server::routes::tasks::ShareTaskResponse::decl(),
         server::routes::tasks::CreateAndStartTaskRequest::decl(),
+        server::routes::task_templates::InstantiateTaskTemplateRequest::decl(),
+        services::services::task_templates::InstantiatedTask::decl(),
+        server::routes::attempt_groups::CreateAttemptGroupRequest::decl(),
+        server::routes::attempt_groups::PickAttemptWinnerRequest::decl(),
+        db::models::token_usage::ModelUsage::decl(),
+        db::models::token_usage::UsageSummary::decl(),
+        services::services::attempt_comparison::AttemptFileStat::decl(),
+        services::services::attempt_comparison::AttemptComparison::decl(),
+        services::services::attempt_comparison::AttemptGroupComparison::decl(),
+        db::models::coding_agent_checkpoint::CodingAgentCheckpoint::decl(),
+        server::routes::sessions::checkpoints::RollbackToCheckpointRequest::decl(),
+        services::services::checkpoint::RepoCheckpoint::decl(),
+        services::services::checkpoint::TurnCheckpoint::decl(),
+        services::services::checkpoint::SessionCheckpoints::decl(),
+        services::services::transcript::export::TranscriptFormat::decl(),
+        db::models::queued_attempt::QueuedAttempt::decl(),
+        server::routes::execution_queue::MoveQueuedAttemptRequest::decl(),
+        db::models::merge_queue::MergeQueueEntry::decl(),
+        db::models::merge_queue::MergeQueueStatus::decl(),
+        db::models::merge_queue::MergeStrategy::decl(),
+        db::models::merge_queue::MergeQueueConfig::decl(),
+        db::models::merge_queue::UpsertMergeQueueConfig::decl(),
+        server::routes::merge_queue::EnqueueMergeRequest::decl(),
         server::routes::task_attempts::pr::CreateGitHubPrRequest::decl(),
         server::routes::images::ImageResponse::decl(),
         server::routes::images::ImageMetadata::decl(),
@@ -197,6 +239,16 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::pr::AttachExistingPrRequest::decl(),
         server::routes::task_attempts::pr::PrCommentsResponse::decl(),
         server::routes::task_attempts::pr::GetPrCommentsError::decl(),
+        server::routes::task_attempts::pr::AddressPrCommentsRequest::decl(),
+        server::routes::task_attempts::pr::AddressPrCommentsError::decl(),
+        db::models::pr_review_followup::PrReviewFollowUp::decl(),
+        db::models::pr_review_followup::PrReviewFollowUpStatus::decl(),
+        db::models::pr_review_followup::ReviewCommentRef::decl(),
+        db::models::ci_repair_attempt::CiRepairAttempt::decl(),
+        db::models::ci_repair_attempt::CiRepairStatus::decl(),
+        server::routes::task_attempts::conflicts::ResolveConflictsRequest::decl(),
+        db::models::conflict_resolution::ConflictResolution::decl(),
+        db::models::conflict_resolution::ConflictResolutionStatus::decl(),
         server::routes::task_attempts::pr::GetPrCommentsQuery::decl(),
         services::services::github::UnifiedPrComment::decl(),
         server::routes::task_attempts::RepoBranchStatus::decl(),
@@ -212,6 +264,8 @@ fn generate_types_content() -> String {
         services::services::config::SoundFile::decl(),
         services::services::config::UiLanguage::decl(),
         services::services::config::ShowcaseState::decl(),
+        services::services::config::ExecutionConcurrencyConfig::decl(),
+        services::services::config::CiRepairConfig::decl(),
         services::services::git::GitBranch::decl(),
         services::services::share::SharedTaskDetails::decl(),
         services::services::queued_message::QueuedMessage::decl(),
@@ -247,11 +301,14 @@ fn generate_types_content() -> String {
         executors::executors::droid::Droid::decl(),
         executors::executors::droid::Autonomy::decl(),
         executors::executors::droid::ReasoningEffortLevel::decl(),
+        executors::executors::api_agent::ApiAgent::decl(),
+        executors::sse_bridge::AiProvider::decl(),
         executors::executors::AppendPrompt::decl(),
         executors::actions::coding_agent_initial::CodingAgentInitialRequest::decl(),
         executors::actions::coding_agent_follow_up::CodingAgentFollowUpRequest::decl(),
         executors::logs::CommandExitStatus::decl(),
         executors::logs::CommandRunResult::decl(),
+        executors::logs::NormalizedConversation::decl(),
         executors::logs::NormalizedEntry::decl(),
         executors::logs::NormalizedEntryType::decl(),
         executors::logs::FileChange::decl(),
@@ -348,6 +405,10 @@ fn generate_schemas() -> Result<HashMap<&'static str, String>, serde_json::Error
             "droid",
             generate_json_schema::<executors::executors::droid::Droid>()?,
         ),
+        (
+            "api_agent",
+            generate_json_schema::<executors::executors::api_agent::ApiAgent>()?,
+        ),
     ]);
     println!(
         "✅ JSON schemas generated. {} schemas created.",
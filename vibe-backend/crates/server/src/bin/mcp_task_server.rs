@@ -2,13 +2,15 @@ use std::{net::SocketAddr, sync::Arc};
 
 use axum::{
     Extension, Router,
+    body::{Body, to_bytes},
     extract::Request,
-    http::{HeaderMap, StatusCode, header::AUTHORIZATION},
+    http::{HeaderMap, Method, StatusCode, header::AUTHORIZATION},
     middleware::{self, Next},
     response::{IntoResponse, Response},
     routing::get,
 };
-use remote::mcp::TaskServer;
+use db::models::api_key::ApiScope;
+use remote::mcp::{TaskServer, scopes::required_scope};
 use rmcp::{
     ServiceExt,
     transport::{
@@ -16,6 +18,7 @@ use rmcp::{
         stdio,
     },
 };
+use serde::Deserialize;
 use tokio::sync::RwLock;
 use tokio_util::sync::CancellationToken;
 use tracing_subscriber::{EnvFilter, prelude::*};
@@ -24,13 +27,30 @@ use utils::{
     sentry::{self as sentry_utils, SentrySource, sentry_layer},
 };
 
+/// Largest MCP message body inspected for scope checks
+const MAX_MESSAGE_BYTES: usize = 4 * 1024 * 1024;
+
+/// Scopes cached for a token; `None` when the token is invalid
+type CachedScopes = (Option<Vec<ApiScope>>, std::time::Instant);
+
 /// Configuration for MCP authentication - validates tokens against backend
 #[derive(Clone)]
 struct McpAuthConfig {
     backend_url: String,
     http_client: reqwest::Client,
-    /// Cache of validated tokens (token -> is_valid, expires_at)
-    token_cache: Arc<RwLock<std::collections::HashMap<String, (bool, std::time::Instant)>>>,
+    /// Cache of validated tokens (token -> scopes, expires_at)
+    token_cache: Arc<RwLock<std::collections::HashMap<String, CachedScopes>>>,
+}
+
+/// `data` of the backend's `/api/api-keys/current` response
+#[derive(Deserialize)]
+struct CurrentCredentials {
+    scopes: Vec<ApiScope>,
+}
+
+#[derive(Deserialize)]
+struct CurrentCredentialsResponse {
+    data: Option<CurrentCredentials>,
 }
 
 impl McpAuthConfig {
@@ -42,37 +62,49 @@ impl McpAuthConfig {
         }
     }
 
-    /// Validate a token by calling the backend API
+    /// Validate a token by calling the backend API and return its scopes
     /// Caches results for 5 minutes to reduce backend load
-    async fn validate_token(&self, token: &str) -> bool {
+    async fn validate_token(&self, token: &str) -> Option<Vec<ApiScope>> {
         // Check cache first
         {
             let cache = self.token_cache.read().await;
-            if let Some((is_valid, expires_at)) = cache.get(token)
+            if let Some((scopes, expires_at)) = cache.get(token)
                 && std::time::Instant::now() < *expires_at
             {
-                tracing::debug!("[MCP Auth] Token validation from cache: {}", is_valid);
-                return *is_valid;
+                tracing::debug!(
+                    "[MCP Auth] Token validation from cache: {}",
+                    scopes.is_some()
+                );
+                return scopes.clone();
             }
         }
 
-        // Call backend to validate - use /api/projects as a simple auth check
+        // Ask the backend which scopes the token carries
         let result = self
             .http_client
-            .get(format!("{}/api/projects", self.backend_url))
+            .get(format!("{}/api/api-keys/current", self.backend_url))
             .header("Authorization", format!("Bearer {}", token))
             .send()
             .await;
 
-        let is_valid = match result {
+        let scopes = match result {
             Ok(response) => {
                 let status = response.status();
                 tracing::debug!("[MCP Auth] Backend validation response: {}", status);
-                status.is_success()
+                if status.is_success() {
+                    response
+                        .json::<CurrentCredentialsResponse>()
+                        .await
+                        .ok()
+                        .and_then(|body| body.data)
+                        .map(|credentials| credentials.scopes)
+                } else {
+                    None
+                }
             }
             Err(e) => {
                 tracing::error!("[MCP Auth] Backend validation request failed: {}", e);
-                false
+                None
             }
         };
 
@@ -80,7 +112,7 @@ impl McpAuthConfig {
         {
             let mut cache = self.token_cache.write().await;
             let expires_at = std::time::Instant::now() + std::time::Duration::from_secs(300);
-            cache.insert(token.to_string(), (is_valid, expires_at));
+            cache.insert(token.to_string(), (scopes.clone(), expires_at));
 
             // Clean up expired entries if cache is getting large
             if cache.len() > 1000 {
@@ -89,10 +121,27 @@ impl McpAuthConfig {
             }
         }
 
-        is_valid
+        scopes
     }
 }
 
+/// Find the first tool call in a JSON-RPC message (or batch) that `scopes`
+/// do not allow, returning the scope it needs
+fn missing_tool_scope(body: &[u8], scopes: &[ApiScope]) -> Option<ApiScope> {
+    let message: serde_json::Value = serde_json::from_slice(body).ok()?;
+    let calls = match &message {
+        serde_json::Value::Array(batch) => batch.iter().collect(),
+        single => vec![single],
+    };
+
+    calls
+        .into_iter()
+        .filter(|call| call.get("method").and_then(|m| m.as_str()) == Some("tools/call"))
+        .filter_map(|call| call.pointer("/params/name").and_then(|n| n.as_str()))
+        .map(required_scope)
+        .find(|scope| !scope.is_granted_by(scopes))
+}
+
 fn main() -> anyhow::Result<()> {
     sentry_utils::init_once(SentrySource::Mcp);
     tokio::runtime::Builder::new_multi_thread()
@@ -187,36 +236,61 @@ async fn auth_middleware(
             })
         });
 
-    match provided_key {
-        Some(key) => {
-            // Validate token against backend database
-            if config.validate_token(&key).await {
-                tracing::debug!("[MCP Auth] Token validated successfully");
-                next.run(request).await
-            } else {
-                tracing::warn!("[MCP Auth] Invalid or expired API key");
-                (
-                    StatusCode::UNAUTHORIZED,
-                    axum::Json(serde_json::json!({
-                        "error": "unauthorized",
-                        "message": "Invalid or expired API key"
-                    })),
-                )
-                    .into_response()
-            }
-        }
-        None => {
-            tracing::warn!("[MCP Auth] No API key provided");
-            (
-                StatusCode::UNAUTHORIZED,
-                axum::Json(serde_json::json!({
-                    "error": "unauthorized",
-                    "message": "API key required. Provide via Authorization header (Bearer token) or api_key query parameter"
-                })),
-            )
-                .into_response()
+    let Some(key) = provided_key else {
+        tracing::warn!("[MCP Auth] No API key provided");
+        return (
+            StatusCode::UNAUTHORIZED,
+            axum::Json(serde_json::json!({
+                "error": "unauthorized",
+                "message": "API key required. Provide via Authorization header (Bearer token) or api_key query parameter"
+            })),
+        )
+            .into_response();
+    };
+
+    // Validate token against backend database
+    let Some(scopes) = config.validate_token(&key).await else {
+        tracing::warn!("[MCP Auth] Invalid or expired API key");
+        return (
+            StatusCode::UNAUTHORIZED,
+            axum::Json(serde_json::json!({
+                "error": "unauthorized",
+                "message": "Invalid or expired API key"
+            })),
+        )
+            .into_response();
+    };
+    tracing::debug!("[MCP Auth] Token validated successfully");
+
+    if request.method() != Method::POST {
+        return next.run(request).await;
+    }
+
+    // Tool calls arrive as posted JSON-RPC messages; check each against the
+    // key's scopes before handing the body on
+    let (parts, body) = request.into_parts();
+    let bytes = match to_bytes(body, MAX_MESSAGE_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::warn!("[MCP Auth] Failed to read message body: {}", e);
+            return StatusCode::PAYLOAD_TOO_LARGE.into_response();
         }
+    };
+
+    if let Some(scope) = missing_tool_scope(&bytes, &scopes) {
+        tracing::warn!("[MCP Auth] API key is missing scope {}", scope);
+        return (
+            StatusCode::FORBIDDEN,
+            axum::Json(serde_json::json!({
+                "error": "forbidden",
+                "message": format!("API key is missing the required scope '{}'", scope)
+            })),
+        )
+            .into_response();
     }
+
+    next.run(Request::from_parts(parts, Body::from(bytes)))
+        .await
 }
 
 async fn run_sse_server<S>(service: S, backend_url: String) -> anyhow::Result<()>
@@ -316,3 +390,26 @@ async fn get_backend_url() -> anyhow::Result<String> {
     tracing::info!("[MCP] Using backend URL: {}", url);
     Ok(url)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_tool_scope() {
+        let call = br#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"delete_task","arguments":{}}}"#;
+        assert_eq!(
+            missing_tool_scope(call, &[ApiScope::TasksRead]),
+            Some(ApiScope::TasksWrite)
+        );
+        assert_eq!(missing_tool_scope(call, &[ApiScope::TasksWrite]), None);
+
+        let batch = br#"[{"jsonrpc":"2.0","method":"notifications/initialized"},
+            {"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"list_teams"}}]"#;
+        assert_eq!(
+            missing_tool_scope(batch, &[ApiScope::TasksRead]),
+            Some(ApiScope::TeamsRead)
+        );
+        assert_eq!(missing_tool_scope(b"not json", &[]), None);
+    }
+}
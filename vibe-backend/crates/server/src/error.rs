@@ -5,23 +5,31 @@ use axum::{
     response::{IntoResponse, Response},
 };
 use db::models::{
-    execution_process::ExecutionProcessError, project::ProjectError,
-    project_repo::ProjectRepoError, repo::RepoError, scratch::ScratchError, session::SessionError,
-    workspace::WorkspaceError,
+    api_key::MissingScope, execution_process::ExecutionProcessError, project::ProjectError,
+    project_repo::ProjectRepoError, repo::RepoError, scratch::ScratchError, search::SearchError,
+    session::SessionError, task_dependency::TaskDependencyError, workspace::WorkspaceError,
 };
 use deployment::{DeploymentError, RemoteClientNotConfigured};
 use executors::executors::ExecutorError;
 use git2::Error as Git2Error;
 use services::services::{
+    attempt_comparison::AttemptComparisonError,
+    checkpoint::CheckpointError,
     config::{ConfigError, EditorOpenError},
+    conflict_resolution::ConflictResolutionError,
     container::ContainerError,
     git::GitServiceError,
     github::GitHubServiceError,
+    gitlab::GitLabServiceError,
     image::ImageError,
+    merge_queue::MergeQueueError,
+    pr_review::PrReviewError,
     project::ProjectServiceError,
     remote_client::RemoteClientError,
     repo::RepoError as RepoServiceError,
     share::ShareError,
+    task_templates::TaskTemplateError,
+    transcript::TranscriptError,
     worktree_manager::WorktreeError,
 };
 use thiserror::Error;
@@ -47,6 +55,8 @@ pub enum ApiError {
     #[error(transparent)]
     GitHubService(#[from] GitHubServiceError),
     #[error(transparent)]
+    GitLabService(#[from] GitLabServiceError),
+    #[error(transparent)]
     Deployment(#[from] DeploymentError),
     #[error(transparent)]
     Container(#[from] ContainerError),
@@ -100,6 +110,142 @@ impl From<RemoteClientNotConfigured> for ApiError {
     }
 }
 
+impl From<TaskDependencyError> for ApiError {
+    fn from(err: TaskDependencyError) -> Self {
+        match err {
+            TaskDependencyError::SelfDependency => ApiError::BadRequest(err.to_string()),
+            TaskDependencyError::Cycle | TaskDependencyError::AlreadyExists => {
+                ApiError::Conflict(err.to_string())
+            }
+            TaskDependencyError::TaskNotFound | TaskDependencyError::NotFound => {
+                ApiError::NotFound(err.to_string())
+            }
+            TaskDependencyError::Database(e) => ApiError::Database(e),
+        }
+    }
+}
+
+impl From<SearchError> for ApiError {
+    fn from(err: SearchError) -> Self {
+        match err {
+            SearchError::UnknownType(_) => ApiError::BadRequest(err.to_string()),
+            SearchError::NotAMember => ApiError::Forbidden(err.to_string()),
+            SearchError::Database(e) => ApiError::Database(e),
+        }
+    }
+}
+
+impl From<MissingScope> for ApiError {
+    fn from(err: MissingScope) -> Self {
+        ApiError::Forbidden(err.to_string())
+    }
+}
+
+impl From<TaskTemplateError> for ApiError {
+    fn from(err: TaskTemplateError) -> Self {
+        match err {
+            TaskTemplateError::Database(e) => ApiError::Database(e),
+            TaskTemplateError::Container(e) => ApiError::Container(e),
+            TaskTemplateError::RRule(_)
+            | TaskTemplateError::UnknownVariable(_)
+            | TaskTemplateError::UnclosedPlaceholder(_) => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
+
+impl From<AttemptComparisonError> for ApiError {
+    fn from(err: AttemptComparisonError) -> Self {
+        match err {
+            AttemptComparisonError::Database(e) => ApiError::Database(e),
+            AttemptComparisonError::Container(e) => ApiError::Container(e),
+            AttemptComparisonError::Git(e) => ApiError::GitService(e),
+            AttemptComparisonError::Io(e) => ApiError::Io(e),
+            AttemptComparisonError::Join(e) => ApiError::Io(std::io::Error::other(e)),
+            AttemptComparisonError::WorkspaceNotFound(_) => ApiError::NotFound(err.to_string()),
+            AttemptComparisonError::NoVerificationScript => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
+
+impl From<CheckpointError> for ApiError {
+    fn from(err: CheckpointError) -> Self {
+        match err {
+            CheckpointError::Database(e) => ApiError::Database(e),
+            CheckpointError::Container(e) => ApiError::Container(e),
+            CheckpointError::Git(e) => ApiError::GitService(e),
+            CheckpointError::Join(e) => ApiError::Io(std::io::Error::other(e)),
+            CheckpointError::TurnNotFound(_) | CheckpointError::NoCheckpoint => {
+                ApiError::NotFound(err.to_string())
+            }
+            CheckpointError::WorktreeDirty => ApiError::Conflict(err.to_string()),
+            CheckpointError::SessionForkUnsupported(_) => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
+
+impl From<TranscriptError> for ApiError {
+    fn from(err: TranscriptError) -> Self {
+        match err {
+            TranscriptError::Database(e) => ApiError::Database(e),
+            TranscriptError::Container(e) => ApiError::Container(e),
+            TranscriptError::Git(e) => ApiError::GitService(e),
+            TranscriptError::Io(e) => ApiError::Io(e),
+            TranscriptError::Json(e) => ApiError::Io(std::io::Error::other(e)),
+            TranscriptError::Join(e) => ApiError::Io(std::io::Error::other(e)),
+            TranscriptError::TaskNotFound => ApiError::NotFound(err.to_string()),
+        }
+    }
+}
+
+impl From<PrReviewError> for ApiError {
+    fn from(err: PrReviewError) -> Self {
+        match err {
+            PrReviewError::Database(e) => ApiError::Database(e),
+            PrReviewError::Container(e) => ApiError::Container(e),
+            PrReviewError::Git(e) => ApiError::GitService(e),
+            PrReviewError::GitHub(e) => ApiError::GitHubService(e),
+            PrReviewError::RepoNotFound => ApiError::NotFound(err.to_string()),
+            PrReviewError::NoPrAttached | PrReviewError::NoPendingComments => {
+                ApiError::BadRequest(err.to_string())
+            }
+            PrReviewError::AttemptBusy => ApiError::Conflict(err.to_string()),
+        }
+    }
+}
+
+impl From<ConflictResolutionError> for ApiError {
+    fn from(err: ConflictResolutionError) -> Self {
+        match err {
+            ConflictResolutionError::Database(e) => ApiError::Database(e),
+            ConflictResolutionError::Container(e) => ApiError::Container(e),
+            ConflictResolutionError::Git(e) => ApiError::GitService(e),
+            ConflictResolutionError::RepoNotFound | ConflictResolutionError::TaskNotFound => {
+                ApiError::NotFound(err.to_string())
+            }
+            ConflictResolutionError::NoConflicts => ApiError::BadRequest(err.to_string()),
+            ConflictResolutionError::AttemptBusy => ApiError::Conflict(err.to_string()),
+        }
+    }
+}
+
+impl From<MergeQueueError> for ApiError {
+    fn from(err: MergeQueueError) -> Self {
+        match err {
+            MergeQueueError::Database(e) => ApiError::Database(e),
+            MergeQueueError::Container(e) => ApiError::Container(e),
+            MergeQueueError::Git(e) => ApiError::GitService(e),
+            MergeQueueError::Io(e) => ApiError::Io(e),
+            MergeQueueError::RepoNotFound
+            | MergeQueueError::WorkspaceNotFound
+            | MergeQueueError::TaskNotFound => ApiError::NotFound(err.to_string()),
+            MergeQueueError::AlreadyQueued
+            | MergeQueueError::AttemptBusy
+            | MergeQueueError::Conflicts(_) => ApiError::Conflict(err.to_string()),
+            MergeQueueError::VerificationFailed(_) => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
+
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
         let (status_code, error_type) = match &self {
@@ -125,8 +271,20 @@ impl IntoResponse for ApiError {
                 _ => (StatusCode::INTERNAL_SERVER_ERROR, "GitServiceError"),
             },
             ApiError::GitHubService(_) => (StatusCode::INTERNAL_SERVER_ERROR, "GitHubServiceError"),
+            ApiError::GitLabService(gitlab_err) => match gitlab_err {
+                GitLabServiceError::NotFound(_) => (StatusCode::NOT_FOUND, "GitLabServiceError"),
+                GitLabServiceError::Unavailable(_) | GitLabServiceError::Request(_) => {
+                    (StatusCode::BAD_GATEWAY, "GitLabServiceError")
+                }
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "GitLabServiceError"),
+            },
             ApiError::Deployment(_) => (StatusCode::INTERNAL_SERVER_ERROR, "DeploymentError"),
-            ApiError::Container(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ContainerError"),
+            ApiError::Container(err) => match err {
+                ContainerError::BudgetExceeded(_) => {
+                    (StatusCode::PAYMENT_REQUIRED, "BudgetExceeded")
+                }
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "ContainerError"),
+            },
             ApiError::Executor(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ExecutorError"),
             ApiError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, "DatabaseError"),
             ApiError::Worktree(_) => (StatusCode::INTERNAL_SERVER_ERROR, "WorktreeError"),
@@ -255,6 +413,9 @@ impl IntoResponse for ApiError {
                 RemoteClientError::Serde(_) => "Unexpected response from remote service.".to_string(),
                 RemoteClientError::Url(_) => "Remote service URL is invalid.".to_string(),
             },
+            ApiError::Container(ContainerError::BudgetExceeded(msg)) => {
+                format!("This project is over its spend budget: {msg}. Raise or remove the budget to start new attempts.")
+            }
             ApiError::Unauthorized => "Unauthorized. Please sign in again.".to_string(),
             ApiError::BadRequest(msg) => msg.clone(),
             ApiError::Conflict(msg) => msg.clone(),
@@ -63,6 +63,8 @@ async fn main() -> Result<(), VibeKanbanError> {
         .await
         .map_err(DeploymentError::from)?;
     deployment.spawn_pr_monitor_service().await;
+    deployment.spawn_task_template_scheduler().await;
+    deployment.resume_merge_queue().await?;
     deployment
         .track_if_analytics_allowed("session_start", serde_json::json!({}))
         .await;
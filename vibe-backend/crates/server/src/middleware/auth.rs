@@ -10,7 +10,7 @@ use axum::{
     middleware::Next,
     response::{IntoResponse, Response},
 };
-use db::models::api_key::ApiKey;
+use db::models::api_key::{ApiKey, ApiKeyScopes, ApiScope, MissingScope};
 use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header, jwk::JwkSet};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -27,6 +27,10 @@ pub struct ClerkUser {
     /// True if authenticated via API key instead of JWT
     #[serde(default)]
     pub is_api_key: bool,
+    /// Scopes granted to the API key; `None` for JWT sessions, which have
+    /// full access
+    #[serde(default)]
+    pub scopes: Option<Vec<ApiScope>>,
 }
 
 /// JWT claims from Clerk token
@@ -101,10 +105,11 @@ impl AuthState {
         })?;
 
         match ApiKey::validate(pool, key).await {
-            Ok(Some(user_id)) => {
-                tracing::debug!("API key validated for user: {}", user_id);
+            Ok(Some(api_key)) => {
+                tracing::debug!("API key validated for user: {}", api_key.user_id);
                 Ok(ClerkUser {
-                    user_id,
+                    scopes: Some(api_key.granted_scopes()),
+                    user_id: api_key.user_id,
                     email: None,
                     session_id: None,
                     is_api_key: true,
@@ -203,6 +208,7 @@ impl AuthState {
             email: token_data.claims.email,
             session_id: token_data.claims.sid,
             is_api_key: false,
+            scopes: None,
         })
     }
 
@@ -225,10 +231,23 @@ pub enum AuthError {
     KeyNotFound,
     InvalidKey,
     Expired,
+    MissingScope(ApiScope),
+}
+
+impl From<MissingScope> for AuthError {
+    fn from(MissingScope(scope): MissingScope) -> Self {
+        AuthError::MissingScope(scope)
+    }
 }
 
 impl IntoResponse for AuthError {
     fn into_response(self) -> Response {
+        if let AuthError::MissingScope(scope) = self {
+            let message = format!("API key is missing the required scope '{}'", scope);
+            let response = ApiResponse::<()>::error(&message);
+            return (StatusCode::FORBIDDEN, Json(response)).into_response();
+        }
+
         let (status, message) = match self {
             AuthError::MissingToken => (StatusCode::UNAUTHORIZED, "Missing authorization token"),
             AuthError::InvalidToken => (StatusCode::UNAUTHORIZED, "Invalid authorization token"),
@@ -246,6 +265,7 @@ impl IntoResponse for AuthError {
                 "Invalid signing key configuration",
             ),
             AuthError::Expired => (StatusCode::UNAUTHORIZED, "Token has expired"),
+            AuthError::MissingScope(_) => (StatusCode::FORBIDDEN, "Missing API key scope"),
         };
 
         let response = ApiResponse::<()>::error(message);
@@ -288,7 +308,7 @@ pub async fn auth_middleware(
     let user = auth_state.authenticate(&token).await?;
 
     // Inject user into request extensions
-    request.extensions_mut().insert(user);
+    insert_user(&mut request, user);
 
     Ok(next.run(request).await)
 }
@@ -307,13 +327,21 @@ pub async fn optional_auth_middleware(
     {
         // Use authenticate which supports both JWT and API keys
         if let Ok(user) = auth_state.authenticate(token).await {
-            request.extensions_mut().insert(user);
+            insert_user(&mut request, user);
         }
     }
 
     next.run(request).await
 }
 
+/// Store the user, and for API keys their scopes for `scope_middleware`
+fn insert_user(request: &mut Request, user: ClerkUser) {
+    if let Some(scopes) = user.scopes.clone() {
+        request.extensions_mut().insert(ApiKeyScopes(scopes));
+    }
+    request.extensions_mut().insert(user);
+}
+
 /// Extractor for ClerkUser from request extensions
 impl<S> FromRequestParts<S> for ClerkUser
 where
@@ -341,4 +369,34 @@ mod tests {
         assert_eq!(extract_bearer_token("Basic abc123"), None);
         assert_eq!(extract_bearer_token("abc123"), None);
     }
+
+    #[test]
+    fn test_api_key_users_carry_their_scopes() {
+        let mut request = Request::new(axum::body::Body::empty());
+        insert_user(
+            &mut request,
+            ClerkUser {
+                user_id: "user_1".to_string(),
+                email: None,
+                session_id: None,
+                is_api_key: true,
+                scopes: Some(vec![ApiScope::TasksWrite]),
+            },
+        );
+        let scopes = request.extensions().get::<ApiKeyScopes>().unwrap();
+        assert_eq!(scopes.0, vec![ApiScope::TasksWrite]);
+
+        let mut request = Request::new(axum::body::Body::empty());
+        insert_user(
+            &mut request,
+            ClerkUser {
+                user_id: "user_1".to_string(),
+                email: None,
+                session_id: None,
+                is_api_key: false,
+                scopes: None,
+            },
+        );
+        assert!(request.extensions().get::<ApiKeyScopes>().is_none());
+    }
 }
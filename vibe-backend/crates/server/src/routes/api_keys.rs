@@ -1,11 +1,12 @@
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     extract::{Path, State},
     response::Json as ResponseJson,
     routing::{delete, get, post},
 };
-use db::models::api_key::{ApiKey, ApiKeyInfo, ApiKeyWithSecret, CreateApiKey};
+use db::models::api_key::{ApiKey, ApiKeyInfo, ApiKeyWithSecret, ApiScope, CreateApiKey};
 use deployment::Deployment;
+use serde::Serialize;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
@@ -26,10 +27,40 @@ pub async fn create_api_key(
     State(deployment): State<DeploymentImpl>,
     Json(request): Json<CreateApiKey>,
 ) -> Result<ResponseJson<ApiResponse<ApiKeyWithSecret>>, ApiError> {
+    if request.scopes.as_ref().is_some_and(Vec::is_empty) {
+        return Err(ApiError::BadRequest(
+            "An API key needs at least one scope".to_string(),
+        ));
+    }
     let key = ApiKey::create(&deployment.db().pool, &user.user_id, &request).await?;
     Ok(ResponseJson(ApiResponse::success(key)))
 }
 
+/// Scopes held by the caller of `/api-keys/current`
+#[derive(Debug, Serialize)]
+pub struct CurrentCredentials {
+    pub is_api_key: bool,
+    pub scopes: Vec<ApiScope>,
+}
+
+/// Report the scopes of the presented credentials. Sessions, and requests
+/// made while auth is disabled, have every scope.
+pub async fn get_current_credentials(
+    user: Option<Extension<ClerkUser>>,
+) -> ResponseJson<ApiResponse<CurrentCredentials>> {
+    let credentials = match user.and_then(|Extension(user)| user.scopes) {
+        Some(scopes) => CurrentCredentials {
+            is_api_key: true,
+            scopes,
+        },
+        None => CurrentCredentials {
+            is_api_key: false,
+            scopes: vec![ApiScope::Admin],
+        },
+    };
+    ResponseJson(ApiResponse::success(credentials))
+}
+
 /// Revoke an API key (soft delete)
 pub async fn revoke_api_key(
     user: ClerkUser,
@@ -65,3 +96,8 @@ pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/api-keys/{key_id}/revoke", post(revoke_api_key))
         .route("/api-keys/{key_id}", delete(delete_api_key))
 }
+
+/// Routes any authenticated caller may use, whatever its scopes
+pub fn current_router() -> Router<DeploymentImpl> {
+    Router::new().route("/api-keys/current", get(get_current_credentials))
+}
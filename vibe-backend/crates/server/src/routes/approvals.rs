@@ -1,22 +1,46 @@
 use axum::{
-    Json, Router,
-    extract::{Path, State},
+    Extension, Json, Router,
+    extract::{Path, Query, State},
     http::StatusCode,
-    routing::post,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::{
+    approval_policy::{ApprovalPolicyRule, CreateApprovalPolicyRule, UpdateApprovalPolicyRule},
+    project::Project,
+    tool_approval::ToolApproval,
 };
 use deployment::Deployment;
-use utils::approvals::{ApprovalResponse, ApprovalStatus};
+use serde::Deserialize;
+use services::services::approvals::{ApprovalError, policy};
+use utils::{
+    approvals::{ApprovalResponse, ApprovalStatus},
+    response::ApiResponse,
+};
+use uuid::Uuid;
 
-use crate::DeploymentImpl;
+use crate::{DeploymentImpl, error::ApiError, middleware::auth::ClerkUser};
+
+#[derive(Debug, Deserialize)]
+pub struct ListApprovalsQuery {
+    /// Full approval history of one execution process; without it only
+    /// pending approvals are listed
+    pub execution_process_id: Option<Uuid>,
+}
 
 pub async fn respond_to_approval(
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<ClerkUser>>,
     Path(id): Path<String>,
     Json(request): Json<ApprovalResponse>,
 ) -> Result<Json<ApprovalStatus>, StatusCode> {
     let service = deployment.approvals();
+    let user_id = user.map(|Extension(user)| user.user_id);
 
-    match service.respond(&deployment.db().pool, &id, request).await {
+    match service
+        .respond(&deployment.db().pool, &id, request, user_id)
+        .await
+    {
         Ok((status, context)) => {
             deployment
                 .track_if_analytics_allowed(
@@ -32,6 +56,8 @@ pub async fn respond_to_approval(
 
             Ok(Json(status))
         }
+        Err(ApprovalError::NotFound) => Err(StatusCode::NOT_FOUND),
+        Err(ApprovalError::AlreadyCompleted) => Err(StatusCode::CONFLICT),
         Err(e) => {
             tracing::error!("Failed to respond to approval: {:?}", e);
             Err(StatusCode::INTERNAL_SERVER_ERROR)
@@ -39,6 +65,80 @@ pub async fn respond_to_approval(
     }
 }
 
+/// Recorded approvals with the user or policy rule that decided them
+pub async fn list_approvals(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ListApprovalsQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ToolApproval>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let approvals = match query.execution_process_id {
+        Some(execution_process_id) => {
+            ToolApproval::find_by_execution_process_id(pool, execution_process_id).await?
+        }
+        None => ToolApproval::find_pending(pool).await?,
+    };
+    Ok(ResponseJson(ApiResponse::success(approvals)))
+}
+
+pub async fn list_policy_rules(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+) -> Result<ResponseJson<ApiResponse<Vec<ApprovalPolicyRule>>>, ApiError> {
+    let rules = ApprovalPolicyRule::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(rules)))
+}
+
+pub async fn create_policy_rule(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    Json(payload): Json<CreateApprovalPolicyRule>,
+) -> Result<ResponseJson<ApiResponse<ApprovalPolicyRule>>, ApiError> {
+    policy::validate_rule(&payload).map_err(ApiError::BadRequest)?;
+
+    let rule = ApprovalPolicyRule::create(&deployment.db().pool, project.id, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "approval_policy_created",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "rule_id": rule.id.to_string(),
+                "effect": rule.effect.as_str(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(rule)))
+}
+
+pub async fn update_policy_rule(
+    State(deployment): State<DeploymentImpl>,
+    Path((project_id, rule_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdateApprovalPolicyRule>,
+) -> Result<ResponseJson<ApiResponse<ApprovalPolicyRule>>, ApiError> {
+    policy::validate_rule(&payload).map_err(ApiError::BadRequest)?;
+
+    let rule = ApprovalPolicyRule::update(&deployment.db().pool, project_id, rule_id, &payload)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Approval policy rule not found".to_string()))?;
+    Ok(ResponseJson(ApiResponse::success(rule)))
+}
+
+pub async fn delete_policy_rule(
+    State(deployment): State<DeploymentImpl>,
+    Path((project_id, rule_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let deleted = ApprovalPolicyRule::delete(&deployment.db().pool, project_id, rule_id).await?;
+    if deleted == 0 {
+        return Err(ApiError::NotFound(
+            "Approval policy rule not found".to_string(),
+        ));
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
 pub fn router() -> Router<DeploymentImpl> {
-    Router::new().route("/approvals/{id}/respond", post(respond_to_approval))
+    Router::new()
+        .route("/approvals", get(list_approvals))
+        .route("/approvals/{id}/respond", post(respond_to_approval))
 }
@@ -0,0 +1,277 @@
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::{
+    attempt_group::{AttemptGroup, AttemptGroupMember, AttemptGroupWithMembers, AttemptStatus},
+    task::{Task, TaskStatus},
+    workspace::Workspace,
+    workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
+};
+use deployment::Deployment;
+use executors::profile::ExecutorProfileId;
+use futures_util::future;
+use serde::Deserialize;
+use services::services::{
+    attempt_comparison::{self, AttemptGroupComparison},
+    container::ContainerService,
+};
+use sqlx::Error as SqlxError;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::{task_attempts::merge_workspace_repo, tasks::dependencies::ensure_blockers_done},
+};
+
+/// Upper bound on attempts launched together, each of which gets its own
+/// worktree and agent process
+const MAX_PARALLEL_ATTEMPTS: usize = 8;
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateAttemptGroupRequest {
+    pub task_id: Uuid,
+    /// One attempt is started per profile
+    pub executor_profile_ids: Vec<ExecutorProfileId>,
+    pub repos: Vec<CreateWorkspaceRepo>,
+    /// Shell script run in each attempt's worktree by the verify action
+    pub verification_script: Option<String>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct PickAttemptWinnerRequest {
+    pub workspace_id: Uuid,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AttemptGroupQuery {
+    pub task_id: Uuid,
+}
+
+async fn find_group(deployment: &DeploymentImpl, group_id: Uuid) -> Result<AttemptGroup, ApiError> {
+    AttemptGroup::find_by_id(&deployment.db().pool, group_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Attempt group not found".to_string()))
+}
+
+pub async fn get_attempt_groups(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<AttemptGroupQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<AttemptGroupWithMembers>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let groups = AttemptGroup::find_by_task_id(pool, query.task_id).await?;
+    let groups =
+        future::try_join_all(groups.into_iter().map(|group| group.with_members(pool))).await?;
+    Ok(ResponseJson(ApiResponse::success(groups)))
+}
+
+pub async fn get_attempt_group(
+    State(deployment): State<DeploymentImpl>,
+    Path(group_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<AttemptGroupWithMembers>>, ApiError> {
+    let group = find_group(&deployment, group_id).await?;
+    let group = group.with_members(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(group)))
+}
+
+/// Start one attempt per executor profile, each in its own worktree
+pub async fn create_attempt_group(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateAttemptGroupRequest>,
+) -> Result<ResponseJson<ApiResponse<AttemptGroupWithMembers>>, ApiError> {
+    if payload.executor_profile_ids.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one executor profile is required".to_string(),
+        ));
+    }
+    if payload.executor_profile_ids.len() > MAX_PARALLEL_ATTEMPTS {
+        return Err(ApiError::BadRequest(format!(
+            "At most {MAX_PARALLEL_ATTEMPTS} attempts can run in parallel"
+        )));
+    }
+    if payload.repos.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one repository is required".to_string(),
+        ));
+    }
+
+    let pool = &deployment.db().pool;
+    let task = Task::find_by_id(pool, payload.task_id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    ensure_blockers_done(pool, task.id).await?;
+
+    let verification_script = payload
+        .verification_script
+        .as_deref()
+        .map(str::trim)
+        .filter(|script| !script.is_empty());
+    let group = AttemptGroup::create(pool, task.id, verification_script).await?;
+
+    let container = deployment.container();
+    future::try_join_all(
+        payload
+            .executor_profile_ids
+            .iter()
+            .map(|executor_profile_id| {
+                let (task, group, repos) = (&task, &group, &payload.repos);
+                async move {
+                    let workspace = container.create_task_workspace(task, repos).await?;
+                    AttemptGroupMember::create(pool, group.id, workspace.id, executor_profile_id)
+                        .await?;
+                    if let Err(err) = container
+                        .start_workspace(&workspace, executor_profile_id.clone())
+                        .await
+                    {
+                        tracing::error!("Failed to start attempt {}: {}", workspace.id, err);
+                    }
+                    Ok::<_, ApiError>(())
+                }
+            }),
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "attempt_group_started",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "group_id": group.id.to_string(),
+                "attempt_count": payload.executor_profile_ids.len(),
+                "executors": payload
+                    .executor_profile_ids
+                    .iter()
+                    .map(|profile| &profile.executor)
+                    .collect::<Vec<_>>(),
+                "has_verification_script": verification_script.is_some(),
+            }),
+        )
+        .await;
+
+    let group = group.with_members(pool).await?;
+    Ok(ResponseJson(ApiResponse::success(group)))
+}
+
+pub async fn get_attempt_group_comparison(
+    State(deployment): State<DeploymentImpl>,
+    Path(group_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<AttemptGroupComparison>>, ApiError> {
+    let group = find_group(&deployment, group_id).await?;
+    let comparison = attempt_comparison::compare_group(deployment.container(), group).await?;
+    Ok(ResponseJson(ApiResponse::success(comparison)))
+}
+
+/// Run the group's verification script in every active attempt
+pub async fn verify_attempt_group(
+    State(deployment): State<DeploymentImpl>,
+    Path(group_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<AttemptGroupMember>>>, ApiError> {
+    let group = find_group(&deployment, group_id).await?;
+    let members = attempt_comparison::verify_group(deployment.container(), &group).await?;
+    Ok(ResponseJson(ApiResponse::success(members)))
+}
+
+async fn merge_winner(
+    deployment: &DeploymentImpl,
+    winner: &Workspace,
+    task: &Task,
+) -> Result<(), ApiError> {
+    let pool = &deployment.db().pool;
+    for workspace_repo in WorkspaceRepo::find_by_workspace_id(pool, winner.id).await? {
+        merge_workspace_repo(deployment, winner, task, workspace_repo.repo_id).await?;
+    }
+    Ok(())
+}
+
+/// Merge the winning attempt, mark the task done and archive the other
+/// attempts. Archived attempts lose their worktrees but keep their branches.
+pub async fn pick_attempt_winner(
+    State(deployment): State<DeploymentImpl>,
+    Path(group_id): Path<Uuid>,
+    Json(payload): Json<PickAttemptWinnerRequest>,
+) -> Result<ResponseJson<ApiResponse<AttemptGroupWithMembers>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let group = find_group(&deployment, group_id).await?;
+    if group.winner_workspace_id.is_some() {
+        return Err(ApiError::Conflict(
+            "A winner was already picked for this attempt group".to_string(),
+        ));
+    }
+
+    let members = AttemptGroupMember::find_by_group_id(pool, group.id).await?;
+    if !members
+        .iter()
+        .any(|member| member.workspace_id == payload.workspace_id)
+    {
+        return Err(ApiError::BadRequest(
+            "Workspace is not an attempt in this group".to_string(),
+        ));
+    }
+
+    let winner = Workspace::find_by_id(pool, payload.workspace_id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    let task = Task::find_by_id(pool, group.task_id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+
+    // Claim the group before merging so concurrent picks cannot both merge
+    let Some(group) = AttemptGroup::decide(pool, group.id, winner.id).await? else {
+        return Err(ApiError::Conflict(
+            "A winner was already picked for this attempt group".to_string(),
+        ));
+    };
+    if let Err(err) = merge_winner(&deployment, &winner, &task).await {
+        AttemptGroup::clear_winner(pool, group.id, winner.id).await?;
+        return Err(err);
+    }
+    Task::update_status(pool, task.id, TaskStatus::Done).await?;
+
+    let container = deployment.container();
+    container.try_stop(&winner, true).await;
+    for member in &members {
+        if member.workspace_id == winner.id || member.status == AttemptStatus::Archived {
+            continue;
+        }
+        let Some(workspace) = Workspace::find_by_id(pool, member.workspace_id).await? else {
+            continue;
+        };
+        if let Err(err) = container.delete(&workspace).await {
+            tracing::error!("Failed to archive attempt {}: {}", workspace.id, err);
+        }
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "attempt_group_winner_picked",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "group_id": group.id.to_string(),
+                "workspace_id": winner.id.to_string(),
+                "attempt_count": members.len(),
+            }),
+        )
+        .await;
+
+    let group = group.with_members(pool).await?;
+    Ok(ResponseJson(ApiResponse::success(group)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    let attempt_group_router = Router::new()
+        .route("/", get(get_attempt_group))
+        .route("/comparison", get(get_attempt_group_comparison))
+        .route("/verify", post(verify_attempt_group))
+        .route("/winner", post(pick_attempt_winner));
+
+    let attempt_groups_router = Router::new()
+        .route("/", get(get_attempt_groups).post(create_attempt_group))
+        .nest("/{group_id}", attempt_group_router);
+
+    Router::new().nest("/attempt-groups", attempt_groups_router)
+}
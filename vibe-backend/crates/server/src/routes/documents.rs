@@ -15,6 +15,10 @@ use db::models::{
         CreateDocument, CreateDocumentFolder, Document, DocumentFolder, UpdateDocument,
         UpdateDocumentFolder,
     },
+    document_revision::{
+        DocumentRevision, DocumentRevisionDiff, DocumentRevisionSummary, RevisionAuthor,
+        RevisionSource,
+    },
     team::Team,
 };
 use deployment::Deployment;
@@ -31,7 +35,7 @@ use crate::{
     DeploymentImpl,
     error::ApiError,
     file_reader::{ContentType, read_file_content},
-    middleware::load_team_middleware,
+    middleware::{auth::ClerkUser, load_team_middleware},
 };
 
 /// Get the document storage service
@@ -323,6 +327,7 @@ pub async fn get_document_by_slug(
 pub async fn create_document(
     Extension(team): Extension<Team>,
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<ClerkUser>>,
     Json(mut payload): Json<CreateDocument>,
 ) -> Result<ResponseJson<ApiResponse<Document>>, ApiError> {
     let storage = get_document_storage();
@@ -348,8 +353,12 @@ pub async fn create_document(
         None
     };
 
+    let author = user
+        .map(|Extension(user)| RevisionAuthor::user(user.user_id))
+        .unwrap_or_default();
+
     // Create document record in DB (without content)
-    let document = Document::create(&deployment.db().pool, &payload).await?;
+    let document = Document::create(&deployment.db().pool, &payload, &author).await?;
 
     // Only write to local storage if content is explicitly provided
     // or if we are creating a new empty document (no file path provided)
@@ -370,6 +379,17 @@ pub async fn create_document(
             .await
             .map_err(|e| ApiError::Io(std::io::Error::other(e.to_string())))?;
 
+        // The content lives on disk, so record the first revision here
+        DocumentRevision::record(
+            &deployment.db().pool,
+            document.id,
+            &title,
+            &content,
+            &author,
+            None,
+        )
+        .await?;
+
         // Update document with file metadata
         let document = Document::update_file_metadata(
             &deployment.db().pool,
@@ -441,6 +461,7 @@ pub async fn create_document(
 /// Update a document
 pub async fn update_document(
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<ClerkUser>>,
     Path((team_id, document_id)): Path<(Uuid, Uuid)>,
     Json(payload): Json<UpdateDocument>,
 ) -> Result<ResponseJson<ApiResponse<Document>>, ApiError> {
@@ -461,7 +482,11 @@ pub async fn update_document(
     }
 
     // Update document metadata in DB
-    let mut document = Document::update(&deployment.db().pool, document_id, &payload).await?;
+    let author = user
+        .map(|Extension(user)| RevisionAuthor::user(user.user_id))
+        .unwrap_or_default();
+    let mut document =
+        Document::update(&deployment.db().pool, document_id, &payload, &author).await?;
 
     // If content was provided, write to filesystem
     if let Some(ref content) = payload.content {
@@ -528,6 +553,113 @@ pub async fn update_document(
     Ok(ResponseJson(ApiResponse::success(document)))
 }
 
+/// Query parameters for diffing two revisions of a document
+#[derive(Debug, Deserialize, TS)]
+pub struct RevisionDiffQuery {
+    pub from: i32,
+    pub to: i32,
+}
+
+/// Load a document, checking it belongs to the team in the route
+async fn find_team_document(
+    deployment: &DeploymentImpl,
+    team_id: Uuid,
+    document_id: Uuid,
+) -> Result<Document, ApiError> {
+    Document::find_by_id(&deployment.db().pool, document_id)
+        .await?
+        .filter(|document| document.team_id == team_id)
+        .ok_or_else(|| ApiError::NotFound("Document not found".to_string()))
+}
+
+async fn find_revision(
+    deployment: &DeploymentImpl,
+    document_id: Uuid,
+    revision_number: i32,
+) -> Result<DocumentRevision, ApiError> {
+    DocumentRevision::find_by_number(&deployment.db().pool, document_id, revision_number)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Revision {revision_number} not found")))
+}
+
+/// List a document's revisions, newest first
+pub async fn get_document_revisions(
+    State(deployment): State<DeploymentImpl>,
+    Path((team_id, document_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<Vec<DocumentRevisionSummary>>>, ApiError> {
+    find_team_document(&deployment, team_id, document_id).await?;
+    let revisions = DocumentRevision::list_by_document(&deployment.db().pool, document_id).await?;
+    Ok(ResponseJson(ApiResponse::success(revisions)))
+}
+
+/// Get a single revision, including its content
+pub async fn get_document_revision(
+    State(deployment): State<DeploymentImpl>,
+    Path((team_id, document_id, revision_number)): Path<(Uuid, Uuid, i32)>,
+) -> Result<ResponseJson<ApiResponse<DocumentRevision>>, ApiError> {
+    find_team_document(&deployment, team_id, document_id).await?;
+    let revision = find_revision(&deployment, document_id, revision_number).await?;
+    Ok(ResponseJson(ApiResponse::success(revision)))
+}
+
+/// Diff two revisions of a document
+pub async fn diff_document_revisions(
+    State(deployment): State<DeploymentImpl>,
+    Path((team_id, document_id)): Path<(Uuid, Uuid)>,
+    Query(query): Query<RevisionDiffQuery>,
+) -> Result<ResponseJson<ApiResponse<DocumentRevisionDiff>>, ApiError> {
+    find_team_document(&deployment, team_id, document_id).await?;
+    let from = find_revision(&deployment, document_id, query.from).await?;
+    let to = find_revision(&deployment, document_id, query.to).await?;
+    Ok(ResponseJson(ApiResponse::success(from.diff(&to))))
+}
+
+/// Restore a document's content to an earlier revision. The restore is
+/// itself recorded as a new revision, so it can be undone.
+pub async fn restore_document_revision(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<ClerkUser>>,
+    Path((team_id, document_id, revision_number)): Path<(Uuid, Uuid, i32)>,
+) -> Result<ResponseJson<ApiResponse<Document>>, ApiError> {
+    let existing = find_team_document(&deployment, team_id, document_id).await?;
+    let revision = find_revision(&deployment, document_id, revision_number).await?;
+    let author = user
+        .map(|Extension(user)| RevisionAuthor::user(user.user_id))
+        .unwrap_or_default();
+
+    let mut document =
+        Document::restore_revision(&deployment.db().pool, &revision, &author).await?;
+
+    // Keep the file on disk in step with the restored content
+    if let Some(ref file_path) = existing.file_path {
+        tokio::fs::write(file_path, revision.content.as_bytes())
+            .await
+            .map_err(ApiError::Io)?;
+        document = Document::update_file_metadata(
+            &deployment.db().pool,
+            document.id,
+            file_path,
+            revision.content_size,
+            &get_document_storage().get_mime_type_for_file_type(&document.file_type),
+            &document.file_type,
+        )
+        .await?;
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "document_revision_restored",
+            serde_json::json!({
+                "team_id": team_id.to_string(),
+                "document_id": document_id.to_string(),
+                "revision_number": revision_number,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(document)))
+}
+
 /// Delete a document
 pub async fn delete_document(
     State(deployment): State<DeploymentImpl>,
@@ -1145,8 +1277,14 @@ fn is_supported_document(extension: &str) -> bool {
 pub async fn scan_filesystem(
     Extension(team): Extension<Team>,
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<ClerkUser>>,
     Path((_team_id, folder_id)): Path<(Uuid, Uuid)>,
 ) -> Result<ResponseJson<ApiResponse<ScanFilesystemResponse>>, ApiError> {
+    let author = RevisionAuthor::sync(
+        RevisionSource::FilesystemScan,
+        user.map(|Extension(user)| user.user_id),
+    );
+
     // Get the folder to scan
     let folder = DocumentFolder::find_by_id(&deployment.db().pool, folder_id)
         .await?
@@ -1263,6 +1401,7 @@ pub async fn scan_filesystem(
                                 &deployment.db().pool,
                                 existing_doc.id,
                                 &update_payload,
+                                &author,
                             )
                             .await?;
                         }
@@ -1310,6 +1449,7 @@ pub async fn scan_filesystem(
                                 &deployment.db().pool,
                                 existing_doc.id,
                                 &update_payload,
+                                &author,
                             )
                             .await?;
                         }
@@ -1357,7 +1497,8 @@ pub async fn scan_filesystem(
                 storage_key: None,
             };
 
-            let document = Document::create(&deployment.db().pool, &create_payload).await?;
+            let document =
+                Document::create(&deployment.db().pool, &create_payload, &author).await?;
 
             // Update with file metadata
             Document::update_file_metadata(
@@ -1406,7 +1547,13 @@ pub async fn scan_filesystem(
 pub async fn scan_all_filesystem(
     Extension(team): Extension<Team>,
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<ClerkUser>>,
 ) -> Result<ResponseJson<ApiResponse<ScanAllResponse>>, ApiError> {
+    let author = RevisionAuthor::sync(
+        RevisionSource::FilesystemScan,
+        user.map(|Extension(user)| user.user_id),
+    );
+
     // Get team's document_storage_path
     let base_path = team.document_storage_path.as_ref().ok_or_else(|| {
         ApiError::BadRequest(
@@ -1553,7 +1700,8 @@ pub async fn scan_all_filesystem(
                     storage_key: None,
                 };
 
-                let document = Document::create(&deployment.db().pool, &create_payload).await?;
+                let document =
+                    Document::create(&deployment.db().pool, &create_payload, &author).await?;
 
                 // Update with file metadata
                 Document::update_file_metadata(
@@ -1607,12 +1755,16 @@ pub async fn scan_all_filesystem(
 pub async fn upload_documents(
     Extension(team): Extension<Team>,
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<ClerkUser>>,
     mut multipart: Multipart,
 ) -> Result<ResponseJson<ApiResponse<UploadResult>>, ApiError> {
     let mut uploaded = 0;
     let mut skipped = 0;
     let mut errors = Vec::new();
     let mut uploaded_titles = Vec::new();
+    let author = user
+        .map(|Extension(user)| RevisionAuthor::user(user.user_id))
+        .unwrap_or_default();
     let mut folder_id: Option<Uuid> = None;
 
     // Try to get Supabase Storage client
@@ -1727,7 +1879,7 @@ pub async fn upload_documents(
                 storage_key: None,
             };
 
-            match Document::create(&deployment.db().pool, &create_payload).await {
+            match Document::create(&deployment.db().pool, &create_payload, &author).await {
                 Ok(document) => {
                     // Upload ALL files to storage (including text files like .md, .txt)
                     if let Some(ref client) = supabase_client {
@@ -1865,7 +2017,14 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         )
         .route("/content", get(get_document_content))
         .route("/file", get(get_document_file))
-        .route("/signed-url", get(get_document_signed_url));
+        .route("/signed-url", get(get_document_signed_url))
+        .route("/revisions", get(get_document_revisions))
+        .route("/revisions/diff", get(diff_document_revisions))
+        .route("/revisions/{revision_number}", get(get_document_revision))
+        .route(
+            "/revisions/{revision_number}/restore",
+            post(restore_document_revision),
+        );
 
     let folder_item_router = Router::new().route(
         "/",
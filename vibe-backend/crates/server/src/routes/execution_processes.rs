@@ -14,13 +14,16 @@ use db::models::{
     execution_process_repo_state::ExecutionProcessRepoState,
 };
 use deployment::Deployment;
+use executors::logs::NormalizedConversation;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::Deserialize;
-use services::services::container::ContainerService;
+use services::services::{container::ContainerService, transcript};
 use utils::{log_msg::LogMsg, response::ApiResponse};
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError, middleware::load_execution_process_middleware};
+use crate::{
+    DeploymentImpl, error::ApiError, middleware::load_execution_process_middleware, routes::usage,
+};
 
 #[derive(Debug, Deserialize)]
 pub struct ExecutionProcessQuery {
@@ -37,6 +40,15 @@ pub async fn get_execution_process_by_id(
     Ok(ResponseJson(ApiResponse::success(execution_process)))
 }
 
+/// Normalized conversation of a coding agent process, live or stored
+pub async fn get_execution_process_transcript(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<NormalizedConversation>>>, ApiError> {
+    let conversation = transcript::load(deployment.container(), &execution_process).await?;
+    Ok(ResponseJson(ApiResponse::success(conversation)))
+}
+
 pub async fn stream_raw_logs_ws(
     ws: WebSocketUpgrade,
     State(deployment): State<DeploymentImpl>,
@@ -248,6 +260,8 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/", get(get_execution_process_by_id))
         .route("/stop", post(stop_execution_process))
         .route("/repo-states", get(get_execution_process_repo_states))
+        .route("/usage", get(usage::get_execution_process_usage))
+        .route("/transcript", get(get_execution_process_transcript))
         .route("/raw-logs/ws", get(stream_raw_logs_ws))
         .route("/normalized-logs/ws", get(stream_normalized_logs_ws))
         .layer(from_fn_with_state(
@@ -0,0 +1,84 @@
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    response::Json as ResponseJson,
+    routing::{delete, get, put},
+};
+use db::models::queued_attempt::QueuedAttempt;
+use deployment::Deployment;
+use serde::Deserialize;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize)]
+pub struct ExecutionQueueQuery {
+    pub project_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct MoveQueuedAttemptRequest {
+    /// Zero-based target position, clamped to the queue
+    pub position: i32,
+}
+
+/// Attempts waiting for an execution slot, next to start first
+pub async fn get_execution_queue(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ExecutionQueueQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<QueuedAttempt>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let queue = match query.project_id {
+        Some(project_id) => QueuedAttempt::find_by_project_id(pool, project_id).await?,
+        None => QueuedAttempt::find_all(pool).await?,
+    };
+    Ok(ResponseJson(ApiResponse::success(queue)))
+}
+
+/// Move a queued attempt and return the reordered queue
+pub async fn move_queued_attempt(
+    State(deployment): State<DeploymentImpl>,
+    Path(workspace_id): Path<Uuid>,
+    Json(payload): Json<MoveQueuedAttemptRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<QueuedAttempt>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if !QueuedAttempt::move_to(pool, workspace_id, payload.position).await? {
+        return Err(ApiError::NotFound("Attempt is not queued".to_string()));
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "queued_attempt_moved",
+            serde_json::json!({
+                "workspace_id": workspace_id.to_string(),
+                "position": payload.position,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(
+        QueuedAttempt::find_all(pool).await?,
+    )))
+}
+
+/// Take an attempt off the queue without starting it
+pub async fn remove_queued_attempt(
+    State(deployment): State<DeploymentImpl>,
+    Path(workspace_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    if !QueuedAttempt::remove(&deployment.db().pool, workspace_id).await? {
+        return Err(ApiError::NotFound("Attempt is not queued".to_string()));
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    let execution_queue_router = Router::new()
+        .route("/", get(get_execution_queue))
+        .route("/{workspace_id}", delete(remove_queued_attempt))
+        .route("/{workspace_id}/position", put(move_queued_attempt));
+
+    Router::new().nest("/execution-queue", execution_queue_router)
+}
@@ -0,0 +1,355 @@
+//! GitLab Webhook Handlers
+//!
+//! Tracks merge request state into `merges` and reports pipeline results on
+//! the task, for MRs opened or attached from a task attempt. Requests must
+//! carry `X-Gitlab-Token` matching `GITLAB_WEBHOOK_SECRET`.
+
+use axum::{
+    Router,
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::Json as ResponseJson,
+    routing::post,
+};
+use db::models::{
+    merge::{Merge, MergeStatus, PrMerge},
+    task::{Task, TaskStatus},
+    task_comment::{CreateTaskComment, TaskComment},
+    workspace::Workspace,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::gitlab::merge_status_from_state;
+use tracing::{error, info, warn};
+
+use crate::{DeploymentImpl, routes::webhooks::WebhookResponse};
+
+/// GitLab webhook payload for merge request events
+#[derive(Debug, Deserialize)]
+pub struct GitLabMergeRequestEvent {
+    pub object_attributes: GitLabMergeRequestAttributes,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GitLabMergeRequestAttributes {
+    pub iid: i64,
+    pub url: String,
+    pub state: String,
+    pub action: Option<String>,
+    pub merge_commit_sha: Option<String>,
+}
+
+/// GitLab webhook payload for pipeline events
+#[derive(Debug, Deserialize)]
+pub struct GitLabPipelineEvent {
+    pub object_attributes: GitLabPipelineAttributes,
+    pub merge_request: Option<GitLabPipelineMergeRequest>,
+    pub project: GitLabProject,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GitLabPipelineAttributes {
+    pub id: i64,
+    pub status: String,
+    #[serde(rename = "ref")]
+    pub branch_ref: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GitLabPipelineMergeRequest {
+    pub iid: i64,
+    pub url: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GitLabProject {
+    pub web_url: String,
+}
+
+/// Handle GitLab webhooks
+pub async fn handle_gitlab_webhook(
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<(StatusCode, ResponseJson<WebhookResponse>), StatusCode> {
+    let event_type = headers
+        .get("x-gitlab-event")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("unknown");
+
+    info!("Received GitLab webhook event: {}", event_type);
+
+    let secret = std::env::var("GITLAB_WEBHOOK_SECRET").ok();
+    verify_token(secret.as_deref(), &headers)?;
+
+    let handled = match event_type {
+        "Merge Request Hook" => {
+            handle_merge_request_event(&deployment, parse_event(event_type, &body)?).await?;
+            true
+        }
+        "Pipeline Hook" => {
+            handle_pipeline_event(&deployment, parse_event(event_type, &body)?).await?;
+            true
+        }
+        _ => {
+            info!("Ignoring GitLab event type: {}", event_type);
+            false
+        }
+    };
+
+    Ok((
+        StatusCode::OK,
+        ResponseJson(WebhookResponse {
+            received: true,
+            message: if handled {
+                format!("Processed {} event", event_type)
+            } else {
+                format!("Ignored {} event", event_type)
+            },
+        }),
+    ))
+}
+
+/// Check `X-Gitlab-Token` against `GITLAB_WEBHOOK_SECRET`
+fn verify_token(secret: Option<&str>, headers: &HeaderMap) -> Result<(), StatusCode> {
+    let Some(secret) = secret.filter(|s| !s.is_empty()) else {
+        error!("GITLAB_WEBHOOK_SECRET is not set; rejecting GitLab webhook");
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    };
+
+    let token = headers
+        .get("x-gitlab-token")
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| {
+            warn!("GitLab webhook missing X-Gitlab-Token header");
+            StatusCode::UNAUTHORIZED
+        })?;
+
+    if !constant_time_eq(secret.as_bytes(), token.as_bytes()) {
+        warn!("GitLab webhook token mismatch");
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    Ok(())
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn parse_event<T: serde::de::DeserializeOwned>(
+    event_type: &str,
+    body: &[u8],
+) -> Result<T, StatusCode> {
+    serde_json::from_slice(body).map_err(|e| {
+        error!("Failed to parse GitLab {} payload: {}", event_type, e);
+        StatusCode::BAD_REQUEST
+    })
+}
+
+async fn find_tracked_mrs(
+    deployment: &DeploymentImpl,
+    mr_url: &str,
+) -> Result<Vec<PrMerge>, StatusCode> {
+    Merge::find_open_prs_by_url(&deployment.db().pool, mr_url)
+        .await
+        .map_err(|e| {
+            error!("Failed to find merges for MR {}: {}", mr_url, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+/// Handle merge request events: record merged/closed state and finish the task
+async fn handle_merge_request_event(
+    deployment: &DeploymentImpl,
+    event: GitLabMergeRequestEvent,
+) -> Result<(), StatusCode> {
+    let pool = &deployment.db().pool;
+    let mr = event.object_attributes;
+    let status = merge_status_from_state(&mr.state);
+
+    info!(
+        "Processing MR event: {} for !{} ({})",
+        mr.action.as_deref().unwrap_or("unknown"),
+        mr.iid,
+        mr.state
+    );
+
+    if !matches!(status, MergeStatus::Merged | MergeStatus::Closed) {
+        return Ok(());
+    }
+
+    for pr_merge in find_tracked_mrs(deployment, &mr.url).await? {
+        Merge::update_status(
+            pool,
+            pr_merge.id,
+            status.clone(),
+            mr.merge_commit_sha.clone(),
+        )
+        .await
+        .map_err(|e| {
+            error!("Failed to update merge {}: {}", pr_merge.id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+        if !matches!(status, MergeStatus::Merged) {
+            continue;
+        }
+        let Some(workspace) = find_workspace(deployment, &pr_merge).await? else {
+            continue;
+        };
+
+        info!(
+            "MR !{} was merged, updating task {} to done",
+            mr.iid, workspace.task_id
+        );
+        Task::update_status(pool, workspace.task_id, TaskStatus::Done)
+            .await
+            .map_err(|e| {
+                error!("Failed to mark task {} done: {}", workspace.task_id, e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+
+        if let Ok(publisher) = deployment.share_publisher()
+            && let Err(err) = publisher.update_shared_task_by_id(workspace.task_id).await
+        {
+            warn!(
+                ?err,
+                "Failed to propagate shared task update for {}", workspace.task_id
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle pipeline events: comment on the task when an MR pipeline finishes
+async fn handle_pipeline_event(
+    deployment: &DeploymentImpl,
+    event: GitLabPipelineEvent,
+) -> Result<(), StatusCode> {
+    let pipeline = &event.object_attributes;
+    let summary = match pipeline.status.as_str() {
+        "success" => "passed",
+        "failed" => "failed",
+        "canceled" => "was canceled",
+        _ => return Ok(()),
+    };
+    let Some(mr) = &event.merge_request else {
+        info!(
+            "Ignoring pipeline {} on {} without a merge request",
+            pipeline.id,
+            pipeline.branch_ref.as_deref().unwrap_or("unknown ref")
+        );
+        return Ok(());
+    };
+
+    let pipeline_url = format!("{}/-/pipelines/{}", event.project.web_url, pipeline.id);
+    for pr_merge in find_tracked_mrs(deployment, &mr.url).await? {
+        let Some(workspace) = find_workspace(deployment, &pr_merge).await? else {
+            continue;
+        };
+        post_task_comment(
+            deployment,
+            workspace.task_id,
+            &format!(
+                "Pipeline [#{}]({}) for MR [!{}]({}) {}.",
+                pipeline.id, pipeline_url, mr.iid, mr.url, summary
+            ),
+        )
+        .await;
+    }
+
+    Ok(())
+}
+
+async fn find_workspace(
+    deployment: &DeploymentImpl,
+    pr_merge: &PrMerge,
+) -> Result<Option<Workspace>, StatusCode> {
+    Workspace::find_by_id(&deployment.db().pool, pr_merge.workspace_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load workspace {}: {}", pr_merge.workspace_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+async fn post_task_comment(deployment: &DeploymentImpl, task_id: uuid::Uuid, content: &str) {
+    if let Err(e) = TaskComment::create(
+        &deployment.db().pool,
+        task_id,
+        &CreateTaskComment {
+            content: content.to_string(),
+            is_internal: false,
+            author_name: "GitLab".to_string(),
+            author_email: None,
+            author_id: None,
+        },
+    )
+    .await
+    {
+        error!("Failed to create task comment: {}", e);
+    }
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/gitlab", post(handle_gitlab_webhook))
+        .with_state(deployment.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    fn headers_with_token(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-gitlab-token", HeaderValue::from_str(token).unwrap());
+        headers
+    }
+
+    #[test]
+    fn verify_token_accepts_matching_secret() {
+        assert!(verify_token(Some("s3cret"), &headers_with_token("s3cret")).is_ok());
+    }
+
+    #[test]
+    fn verify_token_rejects_wrong_or_missing_token() {
+        assert_eq!(
+            verify_token(Some("s3cret"), &headers_with_token("s3cre")),
+            Err(StatusCode::UNAUTHORIZED)
+        );
+        assert_eq!(
+            verify_token(Some("s3cret"), &HeaderMap::new()),
+            Err(StatusCode::UNAUTHORIZED)
+        );
+        assert_eq!(
+            verify_token(None, &headers_with_token("s3cret")),
+            Err(StatusCode::SERVICE_UNAVAILABLE)
+        );
+    }
+
+    #[test]
+    fn parses_merge_request_and_pipeline_payloads() {
+        let mr: GitLabMergeRequestEvent = parse_event(
+            "Merge Request Hook",
+            br#"{"object_kind":"merge_request","object_attributes":{"iid":5,"url":"https://gitlab.com/g/p/-/merge_requests/5","state":"merged","action":"merge","merge_commit_sha":"abc"}}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            merge_status_from_state(&mr.object_attributes.state),
+            MergeStatus::Merged
+        ));
+
+        let pipeline: GitLabPipelineEvent = parse_event(
+            "Pipeline Hook",
+            br#"{"object_kind":"pipeline","object_attributes":{"id":9,"status":"failed","ref":"vk/x"},"merge_request":{"iid":5,"url":"https://gitlab.com/g/p/-/merge_requests/5"},"project":{"web_url":"https://gitlab.com/g/p"}}"#,
+        )
+        .unwrap();
+        assert_eq!(pipeline.merge_request.unwrap().iid, 5);
+    }
+}
@@ -0,0 +1,221 @@
+//! Merge queue of a project's repositories.
+//!
+//! Attempts are queued from the attempt router; the queue itself and the
+//! per-repository verification settings live here.
+
+use axum::{
+    Extension, Json, Router,
+    extract::{
+        Path, Query, State,
+        ws::{WebSocket, WebSocketUpgrade},
+    },
+    response::{IntoResponse, Json as ResponseJson},
+    routing::{delete, get},
+};
+use db::models::{
+    merge_queue::{MergeQueueConfig, MergeQueueEntry, MergeStrategy, UpsertMergeQueueConfig},
+    project_repo::ProjectRepo,
+    workspace::Workspace,
+};
+use deployment::Deployment;
+use futures_util::{SinkExt, StreamExt, TryStreamExt};
+use serde::Deserialize;
+use services::services::merge_queue;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize)]
+pub struct MergeQueueQuery {
+    pub project_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct EnqueueMergeRequest {
+    pub repo_id: Uuid,
+    /// Defaults to a squash merge
+    pub strategy: Option<MergeStrategy>,
+}
+
+/// Queue one repository of the attempt for landing on its target branch
+pub async fn enqueue_task_attempt(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<EnqueueMergeRequest>,
+) -> Result<ResponseJson<ApiResponse<MergeQueueEntry>>, ApiError> {
+    let entry = merge_queue::enqueue(
+        deployment.container(),
+        &workspace,
+        request.repo_id,
+        request.strategy.unwrap_or_default(),
+    )
+    .await?;
+    deployment.process_merge_queue_lane(entry.repo_id, entry.target_branch.clone());
+
+    deployment
+        .track_if_analytics_allowed(
+            "merge_queue_entry_added",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "repo_id": entry.repo_id.to_string(),
+                "strategy": entry.strategy,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(entry)))
+}
+
+/// Merge queue entries of the attempt, newest first
+pub async fn get_task_attempt_merge_queue(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<MergeQueueEntry>>>, ApiError> {
+    let entries =
+        MergeQueueEntry::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(entries)))
+}
+
+/// Active and recently finished entries of a project, in queue order
+pub async fn get_merge_queue(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<MergeQueueQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<MergeQueueEntry>>>, ApiError> {
+    let entries =
+        MergeQueueEntry::find_by_project_id(&deployment.db().pool, query.project_id).await?;
+    Ok(ResponseJson(ApiResponse::success(entries)))
+}
+
+/// Take an entry out of the queue before it starts landing
+pub async fn remove_merge_queue_entry(
+    State(deployment): State<DeploymentImpl>,
+    Path(entry_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    if !MergeQueueEntry::remove_queued(&deployment.db().pool, entry_id).await? {
+        return Err(ApiError::NotFound("Entry is not queued".to_string()));
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn stream_merge_queue_ws(
+    ws: WebSocketUpgrade,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<MergeQueueQuery>,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| async move {
+        if let Err(e) = handle_merge_queue_ws(socket, deployment, query.project_id).await {
+            tracing::warn!("merge queue WS closed: {}", e);
+        }
+    })
+}
+
+async fn handle_merge_queue_ws(
+    socket: WebSocket,
+    deployment: DeploymentImpl,
+    project_id: Uuid,
+) -> anyhow::Result<()> {
+    let mut stream = deployment
+        .events()
+        .stream_merge_queue_raw(project_id)
+        .await?
+        .map_ok(|msg| msg.to_ws_message_unchecked());
+
+    // Split socket into sender and receiver
+    let (mut sender, mut receiver) = socket.split();
+
+    // Drain (and ignore) any client->server messages so pings/pongs work
+    tokio::spawn(async move { while let Some(Ok(_)) = receiver.next().await {} });
+
+    // Forward server messages
+    while let Some(item) = stream.next().await {
+        match item {
+            Ok(msg) => {
+                if sender.send(msg).await.is_err() {
+                    break; // client disconnected
+                }
+            }
+            Err(e) => {
+                tracing::error!("stream error: {}", e);
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn ensure_project_repo(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    repo_id: Uuid,
+) -> Result<(), ApiError> {
+    ProjectRepo::find_by_project_and_repo(&deployment.db().pool, project_id, repo_id)
+        .await?
+        .map(|_| ())
+        .ok_or_else(|| ApiError::BadRequest("Repository not found in project".to_string()))
+}
+
+pub async fn get_merge_queue_config(
+    State(deployment): State<DeploymentImpl>,
+    Path((project_id, repo_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<Option<MergeQueueConfig>>>, ApiError> {
+    ensure_project_repo(&deployment, project_id, repo_id).await?;
+    let config = MergeQueueConfig::find(&deployment.db().pool, project_id, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(config)))
+}
+
+/// Create or replace the repository's verification; applies to entries that
+/// have not started verifying yet
+pub async fn set_merge_queue_config(
+    State(deployment): State<DeploymentImpl>,
+    Path((project_id, repo_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpsertMergeQueueConfig>,
+) -> Result<ResponseJson<ApiResponse<MergeQueueConfig>>, ApiError> {
+    if payload
+        .verification_timeout_secs
+        .is_some_and(|secs| secs <= 0)
+    {
+        return Err(ApiError::BadRequest(
+            "Verification timeout must be positive".to_string(),
+        ));
+    }
+    ensure_project_repo(&deployment, project_id, repo_id).await?;
+    let config =
+        MergeQueueConfig::upsert(&deployment.db().pool, project_id, repo_id, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "merge_queue_config_set",
+            serde_json::json!({
+                "project_id": project_id.to_string(),
+                "repo_id": repo_id.to_string(),
+                "has_verification_script": config.verification_script.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(config)))
+}
+
+pub async fn delete_merge_queue_config(
+    State(deployment): State<DeploymentImpl>,
+    Path((project_id, repo_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    if MergeQueueConfig::delete(&deployment.db().pool, project_id, repo_id).await? == 0 {
+        return Err(ApiError::NotFound(
+            "Repository has no merge queue settings".to_string(),
+        ));
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    let merge_queue_router = Router::new()
+        .route("/", get(get_merge_queue))
+        .route("/stream/ws", get(stream_merge_queue_ws))
+        .route("/{entry_id}", delete(remove_merge_queue_entry));
+
+    Router::new().nest("/merge-queue", merge_queue_router)
+}
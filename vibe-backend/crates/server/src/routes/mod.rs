@@ -9,13 +9,14 @@ use axum::{
     middleware,
     routing::{IntoMakeService, get},
 };
+use db::models::api_key::{ApiScope, ScopeRule, scope_middleware};
 use deployment::Deployment;
 use tower_http::cors::CorsLayer;
 
 use crate::{
     DeploymentImpl,
     middleware::{
-        auth::{AuthState, auth_middleware},
+        auth::{AuthError, AuthState, auth_middleware},
         rate_limit::{RateLimitConfig, create_rate_limit_layer, rate_limit_middleware},
     },
 };
@@ -24,35 +25,57 @@ pub mod admin;
 pub mod ai_keys;
 pub mod api_keys;
 pub mod approvals;
+pub mod attempt_groups;
 pub mod chat;
 pub mod config;
 pub mod containers;
 pub mod documents;
 pub mod events;
 pub mod execution_processes;
+pub mod execution_queue;
 pub mod filesystem;
 pub mod github;
 pub mod gitlab;
+pub mod gitlab_webhooks;
 // pub mod frontend;
 pub mod health;
 pub mod images;
 pub mod inbox;
+pub mod merge_queue;
 pub mod oauth;
 pub mod organizations;
 pub mod projects;
 pub mod registrations;
 pub mod repo;
+pub mod sandbox;
 pub mod scratch;
+pub mod search;
 pub mod sessions;
 pub mod shared_tasks;
 pub mod storage;
 pub mod tags;
 pub mod task_attempts;
+pub mod task_templates;
 pub mod tasks;
 pub mod teams;
 pub mod tenant_workspaces;
+pub mod usage;
+pub mod webhook_deliveries;
 pub mod webhooks;
 
+/// Require `read` for safe methods and `write` for the rest when a request is
+/// authenticated with an API key
+fn scoped(
+    routes: Router<DeploymentImpl>,
+    read: ApiScope,
+    write: ApiScope,
+) -> Router<DeploymentImpl> {
+    routes.route_layer(middleware::from_fn_with_state(
+        ScopeRule::new(read, write),
+        scope_middleware::<AuthError>,
+    ))
+}
+
 pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
     // Check if auth is enabled (disabled by default for backwards compatibility)
     let auth_enabled = std::env::var("ENABLE_API_AUTH")
@@ -78,38 +101,89 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .route("/health", get(health::health_check))
         .merge(config::router())
         .merge(oauth::router())
-        .nest("/webhooks", webhooks::router(&deployment));
+        .nest(
+            "/webhooks",
+            webhooks::router(&deployment).merge(gitlab_webhooks::router(&deployment)),
+        );
 
-    // Protected routes (auth required when enabled)
-    let protected_routes = Router::new()
-        .merge(admin::router(&deployment))
-        .merge(ai_keys::router(&deployment))
-        .merge(chat::router(&deployment))
-        .merge(api_keys::router(&deployment))
-        .merge(containers::router(&deployment))
-        .merge(projects::router(&deployment))
+    // Protected routes (auth required when enabled), grouped by the API key
+    // scope each group requires
+    let task_routes = Router::new()
         .merge(tasks::router(&deployment))
         .merge(shared_tasks::router())
-        .merge(task_attempts::router(&deployment))
-        .merge(execution_processes::router(&deployment))
         .merge(tags::router(&deployment))
+        .merge(task_templates::router(&deployment))
+        .merge(inbox::router(&deployment))
+        .merge(events::router(&deployment))
+        .merge(search::router())
+        .nest("/images", images::routes());
+
+    let document_routes = Router::new()
+        .merge(documents::router(&deployment))
+        .merge(storage::router(&deployment));
+
+    let project_routes = Router::new()
+        .merge(projects::router(&deployment))
+        .merge(repo::router());
+
+    let team_routes = Router::new()
         .merge(teams::router(&deployment))
         .merge(tenant_workspaces::router())
-        .merge(documents::router(&deployment))
-        .merge(inbox::router(&deployment))
-        .merge(organizations::router())
+        .merge(organizations::router());
+
+    let execution_routes = Router::new()
+        .merge(chat::router(&deployment))
+        .merge(containers::router(&deployment))
+        .merge(task_attempts::router(&deployment))
+        .merge(tasks::execution_router())
+        .merge(attempt_groups::router())
+        .merge(execution_processes::router(&deployment))
+        .merge(execution_queue::router())
+        .merge(merge_queue::router())
+        .merge(approvals::router())
+        .merge(scratch::router(&deployment))
+        .merge(sessions::router(&deployment));
+
+    let admin_routes = Router::new()
+        .merge(admin::router(&deployment))
+        .merge(ai_keys::router(&deployment))
+        .merge(api_keys::router(&deployment))
         .merge(github::router(&deployment))
         .merge(gitlab::router(&deployment))
         .merge(filesystem::router())
-        .merge(repo::router())
-        .merge(events::router(&deployment))
-        .merge(approvals::router())
-        .merge(scratch::router(&deployment))
-        .merge(sessions::router(&deployment))
-        .merge(storage::router(&deployment))
-        .nest("/images", images::routes())
+        .merge(webhook_deliveries::router(&deployment))
+        .merge(projects::admin_router(&deployment))
         .nest("/registrations", registrations::router(&deployment));
 
+    let protected_routes = Router::new()
+        .merge(scoped(
+            task_routes,
+            ApiScope::TasksRead,
+            ApiScope::TasksWrite,
+        ))
+        .merge(scoped(
+            document_routes,
+            ApiScope::DocumentsRead,
+            ApiScope::DocumentsWrite,
+        ))
+        .merge(scoped(
+            project_routes,
+            ApiScope::ProjectsRead,
+            ApiScope::ProjectsWrite,
+        ))
+        .merge(scoped(
+            team_routes,
+            ApiScope::TeamsRead,
+            ApiScope::TeamsWrite,
+        ))
+        .merge(scoped(
+            execution_routes,
+            ApiScope::ExecutionsRead,
+            ApiScope::ExecutionsRun,
+        ))
+        .merge(scoped(admin_routes, ApiScope::Admin, ApiScope::Admin))
+        .merge(api_keys::current_router());
+
     // Apply auth middleware only if enabled
     let protected_routes = if auth_enabled {
         tracing::info!("API authentication is ENABLED");
@@ -159,7 +233,9 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
                 .parse::<HeaderValue>()
                 .unwrap(),
             // Vercel deployment domains
-            "https://i-kanban.vercel.app".parse::<HeaderValue>().unwrap(),
+            "https://i-kanban.vercel.app"
+                .parse::<HeaderValue>()
+                .unwrap(),
         ])
         .allow_methods([
             Method::GET,
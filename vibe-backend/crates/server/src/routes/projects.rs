@@ -10,7 +10,7 @@ use axum::{
     http::StatusCode,
     middleware::from_fn_with_state,
     response::{IntoResponse, Json as ResponseJson},
-    routing::{get, post},
+    routing::{get, post, put},
 };
 use db::models::{
     project::{CreateProject, Project, ProjectError, SearchResult, UpdateProject},
@@ -33,7 +33,12 @@ use utils::{
 };
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError, middleware::load_project_middleware};
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_project_middleware,
+    routes::{approvals, merge_queue, sandbox, usage},
+};
 
 /// Query parameters for listing projects
 #[derive(Debug, Deserialize)]
@@ -625,6 +630,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             "/repositories",
             get(get_project_repositories).post(add_project_repository),
         )
+        .route("/usage", get(usage::get_project_usage))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_project_middleware,
@@ -638,6 +644,12 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
                 .put(update_project_repository)
                 .delete(delete_project_repository),
         )
+        .route(
+            "/{project_id}/repositories/{repo_id}/merge-queue",
+            get(merge_queue::get_merge_queue_config)
+                .put(merge_queue::set_merge_queue_config)
+                .delete(merge_queue::delete_merge_queue_config),
+        )
         .route("/stream/ws", get(stream_projects_ws))
         .nest("/{id}", project_id_router);
 
@@ -646,3 +658,38 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         get(get_remote_project_by_id),
     )
 }
+
+/// Project settings that control what agents may do and spend: sandbox,
+/// approval policies and budget. Mounted with the admin scope.
+pub fn admin_router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let project_id_router = Router::new()
+        .route(
+            "/approval-policies",
+            get(approvals::list_policy_rules).post(approvals::create_policy_rule),
+        )
+        .route(
+            "/budget",
+            get(usage::get_project_budget)
+                .put(usage::set_project_budget)
+                .delete(usage::delete_project_budget),
+        )
+        .route(
+            "/sandbox",
+            get(sandbox::get_project_sandbox)
+                .put(sandbox::set_project_sandbox)
+                .delete(sandbox::delete_project_sandbox),
+        )
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_middleware,
+        ));
+
+    let projects_router = Router::new()
+        .route(
+            "/{project_id}/approval-policies/{rule_id}",
+            put(approvals::update_policy_rule).delete(approvals::delete_policy_rule),
+        )
+        .nest("/{id}", project_id_router);
+
+    Router::new().nest("/projects", projects_router)
+}
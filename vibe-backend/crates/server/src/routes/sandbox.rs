@@ -0,0 +1,88 @@
+//! Per-project sandbox for coding agents and scripts.
+//!
+//! Handlers are mounted on the project router so they reuse its loading
+//! middleware.
+
+use std::path::Path;
+
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use db::models::{
+    project::Project,
+    project_sandbox::{ProjectSandboxConfig, UpsertProjectSandboxConfig},
+};
+use deployment::Deployment;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+fn validate_sandbox_config(payload: &UpsertProjectSandboxConfig) -> Result<(), ApiError> {
+    if payload.memory_limit_mb.is_some_and(|mb| mb <= 0) {
+        return Err(ApiError::BadRequest(
+            "Memory limit must be positive".to_string(),
+        ));
+    }
+    if payload
+        .cpu_limit_percent
+        .is_some_and(|percent| percent <= 0)
+    {
+        return Err(ApiError::BadRequest(
+            "CPU limit must be positive".to_string(),
+        ));
+    }
+    for path in &payload.writable_paths {
+        let absolute = path.starts_with("~/") || Path::new(path).is_absolute();
+        if !absolute || Path::new(path) == Path::new("/") || path == "~/" {
+            return Err(ApiError::BadRequest(format!(
+                "Writable path must be an absolute or ~/ path below the root: {path}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+pub async fn get_project_sandbox(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectSandboxConfig>>>, ApiError> {
+    let config =
+        ProjectSandboxConfig::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(config)))
+}
+
+/// Create or replace the project's sandbox; applies to executions started
+/// afterwards
+pub async fn set_project_sandbox(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpsertProjectSandboxConfig>,
+) -> Result<ResponseJson<ApiResponse<ProjectSandboxConfig>>, ApiError> {
+    validate_sandbox_config(&payload)?;
+    let config = ProjectSandboxConfig::upsert(&deployment.db().pool, project.id, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_sandbox_set",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "enabled": config.enabled,
+                "allow_network": config.allow_network,
+                "has_resource_limits": config.memory_limit_mb.is_some()
+                    || config.cpu_limit_percent.is_some(),
+                "has_env_allowlist": config.env_allowlist.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(config)))
+}
+
+pub async fn delete_project_sandbox(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = ProjectSandboxConfig::delete(&deployment.db().pool, project.id).await?;
+    if rows_affected == 0 {
+        return Err(ApiError::NotFound("Project has no sandbox".to_string()));
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
@@ -0,0 +1,41 @@
+use axum::{
+    Extension, Router,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+    routing::get,
+};
+use db::models::{
+    search::{Search, SearchQuery, SearchResult},
+    tenant_workspace::TenantWorkspace,
+};
+use deployment::Deployment;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::auth::ClerkUser};
+
+/// Ranked full-text search across tasks, comments, documents and project
+/// updates in the caller's workspaces. Requires a signed-in user.
+pub async fn search(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<ClerkUser>>,
+    Query(query): Query<SearchQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<SearchResult>>>, ApiError> {
+    let Some(Extension(user)) = user else {
+        return Err(ApiError::Unauthorized);
+    };
+    let pool = &deployment.db().pool;
+
+    let workspace_ids: Vec<Uuid> = TenantWorkspace::find_all_for_user(pool, &user.user_id)
+        .await?
+        .into_iter()
+        .map(|workspace| workspace.id)
+        .collect();
+    let results = Search::search_as_member(pool, &query, workspace_ids).await?;
+
+    Ok(ResponseJson(ApiResponse::success(results)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/search", get(search))
+}
@@ -0,0 +1,96 @@
+use axum::{
+    Extension, Json, Router,
+    extract::State,
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::{
+    session::Session,
+    workspace::{Workspace, WorkspaceError},
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::checkpoint::{self, SessionCheckpoints};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_session_middleware};
+
+/// Request body for rolling a session's workspace back to a turn
+#[derive(Debug, Deserialize, TS)]
+pub struct RollbackToCheckpointRequest {
+    /// Turn whose checkpoint the workspace is reset to; its changes and those
+    /// of every later turn are discarded
+    pub execution_process_id: Uuid,
+    /// Also drop the discarded turns so the next follow-up forks the agent
+    /// session from before them
+    #[serde(default)]
+    pub fork_session: bool,
+    pub force_when_dirty: Option<bool>,
+}
+
+async fn session_workspace(
+    deployment: &DeploymentImpl,
+    session: &Session,
+) -> Result<Workspace, ApiError> {
+    Workspace::find_by_id(&deployment.db().pool, session.workspace_id)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::ValidationError(
+            "Workspace not found".to_string(),
+        )))
+}
+
+/// Checkpoints taken before each follow-up, with the changes made since
+pub async fn get_checkpoints(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<SessionCheckpoints>>, ApiError> {
+    let workspace = session_workspace(&deployment, &session).await?;
+    let checkpoints =
+        checkpoint::list_checkpoints(deployment.container(), &workspace, &session).await?;
+    Ok(ResponseJson(ApiResponse::success(checkpoints)))
+}
+
+pub async fn rollback_to_checkpoint(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<RollbackToCheckpointRequest>,
+) -> Result<ResponseJson<ApiResponse<SessionCheckpoints>>, ApiError> {
+    let workspace = session_workspace(&deployment, &session).await?;
+    checkpoint::rollback_to_turn(
+        deployment.container(),
+        &workspace,
+        &session,
+        payload.execution_process_id,
+        payload.fork_session,
+        payload.force_when_dirty.unwrap_or(false),
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "checkpoint_rolled_back",
+            serde_json::json!({
+                "session_id": session.id.to_string(),
+                "workspace_id": workspace.id.to_string(),
+                "fork_session": payload.fork_session,
+            }),
+        )
+        .await;
+
+    let checkpoints =
+        checkpoint::list_checkpoints(deployment.container(), &workspace, &session).await?;
+    Ok(ResponseJson(ApiResponse::success(checkpoints)))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/", get(get_checkpoints))
+        .route("/rollback", post(rollback_to_checkpoint))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_session_middleware,
+        ))
+}
@@ -1,3 +1,4 @@
+pub mod checkpoints;
 pub mod queue;
 
 use axum::{
@@ -242,7 +243,8 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let sessions_router = Router::new()
         .route("/", get(get_sessions).post(create_session))
         .nest("/{session_id}", session_id_router)
-        .nest("/{session_id}/queue", queue::router(deployment));
+        .nest("/{session_id}/queue", queue::router(deployment))
+        .nest("/{session_id}/checkpoints", checkpoints::router(deployment));
 
     Router::new().nest("/sessions", sessions_router)
 }
@@ -4,10 +4,13 @@
 //! - Google Drive
 //! - AWS S3
 //! - Dropbox
+//!
+//! plus syncing a team's documents to whichever provider is connected.
 
 pub mod dropbox;
 pub mod google_drive;
 pub mod s3;
+pub mod sync;
 
 use axum::{
     Router,
@@ -46,5 +49,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/storage/dropbox/callback", get(dropbox::oauth_callback))
         .route("/storage/dropbox/disconnect", post(dropbox::disconnect))
         .route("/storage/dropbox/status", get(dropbox::get_status))
+        // Document sync
+        .route("/storage/sync", post(sync::sync_documents))
         .with_state(deployment.clone())
 }
@@ -5,13 +5,30 @@
 use axum::{
     Json,
     extract::{Query, State},
-    response::Response,
+    http::StatusCode,
+    response::{IntoResponse, Response},
 };
+use db::models::team_storage_config::{TeamStorageConfig, UpsertTeamStorageConfig};
+use deployment::Deployment;
 use serde::{Deserialize, Serialize};
+use services::services::cloud_storage::{
+    encryption::encrypt_credential,
+    s3::{S3Config, validate_config},
+};
 use uuid::Uuid;
 
 use crate::DeploymentImpl;
 
+const PROVIDER: &str = "s3";
+
+fn error_response(status: StatusCode, message: impl ToString) -> Response {
+    (
+        status,
+        Json(serde_json::json!({ "error": message.to_string() })),
+    )
+        .into_response()
+}
+
 /// S3 configuration request
 #[derive(Debug, Deserialize)]
 pub struct S3ConfigRequest {
@@ -21,6 +38,8 @@ pub struct S3ConfigRequest {
     pub prefix: Option<String>,
     pub access_key_id: String,
     pub secret_access_key: String,
+    /// For S3-compatible services such as MinIO
+    pub endpoint: Option<String>,
 }
 
 /// Validation response
@@ -127,7 +146,7 @@ pub async fn validate(
 
 /// Configure S3 for a team
 pub async fn configure(
-    State(_deployment): State<DeploymentImpl>,
+    State(deployment): State<DeploymentImpl>,
     Json(req): Json<S3ConfigRequest>,
 ) -> Result<Json<serde_json::Value>, Response> {
     tracing::info!(
@@ -136,8 +155,36 @@ pub async fn configure(
         req.bucket
     );
 
-    // TODO: Encrypt credentials
-    // TODO: Store in team_storage_configs
+    let mut config = S3Config {
+        bucket: req.bucket,
+        region: req.region,
+        prefix: req.prefix,
+        access_key_id: req.access_key_id,
+        secret_access_key: req.secret_access_key,
+        endpoint: req.endpoint,
+    };
+    validate_config(&config).map_err(|e| error_response(StatusCode::BAD_REQUEST, e))?;
+    config.secret_access_key = encrypt_credential(&config.secret_access_key)
+        .map_err(|e| error_response(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    let config_data = serde_json::to_value(&config)
+        .map_err(|e| error_response(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    TeamStorageConfig::upsert(
+        &deployment.db().pool,
+        req.team_id,
+        &UpsertTeamStorageConfig {
+            provider: PROVIDER.to_string(),
+            access_token: None,
+            refresh_token: None,
+            token_expires_at: None,
+            folder_id: Some(config.bucket),
+            config_data,
+            connected_email: None,
+            connected_account_id: None,
+        },
+    )
+    .await
+    .map_err(|e| error_response(StatusCode::INTERNAL_SERVER_ERROR, e))?;
 
     Ok(Json(serde_json::json!({
         "success": true,
@@ -147,12 +194,14 @@ pub async fn configure(
 
 /// Disconnect S3 from team
 pub async fn disconnect(
-    State(_deployment): State<DeploymentImpl>,
+    State(deployment): State<DeploymentImpl>,
     Json(req): Json<DisconnectRequest>,
 ) -> Result<Json<serde_json::Value>, Response> {
     tracing::info!("Disconnecting S3 for team: {}", req.team_id);
 
-    // TODO: Delete from team_storage_configs
+    TeamStorageConfig::delete(&deployment.db().pool, req.team_id, PROVIDER)
+        .await
+        .map_err(|e| error_response(StatusCode::INTERNAL_SERVER_ERROR, e))?;
 
     Ok(Json(serde_json::json!({
         "success": true,
@@ -162,19 +211,34 @@ pub async fn disconnect(
 
 /// Get S3 connection status for team
 pub async fn get_status(
-    State(_deployment): State<DeploymentImpl>,
+    State(deployment): State<DeploymentImpl>,
     Query(query): Query<StatusQuery>,
 ) -> Result<Json<StatusResponse>, Response> {
     tracing::info!("Getting S3 status for team: {}", query.team_id);
 
-    // TODO: Query team_storage_configs for s3 provider
-
-    // For now, return not connected (placeholder)
-    Ok(Json(StatusResponse {
-        connected: false,
-        provider: "s3".to_string(),
-        bucket: None,
-        region: None,
-        prefix: None,
+    let config = TeamStorageConfig::find_by_team_and_provider(
+        &deployment.db().pool,
+        query.team_id,
+        PROVIDER,
+    )
+    .await
+    .map_err(|e| error_response(StatusCode::INTERNAL_SERVER_ERROR, e))?
+    .and_then(|config| serde_json::from_value::<S3Config>(config.config_data).ok());
+
+    Ok(Json(match config {
+        Some(config) => StatusResponse {
+            connected: true,
+            provider: PROVIDER.to_string(),
+            bucket: Some(config.bucket),
+            region: Some(config.region),
+            prefix: config.prefix,
+        },
+        None => StatusResponse {
+            connected: false,
+            provider: PROVIDER.to_string(),
+            bucket: None,
+            region: None,
+            prefix: None,
+        },
     }))
 }
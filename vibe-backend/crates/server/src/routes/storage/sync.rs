@@ -0,0 +1,61 @@
+//! Document Sync Routes
+//!
+//! Mirrors a team's documents to its connected storage provider.
+
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::cloud_storage::{
+    CloudStorageError,
+    sync::{ConflictPolicy, DocumentSyncEngine, SyncReport},
+};
+use uuid::Uuid;
+
+use crate::DeploymentImpl;
+
+/// Sync request body
+#[derive(Debug, Deserialize)]
+pub struct SyncRequest {
+    pub team_id: Uuid,
+    #[serde(default)]
+    pub conflict_policy: ConflictPolicy,
+}
+
+/// Sync the team's documents with its active storage connection
+pub async fn sync_documents(
+    State(deployment): State<DeploymentImpl>,
+    Json(req): Json<SyncRequest>,
+) -> Result<Json<SyncReport>, Response> {
+    tracing::info!("Syncing documents for team: {}", req.team_id);
+
+    let result = match DocumentSyncEngine::for_team(deployment.db().pool.clone(), req.team_id).await
+    {
+        Ok(engine) => {
+            engine
+                .with_policy(req.conflict_policy)
+                .sync_team(req.team_id)
+                .await
+        }
+        Err(error) => Err(error),
+    };
+
+    result.map(Json).map_err(|error| {
+        let status = match error {
+            CloudStorageError::NotConnected(_) | CloudStorageError::Config(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            CloudStorageError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::BAD_GATEWAY,
+        };
+        (
+            status,
+            Json(serde_json::json!({ "error": error.to_string() })),
+        )
+            .into_response()
+    })
+}
@@ -1,8 +1,11 @@
 pub mod codex_setup;
+pub mod conflicts;
 pub mod cursor_setup;
 pub mod gh_cli_setup;
 pub mod images;
 pub mod pr;
+pub mod pr_provider;
+pub mod transcript;
 pub mod util;
 
 use std::{
@@ -46,6 +49,7 @@ use services::services::{
     container::ContainerService,
     git::{ConflictOp, GitCliError, GitServiceError},
     github::GitHubService,
+    merge_queue::merge_commit_message,
 };
 use sqlx::Error as SqlxError;
 use ts_rs::TS;
@@ -53,8 +57,13 @@ use utils::response::ApiResponse;
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, error::ApiError, middleware::load_workspace_middleware,
-    routes::task_attempts::gh_cli_setup::GhCliSetupError,
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_workspace_middleware,
+    routes::{
+        merge_queue, task_attempts::gh_cli_setup::GhCliSetupError,
+        tasks::dependencies::ensure_blockers_done,
+    },
 };
 
 #[derive(Debug, Deserialize, Serialize, TS)]
@@ -141,6 +150,7 @@ pub async fn create_task_attempt(
     let task = Task::find_by_id(&deployment.db().pool, payload.task_id)
         .await?
         .ok_or(SqlxError::RowNotFound)?;
+    ensure_blockers_done(pool, task.id).await?;
 
     let project = task
         .parent_project(pool)
@@ -310,18 +320,19 @@ pub struct PushTaskAttemptRequest {
     pub repo_id: Uuid,
 }
 
-#[axum::debug_handler]
-pub async fn merge_task_attempt(
-    Extension(workspace): Extension<Workspace>,
-    State(deployment): State<DeploymentImpl>,
-    Json(request): Json<MergeTaskAttemptRequest>,
-) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+/// Squash-merge one repository of a workspace into its target branch and
+/// record the merge
+pub(crate) async fn merge_workspace_repo(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    task: &Task,
+    repo_id: Uuid,
+) -> Result<(), ApiError> {
     let pool = &deployment.db().pool;
 
-    let workspace_repo =
-        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id)
-            .await?
-            .ok_or(RepoError::NotFound)?;
+    let workspace_repo = WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
 
     let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
         .await?
@@ -329,34 +340,17 @@ pub async fn merge_task_attempt(
 
     let container_ref = deployment
         .container()
-        .ensure_container_exists(&workspace)
+        .ensure_container_exists(workspace)
         .await?;
     let workspace_path = Path::new(&container_ref);
     let worktree_path = workspace_path.join(repo.name);
 
-    let task = workspace
-        .parent_task(pool)
-        .await?
-        .ok_or(ApiError::Workspace(WorkspaceError::TaskNotFound))?;
-    let task_uuid_str = task.id.to_string();
-    let first_uuid_section = task_uuid_str.split('-').next().unwrap_or(&task_uuid_str);
-
-    let mut commit_message = format!("{} (vibe-kanban {})", task.title, first_uuid_section);
-
-    // Add description on next line if it exists
-    if let Some(description) = &task.description
-        && !description.trim().is_empty()
-    {
-        commit_message.push_str("\n\n");
-        commit_message.push_str(description);
-    }
-
     let merge_commit_id = deployment.git().merge_changes(
         &repo.path,
         &worktree_path,
         &workspace.branch,
         &workspace_repo.target_branch,
-        &commit_message,
+        &merge_commit_message(task),
     )?;
 
     Merge::create_direct(
@@ -367,6 +361,22 @@ pub async fn merge_task_attempt(
         &merge_commit_id,
     )
     .await?;
+    Ok(())
+}
+
+#[axum::debug_handler]
+pub async fn merge_task_attempt(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<MergeTaskAttemptRequest>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::TaskNotFound))?;
+    merge_workspace_repo(&deployment, &workspace, &task, request.repo_id).await?;
     Task::update_status(pool, task.id, TaskStatus::Done).await?;
 
     // Stop any running dev servers for this workspace
@@ -1488,13 +1498,32 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/branch-status", get(get_task_attempt_branch_status))
         .route("/diff/ws", get(stream_task_attempt_diff_ws))
         .route("/merge", post(merge_task_attempt))
+        .route(
+            "/merge-queue",
+            get(merge_queue::get_task_attempt_merge_queue).post(merge_queue::enqueue_task_attempt),
+        )
         .route("/push", post(push_task_attempt_branch))
         .route("/push/force", post(force_push_task_attempt_branch))
         .route("/rebase", post(rebase_task_attempt))
         .route("/conflicts/abort", post(abort_conflicts_task_attempt))
+        .route(
+            "/conflicts/resolve",
+            post(conflicts::resolve_conflicts_task_attempt),
+        )
+        .route(
+            "/conflicts/resolutions",
+            get(conflicts::get_conflict_resolutions),
+        )
         .route("/pr", post(pr::create_github_pr))
         .route("/pr/attach", post(pr::attach_existing_pr))
         .route("/pr/comments", get(pr::get_pr_comments))
+        .route("/pr/comments/address", post(pr::address_pr_comments))
+        .route("/pr/review-follow-ups", get(pr::get_pr_review_follow_ups))
+        .route("/pr/ci-repairs", get(pr::get_ci_repairs))
+        .route(
+            "/transcript",
+            get(transcript::export_task_attempt_transcript),
+        )
         .route("/open-editor", post(open_task_attempt_in_editor))
         .route("/children", get(get_task_attempt_children))
         .route("/stop", post(stop_task_attempt_execution))
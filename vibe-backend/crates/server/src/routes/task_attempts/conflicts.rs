@@ -0,0 +1,50 @@
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use db::models::{conflict_resolution::ConflictResolution, workspace::Workspace};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::conflict_resolution;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct ResolveConflictsRequest {
+    pub repo_id: Uuid,
+}
+
+/// Start an agent follow-up resolving the conflicts of a stopped rebase or
+/// merge; it is continued once the agent is done
+pub async fn resolve_conflicts_task_attempt(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<ResolveConflictsRequest>,
+) -> Result<ResponseJson<ApiResponse<ConflictResolution>>, ApiError> {
+    let resolution =
+        conflict_resolution::resolve_conflicts(deployment.container(), &workspace, request.repo_id)
+            .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "conflict_resolution_started",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "repo_id": request.repo_id.to_string(),
+                "conflicted_files": resolution.conflicted_files.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(resolution)))
+}
+
+/// Conflict resolutions of the attempt, newest first
+pub async fn get_conflict_resolutions(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ConflictResolution>>>, ApiError> {
+    let resolutions =
+        ConflictResolution::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(resolutions)))
+}
@@ -6,8 +6,10 @@ use axum::{
     response::Json as ResponseJson,
 };
 use db::models::{
+    ci_repair_attempt::CiRepairAttempt,
     execution_process::{ExecutionProcess, ExecutionProcessRunReason},
     merge::{Merge, MergeStatus},
+    pr_review_followup::PrReviewFollowUp,
     repo::{Repo, RepoError},
     session::{CreateSession, Session},
     task::{Task, TaskStatus},
@@ -25,11 +27,14 @@ use services::services::{
     container::ContainerService,
     git::{GitCliError, GitServiceError},
     github::{CreatePrRequest, GitHubService, GitHubServiceError, UnifiedPrComment},
+    gitlab::GitLabServiceError,
+    pr_review::{self, PrReviewError},
 };
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
+use super::pr_provider::{DEFAULT_MR_DESCRIPTION_PROMPT, PrProvider, resolve_provider};
 use crate::{DeploymentImpl, error::ApiError};
 
 #[derive(Debug, Deserialize, Serialize, TS)]
@@ -52,6 +57,8 @@ pub enum CreatePrError {
     GitCliNotLoggedIn,
     GitCliNotInstalled,
     TargetBranchNotFound { branch: String },
+    GitlabNotConnected { host: String },
+    GitlabAuthFailed,
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -79,6 +86,8 @@ pub enum GetPrCommentsError {
     NoPrAttached,
     GithubCliNotInstalled,
     GithubCliNotLoggedIn,
+    GitlabNotConnected { host: String },
+    GitlabAuthFailed,
 }
 
 #[derive(Debug, Deserialize, TS)]
@@ -86,6 +95,25 @@ pub struct GetPrCommentsQuery {
     pub repo_id: Uuid,
 }
 
+#[derive(Debug, Deserialize, TS)]
+pub struct AddressPrCommentsRequest {
+    pub repo_id: Uuid,
+    /// Limit the follow-up to these comments; all pending ones when omitted
+    pub comment_ids: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum AddressPrCommentsError {
+    NoPrAttached,
+    NoPendingComments,
+    GithubCliNotInstalled,
+    GithubCliNotLoggedIn,
+    /// Only GitHub review threads can be addressed
+    UnsupportedProvider,
+}
+
 pub const DEFAULT_PR_DESCRIPTION_PROMPT: &str = r#"Update the GitHub PR that was just created with a better title and description.
 The PR number is #{pr_number} and the URL is {pr_url}.
 
@@ -104,13 +132,14 @@ async fn trigger_pr_description_follow_up(
     workspace: &Workspace,
     pr_number: i64,
     pr_url: &str,
+    default_prompt: &str,
 ) -> Result<(), ApiError> {
     // Get the custom prompt from config, or use default
     let config = deployment.config().read().await;
     let prompt_template = config
         .pr_auto_description_prompt
         .as_deref()
-        .unwrap_or(DEFAULT_PR_DESCRIPTION_PROMPT);
+        .unwrap_or(default_prompt);
 
     // Replace placeholders in prompt
     let prompt = prompt_template
@@ -206,6 +235,13 @@ pub async fn create_github_pr(
         workspace_repo.target_branch.clone()
     };
 
+    let provider = resolve_provider(&deployment, &repo_path).await?;
+    if let PrProvider::GitLabNotConnected { host } = &provider {
+        return Ok(ResponseJson(ApiResponse::error_with_data(
+            CreatePrError::GitlabNotConnected { host: host.clone() },
+        )));
+    }
+
     let container_ref = deployment
         .container()
         .ensure_container_exists(&workspace)
@@ -238,12 +274,12 @@ pub async fn create_github_pr(
         Ok(true) => {}
     }
 
-    // Push the branch to GitHub first
+    // Push the branch to the remote first
     if let Err(e) = deployment
         .git()
         .push_to_github(&worktree_path, &workspace.branch, false)
     {
-        tracing::error!("Failed to push branch to GitHub: {}", e);
+        tracing::error!("Failed to push branch to remote: {}", e);
         match e {
             GitServiceError::GitCLI(GitCliError::AuthFailed(_)) => {
                 return Ok(ResponseJson(ApiResponse::error_with_data(
@@ -286,76 +322,109 @@ pub async fn create_github_pr(
         base_branch: norm_target_branch_name.clone(),
         draft: request.draft,
     };
-    // Use GitService to get the remote URL, then create GitHubRepoInfo
-    let repo_info = deployment.git().get_github_repo_info(&repo_path)?;
-
-    // Use GitHubService to create the PR
-    let github_service = GitHubService::new()?;
-    match github_service.create_pr(&repo_info, &pr_request).await {
-        Ok(pr_info) => {
-            // Update the workspace with PR information
-            if let Err(e) = Merge::create_pr(
-                pool,
-                workspace.id,
-                workspace_repo.repo_id,
-                &norm_target_branch_name,
-                pr_info.number,
-                &pr_info.url,
-            )
-            .await
-            {
-                tracing::error!("Failed to update workspace PR status: {}", e);
-            }
-
-            // Auto-open PR in browser
-            if let Err(e) = utils::browser::open_browser(&pr_info.url).await {
-                tracing::warn!("Failed to open PR in browser: {}", e);
-            }
-            deployment
-                .track_if_analytics_allowed(
-                    "github_pr_created",
-                    serde_json::json!({
-                        "workspace_id": workspace.id.to_string(),
-                    }),
-                )
-                .await;
-
-            // Trigger auto-description follow-up if enabled
-            if request.auto_generate_description
-                && let Err(e) = trigger_pr_description_follow_up(
-                    &deployment,
-                    &workspace,
-                    pr_info.number,
-                    &pr_info.url,
-                )
-                .await
-            {
-                tracing::warn!(
-                    "Failed to trigger PR description follow-up for attempt {}: {}",
-                    workspace.id,
-                    e
-                );
+    let is_gitlab = provider.is_gitlab();
+    let pr_info = match provider {
+        PrProvider::GitLab { service, repo_info } => {
+            match service.create_mr(&repo_info, &pr_request).await {
+                Ok(pr_info) => pr_info,
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to create GitLab MR for attempt {}: {}",
+                        workspace.id,
+                        e
+                    );
+                    return match e {
+                        GitLabServiceError::AuthFailed(_) => Ok(ResponseJson(
+                            ApiResponse::error_with_data(CreatePrError::GitlabAuthFailed),
+                        )),
+                        _ => Err(ApiError::GitLabService(e)),
+                    };
+                }
             }
-
-            Ok(ResponseJson(ApiResponse::success(pr_info.url)))
         }
-        Err(e) => {
-            tracing::error!(
-                "Failed to create GitHub PR for attempt {}: {}",
-                workspace.id,
-                e
-            );
-            match &e {
-                GitHubServiceError::GhCliNotInstalled(_) => Ok(ResponseJson(
-                    ApiResponse::error_with_data(CreatePrError::GithubCliNotInstalled),
-                )),
-                GitHubServiceError::AuthFailed(_) => Ok(ResponseJson(
-                    ApiResponse::error_with_data(CreatePrError::GithubCliNotLoggedIn),
-                )),
-                _ => Err(ApiError::GitHubService(e)),
+        // Not-connected GitLab remotes returned before pushing
+        PrProvider::GitHub | PrProvider::GitLabNotConnected { .. } => {
+            // Use GitService to get the remote URL, then create GitHubRepoInfo
+            let repo_info = deployment.git().get_github_repo_info(&repo_path)?;
+
+            // Use GitHubService to create the PR
+            let github_service = GitHubService::new()?;
+            match github_service.create_pr(&repo_info, &pr_request).await {
+                Ok(pr_info) => pr_info,
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to create GitHub PR for attempt {}: {}",
+                        workspace.id,
+                        e
+                    );
+                    return match &e {
+                        GitHubServiceError::GhCliNotInstalled(_) => Ok(ResponseJson(
+                            ApiResponse::error_with_data(CreatePrError::GithubCliNotInstalled),
+                        )),
+                        GitHubServiceError::AuthFailed(_) => Ok(ResponseJson(
+                            ApiResponse::error_with_data(CreatePrError::GithubCliNotLoggedIn),
+                        )),
+                        _ => Err(ApiError::GitHubService(e)),
+                    };
+                }
             }
         }
+    };
+
+    // Update the workspace with PR information
+    if let Err(e) = Merge::create_pr(
+        pool,
+        workspace.id,
+        workspace_repo.repo_id,
+        &norm_target_branch_name,
+        pr_info.number,
+        &pr_info.url,
+    )
+    .await
+    {
+        tracing::error!("Failed to update workspace PR status: {}", e);
+    }
+
+    // Auto-open PR in browser
+    if let Err(e) = utils::browser::open_browser(&pr_info.url).await {
+        tracing::warn!("Failed to open PR in browser: {}", e);
+    }
+    deployment
+        .track_if_analytics_allowed(
+            if is_gitlab {
+                "gitlab_mr_created"
+            } else {
+                "github_pr_created"
+            },
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+            }),
+        )
+        .await;
+
+    // Trigger auto-description follow-up if enabled
+    if request.auto_generate_description
+        && let Err(e) = trigger_pr_description_follow_up(
+            &deployment,
+            &workspace,
+            pr_info.number,
+            &pr_info.url,
+            if is_gitlab {
+                DEFAULT_MR_DESCRIPTION_PROMPT
+            } else {
+                DEFAULT_PR_DESCRIPTION_PROMPT
+            },
+        )
+        .await
+    {
+        tracing::warn!(
+            "Failed to trigger PR description follow-up for attempt {}: {}",
+            workspace.id,
+            e
+        );
     }
+
+    Ok(ResponseJson(ApiResponse::success(pr_info.url)))
 }
 
 pub async fn attach_existing_pr(
@@ -390,13 +459,26 @@ pub async fn attach_existing_pr(
         })));
     }
 
-    let github_service = GitHubService::new()?;
-    let repo_info = deployment.git().get_github_repo_info(&repo.path)?;
-
     // List all PRs for branch (open, closed, and merged)
-    let prs = github_service
-        .list_all_prs_for_branch(&repo_info, &workspace.branch)
-        .await?;
+    let prs = match resolve_provider(&deployment, &repo.path).await? {
+        PrProvider::GitHub => {
+            let github_service = GitHubService::new()?;
+            let repo_info = deployment.git().get_github_repo_info(&repo.path)?;
+            github_service
+                .list_all_prs_for_branch(&repo_info, &workspace.branch)
+                .await?
+        }
+        PrProvider::GitLab { service, repo_info } => {
+            service
+                .list_all_mrs_for_branch(&repo_info, &workspace.branch)
+                .await?
+        }
+        PrProvider::GitLabNotConnected { host } => {
+            return Err(ApiError::BadRequest(format!(
+                "No GitLab connection configured for {host}"
+            )));
+        }
+    };
 
     // Take the first PR (prefer open, but also accept merged/closed)
     if let Some(pr_info) = prs.into_iter().next() {
@@ -489,7 +571,38 @@ pub async fn get_pr_comments(
         }
     };
 
-    let github_service = GitHubService::new()?;
+    let github_service = match resolve_provider(&deployment, &repo.path).await? {
+        PrProvider::GitHub => GitHubService::new()?,
+        PrProvider::GitLab { service, repo_info } => {
+            return match service
+                .get_mr_comments(&repo_info, pr_info.number, &pr_info.url)
+                .await
+            {
+                Ok(comments) => Ok(ResponseJson(ApiResponse::success(PrCommentsResponse {
+                    comments,
+                }))),
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to fetch MR comments for attempt {}, MR !{}: {}",
+                        workspace.id,
+                        pr_info.number,
+                        e
+                    );
+                    match e {
+                        GitLabServiceError::AuthFailed(_) => Ok(ResponseJson(
+                            ApiResponse::error_with_data(GetPrCommentsError::GitlabAuthFailed),
+                        )),
+                        _ => Err(ApiError::GitLabService(e)),
+                    }
+                }
+            };
+        }
+        PrProvider::GitLabNotConnected { host } => {
+            return Ok(ResponseJson(ApiResponse::error_with_data(
+                GetPrCommentsError::GitlabNotConnected { host },
+            )));
+        }
+    };
     let repo_info = deployment.git().get_github_repo_info(&repo.path)?;
 
     // Fetch comments from GitHub
@@ -519,3 +632,92 @@ pub async fn get_pr_comments(
         }
     }
 }
+
+/// Start a follow-up that addresses the PR's unresolved review comments. The
+/// branch is pushed and the threads are answered when it finishes.
+pub async fn address_pr_comments(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<AddressPrCommentsRequest>,
+) -> Result<ResponseJson<ApiResponse<PrReviewFollowUp, AddressPrCommentsError>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let workspace_repo =
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+
+    let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    if !matches!(
+        resolve_provider(&deployment, &repo.path).await?,
+        PrProvider::GitHub
+    ) {
+        return Ok(ResponseJson(ApiResponse::error_with_data(
+            AddressPrCommentsError::UnsupportedProvider,
+        )));
+    }
+
+    let github_service = GitHubService::new()?;
+    let follow_up = match pr_review::address_review_comments(
+        deployment.container(),
+        &github_service,
+        &workspace,
+        &repo,
+        request.comment_ids.as_deref(),
+    )
+    .await
+    {
+        Ok(follow_up) => follow_up,
+        Err(e) => {
+            let error = match e {
+                PrReviewError::NoPrAttached => AddressPrCommentsError::NoPrAttached,
+                PrReviewError::NoPendingComments => AddressPrCommentsError::NoPendingComments,
+                PrReviewError::GitHub(GitHubServiceError::GhCliNotInstalled(_)) => {
+                    AddressPrCommentsError::GithubCliNotInstalled
+                }
+                PrReviewError::GitHub(GitHubServiceError::AuthFailed(_)) => {
+                    AddressPrCommentsError::GithubCliNotLoggedIn
+                }
+                e => return Err(e.into()),
+            };
+            return Ok(ResponseJson(ApiResponse::error_with_data(error)));
+        }
+    };
+
+    deployment
+        .track_if_analytics_allowed(
+            "pr_review_comments_addressed",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "pr_number": follow_up.pr_number,
+                "comment_count": follow_up.comments.len(),
+                "automatic": false,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(follow_up)))
+}
+
+/// Review follow-ups of the attempt, newest first
+pub async fn get_pr_review_follow_ups(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<PrReviewFollowUp>>>, ApiError> {
+    let follow_ups =
+        PrReviewFollowUp::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(follow_ups)))
+}
+
+/// CI repairs of the attempt, newest first
+pub async fn get_ci_repairs(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<CiRepairAttempt>>>, ApiError> {
+    let repairs =
+        CiRepairAttempt::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(repairs)))
+}
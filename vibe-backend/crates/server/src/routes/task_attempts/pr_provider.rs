@@ -0,0 +1,72 @@
+//! Picks the hosting provider for a repo's PR routes from its remote URL.
+//! GitHub remotes go through the `gh` CLI; remotes on the connected GitLab
+//! instance use the GitLab API with the workspace connection's token.
+
+use std::path::Path;
+
+use db::models::gitlab_connection::GitLabConnection;
+use deployment::Deployment;
+use services::services::{
+    github::GitHubRepoInfo,
+    gitlab::{GitLabRepoInfo, GitLabService},
+};
+
+use crate::{DeploymentImpl, error::ApiError};
+
+pub const DEFAULT_MR_DESCRIPTION_PROMPT: &str = r#"Update the GitLab merge request that was just created with a better title and description.
+The MR number is !{pr_number} and the URL is {pr_url}.
+
+Analyze the changes in this branch and write:
+1. A concise, descriptive title that summarizes the changes, postfixed with "(Vibe Kanban)"
+2. A detailed description that explains:
+   - What changes were made
+   - Why they were made (based on the task context)
+   - Any important implementation details
+   - At the end, include a note: "This MR was written using [Vibe Kanban](https://vibekanban.com)"
+
+Use `glab mr update` to update the MR."#;
+
+pub enum PrProvider {
+    GitHub,
+    GitLab {
+        service: GitLabService,
+        repo_info: GitLabRepoInfo,
+    },
+    /// The remote looks like GitLab but no connection serves its host
+    GitLabNotConnected {
+        host: String,
+    },
+}
+
+impl PrProvider {
+    pub fn is_gitlab(&self) -> bool {
+        !matches!(self, PrProvider::GitHub)
+    }
+}
+
+pub async fn resolve_provider(
+    deployment: &DeploymentImpl,
+    repo_path: &Path,
+) -> Result<PrProvider, ApiError> {
+    let remote_url = deployment.git().get_remote_url(repo_path)?;
+    if GitHubRepoInfo::from_remote_url(&remote_url).is_ok() {
+        return Ok(PrProvider::GitHub);
+    }
+    let Ok(repo_info) = GitLabRepoInfo::from_remote_url(&remote_url) else {
+        // Unrecognised remote; the GitHub path reports it
+        return Ok(PrProvider::GitHub);
+    };
+
+    if let Some(connection) =
+        GitLabConnection::find_workspace_connection(&deployment.db().pool).await?
+    {
+        let service = GitLabService::from_connection(&connection)?;
+        if service.serves(&repo_info) {
+            return Ok(PrProvider::GitLab { service, repo_info });
+        }
+    }
+
+    Ok(PrProvider::GitLabNotConnected {
+        host: repo_info.host,
+    })
+}
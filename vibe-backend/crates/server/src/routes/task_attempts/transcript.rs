@@ -0,0 +1,58 @@
+use axum::{
+    Extension,
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+};
+use db::models::workspace::Workspace;
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::transcript::export::{TranscriptFormat, build_attempt_transcript, render};
+use utils::text::short_uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize)]
+pub struct TranscriptExportQuery {
+    #[serde(default)]
+    pub format: TranscriptFormat,
+}
+
+/// Download the attempt's full history: every session's conversation, tool
+/// calls and approvals, and the resulting diff
+pub async fn export_task_attempt_transcript(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<TranscriptExportQuery>,
+) -> Result<Response, ApiError> {
+    let transcript = build_attempt_transcript(deployment.container(), &workspace).await?;
+    let body = render(&transcript, query.format);
+
+    deployment
+        .track_if_analytics_allowed(
+            "transcript_exported",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "format": query.format.extension(),
+                "session_count": transcript.sessions.len(),
+            }),
+        )
+        .await;
+
+    let disposition = format!(
+        "attachment; filename=\"attempt-{}.{}\"",
+        short_uuid(&workspace.id),
+        query.format.extension()
+    );
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                query.format.content_type().to_string(),
+            ),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    )
+        .into_response())
+}
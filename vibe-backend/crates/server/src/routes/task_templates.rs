@@ -0,0 +1,355 @@
+use std::collections::HashMap;
+
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use chrono::Utc;
+use db::models::{
+    api_key::{ApiKeyScopes, ApiScope},
+    project::Project,
+    task_template::{
+        CreateTaskTemplate, CreateTaskTemplateRecurrence, TaskTemplate, TaskTemplateRecurrence,
+        UpdateTaskTemplate, UpdateTaskTemplateRecurrence,
+    },
+    team::Team,
+    workspace_repo::CreateWorkspaceRepo,
+};
+use deployment::Deployment;
+use executors::profile::ExecutorProfileId;
+use serde::Deserialize;
+use services::services::task_templates::{self, InstantiatedTask};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{auth::ClerkUser, load_team_middleware},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct TemplatePath {
+    pub template_id: Uuid,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RecurrencePath {
+    pub template_id: Uuid,
+    pub recurrence_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct InstantiateTaskTemplateRequest {
+    /// Values overriding the template's default variables
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+    /// Start an agent attempt for the new task with this profile
+    pub executor_profile_id: Option<ExecutorProfileId>,
+    #[serde(default)]
+    pub repos: Vec<CreateWorkspaceRepo>,
+}
+
+async fn find_team_template(
+    deployment: &DeploymentImpl,
+    team: &Team,
+    template_id: Uuid,
+) -> Result<TaskTemplate, ApiError> {
+    TaskTemplate::find_by_id(&deployment.db().pool, template_id)
+        .await?
+        .filter(|template| template.team_id == team.id)
+        .ok_or_else(|| ApiError::NotFound("Task template not found".to_string()))
+}
+
+async fn find_template_recurrence(
+    deployment: &DeploymentImpl,
+    template: &TaskTemplate,
+    recurrence_id: Uuid,
+) -> Result<TaskTemplateRecurrence, ApiError> {
+    TaskTemplateRecurrence::find_by_id(&deployment.db().pool, recurrence_id)
+        .await?
+        .filter(|recurrence| recurrence.template_id == template.id)
+        .ok_or_else(|| ApiError::NotFound("Recurrence not found".to_string()))
+}
+
+/// Templates may only create tasks in projects that exist
+async fn ensure_project_exists(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+) -> Result<(), ApiError> {
+    Project::find_by_id(&deployment.db().pool, project_id)
+        .await?
+        .map(|_| ())
+        .ok_or_else(|| ApiError::BadRequest(format!("Project {project_id} not found")))
+}
+
+/// Starting an attempt needs the executions scope on top of the tasks scope
+/// these routes are mounted under
+fn ensure_can_start_attempt(
+    scopes: Option<&ApiKeyScopes>,
+    executor_profile_id: Option<&ExecutorProfileId>,
+) -> Result<(), ApiError> {
+    if let (Some(scopes), Some(_)) = (scopes, executor_profile_id) {
+        scopes.require(ApiScope::ExecutionsRun)?;
+    }
+    Ok(())
+}
+
+fn ensure_attempt_repos(
+    executor_profile_id: Option<&ExecutorProfileId>,
+    repos: &[CreateWorkspaceRepo],
+) -> Result<(), ApiError> {
+    if executor_profile_id.is_some() && repos.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one repository is required to start an attempt".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub async fn get_task_templates(
+    Extension(team): Extension<Team>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskTemplate>>>, ApiError> {
+    let templates = TaskTemplate::find_by_team(&deployment.db().pool, team.id).await?;
+    Ok(ResponseJson(ApiResponse::success(templates)))
+}
+
+pub async fn create_task_template(
+    Extension(team): Extension<Team>,
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<ClerkUser>>,
+    Json(payload): Json<CreateTaskTemplate>,
+) -> Result<ResponseJson<ApiResponse<TaskTemplate>>, ApiError> {
+    if payload.name.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "Template name is required".to_string(),
+        ));
+    }
+    ensure_project_exists(&deployment, payload.project_id).await?;
+
+    let created_by = user.map(|Extension(user)| user.user_id);
+    let template = TaskTemplate::create(
+        &deployment.db().pool,
+        team.id,
+        &payload,
+        created_by.as_deref(),
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_template_created",
+            serde_json::json!({
+                "template_id": template.id.to_string(),
+                "team_id": team.id.to_string(),
+                "sub_issue_count": template.sub_issues.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(template)))
+}
+
+pub async fn get_task_template(
+    Extension(team): Extension<Team>,
+    State(deployment): State<DeploymentImpl>,
+    Path(path): Path<TemplatePath>,
+) -> Result<ResponseJson<ApiResponse<TaskTemplate>>, ApiError> {
+    let template = find_team_template(&deployment, &team, path.template_id).await?;
+    Ok(ResponseJson(ApiResponse::success(template)))
+}
+
+pub async fn update_task_template(
+    Extension(team): Extension<Team>,
+    State(deployment): State<DeploymentImpl>,
+    Path(path): Path<TemplatePath>,
+    Json(payload): Json<UpdateTaskTemplate>,
+) -> Result<ResponseJson<ApiResponse<TaskTemplate>>, ApiError> {
+    let existing = find_team_template(&deployment, &team, path.template_id).await?;
+    if let Some(project_id) = payload.project_id {
+        ensure_project_exists(&deployment, project_id).await?;
+    }
+    let template = TaskTemplate::update(&deployment.db().pool, &existing, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(template)))
+}
+
+pub async fn delete_task_template(
+    Extension(team): Extension<Team>,
+    State(deployment): State<DeploymentImpl>,
+    Path(path): Path<TemplatePath>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let template = find_team_template(&deployment, &team, path.template_id).await?;
+    TaskTemplate::delete(&deployment.db().pool, template.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Create a task (and its sub-issues) from a template, optionally starting an
+/// agent attempt for it
+pub async fn instantiate_task_template(
+    Extension(team): Extension<Team>,
+    scopes: Option<Extension<ApiKeyScopes>>,
+    State(deployment): State<DeploymentImpl>,
+    Path(path): Path<TemplatePath>,
+    Json(payload): Json<InstantiateTaskTemplateRequest>,
+) -> Result<ResponseJson<ApiResponse<InstantiatedTask>>, ApiError> {
+    ensure_can_start_attempt(scopes.as_deref(), payload.executor_profile_id.as_ref())?;
+    let template = find_team_template(&deployment, &team, path.template_id).await?;
+    ensure_attempt_repos(payload.executor_profile_id.as_ref(), &payload.repos)?;
+
+    let instantiated = task_templates::instantiate(
+        &deployment.db().pool,
+        &template,
+        &payload.variables,
+        Utc::now(),
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_template_instantiated",
+            serde_json::json!({
+                "template_id": template.id.to_string(),
+                "task_id": instantiated.task.id.to_string(),
+                "auto_start": payload.executor_profile_id.is_some(),
+            }),
+        )
+        .await;
+
+    if let Some(executor_profile_id) = payload.executor_profile_id
+        && let Err(err) = task_templates::start_attempt(
+            deployment.container(),
+            &instantiated.task,
+            executor_profile_id,
+            &payload.repos,
+        )
+        .await
+    {
+        tracing::error!("Failed to start task attempt: {}", err);
+    }
+
+    Ok(ResponseJson(ApiResponse::success(instantiated)))
+}
+
+pub async fn get_template_recurrences(
+    Extension(team): Extension<Team>,
+    State(deployment): State<DeploymentImpl>,
+    Path(path): Path<TemplatePath>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskTemplateRecurrence>>>, ApiError> {
+    let template = find_team_template(&deployment, &team, path.template_id).await?;
+    let recurrences =
+        TaskTemplateRecurrence::find_by_template(&deployment.db().pool, template.id).await?;
+    Ok(ResponseJson(ApiResponse::success(recurrences)))
+}
+
+pub async fn create_template_recurrence(
+    Extension(team): Extension<Team>,
+    scopes: Option<Extension<ApiKeyScopes>>,
+    State(deployment): State<DeploymentImpl>,
+    Path(path): Path<TemplatePath>,
+    Json(payload): Json<CreateTaskTemplateRecurrence>,
+) -> Result<ResponseJson<ApiResponse<TaskTemplateRecurrence>>, ApiError> {
+    ensure_can_start_attempt(scopes.as_deref(), payload.executor_profile_id.as_ref())?;
+    let template = find_team_template(&deployment, &team, path.template_id).await?;
+    ensure_attempt_repos(payload.executor_profile_id.as_ref(), &payload.repos)?;
+
+    let now = Utc::now();
+    let starts_at = payload.starts_at.unwrap_or(now);
+    let next_run_at = task_templates::next_run_at(&payload.rrule, starts_at, now)
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    let recurrence = TaskTemplateRecurrence::create(
+        &deployment.db().pool,
+        template.id,
+        &payload,
+        starts_at,
+        next_run_at,
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_template_recurrence_created",
+            serde_json::json!({
+                "template_id": template.id.to_string(),
+                "recurrence_id": recurrence.id.to_string(),
+                "auto_start": recurrence.executor_profile_id.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(recurrence)))
+}
+
+pub async fn update_template_recurrence(
+    Extension(team): Extension<Team>,
+    scopes: Option<Extension<ApiKeyScopes>>,
+    State(deployment): State<DeploymentImpl>,
+    Path(path): Path<RecurrencePath>,
+    Json(payload): Json<UpdateTaskTemplateRecurrence>,
+) -> Result<ResponseJson<ApiResponse<TaskTemplateRecurrence>>, ApiError> {
+    let template = find_team_template(&deployment, &team, path.template_id).await?;
+    let existing = find_template_recurrence(&deployment, &template, path.recurrence_id).await?;
+
+    let executor_profile_id = if payload.clear_executor_profile {
+        None
+    } else {
+        payload
+            .executor_profile_id
+            .as_ref()
+            .or(existing.executor_profile_id.as_deref())
+    };
+    ensure_can_start_attempt(scopes.as_deref(), executor_profile_id)?;
+    let repos = payload.repos.as_deref().unwrap_or(&existing.repos.0);
+    ensure_attempt_repos(executor_profile_id, repos)?;
+
+    let rrule = payload.rrule.as_deref().unwrap_or(&existing.rrule);
+    let starts_at = payload.starts_at.unwrap_or(existing.starts_at);
+    let next_run_at = task_templates::next_run_at(rrule, starts_at, Utc::now())
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    let recurrence =
+        TaskTemplateRecurrence::update(&deployment.db().pool, &existing, &payload, next_run_at)
+            .await?;
+    Ok(ResponseJson(ApiResponse::success(recurrence)))
+}
+
+pub async fn delete_template_recurrence(
+    Extension(team): Extension<Team>,
+    State(deployment): State<DeploymentImpl>,
+    Path(path): Path<RecurrencePath>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let template = find_team_template(&deployment, &team, path.template_id).await?;
+    let recurrence = find_template_recurrence(&deployment, &template, path.recurrence_id).await?;
+    TaskTemplateRecurrence::delete(&deployment.db().pool, recurrence.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let template_router = Router::new()
+        .route(
+            "/",
+            get(get_task_template)
+                .put(update_task_template)
+                .delete(delete_task_template),
+        )
+        .route("/instantiate", post(instantiate_task_template))
+        .route(
+            "/recurrences",
+            get(get_template_recurrences).post(create_template_recurrence),
+        )
+        .route(
+            "/recurrences/{recurrence_id}",
+            axum::routing::put(update_template_recurrence).delete(delete_template_recurrence),
+        );
+
+    let templates_router = Router::new()
+        .route("/", get(get_task_templates).post(create_task_template))
+        .nest("/{template_id}", template_router)
+        .layer(from_fn_with_state(deployment.clone(), load_team_middleware));
+
+    let inner = Router::new().nest("/{team_id}/task-templates", templates_router);
+    Router::new().nest("/teams", inner)
+}
@@ -1,3 +1,5 @@
+pub mod dependencies;
+
 use std::path::PathBuf;
 
 use anyhow;
@@ -23,7 +25,7 @@ use db::models::{
     task_comment::{CreateTaskComment, TaskComment, UpdateTaskComment},
     task_document_link::{LinkDocumentsRequest, LinkedDocument, TaskDocumentLink},
     task_tag::{AddTagRequest, TaskTag, TaskTagWithDetails},
-    workspace::{CreateWorkspace, Workspace},
+    workspace::Workspace,
     workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
 };
 use deployment::Deployment;
@@ -31,7 +33,8 @@ use executors::profile::ExecutorProfileId;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
 use services::services::{
-    container::ContainerService, share::ShareError, workspace_manager::WorkspaceManager,
+    container::ContainerService, execution_queue::AttemptStart, share::ShareError,
+    workspace_manager::WorkspaceManager,
 };
 use sqlx::Error as SqlxError;
 use ts_rs::TS;
@@ -39,8 +42,10 @@ use utils::{api::oauth::LoginStatus, response::ApiResponse};
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, error::ApiError, middleware::load_task_middleware,
-    routes::task_attempts::WorkspaceRepoInput,
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_task_middleware,
+    routes::{task_attempts::WorkspaceRepoInput, usage},
 };
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -184,33 +189,6 @@ pub async fn create_task_and_start(
         )
         .await;
 
-    let project = Project::find_by_id(pool, task.project_id)
-        .await?
-        .ok_or(ProjectError::ProjectNotFound)?;
-
-    let attempt_id = Uuid::new_v4();
-    let git_branch_name = deployment
-        .container()
-        .git_branch_from_workspace(&attempt_id, &task.title)
-        .await;
-
-    let agent_working_dir = project
-        .default_agent_working_dir
-        .as_ref()
-        .filter(|dir: &&String| !dir.is_empty())
-        .cloned();
-
-    let workspace = Workspace::create(
-        pool,
-        &CreateWorkspace {
-            branch: git_branch_name,
-            agent_working_dir,
-        },
-        attempt_id,
-        task.id,
-    )
-    .await?;
-
     let workspace_repos: Vec<CreateWorkspaceRepo> = payload
         .repos
         .iter()
@@ -219,14 +197,17 @@ pub async fn create_task_and_start(
             target_branch: r.target_branch.clone(),
         })
         .collect();
-    WorkspaceRepo::create_many(&deployment.db().pool, workspace.id, &workspace_repos).await?;
+    let workspace = deployment
+        .container()
+        .create_task_workspace(&task, &workspace_repos)
+        .await?;
 
-    let is_attempt_running = deployment
+    let attempt_start = deployment
         .container()
         .start_workspace(&workspace, payload.executor_profile_id.clone())
         .await
         .inspect_err(|err| tracing::error!("Failed to start task attempt: {}", err))
-        .is_ok();
+        .ok();
     deployment
         .track_if_analytics_allowed(
             "task_attempt_started",
@@ -246,7 +227,8 @@ pub async fn create_task_and_start(
     tracing::info!("Started attempt for task {}", task.id);
     Ok(ResponseJson(ApiResponse::success(TaskWithAttemptStatus {
         task,
-        has_in_progress_attempt: is_attempt_running,
+        has_in_progress_attempt: matches!(attempt_start, Some(AttemptStart::Started(_))),
+        has_queued_attempt: matches!(attempt_start, Some(AttemptStart::Queued { .. })),
         last_attempt_failed: false,
         executor: Some(payload.executor_profile_id.executor.to_string()),
     })))
@@ -1517,6 +1499,10 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         // Task tag routes
         .route("/tags", get(get_task_tags).post(add_task_tag))
         .route("/tags/{tag_id}", delete(remove_task_tag))
+        // Task dependency routes
+        .merge(dependencies::router())
+        // Token usage and spend
+        .route("/usage", get(usage::get_task_usage))
         // Copilot assignment routes
         .route(
             "/copilot",
@@ -1536,9 +1522,13 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let inner = Router::new()
         .route("/", get(get_tasks).post(create_task))
         .route("/stream/ws", get(stream_tasks_ws))
-        .route("/create-and-start", post(create_task_and_start))
         .nest("/{task_id}", task_id_router);
 
     // mount under /projects/:project_id/tasks
     Router::new().nest("/tasks", inner)
 }
+
+/// Task routes that start a coding agent; mounted under the executions scope
+pub fn execution_router() -> Router<DeploymentImpl> {
+    Router::new().route("/tasks/create-and-start", post(create_task_and_start))
+}
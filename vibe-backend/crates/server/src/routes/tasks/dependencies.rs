@@ -0,0 +1,126 @@
+//! "Blocked by" dependencies between tasks.
+//!
+//! Set `ENFORCE_TASK_DEPENDENCIES=true` to refuse starting an attempt for a
+//! task whose blockers are not all done.
+
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+    routing::{delete, get},
+};
+use db::models::{
+    task::Task,
+    task_dependency::{AddDependencyRequest, TaskDependencies, TaskDependency},
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use sqlx::PgPool;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route(
+            "/dependencies",
+            get(get_task_dependencies).post(add_task_dependency),
+        )
+        .route(
+            "/dependencies/{depends_on_task_id}",
+            delete(remove_task_dependency),
+        )
+}
+
+/// List the tasks blocking this task and the tasks it blocks
+pub async fn get_task_dependencies(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<TaskDependencies>>, ApiError> {
+    let dependencies = TaskDependency::find_by_task_id(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(dependencies)))
+}
+
+/// Mark this task as blocked by another task
+pub async fn add_task_dependency(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<AddDependencyRequest>,
+) -> Result<(StatusCode, ResponseJson<ApiResponse<TaskDependency>>), ApiError> {
+    let dependency = TaskDependency::create(
+        &deployment.db().pool,
+        task.id,
+        payload.depends_on_task_id,
+        None,
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_dependency_added",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "depends_on_task_id": payload.depends_on_task_id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok((
+        StatusCode::CREATED,
+        ResponseJson(ApiResponse::success(dependency)),
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TaskDependencyPath {
+    pub depends_on_task_id: Uuid,
+}
+
+/// Remove a dependency from this task
+pub async fn remove_task_dependency(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Path(path): Path<TaskDependencyPath>,
+) -> Result<(StatusCode, ResponseJson<ApiResponse<()>>), ApiError> {
+    let rows =
+        TaskDependency::delete(&deployment.db().pool, task.id, path.depends_on_task_id).await?;
+
+    if rows == 0 {
+        return Err(ApiError::NotFound("Task dependency not found".to_string()));
+    }
+
+    Ok((StatusCode::OK, ResponseJson(ApiResponse::success(()))))
+}
+
+fn dependencies_enforced() -> bool {
+    std::env::var("ENFORCE_TASK_DEPENDENCIES")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+/// Refuse to start work on a task while any of its blockers is unfinished,
+/// when `ENFORCE_TASK_DEPENDENCIES` is enabled
+pub async fn ensure_blockers_done(pool: &PgPool, task_id: Uuid) -> Result<(), ApiError> {
+    if !dependencies_enforced() {
+        return Ok(());
+    }
+
+    let unfinished = TaskDependency::find_unfinished_blockers(pool, task_id).await?;
+    if unfinished.is_empty() {
+        return Ok(());
+    }
+
+    let names: Vec<String> = unfinished
+        .iter()
+        .map(|b| match &b.issue_key {
+            Some(key) => format!("{key} ({})", b.status),
+            None => format!("{} ({})", b.title, b.status),
+        })
+        .collect();
+    Err(ApiError::Conflict(format!(
+        "Task is blocked by unfinished tasks: {}",
+        names.join(", ")
+    )))
+}
@@ -33,7 +33,12 @@ use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError, middleware::load_team_middleware};
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{auth::ClerkUser, load_team_middleware},
+    routes::usage,
+};
 
 /// Query parameters for listing teams
 #[derive(Debug, Deserialize)]
@@ -1067,9 +1072,18 @@ pub async fn push_documents_to_github(
 pub async fn pull_documents_from_github(
     Extension(team): Extension<Team>,
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<ClerkUser>>,
     Path((_team_id, repo_id)): Path<(Uuid, Uuid)>,
 ) -> Result<ResponseJson<ApiResponse<SyncOperationResponse>>, ApiError> {
-    use db::models::document::{CreateDocument, Document, DocumentFolder, UpdateDocument};
+    use db::models::{
+        document::{CreateDocument, Document, DocumentFolder, UpdateDocument},
+        document_revision::{RevisionAuthor, RevisionSource},
+    };
+
+    let author = RevisionAuthor::sync(
+        RevisionSource::GithubSync,
+        user.map(|Extension(user)| user.user_id),
+    );
 
     // Get connection (try team-level first, then fall back to workspace-level)
     let connection = match GitHubConnection::find_by_team_id(&deployment.db().pool, team.id).await?
@@ -1306,7 +1320,7 @@ pub async fn pull_documents_from_github(
                             is_archived: None,
                             position: None,
                         };
-                        Document::update(&deployment.db().pool, doc.id, &update).await?;
+                        Document::update(&deployment.db().pool, doc.id, &update, &author).await?;
                     } else {
                         // Create new document
                         let create = CreateDocument {
@@ -1322,7 +1336,7 @@ pub async fn pull_documents_from_github(
                             storage_provider: None,
                             storage_key: None,
                         };
-                        Document::create(&deployment.db().pool, &create).await?;
+                        Document::create(&deployment.db().pool, &create, &author).await?;
                     }
 
                     synced_files.push(format!("{}/{}", github_path, file_name));
@@ -1918,6 +1932,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let team_router = Router::new()
         .route("/", get(get_team).put(update_team).delete(delete_team))
         .route("/dashboard", get(get_team_dashboard))
+        .route("/usage", get(usage::get_team_usage))
         .route("/issues", get(get_team_issues))
         .route("/migrate-tasks", post(migrate_tasks_to_team))
         .route(
@@ -0,0 +1,131 @@
+//! Token usage and spend of coding agent runs, and per-project budgets.
+//!
+//! Handlers are mounted on the task, project, team and execution process
+//! routers so they reuse those routers' loading middleware.
+
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use db::models::{
+    execution_process::ExecutionProcess,
+    project::Project,
+    project_budget::{BudgetStatus, ProjectBudget, UpsertProjectBudget},
+    task::Task,
+    team::Team,
+    token_usage::{ExecutionProcessUsage, UsageSummary},
+};
+use deployment::Deployment;
+use serde::Serialize;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Project usage for the current budget period alongside lifetime usage
+#[derive(Debug, Serialize, TS)]
+pub struct ProjectUsage {
+    pub total: UsageSummary,
+    /// Usage since the start of the budget period; absent without a budget
+    pub current_period: Option<UsageSummary>,
+    pub budget: Option<BudgetStatus>,
+}
+
+pub async fn get_task_usage(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<UsageSummary>>, ApiError> {
+    let summary = ExecutionProcessUsage::summary_for_task(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(summary)))
+}
+
+pub async fn get_execution_process_usage(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<UsageSummary>>, ApiError> {
+    let summary = ExecutionProcessUsage::summary_for_execution_process(
+        &deployment.db().pool,
+        execution_process.id,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(summary)))
+}
+
+pub async fn get_team_usage(
+    Extension(team): Extension<Team>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<UsageSummary>>, ApiError> {
+    let summary =
+        ExecutionProcessUsage::summary_for_team(&deployment.db().pool, team.id, None).await?;
+    Ok(ResponseJson(ApiResponse::success(summary)))
+}
+
+pub async fn get_project_usage(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectUsage>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let total = ExecutionProcessUsage::summary_for_project(pool, project.id, None).await?;
+    let budget = ProjectBudget::status_for_project(pool, project.id).await?;
+
+    let current_period = match budget.as_ref() {
+        Some(status) if status.period_start.is_some() => Some(
+            ExecutionProcessUsage::summary_for_project(pool, project.id, status.period_start)
+                .await?,
+        ),
+        Some(_) => Some(total.clone()),
+        None => None,
+    };
+
+    Ok(ResponseJson(ApiResponse::success(ProjectUsage {
+        total,
+        current_period,
+        budget,
+    })))
+}
+
+pub async fn get_project_budget(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<BudgetStatus>>>, ApiError> {
+    let status = ProjectBudget::status_for_project(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+/// Create or replace the project's budget
+pub async fn set_project_budget(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpsertProjectBudget>,
+) -> Result<ResponseJson<ApiResponse<BudgetStatus>>, ApiError> {
+    if payload.limit_microdollars < 0 {
+        return Err(ApiError::BadRequest(
+            "Budget limit cannot be negative".to_string(),
+        ));
+    }
+
+    let pool = &deployment.db().pool;
+    let budget = ProjectBudget::upsert(pool, project.id, &payload).await?;
+    let status = budget.status(pool).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_budget_set",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "period": status.budget.period.as_str(),
+                "stop_running": status.budget.stop_running,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+pub async fn delete_project_budget(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = ProjectBudget::delete(&deployment.db().pool, project.id).await?;
+    if rows_affected == 0 {
+        return Err(ApiError::NotFound("Project has no budget".to_string()));
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
@@ -0,0 +1,126 @@
+//! Admin endpoints for the GitHub webhook delivery ledger
+//!
+//! Lists recorded deliveries and replays failed or abandoned ones through
+//! the same handlers used by `/webhooks/github`.
+
+use axum::{
+    Extension, Router,
+    extract::{Path, Query, State},
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::github_webhook_delivery::{GitHubWebhookDelivery, WebhookDeliveryStatus};
+use deployment::Deployment;
+use serde::Deserialize;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl, error::ApiError, middleware::auth::ClerkUser,
+    routes::webhooks::process_delivery,
+};
+
+const DEFAULT_LIST_LIMIT: i64 = 100;
+const MAX_LIST_LIMIT: i64 = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct ListDeliveriesQuery {
+    /// Defaults to `failed`; pass `all` to list every status
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// Only superadmins may inspect or replay deliveries. Without an
+/// authenticated user (API auth disabled) there is nobody to check, so the
+/// routes are closed.
+async fn ensure_superadmin(
+    deployment: &DeploymentImpl,
+    user: Option<&ClerkUser>,
+) -> Result<(), ApiError> {
+    let Some(user) = user else {
+        return Err(ApiError::Unauthorized);
+    };
+    // Runtime query: the superadmins table is owned by the remote crate
+    let is_superadmin = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM superadmins WHERE user_id = $1 AND is_active = true)",
+    )
+    .bind(&user.user_id)
+    .fetch_one(&deployment.db().pool)
+    .await?;
+
+    if user.is_api_key || !is_superadmin {
+        return Err(ApiError::Forbidden(
+            "Superadmin access required".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// List recorded GitHub deliveries (failed ones by default)
+pub async fn list_deliveries(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<ClerkUser>>,
+    Query(query): Query<ListDeliveriesQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<GitHubWebhookDelivery>>>, ApiError> {
+    ensure_superadmin(&deployment, user.as_deref()).await?;
+
+    let status = match query.status.as_deref() {
+        None => Some(WebhookDeliveryStatus::Failed),
+        Some("all") => None,
+        Some(s) => Some(
+            s.parse::<WebhookDeliveryStatus>()
+                .map_err(|_| ApiError::BadRequest(format!("Invalid status: {}", s)))?,
+        ),
+    };
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_LIST_LIMIT)
+        .clamp(1, MAX_LIST_LIMIT);
+
+    let deliveries = GitHubWebhookDelivery::list(&deployment.db().pool, status, limit).await?;
+    Ok(ResponseJson(ApiResponse::success(deliveries)))
+}
+
+/// Re-run a failed or abandoned delivery and return its updated ledger entry
+pub async fn replay_delivery(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<ClerkUser>>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<GitHubWebhookDelivery>>, ApiError> {
+    ensure_superadmin(&deployment, user.as_deref()).await?;
+    let pool = &deployment.db().pool;
+
+    let Some(delivery) = GitHubWebhookDelivery::begin_replay(pool, id).await? else {
+        return match GitHubWebhookDelivery::find_by_id(pool, id).await? {
+            Some(existing) => Err(ApiError::Conflict(format!(
+                "Only failed or abandoned deliveries can be replayed (delivery is {})",
+                existing.status
+            ))),
+            None => Err(ApiError::NotFound("Delivery not found".to_string())),
+        };
+    };
+
+    tracing::info!(
+        "Replaying GitHub delivery {} ({}), attempt {}",
+        delivery.delivery_id,
+        delivery.event_type,
+        delivery.attempts
+    );
+    // The outcome, including any failure, is written to the ledger
+    let _ = process_delivery(&deployment, &delivery).await;
+
+    let delivery = GitHubWebhookDelivery::find_by_id(pool, id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Delivery not found".to_string()))?;
+    Ok(ResponseJson(ApiResponse::success(delivery)))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/webhooks/github/deliveries", get(list_deliveries))
+        .route(
+            "/webhooks/github/deliveries/{id}/replay",
+            post(replay_delivery),
+        )
+        .with_state(deployment.clone())
+}
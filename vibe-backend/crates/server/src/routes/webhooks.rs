@@ -1,9 +1,13 @@
 //! GitHub Webhook Handlers (IKA-93 + IKA-94: Copilot Integration Phases 1 & 2)
 //!
-//! Handles GitHub webhook events for PR tracking, CI status, auto-merge, and deployment.
+//! Handles GitHub webhook events for PR tracking, review follow-ups, CI status and repair,
+//! auto-merge, and deployment.
+//! Deliveries are signature-checked and recorded in `github_webhook_deliveries`
+//! before processing, so duplicates are skipped and failures can be replayed.
 
 use axum::{
     Router,
+    body::Bytes,
     extract::State,
     http::{HeaderMap, StatusCode},
     response::Json as ResponseJson,
@@ -14,12 +18,22 @@ use db::models::{
     copilot_assignment::{CopilotAssignment, UpdateCopilotAssignment},
     copilot_deployment_config::CopilotDeploymentConfig,
     github_connection::GitHubConnection,
+    github_webhook_delivery::{GitHubWebhookDelivery, WebhookDeliveryStatus},
+    merge::Merge,
+    repo::Repo,
     task::{Task, TaskStatus},
     task_comment::{CreateTaskComment, TaskComment},
+    workspace::Workspace,
 };
 use deployment::Deployment;
-use serde::{Deserialize, Serialize};
-use tracing::{error, info};
+use remote::github_app::verify_webhook_signature;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use services::services::{
+    ci_repair::{self, CiRepairOutcome},
+    github::{CiRun, GitHubService},
+    pr_review::{self, PrReviewError},
+};
+use tracing::{error, info, warn};
 
 use crate::DeploymentImpl;
 
@@ -60,6 +74,34 @@ pub struct GitHubOwner {
     pub login: String,
 }
 
+/// GitHub webhook payload for pull request review events
+#[derive(Debug, Deserialize)]
+pub struct GitHubPullRequestReviewEvent {
+    pub action: String,
+    pub review: GitHubReview,
+    pub pull_request: GitHubPullRequest,
+    pub repository: GitHubRepository,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GitHubReview {
+    pub id: i64,
+    /// `commented`, `changes_requested` or `approved`
+    pub state: String,
+    /// `OWNER`, `MEMBER`, `COLLABORATOR`, `CONTRIBUTOR`, `NONE`, ...
+    pub author_association: String,
+}
+
+impl GitHubPullRequestReviewEvent {
+    /// A newly submitted review asking for changes, from someone with access
+    /// to the repository
+    fn needs_follow_up(&self) -> bool {
+        self.action == "submitted"
+            && self.review.state != "approved"
+            && pr_review::is_trusted_author(&self.review.author_association)
+    }
+}
+
 /// GitHub webhook payload for check suite events
 #[derive(Debug, Deserialize)]
 pub struct GitHubCheckSuiteEvent {
@@ -74,10 +116,11 @@ pub struct GitHubCheckSuite {
     pub conclusion: Option<String>,
     pub status: String,
     pub head_branch: Option<String>,
+    pub head_sha: String,
     pub pull_requests: Vec<GitHubCheckSuitePR>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, PartialEq)]
 pub struct GitHubCheckSuitePR {
     pub id: i64,
     pub number: i64,
@@ -98,6 +141,40 @@ pub struct GitHubWorkflowRun {
     pub conclusion: Option<String>,
     pub status: String,
     pub html_url: String,
+    pub head_sha: String,
+    pub pull_requests: Vec<GitHubCheckSuitePR>,
+}
+
+/// A CI run that failed on the head commit of some pull requests
+#[derive(Debug, PartialEq)]
+struct CiFailure<'a> {
+    run: CiRun,
+    head_sha: &'a str,
+    pull_requests: &'a [GitHubCheckSuitePR],
+}
+
+fn is_ci_failure(action: &str, conclusion: Option<&str>) -> bool {
+    action == "completed" && matches!(conclusion, Some("failure" | "timed_out"))
+}
+
+impl GitHubCheckSuiteEvent {
+    fn ci_failure(&self) -> Option<CiFailure<'_>> {
+        is_ci_failure(&self.action, self.check_suite.conclusion.as_deref()).then(|| CiFailure {
+            run: CiRun::CheckSuite(self.check_suite.id),
+            head_sha: &self.check_suite.head_sha,
+            pull_requests: &self.check_suite.pull_requests,
+        })
+    }
+}
+
+impl GitHubWorkflowRunEvent {
+    fn ci_failure(&self) -> Option<CiFailure<'_>> {
+        is_ci_failure(&self.action, self.workflow_run.conclusion.as_deref()).then(|| CiFailure {
+            run: CiRun::WorkflowRun(self.workflow_run.id),
+            head_sha: &self.workflow_run.head_sha,
+            pull_requests: &self.workflow_run.pull_requests,
+        })
+    }
 }
 
 /// Response for webhook endpoints
@@ -111,56 +188,167 @@ pub struct WebhookResponse {
 pub async fn handle_github_webhook(
     State(deployment): State<DeploymentImpl>,
     headers: HeaderMap,
-    body: String,
+    body: Bytes,
 ) -> Result<(StatusCode, ResponseJson<WebhookResponse>), StatusCode> {
     // Get the event type from headers
-    let event_type = headers
-        .get("x-github-event")
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("unknown");
+    let event_type = header_str(&headers, "x-github-event").unwrap_or("unknown");
 
     info!("Received GitHub webhook event: {}", event_type);
 
-    // TODO: Verify webhook signature using GITHUB_WEBHOOK_SECRET
-    // let signature = headers.get("x-hub-signature-256");
+    let secret = std::env::var("GITHUB_WEBHOOK_SECRET").ok();
+    verify_signature(secret.as_deref(), &headers, &body)?;
 
-    match event_type {
-        "pull_request" => {
-            let event: GitHubPullRequestEvent = serde_json::from_str(&body).map_err(|e| {
-                error!("Failed to parse pull_request event: {}", e);
-                StatusCode::BAD_REQUEST
-            })?;
+    let delivery_id = header_str(&headers, "x-github-delivery").ok_or_else(|| {
+        warn!("GitHub webhook missing X-GitHub-Delivery header");
+        StatusCode::BAD_REQUEST
+    })?;
+
+    let payload: serde_json::Value = serde_json::from_slice(&body).map_err(|e| {
+        error!("Failed to parse GitHub webhook body: {}", e);
+        StatusCode::BAD_REQUEST
+    })?;
+    let action = payload.get("action").and_then(|a| a.as_str());
+
+    let delivery = GitHubWebhookDelivery::record(
+        &deployment.db().pool,
+        delivery_id,
+        event_type,
+        action,
+        &payload,
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to record GitHub delivery {}: {}", delivery_id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let Some(delivery) = delivery else {
+        info!("Skipping duplicate GitHub delivery {}", delivery_id);
+        return Ok((
+            StatusCode::OK,
+            ResponseJson(WebhookResponse {
+                received: true,
+                message: format!("Delivery {} already recorded", delivery_id),
+            }),
+        ));
+    };
+
+    let status = process_delivery(&deployment, &delivery).await?;
+
+    Ok((
+        StatusCode::OK,
+        ResponseJson(WebhookResponse {
+            received: true,
+            message: match status {
+                WebhookDeliveryStatus::Ignored => format!("Ignored {} event", event_type),
+                _ => format!("Processed {} event", event_type),
+            },
+        }),
+    ))
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+/// Check `X-Hub-Signature-256` against `GITHUB_WEBHOOK_SECRET`
+fn verify_signature(
+    secret: Option<&str>,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Result<(), StatusCode> {
+    let Some(secret) = secret.filter(|s| !s.is_empty()) else {
+        error!("GITHUB_WEBHOOK_SECRET is not set; rejecting GitHub webhook");
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    };
+
+    let signature = header_str(headers, "x-hub-signature-256").ok_or_else(|| {
+        warn!("GitHub webhook missing X-Hub-Signature-256 header");
+        StatusCode::UNAUTHORIZED
+    })?;
 
-            handle_pull_request_event(&deployment, event).await?;
+    if !verify_webhook_signature(secret.as_bytes(), signature, body) {
+        warn!("GitHub webhook signature mismatch");
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    Ok(())
+}
+
+/// Why a stored delivery could not be processed
+struct DeliveryError {
+    status: StatusCode,
+    message: String,
+}
+
+fn parse_event<T: DeserializeOwned>(
+    event_type: &str,
+    payload: &serde_json::Value,
+) -> Result<T, DeliveryError> {
+    serde_json::from_value(payload.clone()).map_err(|e| {
+        error!("Failed to parse {} event: {}", event_type, e);
+        DeliveryError {
+            status: StatusCode::BAD_REQUEST,
+            message: format!("Failed to parse {} event: {}", event_type, e),
         }
-        "check_suite" => {
-            let event: GitHubCheckSuiteEvent = serde_json::from_str(&body).map_err(|e| {
-                error!("Failed to parse check_suite event: {}", e);
-                StatusCode::BAD_REQUEST
-            })?;
+    })
+}
 
-            handle_check_suite_event(&deployment, event).await?;
+/// Route a delivery to its handler. Returns `false` for events we ignore.
+async fn dispatch_event(
+    deployment: &DeploymentImpl,
+    event_type: &str,
+    payload: &serde_json::Value,
+) -> Result<bool, DeliveryError> {
+    let result = match event_type {
+        "pull_request" => {
+            handle_pull_request_event(deployment, parse_event(event_type, payload)?).await
+        }
+        "pull_request_review" => {
+            handle_pull_request_review_event(deployment, parse_event(event_type, payload)?).await
+        }
+        "check_suite" => {
+            handle_check_suite_event(deployment, parse_event(event_type, payload)?).await
         }
         "workflow_run" => {
-            let event: GitHubWorkflowRunEvent = serde_json::from_str(&body).map_err(|e| {
-                error!("Failed to parse workflow_run event: {}", e);
-                StatusCode::BAD_REQUEST
-            })?;
-
-            handle_workflow_run_event(&deployment, event).await?;
+            handle_workflow_run_event(deployment, parse_event(event_type, payload)?).await
         }
         _ => {
             info!("Ignoring GitHub event type: {}", event_type);
+            return Ok(false);
         }
+    };
+
+    result.map(|_| true).map_err(|status| DeliveryError {
+        status,
+        message: format!("{} handler failed with {}", event_type, status),
+    })
+}
+
+/// Process a recorded delivery and store its outcome in the ledger
+pub(crate) async fn process_delivery(
+    deployment: &DeploymentImpl,
+    delivery: &GitHubWebhookDelivery,
+) -> Result<WebhookDeliveryStatus, StatusCode> {
+    let result = dispatch_event(deployment, &delivery.event_type, &delivery.payload).await;
+
+    let (status, error_message) = match &result {
+        Ok(true) => (WebhookDeliveryStatus::Processed, None),
+        Ok(false) => (WebhookDeliveryStatus::Ignored, None),
+        Err(e) => (WebhookDeliveryStatus::Failed, Some(e.message.as_str())),
+    };
+
+    if let Err(e) =
+        GitHubWebhookDelivery::finish(&deployment.db().pool, delivery.id, status, error_message)
+            .await
+    {
+        error!(
+            "Failed to record outcome of GitHub delivery {}: {}",
+            delivery.delivery_id, e
+        );
     }
 
-    Ok((
-        StatusCode::OK,
-        ResponseJson(WebhookResponse {
-            received: true,
-            message: format!("Processed {} event", event_type),
-        }),
-    ))
+    result.map(|_| status).map_err(|e| e.status)
 }
 
 /// Handle pull request events
@@ -251,6 +439,106 @@ async fn handle_pull_request_event(
     Ok(())
 }
 
+/// Handle pull request review events: when automatic addressing is enabled,
+/// start a follow-up for the new comments on each attempt tracking the PR
+async fn handle_pull_request_review_event(
+    deployment: &DeploymentImpl,
+    event: GitHubPullRequestReviewEvent,
+) -> Result<(), StatusCode> {
+    if !event.needs_follow_up() {
+        return Ok(());
+    }
+    if !deployment.config().read().await.pr_review_auto_address {
+        return Ok(());
+    }
+
+    let pool = &deployment.db().pool;
+    let pr_number = event.pull_request.number;
+    info!(
+        "Processing review {} ({}) for {} PR #{}",
+        event.review.id, event.review.state, event.repository.full_name, pr_number
+    );
+
+    let pr_merges = Merge::find_open_prs_by_url(pool, &event.pull_request.html_url)
+        .await
+        .map_err(|e| {
+            error!("Failed to find merges for PR #{}: {}", pr_number, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    if pr_merges.is_empty() {
+        return Ok(());
+    }
+
+    let github_service = GitHubService::new().map_err(|e| {
+        error!("Failed to create GitHub service: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    for pr_merge in pr_merges {
+        let workspace = Workspace::find_by_id(pool, pr_merge.workspace_id)
+            .await
+            .map_err(|e| {
+                error!("Failed to load workspace {}: {}", pr_merge.workspace_id, e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+        let repo = Repo::find_by_id(pool, pr_merge.repo_id)
+            .await
+            .map_err(|e| {
+                error!("Failed to load repo {}: {}", pr_merge.repo_id, e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+        let (Some(workspace), Some(repo)) = (workspace, repo) else {
+            continue;
+        };
+
+        match pr_review::address_review_comments(
+            deployment.container(),
+            &github_service,
+            &workspace,
+            &repo,
+            None,
+        )
+        .await
+        {
+            Ok(follow_up) => {
+                info!(
+                    "Addressing {} review comments on PR #{} in workspace {}",
+                    follow_up.comments.len(),
+                    pr_number,
+                    workspace.id
+                );
+                deployment
+                    .track_if_analytics_allowed(
+                        "pr_review_comments_addressed",
+                        serde_json::json!({
+                            "workspace_id": workspace.id.to_string(),
+                            "pr_number": pr_number,
+                            "comment_count": follow_up.comments.len(),
+                            "automatic": true,
+                        }),
+                    )
+                    .await;
+            }
+            Err(PrReviewError::NoPendingComments) => {}
+            Err(PrReviewError::AttemptBusy) => {
+                info!(
+                    "Workspace {} is running; leaving review comments on PR #{} for later",
+                    workspace.id, pr_number
+                );
+            }
+            Err(e) => {
+                error!(
+                    "Failed to address review comments on PR #{} in workspace {}: {}",
+                    pr_number, workspace.id, e
+                );
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        }
+    }
+
+    Ok(())
+}
+
 /// Handle PR merged event (Phase 2)
 async fn handle_pr_merged(
     deployment: &DeploymentImpl,
@@ -325,6 +613,10 @@ async fn handle_check_suite_event(
         event.action, repo_owner, repo_name
     );
 
+    if let Some(failure) = event.ci_failure() {
+        repair_ci_failure(deployment, &event.repository, failure).await?;
+    }
+
     // Find assignments for any PRs in this check suite
     for pr in &event.check_suite.pull_requests {
         let assignment = CopilotAssignment::find_by_pr(pool, repo_owner, repo_name, pr.number)
@@ -504,6 +796,118 @@ async fn handle_ci_failure(
     Ok(())
 }
 
+/// When CI repair is enabled, start a repair follow-up on each attempt
+/// tracking one of the failed run's pull requests. The check suite and
+/// workflow run of the same failure start a single repair.
+async fn repair_ci_failure(
+    deployment: &DeploymentImpl,
+    repository: &GitHubRepository,
+    failure: CiFailure<'_>,
+) -> Result<(), StatusCode> {
+    let config = deployment.config().read().await.ci_repair.clone();
+    if !config.enabled || failure.pull_requests.is_empty() {
+        return Ok(());
+    }
+
+    let pool = &deployment.db().pool;
+    let github_service = GitHubService::new().map_err(|e| {
+        error!("Failed to create GitHub service: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    for pr in failure.pull_requests {
+        let pr_url = format!(
+            "https://github.com/{}/pull/{}",
+            repository.full_name, pr.number
+        );
+        let pr_merges = Merge::find_open_prs_by_url(pool, &pr_url)
+            .await
+            .map_err(|e| {
+                error!("Failed to find merges for PR #{}: {}", pr.number, e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+
+        for pr_merge in pr_merges {
+            let workspace = Workspace::find_by_id(pool, pr_merge.workspace_id)
+                .await
+                .map_err(|e| {
+                    error!("Failed to load workspace {}: {}", pr_merge.workspace_id, e);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?;
+            let repo = Repo::find_by_id(pool, pr_merge.repo_id)
+                .await
+                .map_err(|e| {
+                    error!("Failed to load repo {}: {}", pr_merge.repo_id, e);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?;
+            let (Some(workspace), Some(repo)) = (workspace, repo) else {
+                continue;
+            };
+
+            match ci_repair::repair_ci_failure(
+                deployment.container(),
+                &github_service,
+                &workspace,
+                &repo,
+                &pr_merge.pr_info,
+                failure.head_sha,
+                failure.run,
+                &config,
+            )
+            .await
+            {
+                Ok(CiRepairOutcome::Started(attempt)) => {
+                    info!(
+                        "Repairing CI failure of {} on PR #{} in workspace {}",
+                        attempt.head_sha, pr.number, workspace.id
+                    );
+                    deployment
+                        .track_if_analytics_allowed(
+                            "ci_repair_started",
+                            serde_json::json!({
+                                "workspace_id": workspace.id.to_string(),
+                                "pr_number": pr.number,
+                                "failed_job_count": attempt.failed_jobs.len(),
+                            }),
+                        )
+                        .await;
+                }
+                Ok(CiRepairOutcome::Escalated) => {
+                    info!(
+                        "PR #{} in workspace {} is out of CI repairs; escalated to the inbox",
+                        pr.number, workspace.id
+                    );
+                    deployment
+                        .track_if_analytics_allowed(
+                            "ci_repair_escalated",
+                            serde_json::json!({
+                                "workspace_id": workspace.id.to_string(),
+                                "pr_number": pr.number,
+                            }),
+                        )
+                        .await;
+                }
+                Ok(CiRepairOutcome::AttemptBusy) => {
+                    info!(
+                        "Workspace {} is running; not repairing CI on PR #{}",
+                        workspace.id, pr.number
+                    );
+                }
+                Ok(CiRepairOutcome::AlreadyHandled | CiRepairOutcome::Stale) => {}
+                Err(e) => {
+                    error!(
+                        "Failed to repair CI on PR #{} in workspace {}: {}",
+                        pr.number, workspace.id, e
+                    );
+                    return Err(StatusCode::INTERNAL_SERVER_ERROR);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
 /// Trigger auto-merge (Phase 2)
 async fn trigger_auto_merge(
     deployment: &DeploymentImpl,
@@ -723,6 +1127,10 @@ async fn handle_workflow_run_event(
         event.action, event.workflow_run.name, event.workflow_run.id
     );
 
+    if let Some(failure) = event.ci_failure() {
+        repair_ci_failure(deployment, &event.repository, failure).await?;
+    }
+
     // Find assignment by workflow run ID
     let assignment = CopilotAssignment::find_by_workflow_run(pool, event.workflow_run.id)
         .await
@@ -1046,3 +1454,131 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/github", post(handle_github_webhook))
         .with_state(deployment.clone())
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+
+    use super::*;
+
+    fn signed_headers(secret: &str, body: &[u8]) -> HeaderMap {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(body);
+        let signature = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-hub-signature-256",
+            HeaderValue::from_str(&signature).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn accepts_valid_signature() {
+        let body = br#"{"action":"completed"}"#;
+        let headers = signed_headers("s3cret", body);
+        assert!(verify_signature(Some("s3cret"), &headers, body).is_ok());
+    }
+
+    #[test]
+    fn rejects_bad_or_missing_signature() {
+        let body = br#"{"action":"completed"}"#;
+        let headers = signed_headers("other", body);
+        assert_eq!(
+            verify_signature(Some("s3cret"), &headers, body),
+            Err(StatusCode::UNAUTHORIZED)
+        );
+        assert_eq!(
+            verify_signature(Some("s3cret"), &HeaderMap::new(), body),
+            Err(StatusCode::UNAUTHORIZED)
+        );
+    }
+
+    #[test]
+    fn check_suite_failure_is_repairable() {
+        let event: GitHubCheckSuiteEvent = serde_json::from_str(include_str!(
+            "../../tests/fixtures/github/check_suite_completed_failure.json"
+        ))
+        .unwrap();
+
+        let failure = event.ci_failure().unwrap();
+        assert_eq!(failure.run, CiRun::CheckSuite(31526480914));
+        assert_eq!(failure.head_sha, "9f2c4e1b7a3d5c8e0f6a2b4d6e8f0a1c3e5b7d9f");
+        assert_eq!(
+            failure.pull_requests,
+            &[GitHubCheckSuitePR {
+                id: 2291837465,
+                number: 42
+            }]
+        );
+    }
+
+    #[test]
+    fn workflow_run_failure_is_repairable() {
+        let event: GitHubWorkflowRunEvent = serde_json::from_str(include_str!(
+            "../../tests/fixtures/github/workflow_run_completed_failure.json"
+        ))
+        .unwrap();
+
+        let failure = event.ci_failure().unwrap();
+        assert_eq!(failure.run, CiRun::WorkflowRun(13308294471));
+        assert_eq!(failure.head_sha, "9f2c4e1b7a3d5c8e0f6a2b4d6e8f0a1c3e5b7d9f");
+        assert_eq!(failure.pull_requests.len(), 1);
+    }
+
+    #[test]
+    fn passing_or_cancelled_runs_are_not_repaired() {
+        let mut payload: serde_json::Value = serde_json::from_str(include_str!(
+            "../../tests/fixtures/github/check_suite_completed_failure.json"
+        ))
+        .unwrap();
+        for conclusion in ["success", "cancelled"] {
+            payload["check_suite"]["conclusion"] = conclusion.into();
+            let event: GitHubCheckSuiteEvent = serde_json::from_value(payload.clone()).unwrap();
+            assert_eq!(event.ci_failure(), None);
+        }
+
+        payload["action"] = "requested".into();
+        payload["check_suite"]["conclusion"] = "failure".into();
+        let event: GitHubCheckSuiteEvent = serde_json::from_value(payload).unwrap();
+        assert_eq!(event.ci_failure(), None);
+    }
+
+    #[test]
+    fn only_reviews_from_the_repository_need_follow_up() {
+        let mut payload: serde_json::Value = serde_json::from_str(include_str!(
+            "../../tests/fixtures/github/pull_request_review_submitted.json"
+        ))
+        .unwrap();
+        for association in ["OWNER", "MEMBER", "COLLABORATOR"] {
+            payload["review"]["author_association"] = association.into();
+            let event: GitHubPullRequestReviewEvent =
+                serde_json::from_value(payload.clone()).unwrap();
+            assert!(event.needs_follow_up(), "{association}");
+        }
+        for association in ["CONTRIBUTOR", "NONE"] {
+            payload["review"]["author_association"] = association.into();
+            let event: GitHubPullRequestReviewEvent =
+                serde_json::from_value(payload.clone()).unwrap();
+            assert!(!event.needs_follow_up(), "{association}");
+        }
+
+        payload["review"]["author_association"] = "MEMBER".into();
+        payload["review"]["state"] = "approved".into();
+        let event: GitHubPullRequestReviewEvent = serde_json::from_value(payload).unwrap();
+        assert!(!event.needs_follow_up());
+    }
+
+    #[test]
+    fn rejects_when_secret_unset() {
+        let body = b"{}";
+        let headers = signed_headers("s3cret", body);
+        assert_eq!(
+            verify_signature(None, &headers, body),
+            Err(StatusCode::SERVICE_UNAVAILABLE)
+        );
+    }
+}
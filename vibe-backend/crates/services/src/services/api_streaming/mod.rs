@@ -1,11 +1,11 @@
 //! API Streaming Module
 //!
 //! Provides infrastructure for streaming AI provider API responses:
-//! - SSE to LogMsg bridge for converting provider-specific events
+//! - SSE to LogMsg bridge for converting provider-specific events (lives in
+//!   the executors crate so the API-mode coding agent can use it)
 //! - API log writer for persisting and broadcasting responses
 
 mod log_writer;
-mod sse_bridge;
 
+pub use executors::sse_bridge::{AiProvider, SseBridge, SseBridgeError, SseEvent};
 pub use log_writer::ApiLogWriter;
-pub use sse_bridge::{AiProvider, SseBridge, SseBridgeError, SseEvent};
@@ -1,4 +1,5 @@
 pub mod executor_approvals;
+pub mod policy;
 
 use std::{collections::HashMap, sync::Arc, time::Duration as StdDuration};
 
@@ -6,11 +7,12 @@ use dashmap::DashMap;
 use db::models::{
     execution_process::ExecutionProcess,
     task::{Task, TaskStatus},
+    tool_approval::{ApprovalDecision, ToolApproval},
 };
 use executors::{
     approvals::ToolCallMetadata,
     logs::{
-        NormalizedEntry, NormalizedEntryType, ToolStatus,
+        ActionType, NormalizedEntry, NormalizedEntryType, ToolStatus,
         utils::patch::{ConversationPatch, extract_normalized_entry_from_patch},
     },
 };
@@ -74,8 +76,11 @@ impl Approvals {
         }
     }
 
+    /// Register a request that waits for a user decision. The request is
+    /// persisted so it stays visible and answerable across restarts.
     pub async fn create_with_waiter(
         &self,
+        pool: &PgPool,
         request: ApprovalRequest,
     ) -> Result<(ApprovalRequest, ApprovalWaiter), ApprovalError> {
         let (tx, rx) = oneshot::channel();
@@ -84,12 +89,14 @@ impl Approvals {
             .boxed()
             .shared();
         let req_id = request.id.clone();
+        let mut action = None;
 
         if let Some(store) = self.msg_store_by_id(&request.execution_process_id).await {
             // Find the matching tool use entry by name and input
             let matching_tool = find_matching_tool_use(store.clone(), &request.tool_call_id);
 
             if let Some((idx, matching_tool)) = matching_tool {
+                action = action_value(&matching_tool);
                 let approval_entry = matching_tool
                     .with_tool_status(ToolStatus::PendingApproval {
                         approval_id: req_id.clone(),
@@ -129,21 +136,115 @@ impl Approvals {
             );
         }
 
-        self.spawn_timeout_watcher(req_id.clone(), request.timeout_at, waiter.clone());
+        if let Err(e) = ToolApproval::create_pending(pool, &request, action.as_ref()).await {
+            tracing::warn!("Failed to persist approval request {}: {}", req_id, e);
+        }
+
+        self.spawn_timeout_watcher(
+            pool.clone(),
+            req_id.clone(),
+            request.timeout_at,
+            waiter.clone(),
+        );
         Ok((request, waiter))
     }
 
-    #[tracing::instrument(skip(self, id, req))]
+    /// Record a request that a policy rule settled without asking the user
+    pub async fn record_policy_decision(
+        &self,
+        pool: &PgPool,
+        request: &ApprovalRequest,
+        action: Option<&ActionType>,
+        decision: &ApprovalDecision,
+    ) -> Result<(), ApprovalError> {
+        let action = action.and_then(|action| serde_json::to_value(action).ok());
+        ToolApproval::create_decided(pool, request, action.as_ref(), decision).await?;
+        self.completed
+            .insert(request.id.clone(), decision.status.clone());
+
+        // Show policy denials on the tool use entry, as a user denial would
+        if matches!(decision.status, ApprovalStatus::Denied { .. })
+            && let Some(store) = self.msg_store_by_id(&request.execution_process_id).await
+            && let Some((idx, entry)) = find_matching_tool_use(store.clone(), &request.tool_call_id)
+            && let Some(status) = ToolStatus::from_approval_status(&decision.status)
+            && let Some(updated_entry) = entry.with_tool_status(status)
+        {
+            store.push_patch(ConversationPatch::replace(idx, updated_entry));
+        }
+
+        Ok(())
+    }
+
+    /// Normalized action of the tool call awaiting approval, if its tool use
+    /// entry has been logged
+    pub async fn tool_action(
+        &self,
+        execution_process_id: &Uuid,
+        tool_call_id: &str,
+    ) -> Option<ActionType> {
+        let store = self.msg_store_by_id(execution_process_id).await?;
+        let (_, entry) = find_matching_tool_use(store, tool_call_id)?;
+        match entry.entry_type {
+            NormalizedEntryType::ToolUse { action_type, .. } => Some(action_type),
+            _ => None,
+        }
+    }
+
+    /// Re-arm approvals that were pending when the server stopped.
+    ///
+    /// Requests past their deadline are marked timed out; the rest can still
+    /// be answered through `respond` and time out on their original deadline.
+    pub async fn restore_pending(&self, pool: &PgPool) -> Result<usize, ApprovalError> {
+        let expired = ToolApproval::expire_overdue(pool).await?;
+        if !expired.is_empty() {
+            tracing::info!(
+                "Timed out {} approvals that expired while offline",
+                expired.len()
+            );
+        }
+
+        let pending = ToolApproval::find_pending(pool).await?;
+        for approval in &pending {
+            let pool = pool.clone();
+            let completed = self.completed.clone();
+            let id = approval.id.clone();
+            let to_wait = (approval.timeout_at - chrono::Utc::now())
+                .to_std()
+                .unwrap_or_else(|_| StdDuration::from_secs(0));
+
+            tokio::spawn(async move {
+                tokio::time::sleep(to_wait).await;
+                match ToolApproval::resolve(&pool, &id, &ApprovalDecision::timed_out()).await {
+                    Ok(Some(_)) => {
+                        completed.insert(id, ApprovalStatus::TimedOut);
+                    }
+                    Ok(None) => {}
+                    Err(e) => tracing::warn!("Failed to time out approval {}: {}", id, e),
+                }
+            });
+        }
+
+        Ok(pending.len())
+    }
+
+    #[tracing::instrument(skip(self, pool, id, req, user_id))]
     pub async fn respond(
         &self,
         pool: &PgPool,
         id: &str,
         req: ApprovalResponse,
+        user_id: Option<String>,
     ) -> Result<(ApprovalStatus, ToolContext), ApprovalError> {
+        let decision = ApprovalDecision::by_user(req.status.clone(), user_id);
+
         if let Some((_, p)) = self.pending.remove(id) {
             self.completed.insert(id.to_string(), req.status.clone());
             let _ = p.response_tx.send(req.status.clone());
 
+            if let Err(e) = ToolApproval::resolve(pool, id, &decision).await {
+                tracing::warn!("Failed to persist approval decision {}: {}", id, e);
+            }
+
             if let Some(store) = self.msg_store_by_id(&p.execution_process_id).await {
                 let status = ToolStatus::from_approval_status(&req.status).ok_or(
                     ApprovalError::Custom(anyhow::anyhow!("Invalid approval status")),
@@ -166,32 +267,39 @@ impl Approvals {
                 execution_process_id: p.execution_process_id,
             };
 
-            // If approved or denied, and task is still InReview, move back to InProgress
-            if matches!(
-                req.status,
-                ApprovalStatus::Approved | ApprovalStatus::Denied { .. }
-            ) && let Ok(ctx) =
-                ExecutionProcess::load_context(pool, tool_ctx.execution_process_id).await
-                && ctx.task.status == TaskStatus::InReview
-                && let Err(e) = Task::update_status(pool, ctx.task.id, TaskStatus::InProgress).await
-            {
-                tracing::warn!(
-                    "Failed to update task status to InProgress after approval response: {}",
-                    e
-                );
-            }
+            resume_task_after_response(pool, tool_ctx.execution_process_id, &req.status).await;
 
             Ok((req.status, tool_ctx))
         } else if self.completed.contains_key(id) {
             Err(ApprovalError::AlreadyCompleted)
         } else {
-            Err(ApprovalError::NotFound)
+            // Not held in memory: a request restored after a restart, or one
+            // whose tool use entry was never found
+            let stored = ToolApproval::find_by_id(pool, id)
+                .await?
+                .ok_or(ApprovalError::NotFound)?;
+            let resolved = ToolApproval::resolve(pool, id, &decision)
+                .await?
+                .ok_or(ApprovalError::AlreadyCompleted)?;
+            self.completed
+                .insert(id.to_string(), resolved.approval_status());
+
+            resume_task_after_response(pool, stored.execution_process_id, &req.status).await;
+
+            Ok((
+                req.status,
+                ToolContext {
+                    tool_name: stored.tool_name,
+                    execution_process_id: stored.execution_process_id,
+                },
+            ))
         }
     }
 
-    #[tracing::instrument(skip(self, id, timeout_at, waiter))]
+    #[tracing::instrument(skip(self, pool, id, timeout_at, waiter))]
     fn spawn_timeout_watcher(
         &self,
+        pool: PgPool,
         id: String,
         timeout_at: chrono::DateTime<chrono::Utc>,
         waiter: ApprovalWaiter,
@@ -217,6 +325,13 @@ impl Approvals {
             let is_timeout = matches!(&status, ApprovalStatus::TimedOut);
             completed.insert(id.clone(), status.clone());
 
+            if is_timeout
+                && let Err(e) =
+                    ToolApproval::resolve(&pool, &id, &ApprovalDecision::timed_out()).await
+            {
+                tracing::warn!("Failed to persist approval timeout {}: {}", id, e);
+            }
+
             if is_timeout && let Some((_, pending_approval)) = pending.remove(&id) {
                 if pending_approval.response_tx.send(status.clone()).is_err() {
                     tracing::debug!("approval '{}' timeout notification receiver dropped", id);
@@ -258,6 +373,26 @@ impl Approvals {
     }
 }
 
+/// If approved or denied, and task is still InReview, move back to InProgress
+async fn resume_task_after_response(
+    pool: &PgPool,
+    execution_process_id: Uuid,
+    status: &ApprovalStatus,
+) {
+    if matches!(
+        status,
+        ApprovalStatus::Approved | ApprovalStatus::Denied { .. }
+    ) && let Ok(ctx) = ExecutionProcess::load_context(pool, execution_process_id).await
+        && ctx.task.status == TaskStatus::InReview
+        && let Err(e) = Task::update_status(pool, ctx.task.id, TaskStatus::InProgress).await
+    {
+        tracing::warn!(
+            "Failed to update task status to InProgress after approval response: {}",
+            e
+        );
+    }
+}
+
 pub(crate) async fn ensure_task_in_review(pool: &PgPool, execution_process_id: Uuid) {
     if let Ok(ctx) = ExecutionProcess::load_context(pool, execution_process_id).await
         && ctx.task.status == TaskStatus::InProgress
@@ -270,6 +405,13 @@ pub(crate) async fn ensure_task_in_review(pool: &PgPool, execution_process_id: U
     }
 }
 
+fn action_value(entry: &NormalizedEntry) -> Option<serde_json::Value> {
+    match &entry.entry_type {
+        NormalizedEntryType::ToolUse { action_type, .. } => serde_json::to_value(action_type).ok(),
+        _ => None,
+    }
+}
+
 /// Find a matching tool use entry that hasn't been assigned to an approval yet
 /// Matches by tool call id from tool metadata
 fn find_matching_tool_use(
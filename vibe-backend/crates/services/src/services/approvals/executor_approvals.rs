@@ -1,19 +1,38 @@
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 use async_trait::async_trait;
-use db::{self, DBService};
-use executors::approvals::{ExecutorApprovalError, ExecutorApprovalService};
+use db::{
+    self, DBService,
+    models::{
+        approval_policy::{ApprovalPolicyRule, PolicyEffect},
+        execution_process::ExecutionProcess,
+        tool_approval::ApprovalDecision,
+    },
+};
+use executors::{
+    approvals::{ExecutorApprovalError, ExecutorApprovalService},
+    executors::BaseCodingAgent,
+    logs::ActionType,
+};
 use serde_json::Value;
 use utils::approvals::{ApprovalRequest, ApprovalStatus, CreateApprovalRequest};
 use uuid::Uuid;
 
-use crate::services::{approvals::Approvals, notification::NotificationService};
+use crate::services::{
+    approvals::{
+        Approvals,
+        policy::{self, PolicyMatch, PolicyToolCall},
+    },
+    notification::NotificationService,
+};
 
 pub struct ExecutorApprovalBridge {
     approvals: Approvals,
     db: DBService,
     notification_service: NotificationService,
     execution_process_id: Uuid,
+    executor: Option<BaseCodingAgent>,
+    worktree_path: PathBuf,
 }
 
 impl ExecutorApprovalBridge {
@@ -22,14 +41,49 @@ impl ExecutorApprovalBridge {
         db: DBService,
         notification_service: NotificationService,
         execution_process_id: Uuid,
+        executor: Option<BaseCodingAgent>,
+        worktree_path: PathBuf,
     ) -> Arc<Self> {
         Arc::new(Self {
             approvals,
             db,
             notification_service,
             execution_process_id,
+            executor,
+            worktree_path,
         })
     }
+
+    /// The project rule that decides this tool call, if any. Failing to load
+    /// the rules falls back to asking the user.
+    async fn match_policy(
+        &self,
+        tool_name: &str,
+        action: Option<&ActionType>,
+    ) -> Option<PolicyMatch> {
+        let pool = &self.db.pool;
+        let rules = match ExecutionProcess::load_context(pool, self.execution_process_id).await {
+            Ok(ctx) => ApprovalPolicyRule::find_enabled_by_project_id(pool, ctx.project.id).await,
+            Err(e) => Err(e),
+        };
+        let rules = match rules {
+            Ok(rules) => rules,
+            Err(e) => {
+                tracing::warn!("Failed to load approval policies: {}", e);
+                return None;
+            }
+        };
+
+        policy::evaluate(
+            &rules,
+            &PolicyToolCall {
+                tool_name,
+                action,
+                executor: self.executor,
+                worktree_path: Some(&self.worktree_path),
+            },
+        )
+    }
 }
 
 #[async_trait]
@@ -40,8 +94,6 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
         tool_input: Value,
         tool_call_id: &str,
     ) -> Result<ApprovalStatus, ExecutorApprovalError> {
-        super::ensure_task_in_review(&self.db.pool, self.execution_process_id).await;
-
         let request = ApprovalRequest::from_create(
             CreateApprovalRequest {
                 tool_name: tool_name.to_string(),
@@ -51,9 +103,43 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
             self.execution_process_id,
         );
 
+        let action = self
+            .approvals
+            .tool_action(&self.execution_process_id, tool_call_id)
+            .await;
+        if let Some(matched) = self.match_policy(tool_name, action.as_ref()).await {
+            let status = match matched.effect {
+                PolicyEffect::Allow => Some(ApprovalStatus::Approved),
+                PolicyEffect::Deny => Some(ApprovalStatus::Denied {
+                    reason: Some(format!("Denied by approval policy '{}'", matched.rule_name)),
+                }),
+                PolicyEffect::Ask => None,
+            };
+            if let Some(status) = status {
+                tracing::debug!(
+                    "Approval policy '{}' decided tool '{}': {:?}",
+                    matched.rule_name,
+                    tool_name,
+                    status
+                );
+                self.approvals
+                    .record_policy_decision(
+                        &self.db.pool,
+                        &request,
+                        action.as_ref(),
+                        &ApprovalDecision::by_policy(status.clone(), matched.rule_id),
+                    )
+                    .await
+                    .map_err(ExecutorApprovalError::request_failed)?;
+                return Ok(status);
+            }
+        }
+
+        super::ensure_task_in_review(&self.db.pool, self.execution_process_id).await;
+
         let (_, waiter) = self
             .approvals
-            .create_with_waiter(request)
+            .create_with_waiter(&self.db.pool, request)
             .await
             .map_err(ExecutorApprovalError::request_failed)?;
 
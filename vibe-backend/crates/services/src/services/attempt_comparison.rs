@@ -0,0 +1,440 @@
+//! Side-by-side comparison of the attempts in an attempt group: diff stats,
+//! agent run time, token usage and verification script results.
+
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+    time::{Duration, Instant},
+};
+
+use command_group::AsyncCommandGroup;
+use db::models::{
+    attempt_group::{AttemptGroup, AttemptGroupMember, AttemptStatus, VerificationRun},
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    token_usage::{ExecutionProcessUsage, UsageSummary},
+    workspace::Workspace,
+    workspace_repo::{RepoWithTargetBranch, WorkspaceRepo},
+};
+use executors::profile::ExecutorProfileId;
+use futures::future;
+use serde::Serialize;
+use thiserror::Error;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt},
+    process::Command,
+    task::JoinError,
+};
+use ts_rs::TS;
+use utils::{
+    diff::{Diff, DiffChangeKind},
+    shell::get_shell_command,
+};
+use uuid::Uuid;
+
+use crate::services::{
+    container::{ContainerError, ContainerService},
+    git::{DiffTarget, GitServiceError},
+};
+
+const VERIFICATION_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+/// Only the end of the script's output is kept; failures are reported last
+const MAX_VERIFICATION_OUTPUT_BYTES: usize = 16 * 1024;
+
+#[derive(Debug, Error)]
+pub enum AttemptComparisonError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Background task failed: {0}")]
+    Join(#[from] JoinError),
+    #[error("Workspace {0} not found")]
+    WorkspaceNotFound(Uuid),
+    #[error("Attempt group has no verification script")]
+    NoVerificationScript,
+}
+
+/// Lines changed in one file of an attempt
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct AttemptFileStat {
+    /// Prefixed with the repository name when the attempt spans several repos
+    pub path: String,
+    pub change: DiffChangeKind,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct AttemptComparison {
+    pub workspace_id: Uuid,
+    pub branch: String,
+    pub executor_profile_id: ExecutorProfileId,
+    pub status: AttemptStatus,
+    /// Status of the latest coding agent run
+    pub agent_status: Option<ExecutionProcessStatus>,
+    pub files: Vec<AttemptFileStat>,
+    pub additions: usize,
+    pub deletions: usize,
+    /// Set when the attempt's changes could not be diffed
+    pub diff_error: Option<String>,
+    /// Wall-clock time spent in coding agent runs
+    pub run_time_ms: i64,
+    pub usage: UsageSummary,
+    pub verification: Option<VerificationRun>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct AttemptGroupComparison {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub group: AttemptGroup,
+    pub attempts: Vec<AttemptComparison>,
+}
+
+/// Per-file stats for `diffs`, sorted by path
+pub fn file_stats(diffs: &[Diff], path_prefix: Option<&str>) -> Vec<AttemptFileStat> {
+    let mut files: Vec<AttemptFileStat> = diffs
+        .iter()
+        .filter_map(|diff| {
+            let path = diff.new_path.as_ref().or(diff.old_path.as_ref())?;
+            Some(AttemptFileStat {
+                path: match path_prefix {
+                    Some(prefix) => format!("{prefix}/{path}"),
+                    None => path.clone(),
+                },
+                change: diff.change.clone(),
+                additions: diff.additions.unwrap_or(0),
+                deletions: diff.deletions.unwrap_or(0),
+            })
+        })
+        .collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files
+}
+
+/// Keep the last `max_bytes` of `text`, marking how much was dropped
+pub fn truncate_output_tail(text: String, max_bytes: usize) -> String {
+    if text.len() <= max_bytes {
+        return text;
+    }
+    let mut cut = text.len() - max_bytes;
+    while !text.is_char_boundary(cut) {
+        cut += 1;
+    }
+    format!("[{cut} bytes truncated] ...\n{}", &text[cut..])
+}
+
+/// Compare every attempt in `group`, in launch order
+pub async fn compare_group<C: ContainerService + Sync>(
+    container: &C,
+    group: AttemptGroup,
+) -> Result<AttemptGroupComparison, AttemptComparisonError> {
+    let members = AttemptGroupMember::find_by_group_id(&container.db().pool, group.id).await?;
+    let attempts = future::try_join_all(
+        members
+            .iter()
+            .map(|member| compare_attempt(container, member)),
+    )
+    .await?;
+    Ok(AttemptGroupComparison { group, attempts })
+}
+
+async fn compare_attempt<C: ContainerService + Sync>(
+    container: &C,
+    member: &AttemptGroupMember,
+) -> Result<AttemptComparison, AttemptComparisonError> {
+    let pool = &container.db().pool;
+    let workspace = Workspace::find_by_id(pool, member.workspace_id)
+        .await?
+        .ok_or(AttemptComparisonError::WorkspaceNotFound(
+            member.workspace_id,
+        ))?;
+
+    let (files, diff_error) = match attempt_file_stats(container, &workspace, member.status).await {
+        Ok(files) => (files, None),
+        Err(e) => {
+            tracing::warn!("Failed to diff attempt {}: {}", workspace.id, e);
+            (Vec::new(), Some(e.to_string()))
+        }
+    };
+    let agent_status = ExecutionProcess::find_latest_by_workspace_and_run_reason(
+        pool,
+        workspace.id,
+        &ExecutionProcessRunReason::CodingAgent,
+    )
+    .await?
+    .map(|process| process.status);
+    let run_time_ms = ExecutionProcess::coding_agent_run_time_ms(pool, workspace.id).await?;
+    let usage = ExecutionProcessUsage::summary_for_workspace(pool, workspace.id).await?;
+
+    let verification = member.verification_passed.map(|passed| VerificationRun {
+        passed,
+        exit_code: member.verification_exit_code,
+        output: member.verification_output.clone().unwrap_or_default(),
+        duration_ms: member.verification_duration_ms.unwrap_or(0),
+    });
+
+    Ok(AttemptComparison {
+        workspace_id: workspace.id,
+        branch: workspace.branch,
+        executor_profile_id: member.executor_profile_id.0.clone(),
+        status: member.status,
+        agent_status,
+        additions: files.iter().map(|file| file.additions).sum(),
+        deletions: files.iter().map(|file| file.deletions).sum(),
+        files,
+        diff_error,
+        run_time_ms,
+        usage,
+        verification,
+    })
+}
+
+/// Diff an attempt against its target branches. Active attempts are diffed
+/// in their worktree so uncommitted work counts; archived ones no longer
+/// have a worktree, so their branch is used instead.
+async fn attempt_file_stats<C: ContainerService + Sync>(
+    container: &C,
+    workspace: &Workspace,
+    status: AttemptStatus,
+) -> Result<Vec<AttemptFileStat>, AttemptComparisonError> {
+    let repos = WorkspaceRepo::find_repos_with_target_branch_for_workspace(
+        &container.db().pool,
+        workspace.id,
+    )
+    .await?;
+    let container_ref = match status {
+        AttemptStatus::Archived => None,
+        AttemptStatus::Active | AttemptStatus::Winner => Some(PathBuf::from(
+            container.ensure_container_exists(workspace).await?,
+        )),
+    };
+    let prefix_paths = repos.len() > 1;
+
+    let mut files = Vec::new();
+    for RepoWithTargetBranch {
+        repo,
+        target_branch,
+    } in repos
+    {
+        let git = container.git().clone();
+        let branch = workspace.branch.clone();
+        let worktree_path = container_ref.as_ref().map(|dir| dir.join(&repo.name));
+        let repo_path = repo.path.clone();
+        let diffs = tokio::task::spawn_blocking(move || match worktree_path {
+            Some(worktree_path) => {
+                let base_commit = git.get_base_commit(&repo_path, &branch, &target_branch)?;
+                git.get_diffs(
+                    DiffTarget::Worktree {
+                        worktree_path: &worktree_path,
+                        base_commit: &base_commit,
+                    },
+                    None,
+                )
+            }
+            None => git.get_diffs(
+                DiffTarget::Branch {
+                    repo_path: &repo_path,
+                    branch_name: &branch,
+                    base_branch: &target_branch,
+                },
+                None,
+            ),
+        })
+        .await??;
+        files.extend(file_stats(
+            &diffs,
+            prefix_paths.then_some(repo.name.as_str()),
+        ));
+    }
+    Ok(files)
+}
+
+/// Run `group`'s verification script in every active attempt concurrently
+/// and record the results
+pub async fn verify_group<C: ContainerService + Sync>(
+    container: &C,
+    group: &AttemptGroup,
+) -> Result<Vec<AttemptGroupMember>, AttemptComparisonError> {
+    let script = group
+        .verification_script
+        .as_deref()
+        .filter(|script| !script.trim().is_empty())
+        .ok_or(AttemptComparisonError::NoVerificationScript)?;
+    let members = AttemptGroupMember::find_by_group_id(&container.db().pool, group.id).await?;
+    future::try_join_all(
+        members
+            .into_iter()
+            .map(|member| verify_member(container, member, script)),
+    )
+    .await
+}
+
+async fn verify_member<C: ContainerService + Sync>(
+    container: &C,
+    member: AttemptGroupMember,
+    script: &str,
+) -> Result<AttemptGroupMember, AttemptComparisonError> {
+    if member.status == AttemptStatus::Archived {
+        return Ok(member);
+    }
+    let pool = &container.db().pool;
+    let workspace = Workspace::find_by_id(pool, member.workspace_id)
+        .await?
+        .ok_or(AttemptComparisonError::WorkspaceNotFound(
+            member.workspace_id,
+        ))?;
+    let container_ref = PathBuf::from(container.ensure_container_exists(&workspace).await?);
+
+    // Single-repo attempts run the script inside the repository itself
+    let repos =
+        WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id).await?;
+    let current_dir = match repos.as_slice() {
+        [only] => container_ref.join(&only.repo.name),
+        _ => container_ref,
+    };
+
+    let run = run_verification(&current_dir, script, VERIFICATION_TIMEOUT).await?;
+    tracing::info!(
+        "Verification of attempt {} {} in {}ms",
+        workspace.id,
+        if run.passed { "passed" } else { "failed" },
+        run.duration_ms
+    );
+    Ok(AttemptGroupMember::record_verification(pool, member.id, &run).await?)
+}
+
+async fn read_pipe(pipe: Option<impl AsyncRead + Unpin>) -> std::io::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    if let Some(mut pipe) = pipe {
+        pipe.read_to_end(&mut buf).await?;
+    }
+    Ok(buf)
+}
+
+/// Run `script` with the platform shell in `current_dir`, failing it once
+/// `timeout` is up. The script runs in its own process group, which is killed
+/// on timeout so processes it started do not outlive it.
+pub async fn run_verification(
+    current_dir: &Path,
+    script: &str,
+    timeout: Duration,
+) -> std::io::Result<VerificationRun> {
+    let (shell, shell_arg) = get_shell_command();
+    let mut command = Command::new(shell);
+    command
+        .kill_on_drop(true)
+        .arg(shell_arg)
+        .arg(script)
+        .current_dir(current_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let started = Instant::now();
+    let mut child = command.group_spawn()?;
+    let stdout = child.inner().stdout.take();
+    let stderr = child.inner().stderr.take();
+    let result = tokio::time::timeout(timeout, async {
+        tokio::try_join!(child.wait(), read_pipe(stdout), read_pipe(stderr))
+    })
+    .await;
+    let duration_ms = started.elapsed().as_millis() as i64;
+
+    let Ok(output) = result else {
+        if let Err(err) = child.kill().await {
+            tracing::warn!("Failed to kill timed out verification script: {}", err);
+        }
+        let _ = child.wait().await;
+        return Ok(VerificationRun {
+            passed: false,
+            exit_code: None,
+            output: format!("Verification script timed out after {}s", timeout.as_secs()),
+            duration_ms,
+        });
+    };
+    let (status, stdout, stderr) = output?;
+
+    let mut text = String::from_utf8_lossy(&stdout).into_owned();
+    let stderr = String::from_utf8_lossy(&stderr);
+    if !stderr.is_empty() {
+        if !text.is_empty() && !text.ends_with('\n') {
+            text.push('\n');
+        }
+        text.push_str(&stderr);
+    }
+
+    Ok(VerificationRun {
+        passed: status.success(),
+        exit_code: status.code(),
+        output: truncate_output_tail(text, MAX_VERIFICATION_OUTPUT_BYTES),
+        duration_ms,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diff(old_path: Option<&str>, new_path: Option<&str>, additions: usize) -> Diff {
+        Diff {
+            change: DiffChangeKind::Modified,
+            old_path: old_path.map(str::to_string),
+            new_path: new_path.map(str::to_string),
+            old_content: None,
+            new_content: None,
+            content_omitted: false,
+            additions: Some(additions),
+            deletions: None,
+        }
+    }
+
+    #[test]
+    fn file_stats_uses_new_path_and_prefix() {
+        let diffs = vec![
+            diff(Some("src/old.rs"), Some("src/new.rs"), 3),
+            diff(Some("README.md"), None, 0),
+        ];
+
+        let files = file_stats(&diffs, None);
+        assert_eq!(
+            files.iter().map(|f| f.path.as_str()).collect::<Vec<_>>(),
+            ["README.md", "src/new.rs"]
+        );
+        assert_eq!(files[1].additions, 3);
+        assert_eq!(files[1].deletions, 0);
+
+        let files = file_stats(&diffs, Some("backend"));
+        assert_eq!(files[0].path, "backend/README.md");
+    }
+
+    #[test]
+    fn truncate_output_tail_keeps_end() {
+        assert_eq!(truncate_output_tail("short".to_string(), 16), "short");
+
+        let truncated = truncate_output_tail("é".repeat(10), 5);
+        assert!(truncated.starts_with("[16 bytes truncated]"));
+        assert!(truncated.ends_with("éé"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn timed_out_verification_kills_spawned_children() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = "(sleep 2; touch late) & sleep 30";
+
+        let run = run_verification(dir.path(), script, Duration::from_millis(500))
+            .await
+            .unwrap();
+        assert!(!run.passed);
+        assert_eq!(run.exit_code, None);
+        assert!(run.duration_ms < 2_000);
+
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        assert!(!dir.path().join("late").exists());
+    }
+}
@@ -0,0 +1,345 @@
+//! Checkpoints taken in every repository before each coding agent follow-up,
+//! and rolling a workspace back to the start of a turn.
+
+use std::{collections::HashMap, path::PathBuf};
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    coding_agent_checkpoint::CodingAgentCheckpoint,
+    coding_agent_turn::CodingAgentTurn,
+    execution_process::{ExecutionProcess, ExecutionProcessStatus},
+    repo::Repo,
+    session::Session,
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+use executors::{
+    actions::ExecutorActionType, executors::BaseAgentCapability, profile::ExecutorConfigs,
+};
+use serde::Serialize;
+use thiserror::Error;
+use tokio::task::JoinError;
+use ts_rs::TS;
+use utils::diff::Diff;
+use uuid::Uuid;
+
+use crate::services::{
+    container::{ContainerError, ContainerService},
+    git::{Commit, DiffTarget, GitServiceError},
+};
+
+pub const CHECKPOINT_COMMIT_MESSAGE: &str = "Checkpoint before follow-up";
+
+#[derive(Debug, Error)]
+pub enum CheckpointError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error("Background task failed: {0}")]
+    Join(#[from] JoinError),
+    #[error("Execution process {0} is not a turn of this session")]
+    TurnNotFound(Uuid),
+    #[error("No checkpoint was taken before this turn")]
+    NoCheckpoint,
+    #[error("Workspace has uncommitted changes; force the rollback to discard them")]
+    WorktreeDirty,
+    #[error("{0} cannot fork its session")]
+    SessionForkUnsupported(String),
+}
+
+/// Ref keeping a checkpoint commit reachable; snapshots of uncommitted
+/// changes are never on the branch
+pub fn checkpoint_ref(execution_process_id: Uuid) -> String {
+    format!("refs/vibe-kanban/checkpoints/{execution_process_id}")
+}
+
+/// Changes made in one repository since a checkpoint
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct RepoCheckpoint {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    pub commit_sha: String,
+    /// Whether the checkpoint snapshots uncommitted changes on top of the
+    /// branch's commit
+    pub committed_changes: bool,
+    pub files_changed: usize,
+    pub additions: usize,
+    pub deletions: usize,
+    /// Set when the changes could not be diffed
+    pub diff_error: Option<String>,
+}
+
+/// A follow-up turn and the checkpoint taken before it. Diff stats cover the
+/// changes up to the next turn's checkpoint, or the current worktree for the
+/// latest turn, so rolling back to a turn discards its changes and those of
+/// every later turn.
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct TurnCheckpoint {
+    pub execution_process_id: Uuid,
+    pub prompt: Option<String>,
+    pub summary: Option<String>,
+    pub status: ExecutionProcessStatus,
+    pub repos: Vec<RepoCheckpoint>,
+    pub additions: usize,
+    pub deletions: usize,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SessionCheckpoints {
+    /// Whether the session's executor can fork its agent session on rollback
+    pub can_fork_session: bool,
+    /// Oldest turn first
+    pub turns: Vec<TurnCheckpoint>,
+}
+
+/// Totals of `diffs`: (files changed, additions, deletions)
+pub fn diff_totals(diffs: &[Diff]) -> (usize, usize, usize) {
+    diffs
+        .iter()
+        .fold((0, 0, 0), |(files, additions, deletions), diff| {
+            (
+                files + 1,
+                additions + diff.additions.unwrap_or(0),
+                deletions + diff.deletions.unwrap_or(0),
+            )
+        })
+}
+
+/// Whether the executor behind `process` supports session forks, and its name
+fn executor_can_fork(process: &ExecutionProcess) -> (bool, Option<String>) {
+    let Ok(action) = process.executor_action() else {
+        return (false, None);
+    };
+    let executor_profile_id = match action.typ() {
+        ExecutorActionType::CodingAgentInitialRequest(request) => &request.executor_profile_id,
+        ExecutorActionType::CodingAgentFollowUpRequest(request) => &request.executor_profile_id,
+        _ => return (false, None),
+    };
+    let can_fork = ExecutorConfigs::get_cached()
+        .get_coding_agent(executor_profile_id)
+        .is_some_and(|agent| {
+            agent
+                .capabilities()
+                .contains(&BaseAgentCapability::SessionFork)
+        });
+    (can_fork, Some(executor_profile_id.executor.to_string()))
+}
+
+/// Every checkpointed turn of `session` that has not been rolled back
+pub async fn list_checkpoints<C: ContainerService + Sync>(
+    container: &C,
+    workspace: &Workspace,
+    session: &Session,
+) -> Result<SessionCheckpoints, CheckpointError> {
+    let pool = &container.db().pool;
+    let checkpoints = CodingAgentCheckpoint::find_by_session_id(pool, session.id).await?;
+    let repos: HashMap<Uuid, Repo> = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id)
+        .await?
+        .into_iter()
+        .map(|repo| (repo.id, repo))
+        .collect();
+    let workspace_dir = PathBuf::from(container.ensure_container_exists(workspace).await?);
+
+    let mut process_ids: Vec<Uuid> = Vec::new();
+    for checkpoint in &checkpoints {
+        if !process_ids.contains(&checkpoint.execution_process_id) {
+            process_ids.push(checkpoint.execution_process_id);
+        }
+    }
+
+    let mut can_fork_session = false;
+    let mut turns = Vec::with_capacity(process_ids.len());
+    for process_id in process_ids {
+        let Some(process) = ExecutionProcess::find_by_id(pool, process_id).await? else {
+            continue;
+        };
+        can_fork_session = executor_can_fork(&process).0;
+        let turn = CodingAgentTurn::find_by_execution_process_id(pool, process_id).await?;
+
+        let mut repo_checkpoints = Vec::new();
+        for checkpoint in checkpoints
+            .iter()
+            .filter(|checkpoint| checkpoint.execution_process_id == process_id)
+        {
+            let Some(repo) = repos.get(&checkpoint.repo_id) else {
+                continue;
+            };
+            // The next turn's checkpoint in this repo closes this turn
+            let next_commit = checkpoints
+                .iter()
+                .skip_while(|c| c.id != checkpoint.id)
+                .skip(1)
+                .find(|c| c.repo_id == checkpoint.repo_id)
+                .map(|c| c.commit_sha.clone());
+
+            let git = container.git().clone();
+            let worktree_path = workspace_dir.join(&repo.name);
+            let from_commit = checkpoint.commit_sha.clone();
+            let diffs = tokio::task::spawn_blocking(move || match next_commit {
+                Some(to_commit) => git.get_diffs(
+                    DiffTarget::Range {
+                        repo_path: &worktree_path,
+                        from_commit: &from_commit,
+                        to_commit: &to_commit,
+                    },
+                    None,
+                ),
+                None => {
+                    let oid = git2::Oid::from_str(&from_commit)?;
+                    git.get_diffs(
+                        DiffTarget::Worktree {
+                            worktree_path: &worktree_path,
+                            base_commit: &Commit::new(oid),
+                        },
+                        None,
+                    )
+                }
+            })
+            .await?;
+
+            let (files_changed, additions, deletions, diff_error) = match diffs {
+                Ok(diffs) => {
+                    let (files, additions, deletions) = diff_totals(&diffs);
+                    (files, additions, deletions, None)
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to diff checkpoint {} of repo {}: {}",
+                        checkpoint.id,
+                        repo.name,
+                        e
+                    );
+                    (0, 0, 0, Some(e.to_string()))
+                }
+            };
+            repo_checkpoints.push(RepoCheckpoint {
+                repo_id: repo.id,
+                repo_name: repo.name.clone(),
+                commit_sha: checkpoint.commit_sha.clone(),
+                committed_changes: checkpoint.committed_changes,
+                files_changed,
+                additions,
+                deletions,
+                diff_error,
+            });
+        }
+
+        turns.push(TurnCheckpoint {
+            execution_process_id: process.id,
+            prompt: turn.as_ref().and_then(|turn| turn.prompt.clone()),
+            summary: turn.and_then(|turn| turn.summary),
+            status: process.status,
+            additions: repo_checkpoints.iter().map(|repo| repo.additions).sum(),
+            deletions: repo_checkpoints.iter().map(|repo| repo.deletions).sum(),
+            repos: repo_checkpoints,
+            created_at: process.created_at,
+        });
+    }
+
+    Ok(SessionCheckpoints {
+        can_fork_session,
+        turns,
+    })
+}
+
+/// Reset every repository to the checkpoint taken before `execution_process_id`,
+/// stopping running processes first. With `fork_session` that turn and every
+/// later one are dropped, so the next follow-up resumes the agent session as
+/// it was before the turn; otherwise the agent keeps its memory of them.
+pub async fn rollback_to_turn<C: ContainerService + Sync>(
+    container: &C,
+    workspace: &Workspace,
+    session: &Session,
+    execution_process_id: Uuid,
+    fork_session: bool,
+    force_when_dirty: bool,
+) -> Result<(), CheckpointError> {
+    let pool = &container.db().pool;
+    let process = ExecutionProcess::find_by_id(pool, execution_process_id)
+        .await?
+        .filter(|process| process.session_id == session.id && !process.dropped)
+        .ok_or(CheckpointError::TurnNotFound(execution_process_id))?;
+    let checkpoints = CodingAgentCheckpoint::find_by_execution_process_id(pool, process.id).await?;
+    if checkpoints.is_empty() {
+        return Err(CheckpointError::NoCheckpoint);
+    }
+    if fork_session {
+        let (can_fork, executor) = executor_can_fork(&process);
+        if !can_fork {
+            return Err(CheckpointError::SessionForkUnsupported(
+                executor.unwrap_or_else(|| "This executor".to_string()),
+            ));
+        }
+    }
+
+    let workspace_dir = PathBuf::from(container.ensure_container_exists(workspace).await?);
+    if !force_when_dirty && !container.is_container_clean(workspace).await? {
+        return Err(CheckpointError::WorktreeDirty);
+    }
+
+    container.try_stop(workspace, false).await;
+
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+    for checkpoint in &checkpoints {
+        let Some(repo) = repos.iter().find(|repo| repo.id == checkpoint.repo_id) else {
+            continue;
+        };
+        let git = container.git().clone();
+        let worktree_path = workspace_dir.join(&repo.name);
+        let commit_sha = checkpoint.commit_sha.clone();
+        let has_uncommitted_changes = checkpoint.committed_changes;
+        tokio::task::spawn_blocking(move || {
+            git.restore_checkpoint(&worktree_path, &commit_sha, has_uncommitted_changes)
+        })
+        .await??;
+    }
+
+    if fork_session {
+        ExecutionProcess::drop_at_and_after(pool, session.id, process.id).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use utils::diff::DiffChangeKind;
+
+    use super::*;
+
+    fn diff(additions: Option<usize>, deletions: Option<usize>) -> Diff {
+        Diff {
+            change: DiffChangeKind::Modified,
+            old_path: Some("src/lib.rs".to_string()),
+            new_path: Some("src/lib.rs".to_string()),
+            old_content: None,
+            new_content: None,
+            content_omitted: true,
+            additions,
+            deletions,
+        }
+    }
+
+    #[test]
+    fn test_diff_totals() {
+        assert_eq!(diff_totals(&[]), (0, 0, 0));
+        assert_eq!(
+            diff_totals(&[diff(Some(3), Some(1)), diff(None, Some(2))]),
+            (2, 3, 3)
+        );
+    }
+
+    #[test]
+    fn test_checkpoint_ref_is_outside_branches() {
+        let id = Uuid::new_v4();
+        assert_eq!(
+            checkpoint_ref(id),
+            format!("refs/vibe-kanban/checkpoints/{id}")
+        );
+        assert!(!checkpoint_ref(id).starts_with("refs/heads/"));
+    }
+}
@@ -0,0 +1,523 @@
+//! Repairing failing CI on an attempt's pull request.
+//!
+//! When a check suite or workflow run fails on the commit the attempt's
+//! branch points at, the failed jobs' logs are downloaded, cut down to the
+//! step that failed, and handed to the agent as a follow-up on the attempt's
+//! session. When that run is over its commits are pushed, which runs CI
+//! again. Every failing commit gets at most one repair, and a PR gets
+//! `max_attempts_per_pr` of them; after that, or when the agent finds
+//! nothing to change, the loop stops and the failure goes to the inbox.
+
+use std::{path::PathBuf, sync::LazyLock};
+
+use db::models::{
+    ci_repair_attempt::{CiRepairAttempt, CiRepairStatus, CreateCiRepairAttempt},
+    execution_process::ExecutionProcess,
+    inbox::{CreateInboxItem, InboxItem, InboxNotificationType},
+    merge::PullRequestInfo,
+    repo::Repo,
+    workspace::Workspace,
+};
+use regex::Regex;
+use thiserror::Error;
+
+use crate::services::{
+    config::CiRepairConfig,
+    container::{ContainerError, ContainerService, FollowUpPush},
+    git::GitServiceError,
+    github::{CiJob, CiRun, GitHubService, GitHubServiceError},
+    prompt::fence_for,
+};
+
+/// Lines of log kept per failed job
+const EXCERPT_MAX_LINES: usize = 80;
+
+/// Failed jobs whose logs go into one prompt
+const MAX_JOBS_PER_REPAIR: usize = 3;
+
+static LOG_TIMESTAMP: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(\.\d+)?Z ").expect("valid regex")
+});
+
+static ANSI_ESCAPE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\x1b\[[0-9;]*[A-Za-z]").expect("valid regex"));
+
+#[derive(Debug, Error)]
+pub enum CiRepairError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error(transparent)]
+    GitHub(#[from] GitHubServiceError),
+    #[error("Repository not found")]
+    RepoNotFound,
+    #[error("The CI run has no failed jobs")]
+    NoFailedJobs,
+}
+
+/// What a CI failure led to
+#[derive(Debug)]
+pub enum CiRepairOutcome {
+    Started(CiRepairAttempt),
+    /// The PR has no repairs left; the failure went to the inbox
+    Escalated,
+    /// Another webhook already handled this commit, or the PR was escalated
+    AlreadyHandled,
+    /// CI failed on a commit the branch has moved past
+    Stale,
+    /// Something other than a dev server is running in the attempt
+    AttemptBusy,
+}
+
+/// The part of a job log that explains why the job failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailureExcerpt {
+    /// Command of the failing step, from its `Run …` header
+    pub step: Option<String>,
+    pub text: String,
+}
+
+fn clean_log_line(line: &str) -> String {
+    let line = line.trim_start_matches('\u{feff}');
+    let line = LOG_TIMESTAMP.replace(line, "");
+    ANSI_ESCAPE.replace_all(&line, "").trim_end().to_string()
+}
+
+/// Find the first failing step of a GitHub Actions job log and keep its
+/// output up to the last error it reported. Logs without error annotations
+/// (other CI providers) fall back to their last lines.
+pub fn extract_failure_excerpt(log: &str) -> FailureExcerpt {
+    let lines: Vec<String> = log.lines().map(clean_log_line).collect();
+    let is_step_header = |line: &String| line.starts_with("##[group]Run ");
+    let is_error = |line: &String| line.starts_with("##[error]");
+
+    let Some(first_error) = lines.iter().position(is_error) else {
+        return FailureExcerpt {
+            step: None,
+            text: excerpt_tail(&lines),
+        };
+    };
+
+    let header = lines[..first_error].iter().rposition(is_step_header);
+    let step = header.map(|h| lines[h]["##[group]Run ".len()..].trim().to_string());
+    // Output starts once the header group (the command and its env) closes
+    let start = header.map_or(0, |h| {
+        lines[h..first_error]
+            .iter()
+            .position(|line| line == "##[endgroup]")
+            .map_or(h, |end| h + end + 1)
+    });
+    let step_end = lines[first_error..]
+        .iter()
+        .position(is_step_header)
+        .map_or(lines.len(), |next| first_error + next);
+    let end = lines[first_error..step_end]
+        .iter()
+        .rposition(is_error)
+        .map_or(step_end, |last| first_error + last + 1);
+
+    FailureExcerpt {
+        step,
+        text: excerpt_tail(&lines[start..end]),
+    }
+}
+
+fn excerpt_tail(lines: &[String]) -> String {
+    let kept: Vec<String> = lines
+        .iter()
+        .filter(|line| !line.starts_with("##[group]") && *line != "##[endgroup]")
+        .map(|line| match line.strip_prefix("##[error]") {
+            Some(message) => format!("Error: {message}"),
+            None => line.clone(),
+        })
+        .collect();
+    kept[kept.len().saturating_sub(EXCERPT_MAX_LINES)..].join("\n")
+}
+
+/// A failed job with what its log says, if the log could be fetched
+#[derive(Debug, Clone)]
+pub struct FailedJob {
+    pub job: CiJob,
+    pub excerpt: Option<FailureExcerpt>,
+}
+
+/// The follow-up prompt: each failed job with the excerpt of its log
+pub fn build_prompt(
+    pr: &PullRequestInfo,
+    head_sha: &str,
+    attempt: u32,
+    max_attempts: u32,
+    jobs: &[FailedJob],
+) -> String {
+    let short_sha = &head_sha[..head_sha.len().min(7)];
+    let mut prompt = format!(
+        "CI failed on pull request #{} ({}) at commit {short_sha}. Find the cause and fix it; \
+         this is automatic repair {attempt} of {max_attempts}.\n\nYour changes are committed and \
+         pushed when you finish, which runs CI again, so don't push yourself. If the failure has \
+         nothing to do with this branch (a flaky test or broken infrastructure), leave the code \
+         as it is and say so in your final message.\n",
+        pr.number, pr.url
+    );
+
+    for failed in jobs {
+        match &failed.job.html_url {
+            Some(url) => prompt.push_str(&format!("\n## {} ({url})\n", failed.job.name)),
+            None => prompt.push_str(&format!("\n## {}\n", failed.job.name)),
+        }
+        match &failed.excerpt {
+            Some(excerpt) => {
+                if let Some(step) = &excerpt.step {
+                    prompt.push_str(&format!("Failed step: `{step}`\n"));
+                }
+                let fence = fence_for(&excerpt.text);
+                prompt.push_str(&format!("{fence}text\n{}\n{fence}\n", excerpt.text));
+            }
+            None => prompt.push_str("The log of this job could not be downloaded.\n"),
+        }
+    }
+
+    prompt
+}
+
+/// Start a repair of the CI failure `run` reported on `head_sha` of the PR
+/// attached to `repo`, or escalate it once the PR is out of repairs
+#[allow(clippy::too_many_arguments)]
+pub async fn repair_ci_failure<C: ContainerService + Sync>(
+    container: &C,
+    github: &GitHubService,
+    workspace: &Workspace,
+    repo: &Repo,
+    pr: &PullRequestInfo,
+    head_sha: &str,
+    run: CiRun,
+    config: &CiRepairConfig,
+) -> Result<CiRepairOutcome, CiRepairError> {
+    let pool = &container.db().pool;
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Ok(CiRepairOutcome::AttemptBusy);
+    }
+
+    let worktree_path =
+        PathBuf::from(container.ensure_container_exists(workspace).await?).join(&repo.name);
+    if container.git().get_head_info(&worktree_path)?.oid != head_sha {
+        return Ok(CiRepairOutcome::Stale);
+    }
+
+    let used = CiRepairAttempt::count_repairs(pool, workspace.id, repo.id, pr.number).await?;
+    if used >= i64::from(config.max_attempts_per_pr) {
+        return escalate(container, workspace, repo, pr, head_sha, used).await;
+    }
+
+    let Some(attempt) = CiRepairAttempt::claim(
+        pool,
+        &CreateCiRepairAttempt {
+            workspace_id: workspace.id,
+            repo_id: repo.id,
+            pr_number: pr.number,
+            head_sha: head_sha.to_string(),
+            status: CiRepairStatus::Running,
+        },
+    )
+    .await?
+    else {
+        return Ok(CiRepairOutcome::AlreadyHandled);
+    };
+
+    let number = u32::try_from(used + 1).unwrap_or(u32::MAX);
+    match start_repair(
+        container, github, workspace, repo, pr, &attempt, run, number, config,
+    )
+    .await
+    {
+        Ok(attempt) => Ok(CiRepairOutcome::Started(attempt)),
+        Err(e) => {
+            CiRepairAttempt::complete(
+                pool,
+                attempt.id,
+                CiRepairStatus::Failed,
+                None,
+                Some(&e.to_string()),
+            )
+            .await?;
+            Err(e)
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn start_repair<C: ContainerService + Sync>(
+    container: &C,
+    github: &GitHubService,
+    workspace: &Workspace,
+    repo: &Repo,
+    pr: &PullRequestInfo,
+    attempt: &CiRepairAttempt,
+    run: CiRun,
+    number: u32,
+    config: &CiRepairConfig,
+) -> Result<CiRepairAttempt, CiRepairError> {
+    let repo_info = container.git().get_github_repo_info(&repo.path)?;
+    let failed_jobs = github.get_failed_ci_jobs(&repo_info, run).await?;
+    if failed_jobs.is_empty() {
+        return Err(CiRepairError::NoFailedJobs);
+    }
+
+    let mut jobs = Vec::new();
+    for job in failed_jobs.into_iter().take(MAX_JOBS_PER_REPAIR) {
+        let excerpt = match github.get_ci_job_log(&repo_info, job.id).await {
+            Ok(log) => Some(extract_failure_excerpt(&log)),
+            Err(e) => {
+                tracing::warn!("Failed to download log of CI job {}: {}", job.id, e);
+                None
+            }
+        };
+        jobs.push(FailedJob { job, excerpt });
+    }
+
+    let prompt = build_prompt(
+        pr,
+        &attempt.head_sha,
+        number,
+        config.max_attempts_per_pr,
+        &jobs,
+    );
+    let execution_process = container.start_agent_follow_up(workspace, prompt).await?;
+    let job_names: Vec<String> = jobs.into_iter().map(|failed| failed.job.name).collect();
+    Ok(CiRepairAttempt::set_execution_process(
+        &container.db().pool,
+        attempt.id,
+        execution_process.id,
+        &job_names,
+    )
+    .await?)
+}
+
+async fn escalate<C: ContainerService + Sync>(
+    container: &C,
+    workspace: &Workspace,
+    repo: &Repo,
+    pr: &PullRequestInfo,
+    head_sha: &str,
+    used: i64,
+) -> Result<CiRepairOutcome, CiRepairError> {
+    let pool = &container.db().pool;
+    if CiRepairAttempt::has_escalated(pool, workspace.id, repo.id, pr.number).await? {
+        return Ok(CiRepairOutcome::AlreadyHandled);
+    }
+    let claimed = CiRepairAttempt::claim(
+        pool,
+        &CreateCiRepairAttempt {
+            workspace_id: workspace.id,
+            repo_id: repo.id,
+            pr_number: pr.number,
+            head_sha: head_sha.to_string(),
+            status: CiRepairStatus::Escalated,
+        },
+    )
+    .await?;
+    if claimed.is_none() {
+        return Ok(CiRepairOutcome::AlreadyHandled);
+    }
+
+    notify_inbox(
+        container,
+        workspace,
+        format!("CI is still failing on PR #{}", pr.number),
+        format!(
+            "CI failed on branch {} after {used} automatic repair attempts. Automatic repairs \
+             are stopped for this PR: {}",
+            workspace.branch, pr.url
+        ),
+    )
+    .await?;
+    Ok(CiRepairOutcome::Escalated)
+}
+
+/// The inbox's workspace column refers to organizations, so the attempt is
+/// linked through its task
+async fn notify_inbox<C: ContainerService + Sync>(
+    container: &C,
+    workspace: &Workspace,
+    title: String,
+    message: String,
+) -> Result<(), sqlx::Error> {
+    let pool = &container.db().pool;
+    let task = workspace.parent_task(pool).await?;
+    InboxItem::create(
+        pool,
+        &CreateInboxItem {
+            notification_type: InboxNotificationType::SystemNotification,
+            title,
+            message: Some(message),
+            task_id: task.as_ref().map(|task| task.id),
+            project_id: task.as_ref().map(|task| task.project_id),
+            workspace_id: None,
+        },
+    )
+    .await?;
+    Ok(())
+}
+
+/// Push the workspace's finished CI repairs once nothing but dev servers is
+/// running in it
+pub async fn complete_repairs<C: ContainerService + Sync>(
+    container: &C,
+    workspace: &Workspace,
+) -> Result<(), CiRepairError> {
+    let pool = &container.db().pool;
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Ok(());
+    }
+
+    for attempt in CiRepairAttempt::find_running_by_workspace_id(pool, workspace.id).await? {
+        let (status, commit_sha, error) = match push_repair(container, workspace, &attempt).await {
+            Ok(FollowUpPush::Pushed { commit_sha }) => {
+                (CiRepairStatus::Pushed, Some(commit_sha), None)
+            }
+            Ok(FollowUpPush::NoChanges) => (CiRepairStatus::NoChanges, None, None),
+            Ok(FollowUpPush::Unfinished) => (
+                CiRepairStatus::Failed,
+                None,
+                Some("The agent did not finish".to_string()),
+            ),
+            Err(e) => (CiRepairStatus::Failed, None, Some(e.to_string())),
+        };
+        if let Some(error) = &error {
+            tracing::warn!(
+                "CI repair {} on PR #{}: {}",
+                attempt.id,
+                attempt.pr_number,
+                error
+            );
+        }
+        CiRepairAttempt::complete(
+            pool,
+            attempt.id,
+            status,
+            commit_sha.as_deref(),
+            error.as_deref(),
+        )
+        .await?;
+
+        // Nothing was pushed, so CI won't run again and the loop ends here
+        if status == CiRepairStatus::NoChanges {
+            notify_inbox(
+                container,
+                workspace,
+                format!("CI repair made no changes on PR #{}", attempt.pr_number),
+                format!(
+                    "The agent found nothing to fix for the CI failure on branch {}. The \
+                     failure may be flaky or need a closer look.",
+                    workspace.branch
+                ),
+            )
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+async fn push_repair<C: ContainerService + Sync>(
+    container: &C,
+    workspace: &Workspace,
+    attempt: &CiRepairAttempt,
+) -> Result<FollowUpPush, CiRepairError> {
+    let Some(execution_process_id) = attempt.execution_process_id else {
+        return Ok(FollowUpPush::Unfinished);
+    };
+    let repo = Repo::find_by_id(&container.db().pool, attempt.repo_id)
+        .await?
+        .ok_or(CiRepairError::RepoNotFound)?;
+    Ok(container
+        .push_agent_changes(workspace, &repo, execution_process_id)
+        .await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use db::models::merge::MergeStatus;
+
+    use super::*;
+
+    const CARGO_TEST_LOG: &str = include_str!("../../tests/fixtures/ci/cargo_test_failure.log");
+
+    #[test]
+    fn test_excerpt_keeps_failing_step_output() {
+        let excerpt = extract_failure_excerpt(CARGO_TEST_LOG);
+
+        assert_eq!(excerpt.step.as_deref(), Some("cargo test --workspace"));
+        assert!(
+            excerpt
+                .text
+                .contains("assertion `left == right` failed: total should include tax")
+        );
+        assert!(
+            excerpt
+                .text
+                .ends_with("Error: Process completed with exit code 101.")
+        );
+        // Earlier steps, the step's env group, post-job cleanup and timestamps are dropped
+        assert!(!excerpt.text.contains("actions/checkout"));
+        assert!(!excerpt.text.contains("CARGO_TERM_COLOR"));
+        assert!(!excerpt.text.contains("Post job cleanup"));
+        assert!(!excerpt.text.contains("2026-02-15T"));
+        assert!(!excerpt.text.contains('\u{1b}'));
+    }
+
+    #[test]
+    fn test_excerpt_without_annotations_keeps_tail() {
+        let log: String = (1..=200).map(|i| format!("line {i}\n")).collect();
+        let excerpt = extract_failure_excerpt(&log);
+
+        assert_eq!(excerpt.step, None);
+        assert_eq!(excerpt.text.lines().count(), EXCERPT_MAX_LINES);
+        assert!(excerpt.text.starts_with("line 121\n"));
+        assert!(excerpt.text.ends_with("line 200"));
+    }
+
+    #[test]
+    fn test_prompt_lists_failed_jobs() {
+        let pr = PullRequestInfo {
+            number: 42,
+            url: "https://github.com/acme/shop/pull/42".to_string(),
+            status: MergeStatus::Open,
+            merged_at: None,
+            merge_commit_sha: None,
+        };
+        let jobs = vec![
+            FailedJob {
+                job: CiJob {
+                    id: 1,
+                    name: "test".to_string(),
+                    conclusion: Some("failure".to_string()),
+                    html_url: Some("https://github.com/acme/shop/actions/runs/9/job/1".to_string()),
+                },
+                excerpt: Some(extract_failure_excerpt(CARGO_TEST_LOG)),
+            },
+            FailedJob {
+                job: CiJob {
+                    id: 2,
+                    name: "lint".to_string(),
+                    conclusion: Some("timed_out".to_string()),
+                    html_url: None,
+                },
+                excerpt: None,
+            },
+        ];
+
+        let prompt = build_prompt(&pr, "0123456789abcdef", 2, 3, &jobs);
+
+        assert!(prompt.contains("commit 0123456"));
+        assert!(prompt.contains("repair 2 of 3"));
+        assert!(prompt.contains("## test (https://github.com/acme/shop/actions/runs/9/job/1)"));
+        assert!(prompt.contains("Failed step: `cargo test --workspace`"));
+        assert!(prompt.contains("## lint\nThe log of this job could not be downloaded."));
+    }
+}
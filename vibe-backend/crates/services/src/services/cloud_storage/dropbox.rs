@@ -2,9 +2,13 @@
 //!
 //! Implements OAuth 2.0 flow and file operations for Dropbox.
 
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
-use super::{CloudStorageError, ConnectionStatus, DownloadLinkResult, UploadResult};
+use super::{
+    CloudStorageBackend, CloudStorageError, ConnectionStatus, DownloadLinkResult, RemoteObject,
+    StorageProvider, UploadResult,
+};
 
 const DROPBOX_AUTH_URL: &str = "https://www.dropbox.com/oauth2/authorize";
 const DROPBOX_TOKEN_URL: &str = "https://api.dropbox.com/oauth2/token";
@@ -42,13 +46,17 @@ pub struct AccountName {
 /// File metadata from Dropbox
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DropboxFile {
-    #[serde(rename = ".tag")]
+    /// Absent from upload responses, which always describe a file
+    #[serde(rename = ".tag", default)]
     pub tag: String,
     pub id: Option<String>,
     pub name: String,
     pub path_lower: Option<String>,
     pub path_display: Option<String>,
     pub size: Option<u64>,
+    /// Revision, changes on every write to a file
+    #[serde(default)]
+    pub rev: Option<String>,
 }
 
 /// Temporary link response
@@ -257,6 +265,7 @@ impl DropboxClient {
                 path_lower: Some(path.to_lowercase()),
                 path_display: Some(path.to_string()),
                 size: None,
+                rev: None,
             });
         }
 
@@ -326,6 +335,110 @@ impl DropboxClient {
         })
     }
 
+    /// Write a file, replacing whatever is at `path`
+    pub async fn put_file(
+        &self,
+        access_token: &str,
+        path: &str,
+        content: Vec<u8>,
+    ) -> Result<DropboxFile, CloudStorageError> {
+        let api_arg = serde_json::json!({
+            "path": path,
+            "mode": "overwrite",
+            "autorename": false,
+            "mute": true
+        });
+
+        let response = self
+            .client
+            .post(format!("{}/files/upload", DROPBOX_CONTENT_URL))
+            .bearer_auth(access_token)
+            .header("Content-Type", "application/octet-stream")
+            .header("Dropbox-API-Arg", api_arg.to_string())
+            .body(content)
+            .send()
+            .await
+            .map_err(|e| CloudStorageError::Http(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(CloudStorageError::from_response(response, "Upload failed").await);
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| CloudStorageError::Api(format!("Failed to parse upload response: {}", e)))
+    }
+
+    /// Metadata for a path or `id:` reference, `None` if it does not exist
+    pub async fn get_metadata(
+        &self,
+        access_token: &str,
+        path: &str,
+    ) -> Result<Option<DropboxFile>, CloudStorageError> {
+        let body = serde_json::json!({
+            "path": path
+        });
+
+        let response = self
+            .client
+            .post(format!("{}/files/get_metadata", DROPBOX_API_URL))
+            .bearer_auth(access_token)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| CloudStorageError::Http(e.to_string()))?;
+
+        // 409 Conflict with path/not_found
+        if response.status() == reqwest::StatusCode::CONFLICT {
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            return Err(CloudStorageError::from_response(response, "Failed to get metadata").await);
+        }
+
+        response
+            .json()
+            .await
+            .map(Some)
+            .map_err(|e| CloudStorageError::Api(format!("Failed to parse metadata: {}", e)))
+    }
+
+    /// Download a file's content
+    pub async fn download_file(
+        &self,
+        access_token: &str,
+        path: &str,
+    ) -> Result<Vec<u8>, CloudStorageError> {
+        let api_arg = serde_json::json!({
+            "path": path
+        });
+
+        let response = self
+            .client
+            .post(format!("{}/files/download", DROPBOX_CONTENT_URL))
+            .bearer_auth(access_token)
+            .header("Dropbox-API-Arg", api_arg.to_string())
+            .send()
+            .await
+            .map_err(|e| CloudStorageError::Http(e.to_string()))?;
+
+        if response.status() == reqwest::StatusCode::CONFLICT {
+            return Err(CloudStorageError::NotFound(path.to_string()));
+        }
+
+        if !response.status().is_success() {
+            return Err(CloudStorageError::from_response(response, "Download failed").await);
+        }
+
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| CloudStorageError::DownloadFailed(e.to_string()))?;
+        Ok(bytes.to_vec())
+    }
+
     /// Get a temporary download link (valid for 4 hours)
     pub async fn get_temporary_link(
         &self,
@@ -432,6 +545,99 @@ impl DropboxClient {
     }
 }
 
+/// [`CloudStorageBackend`] over one connected Dropbox account.
+///
+/// Paths are placed under `root` (e.g. `/iKanban`); object IDs are Dropbox
+/// `id:` references, which stay valid when a file is moved.
+pub struct DropboxBackend {
+    client: DropboxClient,
+    access_token: String,
+    root: String,
+}
+
+impl DropboxBackend {
+    pub fn new(client: DropboxClient, access_token: String, root: Option<String>) -> Self {
+        let root = root.unwrap_or_default().trim_end_matches('/').to_string();
+        Self {
+            client,
+            access_token,
+            root,
+        }
+    }
+
+    fn full_path(&self, path: &str) -> String {
+        let path = path.trim_matches('/');
+        if path.is_empty() {
+            self.root.clone()
+        } else {
+            format!("{}/{}", self.root, path)
+        }
+    }
+}
+
+fn remote_object(file: DropboxFile, path: &str) -> Result<RemoteObject, CloudStorageError> {
+    let etag = file
+        .rev
+        .ok_or_else(|| CloudStorageError::Api(format!("{} is not a file", path)))?;
+    Ok(RemoteObject {
+        id: file.id.unwrap_or_else(|| path.to_string()),
+        etag,
+        size: file.size.map(|size| size as i64),
+    })
+}
+
+#[async_trait]
+impl CloudStorageBackend for DropboxBackend {
+    fn provider(&self) -> StorageProvider {
+        StorageProvider::Dropbox
+    }
+
+    async fn ensure_folder(&self, path: &str) -> Result<(), CloudStorageError> {
+        if path.is_empty() && self.root.is_empty() {
+            return Ok(());
+        }
+        // Creates missing parents; an existing folder is not an error
+        self.client
+            .create_folder(&self.access_token, &self.full_path(path))
+            .await
+            .map(|_| ())
+    }
+
+    async fn put_object(
+        &self,
+        path: &str,
+        _existing: Option<&str>,
+        content: Vec<u8>,
+        _content_type: &str,
+    ) -> Result<RemoteObject, CloudStorageError> {
+        let full_path = self.full_path(path);
+        let file = self
+            .client
+            .put_file(&self.access_token, &full_path, content)
+            .await?;
+        remote_object(file, &full_path)
+    }
+
+    async fn stat_object(&self, id: &str) -> Result<Option<RemoteObject>, CloudStorageError> {
+        match self.client.get_metadata(&self.access_token, id).await? {
+            Some(file) if file.tag == "file" => remote_object(file, id).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    async fn get_object(&self, id: &str) -> Result<Vec<u8>, CloudStorageError> {
+        self.client.download_file(&self.access_token, id).await
+    }
+
+    async fn delete_object(&self, id: &str) -> Result<(), CloudStorageError> {
+        self.client.delete_file(&self.access_token, id).await
+    }
+
+    async fn download_link(&self, id: &str) -> Result<DownloadLinkResult, CloudStorageError> {
+        self.client.get_temporary_link(&self.access_token, id).await
+    }
+}
+
 /// URL encoding helper
 mod urlencoding {
     pub fn encode(s: &str) -> String {
@@ -2,15 +2,26 @@
 //!
 //! Implements OAuth 2.0 flow and file operations for Google Drive.
 
+use std::collections::HashMap;
+
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
 
-use super::{CloudStorageError, ConnectionStatus, DownloadLinkResult, UploadResult};
+use super::{
+    CloudStorageBackend, CloudStorageError, ConnectionStatus, DownloadLinkResult, RemoteObject,
+    StorageProvider, UploadResult,
+};
 
 const GOOGLE_AUTH_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
 const GOOGLE_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
 const GOOGLE_DRIVE_API_URL: &str = "https://www.googleapis.com/drive/v3";
 const GOOGLE_UPLOAD_URL: &str = "https://www.googleapis.com/upload/drive/v3";
 const GOOGLE_USERINFO_URL: &str = "https://www.googleapis.com/oauth2/v2/userinfo";
+const FOLDER_MIME_TYPE: &str = "application/vnd.google-apps.folder";
+/// File fields requested from the API
+const FILE_FIELDS: &str =
+    "id,name,mimeType,size,md5Checksum,version,trashed,webViewLink,webContentLink";
 
 /// Scopes required for Google Drive access
 const SCOPES: &[&str] = &[
@@ -50,6 +61,25 @@ pub struct DriveFile {
     pub web_view_link: Option<String>,
     #[serde(rename = "webContentLink")]
     pub web_content_link: Option<String>,
+    #[serde(rename = "md5Checksum", default)]
+    pub md5_checksum: Option<String>,
+    /// Increases on every change to the file
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub trashed: bool,
+}
+
+impl DriveFile {
+    /// Content checksum, falling back to the version for files without one
+    fn etag(&self) -> Option<String> {
+        self.md5_checksum.clone().or_else(|| self.version.clone())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct FileList {
+    files: Vec<DriveFile>,
 }
 
 /// Google Drive client
@@ -206,7 +236,7 @@ impl GoogleDriveClient {
     ) -> Result<DriveFile, CloudStorageError> {
         let mut metadata = serde_json::json!({
             "name": name,
-            "mimeType": "application/vnd.google-apps.folder"
+            "mimeType": FOLDER_MIME_TYPE
         });
 
         if let Some(parent) = parent_id {
@@ -245,6 +275,29 @@ impl GoogleDriveClient {
         mime_type: &str,
         parent_id: Option<&str>,
     ) -> Result<UploadResult, CloudStorageError> {
+        let size = content.len() as i64;
+        let file = self
+            .create_file(access_token, filename, content, mime_type, parent_id)
+            .await?;
+
+        Ok(UploadResult {
+            file_id: file.id,
+            name: file.name,
+            size,
+            mime_type: file.mime_type,
+            web_link: file.web_view_link,
+        })
+    }
+
+    /// Create a file with a multipart upload, returning its metadata
+    pub async fn create_file(
+        &self,
+        access_token: &str,
+        filename: &str,
+        content: Vec<u8>,
+        mime_type: &str,
+        parent_id: Option<&str>,
+    ) -> Result<DriveFile, CloudStorageError> {
         // Create file metadata
         let mut metadata = serde_json::json!({
             "name": filename
@@ -259,7 +312,7 @@ impl GoogleDriveClient {
             .mime_str("application/json")
             .map_err(|e| CloudStorageError::Http(e.to_string()))?;
 
-        let file_part = reqwest::multipart::Part::bytes(content.clone())
+        let file_part = reqwest::multipart::Part::bytes(content)
             .mime_str(mime_type)
             .map_err(|e| CloudStorageError::Http(e.to_string()))?;
 
@@ -269,7 +322,10 @@ impl GoogleDriveClient {
 
         let response = self
             .client
-            .post(format!("{}/files?uploadType=multipart&fields=id,name,mimeType,size,webViewLink,webContentLink", GOOGLE_UPLOAD_URL))
+            .post(format!(
+                "{}/files?uploadType=multipart&fields={}",
+                GOOGLE_UPLOAD_URL, FILE_FIELDS
+            ))
             .bearer_auth(access_token)
             .multipart(form)
             .send()
@@ -277,24 +333,142 @@ impl GoogleDriveClient {
             .map_err(|e| CloudStorageError::Http(e.to_string()))?;
 
         if !response.status().is_success() {
-            let error_body = response.text().await.unwrap_or_default();
-            return Err(CloudStorageError::UploadFailed(format!(
-                "Upload failed: {}",
-                error_body
-            )));
+            return Err(CloudStorageError::from_response(response, "Upload failed").await);
         }
 
-        let file: DriveFile = response.json().await.map_err(|e| {
-            CloudStorageError::Api(format!("Failed to parse upload response: {}", e))
-        })?;
+        response
+            .json()
+            .await
+            .map_err(|e| CloudStorageError::Api(format!("Failed to parse upload response: {}", e)))
+    }
 
-        Ok(UploadResult {
-            file_id: file.id,
-            name: file.name,
-            size: content.len() as i64,
-            mime_type: file.mime_type,
-            web_link: file.web_view_link,
-        })
+    /// Replace the content of an existing file, keeping its ID
+    pub async fn update_file_content(
+        &self,
+        access_token: &str,
+        file_id: &str,
+        content: Vec<u8>,
+        mime_type: &str,
+    ) -> Result<DriveFile, CloudStorageError> {
+        let response = self
+            .client
+            .patch(format!(
+                "{}/files/{}?uploadType=media&fields={}",
+                GOOGLE_UPLOAD_URL, file_id, FILE_FIELDS
+            ))
+            .bearer_auth(access_token)
+            .header(reqwest::header::CONTENT_TYPE, mime_type)
+            .body(content)
+            .send()
+            .await
+            .map_err(|e| CloudStorageError::Http(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(CloudStorageError::from_response(response, "Update failed").await);
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| CloudStorageError::Api(format!("Failed to parse update response: {}", e)))
+    }
+
+    /// File metadata, `None` if the file does not exist
+    pub async fn get_file(
+        &self,
+        access_token: &str,
+        file_id: &str,
+    ) -> Result<Option<DriveFile>, CloudStorageError> {
+        let response = self
+            .client
+            .get(format!(
+                "{}/files/{}?fields={}",
+                GOOGLE_DRIVE_API_URL, file_id, FILE_FIELDS
+            ))
+            .bearer_auth(access_token)
+            .send()
+            .await
+            .map_err(|e| CloudStorageError::Http(e.to_string()))?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(CloudStorageError::from_response(response, "Failed to get file").await);
+        }
+
+        response
+            .json()
+            .await
+            .map(Some)
+            .map_err(|e| CloudStorageError::Api(format!("Failed to parse file response: {}", e)))
+    }
+
+    /// Download a file's content
+    pub async fn download_file(
+        &self,
+        access_token: &str,
+        file_id: &str,
+    ) -> Result<Vec<u8>, CloudStorageError> {
+        let response = self
+            .client
+            .get(format!(
+                "{}/files/{}?alt=media",
+                GOOGLE_DRIVE_API_URL, file_id
+            ))
+            .bearer_auth(access_token)
+            .send()
+            .await
+            .map_err(|e| CloudStorageError::Http(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(CloudStorageError::from_response(response, "Download failed").await);
+        }
+
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| CloudStorageError::DownloadFailed(e.to_string()))?;
+        Ok(bytes.to_vec())
+    }
+
+    /// Find a folder by name directly under `parent_id`
+    pub async fn find_folder(
+        &self,
+        access_token: &str,
+        name: &str,
+        parent_id: &str,
+    ) -> Result<Option<DriveFile>, CloudStorageError> {
+        let escaped = name.replace('\\', "\\\\").replace('\'', "\\'");
+        let query = format!(
+            "name = '{}' and '{}' in parents and mimeType = '{}' and trashed = false",
+            escaped, parent_id, FOLDER_MIME_TYPE
+        );
+
+        let response = self
+            .client
+            .get(format!("{}/files", GOOGLE_DRIVE_API_URL))
+            .query(&[
+                ("q", query.as_str()),
+                ("fields", "files(id,name,mimeType)"),
+                ("pageSize", "1"),
+            ])
+            .bearer_auth(access_token)
+            .send()
+            .await
+            .map_err(|e| CloudStorageError::Http(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(
+                CloudStorageError::from_response(response, "Failed to search folders").await,
+            );
+        }
+
+        let list: FileList = response
+            .json()
+            .await
+            .map_err(|e| CloudStorageError::Api(format!("Failed to parse file list: {}", e)))?;
+        Ok(list.files.into_iter().next())
     }
 
     /// Get a download link for a file
@@ -399,6 +573,144 @@ impl GoogleDriveClient {
     }
 }
 
+/// [`CloudStorageBackend`] over one connected Drive account.
+///
+/// Drive addresses files by ID, so folder paths are resolved (and created)
+/// one segment at a time below the root folder and cached for the lifetime
+/// of the backend. Object IDs are Drive file IDs.
+pub struct GoogleDriveBackend {
+    client: GoogleDriveClient,
+    access_token: String,
+    root_folder_id: String,
+    folders: Mutex<HashMap<String, String>>,
+}
+
+impl GoogleDriveBackend {
+    /// `root_folder_id` defaults to the root of "My Drive"
+    pub fn new(
+        client: GoogleDriveClient,
+        access_token: String,
+        root_folder_id: Option<String>,
+    ) -> Self {
+        Self {
+            client,
+            access_token,
+            root_folder_id: root_folder_id.unwrap_or_else(|| "root".to_string()),
+            folders: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// ID of the folder at `path`, creating missing folders along the way
+    async fn folder_id(&self, path: &str) -> Result<String, CloudStorageError> {
+        // Held across the lookups so concurrent callers never create the
+        // same folder twice
+        let mut folders = self.folders.lock().await;
+        let mut parent = self.root_folder_id.clone();
+        let mut current = String::new();
+
+        for segment in path.split('/').filter(|s| !s.is_empty()) {
+            if !current.is_empty() {
+                current.push('/');
+            }
+            current.push_str(segment);
+
+            if let Some(id) = folders.get(&current) {
+                parent = id.clone();
+                continue;
+            }
+
+            let id = match self
+                .client
+                .find_folder(&self.access_token, segment, &parent)
+                .await?
+            {
+                Some(folder) => folder.id,
+                None => {
+                    self.client
+                        .create_folder(&self.access_token, segment, Some(&parent))
+                        .await?
+                        .id
+                }
+            };
+            folders.insert(current.clone(), id.clone());
+            parent = id;
+        }
+
+        Ok(parent)
+    }
+}
+
+fn remote_object(file: DriveFile) -> Result<RemoteObject, CloudStorageError> {
+    let etag = file
+        .etag()
+        .ok_or_else(|| CloudStorageError::Api(format!("Drive file {} has no checksum", file.id)))?;
+    Ok(RemoteObject {
+        size: file.size.as_deref().and_then(|size| size.parse().ok()),
+        id: file.id,
+        etag,
+    })
+}
+
+#[async_trait]
+impl CloudStorageBackend for GoogleDriveBackend {
+    fn provider(&self) -> StorageProvider {
+        StorageProvider::GoogleDrive
+    }
+
+    async fn ensure_folder(&self, path: &str) -> Result<(), CloudStorageError> {
+        self.folder_id(path).await.map(|_| ())
+    }
+
+    async fn put_object(
+        &self,
+        path: &str,
+        existing: Option<&str>,
+        content: Vec<u8>,
+        content_type: &str,
+    ) -> Result<RemoteObject, CloudStorageError> {
+        let file = match existing {
+            Some(file_id) => {
+                self.client
+                    .update_file_content(&self.access_token, file_id, content, content_type)
+                    .await?
+            }
+            None => {
+                let (folder, name) = path.rsplit_once('/').unwrap_or(("", path));
+                let parent = self.folder_id(folder).await?;
+                self.client
+                    .create_file(
+                        &self.access_token,
+                        name,
+                        content,
+                        content_type,
+                        Some(&parent),
+                    )
+                    .await?
+            }
+        };
+        remote_object(file)
+    }
+
+    async fn stat_object(&self, id: &str) -> Result<Option<RemoteObject>, CloudStorageError> {
+        match self.client.get_file(&self.access_token, id).await? {
+            Some(file) if !file.trashed => remote_object(file).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    async fn get_object(&self, id: &str) -> Result<Vec<u8>, CloudStorageError> {
+        self.client.download_file(&self.access_token, id).await
+    }
+
+    async fn delete_object(&self, id: &str) -> Result<(), CloudStorageError> {
+        self.client.delete_file(&self.access_token, id).await
+    }
+
+    async fn download_link(&self, id: &str) -> Result<DownloadLinkResult, CloudStorageError> {
+        self.client.get_download_link(&self.access_token, id).await
+    }
+}
+
 /// URL encoding helper
 mod urlencoding {
     pub fn encode(s: &str) -> String {
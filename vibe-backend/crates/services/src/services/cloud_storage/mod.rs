@@ -4,12 +4,17 @@
 //! - Google Drive (OAuth 2.0)
 //! - AWS S3 (API credentials)
 //! - Dropbox (OAuth 2.0)
+//!
+//! Each provider implements [`CloudStorageBackend`], which the document
+//! [`sync`] engine uses to mirror a team's documents.
 
 pub mod dropbox;
 pub mod encryption;
 pub mod google_drive;
 pub mod s3;
+pub mod sync;
 
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use uuid::Uuid;
@@ -36,6 +41,36 @@ pub enum CloudStorageError {
     Encryption(String),
     #[error("Database error: {0}")]
     Database(String),
+    #[error("Service unavailable: {0}")]
+    Unavailable(String),
+}
+
+impl From<sqlx::Error> for CloudStorageError {
+    fn from(error: sqlx::Error) -> Self {
+        Self::Database(error.to_string())
+    }
+}
+
+impl CloudStorageError {
+    /// Whether retrying the same request may succeed
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::Http(_) | Self::Unavailable(_))
+    }
+
+    /// Map an unsuccessful provider response, keeping rate limits and server
+    /// errors retryable
+    pub(crate) async fn from_response(response: reqwest::Response, context: &str) -> Self {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        let message = format!("{context} ({status}): {body}");
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+            Self::Unavailable(message)
+        } else if status == reqwest::StatusCode::NOT_FOUND {
+            Self::NotFound(message)
+        } else {
+            Self::Api(message)
+        }
+    }
 }
 
 /// Storage provider type
@@ -91,6 +126,49 @@ pub struct DownloadLinkResult {
     pub expires_in: Option<u64>,
 }
 
+/// Current version of an object in a storage backend
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteObject {
+    /// Handle for later reads and deletes: the S3 key, Drive file ID or
+    /// Dropbox `id:` reference
+    pub id: String,
+    /// Changes whenever the object's content changes
+    pub etag: String,
+    pub size: Option<i64>,
+}
+
+/// Common file operations over a connected storage provider.
+///
+/// Objects are written to `/`-separated paths relative to the connection's
+/// root folder; afterwards they are addressed by [`RemoteObject::id`].
+#[async_trait]
+pub trait CloudStorageBackend: Send + Sync {
+    fn provider(&self) -> StorageProvider;
+
+    /// Create the folder at `path` and any missing parents
+    async fn ensure_folder(&self, path: &str) -> Result<(), CloudStorageError>;
+
+    /// Write `content` to `path`. When `existing` is given the object is
+    /// replaced in place, so ID-keyed providers keep the same file.
+    async fn put_object(
+        &self,
+        path: &str,
+        existing: Option<&str>,
+        content: Vec<u8>,
+        content_type: &str,
+    ) -> Result<RemoteObject, CloudStorageError>;
+
+    /// Current version of an object, `None` if it no longer exists
+    async fn stat_object(&self, id: &str) -> Result<Option<RemoteObject>, CloudStorageError>;
+
+    async fn get_object(&self, id: &str) -> Result<Vec<u8>, CloudStorageError>;
+
+    /// Delete an object; deleting a missing object succeeds
+    async fn delete_object(&self, id: &str) -> Result<(), CloudStorageError>;
+
+    async fn download_link(&self, id: &str) -> Result<DownloadLinkResult, CloudStorageError>;
+}
+
 /// Storage connection status
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConnectionStatus {
@@ -4,9 +4,16 @@
 
 use std::time::Duration;
 
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
-use super::{CloudStorageError, ConnectionStatus, DownloadLinkResult, UploadResult};
+use super::{
+    CloudStorageBackend, CloudStorageError, ConnectionStatus, DownloadLinkResult, RemoteObject,
+    StorageProvider, UploadResult,
+};
+
+/// Lifetime of download links handed out through [`CloudStorageBackend`]
+const DOWNLOAD_LINK_TTL: Duration = Duration::from_secs(60 * 60);
 
 /// S3 configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -77,6 +84,16 @@ impl S3Client {
         }
     }
 
+    /// URL of an object, with the prefix applied
+    fn object_url(&self, key: &str) -> String {
+        format!(
+            "{}/{}/{}",
+            self.endpoint_url(),
+            self.config.bucket,
+            self.full_key(key)
+        )
+    }
+
     /// Validate S3 bucket access by performing a HEAD request
     pub async fn validate_bucket(&self) -> Result<bool, CloudStorageError> {
         // Use a simple GET request to check bucket exists
@@ -260,6 +277,109 @@ impl S3Client {
     }
 }
 
+/// Object IDs are keys relative to the configured prefix
+#[async_trait]
+impl CloudStorageBackend for S3Client {
+    fn provider(&self) -> StorageProvider {
+        StorageProvider::S3
+    }
+
+    async fn ensure_folder(&self, _path: &str) -> Result<(), CloudStorageError> {
+        // S3 has no folders; the key carries the hierarchy
+        Ok(())
+    }
+
+    async fn put_object(
+        &self,
+        path: &str,
+        _existing: Option<&str>,
+        content: Vec<u8>,
+        content_type: &str,
+    ) -> Result<RemoteObject, CloudStorageError> {
+        let size = content.len() as i64;
+        let response = self
+            .client
+            .put(self.object_url(path))
+            .header(reqwest::header::CONTENT_TYPE, content_type)
+            .body(content)
+            .send()
+            .await
+            .map_err(|e| CloudStorageError::Http(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(CloudStorageError::from_response(response, "S3 upload failed").await);
+        }
+
+        let etag = etag_header(&response)
+            .ok_or_else(|| CloudStorageError::Api("S3 upload returned no ETag".to_string()))?;
+        Ok(RemoteObject {
+            id: path.to_string(),
+            etag,
+            size: Some(size),
+        })
+    }
+
+    async fn stat_object(&self, id: &str) -> Result<Option<RemoteObject>, CloudStorageError> {
+        let response = self
+            .client
+            .head(self.object_url(id))
+            .send()
+            .await
+            .map_err(|e| CloudStorageError::Http(e.to_string()))?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(CloudStorageError::from_response(response, "S3 HEAD failed").await);
+        }
+
+        let etag = etag_header(&response)
+            .ok_or_else(|| CloudStorageError::Api("S3 HEAD returned no ETag".to_string()))?;
+        Ok(Some(RemoteObject {
+            id: id.to_string(),
+            etag,
+            size: response.content_length().map(|len| len as i64),
+        }))
+    }
+
+    async fn get_object(&self, id: &str) -> Result<Vec<u8>, CloudStorageError> {
+        let response = self
+            .client
+            .get(self.object_url(id))
+            .send()
+            .await
+            .map_err(|e| CloudStorageError::Http(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(CloudStorageError::from_response(response, "S3 download failed").await);
+        }
+
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| CloudStorageError::DownloadFailed(e.to_string()))?;
+        Ok(bytes.to_vec())
+    }
+
+    async fn delete_object(&self, id: &str) -> Result<(), CloudStorageError> {
+        self.delete_file(id).await
+    }
+
+    async fn download_link(&self, id: &str) -> Result<DownloadLinkResult, CloudStorageError> {
+        self.generate_presigned_download_url(id, DOWNLOAD_LINK_TTL)
+    }
+}
+
+/// ETag response header without its surrounding quotes
+fn etag_header(response: &reqwest::Response) -> Option<String> {
+    response
+        .headers()
+        .get(reqwest::header::ETAG)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.trim_matches('"').to_string())
+}
+
 /// Validate S3 configuration without creating a full client
 pub fn validate_config(config: &S3Config) -> Result<(), CloudStorageError> {
     if config.bucket.is_empty() {
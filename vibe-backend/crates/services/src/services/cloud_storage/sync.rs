@@ -0,0 +1,742 @@
+//! Document Sync
+//!
+//! Mirrors a team's documents and folders to its connected storage backend.
+//! Folders become directories and documents become files named after their
+//! title. After each upload the remote etag and a hash of the synced content
+//! are stored under `storage_metadata.cloud_sync`, which is how the next run
+//! tells the two sides' edits apart:
+//!
+//! - only the document changed: upload it
+//! - only the remote copy changed: pull it into the document (text documents)
+//! - both changed: resolved by the [`ConflictPolicy`]
+//!
+//! Archived documents are deleted from the backend unless their remote copy
+//! was edited since the last sync. Transient provider errors are retried
+//! with backoff; a document that still fails is reported without stopping
+//! the run.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+
+use backon::{ExponentialBuilder, Retryable};
+use chrono::{DateTime, Utc};
+use db::models::{
+    document::{Document, DocumentFileType, DocumentFolder},
+    team_storage_config::TeamStorageConfig,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::{
+    CloudStorageBackend, CloudStorageError, StorageProvider,
+    dropbox::{DropboxBackend, DropboxClient},
+    encryption::{decrypt_credential, encrypt_credential},
+    google_drive::{GoogleDriveBackend, GoogleDriveClient},
+    s3::{S3Client, S3Config},
+    sanitize_filename,
+};
+
+const SYNC_METADATA_KEY: &str = "cloud_sync";
+
+/// What to do when a document and its remote copy both changed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// Upload the document and keep the remote version next to it as a
+    /// conflict copy
+    #[default]
+    KeepBoth,
+    /// Overwrite the remote copy
+    PreferLocal,
+    /// Replace the document with the remote copy; documents that cannot be
+    /// edited in place fall back to keeping both
+    PreferRemote,
+}
+
+/// Sync state of one document, stored under `storage_metadata.cloud_sync`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DocumentSyncState {
+    pub provider: StorageProvider,
+    pub remote_id: String,
+    pub path: String,
+    pub etag: String,
+    /// SHA-256 of the content last uploaded or pulled
+    pub content_hash: String,
+    pub synced_at: DateTime<Utc>,
+}
+
+impl DocumentSyncState {
+    /// State recorded for `provider`; state left by a previously connected
+    /// provider is ignored so the document is uploaded afresh
+    pub fn from_metadata(
+        metadata: Option<&serde_json::Value>,
+        provider: &StorageProvider,
+    ) -> Option<Self> {
+        let state: Self = serde_json::from_value(metadata?.get(SYNC_METADATA_KEY)?.clone()).ok()?;
+        (state.provider == *provider).then_some(state)
+    }
+
+    fn to_value(&self) -> serde_json::Value {
+        serde_json::to_value(self).expect("sync state serializes")
+    }
+}
+
+/// A document as the sync engine sees it
+#[derive(Debug, Clone)]
+pub struct LocalDocument {
+    pub id: Uuid,
+    /// Path relative to the backend root
+    pub path: String,
+    pub content: Vec<u8>,
+    pub content_type: String,
+    /// Content lives in the database, so remote edits can be pulled into it
+    pub editable: bool,
+    pub state: Option<DocumentSyncState>,
+}
+
+/// Result of syncing one document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncOutcome {
+    Unchanged,
+    Uploaded(DocumentSyncState),
+    /// The remote copy replaces the document's content
+    Pulled {
+        content: String,
+        state: DocumentSyncState,
+    },
+    /// Both sides changed; the remote version was saved at `conflict_copy`
+    /// and the document uploaded over it
+    Conflict {
+        conflict_copy: String,
+        state: DocumentSyncState,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncFailure {
+    /// `None` for folders
+    pub document_id: Option<Uuid>,
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SyncReport {
+    pub uploaded: usize,
+    pub pulled: usize,
+    pub deleted: usize,
+    pub unchanged: usize,
+    /// Documents without content the engine can read, e.g. files kept in
+    /// Supabase storage
+    pub skipped: usize,
+    /// Paths of conflict copies written during the run
+    pub conflicts: Vec<String>,
+    pub failed: Vec<SyncFailure>,
+}
+
+/// Sync one document against the backend. Performs no database writes;
+/// the caller stores the new state from the outcome.
+pub async fn sync_document(
+    backend: &dyn CloudStorageBackend,
+    policy: ConflictPolicy,
+    document: &LocalDocument,
+) -> Result<SyncOutcome, CloudStorageError> {
+    let hash = content_hash(&document.content);
+    let Some(state) = &document.state else {
+        return upload(backend, document, hash, None)
+            .await
+            .map(SyncOutcome::Uploaded);
+    };
+
+    let Some(remote) = backend.stat_object(&state.remote_id).await? else {
+        // Deleted remotely while the document still exists; restore it
+        return upload(backend, document, hash, None)
+            .await
+            .map(SyncOutcome::Uploaded);
+    };
+
+    let local_changed = hash != state.content_hash;
+    if remote.etag == state.etag {
+        if !local_changed && document.path == state.path {
+            return Ok(SyncOutcome::Unchanged);
+        }
+        return upload(backend, document, hash, Some(&state.remote_id))
+            .await
+            .map(SyncOutcome::Uploaded);
+    }
+
+    // The remote copy changed since the last sync
+    let pull = document.editable
+        && match policy {
+            ConflictPolicy::KeepBoth => !local_changed,
+            ConflictPolicy::PreferLocal => false,
+            ConflictPolicy::PreferRemote => true,
+        };
+    if policy == ConflictPolicy::PreferLocal {
+        return upload(backend, document, hash, Some(&state.remote_id))
+            .await
+            .map(SyncOutcome::Uploaded);
+    }
+
+    let remote_content = backend.get_object(&state.remote_id).await?;
+    let remote_content = if pull {
+        match String::from_utf8(remote_content) {
+            Ok(content) => {
+                return Ok(SyncOutcome::Pulled {
+                    state: DocumentSyncState {
+                        etag: remote.etag,
+                        content_hash: content_hash(content.as_bytes()),
+                        synced_at: Utc::now(),
+                        ..state.clone()
+                    },
+                    content,
+                });
+            }
+            // Not text; keep both instead
+            Err(error) => error.into_bytes(),
+        }
+    } else {
+        remote_content
+    };
+
+    let conflict_copy = conflict_path(&state.path, Utc::now());
+    backend
+        .put_object(&conflict_copy, None, remote_content, &document.content_type)
+        .await?;
+    let state = upload(backend, document, hash, Some(&state.remote_id)).await?;
+    Ok(SyncOutcome::Conflict {
+        conflict_copy,
+        state,
+    })
+}
+
+/// Upload the document, replacing `previous` in place or, when the document
+/// moved, writing the new path and deleting the old object
+async fn upload(
+    backend: &dyn CloudStorageBackend,
+    document: &LocalDocument,
+    content_hash: String,
+    previous: Option<&str>,
+) -> Result<DocumentSyncState, CloudStorageError> {
+    let moved = document
+        .state
+        .as_ref()
+        .is_some_and(|state| state.path != document.path);
+    let replace = previous.filter(|_| !moved);
+
+    let object = backend
+        .put_object(
+            &document.path,
+            replace,
+            document.content.clone(),
+            &document.content_type,
+        )
+        .await?;
+
+    if moved
+        && let Some(previous) = previous
+        && previous != object.id
+    {
+        backend.delete_object(previous).await?;
+    }
+
+    Ok(DocumentSyncState {
+        provider: backend.provider(),
+        remote_id: object.id,
+        path: document.path.clone(),
+        etag: object.etag,
+        content_hash,
+        synced_at: Utc::now(),
+    })
+}
+
+fn content_hash(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+/// `notes/plan.md` -> `notes/plan (conflict 2026-03-17 140500).md`
+fn conflict_path(path: &str, at: DateTime<Utc>) -> String {
+    let (folder, name) = match path.rsplit_once('/') {
+        Some((folder, name)) => (Some(folder), name),
+        None => (None, path),
+    };
+    let stamp = at.format("%Y-%m-%d %H%M%S");
+    let name = match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => {
+            format!("{stem} (conflict {stamp}).{extension}")
+        }
+        _ => format!("{name} (conflict {stamp})"),
+    };
+    match folder {
+        Some(folder) => format!("{folder}/{name}"),
+        None => name,
+    }
+}
+
+fn file_extension(file_type: &str) -> &'static str {
+    match DocumentFileType::from(file_type.to_string()) {
+        DocumentFileType::Markdown => "md",
+        DocumentFileType::Pdf => "pdf",
+        DocumentFileType::Txt => "txt",
+        DocumentFileType::Csv => "csv",
+        DocumentFileType::Xlsx => "xlsx",
+    }
+}
+
+fn path_segment(name: &str) -> String {
+    match sanitize_filename(name) {
+        name if name.is_empty() => "untitled".to_string(),
+        name => name,
+    }
+}
+
+/// Backend path of every folder, built from its parent chain
+fn folder_paths(folders: &[DocumentFolder]) -> HashMap<Uuid, String> {
+    let by_id: HashMap<Uuid, &DocumentFolder> =
+        folders.iter().map(|folder| (folder.id, folder)).collect();
+
+    folders
+        .iter()
+        .map(|folder| {
+            let mut segments = Vec::new();
+            let mut current = Some(folder);
+            // Bounded in case of a parent cycle
+            while let Some(f) = current
+                && segments.len() <= folders.len()
+            {
+                segments.push(path_segment(&f.name));
+                current = f.parent_id.and_then(|id| by_id.get(&id).copied());
+            }
+            segments.reverse();
+            (folder.id, segments.join("/"))
+        })
+        .collect()
+}
+
+/// Backend path of a document. Documents sharing a title within a folder
+/// get a suffix from their ID, so callers must visit documents in a stable
+/// order.
+fn document_path(
+    document: &Document,
+    folder_paths: &HashMap<Uuid, String>,
+    taken: &mut HashSet<String>,
+) -> String {
+    let folder = document
+        .folder_id
+        .and_then(|id| folder_paths.get(&id))
+        .map(String::as_str)
+        .unwrap_or("");
+    let stem = path_segment(&document.title);
+    let extension = file_extension(&document.file_type);
+    let join = |name: String| {
+        if folder.is_empty() {
+            name
+        } else {
+            format!("{folder}/{name}")
+        }
+    };
+
+    let path = join(format!("{stem}.{extension}"));
+    // Drive and Dropbox compare names case-insensitively
+    if taken.insert(path.to_lowercase()) {
+        return path;
+    }
+    let id = document.id.simple().to_string();
+    let path = join(format!("{stem}-{}.{extension}", &id[..8]));
+    taken.insert(path.to_lowercase());
+    path
+}
+
+/// Open the backend for a stored connection, refreshing an expiring OAuth
+/// token first
+pub async fn open_backend(
+    pool: &PgPool,
+    config: &TeamStorageConfig,
+) -> Result<Arc<dyn CloudStorageBackend>, CloudStorageError> {
+    let provider = StorageProvider::from_str(&config.provider).ok_or_else(|| {
+        CloudStorageError::Config(format!("Unknown storage provider: {}", config.provider))
+    })?;
+
+    match provider {
+        StorageProvider::S3 => {
+            let mut s3_config: S3Config = serde_json::from_value(config.config_data.clone())
+                .map_err(|e| CloudStorageError::Config(format!("Invalid S3 config: {}", e)))?;
+            s3_config.secret_access_key = decrypt_credential(&s3_config.secret_access_key)?;
+            Ok(Arc::new(S3Client::new(s3_config)?))
+        }
+        StorageProvider::GoogleDrive => {
+            let client = GoogleDriveClient::from_env()?;
+            let access_token = match refresh_token_due(config)? {
+                Some(refresh_token) => {
+                    let token = client.refresh_token(&refresh_token).await?;
+                    store_access_token(pool, config, &token.access_token, Some(token.expires_in))
+                        .await?;
+                    token.access_token
+                }
+                None => access_token(config)?,
+            };
+            Ok(Arc::new(GoogleDriveBackend::new(
+                client,
+                access_token,
+                config.folder_id.clone(),
+            )))
+        }
+        StorageProvider::Dropbox => {
+            let client = DropboxClient::from_env()?;
+            let access_token = match refresh_token_due(config)? {
+                Some(refresh_token) => {
+                    let token = client.refresh_token(&refresh_token).await?;
+                    store_access_token(pool, config, &token.access_token, token.expires_in).await?;
+                    token.access_token
+                }
+                None => access_token(config)?,
+            };
+            Ok(Arc::new(DropboxBackend::new(
+                client,
+                access_token,
+                config.folder_id.clone(),
+            )))
+        }
+    }
+}
+
+fn access_token(config: &TeamStorageConfig) -> Result<String, CloudStorageError> {
+    let token = config.access_token.as_deref().ok_or_else(|| {
+        CloudStorageError::NotConnected(format!("{} has no access token", config.provider))
+    })?;
+    decrypt_credential(token)
+}
+
+/// Decrypted refresh token when the access token is missing or expires
+/// within five minutes
+fn refresh_token_due(config: &TeamStorageConfig) -> Result<Option<String>, CloudStorageError> {
+    let expiring = config.access_token.is_none()
+        || config
+            .token_expires_at
+            .is_some_and(|at| at - chrono::Duration::minutes(5) <= Utc::now());
+    match &config.refresh_token {
+        Some(refresh_token) if expiring => decrypt_credential(refresh_token).map(Some),
+        _ => Ok(None),
+    }
+}
+
+async fn store_access_token(
+    pool: &PgPool,
+    config: &TeamStorageConfig,
+    access_token: &str,
+    expires_in: Option<i64>,
+) -> Result<(), CloudStorageError> {
+    let expires_at = expires_in.map(|secs| Utc::now() + chrono::Duration::seconds(secs));
+    TeamStorageConfig::update_access_token(
+        pool,
+        config.id,
+        &encrypt_credential(access_token)?,
+        expires_at,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Mirrors one team's documents to a storage backend
+pub struct DocumentSyncEngine {
+    pool: PgPool,
+    backend: Arc<dyn CloudStorageBackend>,
+    policy: ConflictPolicy,
+    retry: ExponentialBuilder,
+}
+
+impl DocumentSyncEngine {
+    pub fn new(pool: PgPool, backend: Arc<dyn CloudStorageBackend>) -> Self {
+        Self {
+            pool,
+            backend,
+            policy: ConflictPolicy::default(),
+            retry: ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        }
+    }
+
+    /// Engine for the team's active storage connection
+    pub async fn for_team(pool: PgPool, team_id: Uuid) -> Result<Self, CloudStorageError> {
+        let config = TeamStorageConfig::find_active_by_team(&pool, team_id)
+            .await?
+            .ok_or_else(|| {
+                CloudStorageError::NotConnected(format!(
+                    "Team {} has no storage connection",
+                    team_id
+                ))
+            })?;
+        let backend = open_backend(&pool, &config).await?;
+        Ok(Self::new(pool, backend))
+    }
+
+    pub fn with_policy(mut self, policy: ConflictPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn with_retry(mut self, retry: ExponentialBuilder) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub async fn sync_team(&self, team_id: Uuid) -> Result<SyncReport, CloudStorageError> {
+        let mut report = SyncReport::default();
+
+        let folders = DocumentFolder::find_all_by_team(&self.pool, team_id).await?;
+        let folder_paths = folder_paths(&folders);
+        let mut paths: Vec<&String> = folder_paths.values().collect();
+        paths.sort();
+        paths.dedup();
+        for path in paths {
+            if let Err(error) = self.retrying(|| self.backend.ensure_folder(path)).await {
+                report.failed.push(SyncFailure {
+                    document_id: None,
+                    path: path.clone(),
+                    error: error.to_string(),
+                });
+            }
+        }
+
+        let mut documents = Document::find_all_by_team(&self.pool, team_id, true).await?;
+        documents.sort_by_key(|document| (document.created_at, document.id));
+
+        let provider = self.backend.provider();
+        let mut taken = HashSet::new();
+        for document in documents {
+            let state =
+                DocumentSyncState::from_metadata(document.storage_metadata.as_ref(), &provider);
+
+            if document.is_archived {
+                if let Some(state) = state {
+                    self.remove(&document, &state, &mut report).await;
+                }
+                continue;
+            }
+
+            let path = document_path(&document, &folder_paths, &mut taken);
+            let Some(local) = load_document(&document, path, state).await else {
+                report.skipped += 1;
+                continue;
+            };
+
+            let result = match self
+                .retrying(|| sync_document(self.backend.as_ref(), self.policy, &local))
+                .await
+            {
+                Ok(outcome) => self.apply(&document, outcome, &mut report).await,
+                Err(error) => Err(error),
+            };
+            if let Err(error) = result {
+                tracing::warn!(document_id = %document.id, "Document sync failed: {}", error);
+                report.failed.push(SyncFailure {
+                    document_id: Some(document.id),
+                    path: local.path,
+                    error: error.to_string(),
+                });
+            }
+        }
+
+        tracing::info!(
+            %team_id,
+            uploaded = report.uploaded,
+            pulled = report.pulled,
+            deleted = report.deleted,
+            conflicts = report.conflicts.len(),
+            failed = report.failed.len(),
+            "Document sync finished"
+        );
+        Ok(report)
+    }
+
+    async fn apply(
+        &self,
+        document: &Document,
+        outcome: SyncOutcome,
+        report: &mut SyncReport,
+    ) -> Result<(), CloudStorageError> {
+        let state = match outcome {
+            SyncOutcome::Unchanged => {
+                report.unchanged += 1;
+                return Ok(());
+            }
+            SyncOutcome::Uploaded(state) => {
+                report.uploaded += 1;
+                state
+            }
+            SyncOutcome::Conflict {
+                conflict_copy,
+                state,
+            } => {
+                report.conflicts.push(conflict_copy);
+                state
+            }
+            SyncOutcome::Pulled { content, state } => {
+                let applied = Document::update_synced_content(
+                    &self.pool,
+                    document.id,
+                    &content,
+                    document.updated_at,
+                )
+                .await?;
+                if !applied {
+                    // Edited during the sync; the next run sees both sides
+                    // changed and resolves it as a conflict
+                    return Ok(());
+                }
+                report.pulled += 1;
+                state
+            }
+        };
+        Document::set_cloud_sync_state(&self.pool, document.id, Some(&state.to_value())).await?;
+        Ok(())
+    }
+
+    async fn remove(
+        &self,
+        document: &Document,
+        state: &DocumentSyncState,
+        report: &mut SyncReport,
+    ) {
+        let result = async {
+            let remote = self
+                .retrying(|| self.backend.stat_object(&state.remote_id))
+                .await?;
+            match remote {
+                Some(remote) if remote.etag != state.etag => {
+                    // Edited remotely after the last sync; leave it in place
+                }
+                Some(_) => {
+                    self.retrying(|| self.backend.delete_object(&state.remote_id))
+                        .await?;
+                    report.deleted += 1;
+                }
+                None => {}
+            }
+            Document::set_cloud_sync_state(&self.pool, document.id, None).await?;
+            Ok::<_, CloudStorageError>(())
+        }
+        .await;
+
+        if let Err(error) = result {
+            report.failed.push(SyncFailure {
+                document_id: Some(document.id),
+                path: state.path.clone(),
+                error: error.to_string(),
+            });
+        }
+    }
+
+    async fn retrying<T, F, Fut>(&self, operation: F) -> Result<T, CloudStorageError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, CloudStorageError>>,
+    {
+        operation
+            .retry(&self.retry)
+            .when(CloudStorageError::is_transient)
+            .notify(|err: &CloudStorageError, dur: Duration| {
+                tracing::warn!(
+                    "Cloud storage request failed, retrying after {:.2}s: {}",
+                    dur.as_secs_f64(),
+                    err
+                );
+            })
+            .await
+    }
+}
+
+/// Read a document's content: the text column, or the file on disk for
+/// uploaded files. `None` when neither is available.
+async fn load_document(
+    document: &Document,
+    path: String,
+    state: Option<DocumentSyncState>,
+) -> Option<LocalDocument> {
+    if let Some(content) = &document.content {
+        return Some(LocalDocument {
+            id: document.id,
+            path,
+            content: content.clone().into_bytes(),
+            content_type: document
+                .mime_type
+                .clone()
+                .unwrap_or_else(|| "text/markdown".to_string()),
+            editable: true,
+            state,
+        });
+    }
+
+    let file_path = document.file_path.as_ref()?;
+    match tokio::fs::read(file_path).await {
+        Ok(content) => Some(LocalDocument {
+            id: document.id,
+            path,
+            content,
+            content_type: document
+                .mime_type
+                .clone()
+                .unwrap_or_else(|| "application/octet-stream".to_string()),
+            editable: false,
+            state,
+        }),
+        Err(error) => {
+            tracing::warn!(document_id = %document.id, file_path, "Cannot read document file: {}", error);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn conflict_path_keeps_folder_and_extension() {
+        let at = Utc.with_ymd_and_hms(2026, 3, 17, 14, 5, 0).unwrap();
+        assert_eq!(
+            conflict_path("notes/plan.md", at),
+            "notes/plan (conflict 2026-03-17 140500).md"
+        );
+        assert_eq!(
+            conflict_path("README", at),
+            "README (conflict 2026-03-17 140500)"
+        );
+    }
+
+    #[test]
+    fn sync_state_is_scoped_to_provider() {
+        let state = DocumentSyncState {
+            provider: StorageProvider::S3,
+            remote_id: "teams/plan.md".to_string(),
+            path: "plan.md".to_string(),
+            etag: "abc".to_string(),
+            content_hash: content_hash(b"plan"),
+            synced_at: Utc::now(),
+        };
+        let metadata = serde_json::json!({ "bucket": "docs", "cloud_sync": state.to_value() });
+
+        assert_eq!(
+            DocumentSyncState::from_metadata(Some(&metadata), &StorageProvider::S3),
+            Some(state)
+        );
+        assert_eq!(
+            DocumentSyncState::from_metadata(Some(&metadata), &StorageProvider::Dropbox),
+            None
+        );
+        assert_eq!(
+            DocumentSyncState::from_metadata(None, &StorageProvider::S3),
+            None
+        );
+    }
+}
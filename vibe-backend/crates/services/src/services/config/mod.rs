@@ -26,6 +26,8 @@ pub type EditorType = versions::v8::EditorType;
 pub type GitHubConfig = versions::v8::GitHubConfig;
 pub type UiLanguage = versions::v8::UiLanguage;
 pub type ShowcaseState = versions::v8::ShowcaseState;
+pub type ExecutionConcurrencyConfig = versions::v8::ExecutionConcurrencyConfig;
+pub type CiRepairConfig = versions::v8::CiRepairConfig;
 
 /// Will always return config, trying old schemas or eventually returning default
 pub async fn load_config_from_file(config_path: &PathBuf) -> Config {
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use anyhow::Error;
 use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
 use serde::{Deserialize, Serialize};
@@ -17,6 +19,56 @@ fn default_pr_auto_description_enabled() -> bool {
     true
 }
 
+fn default_transcript_retention_days() -> Option<u32> {
+    Some(90)
+}
+
+/// Caps on how many attempts run at once. Attempts started over a cap wait
+/// in the execution queue; `None` means unlimited.
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct ExecutionConcurrencyConfig {
+    #[serde(default)]
+    pub max_running: Option<u32>,
+    #[serde(default)]
+    pub max_running_per_project: Option<u32>,
+    /// Keyed by executor, e.g. `CLAUDE_CODE`
+    #[serde(default)]
+    pub max_running_per_executor: HashMap<String, u32>,
+}
+
+impl Default for ExecutionConcurrencyConfig {
+    fn default() -> Self {
+        Self {
+            max_running: Some(4),
+            max_running_per_project: None,
+            max_running_per_executor: HashMap::new(),
+        }
+    }
+}
+
+/// Agent follow-ups that try to fix failing CI on an attempt's PR. Once a PR
+/// has used `max_attempts_per_pr` repairs the failure goes to the inbox.
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct CiRepairConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_ci_repair_max_attempts_per_pr")]
+    pub max_attempts_per_pr: u32,
+}
+
+fn default_ci_repair_max_attempts_per_pr() -> u32 {
+    3
+}
+
+impl Default for CiRepairConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_attempts_per_pr: default_ci_repair_max_attempts_per_pr(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, TS)]
 pub struct Config {
     pub config_version: String,
@@ -41,6 +93,16 @@ pub struct Config {
     pub pr_auto_description_enabled: bool,
     #[serde(default)]
     pub pr_auto_description_prompt: Option<String>,
+    /// Days stored execution transcripts are kept; `None` keeps them forever
+    #[serde(default = "default_transcript_retention_days")]
+    pub transcript_retention_days: Option<u32>,
+    #[serde(default)]
+    pub execution_concurrency: ExecutionConcurrencyConfig,
+    /// Address new review comments as soon as a PR review is submitted
+    #[serde(default)]
+    pub pr_review_auto_address: bool,
+    #[serde(default)]
+    pub ci_repair: CiRepairConfig,
 }
 
 impl Config {
@@ -66,6 +128,10 @@ impl Config {
             showcases: old_config.showcases,
             pr_auto_description_enabled: true,
             pr_auto_description_prompt: None,
+            transcript_retention_days: default_transcript_retention_days(),
+            execution_concurrency: ExecutionConcurrencyConfig::default(),
+            pr_review_auto_address: false,
+            ci_repair: CiRepairConfig::default(),
         }
     }
 
@@ -122,6 +188,10 @@ impl Default for Config {
             showcases: ShowcaseState::default(),
             pr_auto_description_enabled: true,
             pr_auto_description_prompt: None,
+            transcript_retention_days: default_transcript_retention_days(),
+            execution_concurrency: ExecutionConcurrencyConfig::default(),
+            pr_review_auto_address: false,
+            ci_repair: CiRepairConfig::default(),
         }
     }
 }
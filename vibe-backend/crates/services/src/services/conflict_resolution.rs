@@ -0,0 +1,601 @@
+//! Resolving rebase and merge conflicts with the coding agent.
+//!
+//! When a rebase (or merge, cherry-pick or revert) stops on conflicts, the
+//! agent gets a follow-up in the conflicted worktree with the conflicted
+//! files, the commits on both sides and the task description. When that run
+//! is over, files still holding conflict markers are reported back; if there
+//! are none the resolution is staged and the stopped operation continued.
+
+use std::path::{Path, PathBuf};
+
+use db::models::{
+    conflict_resolution::{ConflictResolution, ConflictResolutionStatus, CreateConflictResolution},
+    execution_process::{ExecutionProcess, ExecutionProcessStatus},
+    repo::Repo,
+    task::Task,
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::services::{
+    container::{ContainerError, ContainerService},
+    git::{ConflictOp, GitService, GitServiceError},
+    prompt::fence_for,
+};
+
+/// Commit subjects listed per side of the conflict
+const MAX_SUBJECTS_PER_SIDE: usize = 20;
+
+#[derive(Debug, Error)]
+pub enum ConflictResolutionError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error("Repository not found")]
+    RepoNotFound,
+    #[error("Task not found")]
+    TaskNotFound,
+    #[error("There are no conflicts to resolve")]
+    NoConflicts,
+    #[error("The attempt has a running process")]
+    AttemptBusy,
+}
+
+/// What stopped on conflicts, and the commits on either side
+#[derive(Debug, Clone)]
+pub struct ConflictContext {
+    pub op: ConflictOp,
+    pub repo_name: String,
+    pub task_branch: String,
+    pub target_branch: String,
+    pub conflicted_files: Vec<String>,
+    /// Commits on the target branch the task branch doesn't have
+    pub target_subjects: Vec<String>,
+    /// Commits of the task branch being replayed
+    pub task_subjects: Vec<String>,
+}
+
+fn op_name(op: &ConflictOp) -> &'static str {
+    match op {
+        ConflictOp::Rebase => "rebase",
+        ConflictOp::Merge => "merge",
+        ConflictOp::CherryPick => "cherry-pick",
+        ConflictOp::Revert => "revert",
+    }
+}
+
+/// True if `text` still has a conflict block, i.e. both an opening and a
+/// closing marker at the start of a line
+pub fn has_conflict_markers(text: &str) -> bool {
+    let is_marker = |line: &str, marker: &str| {
+        line.strip_prefix(marker)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+    };
+    let mut opened = false;
+    for line in text.lines() {
+        if is_marker(line, "<<<<<<<") {
+            opened = true;
+        } else if opened && is_marker(line, ">>>>>>>") {
+            return true;
+        }
+    }
+    false
+}
+
+fn push_subjects(prompt: &mut String, heading: &str, subjects: &[String]) {
+    prompt.push_str(&format!("\n## {heading}\n"));
+    if subjects.is_empty() {
+        prompt.push_str("None found.\n");
+    }
+    for subject in subjects {
+        prompt.push_str(&format!("- {subject}\n"));
+    }
+}
+
+/// The follow-up prompt: what stopped, the conflicted files, the commits on
+/// both sides and what the task set out to do
+pub fn build_prompt(task: &Task, context: &ConflictContext) -> String {
+    let op = op_name(&context.op);
+    let mut prompt = format!(
+        "A git {op} of branch `{}` onto `{}` in the `{}` repository stopped on conflicts. \
+         Resolve every conflict so that the changes from both sides are kept and the result \
+         builds, then remove the conflict markers and stage the files with `git add`.\n\nDon't \
+         commit, and don't run `git {op} --continue` or `--abort` yourself: the {op} is \
+         continued for you when you finish, and files that still have conflict markers are \
+         reported back.\n",
+        context.task_branch, context.target_branch, context.repo_name
+    );
+
+    prompt.push_str("\n## Conflicted files\n");
+    for file in &context.conflicted_files {
+        prompt.push_str(&format!("- `{file}`\n"));
+    }
+
+    push_subjects(
+        &mut prompt,
+        &format!("Commits on `{}`", context.target_branch),
+        &context.target_subjects,
+    );
+    push_subjects(
+        &mut prompt,
+        &format!("Commits on `{}` (this task)", context.task_branch),
+        &context.task_subjects,
+    );
+
+    prompt.push_str(&format!("\n## Task: {}\n", task.title));
+    if let Some(description) = task
+        .description
+        .as_deref()
+        .filter(|description| !description.trim().is_empty())
+    {
+        let fence = fence_for(description);
+        prompt.push_str(&format!("{fence}text\n{}\n{fence}\n", description.trim()));
+    }
+
+    prompt
+}
+
+/// The operation stopped in `worktree_path` and its conflicted files
+fn find_conflicts(
+    git: &GitService,
+    worktree_path: &Path,
+) -> Result<(ConflictOp, Vec<String>), ConflictResolutionError> {
+    let conflicted_files = git.get_conflicted_files(worktree_path)?;
+    let Some(op) = git.detect_conflict_op(worktree_path)? else {
+        return Err(ConflictResolutionError::NoConflicts);
+    };
+    if conflicted_files.is_empty() {
+        return Err(ConflictResolutionError::NoConflicts);
+    }
+    Ok((op, conflicted_files))
+}
+
+/// Start a follow-up resolving the conflicts that stopped an operation in
+/// one repository of the attempt
+pub async fn resolve_conflicts<C: ContainerService + Sync>(
+    container: &C,
+    workspace: &Workspace,
+    repo_id: Uuid,
+) -> Result<ConflictResolution, ConflictResolutionError> {
+    let pool = &container.db().pool;
+    let git = container.git();
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Err(ConflictResolutionError::AttemptBusy);
+    }
+
+    let workspace_repo = WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, repo_id)
+        .await?
+        .ok_or(ConflictResolutionError::RepoNotFound)?;
+    let repo = Repo::find_by_id(pool, repo_id)
+        .await?
+        .ok_or(ConflictResolutionError::RepoNotFound)?;
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(ConflictResolutionError::TaskNotFound)?;
+
+    let worktree_path =
+        PathBuf::from(container.ensure_container_exists(workspace).await?).join(&repo.name);
+    let (op, conflicted_files) = find_conflicts(git, &worktree_path)?;
+
+    // The task branch ref still points at the commits being replayed
+    let target_branch = workspace_repo.target_branch;
+    let subjects = |base: &str, branch: &str| {
+        git.get_commit_subjects(&repo.path, base, branch, MAX_SUBJECTS_PER_SIDE)
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to list commits {base}..{branch}: {e}");
+                Vec::new()
+            })
+    };
+    let context = ConflictContext {
+        target_subjects: subjects(&workspace.branch, &target_branch),
+        task_subjects: subjects(&target_branch, &workspace.branch),
+        op,
+        repo_name: repo.name.clone(),
+        task_branch: workspace.branch.clone(),
+        target_branch,
+        conflicted_files,
+    };
+
+    let process = container
+        .start_agent_follow_up(workspace, build_prompt(&task, &context))
+        .await?;
+    Ok(ConflictResolution::create(
+        pool,
+        &CreateConflictResolution {
+            workspace_id: workspace.id,
+            repo_id: repo.id,
+            execution_process_id: process.id,
+            conflicted_files: context.conflicted_files,
+        },
+    )
+    .await?)
+}
+
+/// How a finished resolution turned out
+struct Completion {
+    status: ConflictResolutionStatus,
+    unresolved_files: Vec<String>,
+    error: Option<String>,
+}
+
+impl Completion {
+    fn failed(error: impl Into<String>) -> Self {
+        Self {
+            status: ConflictResolutionStatus::Failed,
+            unresolved_files: Vec::new(),
+            error: Some(error.into()),
+        }
+    }
+
+    fn unresolved(files: Vec<String>, error: Option<String>) -> Self {
+        Self {
+            status: ConflictResolutionStatus::Unresolved,
+            unresolved_files: files,
+            error,
+        }
+    }
+}
+
+/// Check and continue the workspace's conflict resolutions once nothing but
+/// dev servers is running in it
+pub async fn complete_resolutions<C: ContainerService + Sync>(
+    container: &C,
+    workspace: &Workspace,
+) -> Result<(), ConflictResolutionError> {
+    let pool = &container.db().pool;
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Ok(());
+    }
+
+    for resolution in ConflictResolution::find_running_by_workspace_id(pool, workspace.id).await? {
+        let completion = complete_resolution(container, workspace, &resolution)
+            .await
+            .unwrap_or_else(|e| Completion::failed(e.to_string()));
+        if let Some(error) = &completion.error {
+            tracing::warn!("Conflict resolution {}: {}", resolution.id, error);
+        }
+        ConflictResolution::complete(
+            pool,
+            resolution.id,
+            completion.status,
+            &completion.unresolved_files,
+            completion.error.as_deref(),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+async fn complete_resolution<C: ContainerService + Sync>(
+    container: &C,
+    workspace: &Workspace,
+    resolution: &ConflictResolution,
+) -> Result<Completion, ConflictResolutionError> {
+    let pool = &container.db().pool;
+    let finished = ExecutionProcess::find_by_id(pool, resolution.execution_process_id)
+        .await?
+        .is_some_and(|process| process.status == ExecutionProcessStatus::Completed);
+    if !finished {
+        // The conflicts stay as they are for the user to resolve or abort
+        return Ok(Completion::failed("The agent did not finish"));
+    }
+
+    let repo = Repo::find_by_id(pool, resolution.repo_id)
+        .await?
+        .ok_or(ConflictResolutionError::RepoNotFound)?;
+    let worktree_path =
+        PathBuf::from(container.ensure_container_exists(workspace).await?).join(&repo.name);
+
+    continue_resolved(
+        container.git(),
+        &worktree_path,
+        &resolution.conflicted_files.0,
+    )
+    .await
+}
+
+/// Stage the agent's resolution of `conflicted_files` and continue the
+/// stopped operation, unless files still hold conflict markers
+async fn continue_resolved(
+    git: &GitService,
+    worktree_path: &Path,
+    conflicted_files: &[String],
+) -> Result<Completion, ConflictResolutionError> {
+    let mut files = conflicted_files.to_vec();
+    for file in git.get_conflicted_files(worktree_path)? {
+        if !files.contains(&file) {
+            files.push(file);
+        }
+    }
+    let mut marked = Vec::new();
+    for file in &files {
+        // A deleted file was resolved by taking the deletion
+        if let Ok(bytes) = tokio::fs::read(worktree_path.join(file)).await
+            && has_conflict_markers(&String::from_utf8_lossy(&bytes))
+        {
+            marked.push(file.clone());
+        }
+    }
+    if !marked.is_empty() {
+        return Ok(Completion::unresolved(marked, None));
+    }
+
+    // The agent may have continued or aborted on its own
+    if git.detect_conflict_op(worktree_path)?.is_none() {
+        return Ok(Completion {
+            status: ConflictResolutionStatus::Resolved,
+            unresolved_files: Vec::new(),
+            error: None,
+        });
+    }
+
+    git.stage_resolved_files(worktree_path, &files)?;
+    let remaining = git.get_conflicted_files(worktree_path)?;
+    if !remaining.is_empty() {
+        return Ok(Completion::unresolved(remaining, None));
+    }
+    let continued = git.continue_conflicts(worktree_path);
+
+    // A rebase stops again, failing the continue, at the next commit that
+    // conflicts
+    let next = git.get_conflicted_files(worktree_path)?;
+    if let Some(op) = git.detect_conflict_op(worktree_path)?
+        && !next.is_empty()
+    {
+        let error = format!(
+            "The {} stopped on conflicts in a later commit",
+            op_name(&op)
+        );
+        return Ok(Completion::unresolved(next, Some(error)));
+    }
+    continued?;
+
+    Ok(Completion {
+        status: ConflictResolutionStatus::Resolved,
+        unresolved_files: Vec::new(),
+        error: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use db::models::task::TaskStatus;
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::services::{checkpoint, git::GitCli};
+
+    const BRANCH: &str = "vk/1a2b-retry";
+
+    fn git(path: &Path, args: &[&str]) -> String {
+        GitCli::new().git(path, args).unwrap().trim().to_string()
+    }
+
+    fn commit_file(path: &Path, file: &str, content: &str, message: &str) {
+        std::fs::write(path.join(file), content).unwrap();
+        git(path, &["add", file]);
+        git(path, &["commit", "-m", message]);
+    }
+
+    /// An attempt worktree whose rebase onto `main` stopped on conflicts.
+    /// Each of `files` was changed on `main` and in its own attempt commit.
+    struct StoppedRebase {
+        _root: TempDir,
+        git: GitService,
+        repo_path: PathBuf,
+        worktree_path: PathBuf,
+    }
+
+    impl StoppedRebase {
+        fn new(files: &[&str]) -> Self {
+            let root = TempDir::new().unwrap();
+            let repo_path = root.path().join("repo");
+            let worktree_path = root.path().join("worktree");
+            let git_service = GitService::new();
+            git_service
+                .initialize_repo_with_main_branch(&repo_path)
+                .unwrap();
+            git(&repo_path, &["config", "user.name", "Test User"]);
+            git(&repo_path, &["config", "user.email", "test@example.com"]);
+            for file in files {
+                commit_file(&repo_path, file, "base\n", &format!("Add {file}"));
+            }
+
+            git_service
+                .add_worktree(&repo_path, &worktree_path, BRANCH, true)
+                .unwrap();
+            for file in files {
+                commit_file(
+                    &worktree_path,
+                    file,
+                    "attempt\n",
+                    &format!("Retry in {file}"),
+                );
+                commit_file(&repo_path, file, "main\n", &format!("Rework {file}"));
+            }
+
+            let rebase =
+                git_service.rebase_branch(&repo_path, &worktree_path, "main", "main", BRANCH);
+            assert!(
+                matches!(rebase, Err(GitServiceError::MergeConflicts(_))),
+                "{rebase:?}"
+            );
+
+            Self {
+                _root: root,
+                git: git_service,
+                repo_path,
+                worktree_path,
+            }
+        }
+
+        /// What the agent does: replace the conflict block in `file`
+        fn resolve(&self, file: &str) {
+            std::fs::write(self.worktree_path.join(file), "main and attempt\n").unwrap();
+        }
+
+        fn stopped_op(&self) -> Option<ConflictOp> {
+            self.git.detect_conflict_op(&self.worktree_path).unwrap()
+        }
+    }
+
+    fn task(description: Option<&str>) -> Task {
+        Task {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            title: "Add retry to uploads".to_string(),
+            description: description.map(str::to_string),
+            status: TaskStatus::InProgress,
+            parent_workspace_id: None,
+            shared_task_id: None,
+            team_id: None,
+            issue_number: None,
+            priority: None,
+            due_date: None,
+            assignee_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn context() -> ConflictContext {
+        ConflictContext {
+            op: ConflictOp::Rebase,
+            repo_name: "backend".to_string(),
+            task_branch: "vk/1a2b-retry".to_string(),
+            target_branch: "main".to_string(),
+            conflicted_files: vec!["src/upload.rs".to_string(), "Cargo.toml".to_string()],
+            target_subjects: vec!["Bump reqwest".to_string()],
+            task_subjects: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn prompt_lists_files_both_sides_and_task() {
+        let prompt = build_prompt(
+            &task(Some("Retry failed uploads with ```backoff```")),
+            &context(),
+        );
+
+        assert!(prompt.starts_with("A git rebase of branch `vk/1a2b-retry` onto `main`"));
+        assert!(prompt.contains("don't run `git rebase --continue`"));
+        assert!(prompt.contains("- `src/upload.rs`\n- `Cargo.toml`\n"));
+        assert!(prompt.contains("## Commits on `main`\n- Bump reqwest\n"));
+        assert!(prompt.contains("## Commits on `vk/1a2b-retry` (this task)\nNone found.\n"));
+        assert!(prompt.contains("## Task: Add retry to uploads\n````text\n"));
+
+        let without_description = build_prompt(&task(Some("  ")), &context());
+        assert!(without_description.ends_with("## Task: Add retry to uploads\n"));
+    }
+
+    #[test]
+    fn conflict_markers_need_a_whole_block() {
+        let conflicted =
+            "fn main() {\n<<<<<<< HEAD\n    a();\n=======\n    b();\n>>>>>>> 1a2b3c4 (Add b)\n}\n";
+        assert!(has_conflict_markers(conflicted));
+
+        let diff3 = "<<<<<<<\nours\n||||||| base\nbase\n=======\ntheirs\n>>>>>>>\n";
+        assert!(has_conflict_markers(diff3));
+
+        // Setext headings and lone separators are not conflicts
+        assert!(!has_conflict_markers("Title\n=======\n\ntext\n"));
+        assert!(!has_conflict_markers("<<<<<<<< eight\n>>>>>>>> eight\n"));
+        assert!(!has_conflict_markers(
+            ">>>>>>> closing first\n<<<<<<< then open\n"
+        ));
+        assert!(!has_conflict_markers(
+            "    <<<<<<< indented\n    >>>>>>> indented\n"
+        ));
+    }
+
+    #[tokio::test]
+    async fn resolution_of_a_stopped_rebase_continues_it() {
+        let stopped = StoppedRebase::new(&["upload.rs"]);
+        let (op, files) = find_conflicts(&stopped.git, &stopped.worktree_path).unwrap();
+        assert_eq!(op, ConflictOp::Rebase);
+        assert_eq!(files, ["upload.rs"]);
+
+        // The follow-up starting the agent takes no checkpoint mid-rebase
+        let checkpoint = stopped
+            .git
+            .create_checkpoint(
+                &stopped.worktree_path,
+                &checkpoint::checkpoint_ref(Uuid::new_v4()),
+                checkpoint::CHECKPOINT_COMMIT_MESSAGE,
+            )
+            .unwrap();
+        assert!(checkpoint.is_none());
+        assert_eq!(stopped.stopped_op(), Some(ConflictOp::Rebase));
+
+        stopped.resolve("upload.rs");
+        let completion = continue_resolved(&stopped.git, &stopped.worktree_path, &files)
+            .await
+            .unwrap();
+
+        assert_eq!(completion.status, ConflictResolutionStatus::Resolved);
+        assert!(completion.error.is_none());
+        assert_eq!(stopped.stopped_op(), None);
+        let worktree = &stopped.worktree_path;
+        assert_eq!(
+            git(worktree, &["rev-parse", "--abbrev-ref", "HEAD"]),
+            BRANCH
+        );
+        assert_eq!(
+            git(worktree, &["rev-parse", "HEAD^"]),
+            git(&stopped.repo_path, &["rev-parse", "main"])
+        );
+        assert_eq!(
+            git(worktree, &["log", "-1", "--format=%s"]),
+            "Retry in upload.rs"
+        );
+        assert_eq!(
+            std::fs::read_to_string(worktree.join("upload.rs")).unwrap(),
+            "main and attempt\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn markers_left_by_the_agent_keep_the_rebase_stopped() {
+        let stopped = StoppedRebase::new(&["upload.rs"]);
+        let (_, files) = find_conflicts(&stopped.git, &stopped.worktree_path).unwrap();
+
+        let completion = continue_resolved(&stopped.git, &stopped.worktree_path, &files)
+            .await
+            .unwrap();
+
+        assert_eq!(completion.status, ConflictResolutionStatus::Unresolved);
+        assert_eq!(completion.unresolved_files, ["upload.rs"]);
+        assert_eq!(stopped.stopped_op(), Some(ConflictOp::Rebase));
+    }
+
+    #[tokio::test]
+    async fn rebase_stopping_at_a_later_commit_is_unresolved() {
+        let stopped = StoppedRebase::new(&["upload.rs", "retry.rs"]);
+        let (_, files) = find_conflicts(&stopped.git, &stopped.worktree_path).unwrap();
+        assert_eq!(files, ["upload.rs"]);
+
+        stopped.resolve("upload.rs");
+        let completion = continue_resolved(&stopped.git, &stopped.worktree_path, &files)
+            .await
+            .unwrap();
+
+        assert_eq!(completion.status, ConflictResolutionStatus::Unresolved);
+        assert_eq!(completion.unresolved_files, ["retry.rs"]);
+        assert_eq!(
+            completion.error.as_deref(),
+            Some("The rebase stopped on conflicts in a later commit")
+        );
+        assert_eq!(stopped.stopped_op(), Some(ConflictOp::Rebase));
+    }
+}
@@ -9,6 +9,7 @@ use async_trait::async_trait;
 use db::{
     DBService,
     models::{
+        coding_agent_checkpoint::{CodingAgentCheckpoint, CreateCodingAgentCheckpoint},
         coding_agent_turn::{CodingAgentTurn, CreateCodingAgentTurn},
         execution_process::{
             CreateExecutionProcess, ExecutionContext, ExecutionProcess, ExecutionProcessRunReason,
@@ -20,16 +21,18 @@ use db::{
         },
         project::{Project, UpdateProject},
         project_repo::{ProjectRepo, ProjectRepoWithName},
+        queued_attempt::{CreateQueuedAttempt, QueuedAttempt},
         repo::Repo,
         session::{CreateSession, Session, SessionError},
         task::{Task, TaskStatus},
-        workspace::{Workspace, WorkspaceError},
-        workspace_repo::WorkspaceRepo,
+        workspace::{CreateWorkspace, Workspace, WorkspaceError},
+        workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
     },
 };
 use executors::{
     actions::{
         ExecutorAction, ExecutorActionType,
+        coding_agent_follow_up::CodingAgentFollowUpRequest,
         coding_agent_initial::CodingAgentInitialRequest,
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
     },
@@ -40,7 +43,10 @@ use executors::{
 use futures::{StreamExt, future};
 use sqlx::Error as SqlxError;
 use thiserror::Error;
-use tokio::{sync::RwLock, task::JoinHandle};
+use tokio::{
+    sync::{Mutex, RwLock, mpsc::UnboundedSender},
+    task::JoinHandle,
+};
 use utils::{
     log_msg::LogMsg,
     msg_store::MsgStore,
@@ -49,14 +55,30 @@ use utils::{
 use uuid::Uuid;
 
 use crate::services::{
+    checkpoint,
+    config::ExecutionConcurrencyConfig,
+    execution_queue::{AttemptStart, ExecutionSlots},
     git::{GitService, GitServiceError},
     notification::NotificationService,
     share::SharePublisher,
+    usage::{self, UsageContext},
     workspace_manager::WorkspaceError as WorkspaceManagerError,
     worktree_manager::WorktreeError,
 };
 pub type ContainerRef = String;
 
+/// What became of a finished agent follow-up's work in one repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FollowUpPush {
+    /// The agent failed or was stopped, so nothing was pushed
+    Unfinished,
+    /// The agent made no commits
+    NoChanges,
+    Pushed {
+        commit_sha: String,
+    },
+}
+
 #[derive(Debug, Error)]
 pub enum ContainerError {
     #[error(transparent)]
@@ -77,6 +99,8 @@ pub enum ContainerError {
     Io(#[from] std::io::Error),
     #[error("Failed to kill process: {0}")]
     KillFailed(std::io::Error),
+    #[error("Budget exceeded: {0}")]
+    BudgetExceeded(String),
     #[error(transparent)]
     Other(#[from] AnyhowError), // Catches any unclassified errors
 }
@@ -93,6 +117,12 @@ pub trait ContainerService {
 
     fn notification_service(&self) -> &NotificationService;
 
+    /// Receives execution processes to stop because their project went over a
+    /// budget with `stop_running` set. `None` disables stopping running work.
+    fn budget_stop_sender(&self) -> Option<UnboundedSender<Uuid>> {
+        None
+    }
+
     fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf;
 
     async fn create(&self, workspace: &Workspace) -> Result<ContainerRef, ContainerError>;
@@ -540,6 +570,15 @@ pub trait ContainerService {
     }
 
     async fn try_stop(&self, workspace: &Workspace, include_dev_server: bool) {
+        // A queued attempt never starts once stopped
+        if let Err(e) = QueuedAttempt::remove(&self.db().pool, workspace.id).await {
+            tracing::warn!(
+                "Failed to remove workspace {} from the execution queue: {}",
+                workspace.id,
+                e
+            );
+        }
+
         // stop execution processes for this workspace's sessions
         let sessions = match Session::find_by_workspace_id(&self.db().pool, workspace.id).await {
             Ok(s) => s,
@@ -618,6 +657,11 @@ pub trait ContainerService {
 
     async fn git_branch_prefix(&self) -> String;
 
+    async fn execution_concurrency(&self) -> ExecutionConcurrencyConfig;
+
+    /// Serializes execution queue admission so caps are not overshot
+    fn execution_queue_lock(&self) -> &Mutex<()>;
+
     async fn git_branch_from_workspace(&self, workspace_id: &Uuid, task_title: &str) -> String {
         let task_title_id = git_branch_id(task_title);
         let prefix = self.git_branch_prefix().await;
@@ -629,6 +673,41 @@ pub trait ContainerService {
         }
     }
 
+    /// Create a new attempt workspace for `task` on `repos`, using the
+    /// project's default agent working directory. The workspace is not started.
+    async fn create_task_workspace(
+        &self,
+        task: &Task,
+        repos: &[CreateWorkspaceRepo],
+    ) -> Result<Workspace, ContainerError> {
+        let pool = &self.db().pool;
+        let project = task
+            .parent_project(pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+
+        let attempt_id = Uuid::new_v4();
+        let branch = self
+            .git_branch_from_workspace(&attempt_id, &task.title)
+            .await;
+        let agent_working_dir = project
+            .default_agent_working_dir
+            .filter(|dir| !dir.is_empty());
+
+        let workspace = Workspace::create(
+            pool,
+            &CreateWorkspace {
+                branch,
+                agent_working_dir,
+            },
+            attempt_id,
+            task.id,
+        )
+        .await?;
+        WorkspaceRepo::create_many(pool, workspace.id, repos).await?;
+        Ok(workspace)
+    }
+
     async fn stream_raw_logs(
         &self,
         id: &Uuid,
@@ -817,8 +896,13 @@ pub trait ContainerService {
         let execution_id = *execution_id;
         let msg_stores = self.msg_stores().clone();
         let db = self.db().clone();
+        let budget_stop = self.budget_stop_sender();
 
         tokio::spawn(async move {
+            // Attribution for token usage, loaded on the first usage event
+            let mut usage_ctx: Option<UsageContext> = None;
+            let mut stop_requested = false;
+
             // Get the message store for this execution
             let store = {
                 let map = msg_stores.read().await;
@@ -877,6 +961,51 @@ pub trait ContainerService {
                                 );
                             }
                         }
+                        LogMsg::TokenUsage(token_usage) => {
+                            if usage_ctx.is_none() {
+                                match UsageContext::load(&db.pool, execution_id).await {
+                                    Ok(ctx) => usage_ctx = Some(ctx),
+                                    Err(e) => {
+                                        tracing::error!(
+                                            "Failed to load usage context for execution {}: {}",
+                                            execution_id,
+                                            e
+                                        );
+                                        continue;
+                                    }
+                                }
+                            }
+                            let Some(ctx) = usage_ctx.as_ref() else {
+                                continue;
+                            };
+
+                            match usage::record_usage(&db.pool, ctx, token_usage).await {
+                                Ok(Some(status))
+                                    if status.exceeded
+                                        && status.budget.stop_running
+                                        && !stop_requested =>
+                                {
+                                    tracing::warn!(
+                                        "Project {} is over its budget ({} of {}), stopping execution {}",
+                                        ctx.project_id,
+                                        usage::format_usd(status.spent_microdollars),
+                                        usage::format_usd(status.budget.limit_microdollars),
+                                        execution_id
+                                    );
+                                    if let Some(sender) = &budget_stop {
+                                        stop_requested = sender.send(execution_id).is_ok();
+                                    }
+                                }
+                                Ok(_) => {}
+                                Err(e) => {
+                                    tracing::error!(
+                                        "Failed to record token usage for execution {}: {}",
+                                        execution_id,
+                                        e
+                                    );
+                                }
+                            }
+                        }
                         LogMsg::Finished => {
                             break;
                         }
@@ -887,10 +1016,129 @@ pub trait ContainerService {
         })
     }
 
+    /// Start an attempt, or queue it when a concurrency cap is reached or
+    /// other attempts are already waiting
     async fn start_workspace(
         &self,
         workspace: &Workspace,
         executor_profile_id: ExecutorProfileId,
+    ) -> Result<AttemptStart, ContainerError> {
+        let pool = &self.db().pool;
+        let task = workspace
+            .parent_task(pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        let executor = executor_profile_id.executor.to_string();
+
+        {
+            let _queue_guard = self.execution_queue_lock().lock().await;
+            let slots = ExecutionSlots::new(
+                self.execution_concurrency().await,
+                &QueuedAttempt::find_running_attempts(pool).await?,
+            );
+            if slots.is_unlimited()
+                || (QueuedAttempt::find_all(pool).await?.is_empty()
+                    && slots.has_room(task.project_id, &executor))
+            {
+                return self
+                    .launch_workspace(workspace, executor_profile_id)
+                    .await
+                    .map(AttemptStart::Started);
+            }
+            QueuedAttempt::enqueue(
+                pool,
+                &CreateQueuedAttempt {
+                    workspace_id: workspace.id,
+                    task_id: task.id,
+                    project_id: task.project_id,
+                    executor_profile_id,
+                    priority: task.priority.unwrap_or(0),
+                },
+            )
+            .await?;
+        }
+
+        // A higher priority attempt may still fit ahead of those waiting
+        let mut started = self.start_queued_attempts().await?;
+        if let Some(index) = started
+            .iter()
+            .position(|(workspace_id, _)| *workspace_id == workspace.id)
+        {
+            return Ok(AttemptStart::Started(started.swap_remove(index).1));
+        }
+        match QueuedAttempt::find_by_workspace_id(pool, workspace.id).await? {
+            Some(QueuedAttempt {
+                launch_error: Some(error),
+                ..
+            }) => Err(ContainerError::Other(anyhow!(
+                "Queued attempt {} failed to start: {}",
+                workspace.id,
+                error
+            ))),
+            Some(queued) => {
+                tracing::info!(
+                    "Queued attempt {} at position {}",
+                    workspace.id,
+                    queued.position
+                );
+                Ok(AttemptStart::Queued {
+                    position: queued.position,
+                })
+            }
+            None => Err(ContainerError::Other(anyhow!(
+                "Queued attempt {} failed to start",
+                workspace.id
+            ))),
+        }
+    }
+
+    /// Start queued attempts, in queue order, for which a slot is free.
+    /// Entries leave the queue once started; those that fail keep their place
+    /// with the error. Returns the started workspaces and their first process.
+    async fn start_queued_attempts(&self) -> Result<Vec<(Uuid, ExecutionProcess)>, ContainerError> {
+        let pool = &self.db().pool;
+        let _queue_guard = self.execution_queue_lock().lock().await;
+        let queue = QueuedAttempt::find_all(pool).await?;
+        if queue.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut slots = ExecutionSlots::new(
+            self.execution_concurrency().await,
+            &QueuedAttempt::find_running_attempts(pool).await?,
+        );
+
+        let mut started = Vec::new();
+        for queued in queue {
+            let executor_profile_id = queued.executor_profile_id.0;
+            let executor = executor_profile_id.executor.to_string();
+            if queued.launch_error.is_some() || !slots.has_room(queued.project_id, &executor) {
+                continue;
+            }
+            let Some(workspace) = Workspace::find_by_id(pool, queued.workspace_id).await? else {
+                QueuedAttempt::remove(pool, queued.workspace_id).await?;
+                continue;
+            };
+            match self.launch_workspace(&workspace, executor_profile_id).await {
+                Ok(execution_process) => {
+                    QueuedAttempt::remove(pool, workspace.id).await?;
+                    slots.take(queued.project_id, &executor);
+                    started.push((workspace.id, execution_process));
+                }
+                Err(e) => {
+                    tracing::error!("Failed to start queued attempt {}: {}", workspace.id, e);
+                    QueuedAttempt::mark_launch_failed(pool, workspace.id, &e.to_string()).await?;
+                }
+            }
+        }
+        Ok(started)
+    }
+
+    /// Create the attempt's worktrees and session, then run its setup scripts
+    /// and coding agent, bypassing the execution queue
+    async fn launch_workspace(
+        &self,
+        workspace: &Workspace,
+        executor_profile_id: ExecutorProfileId,
     ) -> Result<ExecutionProcess, ContainerError> {
         // Create container
         self.create(workspace).await?;
@@ -1001,6 +1249,15 @@ pub trait ContainerService {
             .parent_task(&self.db().pool)
             .await?
             .ok_or(SqlxError::RowNotFound)?;
+        if run_reason == &ExecutionProcessRunReason::CodingAgent
+            && let Some(status) = usage::exceeded_budget(&self.db().pool, task.project_id).await?
+        {
+            return Err(ContainerError::BudgetExceeded(format!(
+                "project has spent {} of its {} budget",
+                usage::format_usd(status.spent_microdollars),
+                usage::format_usd(status.budget.limit_microdollars)
+            )));
+        }
         if task.status != TaskStatus::InProgress
             && run_reason != &ExecutionProcessRunReason::DevServer
         {
@@ -1032,9 +1289,47 @@ pub trait ContainerService {
             .map(std::path::PathBuf::from)
             .ok_or_else(|| ContainerError::Other(anyhow!("Container ref not found")))?;
 
+        // Follow-ups get a checkpoint per repository so the turn can be rolled back
+        let execution_process_id = Uuid::new_v4();
+        let take_checkpoint = matches!(
+            executor_action.typ(),
+            ExecutorActionType::CodingAgentFollowUpRequest(_)
+        );
+        let mut checkpoints = Vec::new();
+
         let mut repo_states = Vec::with_capacity(repositories.len());
         for repo in &repositories {
             let repo_path = workspace_root.join(&repo.name);
+            if take_checkpoint {
+                let ref_name = checkpoint::checkpoint_ref(execution_process_id);
+                // A follow-up without a checkpoint could not be rolled back,
+                // so it does not start
+                let checkpoint = self
+                    .git()
+                    .create_checkpoint(&repo_path, &ref_name, checkpoint::CHECKPOINT_COMMIT_MESSAGE)
+                    .map_err(|err| {
+                        ContainerError::Other(anyhow!(
+                            "Failed to checkpoint repo {} before follow-up: {err}",
+                            repo.name
+                        ))
+                    })?;
+                // A repo stopped on conflicts, e.g. while the agent resolves
+                // them, gets none and the turn is not rolled back there
+                match checkpoint {
+                    Some((commit_sha, committed_changes)) => {
+                        checkpoints.push(CreateCodingAgentCheckpoint {
+                            repo_id: repo.id,
+                            commit_sha,
+                            ref_name,
+                            committed_changes,
+                        });
+                    }
+                    None => tracing::info!(
+                        "Not checkpointing repo {} before follow-up: it is stopped on conflicts",
+                        repo.name
+                    ),
+                }
+            }
             let before_head_commit = self.git().get_head_info(&repo_path).ok().map(|h| h.oid);
             repo_states.push(CreateExecutionProcessRepoState {
                 repo_id: repo.id,
@@ -1052,10 +1347,14 @@ pub trait ContainerService {
         let execution_process = ExecutionProcess::create(
             &self.db().pool,
             &create_execution_process,
-            Uuid::new_v4(),
+            execution_process_id,
             &repo_states,
         )
         .await?;
+        if !checkpoints.is_empty() {
+            CodingAgentCheckpoint::create_many(&self.db().pool, execution_process.id, &checkpoints)
+                .await?;
+        }
 
         if let Some(prompt) = match executor_action.typ() {
             ExecutorActionType::CodingAgentInitialRequest(coding_agent_request) => {
@@ -1167,6 +1466,112 @@ pub trait ContainerService {
         Ok(execution_process)
     }
 
+    /// Continue the workspace's latest session with `prompt`, using the
+    /// executor of its last coding agent turn. The repositories' cleanup
+    /// scripts run afterwards, as for a user follow-up.
+    async fn start_agent_follow_up(
+        &self,
+        workspace: &Workspace,
+        prompt: String,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        let pool = &self.db().pool;
+        let session = match Session::find_latest_by_workspace_id(pool, workspace.id).await? {
+            Some(session) => session,
+            None => {
+                Session::create(
+                    pool,
+                    &CreateSession { executor: None },
+                    Uuid::new_v4(),
+                    workspace.id,
+                )
+                .await?
+            }
+        };
+        let executor_profile_id =
+            ExecutionProcess::latest_executor_profile_for_session(pool, session.id)
+                .await
+                .map_err(|e| {
+                    ContainerError::Other(anyhow!("Failed to get executor profile: {e}"))
+                })?;
+        let latest_agent_session_id =
+            ExecutionProcess::find_latest_coding_agent_turn_session_id(pool, session.id).await?;
+
+        let task = workspace
+            .parent_task(pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        let project_repos =
+            ProjectRepo::find_by_project_id_with_names(pool, task.project_id).await?;
+        let cleanup_action = self.cleanup_actions_for_repos(&project_repos);
+
+        let working_dir = workspace
+            .agent_working_dir
+            .as_ref()
+            .filter(|dir| !dir.is_empty())
+            .cloned();
+
+        let action_type = if let Some(agent_session_id) = latest_agent_session_id {
+            ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+                prompt,
+                session_id: agent_session_id,
+                executor_profile_id,
+                working_dir,
+            })
+        } else {
+            ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                prompt,
+                executor_profile_id,
+                working_dir,
+            })
+        };
+        let action = ExecutorAction::new(action_type, cleanup_action.map(Box::new));
+
+        self.start_execution(
+            workspace,
+            &session,
+            &action,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await
+    }
+
+    /// Push the commits an agent follow-up made in `repo`, once it and its
+    /// cleanup scripts are done
+    async fn push_agent_changes(
+        &self,
+        workspace: &Workspace,
+        repo: &Repo,
+        execution_process_id: Uuid,
+    ) -> Result<FollowUpPush, ContainerError> {
+        let pool = &self.db().pool;
+        let finished = ExecutionProcess::find_by_id(pool, execution_process_id)
+            .await?
+            .is_some_and(|process| process.status == ExecutionProcessStatus::Completed);
+        if !finished {
+            return Ok(FollowUpPush::Unfinished);
+        }
+
+        let container_ref = self.ensure_container_exists(workspace).await?;
+        let worktree_path = PathBuf::from(container_ref).join(&repo.name);
+        let head = self.git().get_head_info(&worktree_path)?;
+
+        let before =
+            ExecutionProcessRepoState::find_by_execution_process_id(pool, execution_process_id)
+                .await?
+                .into_iter()
+                .find(|state| state.repo_id == repo.id)
+                .and_then(|state| state.before_head_commit);
+        if before.as_deref() == Some(head.oid.as_str()) {
+            return Ok(FollowUpPush::NoChanges);
+        }
+
+        self.git()
+            .push_to_github(&worktree_path, &workspace.branch, false)?;
+        Ok(FollowUpPush::Pushed {
+            commit_sha: head.oid,
+        })
+    }
+
     async fn try_start_next_action(&self, ctx: &ExecutionContext) -> Result<(), ContainerError> {
         let action = ctx.execution_process.executor_action()?;
         let next_action = if let Some(next_action) = action.next_action() {
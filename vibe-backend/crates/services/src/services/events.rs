@@ -4,6 +4,8 @@ use db::DBService;
 use tokio::sync::RwLock;
 use utils::msg_store::MsgStore;
 
+#[path = "events/listener.rs"]
+pub mod listener;
 #[path = "events/patches.rs"]
 pub mod patches;
 #[path = "events/streams.rs"]
@@ -11,8 +13,10 @@ mod streams;
 #[path = "events/types.rs"]
 pub mod types;
 
+pub use listener::{CHANGE_FEED_CHANNEL, ChangeNotification, ChangeOp};
 pub use patches::{
-    execution_process_patch, project_patch, scratch_patch, task_patch, workspace_patch,
+    execution_process_patch, merge_queue_patch, project_patch, scratch_patch, task_patch,
+    workspace_patch,
 };
 pub use types::{EventError, EventPatch, EventPatchInner, HookTables, RecordTypes};
 
@@ -35,8 +39,8 @@ impl EventService {
     }
 
     /// Creates the hook function that should be used with DBService::new_with_after_connect
-    /// NOTE: Postgres does not support sqlite-style hooks. This is currently valid but does nothing.
-    /// Realtime updates should be handled via app-level events or Postgres LISTEN/NOTIFY.
+    /// NOTE: Postgres does not support sqlite-style hooks, so this does nothing.
+    /// Realtime updates come from the LISTEN/NOTIFY change feed, see `spawn_change_listener`.
     pub fn create_hook(
         _msg_store: Arc<MsgStore>,
         _entry_count: Arc<RwLock<usize>>,
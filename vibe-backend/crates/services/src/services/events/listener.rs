@@ -0,0 +1,318 @@
+use std::{str::FromStr, time::Duration};
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    execution_process::ExecutionProcess,
+    merge_queue::MergeQueueEntry,
+    project::Project,
+    scratch::{Scratch, ScratchType},
+    task::Task,
+    workspace::Workspace,
+};
+use serde::Deserialize;
+use sqlx::postgres::PgListener;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use super::{
+    EventService,
+    patches::{
+        execution_process_patch, merge_queue_patch, project_patch, scratch_patch, task_patch,
+        workspace_patch,
+    },
+    types::{EventError, HookTables},
+};
+
+/// Channel the `notify_hook_change` trigger publishes on
+pub const CHANGE_FEED_CHANNEL: &str = "ikanban_changes";
+
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_millis(500);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+/// Rows changed shortly before a drop may not have been delivered yet
+const RESYNC_SKEW_SECS: i64 = 5;
+/// Upper bound on rows replayed per table after a reconnect
+const RESYNC_LIMIT: i64 = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ChangeOp {
+    Insert,
+    Update,
+    Delete,
+}
+
+/// Payload published by the `notify_hook_change` trigger
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChangeNotification {
+    pub table: String,
+    pub op: ChangeOp,
+    pub id: Uuid,
+    #[serde(default)]
+    pub project_id: Option<Uuid>,
+    #[serde(default)]
+    pub task_id: Option<Uuid>,
+    #[serde(default)]
+    pub session_id: Option<Uuid>,
+    #[serde(default)]
+    pub scratch_type: Option<String>,
+}
+
+impl EventService {
+    /// Spawn the background task that turns Postgres NOTIFY payloads into
+    /// `LogMsg::JsonPatch` records on the event store.
+    ///
+    /// The listener reconnects with exponential backoff when its connection
+    /// drops, then replays rows updated while it was disconnected.
+    pub fn spawn_change_listener(&self) -> JoinHandle<()> {
+        let service = self.clone();
+        tokio::spawn(async move { service.run_change_listener().await })
+    }
+
+    async fn run_change_listener(self) {
+        let mut delay = INITIAL_RECONNECT_DELAY;
+        // None until the first successful connection; nothing to resync before that
+        let mut last_connected: Option<DateTime<Utc>> = None;
+
+        loop {
+            let mut listener = match self.connect_listener().await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    tracing::warn!(error = %e, "change feed listener failed to connect");
+                    tokio::time::sleep(delay).await;
+                    delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+                    continue;
+                }
+            };
+            delay = INITIAL_RECONNECT_DELAY;
+
+            if let Some(since) = last_connected {
+                tracing::info!(%since, "change feed listener reconnected; resyncing");
+                if let Err(e) = self
+                    .resync_since(since - chrono::Duration::seconds(RESYNC_SKEW_SECS))
+                    .await
+                {
+                    tracing::error!(error = %e, "change feed resync failed");
+                }
+            }
+
+            loop {
+                last_connected = Some(Utc::now());
+                match listener.try_recv().await {
+                    Ok(Some(notification)) => {
+                        match serde_json::from_str::<ChangeNotification>(notification.payload()) {
+                            Ok(change) => {
+                                if let Err(e) = self.apply_change(&change).await {
+                                    tracing::warn!(
+                                        error = %e,
+                                        table = %change.table,
+                                        id = %change.id,
+                                        "failed to apply change notification"
+                                    );
+                                }
+                            }
+                            Err(e) => {
+                                tracing::warn!(error = %e, "malformed change notification");
+                            }
+                        }
+                    }
+                    Ok(None) => {
+                        tracing::warn!("change feed connection closed");
+                        break;
+                    }
+                    Err(e) => {
+                        tracing::warn!(error = %e, "change feed listener error");
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    async fn connect_listener(&self) -> Result<PgListener, sqlx::Error> {
+        let mut listener = PgListener::connect_with(&self.db.pool).await?;
+        listener.listen(CHANGE_FEED_CHANNEL).await?;
+        Ok(listener)
+    }
+
+    /// Replay every row touched since `since` as an update. Deletes that
+    /// happened while disconnected cannot be recovered; clients drop stale
+    /// entries on their next full snapshot.
+    async fn resync_since(&self, since: DateTime<Utc>) -> Result<(), EventError> {
+        for table in [
+            HookTables::Projects,
+            HookTables::Tasks,
+            HookTables::Workspaces,
+            HookTables::ExecutionProcesses,
+            HookTables::Scratch,
+            HookTables::MergeQueueEntries,
+        ] {
+            let query = format!(
+                "SELECT id, {} FROM {table} WHERE updated_at >= $1 ORDER BY updated_at ASC LIMIT $2",
+                match table {
+                    HookTables::Scratch => "scratch_type",
+                    _ => "NULL::text",
+                }
+            );
+            let rows: Vec<(Uuid, Option<String>)> = sqlx::query_as(&query)
+                .bind(since)
+                .bind(RESYNC_LIMIT)
+                .fetch_all(&self.db.pool)
+                .await?;
+
+            for (id, scratch_type) in rows {
+                let change = ChangeNotification {
+                    table: table.to_string(),
+                    op: ChangeOp::Update,
+                    id,
+                    project_id: None,
+                    task_id: None,
+                    session_id: None,
+                    scratch_type,
+                };
+                if let Err(e) = self.apply_change(&change).await {
+                    tracing::warn!(error = %e, %table, %id, "failed to resync row");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Convert a single change notification into JSON patches on the event store
+    pub async fn apply_change(&self, change: &ChangeNotification) -> Result<(), EventError> {
+        let table = HookTables::from_str(&change.table)
+            .map_err(|_| anyhow::anyhow!("unknown hook table: {}", change.table))?;
+        let pool = &self.db.pool;
+
+        match (table, change.op) {
+            (HookTables::Tasks, ChangeOp::Delete) => {
+                self.msg_store.push_patch(task_patch::remove(change.id));
+            }
+            (HookTables::Tasks, op) => {
+                if let Some(task) = Task::find_by_id_with_attempt_status(pool, change.id).await? {
+                    self.msg_store.push_patch(match op {
+                        ChangeOp::Insert => task_patch::add(&task),
+                        _ => task_patch::replace(&task),
+                    });
+                }
+            }
+            (HookTables::Workspaces, ChangeOp::Delete) => {
+                self.msg_store
+                    .push_patch(workspace_patch::remove(change.id));
+                if let Some(task_id) = change.task_id {
+                    self.refresh_task(task_id).await?;
+                }
+            }
+            (HookTables::Workspaces, op) => {
+                if let Some(workspace) = Workspace::find_by_id(pool, change.id).await? {
+                    self.msg_store.push_patch(match op {
+                        ChangeOp::Insert => workspace_patch::add(&workspace),
+                        _ => workspace_patch::replace(&workspace),
+                    });
+                    self.refresh_task(workspace.task_id).await?;
+                }
+            }
+            (HookTables::ExecutionProcesses, ChangeOp::Delete) => {
+                self.msg_store
+                    .push_patch(execution_process_patch::remove(change.id));
+            }
+            (HookTables::ExecutionProcesses, op) => {
+                if let Some(process) = ExecutionProcess::find_by_id(pool, change.id).await? {
+                    self.msg_store.push_patch(match op {
+                        ChangeOp::Insert => execution_process_patch::add(&process),
+                        _ => execution_process_patch::replace(&process),
+                    });
+                    // Attempt status on the task card is derived from its processes
+                    if let Some((workspace, _)) = process.parent_workspace_and_session(pool).await?
+                    {
+                        self.refresh_task(workspace.task_id).await?;
+                    }
+                }
+            }
+            (HookTables::Scratch, op) => {
+                let Some(scratch_type) = change
+                    .scratch_type
+                    .as_deref()
+                    .and_then(|s| ScratchType::from_str(s).ok())
+                else {
+                    return Ok(());
+                };
+                if op == ChangeOp::Delete {
+                    self.msg_store
+                        .push_patch(scratch_patch::remove(change.id, &scratch_type.to_string()));
+                } else if let Some(scratch) = Scratch::find_by_id(pool, change.id, &scratch_type)
+                    .await
+                    .map_err(anyhow::Error::from)?
+                {
+                    self.msg_store.push_patch(match op {
+                        ChangeOp::Insert => scratch_patch::add(&scratch),
+                        _ => scratch_patch::replace(&scratch),
+                    });
+                }
+            }
+            (HookTables::Projects, ChangeOp::Delete) => {
+                self.msg_store.push_patch(project_patch::remove(change.id));
+            }
+            (HookTables::Projects, op) => {
+                if let Some(project) = Project::find_by_id(pool, change.id).await? {
+                    self.msg_store.push_patch(match op {
+                        ChangeOp::Insert => project_patch::add(&project),
+                        _ => project_patch::replace(&project),
+                    });
+                }
+            }
+            // Task cards show whether an attempt is queued
+            (HookTables::QueuedAttempts, _) => {
+                if let Some(task_id) = change.task_id {
+                    self.refresh_task(task_id).await?;
+                }
+            }
+            (HookTables::MergeQueueEntries, ChangeOp::Delete) => {
+                self.msg_store
+                    .push_patch(merge_queue_patch::remove(change.id));
+            }
+            (HookTables::MergeQueueEntries, op) => {
+                if let Some(entry) = MergeQueueEntry::find_by_id(pool, change.id).await? {
+                    self.msg_store.push_patch(match op {
+                        ChangeOp::Insert => merge_queue_patch::add(&entry),
+                        _ => merge_queue_patch::replace(&entry),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn refresh_task(&self, task_id: Uuid) -> Result<(), EventError> {
+        if let Some(task) = Task::find_by_id_with_attempt_status(&self.db.pool, task_id).await? {
+            self.msg_store.push_patch(task_patch::replace(&task));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_trigger_payload() {
+        let payload = r#"{"table":"workspaces","op":"DELETE","id":"5a3c1f8e-0a4b-4f57-9a53-4c3c8c2d1e10","task_id":"0f7c3b1a-3e5d-4a59-8d3b-2b1f6c7d8e9f"}"#;
+        let change: ChangeNotification = serde_json::from_str(payload).unwrap();
+
+        assert_eq!(change.op, ChangeOp::Delete);
+        assert!(matches!(
+            HookTables::from_str(&change.table),
+            Ok(HookTables::Workspaces)
+        ));
+        assert!(change.task_id.is_some());
+        assert!(change.project_id.is_none());
+    }
+
+    #[test]
+    fn rejects_unknown_op() {
+        let payload =
+            r#"{"table":"tasks","op":"TRUNCATE","id":"5a3c1f8e-0a4b-4f57-9a53-4c3c8c2d1e10"}"#;
+        assert!(serde_json::from_str::<ChangeNotification>(payload).is_err());
+    }
+}
@@ -1,11 +1,18 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+
 use db::models::{
     execution_process::ExecutionProcess,
+    merge_queue::MergeQueueEntry,
     project::Project,
     scratch::Scratch,
     session::Session,
     task::{Task, TaskWithAttemptStatus},
 };
 use futures::StreamExt;
+use json_patch::PatchOperation;
 use serde_json::json;
 use tokio_stream::wrappers::{BroadcastStream, errors::BroadcastStreamRecvError};
 use utils::log_msg::LogMsg;
@@ -226,6 +233,89 @@ impl EventService {
         Ok(combined_stream)
     }
 
+    /// Stream merge queue entries of a project with initial snapshot
+    pub async fn stream_merge_queue_raw(
+        &self,
+        project_id: Uuid,
+    ) -> Result<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>, EventError>
+    {
+        fn build_merge_queue_snapshot(entries: Vec<MergeQueueEntry>) -> LogMsg {
+            // Convert entries array to object keyed by entry ID
+            let entries_map: serde_json::Map<String, serde_json::Value> = entries
+                .into_iter()
+                .map(|entry| (entry.id.to_string(), serde_json::to_value(entry).unwrap()))
+                .collect();
+
+            let patch = json!([
+                {
+                    "op": "replace",
+                    "path": "/merge_queue",
+                    "value": entries_map
+                }
+            ]);
+
+            LogMsg::JsonPatch(serde_json::from_value(patch).unwrap())
+        }
+
+        let entries = MergeQueueEntry::find_by_project_id(&self.db.pool, project_id).await?;
+        // Removals only carry the entry id, so remember which entries were sent
+        let known_ids = Arc::new(Mutex::new(
+            entries.iter().map(|entry| entry.id).collect::<HashSet<_>>(),
+        ));
+        let initial_msg = build_merge_queue_snapshot(entries);
+
+        let db_pool = self.db.pool.clone();
+
+        let filtered_stream =
+            BroadcastStream::new(self.msg_store.get_receiver()).filter_map(move |msg_result| {
+                let db_pool = db_pool.clone();
+                let known_ids = known_ids.clone();
+                async move {
+                    match msg_result {
+                        Ok(LogMsg::JsonPatch(patch)) => {
+                            let patch_op = patch.0.first()?;
+                            let in_project = merge_queue_patch_in_project(
+                                patch_op,
+                                project_id,
+                                &mut known_ids.lock().unwrap_or_else(|e| e.into_inner()),
+                            );
+                            in_project.then_some(Ok(LogMsg::JsonPatch(patch)))
+                        }
+                        Ok(other) => Some(Ok(other)), // Pass through non-patch messages
+                        Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                            tracing::warn!(
+                                skipped = skipped,
+                                "merge queue stream lagged; resyncing snapshot"
+                            );
+
+                            match MergeQueueEntry::find_by_project_id(&db_pool, project_id).await {
+                                Ok(entries) => {
+                                    *known_ids.lock().unwrap_or_else(|e| e.into_inner()) =
+                                        entries.iter().map(|entry| entry.id).collect();
+                                    Some(Ok(build_merge_queue_snapshot(entries)))
+                                }
+                                Err(err) => {
+                                    tracing::error!(
+                                        error = %err,
+                                        "failed to resync merge queue after lag"
+                                    );
+                                    Some(Err(std::io::Error::other(format!(
+                                        "failed to resync merge queue after lag: {err}"
+                                    ))))
+                                }
+                            }
+                        }
+                    }
+                }
+            });
+
+        // Start with initial snapshot, then live updates
+        let initial_stream = futures::stream::once(async move { Ok(initial_msg) });
+        let combined_stream = initial_stream.chain(filtered_stream).boxed();
+
+        Ok(combined_stream)
+    }
+
     /// Stream execution processes for a specific workspace with initial snapshot (raw LogMsg format for WebSocket)
     pub async fn stream_execution_processes_for_workspace_raw(
         &self,
@@ -446,3 +536,87 @@ impl EventService {
         Ok(combined_stream)
     }
 }
+
+/// Whether a patch is about a merge queue entry of `project_id`. Removals only
+/// carry the entry id, so they match when `known_ids` (the project's entries
+/// sent so far) holds it.
+fn merge_queue_patch_in_project(
+    patch_op: &PatchOperation,
+    project_id: Uuid,
+    known_ids: &mut HashSet<Uuid>,
+) -> bool {
+    let Some(entry_id) = patch_op
+        .path()
+        .strip_prefix("/merge_queue/")
+        .and_then(|id| Uuid::parse_str(id).ok())
+    else {
+        return false;
+    };
+    let value = match patch_op {
+        PatchOperation::Add(op) => &op.value,
+        PatchOperation::Replace(op) => &op.value,
+        PatchOperation::Remove(_) => return known_ids.remove(&entry_id),
+        _ => return false,
+    };
+    let in_project = serde_json::from_value::<MergeQueueEntry>(value.clone())
+        .is_ok_and(|entry| entry.project_id == project_id);
+    if in_project {
+        known_ids.insert(entry_id);
+    }
+    in_project
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use db::models::merge_queue::{MergeQueueStatus, MergeStrategy};
+
+    use super::*;
+    use crate::services::events::patches::{merge_queue_patch, project_patch};
+
+    fn entry(project_id: Uuid) -> MergeQueueEntry {
+        MergeQueueEntry {
+            id: Uuid::new_v4(),
+            workspace_id: Uuid::new_v4(),
+            repo_id: Uuid::new_v4(),
+            task_id: Uuid::new_v4(),
+            project_id,
+            target_branch: "main".to_string(),
+            strategy: MergeStrategy::Squash,
+            status: MergeQueueStatus::Queued,
+            merge_commit_sha: None,
+            eject_reason: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            completed_at: None,
+        }
+    }
+
+    #[test]
+    fn merge_queue_removals_reach_only_the_entrys_project() {
+        let project_id = Uuid::new_v4();
+        let ours = entry(project_id);
+        let theirs = entry(Uuid::new_v4());
+        let mut known_ids = HashSet::new();
+        let mut in_project = |patch: json_patch::Patch| {
+            merge_queue_patch_in_project(&patch.0[0], project_id, &mut known_ids)
+        };
+
+        assert!(in_project(merge_queue_patch::add(&ours)));
+        assert!(!in_project(merge_queue_patch::add(&theirs)));
+        assert!(!in_project(merge_queue_patch::remove(theirs.id)));
+        assert!(in_project(merge_queue_patch::remove(ours.id)));
+        // Each removal is sent once
+        assert!(!in_project(merge_queue_patch::remove(ours.id)));
+    }
+
+    #[test]
+    fn other_patches_are_not_merge_queue_patches() {
+        let patch = project_patch::remove(Uuid::new_v4());
+        assert!(!merge_queue_patch_in_project(
+            &patch.0[0],
+            Uuid::new_v4(),
+            &mut HashSet::new()
+        ));
+    }
+}
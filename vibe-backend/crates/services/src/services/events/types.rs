@@ -32,6 +32,10 @@ pub enum HookTables {
     Scratch,
     #[strum(to_string = "projects")]
     Projects,
+    #[strum(to_string = "queued_attempts")]
+    QueuedAttempts,
+    #[strum(to_string = "merge_queue_entries")]
+    MergeQueueEntries,
 }
 
 #[derive(Serialize, Deserialize, TS)]
@@ -0,0 +1,130 @@
+//! Concurrency caps on running attempts.
+//!
+//! An attempt holds an execution slot while its setup script or coding agent
+//! runs. Attempts started while a cap is reached wait in the persisted queue
+//! (`QueuedAttempt`) and are started in queue order as slots free up; an
+//! entry blocked by its project or executor cap does not hold back entries
+//! behind it. Follow-ups on an attempt that already ran are never queued.
+
+use std::collections::HashMap;
+
+use db::models::{execution_process::ExecutionProcess, queued_attempt::RunningAttempt};
+use uuid::Uuid;
+
+use crate::services::config::ExecutionConcurrencyConfig;
+
+/// Outcome of starting an attempt
+#[derive(Debug, Clone)]
+pub enum AttemptStart {
+    Started(ExecutionProcess),
+    /// Waiting for a slot at this zero-based queue position
+    Queued {
+        position: i32,
+    },
+}
+
+/// Free execution slots under the configured caps
+#[derive(Debug, Clone)]
+pub struct ExecutionSlots {
+    limits: ExecutionConcurrencyConfig,
+    running: u32,
+    running_per_project: HashMap<Uuid, u32>,
+    running_per_executor: HashMap<String, u32>,
+}
+
+impl ExecutionSlots {
+    pub fn new(limits: ExecutionConcurrencyConfig, running: &[RunningAttempt]) -> Self {
+        let mut slots = Self {
+            limits,
+            running: 0,
+            running_per_project: HashMap::new(),
+            running_per_executor: HashMap::new(),
+        };
+        for attempt in running {
+            slots.take(
+                attempt.project_id,
+                attempt.executor.as_deref().unwrap_or_default(),
+            );
+        }
+        slots
+    }
+
+    /// No caps are configured, so nothing ever waits
+    pub fn is_unlimited(&self) -> bool {
+        self.limits.max_running.is_none()
+            && self.limits.max_running_per_project.is_none()
+            && self.limits.max_running_per_executor.is_empty()
+    }
+
+    pub fn has_room(&self, project_id: Uuid, executor: &str) -> bool {
+        let under = |limit: Option<u32>, count: u32| limit.is_none_or(|limit| count < limit);
+        under(self.limits.max_running, self.running)
+            && under(
+                self.limits.max_running_per_project,
+                self.running_per_project
+                    .get(&project_id)
+                    .copied()
+                    .unwrap_or(0),
+            )
+            && under(
+                self.limits.max_running_per_executor.get(executor).copied(),
+                self.running_per_executor
+                    .get(executor)
+                    .copied()
+                    .unwrap_or(0),
+            )
+    }
+
+    pub fn take(&mut self, project_id: Uuid, executor: &str) {
+        self.running += 1;
+        *self.running_per_project.entry(project_id).or_default() += 1;
+        *self
+            .running_per_executor
+            .entry(executor.to_string())
+            .or_default() += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn running(project_id: Uuid, executor: &str) -> RunningAttempt {
+        RunningAttempt {
+            workspace_id: Uuid::new_v4(),
+            project_id,
+            executor: Some(executor.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_global_cap() {
+        let project = Uuid::new_v4();
+        let limits = ExecutionConcurrencyConfig {
+            max_running: Some(2),
+            max_running_per_project: None,
+            max_running_per_executor: HashMap::new(),
+        };
+        let mut slots = ExecutionSlots::new(limits, &[running(project, "CODEX")]);
+
+        assert!(slots.has_room(Uuid::new_v4(), "CLAUDE_CODE"));
+        slots.take(project, "CODEX");
+        assert!(!slots.has_room(Uuid::new_v4(), "CLAUDE_CODE"));
+    }
+
+    #[test]
+    fn test_project_and_executor_caps_are_independent() {
+        let (busy_project, other_project) = (Uuid::new_v4(), Uuid::new_v4());
+        let limits = ExecutionConcurrencyConfig {
+            max_running: None,
+            max_running_per_project: Some(1),
+            max_running_per_executor: HashMap::from([("CODEX".to_string(), 1)]),
+        };
+        let slots = ExecutionSlots::new(limits, &[running(busy_project, "CODEX")]);
+
+        assert!(!slots.is_unlimited());
+        assert!(!slots.has_room(busy_project, "CLAUDE_CODE"));
+        assert!(!slots.has_room(other_project, "CODEX"));
+        assert!(slots.has_room(other_project, "CLAUDE_CODE"));
+    }
+}
@@ -138,6 +138,12 @@ pub enum DiffTarget<'p> {
         repo_path: &'p Path,
         commit_sha: &'p str,
     },
+    /// Changes between two commits
+    Range {
+        repo_path: &'p Path,
+        from_commit: &'p str,
+        to_commit: &'p str,
+    },
 }
 
 impl Default for GitService {
@@ -406,6 +412,37 @@ impl GitService {
                 let mut find_opts = git2::DiffFindOptions::new();
                 diff.find_similar(Some(&mut find_opts))?;
 
+                self.convert_diff_to_file_diffs(diff, &repo)
+            }
+            DiffTarget::Range {
+                repo_path,
+                from_commit,
+                to_commit,
+            } => {
+                let repo = self.open_repo(repo_path)?;
+                let parse_oid = |sha: &str| {
+                    git2::Oid::from_str(sha).map_err(|_| {
+                        GitServiceError::InvalidRepository(format!("Invalid commit SHA: {sha}"))
+                    })
+                };
+                let from_tree = repo.find_commit(parse_oid(from_commit)?)?.tree()?;
+                let to_tree = repo.find_commit(parse_oid(to_commit)?)?.tree()?;
+
+                let mut diff_opts = DiffOptions::new();
+                diff_opts.include_typechange(true);
+
+                if let Some(paths) = path_filter {
+                    for path in paths {
+                        diff_opts.pathspec(*path);
+                    }
+                }
+
+                let mut diff =
+                    repo.diff_tree_to_tree(Some(&from_tree), Some(&to_tree), Some(&mut diff_opts))?;
+
+                let mut find_opts = DiffFindOptions::new();
+                diff.find_similar(Some(&mut find_opts))?;
+
                 self.convert_diff_to_file_diffs(diff, &repo)
             }
         }
@@ -881,6 +918,67 @@ impl GitService {
             }
         }
     }
+
+    /// Move the base branch to the tip of the task branch, keeping the task
+    /// branch's commits as they are. The task branch must already contain
+    /// the base branch, e.g. after `rebase_branch`.
+    pub fn fast_forward_changes(
+        &self,
+        base_worktree_path: &Path,
+        task_branch_name: &str,
+        base_branch_name: &str,
+    ) -> Result<String, GitServiceError> {
+        let (_, task_behind) =
+            self.get_branch_status(base_worktree_path, task_branch_name, base_branch_name)?;
+
+        if task_behind > 0 {
+            return Err(GitServiceError::BranchesDiverged(format!(
+                "Cannot fast-forward: base branch '{base_branch_name}' is {task_behind} commits ahead of task branch '{task_branch_name}'.",
+            )));
+        }
+
+        match self.find_checkout_path_for_branch(base_worktree_path, base_branch_name)? {
+            Some(base_checkout_path) => {
+                // base branch is checked out somewhere - let git move the working tree too
+                let git_cli = GitCli::new();
+
+                if git_cli
+                    .has_staged_changes(&base_checkout_path)
+                    .map_err(|e| {
+                        GitServiceError::InvalidRepository(format!("git diff --cached failed: {e}"))
+                    })?
+                {
+                    return Err(GitServiceError::WorktreeDirty(
+                        base_branch_name.to_string(),
+                        "staged changes present".to_string(),
+                    ));
+                }
+
+                git_cli
+                    .merge_ff_only(&base_checkout_path, base_branch_name, task_branch_name)
+                    .map_err(|e| {
+                        GitServiceError::InvalidRepository(format!("CLI fast-forward failed: {e}"))
+                    })
+            }
+            None => {
+                // base branch not checked out anywhere - just move the ref
+                let repo = self.open_repo(base_worktree_path)?;
+                let task_commit = Self::find_branch(&repo, task_branch_name)?
+                    .get()
+                    .peel_to_commit()?;
+                let base_refname = format!("refs/heads/{base_branch_name}");
+                repo.reference(
+                    &base_refname,
+                    task_commit.id(),
+                    true,
+                    "Fast-forward to task branch",
+                )?;
+
+                Ok(task_commit.id().to_string())
+            }
+        }
+    }
+
     fn get_branch_status_inner(
         &self,
         repo: &Repository,
@@ -1161,6 +1259,60 @@ impl GitService {
         Ok(())
     }
 
+    /// Record the worktree's current state under `ref_name` without moving
+    /// HEAD or the branch. Uncommitted changes, untracked files included, are
+    /// snapshotted in a commit on top of HEAD that only the ref points to.
+    /// Returns the checkpoint commit and whether it holds uncommitted changes,
+    /// or `None` while a rebase or merge is stopped in the worktree: restoring
+    /// the checkpoint would reset over it.
+    pub fn create_checkpoint(
+        &self,
+        worktree_path: &Path,
+        ref_name: &str,
+        message: &str,
+    ) -> Result<Option<(String, bool)>, GitServiceError> {
+        if self.detect_conflict_op(worktree_path)?.is_some() {
+            return Ok(None);
+        }
+        let git = GitCli::new();
+        let has_changes = git
+            .has_changes(worktree_path)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git status failed: {e}")))?;
+        let commit = if has_changes {
+            self.ensure_cli_commit_identity(worktree_path)?;
+            git.snapshot_commit(worktree_path, message)?
+        } else {
+            self.get_head_info(worktree_path)?.oid
+        };
+        git.update_ref(worktree_path, ref_name, &commit)?;
+        Ok(Some((commit, has_changes)))
+    }
+
+    /// Reset the worktree to a checkpoint from [`Self::create_checkpoint`]:
+    /// HEAD and the branch go back to the commit the checkpoint was taken on,
+    /// and changes that were uncommitted then are restored as uncommitted.
+    /// Untracked files created since are removed; ignored files are kept.
+    pub fn restore_checkpoint(
+        &self,
+        worktree_path: &Path,
+        commit_sha: &str,
+        has_uncommitted_changes: bool,
+    ) -> Result<(), GitServiceError> {
+        GitCli::new()
+            .git(worktree_path, ["clean", "-fd", "--quiet"])
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git clean failed: {e}")))?;
+        self.reset_worktree_to_commit(worktree_path, commit_sha, true)?;
+        if has_uncommitted_changes {
+            // The snapshot's parent is the HEAD it was taken on
+            GitCli::new()
+                .git(worktree_path, ["reset", "--mixed", "--quiet", "HEAD^"])
+                .map_err(|e| {
+                    GitServiceError::InvalidRepository(format!("git reset --mixed failed: {e}"))
+                })?;
+        }
+        Ok(())
+    }
+
     /// Add a worktree for a branch, optionally creating the branch
     pub fn add_worktree(
         &self,
@@ -1578,6 +1730,52 @@ impl GitService {
         Ok(())
     }
 
+    /// Continue the operation stopped by conflicts once they are resolved and
+    /// staged (no-op if none is in progress).
+    pub fn continue_conflicts(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
+        let Some(op) = self.detect_conflict_op(worktree_path)? else {
+            return Ok(());
+        };
+        // Continuing commits the resolution
+        self.ensure_cli_commit_identity(worktree_path)?;
+        let git = GitCli::new();
+        let (result, command) = match op {
+            ConflictOp::Rebase => (git.continue_rebase(worktree_path), "rebase --continue"),
+            ConflictOp::Merge => (git.continue_merge(worktree_path), "commit"),
+            ConflictOp::CherryPick => (
+                git.continue_cherry_pick(worktree_path),
+                "cherry-pick --continue",
+            ),
+            ConflictOp::Revert => (git.continue_revert(worktree_path), "revert --continue"),
+        };
+        result.map_err(|e| GitServiceError::InvalidRepository(format!("git {command} failed: {e}")))
+    }
+
+    /// Mark conflicted files as resolved.
+    pub fn stage_resolved_files(
+        &self,
+        worktree_path: &Path,
+        paths: &[String],
+    ) -> Result<(), GitServiceError> {
+        let git = GitCli::new();
+        git.stage_paths(worktree_path, paths)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git add failed: {e}")))
+    }
+
+    /// Subjects of up to `limit` commits on `branch` that are not on `base`,
+    /// newest first.
+    pub fn get_commit_subjects(
+        &self,
+        repo_path: &Path,
+        base: &str,
+        branch: &str,
+        limit: usize,
+    ) -> Result<Vec<String>, GitServiceError> {
+        let git = GitCli::new();
+        git.commit_subjects(repo_path, &format!("{base}..{branch}"), limit)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git log failed: {e}")))
+    }
+
     pub fn find_branch<'a>(
         repo: &'a Repository,
         branch_name: &str,
@@ -1595,21 +1793,27 @@ impl GitService {
         }
     }
 
-    /// Extract GitHub owner and repo name from git repo path
-    pub fn get_github_repo_info(
-        &self,
-        repo_path: &Path,
-    ) -> Result<GitHubRepoInfo, GitServiceError> {
+    /// URL of the default remote for the git repo at `repo_path`
+    pub fn get_remote_url(&self, repo_path: &Path) -> Result<String, GitServiceError> {
         let repo = self.open_repo(repo_path)?;
         let remote_name = self.default_remote_name(&repo);
         let remote = repo.find_remote(&remote_name).map_err(|_| {
             GitServiceError::InvalidRepository(format!("No '{remote_name}' remote found"))
         })?;
 
-        let url = remote
+        remote
             .url()
-            .ok_or_else(|| GitServiceError::InvalidRepository("Remote has no URL".to_string()))?;
-        GitHubRepoInfo::from_remote_url(url).map_err(|e| {
+            .map(str::to_string)
+            .ok_or_else(|| GitServiceError::InvalidRepository("Remote has no URL".to_string()))
+    }
+
+    /// Extract GitHub owner and repo name from git repo path
+    pub fn get_github_repo_info(
+        &self,
+        repo_path: &Path,
+    ) -> Result<GitHubRepoInfo, GitServiceError> {
+        let url = self.get_remote_url(repo_path)?;
+        GitHubRepoInfo::from_remote_url(&url).map_err(|e| {
             GitServiceError::InvalidRepository(format!("Failed to parse remote URL: {e}"))
         })
     }
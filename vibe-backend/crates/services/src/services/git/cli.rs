@@ -227,6 +227,43 @@ impl GitCli {
         Ok(Self::parse_name_status(&out))
     }
 
+    /// Commit the working tree, untracked files included, on top of HEAD
+    /// through a temporary index. HEAD, the branch and the real index are left
+    /// untouched, so only a ref the caller sets keeps the commit. Returns the
+    /// new commit's sha.
+    pub fn snapshot_commit(
+        &self,
+        worktree_path: &Path,
+        message: &str,
+    ) -> Result<String, GitCliError> {
+        let tmp_dir = tempfile::TempDir::new()
+            .map_err(|e| GitCliError::CommandFailed(format!("temp dir create failed: {e}")))?;
+        let tmp_index = tmp_dir.path().join("index");
+        let envs = vec![(
+            OsString::from("GIT_INDEX_FILE"),
+            tmp_index.as_os_str().to_os_string(),
+        )];
+
+        self.git_with_env(worktree_path, ["read-tree", "HEAD"], &envs)?;
+        self.git_with_env(
+            worktree_path,
+            Self::apply_default_excludes(vec!["add", "-A"]),
+            &envs,
+        )?;
+        let tree = self
+            .git_with_env(worktree_path, ["write-tree"], &envs)?
+            .trim()
+            .to_string();
+        let sha = self
+            .git(
+                worktree_path,
+                ["commit-tree", tree.as_str(), "-p", "HEAD", "-m", message],
+            )?
+            .trim()
+            .to_string();
+        Ok(sha)
+    }
+
     /// Return `git status --porcelain` parsed into a structured summary
     pub fn get_worktree_status(&self, worktree_path: &Path) -> Result<WorktreeStatus, GitCliError> {
         // Using -z for NUL-separated output which correctly handles paths with special chars.
@@ -594,6 +631,23 @@ impl GitCli {
         Ok(sha)
     }
 
+    /// Checkout base branch and fast-forward it to from_branch. Returns new HEAD sha.
+    pub fn merge_ff_only(
+        &self,
+        repo_path: &Path,
+        base_branch: &str,
+        from_branch: &str,
+    ) -> Result<String, GitCliError> {
+        self.git(repo_path, ["checkout", base_branch]).map(|_| ())?;
+        self.git(repo_path, ["merge", "--ff-only", from_branch])
+            .map(|_| ())?;
+        let sha = self
+            .git(repo_path, ["rev-parse", "HEAD"])?
+            .trim()
+            .to_string();
+        Ok(sha)
+    }
+
     /// Update a ref to a specific sha in the repo.
     pub fn update_ref(
         &self,
@@ -627,6 +681,63 @@ impl GitCli {
         self.git(worktree_path, ["revert", "--abort"]).map(|_| ())
     }
 
+    /// Continue a stopped rebase, keeping the messages of the replayed commits.
+    pub fn continue_rebase(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git(
+            worktree_path,
+            ["-c", "core.editor=true", "rebase", "--continue"],
+        )
+        .map(|_| ())
+    }
+
+    /// Commit a stopped merge with its prepared message.
+    pub fn continue_merge(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git(worktree_path, ["commit", "--no-edit"]).map(|_| ())
+    }
+
+    pub fn continue_cherry_pick(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git(
+            worktree_path,
+            ["-c", "core.editor=true", "cherry-pick", "--continue"],
+        )
+        .map(|_| ())
+    }
+
+    pub fn continue_revert(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git(
+            worktree_path,
+            ["-c", "core.editor=true", "revert", "--continue"],
+        )
+        .map(|_| ())
+    }
+
+    /// Stage the given paths, including deletions, e.g. to mark conflicts resolved.
+    pub fn stage_paths(&self, worktree_path: &Path, paths: &[String]) -> Result<(), GitCliError> {
+        if paths.is_empty() {
+            return Ok(());
+        }
+        let mut args = vec!["add", "-A", "--"];
+        args.extend(paths.iter().map(String::as_str));
+        self.git(worktree_path, args).map(|_| ())
+    }
+
+    /// Subjects of the commits in `range` (e.g. `main..feature`), newest first.
+    pub fn commit_subjects(
+        &self,
+        repo_path: &Path,
+        range: &str,
+        limit: usize,
+    ) -> Result<Vec<String>, GitCliError> {
+        let limit = format!("--max-count={limit}");
+        let out = self.git(repo_path, ["log", "--format=%s", limit.as_str(), range])?;
+        Ok(out
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+
     /// List files currently in a conflicted (unmerged) state in the worktree.
     pub fn get_conflicted_files(&self, worktree_path: &Path) -> Result<Vec<String>, GitCliError> {
         // `--diff-filter=U` lists paths with unresolved conflicts
@@ -12,8 +12,15 @@ use ts_rs::TS;
 
 mod cli;
 
+pub use cli::{CiJob, PrCommentAuthor, ReviewCommentUser};
 use cli::{GhCli, GhCliError, PrComment, PrReviewComment};
-pub use cli::{PrCommentAuthor, ReviewCommentUser};
+
+/// A CI run GitHub reported on a commit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CiRun {
+    CheckSuite(i64),
+    WorkflowRun(i64),
+}
 
 /// Unified PR comment that can be either a general comment or review comment
 #[derive(Debug, Clone, Serialize, TS)]
@@ -44,7 +51,7 @@ pub enum UnifiedPrComment {
 }
 
 impl UnifiedPrComment {
-    fn created_at(&self) -> DateTime<Utc> {
+    pub(crate) fn created_at(&self) -> DateTime<Utc> {
         match self {
             UnifiedPrComment::General { created_at, .. } => *created_at,
             UnifiedPrComment::Review { created_at, .. } => *created_at,
@@ -52,6 +59,26 @@ impl UnifiedPrComment {
     }
 }
 
+/// Inline review comments sharing a thread, oldest first
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct PrReviewThread {
+    /// Id of the comment that started the thread; replies are posted to it
+    pub root_id: i64,
+    pub path: String,
+    pub line: Option<i64>,
+    pub diff_hunk: String,
+    pub is_resolved: bool,
+    pub comments: Vec<UnifiedPrComment>,
+}
+
+/// Everything reviewers said on a pull request
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct PrReviewFeedback {
+    /// Conversation comments, oldest first
+    pub general: Vec<UnifiedPrComment>,
+    pub threads: Vec<PrReviewThread>,
+}
+
 #[derive(Debug, Error)]
 pub enum GitHubServiceError {
     #[error("Repository error: {0}")]
@@ -450,4 +477,240 @@ impl GitHubService {
         })
         .await
     }
+
+    /// Fetch conversation comments and review threads with their resolution
+    /// state
+    pub async fn get_pr_review_feedback(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        pr_number: i64,
+    ) -> Result<PrReviewFeedback, GitHubServiceError> {
+        let (general_result, review_result, resolved_result) = tokio::join!(
+            self.fetch_general_comments(repo_info, pr_number),
+            self.fetch_review_comments(repo_info, pr_number),
+            self.fetch_resolved_thread_roots(repo_info, pr_number)
+        );
+
+        let mut general: Vec<UnifiedPrComment> = general_result?
+            .into_iter()
+            .map(|c| UnifiedPrComment::General {
+                id: c.id,
+                author: c.author.login,
+                author_association: c.author_association,
+                body: c.body,
+                created_at: c.created_at,
+                url: c.url,
+            })
+            .collect();
+        general.sort_by_key(|c| c.created_at());
+
+        Ok(PrReviewFeedback {
+            general,
+            threads: group_review_threads(review_result?, &resolved_result?),
+        })
+    }
+
+    async fn fetch_resolved_thread_roots(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        pr_number: i64,
+    ) -> Result<Vec<i64>, GitHubServiceError> {
+        (|| async {
+            let owner = repo_info.owner.clone();
+            let repo = repo_info.repo_name.clone();
+            let cli = self.gh_cli.clone();
+            let roots = task::spawn_blocking(move || {
+                cli.get_resolved_review_thread_roots(&owner, &repo, pr_number)
+            })
+            .await
+            .map_err(|err| {
+                GitHubServiceError::PullRequest(format!(
+                    "Failed to execute GitHub CLI for fetching PR #{pr_number} review threads: {err}"
+                ))
+            })?;
+            roots.map_err(GitHubServiceError::from)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &GitHubServiceError| e.should_retry())
+        .notify(|err: &GitHubServiceError, dur: Duration| {
+            tracing::warn!(
+                "GitHub API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
+    /// Jobs of a CI run that failed or timed out
+    pub async fn get_failed_ci_jobs(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        run: CiRun,
+    ) -> Result<Vec<CiJob>, GitHubServiceError> {
+        let jobs = (|| async {
+            let owner = repo_info.owner.clone();
+            let repo = repo_info.repo_name.clone();
+            let cli = self.gh_cli.clone();
+            let jobs = task::spawn_blocking(move || match run {
+                CiRun::CheckSuite(id) => cli.list_check_suite_runs(&owner, &repo, id),
+                CiRun::WorkflowRun(id) => cli.list_workflow_run_jobs(&owner, &repo, id),
+            })
+            .await
+            .map_err(|err| {
+                GitHubServiceError::Repository(format!(
+                    "Failed to execute GitHub CLI for listing CI jobs: {err}"
+                ))
+            })?;
+            jobs.map_err(GitHubServiceError::from)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &GitHubServiceError| e.should_retry())
+        .notify(|err: &GitHubServiceError, dur: Duration| {
+            tracing::warn!(
+                "GitHub API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await?;
+
+        Ok(jobs
+            .into_iter()
+            .filter(|job| matches!(job.conclusion.as_deref(), Some("failure" | "timed_out")))
+            .collect())
+    }
+
+    /// Plain-text log of a GitHub Actions job
+    pub async fn get_ci_job_log(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        job_id: i64,
+    ) -> Result<String, GitHubServiceError> {
+        (|| async {
+            let owner = repo_info.owner.clone();
+            let repo = repo_info.repo_name.clone();
+            let cli = self.gh_cli.clone();
+            let log = task::spawn_blocking(move || cli.get_job_log(&owner, &repo, job_id))
+                .await
+                .map_err(|err| {
+                    GitHubServiceError::Repository(format!(
+                        "Failed to execute GitHub CLI for fetching job {job_id} log: {err}"
+                    ))
+                })?;
+            log.map_err(GitHubServiceError::from)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &GitHubServiceError| e.should_retry())
+        .notify(|err: &GitHubServiceError, dur: Duration| {
+            tracing::warn!(
+                "GitHub API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
+    /// Reply in the review thread started by `root_id`. Not retried, so a
+    /// slow success is never posted twice.
+    pub async fn reply_to_review_thread(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        pr_number: i64,
+        root_id: i64,
+        body: &str,
+    ) -> Result<(), GitHubServiceError> {
+        let owner = repo_info.owner.clone();
+        let repo = repo_info.repo_name.clone();
+        let body = body.to_string();
+        let cli = self.gh_cli.clone();
+        task::spawn_blocking(move || {
+            cli.reply_to_review_comment(&owner, &repo, pr_number, root_id, &body)
+        })
+        .await
+        .map_err(|err| {
+            GitHubServiceError::PullRequest(format!(
+                "Failed to execute GitHub CLI for replying on PR #{pr_number}: {err}"
+            ))
+        })?
+        .map_err(GitHubServiceError::from)
+    }
+
+    /// Add a conversation comment to a pull request. Not retried, like
+    /// `reply_to_review_thread`.
+    pub async fn comment_on_pr(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        pr_number: i64,
+        body: &str,
+    ) -> Result<(), GitHubServiceError> {
+        let owner = repo_info.owner.clone();
+        let repo = repo_info.repo_name.clone();
+        let body = body.to_string();
+        let cli = self.gh_cli.clone();
+        task::spawn_blocking(move || cli.comment_on_pr(&owner, &repo, pr_number, &body))
+            .await
+            .map_err(|err| {
+                GitHubServiceError::PullRequest(format!(
+                    "Failed to execute GitHub CLI for commenting on PR #{pr_number}: {err}"
+                ))
+            })?
+            .map_err(GitHubServiceError::from)
+    }
+}
+
+/// Group inline comments by the comment that started their thread
+fn group_review_threads(
+    mut comments: Vec<PrReviewComment>,
+    resolved_roots: &[i64],
+) -> Vec<PrReviewThread> {
+    let mut threads: Vec<PrReviewThread> = Vec::new();
+    comments.sort_by_key(|c| c.created_at);
+
+    for c in comments {
+        let root_id = c.in_reply_to_id.unwrap_or(c.id);
+        let comment = UnifiedPrComment::Review {
+            id: c.id,
+            author: c.user.login,
+            author_association: c.author_association,
+            body: c.body,
+            created_at: c.created_at,
+            url: c.html_url,
+            path: c.path.clone(),
+            line: c.line,
+            diff_hunk: c.diff_hunk.clone(),
+        };
+        match threads.iter_mut().find(|t| t.root_id == root_id) {
+            Some(thread) => thread.comments.push(comment),
+            None => threads.push(PrReviewThread {
+                root_id,
+                path: c.path,
+                line: c.line,
+                diff_hunk: c.diff_hunk,
+                is_resolved: resolved_roots.contains(&root_id),
+                comments: vec![comment],
+            }),
+        }
+    }
+
+    threads
 }
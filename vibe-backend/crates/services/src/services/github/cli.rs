@@ -56,8 +56,35 @@ pub struct PrReviewComment {
     pub side: Option<String>,
     pub diff_hunk: String,
     pub author_association: String,
+    /// Set on replies; points at the first comment of the thread
+    #[serde(default)]
+    pub in_reply_to_id: Option<i64>,
 }
 
+/// A CI job, from a check suite's check runs or a workflow run's jobs
+#[derive(Debug, Clone, Deserialize)]
+pub struct CiJob {
+    pub id: i64,
+    pub name: String,
+    pub conclusion: Option<String>,
+    pub html_url: Option<String>,
+}
+
+/// Lists review threads with their resolution state; REST doesn't expose it.
+const REVIEW_THREADS_QUERY: &str = r#"
+query($owner: String!, $repo: String!, $number: Int!) {
+  repository(owner: $owner, name: $repo) {
+    pullRequest(number: $number) {
+      reviewThreads(first: 100) {
+        nodes {
+          isResolved
+          comments(first: 1) { nodes { databaseId } }
+        }
+      }
+    }
+  }
+}"#;
+
 /// High-level errors originating from the GitHub CLI.
 #[derive(Debug, Error)]
 pub enum GhCliError {
@@ -245,6 +272,105 @@ impl GhCli {
         ])?;
         Self::parse_pr_review_comments(&raw)
     }
+
+    /// Ids of the first comment of each resolved review thread.
+    pub fn get_resolved_review_thread_roots(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+    ) -> Result<Vec<i64>, GhCliError> {
+        let raw = self.run([
+            "api",
+            "graphql",
+            "-f",
+            &format!("query={REVIEW_THREADS_QUERY}"),
+            "-f",
+            &format!("owner={owner}"),
+            "-f",
+            &format!("repo={repo}"),
+            "-F",
+            &format!("number={pr_number}"),
+        ])?;
+        Self::parse_resolved_review_thread_roots(&raw)
+    }
+
+    /// Reply in the review thread started by `comment_id`.
+    pub fn reply_to_review_comment(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+        comment_id: i64,
+        body: &str,
+    ) -> Result<(), GhCliError> {
+        self.run([
+            "api",
+            "--method",
+            "POST",
+            &format!("repos/{owner}/{repo}/pulls/{pr_number}/comments/{comment_id}/replies"),
+            "-f",
+            &format!("body={body}"),
+        ])?;
+        Ok(())
+    }
+
+    /// Add a conversation comment to a pull request.
+    pub fn comment_on_pr(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+        body: &str,
+    ) -> Result<(), GhCliError> {
+        self.run([
+            "pr",
+            "comment",
+            &pr_number.to_string(),
+            "--repo",
+            &format!("{owner}/{repo}"),
+            "--body",
+            body,
+        ])?;
+        Ok(())
+    }
+
+    /// Check runs of a check suite. For GitHub Actions suites a check run's
+    /// id is also its job id.
+    pub fn list_check_suite_runs(
+        &self,
+        owner: &str,
+        repo: &str,
+        check_suite_id: i64,
+    ) -> Result<Vec<CiJob>, GhCliError> {
+        let raw = self.run([
+            "api",
+            &format!("repos/{owner}/{repo}/check-suites/{check_suite_id}/check-runs?per_page=100"),
+        ])?;
+        Self::parse_ci_jobs(&raw, "check_runs")
+    }
+
+    /// Jobs of a GitHub Actions workflow run.
+    pub fn list_workflow_run_jobs(
+        &self,
+        owner: &str,
+        repo: &str,
+        run_id: i64,
+    ) -> Result<Vec<CiJob>, GhCliError> {
+        let raw = self.run([
+            "api",
+            &format!("repos/{owner}/{repo}/actions/runs/{run_id}/jobs?per_page=100"),
+        ])?;
+        Self::parse_ci_jobs(&raw, "jobs")
+    }
+
+    /// Plain-text log of a GitHub Actions job.
+    pub fn get_job_log(&self, owner: &str, repo: &str, job_id: i64) -> Result<String, GhCliError> {
+        self.run([
+            "api",
+            &format!("repos/{owner}/{repo}/actions/jobs/{job_id}/logs"),
+        ])
+    }
 }
 
 impl GhCli {
@@ -357,6 +483,46 @@ impl GhCli {
         })
     }
 
+    fn parse_resolved_review_thread_roots(raw: &str) -> Result<Vec<i64>, GhCliError> {
+        let value: Value = serde_json::from_str(raw.trim()).map_err(|err| {
+            GhCliError::UnexpectedOutput(format!(
+                "Failed to parse review threads response: {err}; raw: {raw}"
+            ))
+        })?;
+
+        let threads = value
+            .pointer("/data/repository/pullRequest/reviewThreads/nodes")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| {
+                GhCliError::UnexpectedOutput(format!(
+                    "Review threads response missing 'reviewThreads' nodes: {value:#?}"
+                ))
+            })?;
+
+        Ok(threads
+            .iter()
+            .filter(|thread| thread.get("isResolved").and_then(Value::as_bool) == Some(true))
+            .filter_map(|thread| {
+                thread
+                    .pointer("/comments/nodes/0/databaseId")
+                    .and_then(Value::as_i64)
+            })
+            .collect())
+    }
+
+    fn parse_ci_jobs(raw: &str, key: &str) -> Result<Vec<CiJob>, GhCliError> {
+        let mut value: Value = serde_json::from_str(raw.trim()).map_err(|err| {
+            GhCliError::UnexpectedOutput(format!(
+                "Failed to parse CI jobs response: {err}; raw: {raw}"
+            ))
+        })?;
+        let jobs = value.get_mut(key).map(Value::take).ok_or_else(|| {
+            GhCliError::UnexpectedOutput(format!("CI jobs response missing '{key}' array"))
+        })?;
+        serde_json::from_value(jobs)
+            .map_err(|err| GhCliError::UnexpectedOutput(format!("Failed to parse CI jobs: {err}")))
+    }
+
     fn extract_pr_info(value: &Value) -> Option<PullRequestInfo> {
         let number = value.get("number")?.as_i64()?;
         let url = value.get("url")?.as_str()?.to_string();
@@ -0,0 +1,295 @@
+use std::{future::Future, sync::LazyLock, time::Duration};
+
+use backon::{ExponentialBuilder, Retryable};
+use db::models::{
+    gitlab_connection::GitLabConnection,
+    merge::{MergeStatus, PullRequestInfo},
+};
+use regex::Regex;
+use reqwest::{RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+use tracing::info;
+use url::Url;
+
+mod api;
+
+pub use api::merge_status_from_state;
+use api::{CreateMergeRequestBody, Discussion, MergeRequest, discussions_to_comments};
+
+use crate::services::github::{CreatePrRequest, UnifiedPrComment};
+
+const DISCUSSIONS_PER_PAGE: usize = 100;
+
+#[derive(Debug, Error)]
+pub enum GitLabServiceError {
+    #[error("Repository error: {0}")]
+    Repository(String),
+    #[error("Merge request error: {0}")]
+    MergeRequest(String),
+    #[error("GitLab authentication failed: {0}")]
+    AuthFailed(String),
+    #[error("Insufficient permissions: {0}")]
+    InsufficientPermissions(String),
+    #[error("GitLab project or merge request not found: {0}")]
+    NotFound(String),
+    #[error("GitLab is unavailable: {0}")]
+    Unavailable(String),
+    #[error("GitLab request failed: {0}")]
+    Request(#[from] reqwest::Error),
+}
+
+impl GitLabServiceError {
+    fn from_response(status: StatusCode, body: String) -> Self {
+        match status {
+            StatusCode::UNAUTHORIZED => Self::AuthFailed(body),
+            StatusCode::FORBIDDEN => Self::InsufficientPermissions(body),
+            StatusCode::NOT_FOUND => Self::NotFound(body),
+            s if s.is_server_error() || s == StatusCode::TOO_MANY_REQUESTS => {
+                Self::Unavailable(format!("{status}: {body}"))
+            }
+            _ => Self::MergeRequest(format!("{status}: {body}")),
+        }
+    }
+
+    pub fn should_retry(&self) -> bool {
+        matches!(self, Self::Unavailable(_) | Self::Request(_))
+    }
+}
+
+/// A GitLab project identified by instance host and full namespace path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitLabRepoInfo {
+    pub host: String,
+    /// e.g. `group/subgroup/project`
+    pub project_path: String,
+}
+
+/// `scheme://[user@]host[:port]/namespace/project[.git]`
+static URL_REMOTE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?:https?|ssh|git)://(?:[^@/\s]+@)?(?P<host>[A-Za-z0-9.-]+)(?::\d+)?/(?P<path>[\w.-]+(?:/[\w.-]+)+)/?$",
+    )
+    .expect("valid regex")
+});
+
+/// scp-like `[user@]host:namespace/project[.git]`
+static SCP_REMOTE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:[^@/:\s]+@)?(?P<host>[A-Za-z0-9.-]+):(?P<path>[\w.-]+(?:/[\w.-]+)+)/?$")
+        .expect("valid regex")
+});
+
+impl GitLabRepoInfo {
+    /// Supports SSH, HTTPS and merge request URLs. See tests for examples.
+    pub fn from_remote_url(remote_url: &str) -> Result<Self, GitLabServiceError> {
+        let invalid =
+            || GitLabServiceError::Repository(format!("Invalid GitLab URL format: {remote_url}"));
+
+        // Merge request and other web URLs put GitLab routes after `/-/`
+        let project_url = remote_url.split("/-/").next().unwrap_or(remote_url);
+        let caps = URL_REMOTE
+            .captures(project_url)
+            .or_else(|| SCP_REMOTE.captures(project_url))
+            .ok_or_else(invalid)?;
+
+        let path = &caps["path"];
+        let project_path = path.strip_suffix(".git").unwrap_or(path);
+        // `.` and `..` segments would point the API path somewhere else
+        if project_path
+            .split('/')
+            .any(|segment| segment.chars().all(|c: char| c == '.'))
+        {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            host: caps["host"].to_ascii_lowercase(),
+            project_path: project_path.to_string(),
+        })
+    }
+}
+
+/// GitLab REST client for merge requests, authenticated with a personal
+/// access token from a `GitLabConnection`
+#[derive(Debug, Clone)]
+pub struct GitLabService {
+    client: reqwest::Client,
+    api_url: String,
+    host: String,
+    access_token: String,
+}
+
+impl GitLabService {
+    pub fn new(gitlab_url: &str, access_token: &str) -> Result<Self, GitLabServiceError> {
+        let base_url = gitlab_url.trim_end_matches('/');
+        let host = Url::parse(base_url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+            .ok_or_else(|| {
+                GitLabServiceError::Repository(format!("Invalid GitLab URL: {gitlab_url}"))
+            })?;
+        let client = reqwest::Client::builder()
+            .user_agent("vibe-kanban")
+            .timeout(Duration::from_secs(30))
+            .build()?;
+
+        Ok(Self {
+            client,
+            api_url: format!("{base_url}/api/v4"),
+            host,
+            access_token: access_token.to_string(),
+        })
+    }
+
+    pub fn from_connection(connection: &GitLabConnection) -> Result<Self, GitLabServiceError> {
+        Self::new(&connection.gitlab_url, &connection.access_token)
+    }
+
+    /// Whether `repo_info` lives on the instance this service talks to
+    pub fn serves(&self, repo_info: &GitLabRepoInfo) -> bool {
+        self.host == repo_info.host
+    }
+
+    fn project_url(&self, repo_info: &GitLabRepoInfo) -> String {
+        format!(
+            "{}/projects/{}",
+            self.api_url,
+            repo_info.project_path.replace('/', "%2F")
+        )
+    }
+
+    async fn send<T: DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<T, GitLabServiceError> {
+        let response = request
+            .header("PRIVATE-TOKEN", &self.access_token)
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(GitLabServiceError::from_response(status, body));
+        }
+        Ok(response.json().await?)
+    }
+
+    /// Create a merge request from `head_branch` into `base_branch`
+    pub async fn create_mr(
+        &self,
+        repo_info: &GitLabRepoInfo,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, GitLabServiceError> {
+        let title = if request.draft.unwrap_or(false) {
+            format!("Draft: {}", request.title)
+        } else {
+            request.title.clone()
+        };
+        let body = CreateMergeRequestBody {
+            source_branch: &request.head_branch,
+            target_branch: &request.base_branch,
+            title,
+            description: request.body.as_deref(),
+        };
+        let url = format!("{}/merge_requests", self.project_url(repo_info));
+
+        // Not retried: the POST is not idempotent, and a request that timed
+        // out may still have created the MR
+        let mr: MergeRequest = self.send(self.client.post(&url).json(&body)).await?;
+
+        info!(
+            "Created GitLab MR !{} for branch {} in {}",
+            mr.iid, request.head_branch, repo_info.project_path
+        );
+        Ok(mr.into())
+    }
+
+    /// Get the current status of a merge request
+    pub async fn update_mr_status(
+        &self,
+        repo_info: &GitLabRepoInfo,
+        mr_iid: i64,
+    ) -> Result<PullRequestInfo, GitLabServiceError> {
+        let url = format!("{}/merge_requests/{mr_iid}", self.project_url(repo_info));
+        let mr: MergeRequest = with_retry(|| self.send(self.client.get(&url))).await?;
+        Ok(mr.into())
+    }
+
+    /// List all merge requests for a source branch, open ones first
+    pub async fn list_all_mrs_for_branch(
+        &self,
+        repo_info: &GitLabRepoInfo,
+        branch_name: &str,
+    ) -> Result<Vec<PullRequestInfo>, GitLabServiceError> {
+        let url = format!("{}/merge_requests", self.project_url(repo_info));
+        let mrs: Vec<MergeRequest> = with_retry(|| {
+            self.send(self.client.get(&url).query(&[
+                ("source_branch", branch_name),
+                ("state", "all"),
+                ("order_by", "created_at"),
+            ]))
+        })
+        .await?;
+
+        let mut prs: Vec<PullRequestInfo> = mrs.into_iter().map(Into::into).collect();
+        prs.sort_by_key(|pr| !matches!(pr.status, MergeStatus::Open));
+        Ok(prs)
+    }
+
+    /// Fetch all notes on a merge request as a unified comment timeline
+    pub async fn get_mr_comments(
+        &self,
+        repo_info: &GitLabRepoInfo,
+        mr_iid: i64,
+        mr_web_url: &str,
+    ) -> Result<Vec<UnifiedPrComment>, GitLabServiceError> {
+        let url = format!(
+            "{}/merge_requests/{mr_iid}/discussions",
+            self.project_url(repo_info)
+        );
+        let per_page = DISCUSSIONS_PER_PAGE.to_string();
+        let mut discussions = Vec::new();
+        for page in 1.. {
+            let page = page.to_string();
+            let batch: Vec<Discussion> = with_retry(|| {
+                self.send(
+                    self.client
+                        .get(&url)
+                        .query(&[("per_page", per_page.as_str()), ("page", page.as_str())]),
+                )
+            })
+            .await?;
+            let done = batch.len() < DISCUSSIONS_PER_PAGE;
+            discussions.extend(batch);
+            if done {
+                break;
+            }
+        }
+
+        Ok(discussions_to_comments(discussions, mr_web_url))
+    }
+}
+
+async fn with_retry<T, F, Fut>(operation: F) -> Result<T, GitLabServiceError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, GitLabServiceError>>,
+{
+    operation
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &GitLabServiceError| e.should_retry())
+        .notify(|err: &GitLabServiceError, dur: Duration| {
+            tracing::warn!(
+                "GitLab API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+}
@@ -0,0 +1,121 @@
+//! GitLab REST API payloads and their mapping onto the shared PR types
+
+use chrono::{DateTime, Utc};
+use db::models::merge::{MergeStatus, PullRequestInfo};
+use serde::{Deserialize, Serialize};
+
+use crate::services::github::UnifiedPrComment;
+
+/// GitLab has no author association; use GitHub's value for "no relation"
+const NO_ASSOCIATION: &str = "NONE";
+
+#[derive(Debug, Serialize)]
+pub(super) struct CreateMergeRequestBody<'a> {
+    pub source_branch: &'a str,
+    pub target_branch: &'a str,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<&'a str>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct MergeRequest {
+    pub iid: i64,
+    pub web_url: String,
+    pub state: String,
+    pub merged_at: Option<DateTime<Utc>>,
+    pub merge_commit_sha: Option<String>,
+    pub squash_commit_sha: Option<String>,
+}
+
+impl From<MergeRequest> for PullRequestInfo {
+    fn from(mr: MergeRequest) -> Self {
+        PullRequestInfo {
+            number: mr.iid,
+            url: mr.web_url,
+            status: merge_status_from_state(&mr.state),
+            merged_at: mr.merged_at,
+            // Squash merges leave `merge_commit_sha` empty
+            merge_commit_sha: mr.merge_commit_sha.or(mr.squash_commit_sha),
+        }
+    }
+}
+
+/// Map a GitLab MR `state` onto our merge status
+pub fn merge_status_from_state(state: &str) -> MergeStatus {
+    match state {
+        "opened" => MergeStatus::Open,
+        "merged" => MergeStatus::Merged,
+        "closed" | "locked" => MergeStatus::Closed,
+        _ => MergeStatus::Unknown,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct Discussion {
+    pub notes: Vec<Note>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct Note {
+    pub id: i64,
+    pub body: String,
+    pub author: NoteAuthor,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub system: bool,
+    pub position: Option<NotePosition>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct NoteAuthor {
+    pub username: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct NotePosition {
+    pub new_path: Option<String>,
+    pub old_path: Option<String>,
+    pub new_line: Option<i64>,
+    pub old_line: Option<i64>,
+}
+
+/// Flatten MR discussions into the unified comment timeline. System notes
+/// (pushes, label changes, ...) are dropped; diff notes become review comments.
+pub(super) fn discussions_to_comments(
+    discussions: Vec<Discussion>,
+    mr_web_url: &str,
+) -> Vec<UnifiedPrComment> {
+    let mut comments: Vec<UnifiedPrComment> = discussions
+        .into_iter()
+        .flat_map(|d| d.notes)
+        .filter(|note| !note.system)
+        .map(|note| {
+            let url = format!("{mr_web_url}#note_{}", note.id);
+            match note.position {
+                Some(position) => UnifiedPrComment::Review {
+                    id: note.id,
+                    author: note.author.username,
+                    author_association: NO_ASSOCIATION.to_string(),
+                    body: note.body,
+                    created_at: note.created_at,
+                    url,
+                    path: position.new_path.or(position.old_path).unwrap_or_default(),
+                    line: position.new_line.or(position.old_line),
+                    diff_hunk: String::new(),
+                },
+                None => UnifiedPrComment::General {
+                    id: note.id.to_string(),
+                    author: note.author.username,
+                    author_association: NO_ASSOCIATION.to_string(),
+                    body: note.body,
+                    created_at: note.created_at,
+                    url,
+                },
+            }
+        })
+        .collect();
+
+    comments.sort_by_key(|c| c.created_at());
+    comments
+}
@@ -0,0 +1,526 @@
+//! Landing attempts on a shared target branch one at a time.
+//!
+//! Attempts queued for the same repository and target branch form a lane.
+//! A lane lands its entries in queue order: the attempt's branch is rebased
+//! onto the current tip of the target, the repository's verification script
+//! runs in the rebased worktree, and only if it passes is the branch squashed
+//! or fast-forwarded in. An entry that conflicts or fails verification is
+//! ejected with the reason and the lane moves on to the next one.
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::{LazyLock, Mutex},
+    time::Duration,
+};
+
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessStatus},
+    merge::Merge,
+    merge_queue::{
+        CreateMergeQueueEntry, MergeQueueConfig, MergeQueueEntry, MergeQueueStatus, MergeStrategy,
+    },
+    repo::Repo,
+    task::{Task, TaskStatus},
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::services::{
+    attempt_comparison::run_verification,
+    container::{ContainerError, ContainerService},
+    git::{GitService, GitServiceError},
+};
+
+/// Lanes a task is currently landing entries of
+static ACTIVE_LANES: LazyLock<Mutex<HashSet<(Uuid, String)>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
+#[derive(Debug, Error)]
+pub enum MergeQueueError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Repository not found")]
+    RepoNotFound,
+    #[error("Workspace not found")]
+    WorkspaceNotFound,
+    #[error("Task not found")]
+    TaskNotFound,
+    #[error("The attempt is already in the merge queue")]
+    AlreadyQueued,
+    #[error("The attempt has a running process")]
+    AttemptBusy,
+    #[error("Rebasing onto the target branch conflicts in: {}", .0.join(", "))]
+    Conflicts(Vec<String>),
+    #[error("Verification failed:\n{0}")]
+    VerificationFailed(String),
+}
+
+/// Exclusive right to land the entries of one lane, released on drop
+#[derive(Debug)]
+struct LaneClaim(Uuid, String);
+
+impl LaneClaim {
+    fn acquire(repo_id: Uuid, target_branch: &str) -> Option<Self> {
+        let mut lanes = ACTIVE_LANES.lock().unwrap_or_else(|e| e.into_inner());
+        lanes
+            .insert((repo_id, target_branch.to_string()))
+            .then(|| Self(repo_id, target_branch.to_string()))
+    }
+}
+
+impl Drop for LaneClaim {
+    fn drop(&mut self) {
+        let mut lanes = ACTIVE_LANES.lock().unwrap_or_else(|e| e.into_inner());
+        lanes.remove(&(self.0, std::mem::take(&mut self.1)));
+    }
+}
+
+/// Commit message of a squash merge: the task title tagged with its id, and
+/// the description below it
+pub fn merge_commit_message(task: &Task) -> String {
+    let task_uuid_str = task.id.to_string();
+    let first_uuid_section = task_uuid_str.split('-').next().unwrap_or(&task_uuid_str);
+
+    let mut commit_message = format!("{} (vibe-kanban {})", task.title, first_uuid_section);
+
+    // Add description on next line if it exists
+    if let Some(description) = &task.description
+        && !description.trim().is_empty()
+    {
+        commit_message.push_str("\n\n");
+        commit_message.push_str(description);
+    }
+    commit_message
+}
+
+/// Queue one repository of an attempt for landing on its target branch. The
+/// caller starts the lane with [`run_lane`].
+pub async fn enqueue<C: ContainerService + Sync>(
+    container: &C,
+    workspace: &Workspace,
+    repo_id: Uuid,
+    strategy: MergeStrategy,
+) -> Result<MergeQueueEntry, MergeQueueError> {
+    let pool = &container.db().pool;
+    let workspace_repo = WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, repo_id)
+        .await?
+        .ok_or(MergeQueueError::RepoNotFound)?;
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(MergeQueueError::TaskNotFound)?;
+
+    MergeQueueEntry::create(
+        pool,
+        &CreateMergeQueueEntry {
+            workspace_id: workspace.id,
+            repo_id,
+            task_id: task.id,
+            project_id: task.project_id,
+            target_branch: workspace_repo.target_branch,
+            strategy,
+        },
+    )
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+            MergeQueueError::AlreadyQueued
+        }
+        e => e.into(),
+    })
+}
+
+/// Land the queued entries of a lane until it is empty. Returns right away
+/// when another task is already working through the lane.
+pub async fn run_lane<C: ContainerService + Sync>(
+    container: &C,
+    repo_id: Uuid,
+    target_branch: &str,
+) {
+    let pool = &container.db().pool;
+    loop {
+        let Some(claim) = LaneClaim::acquire(repo_id, target_branch) else {
+            return;
+        };
+        drain_lane(container, repo_id, target_branch).await;
+        drop(claim);
+
+        // An entry queued while the lane was still claimed found it busy and
+        // relies on this task to pick it up
+        match MergeQueueEntry::next_queued(pool, repo_id, target_branch).await {
+            Ok(Some(_)) => continue,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::error!("Failed to check merge queue of {target_branch}: {e}");
+                return;
+            }
+        }
+    }
+}
+
+async fn drain_lane<C: ContainerService + Sync>(container: &C, repo_id: Uuid, target_branch: &str) {
+    let pool = &container.db().pool;
+    loop {
+        let entry = match MergeQueueEntry::next_queued(pool, repo_id, target_branch).await {
+            Ok(Some(entry)) => entry,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::error!("Failed to load merge queue of {target_branch}: {e}");
+                return;
+            }
+        };
+
+        let result = match land(container, &entry).await {
+            Ok(sha) => {
+                tracing::info!(
+                    "Merge queue landed attempt {} on {} as {}",
+                    entry.workspace_id,
+                    target_branch,
+                    sha
+                );
+                MergeQueueEntry::finish(pool, entry.id, MergeQueueStatus::Merged, Some(&sha), None)
+                    .await
+            }
+            Err(e) => {
+                tracing::info!(
+                    "Merge queue ejected attempt {} from {}: {}",
+                    entry.workspace_id,
+                    target_branch,
+                    e
+                );
+                MergeQueueEntry::finish(
+                    pool,
+                    entry.id,
+                    MergeQueueStatus::Ejected,
+                    None,
+                    Some(&e.to_string()),
+                )
+                .await
+            }
+        };
+        if let Err(e) = result {
+            // Leaving the entry queued would land it again forever
+            tracing::error!("Failed to record merge queue entry {}: {e}", entry.id);
+            return;
+        }
+    }
+}
+
+/// The git side of landing an entry, in the attempt's worktree
+struct Landing<'a> {
+    git: &'a GitService,
+    repo_path: &'a Path,
+    worktree_path: &'a Path,
+    branch: &'a str,
+    target_branch: &'a str,
+}
+
+impl Landing<'_> {
+    /// Rebase the attempt's branch onto the tip of the target. A conflicting
+    /// rebase is aborted, leaving the branch as it was.
+    fn rebase(&self) -> Result<(), MergeQueueError> {
+        match self.git.rebase_branch(
+            self.repo_path,
+            self.worktree_path,
+            self.target_branch,
+            self.target_branch,
+            self.branch,
+        ) {
+            Ok(_) => Ok(()),
+            Err(GitServiceError::MergeConflicts(_)) => {
+                let files = self
+                    .git
+                    .get_conflicted_files(self.worktree_path)
+                    .unwrap_or_default();
+                if let Err(e) = self.git.abort_conflicts(self.worktree_path) {
+                    tracing::error!(
+                        "Failed to abort rebase in {}: {e}",
+                        self.worktree_path.display()
+                    );
+                }
+                Err(MergeQueueError::Conflicts(files))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn verify(&self, script: &str, timeout: Duration) -> Result<(), MergeQueueError> {
+        let run = run_verification(self.worktree_path, script, timeout).await?;
+        if !run.passed {
+            return Err(MergeQueueError::VerificationFailed(run.output));
+        }
+        Ok(())
+    }
+
+    /// Returns the commit the target branch now points at
+    fn merge(
+        &self,
+        strategy: MergeStrategy,
+        commit_message: &str,
+    ) -> Result<String, MergeQueueError> {
+        let sha = match strategy {
+            MergeStrategy::Squash => self.git.merge_changes(
+                self.repo_path,
+                self.worktree_path,
+                self.branch,
+                self.target_branch,
+                commit_message,
+            )?,
+            MergeStrategy::FastForward => {
+                self.git
+                    .fast_forward_changes(self.repo_path, self.branch, self.target_branch)?
+            }
+        };
+        Ok(sha)
+    }
+}
+
+/// Rebase, verify and merge one entry. Returns the commit the target branch
+/// now points at.
+async fn land<C: ContainerService + Sync>(
+    container: &C,
+    entry: &MergeQueueEntry,
+) -> Result<String, MergeQueueError> {
+    let pool = &container.db().pool;
+    let workspace = Workspace::find_by_id(pool, entry.workspace_id)
+        .await?
+        .ok_or(MergeQueueError::WorkspaceNotFound)?;
+    let task = Task::find_by_id(pool, entry.task_id)
+        .await?
+        .ok_or(MergeQueueError::TaskNotFound)?;
+    let repo = Repo::find_by_id(pool, entry.repo_id)
+        .await?
+        .ok_or(MergeQueueError::RepoNotFound)?;
+
+    // The agent could still be committing to the branch
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Err(MergeQueueError::AttemptBusy);
+    }
+
+    MergeQueueEntry::set_status(pool, entry.id, MergeQueueStatus::Rebasing).await?;
+    let worktree_path =
+        PathBuf::from(container.ensure_container_exists(&workspace).await?).join(&repo.name);
+    let landing = Landing {
+        git: container.git(),
+        repo_path: &repo.path,
+        worktree_path: &worktree_path,
+        branch: &workspace.branch,
+        target_branch: &entry.target_branch,
+    };
+    landing.rebase()?;
+
+    if let Some(config) = MergeQueueConfig::find(pool, entry.project_id, entry.repo_id).await?
+        && let Some(script) = config
+            .verification_script
+            .as_deref()
+            .filter(|script| !script.trim().is_empty())
+    {
+        MergeQueueEntry::set_status(pool, entry.id, MergeQueueStatus::Verifying).await?;
+        let timeout = Duration::from_secs(config.verification_timeout_secs.max(1) as u64);
+        landing.verify(script, timeout).await?;
+    }
+
+    let sha = landing.merge(entry.strategy, &merge_commit_message(&task))?;
+
+    Merge::create_direct(pool, workspace.id, repo.id, &entry.target_branch, &sha).await?;
+    Task::update_status(pool, task.id, TaskStatus::Done).await?;
+
+    for dev_server in
+        ExecutionProcess::find_running_dev_servers_by_workspace(pool, workspace.id).await?
+    {
+        if let Err(e) = container
+            .stop_execution(&dev_server, ExecutionProcessStatus::Killed)
+            .await
+        {
+            tracing::error!(
+                "Failed to stop dev server {} for merged attempt {}: {}",
+                dev_server.id,
+                workspace.id,
+                e
+            );
+        }
+    }
+
+    Ok(sha)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::services::git::GitCli;
+
+    const BRANCH: &str = "vk/1a2b-feature";
+
+    fn git(path: &Path, args: &[&str]) -> String {
+        GitCli::new().git(path, args).unwrap().trim().to_string()
+    }
+
+    fn commit_file(path: &Path, file: &str, content: &str, message: &str) {
+        std::fs::write(path.join(file), content).unwrap();
+        git(path, &["add", file]);
+        git(path, &["commit", "-m", message]);
+    }
+
+    /// A repository whose `main` moved on after the attempt branched off it,
+    /// with the attempt checked out in its own worktree
+    struct Lane {
+        _root: TempDir,
+        git: GitService,
+        repo_path: PathBuf,
+        worktree_path: PathBuf,
+    }
+
+    impl Lane {
+        fn new(attempt_file: &str, main_file: &str) -> Self {
+            let root = TempDir::new().unwrap();
+            let repo_path = root.path().join("repo");
+            let worktree_path = root.path().join("worktree");
+            let git_service = GitService::new();
+            git_service
+                .initialize_repo_with_main_branch(&repo_path)
+                .unwrap();
+            git(&repo_path, &["config", "user.name", "Test User"]);
+            git(&repo_path, &["config", "user.email", "test@example.com"]);
+            commit_file(&repo_path, "shared.txt", "base\n", "Base");
+
+            git_service
+                .add_worktree(&repo_path, &worktree_path, BRANCH, true)
+                .unwrap();
+            commit_file(&worktree_path, attempt_file, "attempt\n", "Attempt change");
+            commit_file(&repo_path, main_file, "main\n", "Main change");
+
+            Self {
+                _root: root,
+                git: git_service,
+                repo_path,
+                worktree_path,
+            }
+        }
+
+        fn landing(&self) -> Landing<'_> {
+            Landing {
+                git: &self.git,
+                repo_path: &self.repo_path,
+                worktree_path: &self.worktree_path,
+                branch: BRANCH,
+                target_branch: "main",
+            }
+        }
+
+        fn tip(&self, branch: &str) -> String {
+            git(&self.repo_path, &["rev-parse", branch])
+        }
+    }
+
+    #[tokio::test]
+    async fn squash_lands_the_rebased_attempt_as_one_commit() {
+        let lane = Lane::new("attempt.txt", "main.txt");
+        let main_before = lane.tip("main");
+        let landing = lane.landing();
+
+        landing.rebase().unwrap();
+        landing
+            .verify(
+                "test -f attempt.txt && test -f main.txt",
+                Duration::from_secs(30),
+            )
+            .await
+            .unwrap();
+        let sha = landing
+            .merge(MergeStrategy::Squash, "Add attempt (vibe-kanban 1a2b)")
+            .unwrap();
+
+        assert_eq!(lane.tip("main"), sha);
+        assert_eq!(git(&lane.repo_path, &["rev-parse", "main^"]), main_before);
+        assert_eq!(
+            git(&lane.repo_path, &["log", "-1", "--format=%s", "main"]),
+            "Add attempt (vibe-kanban 1a2b)"
+        );
+        assert!(lane.repo_path.join("attempt.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn fast_forward_lands_the_rebased_commits() {
+        let lane = Lane::new("attempt.txt", "main.txt");
+        let main_before = lane.tip("main");
+        let landing = lane.landing();
+
+        landing.rebase().unwrap();
+        let sha = landing.merge(MergeStrategy::FastForward, "unused").unwrap();
+
+        assert_eq!(lane.tip("main"), sha);
+        assert_eq!(lane.tip(BRANCH), sha);
+        assert_eq!(git(&lane.repo_path, &["rev-parse", "main^"]), main_before);
+        assert_eq!(
+            git(&lane.repo_path, &["log", "-1", "--format=%s", "main"]),
+            "Attempt change"
+        );
+    }
+
+    #[test]
+    fn conflicting_rebase_ejects_and_leaves_the_attempt_as_it_was() {
+        let lane = Lane::new("shared.txt", "shared.txt");
+        let main_before = lane.tip("main");
+        let attempt_before = lane.tip(BRANCH);
+
+        match lane.landing().rebase() {
+            Err(MergeQueueError::Conflicts(files)) => assert_eq!(files, ["shared.txt"]),
+            other => panic!("expected conflicts, got {other:?}"),
+        }
+
+        assert!(
+            lane.git
+                .detect_conflict_op(&lane.worktree_path)
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(lane.tip(BRANCH), attempt_before);
+        assert_eq!(lane.tip("main"), main_before);
+    }
+
+    #[tokio::test]
+    async fn failed_verification_ejects_without_landing() {
+        let lane = Lane::new("attempt.txt", "main.txt");
+        let main_before = lane.tip("main");
+        let landing = lane.landing();
+
+        landing.rebase().unwrap();
+        let result = landing
+            .verify("echo 'tests failed' && exit 1", Duration::from_secs(30))
+            .await;
+
+        match result {
+            Err(MergeQueueError::VerificationFailed(output)) => {
+                assert!(output.contains("tests failed"), "{output}")
+            }
+            other => panic!("expected a failed verification, got {other:?}"),
+        }
+        assert_eq!(lane.tip("main"), main_before);
+    }
+
+    #[test]
+    fn lane_claim_is_exclusive_until_dropped() {
+        let repo_id = Uuid::new_v4();
+
+        let claim = LaneClaim::acquire(repo_id, "main").expect("lane is free");
+        assert!(LaneClaim::acquire(repo_id, "main").is_none());
+        // Other target branches of the repository are separate lanes
+        assert!(LaneClaim::acquire(repo_id, "release").is_some());
+
+        drop(claim);
+        assert!(LaneClaim::acquire(repo_id, "main").is_some());
+    }
+}
@@ -1,28 +1,40 @@
 pub mod analytics;
 pub mod api_streaming;
 pub mod approvals;
+pub mod attempt_comparison;
 pub mod auth;
+pub mod checkpoint;
+pub mod ci_repair;
 pub mod cloud_storage;
 pub mod config;
+pub mod conflict_resolution;
 pub mod container;
 pub mod diff_stream;
 pub mod document_storage;
 pub mod events;
+pub mod execution_queue;
 pub mod file_ranker;
 pub mod file_search_cache;
 pub mod filesystem;
 pub mod filesystem_watcher;
 pub mod git;
 pub mod github;
+pub mod gitlab;
 pub mod image;
+pub mod merge_queue;
 pub mod notification;
 pub mod oauth_credentials;
 pub mod pr_monitor;
+pub mod pr_review;
 pub mod project;
+pub mod prompt;
 pub mod queued_message;
 pub mod remote_client;
 pub mod repo;
 pub mod share;
 pub mod supabase_storage;
+pub mod task_templates;
+pub mod transcript;
+pub mod usage;
 pub mod workspace_manager;
 pub mod worktree_manager;
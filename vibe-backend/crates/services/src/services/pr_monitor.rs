@@ -3,7 +3,8 @@ use std::time::Duration;
 use db::{
     DBService,
     models::{
-        merge::{Merge, MergeStatus, PrMerge},
+        gitlab_connection::GitLabConnection,
+        merge::{Merge, MergeStatus, PrMerge, PullRequestInfo},
         task::{Task, TaskStatus},
         workspace::{Workspace, WorkspaceError},
     },
@@ -17,6 +18,7 @@ use tracing::{debug, error, info};
 use crate::services::{
     analytics::AnalyticsContext,
     github::{GitHubRepoInfo, GitHubService, GitHubServiceError},
+    gitlab::{GitLabRepoInfo, GitLabService, GitLabServiceError},
     share::SharePublisher,
 };
 
@@ -25,12 +27,14 @@ enum PrMonitorError {
     #[error(transparent)]
     GitHubServiceError(#[from] GitHubServiceError),
     #[error(transparent)]
+    GitLabServiceError(#[from] GitLabServiceError),
+    #[error(transparent)]
     WorkspaceError(#[from] WorkspaceError),
     #[error(transparent)]
     Sqlx(#[from] SqlxError),
 }
 
-/// Service to monitor GitHub PRs and update task status when they are merged
+/// Service to monitor GitHub PRs and GitLab MRs and update task status when they are merged
 pub struct PrMonitorService {
     db: DBService,
     poll_interval: Duration,
@@ -93,15 +97,38 @@ impl PrMonitorService {
         Ok(())
     }
 
+    /// Fetch the latest state of a PR from GitHub, or of an MR from the
+    /// connected GitLab instance
+    async fn fetch_pr_status(&self, pr_merge: &PrMerge) -> Result<PullRequestInfo, PrMonitorError> {
+        let url = &pr_merge.pr_info.url;
+        if let Ok(repo_info) = GitHubRepoInfo::from_remote_url(url) {
+            // GitHubService now uses gh CLI, no token needed
+            let github_service = GitHubService::new()?;
+            return Ok(github_service
+                .update_pr_status(&repo_info, pr_merge.pr_info.number)
+                .await?);
+        }
+
+        let repo_info = GitLabRepoInfo::from_remote_url(url)?;
+        let gitlab_service = GitLabConnection::find_workspace_connection(&self.db.pool)
+            .await?
+            .map(|connection| GitLabService::from_connection(&connection))
+            .transpose()?
+            .filter(|service| service.serves(&repo_info))
+            .ok_or_else(|| {
+                GitLabServiceError::Repository(format!(
+                    "No GitLab connection configured for {}",
+                    repo_info.host
+                ))
+            })?;
+        Ok(gitlab_service
+            .update_mr_status(&repo_info, pr_merge.pr_info.number)
+            .await?)
+    }
+
     /// Check the status of a specific PR
     async fn check_pr_status(&self, pr_merge: &PrMerge) -> Result<(), PrMonitorError> {
-        // GitHubService now uses gh CLI, no token needed
-        let github_service = GitHubService::new()?;
-        let repo_info = GitHubRepoInfo::from_remote_url(&pr_merge.pr_info.url)?;
-
-        let pr_status = github_service
-            .update_pr_status(&repo_info, pr_merge.pr_info.number)
-            .await?;
+        let pr_status = self.fetch_pr_status(pr_merge).await?;
 
         debug!(
             "PR #{} status: {:?} (was open)",
@@ -110,7 +137,7 @@ impl PrMonitorService {
 
         // Update the PR status in the database
         if !matches!(&pr_status.status, MergeStatus::Open) {
-            // Update merge status with the latest information from GitHub
+            // Update merge status with the latest information from the provider
             Merge::update_status(
                 &self.db.pool,
                 pr_merge.id,
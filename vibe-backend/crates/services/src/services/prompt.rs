@@ -0,0 +1,26 @@
+//! Helpers shared by the services that build coding agent prompts from
+//! GitHub, CI and git output.
+
+/// A fence longer than any backtick run in `text`, so embedding `text` in a
+/// code block cannot close the block early
+pub fn fence_for(text: &str) -> String {
+    let longest = text
+        .split(|c: char| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fence_outgrows_backtick_runs() {
+        assert_eq!(fence_for("plain text"), "```");
+        assert_eq!(fence_for("inline `code`"), "```");
+        assert_eq!(fence_for("```rust\nfn main() {}\n```"), "````");
+        assert_eq!(fence_for("a ````` b"), "``````");
+    }
+}
@@ -0,0 +1,365 @@
+//! Team task templates: rendering `{{variable}}` placeholders, creating tasks
+//! from templates and materializing recurring tasks on schedule.
+
+pub mod rrule;
+
+use std::{collections::HashMap, time::Duration};
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    task::{CreateTask, Task},
+    task_document_link::TaskDocumentLink,
+    task_tag::TaskTag,
+    task_template::{TaskTemplate, TaskTemplateRecurrence},
+    workspace::Workspace,
+    workspace_repo::CreateWorkspaceRepo,
+};
+use executors::profile::ExecutorProfileId;
+use serde::Serialize;
+use sqlx::PgPool;
+use thiserror::Error;
+use tokio::time::interval;
+use tracing::{debug, error, info, warn};
+use ts_rs::TS;
+
+use self::rrule::{RRuleError, RecurrenceRule};
+use crate::services::container::{ContainerError, ContainerService};
+
+#[derive(Debug, Error)]
+pub enum TaskTemplateError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+    #[error(transparent)]
+    RRule(#[from] RRuleError),
+    #[error("Unknown template variable: {0}")]
+    UnknownVariable(String),
+    #[error("Unclosed placeholder in template: {0}")]
+    UnclosedPlaceholder(String),
+}
+
+/// A task created from a template, with its sub-issues
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct InstantiatedTask {
+    pub task: Task,
+    pub sub_issues: Vec<Task>,
+}
+
+/// Variables available to a template: the built-in `date`, `week`, `month`
+/// and `year` for `now`, then the template's defaults, then `overrides`
+pub fn template_variables(
+    template: &TaskTemplate,
+    overrides: &HashMap<String, String>,
+    now: DateTime<Utc>,
+) -> HashMap<String, String> {
+    let mut variables = HashMap::from([
+        ("date".to_string(), now.format("%Y-%m-%d").to_string()),
+        ("week".to_string(), now.format("%G-W%V").to_string()),
+        ("month".to_string(), now.format("%Y-%m").to_string()),
+        ("year".to_string(), now.format("%Y").to_string()),
+    ]);
+    variables.extend(template.variables.0.clone());
+    variables.extend(overrides.clone());
+    variables
+}
+
+/// Replace each `{{name}}` in `template` with its variable value
+pub fn render(
+    template: &str,
+    variables: &HashMap<String, String>,
+) -> Result<String, TaskTemplateError> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find("{{") {
+        rendered.push_str(&rest[..open]);
+        let after_open = &rest[open + 2..];
+        let close = after_open
+            .find("}}")
+            .ok_or_else(|| TaskTemplateError::UnclosedPlaceholder(template.to_string()))?;
+        let name = after_open[..close].trim();
+        let value = variables
+            .get(name)
+            .ok_or_else(|| TaskTemplateError::UnknownVariable(name.to_string()))?;
+        rendered.push_str(value);
+        rest = &after_open[close + 2..];
+    }
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+fn render_task(
+    template: &TaskTemplate,
+    title: &str,
+    description: Option<&str>,
+    variables: &HashMap<String, String>,
+) -> Result<CreateTask, TaskTemplateError> {
+    let description = description
+        .map(|description| render(description, variables))
+        .transpose()?;
+    let mut task = CreateTask::from_title_description(
+        template.project_id,
+        render(title, variables)?,
+        description,
+    )
+    .with_team(template.team_id);
+    task.priority = template.priority;
+    task.assignee_id = template.assignee_id;
+    Ok(task)
+}
+
+/// Create a task from `template`, with its tags, linked documents and
+/// sub-issues. Every placeholder is rendered before anything is created and
+/// the rows are written in one transaction, so a failure at any step leaves
+/// no partial task behind.
+pub async fn instantiate(
+    pool: &PgPool,
+    template: &TaskTemplate,
+    overrides: &HashMap<String, String>,
+    now: DateTime<Utc>,
+) -> Result<InstantiatedTask, TaskTemplateError> {
+    let variables = template_variables(template, overrides, now);
+    let parent = render_task(
+        template,
+        &template.title_template,
+        template.description_template.as_deref(),
+        &variables,
+    )?;
+    let children = template
+        .sub_issues
+        .iter()
+        .map(|sub_issue| {
+            render_task(
+                template,
+                &sub_issue.title,
+                sub_issue.description.as_deref(),
+                &variables,
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut tx = pool.begin().await?;
+    let task = Task::create(&mut *tx, &parent, uuid::Uuid::new_v4()).await?;
+    for tag_id in &template.tag_ids {
+        TaskTag::add_tag(&mut *tx, task.id, *tag_id).await?;
+    }
+    if !template.document_ids.is_empty() {
+        TaskDocumentLink::link_documents(&mut *tx, task.id, &template.document_ids).await?;
+    }
+
+    let mut sub_issues = Vec::with_capacity(children.len());
+    for child in &children {
+        let sub_issue = Task::create(&mut *tx, child, uuid::Uuid::new_v4()).await?;
+        Task::set_parent(&mut *tx, sub_issue.id, Some(task.id)).await?;
+        sub_issues.push(sub_issue);
+    }
+    tx.commit().await?;
+
+    Ok(InstantiatedTask { task, sub_issues })
+}
+
+/// Validate `rrule` and find its first occurrence after `now`
+pub fn next_run_at(
+    rrule: &str,
+    starts_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>, RRuleError> {
+    Ok(rrule.parse::<RecurrenceRule>()?.next_after(starts_at, now))
+}
+
+/// Start an agent attempt for a task created from a template
+pub async fn start_attempt<C: ContainerService + Sync>(
+    container: &C,
+    task: &Task,
+    executor_profile_id: ExecutorProfileId,
+    repos: &[CreateWorkspaceRepo],
+) -> Result<Workspace, TaskTemplateError> {
+    let workspace = container.create_task_workspace(task, repos).await?;
+    container
+        .start_workspace(&workspace, executor_profile_id)
+        .await?;
+    Ok(workspace)
+}
+
+/// Materializes recurring tasks whose next occurrence has passed.
+///
+/// Occurrences missed while the server was down collapse into a single task;
+/// the schedule then resumes from the current time.
+pub struct TaskTemplateScheduler<'a, C> {
+    container: &'a C,
+    poll_interval: Duration,
+}
+
+impl<'a, C: ContainerService + Sync> TaskTemplateScheduler<'a, C> {
+    pub fn new(container: &'a C) -> Self {
+        Self {
+            container,
+            poll_interval: Duration::from_secs(60),
+        }
+    }
+
+    pub async fn run(&self) {
+        info!(
+            "Starting task template scheduler with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.run_due(Utc::now()).await {
+                error!("Error running recurring task templates: {}", e);
+            }
+        }
+    }
+
+    /// Create tasks for every recurrence due at `now`, returning how many
+    /// were created
+    pub async fn run_due(&self, now: DateTime<Utc>) -> Result<usize, TaskTemplateError> {
+        let pool = &self.container.db().pool;
+        let due = TaskTemplateRecurrence::find_due(pool, now).await?;
+        if due.is_empty() {
+            debug!("No recurring tasks due");
+            return Ok(0);
+        }
+
+        let mut created = 0;
+        for recurrence in due {
+            match self.run_recurrence(&recurrence, now).await {
+                Ok(true) => created += 1,
+                Ok(false) => {}
+                Err(e) => error!(
+                    "Error running recurrence {} of template {}: {}",
+                    recurrence.id, recurrence.template_id, e
+                ),
+            }
+        }
+        Ok(created)
+    }
+
+    async fn run_recurrence(
+        &self,
+        recurrence: &TaskTemplateRecurrence,
+        now: DateTime<Utc>,
+    ) -> Result<bool, TaskTemplateError> {
+        let pool = &self.container.db().pool;
+        let Some(scheduled_at) = recurrence.next_run_at else {
+            return Ok(false);
+        };
+
+        let rule = match recurrence.rrule.parse::<RecurrenceRule>() {
+            Ok(rule) => rule,
+            Err(e) => {
+                // End the schedule rather than failing on every tick
+                TaskTemplateRecurrence::claim_run(pool, recurrence.id, scheduled_at, None).await?;
+                return Err(e.into());
+            }
+        };
+        let next_run_at = rule.next_after(recurrence.starts_at, now);
+        if !TaskTemplateRecurrence::claim_run(pool, recurrence.id, scheduled_at, next_run_at)
+            .await?
+        {
+            // Another scheduler ran this occurrence
+            return Ok(false);
+        }
+
+        let Some(template) = TaskTemplate::find_by_id(pool, recurrence.template_id).await? else {
+            return Ok(false);
+        };
+        let instantiated =
+            instantiate(pool, &template, &recurrence.variables.0, scheduled_at).await?;
+        let task = instantiated.task;
+        TaskTemplateRecurrence::set_last_task(pool, recurrence.id, task.id).await?;
+        info!(
+            "Created task {} from recurring template {}",
+            task.id, template.id
+        );
+
+        if let Some(executor_profile_id) = &recurrence.executor_profile_id {
+            if recurrence.repos.is_empty() {
+                warn!(
+                    "Recurrence {} has an executor profile but no repositories; not starting an attempt",
+                    recurrence.id
+                );
+            } else if let Err(e) = start_attempt(
+                self.container,
+                &task,
+                executor_profile_id.0.clone(),
+                &recurrence.repos.0,
+            )
+            .await
+            {
+                error!("Failed to start attempt for task {}: {}", task.id, e);
+            }
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use sqlx::types::Json;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn template(variables: &[(&str, &str)]) -> TaskTemplate {
+        TaskTemplate {
+            id: Uuid::new_v4(),
+            team_id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            name: "Dependency audit".to_string(),
+            title_template: "Dependency audit {{week}}".to_string(),
+            description_template: None,
+            priority: None,
+            assignee_id: None,
+            tag_ids: Vec::new(),
+            document_ids: Vec::new(),
+            sub_issues: Json(Vec::new()),
+            variables: Json(
+                variables
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            ),
+            created_by: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn renders_builtin_and_template_variables() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap();
+        let template = template(&[("component", "backend"), ("owner", "platform")]);
+        let overrides = HashMap::from([("owner".to_string(), "infra".to_string())]);
+        let variables = template_variables(&template, &overrides, now);
+
+        assert_eq!(
+            render(
+                "{{ component }} audit {{week}} ({{date}}) for {{owner}}",
+                &variables
+            )
+            .unwrap(),
+            "backend audit 2026-W10 (2026-03-02) for infra"
+        );
+        assert_eq!(
+            render("no placeholders", &variables).unwrap(),
+            "no placeholders"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_and_unclosed_placeholders() {
+        let variables = HashMap::new();
+        assert!(matches!(
+            render("Release {{version}}", &variables),
+            Err(TaskTemplateError::UnknownVariable(name)) if name == "version"
+        ));
+        assert!(matches!(
+            render("Release {{version", &variables),
+            Err(TaskTemplateError::UnclosedPlaceholder(_))
+        ));
+    }
+}
@@ -0,0 +1,402 @@
+//! The subset of RFC 5545 recurrence rules recurring tasks support.
+//!
+//! Supported parts are `FREQ` (`DAILY`, `WEEKLY`, `MONTHLY`), `INTERVAL`,
+//! `BYDAY` (with ordinals such as `1MO` or `-1FR` for monthly rules),
+//! `BYMONTHDAY`, `BYHOUR`, `BYMINUTE` and `UNTIL`. Rules are evaluated in UTC
+//! and anchored at the recurrence's start time, which also provides the
+//! default weekday, day of month and time of day.
+
+use std::str::FromStr;
+
+use chrono::{
+    DateTime, Datelike, Days, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike, Utc,
+    Weekday,
+};
+use thiserror::Error;
+
+/// How far ahead to look for the next occurrence, in intervals of a year
+const SEARCH_YEARS: u64 = 8;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum RRuleError {
+    #[error("FREQ is required")]
+    MissingFrequency,
+    #[error("Unsupported rule part: {0}")]
+    Unsupported(String),
+    #[error("Invalid value for {part}: {value}")]
+    InvalidValue { part: String, value: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+/// A `BYDAY` entry. `ordinal` selects the nth (or, when negative, nth from
+/// last) such weekday of the month and is only valid for monthly rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByDay {
+    pub ordinal: Option<i32>,
+    pub weekday: Weekday,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurrenceRule {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub by_day: Vec<ByDay>,
+    pub by_month_day: Vec<u32>,
+    pub by_hour: Option<u32>,
+    pub by_minute: Option<u32>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+fn invalid(part: &str, value: &str) -> RRuleError {
+    RRuleError::InvalidValue {
+        part: part.to_string(),
+        value: value.to_string(),
+    }
+}
+
+fn parse_number<T: FromStr + PartialOrd>(
+    part: &str,
+    value: &str,
+    range: std::ops::RangeInclusive<T>,
+) -> Result<T, RRuleError> {
+    value
+        .parse::<T>()
+        .ok()
+        .filter(|n| range.contains(n))
+        .ok_or_else(|| invalid(part, value))
+}
+
+fn parse_weekday(value: &str) -> Option<Weekday> {
+    Some(match value {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+fn parse_by_day(value: &str) -> Result<ByDay, RRuleError> {
+    let split = value
+        .len()
+        .checked_sub(2)
+        .filter(|_| value.is_ascii())
+        .ok_or_else(|| invalid("BYDAY", value))?;
+    let (ordinal, weekday) = value.split_at(split);
+    let weekday = parse_weekday(weekday).ok_or_else(|| invalid("BYDAY", value))?;
+    let ordinal = match ordinal {
+        "" => None,
+        ordinal => Some(
+            ordinal
+                .parse::<i32>()
+                .ok()
+                .filter(|n| *n != 0 && (-5..=5).contains(n))
+                .ok_or_else(|| invalid("BYDAY", value))?,
+        ),
+    };
+    Ok(ByDay { ordinal, weekday })
+}
+
+fn parse_until(value: &str) -> Result<DateTime<Utc>, RRuleError> {
+    if let Ok(at) = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%SZ") {
+        return Ok(Utc.from_utc_datetime(&at));
+    }
+    // A date-only UNTIL includes that whole day
+    NaiveDate::parse_from_str(value, "%Y%m%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(23, 59, 59))
+        .map(|at| Utc.from_utc_datetime(&at))
+        .ok_or_else(|| invalid("UNTIL", value))
+}
+
+impl FromStr for RecurrenceRule {
+    type Err = RRuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let s = s.strip_prefix("RRULE:").unwrap_or(s);
+
+        let mut frequency = None;
+        let mut rule = RecurrenceRule {
+            frequency: Frequency::Daily,
+            interval: 1,
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_hour: None,
+            by_minute: None,
+            until: None,
+        };
+
+        for part in s.split(';').filter(|part| !part.is_empty()) {
+            let (name, value) = part
+                .split_once('=')
+                .ok_or_else(|| RRuleError::Unsupported(part.to_string()))?;
+            let name = name.trim().to_ascii_uppercase();
+            let value = value.trim().to_ascii_uppercase();
+            match name.as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        _ => return Err(invalid("FREQ", &value)),
+                    })
+                }
+                "INTERVAL" => rule.interval = parse_number(&name, &value, 1..=366)?,
+                "BYDAY" => {
+                    rule.by_day = value
+                        .split(',')
+                        .map(parse_by_day)
+                        .collect::<Result<_, _>>()?
+                }
+                "BYMONTHDAY" => {
+                    rule.by_month_day = value
+                        .split(',')
+                        .map(|day| parse_number(&name, day, 1..=31))
+                        .collect::<Result<_, _>>()?
+                }
+                "BYHOUR" => rule.by_hour = Some(parse_number(&name, &value, 0..=23)?),
+                "BYMINUTE" => rule.by_minute = Some(parse_number(&name, &value, 0..=59)?),
+                "UNTIL" => rule.until = Some(parse_until(&value)?),
+                _ => return Err(RRuleError::Unsupported(name)),
+            }
+        }
+
+        rule.frequency = frequency.ok_or(RRuleError::MissingFrequency)?;
+        if rule.frequency != Frequency::Monthly {
+            if rule.by_day.iter().any(|day| day.ordinal.is_some()) {
+                return Err(RRuleError::Unsupported(
+                    "BYDAY ordinals outside FREQ=MONTHLY".to_string(),
+                ));
+            }
+            if !rule.by_month_day.is_empty() {
+                return Err(RRuleError::Unsupported(
+                    "BYMONTHDAY outside FREQ=MONTHLY".to_string(),
+                ));
+            }
+        }
+        Ok(rule)
+    }
+}
+
+fn days_in_month(date: NaiveDate) -> u32 {
+    let first = date.with_day(1).expect("day 1 exists in every month");
+    let next = first + Months::new(1);
+    (next - first).num_days() as u32
+}
+
+impl RecurrenceRule {
+    /// The first occurrence strictly after `after`, or `None` when the rule
+    /// has ended
+    pub fn next_after(
+        &self,
+        starts_at: DateTime<Utc>,
+        after: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        let time = NaiveTime::from_hms_opt(
+            self.by_hour.unwrap_or(starts_at.hour()),
+            self.by_minute.unwrap_or(starts_at.minute()),
+            0,
+        )?;
+        let start = starts_at.date_naive();
+        let from = start.max(after.date_naive());
+        let search_days = 366 * SEARCH_YEARS * u64::from(self.interval);
+
+        for offset in 0..search_days {
+            let date = from.checked_add_days(Days::new(offset))?;
+            let at = Utc.from_utc_datetime(&date.and_time(time));
+            if let Some(until) = self.until
+                && at > until
+            {
+                return None;
+            }
+            if at <= after || at < starts_at || !self.matches(date, start) {
+                continue;
+            }
+            return Some(at);
+        }
+        None
+    }
+
+    fn matches(&self, date: NaiveDate, start: NaiveDate) -> bool {
+        let interval = i64::from(self.interval);
+        match self.frequency {
+            Frequency::Daily => {
+                (date - start).num_days() % interval == 0
+                    && (self.by_day.is_empty()
+                        || self.by_day.iter().any(|day| day.weekday == date.weekday()))
+            }
+            Frequency::Weekly => {
+                let week_of = |d: NaiveDate| d.week(Weekday::Mon).first_day();
+                let weeks = (week_of(date) - week_of(start)).num_days() / 7;
+                let weekday_matches = if self.by_day.is_empty() {
+                    date.weekday() == start.weekday()
+                } else {
+                    self.by_day.iter().any(|day| day.weekday == date.weekday())
+                };
+                weeks % interval == 0 && weekday_matches
+            }
+            Frequency::Monthly => {
+                let months = i64::from(date.year() - start.year()) * 12 + i64::from(date.month())
+                    - i64::from(start.month());
+                if months % interval != 0 {
+                    return false;
+                }
+                let month_day_matches = if self.by_month_day.is_empty() {
+                    // Without BYDAY, a monthly rule repeats on the start's day
+                    !self.by_day.is_empty() || date.day() == start.day()
+                } else {
+                    self.by_month_day.contains(&date.day())
+                };
+                let weekday_matches = self.by_day.is_empty()
+                    || self.by_day.iter().any(|day| {
+                        day.weekday == date.weekday()
+                            && match day.ordinal {
+                                None => true,
+                                Some(n) if n > 0 => ((date.day() - 1) / 7 + 1) as i32 == n,
+                                Some(n) => {
+                                    ((days_in_month(date) - date.day()) / 7 + 1) as i32 == -n
+                                }
+                            }
+                    });
+                month_day_matches && weekday_matches
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn rule(s: &str) -> RecurrenceRule {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_supported_parts() {
+        let parsed = rule("RRULE:FREQ=MONTHLY;INTERVAL=2;BYDAY=1MO,-1FR;BYHOUR=9;BYMINUTE=30");
+        assert_eq!(parsed.frequency, Frequency::Monthly);
+        assert_eq!(parsed.interval, 2);
+        assert_eq!(
+            parsed.by_day,
+            [
+                ByDay {
+                    ordinal: Some(1),
+                    weekday: Weekday::Mon
+                },
+                ByDay {
+                    ordinal: Some(-1),
+                    weekday: Weekday::Fri
+                },
+            ]
+        );
+        assert_eq!((parsed.by_hour, parsed.by_minute), (Some(9), Some(30)));
+    }
+
+    #[test]
+    fn rejects_unsupported_rules() {
+        assert_eq!(
+            "INTERVAL=2".parse::<RecurrenceRule>(),
+            Err(RRuleError::MissingFrequency)
+        );
+        assert_eq!(
+            "FREQ=YEARLY".parse::<RecurrenceRule>(),
+            Err(invalid("FREQ", "YEARLY"))
+        );
+        assert_eq!(
+            "FREQ=DAILY;COUNT=3".parse::<RecurrenceRule>(),
+            Err(RRuleError::Unsupported("COUNT".to_string()))
+        );
+        assert!("FREQ=WEEKLY;BYDAY=1MO".parse::<RecurrenceRule>().is_err());
+        assert!("FREQ=DAILY;BYHOUR=24".parse::<RecurrenceRule>().is_err());
+    }
+
+    #[test]
+    fn daily_rules_honour_interval_and_weekdays() {
+        let start = at("2026-03-02T09:00:00Z"); // Monday
+        let every_other_day = rule("FREQ=DAILY;INTERVAL=2");
+        assert_eq!(
+            every_other_day.next_after(start, start),
+            Some(at("2026-03-04T09:00:00Z"))
+        );
+
+        let weekdays = rule("FREQ=DAILY;BYDAY=MO,TU,WE,TH,FR");
+        assert_eq!(
+            weekdays.next_after(start, at("2026-03-06T10:00:00Z")),
+            Some(at("2026-03-09T09:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn weekly_rules_default_to_the_start_weekday() {
+        let start = at("2026-03-04T14:15:00Z"); // Wednesday
+        assert_eq!(
+            rule("FREQ=WEEKLY").next_after(start, start),
+            Some(at("2026-03-11T14:15:00Z"))
+        );
+        assert_eq!(
+            rule("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO;BYHOUR=9;BYMINUTE=0")
+                .next_after(start, at("2026-03-10T00:00:00Z")),
+            Some(at("2026-03-16T09:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn first_occurrence_can_be_the_start_itself() {
+        let start = at("2026-03-02T09:00:00Z");
+        assert_eq!(
+            rule("FREQ=WEEKLY").next_after(start, at("2026-03-01T00:00:00Z")),
+            Some(start)
+        );
+    }
+
+    #[test]
+    fn monthly_rules_skip_months_without_the_day() {
+        let start = at("2026-01-31T08:00:00Z");
+        assert_eq!(
+            rule("FREQ=MONTHLY").next_after(start, start),
+            Some(at("2026-03-31T08:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn monthly_rules_support_nth_weekday() {
+        let start = at("2026-01-01T09:00:00Z");
+        let first_monday = rule("FREQ=MONTHLY;BYDAY=1MO");
+        assert_eq!(
+            first_monday.next_after(start, at("2026-02-03T00:00:00Z")),
+            Some(at("2026-03-02T09:00:00Z"))
+        );
+
+        let last_friday = rule("FREQ=MONTHLY;BYDAY=-1FR");
+        assert_eq!(
+            last_friday.next_after(start, start),
+            Some(at("2026-01-30T09:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn rules_end_at_until() {
+        let start = at("2026-03-02T09:00:00Z");
+        let until = rule("FREQ=DAILY;UNTIL=20260303");
+        assert_eq!(
+            until.next_after(start, start),
+            Some(at("2026-03-03T09:00:00Z"))
+        );
+        assert_eq!(until.next_after(start, at("2026-03-03T09:00:00Z")), None);
+    }
+}
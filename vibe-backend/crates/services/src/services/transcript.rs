@@ -0,0 +1,248 @@
+//! Durable transcripts of coding agent executions.
+//!
+//! The normalized conversation only exists in the in-memory `MsgStore` while
+//! a process runs. When it ends the conversation is rebuilt from the store's
+//! patches and stored gzip-compressed, so it can be read and exported after
+//! the store is gone.
+
+pub mod export;
+
+use std::{
+    collections::BTreeMap,
+    io::{Read, Write},
+};
+
+use chrono::{Duration, Utc};
+use db::models::{
+    coding_agent_turn::CodingAgentTurn,
+    execution_process::ExecutionProcess,
+    execution_process_transcript::{ExecutionProcessTranscript, UpsertExecutionProcessTranscript},
+};
+use executors::{
+    actions::ExecutorActionType,
+    logs::{NormalizedConversation, utils::patch::extract_normalized_entry_from_patch},
+};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use sqlx::PgPool;
+use thiserror::Error;
+use utils::log_msg::LogMsg;
+
+use crate::services::{
+    container::{ContainerError, ContainerService},
+    git::GitServiceError,
+};
+
+#[derive(Debug, Error)]
+pub enum TranscriptError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("Background task failed: {0}")]
+    Join(#[from] tokio::task::JoinError),
+    #[error("Task for workspace not found")]
+    TaskNotFound,
+}
+
+/// Executor of a coding agent process, `None` for scripts and dev servers
+pub fn executor_type(process: &ExecutionProcess) -> Option<String> {
+    let action = process.executor_action().ok()?;
+    match action.typ() {
+        ExecutorActionType::CodingAgentInitialRequest(request) => {
+            Some(request.executor_profile_id.executor.to_string())
+        }
+        ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+            Some(request.executor_profile_id.executor.to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Rebuild the normalized conversation from a process's log history. Later
+/// patches for an entry replace earlier ones.
+pub fn conversation_from_history(
+    history: &[LogMsg],
+    executor_type: String,
+    prompt: Option<String>,
+    summary: Option<String>,
+) -> NormalizedConversation {
+    let mut entries = BTreeMap::new();
+    let mut session_id = None;
+    for msg in history {
+        match msg {
+            LogMsg::JsonPatch(patch) => {
+                if let Some((index, entry)) = extract_normalized_entry_from_patch(patch) {
+                    entries.insert(index, entry);
+                }
+            }
+            LogMsg::SessionId(id) => session_id = Some(id.clone()),
+            _ => {}
+        }
+    }
+    NormalizedConversation {
+        entries: entries.into_values().collect(),
+        session_id,
+        executor_type,
+        prompt,
+        summary,
+    }
+}
+
+/// Gzip-compressed JSON of `conversation`, and its uncompressed size
+pub fn encode(conversation: &NormalizedConversation) -> Result<(Vec<u8>, usize), TranscriptError> {
+    let json = serde_json::to_vec(conversation)?;
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&json)?;
+    Ok((encoder.finish()?, json.len()))
+}
+
+pub fn decode(data: &[u8]) -> Result<NormalizedConversation, TranscriptError> {
+    let mut json = Vec::new();
+    GzDecoder::new(data).read_to_end(&mut json)?;
+    Ok(serde_json::from_slice(&json)?)
+}
+
+/// Store the conversation of a finished coding agent process. Other
+/// processes have no normalized conversation and are skipped.
+pub async fn persist(
+    pool: &PgPool,
+    process: &ExecutionProcess,
+    history: &[LogMsg],
+) -> Result<(), TranscriptError> {
+    let Some(executor_type) = executor_type(process) else {
+        return Ok(());
+    };
+    let turn = CodingAgentTurn::find_by_execution_process_id(pool, process.id).await?;
+    let (prompt, summary) = turn
+        .map(|turn| (turn.prompt, turn.summary))
+        .unwrap_or_default();
+    let conversation = conversation_from_history(history, executor_type, prompt, summary);
+    if conversation.entries.is_empty() {
+        return Ok(());
+    }
+
+    let (data, byte_size) = encode(&conversation)?;
+    ExecutionProcessTranscript::upsert(
+        pool,
+        process.id,
+        &UpsertExecutionProcessTranscript {
+            executor_type: &conversation.executor_type,
+            entry_count: i32::try_from(conversation.entries.len()).unwrap_or(i32::MAX),
+            byte_size: byte_size as i64,
+            data: &data,
+        },
+    )
+    .await?;
+    tracing::debug!(
+        "Stored transcript for execution {}: {} entries, {} -> {} bytes",
+        process.id,
+        conversation.entries.len(),
+        byte_size,
+        data.len()
+    );
+    Ok(())
+}
+
+/// The conversation of `process`: the live one while it runs, the stored one
+/// afterwards. `None` for processes without a conversation, or whose
+/// transcript has expired.
+pub async fn load<C: ContainerService + Sync>(
+    container: &C,
+    process: &ExecutionProcess,
+) -> Result<Option<NormalizedConversation>, TranscriptError> {
+    let pool = &container.db().pool;
+    if let Some(store) = container.get_msg_store_by_id(&process.id).await {
+        let Some(executor_type) = executor_type(process) else {
+            return Ok(None);
+        };
+        let turn = CodingAgentTurn::find_by_execution_process_id(pool, process.id).await?;
+        let (prompt, summary) = turn
+            .map(|turn| (turn.prompt, turn.summary))
+            .unwrap_or_default();
+        return Ok(Some(conversation_from_history(
+            &store.get_history(),
+            executor_type,
+            prompt,
+            summary,
+        )));
+    }
+
+    match ExecutionProcessTranscript::find_by_execution_process_id(pool, process.id).await? {
+        Some(transcript) => Ok(Some(decode(&transcript.data)?)),
+        None => Ok(None),
+    }
+}
+
+/// Delete transcripts older than `retention_days`
+pub async fn purge_expired(pool: &PgPool, retention_days: u32) -> Result<u64, TranscriptError> {
+    let cutoff = Utc::now() - Duration::days(i64::from(retention_days));
+    Ok(ExecutionProcessTranscript::delete_created_before(pool, cutoff).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::logs::{NormalizedEntry, NormalizedEntryType, utils::patch::ConversationPatch};
+
+    use super::*;
+
+    fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_conversation_from_history_applies_replacements() {
+        let history = vec![
+            LogMsg::SessionId("agent-session".to_string()),
+            LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(
+                0,
+                entry(NormalizedEntryType::UserMessage, "fix the build"),
+            )),
+            LogMsg::Stdout("raw output".to_string()),
+            LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(
+                1,
+                entry(NormalizedEntryType::AssistantMessage, "Working"),
+            )),
+            LogMsg::JsonPatch(ConversationPatch::replace(
+                1,
+                entry(NormalizedEntryType::AssistantMessage, "Fixed the import"),
+            )),
+        ];
+        let conversation =
+            conversation_from_history(&history, "CLAUDE_CODE".to_string(), None, None);
+
+        assert_eq!(conversation.session_id.as_deref(), Some("agent-session"));
+        assert_eq!(conversation.entries.len(), 2);
+        assert_eq!(conversation.entries[1].content, "Fixed the import");
+    }
+
+    #[test]
+    fn test_encode_round_trip() {
+        let conversation = NormalizedConversation {
+            entries: vec![entry(
+                NormalizedEntryType::AssistantMessage,
+                &"a".repeat(4096),
+            )],
+            session_id: None,
+            executor_type: "CODEX".to_string(),
+            prompt: Some("prompt".to_string()),
+            summary: None,
+        };
+        let (data, byte_size) = encode(&conversation).unwrap();
+        assert!(data.len() < byte_size);
+
+        let decoded = decode(&data).unwrap();
+        assert_eq!(decoded.entries[0].content, conversation.entries[0].content);
+        assert_eq!(decoded.prompt.as_deref(), Some("prompt"));
+    }
+}
@@ -0,0 +1,750 @@
+//! Export of a whole task attempt (every session, tool call, approval and the
+//! resulting diff) as Markdown, JSONL or a self-contained HTML page.
+
+use std::{fmt::Write as _, path::PathBuf};
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    session::Session,
+    tool_approval::ToolApproval,
+    workspace::Workspace,
+    workspace_repo::{RepoWithTargetBranch, WorkspaceRepo},
+};
+use executors::logs::{
+    ActionType, FileChange, NormalizedConversation, NormalizedEntry, NormalizedEntryType,
+    ToolResultValueType, ToolStatus,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use ts_rs::TS;
+use utils::{
+    diff::{Diff, DiffChangeKind, create_unified_diff},
+    text::truncate_to_char_boundary,
+};
+use uuid::Uuid;
+
+use super::{TranscriptError, load};
+use crate::services::{container::ContainerService, git::DiffTarget};
+
+/// Tool output and file contents longer than this are cut in Markdown and
+/// HTML exports; JSONL keeps everything
+const MAX_BLOCK_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptFormat {
+    #[default]
+    Markdown,
+    Jsonl,
+    Html,
+}
+
+impl TranscriptFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Markdown => "text/markdown; charset=utf-8",
+            Self::Jsonl => "application/x-ndjson",
+            Self::Html => "text/html; charset=utf-8",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Jsonl => "jsonl",
+            Self::Html => "html",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AttemptTranscript {
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub task_description: Option<String>,
+    pub workspace_id: Uuid,
+    pub branch: String,
+    pub exported_at: DateTime<Utc>,
+    /// Oldest first
+    pub sessions: Vec<SessionTranscript>,
+    /// The attempt's changes against each repository's target branch
+    pub changes: Vec<FileDiff>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionTranscript {
+    pub session_id: Uuid,
+    pub executor: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub processes: Vec<ProcessTranscript>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessTranscript {
+    pub execution_process_id: Uuid,
+    pub run_reason: ExecutionProcessRunReason,
+    pub status: ExecutionProcessStatus,
+    pub exit_code: Option<i32>,
+    /// Rolled back or replaced by a retry
+    pub dropped: bool,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// `None` for scripts, and for agent runs whose transcript expired
+    pub conversation: Option<NormalizedConversation>,
+    pub approvals: Vec<ToolApproval>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileDiff {
+    pub repo: String,
+    pub path: String,
+    pub change: DiffChangeKind,
+    pub additions: usize,
+    pub deletions: usize,
+    /// `None` when the contents were too large to diff
+    pub unified_diff: Option<String>,
+}
+
+/// Collect everything recorded for `workspace`
+pub async fn build_attempt_transcript<C: ContainerService + Sync>(
+    container: &C,
+    workspace: &Workspace,
+) -> Result<AttemptTranscript, TranscriptError> {
+    let pool = &container.db().pool;
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(TranscriptError::TaskNotFound)?;
+
+    let mut sessions = Session::find_by_workspace_id(pool, workspace.id).await?;
+    sessions.sort_by_key(|session| session.created_at);
+    let mut session_transcripts = Vec::with_capacity(sessions.len());
+    for session in sessions {
+        let mut processes = ExecutionProcess::find_by_session_id(pool, session.id, true).await?;
+        processes.sort_by_key(|process| process.created_at);
+        let mut process_transcripts = Vec::with_capacity(processes.len());
+        for process in processes {
+            let conversation = load(container, &process).await?;
+            let approvals = ToolApproval::find_by_execution_process_id(pool, process.id).await?;
+            process_transcripts.push(ProcessTranscript {
+                execution_process_id: process.id,
+                run_reason: process.run_reason,
+                status: process.status,
+                exit_code: process.exit_code,
+                dropped: process.dropped,
+                started_at: process.started_at,
+                completed_at: process.completed_at,
+                conversation,
+                approvals,
+            });
+        }
+        session_transcripts.push(SessionTranscript {
+            session_id: session.id,
+            executor: session.executor,
+            created_at: session.created_at,
+            processes: process_transcripts,
+        });
+    }
+
+    Ok(AttemptTranscript {
+        task_id: task.id,
+        task_title: task.title,
+        task_description: task.description,
+        workspace_id: workspace.id,
+        branch: workspace.branch.clone(),
+        exported_at: Utc::now(),
+        sessions: session_transcripts,
+        changes: attempt_changes(container, workspace).await?,
+    })
+}
+
+/// Diff against each repository's target branch: in the worktree while it
+/// exists so uncommitted work is included, otherwise on the branch
+async fn attempt_changes<C: ContainerService + Sync>(
+    container: &C,
+    workspace: &Workspace,
+) -> Result<Vec<FileDiff>, TranscriptError> {
+    let repos = WorkspaceRepo::find_repos_with_target_branch_for_workspace(
+        &container.db().pool,
+        workspace.id,
+    )
+    .await?;
+    let workspace_dir = workspace
+        .container_ref
+        .as_ref()
+        .map(PathBuf::from)
+        .filter(|dir| dir.exists());
+
+    let mut changes = Vec::new();
+    for RepoWithTargetBranch {
+        repo,
+        target_branch,
+    } in repos
+    {
+        let git = container.git().clone();
+        let branch = workspace.branch.clone();
+        let worktree_path = workspace_dir.as_ref().map(|dir| dir.join(&repo.name));
+        let repo_path = repo.path.clone();
+        let diffs = tokio::task::spawn_blocking(move || match worktree_path {
+            Some(worktree_path) => {
+                let base_commit = git.get_base_commit(&repo_path, &branch, &target_branch)?;
+                git.get_diffs(
+                    DiffTarget::Worktree {
+                        worktree_path: &worktree_path,
+                        base_commit: &base_commit,
+                    },
+                    None,
+                )
+            }
+            None => git.get_diffs(
+                DiffTarget::Branch {
+                    repo_path: &repo_path,
+                    branch_name: &branch,
+                    base_branch: &target_branch,
+                },
+                None,
+            ),
+        })
+        .await?;
+        match diffs {
+            Ok(diffs) => {
+                changes.extend(diffs.iter().filter_map(|diff| file_diff(&repo.name, diff)))
+            }
+            Err(e) => tracing::warn!(
+                "Failed to diff repo {} for transcript of workspace {}: {}",
+                repo.name,
+                workspace.id,
+                e
+            ),
+        }
+    }
+    Ok(changes)
+}
+
+fn file_diff(repo: &str, diff: &Diff) -> Option<FileDiff> {
+    let path = diff.new_path.as_ref().or(diff.old_path.as_ref())?;
+    let unified_diff = (!diff.content_omitted).then(|| {
+        create_unified_diff(
+            path,
+            diff.old_content.as_deref().unwrap_or_default(),
+            diff.new_content.as_deref().unwrap_or_default(),
+        )
+    });
+    Some(FileDiff {
+        repo: repo.to_string(),
+        path: path.clone(),
+        change: diff.change.clone(),
+        additions: diff.additions.unwrap_or(0),
+        deletions: diff.deletions.unwrap_or(0),
+        unified_diff,
+    })
+}
+
+pub fn render(transcript: &AttemptTranscript, format: TranscriptFormat) -> String {
+    match format {
+        TranscriptFormat::Markdown => render_markdown(&transcript_blocks(transcript)),
+        TranscriptFormat::Jsonl => render_jsonl(transcript),
+        TranscriptFormat::Html => {
+            render_html(&transcript.task_title, &transcript_blocks(transcript))
+        }
+    }
+}
+
+/// Format-independent document structure shared by Markdown and HTML
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Block {
+    Heading(usize, String),
+    /// Markdown written by the agent or the user
+    Text(String),
+    /// A short line of details
+    Meta(String),
+    Code {
+        language: &'static str,
+        text: String,
+    },
+}
+
+fn run_reason_label(run_reason: &ExecutionProcessRunReason) -> &'static str {
+    match run_reason {
+        ExecutionProcessRunReason::SetupScript => "Setup script",
+        ExecutionProcessRunReason::CleanupScript => "Cleanup script",
+        ExecutionProcessRunReason::CodingAgent => "Coding agent",
+        ExecutionProcessRunReason::DevServer => "Dev server",
+    }
+}
+
+fn status_label(status: &ExecutionProcessStatus) -> &'static str {
+    match status {
+        ExecutionProcessStatus::Running => "running",
+        ExecutionProcessStatus::Completed => "completed",
+        ExecutionProcessStatus::Failed => "failed",
+        ExecutionProcessStatus::Killed => "killed",
+    }
+}
+
+fn tool_status_label(status: &ToolStatus) -> String {
+    match status {
+        ToolStatus::Created => "started".to_string(),
+        ToolStatus::Success => "succeeded".to_string(),
+        ToolStatus::Failed => "failed".to_string(),
+        ToolStatus::Denied {
+            reason: Some(reason),
+        } => format!("denied: {reason}"),
+        ToolStatus::Denied { reason: None } => "denied".to_string(),
+        ToolStatus::PendingApproval { .. } => "awaiting approval".to_string(),
+        ToolStatus::TimedOut => "timed out".to_string(),
+    }
+}
+
+fn truncated(text: &str) -> String {
+    if text.len() <= MAX_BLOCK_BYTES {
+        return text.to_string();
+    }
+    format!(
+        "{}\n[{} bytes truncated]",
+        truncate_to_char_boundary(text, MAX_BLOCK_BYTES),
+        text.len() - MAX_BLOCK_BYTES
+    )
+}
+
+fn transcript_blocks(transcript: &AttemptTranscript) -> Vec<Block> {
+    let mut blocks = vec![
+        Block::Heading(1, transcript.task_title.clone()),
+        Block::Meta(format!(
+            "Task {} · branch {} · exported {}",
+            transcript.task_id,
+            transcript.branch,
+            transcript.exported_at.to_rfc3339()
+        )),
+    ];
+    if let Some(description) = transcript
+        .task_description
+        .as_ref()
+        .filter(|description| !description.trim().is_empty())
+    {
+        blocks.push(Block::Text(description.clone()));
+    }
+
+    for (index, session) in transcript.sessions.iter().enumerate() {
+        blocks.push(Block::Heading(2, format!("Session {}", index + 1)));
+        blocks.push(Block::Meta(format!(
+            "{} · started {}",
+            session.executor.as_deref().unwrap_or("unknown executor"),
+            session.created_at.to_rfc3339()
+        )));
+        for process in &session.processes {
+            process_blocks(process, &mut blocks);
+        }
+    }
+
+    if !transcript.changes.is_empty() {
+        blocks.push(Block::Heading(2, "Changes".to_string()));
+        for change in &transcript.changes {
+            blocks.push(Block::Heading(
+                3,
+                format!(
+                    "{}/{} (+{} −{})",
+                    change.repo, change.path, change.additions, change.deletions
+                ),
+            ));
+            match &change.unified_diff {
+                Some(diff) => blocks.push(Block::Code {
+                    language: "diff",
+                    text: truncated(diff),
+                }),
+                None => blocks.push(Block::Meta("Contents omitted".to_string())),
+            }
+        }
+    }
+    blocks
+}
+
+fn process_blocks(process: &ProcessTranscript, blocks: &mut Vec<Block>) {
+    let mut heading = format!(
+        "{} · {}",
+        run_reason_label(&process.run_reason),
+        status_label(&process.status)
+    );
+    if process.dropped {
+        heading.push_str(" · rolled back");
+    }
+    blocks.push(Block::Heading(3, heading));
+    let mut meta = format!("Started {}", process.started_at.to_rfc3339());
+    if let Some(completed_at) = process.completed_at {
+        let _ = write!(meta, " · finished {}", completed_at.to_rfc3339());
+    }
+    if let Some(exit_code) = process.exit_code {
+        let _ = write!(meta, " · exit code {exit_code}");
+    }
+    blocks.push(Block::Meta(meta));
+
+    match &process.conversation {
+        Some(conversation) => {
+            let has_user_message = conversation
+                .entries
+                .iter()
+                .any(|entry| matches!(entry.entry_type, NormalizedEntryType::UserMessage));
+            if !has_user_message && let Some(prompt) = &conversation.prompt {
+                blocks.push(Block::Heading(4, "User".to_string()));
+                blocks.push(Block::Text(prompt.clone()));
+            }
+            for entry in &conversation.entries {
+                entry_blocks(entry, blocks);
+            }
+        }
+        None if process.run_reason == ExecutionProcessRunReason::CodingAgent => {
+            blocks.push(Block::Meta("Transcript not available".to_string()));
+        }
+        None => {}
+    }
+
+    if !process.approvals.is_empty() {
+        blocks.push(Block::Heading(4, "Approvals".to_string()));
+        for approval in &process.approvals {
+            let mut line = format!("{}: {}", approval.tool_name, approval.status);
+            if let Some(decided_by) = &approval.decided_by {
+                let _ = write!(line, " by {decided_by}");
+            }
+            if let Some(reason) = &approval.reason {
+                let _ = write!(line, " ({reason})");
+            }
+            blocks.push(Block::Meta(line));
+        }
+    }
+}
+
+fn entry_blocks(entry: &NormalizedEntry, blocks: &mut Vec<Block>) {
+    match &entry.entry_type {
+        NormalizedEntryType::UserMessage => {
+            blocks.push(Block::Heading(4, "User".to_string()));
+            blocks.push(Block::Text(entry.content.clone()));
+        }
+        NormalizedEntryType::AssistantMessage => {
+            blocks.push(Block::Heading(4, "Assistant".to_string()));
+            blocks.push(Block::Text(entry.content.clone()));
+        }
+        NormalizedEntryType::Thinking => {
+            blocks.push(Block::Heading(4, "Thinking".to_string()));
+            blocks.push(Block::Text(entry.content.clone()));
+        }
+        NormalizedEntryType::UserFeedback { denied_tool } => {
+            blocks.push(Block::Heading(
+                4,
+                format!("Feedback on denied {denied_tool}"),
+            ));
+            blocks.push(Block::Text(entry.content.clone()));
+        }
+        NormalizedEntryType::SystemMessage => blocks.push(Block::Meta(entry.content.clone())),
+        NormalizedEntryType::ErrorMessage { .. } => {
+            blocks.push(Block::Heading(4, "Error".to_string()));
+            blocks.push(Block::Code {
+                language: "text",
+                text: truncated(&entry.content),
+            });
+        }
+        NormalizedEntryType::ToolUse {
+            tool_name,
+            action_type,
+            status,
+        } => {
+            blocks.push(Block::Heading(
+                4,
+                format!("Tool: {tool_name} ({})", tool_status_label(status)),
+            ));
+            if !entry.content.trim().is_empty() {
+                blocks.push(Block::Meta(entry.content.clone()));
+            }
+            action_blocks(action_type, blocks);
+        }
+        NormalizedEntryType::Loading | NormalizedEntryType::NextAction { .. } => {}
+    }
+}
+
+fn action_blocks(action: &ActionType, blocks: &mut Vec<Block>) {
+    match action {
+        ActionType::CommandRun { command, result } => {
+            blocks.push(Block::Code {
+                language: "sh",
+                text: command.clone(),
+            });
+            if let Some(output) = result
+                .as_ref()
+                .and_then(|result| result.output.as_ref())
+                .filter(|output| !output.is_empty())
+            {
+                blocks.push(Block::Code {
+                    language: "text",
+                    text: truncated(output),
+                });
+            }
+        }
+        ActionType::FileEdit { path, changes } => {
+            for change in changes {
+                match change {
+                    FileChange::Edit { unified_diff, .. } => blocks.push(Block::Code {
+                        language: "diff",
+                        text: truncated(unified_diff),
+                    }),
+                    FileChange::Write { content } => {
+                        blocks.push(Block::Meta(format!("Wrote {path}")));
+                        blocks.push(Block::Code {
+                            language: "text",
+                            text: truncated(content),
+                        });
+                    }
+                    FileChange::Delete => blocks.push(Block::Meta(format!("Deleted {path}"))),
+                    FileChange::Rename { new_path } => {
+                        blocks.push(Block::Meta(format!("Renamed {path} to {new_path}")))
+                    }
+                }
+            }
+        }
+        ActionType::Tool {
+            arguments, result, ..
+        } => {
+            if let Some(arguments) = arguments {
+                blocks.push(Block::Code {
+                    language: "json",
+                    text: truncated(&serde_json::to_string_pretty(arguments).unwrap_or_default()),
+                });
+            }
+            if let Some(result) = result {
+                match (&result.r#type, &result.value) {
+                    (ToolResultValueType::Markdown, serde_json::Value::String(markdown)) => {
+                        blocks.push(Block::Text(truncated(markdown)))
+                    }
+                    (_, value) => blocks.push(Block::Code {
+                        language: "json",
+                        text: truncated(&serde_json::to_string_pretty(value).unwrap_or_default()),
+                    }),
+                }
+            }
+        }
+        ActionType::PlanPresentation { plan } => blocks.push(Block::Text(plan.clone())),
+        ActionType::TodoManagement { todos, .. } => {
+            let list = todos
+                .iter()
+                .map(|todo| {
+                    let done = if todo.status == "completed" { "x" } else { " " };
+                    format!("- [{done}] {}", todo.content)
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            blocks.push(Block::Text(list));
+        }
+        ActionType::FileRead { .. }
+        | ActionType::Search { .. }
+        | ActionType::WebFetch { .. }
+        | ActionType::TaskCreate { .. }
+        | ActionType::Other { .. } => {}
+    }
+}
+
+/// A code fence longer than any backtick run in `text`
+fn code_fence(text: &str) -> String {
+    let mut longest = 0;
+    let mut run = 0;
+    for c in text.chars() {
+        if c == '`' {
+            run += 1;
+            longest = longest.max(run);
+        } else {
+            run = 0;
+        }
+    }
+    "`".repeat(longest.max(2) + 1)
+}
+
+fn render_markdown(blocks: &[Block]) -> String {
+    let mut out = String::new();
+    for block in blocks {
+        match block {
+            Block::Heading(level, text) => {
+                let _ = writeln!(out, "{} {text}\n", "#".repeat(*level));
+            }
+            Block::Text(text) => {
+                let _ = writeln!(out, "{}\n", text.trim_end());
+            }
+            Block::Meta(text) => {
+                let _ = writeln!(out, "> {}\n", text.trim_end().replace('\n', "\n> "));
+            }
+            Block::Code { language, text } => {
+                let fence = code_fence(text);
+                let _ = writeln!(out, "{fence}{language}\n{}\n{fence}\n", text.trim_end());
+            }
+        }
+    }
+    out
+}
+
+fn render_jsonl(transcript: &AttemptTranscript) -> String {
+    let mut records = vec![json!({
+        "type": "attempt",
+        "task_id": transcript.task_id,
+        "task_title": transcript.task_title,
+        "task_description": transcript.task_description,
+        "workspace_id": transcript.workspace_id,
+        "branch": transcript.branch,
+        "exported_at": transcript.exported_at,
+    })];
+    for session in &transcript.sessions {
+        records.push(json!({
+            "type": "session",
+            "session_id": session.session_id,
+            "executor": session.executor,
+            "created_at": session.created_at,
+        }));
+        for process in &session.processes {
+            let conversation = process.conversation.as_ref();
+            records.push(json!({
+                "type": "execution_process",
+                "session_id": session.session_id,
+                "execution_process_id": process.execution_process_id,
+                "run_reason": process.run_reason,
+                "status": process.status,
+                "exit_code": process.exit_code,
+                "dropped": process.dropped,
+                "started_at": process.started_at,
+                "completed_at": process.completed_at,
+                "executor_type": conversation.map(|c| &c.executor_type),
+                "agent_session_id": conversation.and_then(|c| c.session_id.as_ref()),
+                "prompt": conversation.and_then(|c| c.prompt.as_ref()),
+                "summary": conversation.and_then(|c| c.summary.as_ref()),
+            }));
+            for (index, entry) in conversation
+                .map(|c| c.entries.as_slice())
+                .unwrap_or_default()
+                .iter()
+                .enumerate()
+            {
+                records.push(json!({
+                    "type": "entry",
+                    "execution_process_id": process.execution_process_id,
+                    "index": index,
+                    "entry": entry,
+                }));
+            }
+            for approval in &process.approvals {
+                records.push(json!({
+                    "type": "approval",
+                    "approval": approval,
+                }));
+            }
+        }
+    }
+    for change in &transcript.changes {
+        records.push(json!({
+            "type": "diff",
+            "repo": change.repo,
+            "path": change.path,
+            "change": change.change,
+            "additions": change.additions,
+            "deletions": change.deletions,
+            "unified_diff": change.unified_diff,
+        }));
+    }
+
+    let mut out = String::new();
+    for record in records {
+        let _ = writeln!(out, "{record}");
+    }
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+const HTML_STYLE: &str = "\
+body{font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',sans-serif;max-width:960px;\
+margin:2rem auto;padding:0 1rem;color:#1f2328;line-height:1.5}\
+h1,h2,h3,h4{margin:1.5rem 0 .5rem}h2{border-bottom:1px solid #d0d7de;padding-bottom:.25rem}\
+.text{white-space:pre-wrap}.meta{color:#59636e;font-size:.875rem;white-space:pre-wrap}\
+pre{background:#f6f8fa;border-radius:6px;padding:.75rem;overflow-x:auto;font-size:.8125rem}";
+
+/// A single HTML file with inline styles and no external resources
+fn render_html(title: &str, blocks: &[Block]) -> String {
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{}</title>\n<style>{HTML_STYLE}</style>\n</head>\n<body>\n",
+        escape_html(title)
+    );
+    for block in blocks {
+        match block {
+            Block::Heading(level, text) => {
+                let _ = writeln!(out, "<h{level}>{}</h{level}>", escape_html(text));
+            }
+            Block::Text(text) => {
+                let _ = writeln!(out, "<div class=\"text\">{}</div>", escape_html(text));
+            }
+            Block::Meta(text) => {
+                let _ = writeln!(out, "<p class=\"meta\">{}</p>", escape_html(text));
+            }
+            Block::Code { language, text } => {
+                let _ = writeln!(
+                    out,
+                    "<pre><code class=\"language-{language}\">{}</code></pre>",
+                    escape_html(text)
+                );
+            }
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_fence_outgrows_backticks() {
+        assert_eq!(code_fence("plain"), "```");
+        assert_eq!(code_fence("a ```nested``` fence"), "````");
+    }
+
+    #[test]
+    fn test_html_escapes_content() {
+        let html = render_html("<script>", &[Block::Text("a < b && \"c\"".to_string())]);
+        assert!(html.contains("<title>&lt;script&gt;</title>"));
+        assert!(html.contains("a &lt; b &amp;&amp; &quot;c&quot;"));
+        assert!(!html.contains("<script>"));
+    }
+
+    #[test]
+    fn test_markdown_renders_tool_calls() {
+        let entry = NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::ToolUse {
+                tool_name: "bash".to_string(),
+                action_type: ActionType::CommandRun {
+                    command: "cargo test".to_string(),
+                    result: None,
+                },
+                status: ToolStatus::Denied {
+                    reason: Some("not now".to_string()),
+                },
+            },
+            content: String::new(),
+            metadata: None,
+        };
+        let mut blocks = Vec::new();
+        entry_blocks(&entry, &mut blocks);
+        let markdown = render_markdown(&blocks);
+
+        assert!(markdown.contains("#### Tool: bash (denied: not now)"));
+        assert!(markdown.contains("```sh\ncargo test\n```"));
+    }
+}
@@ -0,0 +1,258 @@
+//! Token usage pricing and per-project budgets for coding agent runs
+
+use std::{
+    collections::HashMap,
+    fs,
+    sync::{LazyLock, RwLock},
+};
+
+use db::models::{
+    coding_agent_turn::CodingAgentTurn,
+    execution_process::ExecutionProcess,
+    project_budget::{BudgetStatus, ProjectBudget},
+    token_usage::{ExecutionProcessUsage, RecordUsage},
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utils::log_msg::TokenUsage;
+use uuid::Uuid;
+
+const DEFAULT_MODEL_PRICES_JSON: &str = include_str!("../../default_model_prices.json");
+
+static MODEL_PRICES_CACHE: LazyLock<RwLock<ModelPriceTable>> =
+    LazyLock::new(|| RwLock::new(ModelPriceTable::load()));
+
+/// Price of a model in USD per million tokens
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelPrice {
+    #[serde(default)]
+    pub input: f64,
+    #[serde(default)]
+    pub output: f64,
+    #[serde(default)]
+    pub cache_read: f64,
+    #[serde(default)]
+    pub cache_write: f64,
+}
+
+impl ModelPrice {
+    /// Cost of the usage in micro-dollars. A price per million tokens is
+    /// exactly the price per token in micro-dollars.
+    pub fn cost_microdollars(&self, usage: &TokenUsage) -> i64 {
+        let cost = usage.input_tokens as f64 * self.input
+            + usage.output_tokens as f64 * self.output
+            + usage.cache_read_tokens as f64 * self.cache_read
+            + usage.cache_write_tokens as f64 * self.cache_write;
+        cost.round() as i64
+    }
+}
+
+/// Model prices keyed by model-name prefix. The built-in table can be
+/// extended or overridden with `model_prices.json` in the asset directory.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ModelPriceTable {
+    prices: HashMap<String, ModelPrice>,
+}
+
+impl ModelPriceTable {
+    /// Cached price table
+    pub fn get_cached() -> ModelPriceTable {
+        MODEL_PRICES_CACHE.read().unwrap().clone()
+    }
+
+    /// Re-read `model_prices.json`, e.g. after it was edited
+    pub fn reload() {
+        let mut cache = MODEL_PRICES_CACHE.write().unwrap();
+        *cache = Self::load();
+    }
+
+    pub fn from_defaults() -> Self {
+        serde_json::from_str::<Self>(DEFAULT_MODEL_PRICES_JSON)
+            .expect("Failed to parse embedded default_model_prices.json")
+            .normalized()
+    }
+
+    pub fn load() -> Self {
+        let mut table = Self::from_defaults();
+        let prices_path = utils::assets::model_prices_path();
+
+        let Ok(content) = fs::read_to_string(&prices_path) else {
+            return table;
+        };
+
+        match serde_json::from_str::<Self>(&content) {
+            Ok(overrides) => {
+                tracing::info!("Loaded model price overrides from model_prices.json");
+                table.prices.extend(overrides.normalized().prices);
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Failed to parse model_prices.json: {}, using default prices",
+                    e
+                );
+            }
+        }
+        table
+    }
+
+    fn normalized(self) -> Self {
+        Self {
+            prices: self
+                .prices
+                .into_iter()
+                .map(|(model, price)| (model.to_lowercase(), price))
+                .collect(),
+        }
+    }
+
+    /// Price of the longest model prefix matching `model`, ignoring case
+    pub fn price(&self, model: &str) -> Option<ModelPrice> {
+        let model = model.to_lowercase();
+        self.prices
+            .iter()
+            .filter(|(prefix, _)| model.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, price)| *price)
+    }
+
+    /// Cost in micro-dollars and whether it came from the table. Unknown
+    /// models fall back to the cost the agent reported, if any.
+    pub fn cost(&self, usage: &TokenUsage) -> (i64, bool) {
+        match usage.model.as_deref().and_then(|model| self.price(model)) {
+            Some(price) => (price.cost_microdollars(usage), true),
+            None => {
+                let reported = usage
+                    .reported_cost_usd
+                    .map(|usd| (usd * 1_000_000.0).round() as i64)
+                    .unwrap_or(0);
+                (reported, false)
+            }
+        }
+    }
+}
+
+/// Where an execution process's usage is attributed
+#[derive(Debug, Clone)]
+pub struct UsageContext {
+    pub execution_process_id: Uuid,
+    pub coding_agent_turn_id: Option<Uuid>,
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    pub executor: String,
+}
+
+impl UsageContext {
+    pub async fn load(pool: &PgPool, execution_process_id: Uuid) -> Result<Self, sqlx::Error> {
+        let ctx = ExecutionProcess::load_context(pool, execution_process_id).await?;
+        let turn =
+            CodingAgentTurn::find_by_execution_process_id(pool, execution_process_id).await?;
+        let executor = ctx
+            .execution_process
+            .executor_action()
+            .ok()
+            .and_then(|action| action.base_executor())
+            .map(|executor| executor.to_string())
+            .unwrap_or_default();
+
+        Ok(Self {
+            execution_process_id,
+            coding_agent_turn_id: turn.map(|turn| turn.id),
+            task_id: ctx.task.id,
+            project_id: ctx.project.id,
+            executor,
+        })
+    }
+}
+
+/// Price and store a usage event. Returns the project's budget status when
+/// the project has a budget.
+pub async fn record_usage(
+    pool: &PgPool,
+    ctx: &UsageContext,
+    usage: &TokenUsage,
+) -> Result<Option<BudgetStatus>, sqlx::Error> {
+    let (cost_microdollars, priced) = ModelPriceTable::get_cached().cost(usage);
+
+    ExecutionProcessUsage::record(
+        pool,
+        &RecordUsage {
+            execution_process_id: ctx.execution_process_id,
+            coding_agent_turn_id: ctx.coding_agent_turn_id,
+            task_id: Some(ctx.task_id),
+            project_id: Some(ctx.project_id),
+            executor: ctx.executor.clone(),
+            model: usage.model.clone().unwrap_or_default(),
+            input_tokens: clamp_tokens(usage.input_tokens),
+            output_tokens: clamp_tokens(usage.output_tokens),
+            cache_read_tokens: clamp_tokens(usage.cache_read_tokens),
+            cache_write_tokens: clamp_tokens(usage.cache_write_tokens),
+            cost_microdollars,
+            priced,
+        },
+    )
+    .await?;
+
+    ProjectBudget::status_for_project(pool, ctx.project_id).await
+}
+
+/// The project's budget status if it has a budget that is already used up
+pub async fn exceeded_budget(
+    pool: &PgPool,
+    project_id: Uuid,
+) -> Result<Option<BudgetStatus>, sqlx::Error> {
+    Ok(ProjectBudget::status_for_project(pool, project_id)
+        .await?
+        .filter(|status| status.exceeded))
+}
+
+fn clamp_tokens(tokens: u64) -> i64 {
+    i64::try_from(tokens).unwrap_or(i64::MAX)
+}
+
+/// Format micro-dollars as a dollar amount, e.g. `$12.34`
+pub fn format_usd(microdollars: i64) -> String {
+    format!("${:.2}", microdollars as f64 / 1_000_000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(model: Option<&str>) -> TokenUsage {
+        TokenUsage {
+            model: model.map(str::to_string),
+            input_tokens: 10_000,
+            output_tokens: 2_000,
+            cache_read_tokens: 100_000,
+            cache_write_tokens: 0,
+            reported_cost_usd: Some(0.5),
+        }
+    }
+
+    #[test]
+    fn test_longest_prefix_wins() {
+        let table = ModelPriceTable::from_defaults();
+        let opus = table.price("claude-opus-4-1-20250805").unwrap();
+        let opus_45 = table.price("Claude-Opus-4-5-20251101").unwrap();
+        assert_eq!(opus.input, 15.0);
+        assert_eq!(opus_45.input, 5.0);
+        assert!(table.price("unknown-model").is_none());
+    }
+
+    #[test]
+    fn test_cost_from_table_and_fallback() {
+        let table = ModelPriceTable::from_defaults();
+
+        // 10k * $3 + 2k * $15 + 100k * $0.30 per million tokens
+        let (cost, priced) = table.cost(&usage(Some("claude-sonnet-4-5-20250929")));
+        assert!(priced);
+        assert_eq!(cost, 90_000);
+
+        let (cost, priced) = table.cost(&usage(Some("my-local-model")));
+        assert!(!priced);
+        assert_eq!(cost, 500_000);
+
+        assert_eq!(format_usd(90_000), "$0.09");
+    }
+}
@@ -0,0 +1,281 @@
+//! Document sync against a local MinIO-style S3 stand-in
+
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use axum::{
+    Router,
+    body::Bytes,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use services::services::cloud_storage::{
+    CloudStorageBackend, CloudStorageError,
+    s3::{S3Client, S3Config},
+    sync::{ConflictPolicy, LocalDocument, SyncOutcome, sync_document},
+};
+use tokio::net::TcpListener;
+use uuid::Uuid;
+
+const BUCKET: &str = "docs";
+const PREFIX: &str = "ikanban";
+
+#[derive(Clone, Default)]
+struct MockS3 {
+    objects: Arc<Mutex<HashMap<String, (Vec<u8>, String)>>>,
+    versions: Arc<AtomicUsize>,
+    /// Number of upcoming PUTs to reject with 503
+    failing_puts: Arc<AtomicUsize>,
+}
+
+impl MockS3 {
+    fn object(&self, key: &str) -> Option<Vec<u8>> {
+        let objects = self.objects.lock().unwrap();
+        objects
+            .get(&format!("{PREFIX}/{key}"))
+            .map(|(body, _)| body.clone())
+    }
+
+    /// Simulate an edit made directly in the bucket
+    fn edit(&self, key: &str, body: &str) {
+        let etag = format!("v{}", self.versions.fetch_add(1, Ordering::SeqCst));
+        self.objects
+            .lock()
+            .unwrap()
+            .insert(format!("{PREFIX}/{key}"), (body.as_bytes().to_vec(), etag));
+    }
+
+    fn keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.objects.lock().unwrap().keys().cloned().collect();
+        keys.sort();
+        keys
+    }
+}
+
+async fn put_object(
+    State(s3): State<MockS3>,
+    Path((bucket, key)): Path<(String, String)>,
+    body: Bytes,
+) -> Response {
+    assert_eq!(bucket, BUCKET);
+    if s3
+        .failing_puts
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+        .is_ok()
+    {
+        return StatusCode::SERVICE_UNAVAILABLE.into_response();
+    }
+    let etag = format!("v{}", s3.versions.fetch_add(1, Ordering::SeqCst));
+    s3.objects
+        .lock()
+        .unwrap()
+        .insert(key, (body.to_vec(), etag.clone()));
+    (StatusCode::OK, [(header::ETAG, format!("\"{etag}\""))]).into_response()
+}
+
+/// Also serves HEAD
+async fn get_object(State(s3): State<MockS3>, Path((_, key)): Path<(String, String)>) -> Response {
+    match s3.objects.lock().unwrap().get(&key) {
+        Some((body, etag)) => {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::ETAG, format!("\"{etag}\"").parse().unwrap());
+            (headers, body.clone()).into_response()
+        }
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+async fn delete_object(
+    State(s3): State<MockS3>,
+    Path((_, key)): Path<(String, String)>,
+) -> StatusCode {
+    s3.objects.lock().unwrap().remove(&key);
+    StatusCode::NO_CONTENT
+}
+
+async fn spawn_mock_s3() -> (MockS3, S3Client) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = format!("http://{}", listener.local_addr().unwrap());
+    let s3 = MockS3::default();
+
+    let app = Router::new()
+        .route(
+            "/{bucket}/{*key}",
+            get(get_object).put(put_object).delete(delete_object),
+        )
+        .with_state(s3.clone());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let client = S3Client::new(S3Config {
+        bucket: BUCKET.to_string(),
+        region: "us-east-1".to_string(),
+        prefix: Some(PREFIX.to_string()),
+        access_key_id: "minio".to_string(),
+        secret_access_key: "minio-secret".to_string(),
+        endpoint: Some(endpoint),
+    })
+    .unwrap();
+    (s3, client)
+}
+
+fn document(path: &str, content: &str) -> LocalDocument {
+    LocalDocument {
+        id: Uuid::new_v4(),
+        path: path.to_string(),
+        content: content.as_bytes().to_vec(),
+        content_type: "text/markdown".to_string(),
+        editable: true,
+        state: None,
+    }
+}
+
+/// Sync and record the resulting state on the document, as the engine does
+async fn sync(backend: &S3Client, policy: ConflictPolicy, doc: &mut LocalDocument) -> SyncOutcome {
+    let outcome = sync_document(backend, policy, doc).await.unwrap();
+    match &outcome {
+        SyncOutcome::Unchanged => {}
+        SyncOutcome::Uploaded(state) | SyncOutcome::Conflict { state, .. } => {
+            doc.state = Some(state.clone());
+        }
+        SyncOutcome::Pulled { content, state } => {
+            doc.content = content.as_bytes().to_vec();
+            doc.state = Some(state.clone());
+        }
+    }
+    outcome
+}
+
+#[tokio::test]
+async fn uploads_new_and_changed_documents_only() {
+    let (s3, backend) = spawn_mock_s3().await;
+    let mut doc = document("Specs/api.md", "# API");
+
+    assert!(matches!(
+        sync(&backend, ConflictPolicy::KeepBoth, &mut doc).await,
+        SyncOutcome::Uploaded(_)
+    ));
+    assert_eq!(s3.object("Specs/api.md").unwrap(), b"# API");
+    let first_etag = doc.state.as_ref().unwrap().etag.clone();
+
+    assert_eq!(
+        sync(&backend, ConflictPolicy::KeepBoth, &mut doc).await,
+        SyncOutcome::Unchanged
+    );
+
+    doc.content = b"# API v2".to_vec();
+    assert!(matches!(
+        sync(&backend, ConflictPolicy::KeepBoth, &mut doc).await,
+        SyncOutcome::Uploaded(_)
+    ));
+    assert_eq!(s3.object("Specs/api.md").unwrap(), b"# API v2");
+    assert_ne!(doc.state.as_ref().unwrap().etag, first_etag);
+}
+
+#[tokio::test]
+async fn pulls_remote_edits_into_unchanged_documents() {
+    let (s3, backend) = spawn_mock_s3().await;
+    let mut doc = document("notes.md", "draft");
+    sync(&backend, ConflictPolicy::KeepBoth, &mut doc).await;
+
+    s3.edit("notes.md", "edited in the bucket");
+    let outcome = sync(&backend, ConflictPolicy::KeepBoth, &mut doc).await;
+
+    assert!(
+        matches!(outcome, SyncOutcome::Pulled { ref content, .. } if content == "edited in the bucket")
+    );
+    assert_eq!(
+        sync(&backend, ConflictPolicy::KeepBoth, &mut doc).await,
+        SyncOutcome::Unchanged
+    );
+}
+
+#[tokio::test]
+async fn keeps_both_versions_on_conflict() {
+    let (s3, backend) = spawn_mock_s3().await;
+    let mut doc = document("Specs/plan.md", "v1");
+    sync(&backend, ConflictPolicy::KeepBoth, &mut doc).await;
+
+    s3.edit("Specs/plan.md", "remote v2");
+    doc.content = b"local v2".to_vec();
+    let outcome = sync(&backend, ConflictPolicy::KeepBoth, &mut doc).await;
+
+    let SyncOutcome::Conflict { conflict_copy, .. } = outcome else {
+        panic!("expected a conflict, got {outcome:?}");
+    };
+    assert!(conflict_copy.starts_with("Specs/plan (conflict "));
+    assert!(conflict_copy.ends_with(").md"));
+    assert_eq!(s3.object(&conflict_copy).unwrap(), b"remote v2");
+    assert_eq!(s3.object("Specs/plan.md").unwrap(), b"local v2");
+    assert_eq!(
+        sync(&backend, ConflictPolicy::KeepBoth, &mut doc).await,
+        SyncOutcome::Unchanged
+    );
+}
+
+#[tokio::test]
+async fn prefer_local_overwrites_remote_edits() {
+    let (s3, backend) = spawn_mock_s3().await;
+    let mut doc = document("plan.md", "v1");
+    sync(&backend, ConflictPolicy::PreferLocal, &mut doc).await;
+
+    s3.edit("plan.md", "remote v2");
+    assert!(matches!(
+        sync(&backend, ConflictPolicy::PreferLocal, &mut doc).await,
+        SyncOutcome::Uploaded(_)
+    ));
+    assert_eq!(s3.object("plan.md").unwrap(), b"v1");
+    assert_eq!(s3.keys(), [format!("{PREFIX}/plan.md")]);
+}
+
+#[tokio::test]
+async fn moves_renamed_documents_and_restores_deleted_ones() {
+    let (s3, backend) = spawn_mock_s3().await;
+    let mut doc = document("Drafts/plan.md", "v1");
+    sync(&backend, ConflictPolicy::KeepBoth, &mut doc).await;
+
+    doc.path = "Specs/plan.md".to_string();
+    sync(&backend, ConflictPolicy::KeepBoth, &mut doc).await;
+    assert_eq!(s3.keys(), [format!("{PREFIX}/Specs/plan.md")]);
+
+    backend.delete_object("Specs/plan.md").await.unwrap();
+    assert!(matches!(
+        sync(&backend, ConflictPolicy::KeepBoth, &mut doc).await,
+        SyncOutcome::Uploaded(_)
+    ));
+    assert_eq!(s3.object("Specs/plan.md").unwrap(), b"v1");
+}
+
+#[tokio::test]
+async fn throttled_uploads_are_transient_errors() {
+    let (s3, backend) = spawn_mock_s3().await;
+    s3.failing_puts.store(1, Ordering::SeqCst);
+
+    let error = sync_document(
+        &backend,
+        ConflictPolicy::KeepBoth,
+        &document("plan.md", "v1"),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(error, CloudStorageError::Unavailable(_)));
+    assert!(error.is_transient());
+
+    // The next attempt goes through
+    let outcome = sync_document(
+        &backend,
+        ConflictPolicy::KeepBoth,
+        &document("plan.md", "v1"),
+    )
+    .await
+    .unwrap();
+    assert!(matches!(outcome, SyncOutcome::Uploaded(_)));
+}
@@ -259,6 +259,124 @@ fn commit_and_is_worktree_clean() {
     );
 }
 
+#[test]
+fn checkpoint_snapshots_changes_without_moving_the_branch() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, "foo.txt", "one\n");
+    assert!(s.commit(&repo_path, "base").unwrap());
+
+    // Clean worktree: the checkpoint is HEAD
+    let initial = s.get_head_info(&repo_path).unwrap().oid;
+    let (sha, captured) = s
+        .create_checkpoint(&repo_path, "refs/vibe-kanban/checkpoints/a", "checkpoint")
+        .unwrap()
+        .unwrap();
+    assert_eq!(sha, initial);
+    assert!(!captured);
+
+    // Dirty worktree: uncommitted and untracked changes are snapshotted off
+    // the branch, which stays where it was with the changes uncommitted
+    write_file(&repo_path, "foo.txt", "one\ntwo\n");
+    write_file(&repo_path, "notes.txt", "todo\n");
+    let (checkpoint, captured) = s
+        .create_checkpoint(&repo_path, "refs/vibe-kanban/checkpoints/b", "checkpoint")
+        .unwrap()
+        .unwrap();
+    assert!(captured);
+    assert_ne!(checkpoint, initial);
+    let head = s.get_head_info(&repo_path).unwrap();
+    assert_eq!(
+        (head.branch.as_str(), head.oid.as_str()),
+        ("main", initial.as_str())
+    );
+    assert!(!s.is_worktree_clean(&repo_path).unwrap());
+    let git = GitCli::new();
+    let staged = git
+        .git(&repo_path, ["diff", "--cached", "--name-only"])
+        .unwrap();
+    assert!(staged.is_empty());
+
+    let diffs = s
+        .get_diffs(
+            DiffTarget::Range {
+                repo_path: &repo_path,
+                from_commit: &initial,
+                to_commit: &checkpoint,
+            },
+            None,
+        )
+        .unwrap();
+    let mut paths: Vec<_> = diffs.iter().filter_map(|d| d.new_path.clone()).collect();
+    paths.sort();
+    assert_eq!(paths, ["foo.txt", "notes.txt"]);
+
+    // The turn commits more work
+    write_file(&repo_path, "foo.txt", "three\n");
+    write_file(&repo_path, "bar.txt", "bar\n");
+    assert!(s.commit(&repo_path, "agent work").unwrap());
+
+    // Restoring moves the branch back and leaves the snapshot uncommitted
+    s.restore_checkpoint(&repo_path, &checkpoint, true).unwrap();
+    assert_eq!(s.get_head_info(&repo_path).unwrap().oid, initial);
+    assert_eq!(
+        fs::read_to_string(repo_path.join("foo.txt")).unwrap(),
+        "one\ntwo\n"
+    );
+    assert_eq!(
+        fs::read_to_string(repo_path.join("notes.txt")).unwrap(),
+        "todo\n"
+    );
+    assert!(!repo_path.join("bar.txt").exists());
+    assert!(!s.is_worktree_clean(&repo_path).unwrap());
+
+    // A clean checkpoint restores to its commit
+    assert!(s.commit(&repo_path, "agent work again").unwrap());
+    s.restore_checkpoint(&repo_path, &initial, false).unwrap();
+    assert_eq!(s.get_head_info(&repo_path).unwrap().oid, initial);
+    assert!(s.is_worktree_clean(&repo_path).unwrap());
+    assert!(!repo_path.join("notes.txt").exists());
+
+    let repo = Repository::open(&repo_path).unwrap();
+    let reference = repo
+        .find_reference("refs/vibe-kanban/checkpoints/b")
+        .unwrap();
+    assert_eq!(reference.target().unwrap().to_string(), checkpoint);
+}
+
+#[test]
+fn restoring_a_checkpoint_removes_new_untracked_files_but_keeps_ignored_ones() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, "foo.txt", "one\n");
+    assert!(s.commit(&repo_path, "base").unwrap());
+    write_file(&repo_path, ".git/info/exclude", "build/\n");
+
+    write_file(&repo_path, "notes.txt", "todo\n");
+    let (checkpoint, captured) = s
+        .create_checkpoint(&repo_path, "refs/vibe-kanban/checkpoints/a", "checkpoint")
+        .unwrap()
+        .unwrap();
+    assert!(captured);
+
+    // The turn leaves untracked and ignored files behind
+    write_file(&repo_path, "scratch/new.txt", "new\n");
+    write_file(&repo_path, "build/out.txt", "out\n");
+
+    s.restore_checkpoint(&repo_path, &checkpoint, true).unwrap();
+    assert!(!repo_path.join("scratch").exists());
+    assert_eq!(
+        fs::read_to_string(repo_path.join("notes.txt")).unwrap(),
+        "todo\n"
+    );
+    assert_eq!(
+        fs::read_to_string(repo_path.join("build/out.txt")).unwrap(),
+        "out\n"
+    );
+}
+
 #[test]
 fn commit_in_detached_head_succeeds_via_service() {
     let td = TempDir::new().unwrap();
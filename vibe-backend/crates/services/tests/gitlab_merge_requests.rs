@@ -0,0 +1,352 @@
+//! GitLabService against a local mock of the GitLab REST API
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    routing::{get, post},
+};
+use db::models::merge::MergeStatus;
+use serde_json::{Value, json};
+use services::services::{
+    github::{CreatePrRequest, UnifiedPrComment},
+    gitlab::{GitLabRepoInfo, GitLabService, GitLabServiceError},
+};
+use tokio::net::TcpListener;
+
+const TOKEN: &str = "glpat-test";
+const PROJECT: &str = "group/sub/project";
+
+fn authorized(headers: &HeaderMap) -> Result<(), StatusCode> {
+    match headers.get("private-token").and_then(|v| v.to_str().ok()) {
+        Some(TOKEN) => Ok(()),
+        _ => Err(StatusCode::UNAUTHORIZED),
+    }
+}
+
+fn check_project(project: &str) -> Result<(), StatusCode> {
+    if project == PROJECT {
+        Ok(())
+    } else {
+        Err(StatusCode::NOT_FOUND)
+    }
+}
+
+fn mr_json(base_url: &str, iid: i64, state: &str) -> Value {
+    json!({
+        "iid": iid,
+        "web_url": format!("{base_url}/{PROJECT}/-/merge_requests/{iid}"),
+        "state": state,
+        "merged_at": if state == "merged" { json!("2026-01-02T03:04:05Z") } else { Value::Null },
+        "merge_commit_sha": Value::Null,
+        "squash_commit_sha": if state == "merged" { json!("abc123") } else { Value::Null },
+    })
+}
+
+async fn list_mrs(
+    State(base_url): State<String>,
+    headers: HeaderMap,
+    Path(project): Path<String>,
+    Query(query): Query<Vec<(String, String)>>,
+) -> Result<Json<Value>, StatusCode> {
+    authorized(&headers)?;
+    check_project(&project)?;
+    assert!(query.contains(&("source_branch".into(), "vk/feature".into())));
+    assert!(query.contains(&("state".into(), "all".into())));
+    Ok(Json(json!([
+        mr_json(&base_url, 3, "closed"),
+        mr_json(&base_url, 4, "opened"),
+    ])))
+}
+
+async fn create_mr(
+    State(base_url): State<String>,
+    headers: HeaderMap,
+    Path(project): Path<String>,
+    Json(body): Json<Value>,
+) -> Result<(StatusCode, Json<Value>), StatusCode> {
+    authorized(&headers)?;
+    check_project(&project)?;
+    assert_eq!(body["source_branch"], "vk/feature");
+    assert_eq!(body["target_branch"], "main");
+    assert_eq!(body["title"], "Draft: Add feature");
+    assert_eq!(body["description"], "Body");
+    Ok((StatusCode::CREATED, Json(mr_json(&base_url, 7, "opened"))))
+}
+
+async fn get_mr(
+    State(base_url): State<String>,
+    headers: HeaderMap,
+    Path((project, iid)): Path<(String, i64)>,
+) -> Result<Json<Value>, StatusCode> {
+    authorized(&headers)?;
+    check_project(&project)?;
+    Ok(Json(mr_json(&base_url, iid, "merged")))
+}
+
+async fn list_discussions(
+    headers: HeaderMap,
+    Path((project, _iid)): Path<(String, i64)>,
+) -> Result<Json<Value>, StatusCode> {
+    authorized(&headers)?;
+    check_project(&project)?;
+    Ok(Json(json!([
+        {
+            "id": "d1",
+            "notes": [{
+                "id": 11,
+                "body": "added 1 commit",
+                "author": { "username": "alice" },
+                "created_at": "2026-01-01T00:00:00Z",
+                "system": true
+            }]
+        },
+        {
+            "id": "d2",
+            "notes": [{
+                "id": 13,
+                "body": "Rename this",
+                "author": { "username": "bob" },
+                "created_at": "2026-01-01T00:02:00Z",
+                "system": false,
+                "position": {
+                    "new_path": "src/lib.rs",
+                    "old_path": "src/lib.rs",
+                    "new_line": 42,
+                    "old_line": null
+                }
+            }]
+        },
+        {
+            "id": "d3",
+            "notes": [{
+                "id": 12,
+                "body": "Looks good",
+                "author": { "username": "carol" },
+                "created_at": "2026-01-01T00:01:00Z",
+                "system": false
+            }]
+        }
+    ])))
+}
+
+/// Serve a minimal GitLab API and return its base URL
+async fn spawn_mock_gitlab() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+    let app = Router::new()
+        .route(
+            "/api/v4/projects/{project}/merge_requests",
+            get(list_mrs).post(create_mr),
+        )
+        .route(
+            "/api/v4/projects/{project}/merge_requests/{iid}",
+            get(get_mr),
+        )
+        .route(
+            "/api/v4/projects/{project}/merge_requests/{iid}/discussions",
+            get(list_discussions),
+        )
+        .with_state(base_url.clone());
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    base_url
+}
+
+fn repo_info(base_url: &str) -> GitLabRepoInfo {
+    GitLabRepoInfo::from_remote_url(&format!("{base_url}/{PROJECT}.git")).unwrap()
+}
+
+#[tokio::test]
+async fn create_mr_posts_draft_title_and_maps_response() {
+    let base_url = spawn_mock_gitlab().await;
+    let service = GitLabService::new(&base_url, TOKEN).unwrap();
+
+    let pr = service
+        .create_mr(
+            &repo_info(&base_url),
+            &CreatePrRequest {
+                title: "Add feature".to_string(),
+                body: Some("Body".to_string()),
+                head_branch: "vk/feature".to_string(),
+                base_branch: "main".to_string(),
+                draft: Some(true),
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(pr.number, 7);
+    assert_eq!(pr.url, format!("{base_url}/{PROJECT}/-/merge_requests/7"));
+    assert!(matches!(pr.status, MergeStatus::Open));
+}
+
+#[tokio::test]
+async fn failed_create_mr_is_not_retried() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let posts = Arc::new(AtomicUsize::new(0));
+    // GitLab may have created the MR before the gateway gave up
+    let app = Router::new()
+        .route(
+            "/api/v4/projects/{project}/merge_requests",
+            post(|State(posts): State<Arc<AtomicUsize>>| async move {
+                posts.fetch_add(1, Ordering::SeqCst);
+                StatusCode::BAD_GATEWAY
+            }),
+        )
+        .with_state(posts.clone());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    let service = GitLabService::new(&base_url, TOKEN).unwrap();
+
+    let err = service
+        .create_mr(
+            &repo_info(&base_url),
+            &CreatePrRequest {
+                title: "Add feature".to_string(),
+                body: None,
+                head_branch: "vk/feature".to_string(),
+                base_branch: "main".to_string(),
+                draft: None,
+            },
+        )
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, GitLabServiceError::Unavailable(_)));
+    assert_eq!(posts.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn merged_mr_status_uses_squash_commit() {
+    let base_url = spawn_mock_gitlab().await;
+    let service = GitLabService::new(&base_url, TOKEN).unwrap();
+
+    let pr = service
+        .update_mr_status(&repo_info(&base_url), 7)
+        .await
+        .unwrap();
+
+    assert!(matches!(pr.status, MergeStatus::Merged));
+    assert!(pr.merged_at.is_some());
+    assert_eq!(pr.merge_commit_sha.as_deref(), Some("abc123"));
+}
+
+#[tokio::test]
+async fn list_mrs_for_branch_puts_open_first() {
+    let base_url = spawn_mock_gitlab().await;
+    let service = GitLabService::new(&base_url, TOKEN).unwrap();
+
+    let prs = service
+        .list_all_mrs_for_branch(&repo_info(&base_url), "vk/feature")
+        .await
+        .unwrap();
+
+    assert_eq!(prs.iter().map(|p| p.number).collect::<Vec<_>>(), [4, 3]);
+    assert!(matches!(prs[1].status, MergeStatus::Closed));
+}
+
+#[tokio::test]
+async fn mr_comments_skip_system_notes_and_map_diff_notes() {
+    let base_url = spawn_mock_gitlab().await;
+    let service = GitLabService::new(&base_url, TOKEN).unwrap();
+    let mr_url = format!("{base_url}/{PROJECT}/-/merge_requests/7");
+
+    let comments = service
+        .get_mr_comments(&repo_info(&base_url), 7, &mr_url)
+        .await
+        .unwrap();
+
+    assert_eq!(comments.len(), 2);
+    match &comments[0] {
+        UnifiedPrComment::General {
+            id, author, url, ..
+        } => {
+            assert_eq!(id, "12");
+            assert_eq!(author, "carol");
+            assert_eq!(url, &format!("{mr_url}#note_12"));
+        }
+        other => panic!("expected general comment, got {other:?}"),
+    }
+    match &comments[1] {
+        UnifiedPrComment::Review { path, line, .. } => {
+            assert_eq!(path, "src/lib.rs");
+            assert_eq!(*line, Some(42));
+        }
+        other => panic!("expected review comment, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn bad_token_is_auth_failure() {
+    let base_url = spawn_mock_gitlab().await;
+    let service = GitLabService::new(&base_url, "wrong").unwrap();
+
+    let err = service
+        .update_mr_status(&repo_info(&base_url), 7)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, GitLabServiceError::AuthFailed(_)));
+    assert!(!err.should_retry());
+}
+
+#[test]
+fn gitlab_repo_info_parses_ssh_https_and_mr_urls() {
+    let info = GitLabRepoInfo::from_remote_url("git@gitlab.com:group/project.git").unwrap();
+    assert_eq!(info.host, "gitlab.com");
+    assert_eq!(info.project_path, "group/project");
+
+    let info =
+        GitLabRepoInfo::from_remote_url("ssh://git@GitLab.example.com:2222/group/sub/project.git")
+            .unwrap();
+    assert_eq!(info.host, "gitlab.example.com");
+    assert_eq!(info.project_path, "group/sub/project");
+
+    let info = GitLabRepoInfo::from_remote_url(
+        "https://gitlab.example.com/group/sub/project/-/merge_requests/12",
+    )
+    .unwrap();
+    assert_eq!(info.host, "gitlab.example.com");
+    assert_eq!(info.project_path, "group/sub/project");
+
+    let service = GitLabService::new("https://gitlab.example.com/", TOKEN).unwrap();
+    assert!(service.serves(&info));
+
+    let err = GitLabRepoInfo::from_remote_url("https://gitlab.com/project").unwrap_err();
+    assert!(matches!(err, GitLabServiceError::Repository(_)));
+}
+
+#[test]
+fn gitlab_repo_info_rejects_urls_outside_host_and_path() {
+    for url in [
+        "https://gitlab.com/group/project?private_token=abc",
+        "https://gitlab.com/group/project#readme",
+        "https://gitlab.com/group/../admin/project",
+        "https://evil.example/redirect?to=gitlab.com:group/project",
+        "file:///srv/git/group/project.git",
+        "gitlab.com/group/project",
+        "git@gitlab.com:group/project extra",
+        "git@gitlab.com:/group/project",
+    ] {
+        assert!(
+            GitLabRepoInfo::from_remote_url(url).is_err(),
+            "{url} should be rejected"
+        );
+    }
+
+    let info =
+        GitLabRepoInfo::from_remote_url("http://127.0.0.1:8929/my-group/my.project.git/").unwrap();
+    assert_eq!(info.host, "127.0.0.1");
+    assert_eq!(info.project_path, "my-group/my.project");
+}
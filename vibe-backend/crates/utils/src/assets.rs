@@ -36,6 +36,11 @@ pub fn profiles_path() -> std::path::PathBuf {
     asset_dir().join("profiles.json")
 }
 
+/// Optional overrides for the built-in model price table
+pub fn model_prices_path() -> std::path::PathBuf {
+    asset_dir().join("model_prices.json")
+}
+
 pub fn credentials_path() -> std::path::PathBuf {
     asset_dir().join("credentials.json")
 }
@@ -6,6 +6,7 @@ pub const EV_STDOUT: &str = "stdout";
 pub const EV_STDERR: &str = "stderr";
 pub const EV_JSON_PATCH: &str = "json_patch";
 pub const EV_SESSION_ID: &str = "session_id";
+pub const EV_TOKEN_USAGE: &str = "token_usage";
 pub const EV_FINISHED: &str = "finished";
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -14,9 +15,34 @@ pub enum LogMsg {
     Stderr(String),
     JsonPatch(Patch),
     SessionId(String),
+    TokenUsage(TokenUsage),
     Finished,
 }
 
+/// Tokens a coding agent reported spending. Each event is an increment on
+/// top of what the execution already reported, so consumers sum them.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    /// Model id as the agent reported it, when it did
+    pub model: Option<String>,
+    /// Input tokens not served from the prompt cache
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_write_tokens: u64,
+    /// Cost the agent computed itself, used when the model has no known price
+    pub reported_cost_usd: Option<f64>,
+}
+
+impl TokenUsage {
+    pub fn is_empty(&self) -> bool {
+        self.input_tokens == 0
+            && self.output_tokens == 0
+            && self.cache_read_tokens == 0
+            && self.cache_write_tokens == 0
+    }
+}
+
 impl LogMsg {
     pub fn name(&self) -> &'static str {
         match self {
@@ -24,6 +50,7 @@ impl LogMsg {
             LogMsg::Stderr(_) => EV_STDERR,
             LogMsg::JsonPatch(_) => EV_JSON_PATCH,
             LogMsg::SessionId(_) => EV_SESSION_ID,
+            LogMsg::TokenUsage(_) => EV_TOKEN_USAGE,
             LogMsg::Finished => EV_FINISHED,
         }
     }
@@ -37,6 +64,10 @@ impl LogMsg {
                 Event::default().event(EV_JSON_PATCH).data(data)
             }
             LogMsg::SessionId(s) => Event::default().event(EV_SESSION_ID).data(s.clone()),
+            LogMsg::TokenUsage(usage) => {
+                let data = serde_json::to_string(usage).unwrap_or_else(|_| "{}".to_string());
+                Event::default().event(EV_TOKEN_USAGE).data(data)
+            }
             LogMsg::Finished => Event::default().event(EV_FINISHED).data(""),
         }
     }
@@ -73,6 +104,9 @@ impl LogMsg {
                 EV_JSON_PATCH.len() + json_len + OVERHEAD
             }
             LogMsg::SessionId(s) => EV_SESSION_ID.len() + s.len() + OVERHEAD,
+            LogMsg::TokenUsage(usage) => {
+                EV_TOKEN_USAGE.len() + usage.model.as_ref().map_or(0, String::len) + 48 + OVERHEAD
+            }
             LogMsg::Finished => EV_FINISHED.len() + OVERHEAD,
         }
     }
@@ -8,7 +8,10 @@ use futures::{StreamExt, TryStreamExt, future};
 use tokio::{sync::broadcast, task::JoinHandle};
 use tokio_stream::wrappers::BroadcastStream;
 
-use crate::{log_msg::LogMsg, stream_lines::LinesStreamExt};
+use crate::{
+    log_msg::{LogMsg, TokenUsage},
+    stream_lines::LinesStreamExt,
+};
 
 // 100 MB Limit
 const HISTORY_BYTES: usize = 100000 * 1024;
@@ -79,6 +82,10 @@ impl MsgStore {
         self.push(LogMsg::SessionId(session_id));
     }
 
+    pub fn push_token_usage(&self, usage: TokenUsage) {
+        self.push(LogMsg::TokenUsage(usage));
+    }
+
     pub fn push_finished(&self) {
         self.push(LogMsg::Finished);
     }
@@ -40,6 +40,18 @@ export type SearchResult = { path: string, is_file: boolean, match_type: SearchM
 
 export type SearchMatchType = "FileName" | "DirectoryName" | "FullPath";
 
+export type SearchQuery = { q: string, 
+/**
+ * Limit results to one workspace the caller belongs to
+ */
+workspace_id: string | null, team_id: string | null, project_id: string | null, status: TaskStatus | null, assignee_id: string | null, tag_id: string | null, 
+/**
+ * Comma-separated entity types, e.g. `task,comment`
+ */
+types: string | null, limit: bigint | null, offset: bigint | null, };
+
+export type SearchEntityType = "task" | "comment" | "document" | "project_update";
+
 export type Repo = { id: string, path: string, name: string, display_name: string, created_at: Date, updated_at: Date, };
 
 export type ProjectRepo = { id: string, project_id: string, repo_id: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, };
@@ -414,6 +426,112 @@ search: string | null,
  */
 all: boolean | null, };
 
+export type RevisionDiffQuery = { from: number, to: number, };
+
+export type RevisionSource = "filesystem_scan" | "github_sync" | "cloud_storage_sync";
+
+export type DocumentRevision = { id: string, document_id: string, 
+/**
+ * 1-based, per document
+ */
+revision_number: number, title: string, content: string, 
+/**
+ * Content size in bytes
+ */
+content_size: bigint, author_user_id: string | null, 
+/**
+ * Set when the change was made by a coding agent
+ */
+author_execution_process_id: string | null, 
+/**
+ * Set when the change was made by a scan or sync rather than an edit
+ */
+author_source: RevisionSource | null, 
+/**
+ * Revision number this revision restored, if any
+ */
+restored_from: number | null, created_at: Date, };
+
+export type DocumentRevisionSummary = { id: string, document_id: string, revision_number: number, title: string, content_size: bigint, author_user_id: string | null, author_execution_process_id: string | null, author_source: RevisionSource | null, restored_from: number | null, created_at: Date, };
+
+export type DocumentRevisionDiff = { document_id: string, from_revision: number, to_revision: number, unified_diff: string, additions: number, deletions: number, };
+
+export type TaskTemplate = { id: string, team_id: string, 
+/**
+ * Project tasks created from this template belong to
+ */
+project_id: string, name: string, title_template: string, description_template: string | null, priority: number | null, assignee_id: string | null, tag_ids: Array<string>, document_ids: Array<string>, sub_issues: Array<TemplateSubIssue>, 
+/**
+ * Default values for template variables
+ */
+variables: Record<string, string>, created_by: string | null, created_at: Date, updated_at: Date, };
+
+export type TemplateSubIssue = { title: string, description: string | null, };
+
+export type CreateTaskTemplate = { project_id: string, name: string, title_template: string, description_template: string | null, priority: number | null, assignee_id: string | null, tag_ids: Array<string>, document_ids: Array<string>, sub_issues: Array<TemplateSubIssue>, variables: { [key in string]?: string }, };
+
+export type UpdateTaskTemplate = { project_id: string | null, name: string | null, title_template: string | null, description_template: string | null, priority: number | null, assignee_id: string | null, tag_ids: Array<string> | null, document_ids: Array<string> | null, sub_issues: Array<TemplateSubIssue> | null, variables: { [key in string]?: string } | null, };
+
+export type TaskTemplateRecurrence = { id: string, template_id: string, 
+/**
+ * RRULE subset, e.g. `FREQ=WEEKLY;BYDAY=MO;BYHOUR=9`
+ */
+rrule: string, 
+/**
+ * Anchor for `INTERVAL` and the default time of day
+ */
+starts_at: Date, 
+/**
+ * Variable values overriding the template defaults
+ */
+variables: Record<string, string>, 
+/**
+ * When set, an attempt is started with this profile for each created task
+ */
+executor_profile_id: ExecutorProfileId | null, repos: Array<CreateWorkspaceRepo>, enabled: boolean, 
+/**
+ * `None` once the rule has no further occurrences
+ */
+next_run_at: Date | null, last_run_at: Date | null, last_task_id: string | null, created_at: Date, updated_at: Date, };
+
+export type CreateTaskTemplateRecurrence = { rrule: string, 
+/**
+ * Defaults to now
+ */
+starts_at: Date | null, variables: { [key in string]?: string }, executor_profile_id: ExecutorProfileId | null, repos: Array<CreateWorkspaceRepo>, enabled: boolean | null, };
+
+export type UpdateTaskTemplateRecurrence = { rrule: string | null, starts_at: Date | null, variables: { [key in string]?: string } | null, executor_profile_id: ExecutorProfileId | null, 
+/**
+ * Stop auto-starting attempts; takes precedence over `executor_profile_id`
+ */
+clear_executor_profile: boolean, repos: Array<CreateWorkspaceRepo> | null, enabled: boolean | null, };
+
+export type AttemptGroup = { id: string, task_id: string, 
+/**
+ * Shell script run in each worktree to check an attempt's result
+ */
+verification_script: string | null, winner_workspace_id: string | null, created_at: Date, decided_at: Date | null, };
+
+export type AttemptStatus = "active" | "winner" | "archived";
+
+export type AttemptGroupMember = { id: string, group_id: string, workspace_id: string, executor_profile_id: ExecutorProfileId, status: AttemptStatus, verification_passed: boolean | null, verification_exit_code: number | null, 
+/**
+ * Tail of the script's combined stdout and stderr
+ */
+verification_output: string | null, verification_duration_ms: bigint | null, verified_at: Date | null, created_at: Date, };
+
+export type AttemptGroupWithMembers = { members: Array<AttemptGroupMember>, id: string, task_id: string, 
+/**
+ * Shell script run in each worktree to check an attempt's result
+ */
+verification_script: string | null, winner_workspace_id: string | null, created_at: Date, decided_at: Date | null, };
+
+export type VerificationRun = { passed: boolean, 
+/**
+ * `None` when the script was killed, e.g. on timeout
+ */
+exit_code: number | null, output: string, duration_ms: bigint, };
+
 export type InboxNotificationType = "task_assigned" | "task_mentioned" | "task_comment" | "task_status_changed" | "task_completed" | "workspace_created" | "system_notification";
 
 export type InboxItem = { id: string, notification_type: InboxNotificationType, title: string, message: string | null, task_id: string | null, project_id: string | null, workspace_id: string | null, is_read: boolean, created_at: string, updated_at: string, };
@@ -428,7 +546,11 @@ export type TaskPriority = "None" | "Urgent" | "High" | "Medium" | "Low";
 
 export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, shared_task_id: string | null, team_id: string | null, issue_number: number | null, priority: number | null, due_date: string | null, assignee_id: string | null, created_at: string, updated_at: string, };
 
-export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, last_attempt_failed: boolean, executor: string | null, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, shared_task_id: string | null, team_id: string | null, issue_number: number | null, priority: number | null, due_date: string | null, assignee_id: string | null, created_at: string, updated_at: string, };
+export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, 
+/**
+ * An attempt is waiting in the execution queue
+ */
+has_queued_attempt: boolean, last_attempt_failed: boolean, executor: string | null, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, shared_task_id: string | null, team_id: string | null, issue_number: number | null, priority: number | null, due_date: string | null, assignee_id: string | null, created_at: string, updated_at: string, };
 
 export type TaskRelationships = { parent_task: Task | null, current_workspace: Workspace, children: Array<Task>, };
 
@@ -633,6 +755,152 @@ export type ShareTaskResponse = { shared_task_id: string, };
 
 export type CreateAndStartTaskRequest = { task: CreateTask, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, };
 
+export type InstantiateTaskTemplateRequest = { 
+/**
+ * Values overriding the template's default variables
+ */
+variables: { [key in string]?: string }, 
+/**
+ * Start an agent attempt for the new task with this profile
+ */
+executor_profile_id: ExecutorProfileId | null, repos: Array<CreateWorkspaceRepo>, };
+
+export type InstantiatedTask = { task: Task, sub_issues: Array<Task>, };
+
+export type CreateAttemptGroupRequest = { task_id: string, 
+/**
+ * One attempt is started per profile
+ */
+executor_profile_ids: Array<ExecutorProfileId>, repos: Array<CreateWorkspaceRepo>, 
+/**
+ * Shell script run in each attempt's worktree by the verify action
+ */
+verification_script: string | null, };
+
+export type PickAttemptWinnerRequest = { workspace_id: string, };
+
+export type ModelUsage = { model: string, input_tokens: bigint, output_tokens: bigint, cache_read_tokens: bigint, cache_write_tokens: bigint, cost_microdollars: bigint, priced: boolean, };
+
+export type UsageSummary = { input_tokens: bigint, output_tokens: bigint, cache_read_tokens: bigint, cache_write_tokens: bigint, cost_microdollars: bigint, 
+/**
+ * True when some usage could not be priced, so the cost is a lower bound
+ */
+has_unpriced: boolean, by_model: Array<ModelUsage>, };
+
+export type AttemptFileStat = { 
+/**
+ * Prefixed with the repository name when the attempt spans several repos
+ */
+path: string, change: DiffChangeKind, additions: number, deletions: number, };
+
+export type AttemptComparison = { workspace_id: string, branch: string, executor_profile_id: ExecutorProfileId, status: AttemptStatus, 
+/**
+ * Status of the latest coding agent run
+ */
+agent_status: ExecutionProcessStatus | null, files: Array<AttemptFileStat>, additions: number, deletions: number, 
+/**
+ * Set when the attempt's changes could not be diffed
+ */
+diff_error: string | null, 
+/**
+ * Wall-clock time spent in coding agent runs
+ */
+run_time_ms: bigint, usage: UsageSummary, verification: VerificationRun | null, };
+
+export type AttemptGroupComparison = { attempts: Array<AttemptComparison>, id: string, task_id: string, 
+/**
+ * Shell script run in each worktree to check an attempt's result
+ */
+verification_script: string | null, winner_workspace_id: string | null, created_at: Date, decided_at: Date | null, };
+
+export type CodingAgentCheckpoint = { id: string, execution_process_id: string, repo_id: string, commit_sha: string, 
+/**
+ * Ref keeping the checkpoint commit reachable
+ */
+ref_name: string, 
+/**
+ * Whether the checkpoint commit snapshots uncommitted changes on top of
+ * the branch's commit rather than being that commit
+ */
+committed_changes: boolean, created_at: Date, };
+
+export type RollbackToCheckpointRequest = { 
+/**
+ * Turn whose checkpoint the workspace is reset to; its changes and those
+ * of every later turn are discarded
+ */
+execution_process_id: string, 
+/**
+ * Also drop the discarded turns so the next follow-up forks the agent
+ * session from before them
+ */
+fork_session: boolean, force_when_dirty: boolean | null, };
+
+export type RepoCheckpoint = { repo_id: string, repo_name: string, commit_sha: string, 
+/**
+ * Whether the checkpoint snapshots uncommitted changes on top of the
+ * branch's commit
+ */
+committed_changes: boolean, files_changed: number, additions: number, deletions: number, 
+/**
+ * Set when the changes could not be diffed
+ */
+diff_error: string | null, };
+
+export type TurnCheckpoint = { execution_process_id: string, prompt: string | null, summary: string | null, status: ExecutionProcessStatus, repos: Array<RepoCheckpoint>, additions: number, deletions: number, created_at: Date, };
+
+export type SessionCheckpoints = { 
+/**
+ * Whether the session's executor can fork its agent session on rollback
+ */
+can_fork_session: boolean, 
+/**
+ * Oldest turn first
+ */
+turns: Array<TurnCheckpoint>, };
+
+export type TranscriptFormat = "markdown" | "jsonl" | "html";
+
+export type QueuedAttempt = { id: string, workspace_id: string, task_id: string, project_id: string, executor_profile_id: ExecutorProfileId, 
+/**
+ * Task priority when queued: 0=none, 1=urgent, 2=high, 3=medium, 4=low
+ */
+priority: number, 
+/**
+ * Zero-based place in the queue
+ */
+position: number, 
+/**
+ * Why the attempt last failed to start. It is skipped until moved or removed.
+ */
+launch_error: string | null, created_at: Date, };
+
+export type MoveQueuedAttemptRequest = { 
+/**
+ * Zero-based target position, clamped to the queue
+ */
+position: number, };
+
+export type MergeQueueEntry = { id: string, workspace_id: string, repo_id: string, task_id: string, project_id: string, target_branch: string, strategy: MergeStrategy, status: MergeQueueStatus, merge_commit_sha: string | null, eject_reason: string | null, created_at: Date, updated_at: Date, completed_at: Date | null, };
+
+export type MergeQueueStatus = "queued" | "rebasing" | "verifying" | "merged" | "ejected";
+
+export type MergeStrategy = "squash" | "fast_forward";
+
+export type MergeQueueConfig = { project_id: string, repo_id: string, 
+/**
+ * Run with the shell in the rebased worktree; a non-zero exit ejects
+ */
+verification_script: string | null, verification_timeout_secs: number, created_at: Date, updated_at: Date, };
+
+export type UpsertMergeQueueConfig = { verification_script: string | null, verification_timeout_secs: number | null, };
+
+export type EnqueueMergeRequest = { repo_id: string, 
+/**
+ * Defaults to a squash merge
+ */
+strategy: MergeStrategy | null, };
+
 export type CreateGitHubPrRequest = { title: string, body: string | null, target_branch: string | null, draft: boolean | null, repo_id: string, auto_generate_description: boolean, };
 
 export type ImageResponse = { id: string, file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, created_at: string, updated_at: string, };
@@ -657,7 +925,7 @@ export type GitOperationError = { "type": "merge_conflicts", message: string, op
 
 export type PushError = { "type": "force_push_required" };
 
-export type CreatePrError = { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" } | { "type": "git_cli_not_logged_in" } | { "type": "git_cli_not_installed" } | { "type": "target_branch_not_found", branch: string, };
+export type CreatePrError = { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" } | { "type": "git_cli_not_logged_in" } | { "type": "git_cli_not_installed" } | { "type": "target_branch_not_found", branch: string, } | { "type": "gitlab_not_connected", host: string, } | { "type": "gitlab_auth_failed" };
 
 export type BranchStatus = { commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, 
 /**
@@ -681,7 +949,31 @@ export type AttachExistingPrRequest = { repo_id: string, };
 
 export type PrCommentsResponse = { comments: Array<UnifiedPrComment>, };
 
-export type GetPrCommentsError = { "type": "no_pr_attached" } | { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" };
+export type GetPrCommentsError = { "type": "no_pr_attached" } | { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" } | { "type": "gitlab_not_connected", host: string, } | { "type": "gitlab_auth_failed" };
+
+export type AddressPrCommentsRequest = { repo_id: string, 
+/**
+ * Limit the follow-up to these comments; all pending ones when omitted
+ */
+comment_ids: Array<string> | null, };
+
+export type AddressPrCommentsError = { "type": "no_pr_attached" } | { "type": "no_pending_comments" } | { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" } | { "type": "unsupported_provider" };
+
+export type PrReviewFollowUp = { id: string, workspace_id: string, repo_id: string, execution_process_id: string, pr_number: bigint, pr_url: string, comments: Array<ReviewCommentRef>, status: PrReviewFollowUpStatus, commit_sha: string | null, error: string | null, created_at: Date, completed_at: Date | null, };
+
+export type PrReviewFollowUpStatus = "running" | "replied" | "no_changes" | "failed";
+
+export type ReviewCommentRef = { "type": "general", id: string, url: string, } | { "type": "thread", root_id: bigint, comment_ids: Array<bigint>, };
+
+export type CiRepairAttempt = { id: string, workspace_id: string, repo_id: string, pr_number: bigint, head_sha: string, execution_process_id: string | null, failed_jobs: Array<string>, status: CiRepairStatus, commit_sha: string | null, error: string | null, created_at: Date, completed_at: Date | null, };
+
+export type CiRepairStatus = "running" | "pushed" | "no_changes" | "failed" | "escalated";
+
+export type ResolveConflictsRequest = { repo_id: string, };
+
+export type ConflictResolution = { id: string, workspace_id: string, repo_id: string, execution_process_id: string, conflicted_files: Array<string>, status: ConflictResolutionStatus, unresolved_files: Array<string>, error: string | null, created_at: Date, completed_at: Date | null, };
+
+export type ConflictResolutionStatus = "running" | "resolved" | "unresolved" | "failed";
 
 export type GetPrCommentsQuery = { repo_id: string, };
 
@@ -705,7 +997,15 @@ export type DirectoryEntry = { name: string, path: string, is_directory: boolean
 
 export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_path: string, };
 
-export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, };
+export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, 
+/**
+ * Days stored execution transcripts are kept; `None` keeps them forever
+ */
+transcript_retention_days: number | null, execution_concurrency: ExecutionConcurrencyConfig, 
+/**
+ * Address new review comments as soon as a PR review is submitted
+ */
+pr_review_auto_address: boolean, ci_repair: CiRepairConfig, };
 
 export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
 
@@ -725,6 +1025,14 @@ export type UiLanguage = "BROWSER" | "EN" | "JA" | "ES" | "KO" | "ZH_HANS";
 
 export type ShowcaseState = { seen_features: Array<string>, };
 
+export type ExecutionConcurrencyConfig = { max_running: number | null, max_running_per_project: number | null, 
+/**
+ * Keyed by executor, e.g. `CLAUDE_CODE`
+ */
+max_running_per_executor: { [key in string]?: number }, };
+
+export type CiRepairConfig = { enabled: boolean, max_attempts_per_pr: number, };
+
 export type GitBranch = { name: string, is_current: boolean, is_remote: boolean, last_commit_date: Date, };
 
 export type SharedTaskDetails = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, };
@@ -764,9 +1072,9 @@ working_dir: string | null, };
 
 export type ScriptRequestLanguage = "Bash";
 
-export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", DROID = "DROID", CLAUDE = "CLAUDE" }
+export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", DROID = "DROID", CLAUDE = "CLAUDE", API_AGENT = "API_AGENT" }
 
-export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "CLAUDE": Claude };
+export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "CLAUDE": Claude } | { "API_AGENT": ApiAgent };
 
 export type AvailabilityInfo = { "type": "LOGIN_DETECTED", last_auth_timestamp: bigint, } | { "type": "INSTALLATION_FOUND" } | { "type": "NOT_FOUND" };
 
@@ -835,6 +1143,14 @@ export type Droid = { append_prompt: AppendPrompt, autonomy: Autonomy, model?: s
 
 export type Autonomy = "normal" | "low" | "medium" | "high" | "skip-permissions-unsafe";
 
+export type ApiAgent = { append_prompt: AppendPrompt, provider: AiProvider, model?: string | null, base_url?: string | null, max_turns?: number | null, 
+/**
+ * Auto-approve agent actions
+ */
+auto_approve: boolean, };
+
+export type AiProvider = "anthropic" | "google" | "openai";
+
 export type DroidReasoningEffort = "none" | "dynamic" | "off" | "low" | "medium" | "high";
 
 export type AppendPrompt = string | null;
@@ -865,6 +1181,8 @@ export type CommandExitStatus = { "type": "exit_code", code: number, } | { "type
 
 export type CommandRunResult = { exit_status: CommandExitStatus | null, output: string | null, };
 
+export type NormalizedConversation = { entries: Array<NormalizedEntry>, session_id: string | null, executor_type: string, prompt: string | null, summary: string | null, };
+
 export type NormalizedEntry = { timestamp: string | null, entry_type: NormalizedEntryType, content: string, };
 
 export type NormalizedEntryType = { "type": "user_message" } | { "type": "user_feedback", denied_tool: string, } | { "type": "assistant_message" } | { "type": "tool_use", tool_name: string, action_type: ActionType, status: ToolStatus, } | { "type": "system_message" } | { "type": "error_message", error_type: NormalizedEntryError, } | { "type": "thinking" } | { "type": "loading" } | { "type": "next_action", failed: boolean, execution_processes: number, needs_setup: boolean, };
@@ -883,7 +1201,7 @@ export type ActionType = { "action": "file_read", path: string, } | { "action":
 
 export type TodoItem = { content: string, status: string, priority: string | null, };
 
-export type NormalizedEntryError = { "type": "setup_required" } | { "type": "other" };
+export type NormalizedEntryError = { "type": "setup_required" } | { "type": "sandbox_violation" } | { "type": "other" };
 
 export type ToolResult = { type: ToolResultValueType, 
 /**